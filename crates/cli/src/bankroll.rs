@@ -0,0 +1,217 @@
+//! `pokercraft bankroll`: Bankroll simulation from a CSV of tournament results.
+
+use std::path::PathBuf;
+
+use clap::Args;
+use serde::Deserialize;
+
+#[cfg(not(feature = "gpu"))]
+use pokercraft_core::bankroll::simulate_core;
+#[cfg(feature = "gpu")]
+use pokercraft_core::bankroll::simulate_gpu;
+use pokercraft_core::bankroll::{simulate_with_cash_flows, CashFlow};
+use pokercraft_core::PokercraftLocalError;
+
+use crate::report::{BankrollReport, OutputFormat, Quantile, Report};
+
+/// Quantiles of final capitals to report.
+const QUANTILES: [f64; 5] = [0.05, 0.25, 0.5, 0.75, 0.95];
+
+#[derive(Args, Debug)]
+pub struct BankrollArgs {
+    /// CSV file with a net result of a tournament per row,
+    /// in the same unit as `--capital`, like dollars or buy-ins.
+    pub file: PathBuf,
+
+    /// Header name of the column with net results.
+    #[arg(long, default_value = "profit")]
+    pub column: String,
+
+    /// Read the first column of a CSV without a header row.
+    #[arg(long)]
+    pub no_header: bool,
+
+    #[command(flatten)]
+    pub simulation: SimulationArgs,
+}
+
+/// Parameters of bankroll simulations.
+#[derive(Args, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationArgs {
+    /// Initial capital.
+    #[arg(long)]
+    pub capital: f64,
+
+    /// Maximum number of tournaments to play in each simulation.
+    #[arg(long, default_value_t = default_iterations())]
+    #[serde(default = "default_iterations")]
+    pub iterations: u32,
+
+    /// Stop a simulation when the capital reaches this multiple of the initial capital;
+    /// Values below 1 never stop early.
+    #[arg(long, default_value_t = 0.0)]
+    #[serde(default)]
+    pub exit_multiplier: f64,
+
+    /// Number of simulations.
+    #[arg(long, default_value_t = default_simulations())]
+    #[serde(default = "default_simulations")]
+    pub simulations: u32,
+
+    /// Number of tournaments between cash flows, like tournaments played per month;
+    /// 0 disables cash flows.
+    #[arg(long, default_value_t = 0)]
+    #[serde(default)]
+    pub cash_flow_every: u32,
+
+    /// Amount added to the capital at each cash flow; Negative for withdrawals.
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    #[serde(default)]
+    pub cash_flow: f64,
+
+    /// Fraction of the capital added at each cash flow; Negative for withdrawals.
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    #[serde(default)]
+    pub cash_flow_fraction: f64,
+}
+
+impl SimulationArgs {
+    /// Get the cash flow, if enabled.
+    fn cash_flow(&self) -> Option<CashFlow> {
+        (self.cash_flow_every > 0).then_some(CashFlow {
+            every: self.cash_flow_every,
+            amount: self.cash_flow,
+            fraction: self.cash_flow_fraction,
+        })
+    }
+}
+
+/// Default maximum number of tournaments in each simulation.
+pub fn default_iterations() -> u32 {
+    10_000
+}
+
+/// Default number of simulations.
+pub fn default_simulations() -> u32 {
+    10_000
+}
+
+/// Bankroll simulation with net results given directly instead of a CSV file.
+#[derive(Deserialize, Debug)]
+pub struct BankrollRequest {
+    /// Net results of tournaments, in the same unit as `capital`.
+    pub results: Vec<f64>,
+
+    #[serde(flatten)]
+    pub simulation: SimulationArgs,
+}
+
+fn csv_error(err: csv::Error) -> PokercraftLocalError {
+    PokercraftLocalError::GeneralError(format!("Failed to read CSV: {}", err))
+}
+
+/// Read net results from the given CSV file.
+fn read_results(args: &BankrollArgs) -> Result<Vec<f64>, PokercraftLocalError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(!args.no_header)
+        .trim(csv::Trim::All)
+        .from_path(&args.file)
+        .map_err(csv_error)?;
+    let index = if args.no_header {
+        0
+    } else {
+        reader
+            .headers()
+            .map_err(csv_error)?
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(&args.column))
+            .ok_or_else(|| {
+                PokercraftLocalError::GeneralError(format!(
+                    "Column \"{}\" is not found in {}",
+                    args.column,
+                    args.file.display()
+                ))
+            })?
+    };
+
+    let mut results = Vec::new();
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(csv_error)?;
+        let value = record.get(index).unwrap_or_default();
+        if value.is_empty() {
+            continue;
+        }
+        results.push(value.parse::<f64>().map_err(|_| {
+            PokercraftLocalError::GeneralError(format!(
+                "Invalid number \"{}\" at row {}",
+                value,
+                row + 1
+            ))
+        })?);
+    }
+    Ok(results)
+}
+
+/// Simulate bankroll for `pokercraft bankroll`.
+pub fn build_report(args: &BankrollArgs) -> Result<BankrollReport, PokercraftLocalError> {
+    build_report_from_results(read_results(args)?, &args.simulation)
+}
+
+/// Simulate bankroll with the given net results.
+pub fn build_report_from_results(
+    results: Vec<f64>,
+    args: &SimulationArgs,
+) -> Result<BankrollReport, PokercraftLocalError> {
+    let num_results = results.len();
+    let metric = match args.cash_flow() {
+        // Cash flows are simulated on CPU only
+        Some(cash_flow) => simulate_with_cash_flows(
+            args.capital,
+            results,
+            &[cash_flow],
+            args.iterations,
+            args.exit_multiplier,
+            args.simulations,
+        )?,
+        #[cfg(not(feature = "gpu"))]
+        None => simulate_core(
+            args.capital,
+            results,
+            args.iterations,
+            args.exit_multiplier,
+            args.simulations,
+        )?,
+        #[cfg(feature = "gpu")]
+        None => simulate_gpu(
+            args.capital,
+            results,
+            args.iterations,
+            args.exit_multiplier,
+            args.simulations,
+        )?,
+    };
+    Ok(BankrollReport {
+        initial_capital: args.capital,
+        max_iterations: args.iterations,
+        results: num_results,
+        simulations: metric.len(),
+        bankruptcy_rate: metric.get_bankruptcy_rate(),
+        survival_rate: metric.get_survival_rate(),
+        profitable_rate: metric.get_profitable_rate(),
+        target_reached_rate: metric.get_target_reached_rate(),
+        median_time_to_target: metric.get_time_to_target_quantile(0.5),
+        final_capital_quantiles: QUANTILES
+            .iter()
+            .map(|&quantile| Quantile {
+                quantile,
+                capital: metric.get_relative_return_quantile(quantile) * args.capital,
+            })
+            .collect(),
+    })
+}
+
+/// Run `pokercraft bankroll`.
+pub fn run(args: BankrollArgs, format: OutputFormat) -> Result<(), PokercraftLocalError> {
+    build_report(&args)?.print(format)
+}
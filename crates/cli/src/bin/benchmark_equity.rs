@@ -1,17 +1,82 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
 use pokercraft_core::{
-    card::{Card, Hand},
+    card::{Card, Hand, HandRank},
     equity::EquityResult,
+    evaluator::evaluate_batch,
+    utils::FixedSizedCombinationIterator,
     PokercraftLocalError,
 };
 
+/// System allocator counting allocations,
+/// to check that hand evaluation does not allocate.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn benchmark_hand_evaluation() -> Result<(), PokercraftLocalError> {
+    // All 7-card hands from the first 20 cards
+    let hands7 = FixedSizedCombinationIterator::<Card, 7>::new(Card::all().into_iter().take(20))
+        .collect::<Vec<_>>();
+
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let mut checksum: u64 = 0;
+    for card7 in hands7.iter() {
+        let (_, rank) = HandRank::find_best5(card7)?;
+        checksum = checksum.wrapping_add(rank.numerize().1);
+    }
+    let duration = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+    println!(
+        "Evaluating {} 7-card hands took: {:?} ({:.0} hands/s), allocations: {}, checksum: {}",
+        hands7.len(),
+        duration,
+        hands7.len() as f64 / duration.as_secs_f64(),
+        allocations,
+        checksum
+    );
+
+    let start = Instant::now();
+    let keys = evaluate_batch(&hands7);
+    let duration = start.elapsed();
+    println!(
+        "Batch evaluating {} 7-card hands took: {:?} ({:.0} hands/s), checksum: {}",
+        keys.len(),
+        duration,
+        keys.len() as f64 / duration.as_secs_f64(),
+        keys.iter()
+            .fold(0u64, |acc, key| acc.wrapping_add(key.0 as u64))
+    );
+    Ok(())
+}
+
 fn main() -> Result<(), PokercraftLocalError> {
+    benchmark_hand_evaluation()?;
+
+    // Three players, so heads-up preflop cache is not used
     let hand1: Hand = (Card::try_from("Ah")?, Card::try_from("Ad")?);
     let hand2: Hand = (Card::try_from("6s")?, Card::try_from("7s")?);
+    let hand3: Hand = (Card::try_from("Kc")?, Card::try_from("Qc")?);
 
     let start = Instant::now();
-    let result_parallel = EquityResult::new(vec![hand1, hand2], vec![], true)?;
+    let result_parallel = EquityResult::new(vec![hand1, hand2, hand3], vec![], true)?;
     let duration_parallel = start.elapsed();
     println!(
         "Parallel calculation took: {:?}, result: {:?}",
@@ -19,11 +84,28 @@ fn main() -> Result<(), PokercraftLocalError> {
     );
 
     let start = Instant::now();
-    let result_sequential = EquityResult::new(vec![hand1, hand2], vec![], false)?;
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result_sequential = EquityResult::new(vec![hand1, hand2, hand3], vec![], false)?;
     let duration_sequential = start.elapsed();
     println!(
-        "Sequential calculation took: {:?}, result: {:?}",
-        duration_sequential, result_sequential
+        "Sequential calculation took: {:?}, allocations: {}, result: {:?}",
+        duration_sequential,
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations_before,
+        result_sequential
+    );
+
+    // Heads-up on the flop, so the heads-up fast path is used instead of the preflop cache
+    let flop = vec![
+        Card::try_from("2c")?,
+        Card::try_from("8d")?,
+        Card::try_from("Ts")?,
+    ];
+    let start = Instant::now();
+    let result_heads_up = EquityResult::new(vec![hand1, hand2], flop, false)?;
+    println!(
+        "Heads-up flop calculation took: {:?}, result: {:?}",
+        start.elapsed(),
+        result_heads_up
     );
 
     Ok(())
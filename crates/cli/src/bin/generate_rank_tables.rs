@@ -0,0 +1,19 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use pokercraft_core::evaluator::RankTables;
+
+#[derive(Parser, Debug)]
+struct Args {
+    /// Path of the gzip-compressed tables, embedded from `crates/core/tables/rank_tables.bin`.
+    #[arg(long)]
+    file: PathBuf,
+}
+
+fn main() {
+    let args = Args::parse();
+    let tables = RankTables::generate();
+    std::fs::write(&args.file, tables.to_gzip_bytes().unwrap()).unwrap();
+    println!("Wrote rank tables to {}", args.file.display());
+}
@@ -0,0 +1,94 @@
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+use pokercraft_cli::bankroll::{self, BankrollArgs};
+use pokercraft_cli::equity::{self, EquityArgs};
+use pokercraft_cli::html_report::{self, HtmlReportArgs};
+use pokercraft_cli::icm::{self, IcmArgs};
+use pokercraft_cli::import::{self, ImportArgs};
+use pokercraft_cli::luck::{self, LuckArgs};
+use pokercraft_cli::range::{self, RangeArgs};
+use pokercraft_cli::repl::{self, ReplArgs};
+use pokercraft_cli::report::OutputFormat;
+use pokercraft_cli::shove::{self, ShoveArgs};
+
+/// Poker analysis tools for Pokercraft exports.
+#[derive(Parser, Debug)]
+#[command(name = "pokercraft", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Output format.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+
+    /// Shorthand for `--format json`.
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Log diagnostics of parsing, enumeration and simulation to stderr
+    /// at the given level: `error`, `warn`, `info`, `debug` or `trace`.
+    #[cfg(feature = "tracing")]
+    #[arg(long, global = true, value_name = "LEVEL")]
+    log_level: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Calculate equities between hands or ranges.
+    Equity(EquityArgs),
+    /// Simulate bankroll from a CSV of tournament results.
+    Bankroll(BankrollArgs),
+    /// Import Pokercraft exports into the local SQLite database.
+    #[command(alias = "parse")]
+    Import(ImportArgs),
+    /// Explore a hand range on the 13x13 grid.
+    Range(RangeArgs),
+    /// Calculate tournament equities of chip stacks with ICM.
+    Icm(IcmArgs),
+    /// Calculate the EV of jamming all-in against a calling range.
+    Shove(ShoveArgs),
+    /// Measure how lucky the hero was in all-ins of Pokercraft exports.
+    Luck(LuckArgs),
+    /// Start an interactive session to define ranges and boards and query equities.
+    Repl(ReplArgs),
+    /// Generate a standalone HTML report of Pokercraft exports.
+    /// Charts load Plotly from its CDN, so they need network access when the report is opened.
+    Report(HtmlReportArgs),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    #[cfg(feature = "tracing")]
+    if let Some(level) = cli.log_level.as_deref() {
+        if let Err(err) = pokercraft_core::logging::enable_logging(level) {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    }
+    let format = if cli.json {
+        OutputFormat::Json
+    } else {
+        cli.format
+    };
+    let result = match cli.command {
+        Command::Equity(args) => equity::run(args, format),
+        Command::Bankroll(args) => bankroll::run(args, format),
+        Command::Import(args) => import::run(args, format),
+        Command::Range(args) => range::run(args, format),
+        Command::Icm(args) => icm::run(args, format),
+        Command::Shove(args) => shove::run(args, format),
+        Command::Luck(args) => luck::run(args, format),
+        Command::Repl(args) => repl::run(args, format),
+        Command::Report(args) => html_report::run(args, format),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+
+/// HTTP server of pokercraft analyses; See `pokercraft_cli::server` for endpoints.
+#[derive(Parser, Debug)]
+#[command(name = "pokercraft-server", version)]
+struct Cli {
+    /// Address to listen on; Bound to localhost by default.
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    address: String,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    println!("Listening on http://{}", cli.address);
+    match pokercraft_cli::server::serve(&cli.address).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
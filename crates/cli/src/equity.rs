@@ -0,0 +1,79 @@
+//! `pokercraft equity`: Equities between hands or ranges on a board.
+
+use clap::Args;
+use serde::Deserialize;
+
+use pokercraft_core::card::{parse_cards, CardStyle, HAND_RANK_CATEGORIES};
+use pokercraft_core::equity::RangeEquityResult;
+use pokercraft_core::range::HandRange;
+use pokercraft_core::PokercraftLocalError;
+
+use crate::default_trials;
+use crate::report::{EquityReport, OutputFormat, PlayerEquity, Report};
+
+#[derive(Args, Deserialize, Debug)]
+pub struct EquityArgs {
+    /// Hands or ranges of each player, like `AsKs` or `QQ+,AKs,A5s-A2s`.
+    #[arg(required = true, num_args = 1..)]
+    pub players: Vec<String>,
+
+    /// Community cards, like `2c7d9h` or `2c,7d,9h`.
+    #[arg(long, default_value = "")]
+    #[serde(default)]
+    pub board: String,
+
+    /// Number of Monte Carlo trials, used when any player has a range.
+    #[arg(long, default_value_t = default_trials())]
+    #[serde(default = "default_trials")]
+    pub trials: u64,
+
+    /// Style of displaying cards in the table format: `ascii`, `unicode` or `colored`.
+    #[arg(long, default_value = "ascii")]
+    #[serde(default)]
+    pub card_style: CardStyle,
+}
+
+/// Calculate equities for `pokercraft equity`.
+pub fn build_report(args: &EquityArgs) -> Result<EquityReport, PokercraftLocalError> {
+    let board = parse_cards(&args.board)?;
+    let ranges = args
+        .players
+        .iter()
+        .map(|player| HandRange::try_from(player.as_str()))
+        .collect::<Result<Vec<_>, _>>()?;
+    #[cfg(not(feature = "gpu"))]
+    let result = RangeEquityResult::new(&ranges, &board, args.trials)?;
+    #[cfg(feature = "gpu")]
+    let result = RangeEquityResult::new_gpu(&ranges, &board, &[], args.trials)?;
+
+    let mut players = Vec::with_capacity(ranges.len());
+    for (i, (player, range)) in args.players.iter().zip(ranges.iter()).enumerate() {
+        let outcome = result.outcome(i)?;
+        players.push(PlayerEquity {
+            range: player.clone(),
+            combos: range.without_cards(&board).len(),
+            equity: outcome.equity(),
+            win: outcome.win_rate(),
+            tie: outcome.tie_rate(),
+            categories: (0..HAND_RANK_CATEGORIES.len())
+                .map(|category| outcome.category_rate(category))
+                .collect(),
+        });
+    }
+    Ok(EquityReport {
+        board,
+        card_style: args.card_style,
+        exact: result.is_exact(),
+        boards: result.outcome(0)?.total(),
+        categories: HAND_RANK_CATEGORIES
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        players,
+    })
+}
+
+/// Run `pokercraft equity`.
+pub fn run(args: EquityArgs, format: OutputFormat) -> Result<(), PokercraftLocalError> {
+    build_report(&args)?.print(format)
+}
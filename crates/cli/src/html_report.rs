@@ -0,0 +1,50 @@
+//! `pokercraft report`: Standalone HTML report of Pokercraft exports.
+//! Charts of the report load Plotly from its CDN, so they need network access when opened.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use pokercraft_core::parser::ingest_path;
+use pokercraft_core::report::generate_html_report;
+use pokercraft_core::PokercraftLocalError;
+
+use crate::report::OutputFormat;
+
+#[derive(Args, Debug)]
+pub struct HtmlReportArgs {
+    /// A directory, `.zip` bundle, or `.txt` file of Pokercraft exports.
+    pub path: PathBuf,
+
+    /// HTML file to write.
+    #[arg(long, short, default_value = "pokercraft-report.html")]
+    pub output: PathBuf,
+
+    /// Player ID of the hero.
+    #[arg(long, default_value = "Hero")]
+    pub hero: String,
+
+    /// Also include freeroll tournaments.
+    #[arg(long)]
+    pub allow_freerolls: bool,
+}
+
+/// Run `pokercraft report`.
+/// The report is always written as HTML, so other output formats are rejected.
+pub fn run(args: HtmlReportArgs, format: OutputFormat) -> Result<(), PokercraftLocalError> {
+    if format != OutputFormat::Table {
+        return Err(PokercraftLocalError::GeneralError(
+            "`pokercraft report` writes HTML; --format and --json are not supported".to_string(),
+        ));
+    }
+    let parsed = ingest_path(&args.path, args.allow_freerolls)?;
+    let html = generate_html_report(&parsed.tournaments, &parsed.hand_histories, &args.hero)?;
+    std::fs::write(&args.output, html)?;
+    println!(
+        "Wrote report of {} tournaments and {} hands to {}",
+        parsed.tournaments.len(),
+        parsed.hand_histories.len(),
+        args.output.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! `pokercraft icm`: Tournament equities of chip stacks with the Independent Chip Model.
+
+use clap::Args;
+use serde::Deserialize;
+
+use pokercraft_core::icm::icm_equities;
+use pokercraft_core::PokercraftLocalError;
+
+use crate::report::{IcmPlayer, IcmReport, OutputFormat, Report};
+
+#[derive(Args, Deserialize, Debug)]
+pub struct IcmArgs {
+    /// Chip stacks of remaining players, like `5000,3000,2000`.
+    #[arg(long, required = true, value_delimiter = ',')]
+    pub stacks: Vec<f64>,
+
+    /// Prizes from the first place, like `50,30,20`.
+    #[arg(long, required = true, value_delimiter = ',')]
+    pub payouts: Vec<f64>,
+}
+
+/// Calculate ICM equities for `pokercraft icm`.
+pub fn build_report(args: &IcmArgs) -> Result<IcmReport, PokercraftLocalError> {
+    let equities = icm_equities(&args.stacks, &args.payouts)?;
+    let total_chips: f64 = args.stacks.iter().sum();
+    let prize_pool: f64 = args.payouts.iter().take(args.stacks.len()).sum();
+    Ok(IcmReport {
+        payouts: args.payouts.clone(),
+        players: args
+            .stacks
+            .iter()
+            .zip(equities)
+            .map(|(&stack, equity)| IcmPlayer {
+                stack,
+                chip_share: stack / total_chips,
+                equity,
+                equity_share: if prize_pool > 0.0 {
+                    equity / prize_pool
+                } else {
+                    0.0
+                },
+            })
+            .collect(),
+    })
+}
+
+/// Run `pokercraft icm`.
+pub fn run(args: IcmArgs, format: OutputFormat) -> Result<(), PokercraftLocalError> {
+    build_report(&args)?.print(format)
+}
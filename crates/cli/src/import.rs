@@ -0,0 +1,165 @@
+//! `pokercraft import`: Import Pokercraft exports into the local SQLite database.
+
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+
+use pokercraft_core::parser::{
+    ingest_path_localized, AnonymizeMode, Anonymizer, CurrencyPosition, HeroAliases, NumberFormat,
+    PlayerRegistry, GGPOKER,
+};
+use pokercraft_core::store::Store;
+use pokercraft_core::PokercraftLocalError;
+
+use crate::report::{ImportReport, OutputFormat, Report};
+
+/// Number of parse errors shown without `--verbose`.
+const MAX_ERRORS_SHOWN: usize = 10;
+
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// A directory, `.zip` bundle, or `.txt` file of Pokercraft exports.
+    pub path: PathBuf,
+
+    /// SQLite database to create or update.
+    #[arg(long, default_value = "pokercraft.db")]
+    pub db: PathBuf,
+
+    /// Also import freeroll tournaments.
+    #[arg(long)]
+    pub allow_freerolls: bool,
+
+    /// Player IDs of other accounts of the hero, merged into `Hero` for stats.
+    #[arg(long = "hero-alias", value_name = "PLAYER_ID")]
+    pub hero_aliases: Vec<String>,
+
+    /// Also merge accounts of the hero detected from hole cards in the exports.
+    #[arg(long)]
+    pub detect_heroes: bool,
+
+    /// Decimal separator of amounts, for exports of clients in other locales.
+    #[arg(long, default_value_t = '.')]
+    pub decimal_separator: char,
+
+    /// Thousands separator of amounts; `none` if digits are not grouped.
+    #[arg(long, default_value = ",")]
+    pub thousands_separator: String,
+
+    /// Currency symbols are placed after amounts, like `1.000 $`.
+    #[arg(long)]
+    pub currency_suffix: bool,
+
+    /// JSON registry of players to create or update, which renames opponents
+    /// to stable internal IDs so their stats survive nickname changes.
+    #[arg(long, value_name = "PATH")]
+    pub player_registry: Option<PathBuf>,
+
+    /// Anonymize player IDs and table IDs of opponents before storing,
+    /// to share the database.
+    #[arg(long, value_enum)]
+    pub anonymize: Option<AnonymizeArg>,
+
+    /// Secret salt of `--anonymize hash`, to link opponents with other imports;
+    /// Keep it private. A random salt is generated and shown if omitted.
+    #[arg(long, value_name = "SALT")]
+    pub anonymize_salt: Option<String>,
+
+    /// Show all parse errors.
+    #[arg(long)]
+    pub verbose: bool,
+}
+
+/// Anonymization modes of `--anonymize`.
+#[derive(ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AnonymizeArg {
+    /// Replace opponents by their seat numbers.
+    Strip,
+    /// Replace opponents by keyed hashes, linking the same opponent between hands.
+    Hash,
+}
+
+/// Parse `--thousands-separator`, where `none` disables grouping.
+fn parse_thousands_separator(s: &str) -> Result<Option<char>, PokercraftLocalError> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        _ if s.eq_ignore_ascii_case("none") => Ok(None),
+        (Some(c), None) => Ok(Some(c)),
+        _ => Err(PokercraftLocalError::GeneralError(format!(
+            "Thousands separator should be a single character or `none`, got \"{}\"",
+            s
+        ))),
+    }
+}
+
+/// Import exports for `pokercraft import`.
+pub fn build_report(args: &ImportArgs) -> Result<ImportReport, PokercraftLocalError> {
+    let number_format = NumberFormat::new(
+        args.decimal_separator,
+        parse_thousands_separator(&args.thousands_separator)?,
+        if args.currency_suffix {
+            CurrencyPosition::Suffix
+        } else {
+            CurrencyPosition::Prefix
+        },
+    )?;
+    let mut parsed = ingest_path_localized(&args.path, args.allow_freerolls, &number_format)?;
+    let mut aliases = args
+        .hero_aliases
+        .iter()
+        .fold(HeroAliases::default(), |aliases, alias| {
+            aliases.with_alias(alias)
+        });
+    if args.detect_heroes {
+        aliases = aliases.with_detected(&parsed.hand_histories);
+    }
+    parsed.merge_hero_accounts(&aliases);
+    if let Some(path) = args.player_registry.as_ref() {
+        let mut registry = if path.exists() {
+            PlayerRegistry::from_json(&std::fs::read_to_string(path)?)?
+        } else {
+            PlayerRegistry::new()
+        };
+        parsed.register_players(&mut registry, GGPOKER);
+        std::fs::write(path, registry.to_json()?)?;
+    }
+    let mut generated_salt = None;
+    if let Some(mode) = args.anonymize {
+        let mode = match mode {
+            AnonymizeArg::Strip => AnonymizeMode::Strip,
+            AnonymizeArg::Hash => AnonymizeMode::Hash,
+        };
+        let mut anonymizer = Anonymizer::new(mode).keeping(aliases.canonical());
+        match args.anonymize_salt.as_deref() {
+            Some(salt) => anonymizer = anonymizer.with_salt(salt)?,
+            None if mode == AnonymizeMode::Hash => {
+                generated_salt = Some(anonymizer.salt().to_string());
+            }
+            None => {}
+        }
+        parsed.anonymize(&anonymizer);
+    }
+    let mut store = Store::open(&args.db)?;
+    let new_tournaments = store.insert_tournaments(&parsed.tournaments)?;
+    let new_hands = store.insert_hands(&parsed.hand_histories)?;
+    Ok(ImportReport {
+        path: args.path.display().to_string(),
+        database: args.db.display().to_string(),
+        new_hands,
+        duplicate_hands: parsed.hand_histories.len() - new_hands,
+        new_tournaments,
+        duplicate_tournaments: parsed.tournaments.len() - new_tournaments,
+        total_hands: store.hand_count()?,
+        errors_shown: if args.verbose {
+            parsed.errors.len()
+        } else {
+            MAX_ERRORS_SHOWN
+        },
+        errors: parsed.errors,
+        generated_salt,
+    })
+}
+
+/// Run `pokercraft import`.
+pub fn run(args: ImportArgs, format: OutputFormat) -> Result<(), PokercraftLocalError> {
+    build_report(&args)?.print(format)
+}
@@ -1,2 +1,28 @@
 //! CLI tools for pokercraft.
 //! This crate contains binary utilities for poker analysis.
+//! Subcommands of the `pokercraft` binary are implemented in modules here.
+//! Arguments of subcommands are also deserializable, so the `server` feature
+//! accepts them as JSON request bodies and responds with the same reports.
+
+pub mod bankroll;
+pub mod equity;
+pub mod html_report;
+pub mod icm;
+pub mod import;
+pub mod luck;
+pub mod range;
+pub mod repl;
+pub mod report;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod shove;
+
+/// Default number of Monte Carlo trials of range equities.
+pub fn default_trials() -> u64 {
+    100_000
+}
+
+/// Format the given ratio as a percentage.
+pub fn percent(ratio: f64) -> String {
+    format!("{:.2}%", ratio * 100.0)
+}
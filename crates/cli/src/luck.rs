@@ -0,0 +1,72 @@
+//! `pokercraft luck`: How lucky the hero was in all-ins of Pokercraft exports.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use pokercraft_core::equity::LuckCalculator;
+use pokercraft_core::parser::ingest_path;
+use pokercraft_core::stats::all_in::find_all_in_spots;
+use pokercraft_core::stats::{decompose_winnings, Period};
+use pokercraft_core::PokercraftLocalError;
+
+use crate::report::{LuckReport, OutputFormat, Report};
+
+#[derive(Args, Debug)]
+pub struct LuckArgs {
+    /// A directory, `.zip` bundle, or `.txt` file of Pokercraft exports.
+    pub path: PathBuf,
+
+    /// Player ID of the hero.
+    #[arg(long, default_value = "Hero")]
+    pub hero: String,
+
+    /// Also include freeroll tournaments.
+    #[arg(long)]
+    pub allow_freerolls: bool,
+}
+
+/// Calculate the luck of all-ins for `pokercraft luck`.
+pub fn build_report(args: &LuckArgs) -> Result<LuckReport, PokercraftLocalError> {
+    let parsed = ingest_path(&args.path, args.allow_freerolls)?;
+    let found = find_all_in_spots(&parsed.hand_histories, &args.hero);
+    let spots = found.spots;
+    let mut calculator = LuckCalculator::new();
+    for spot in spots.iter() {
+        calculator.add_result(spot.equity, spot.actual)?;
+    }
+    let (upper_tail, lower_tail, two_sided) = match calculator.tails() {
+        Some((upper, lower, two_sided)) => (Some(upper), Some(lower), Some(two_sided)),
+        None => (None, None, None),
+    };
+    let periods = decompose_winnings(
+        &parsed.hand_histories,
+        &parsed.tournaments,
+        &args.hero,
+        Period::Month,
+    )?;
+    Ok(LuckReport {
+        path: args.path.display().to_string(),
+        hero: args.hero.clone(),
+        hands: parsed.hand_histories.len(),
+        tournaments: parsed.tournaments.len(),
+        all_ins: spots.len(),
+        expected_wins: spots.iter().map(|spot| spot.equity).sum(),
+        actual_wins: spots.iter().map(|spot| spot.actual).sum(),
+        luck_score: calculator.luck_score(),
+        upper_tail,
+        lower_tail,
+        two_sided,
+        chip_profit: periods.iter().map(|period| period.chip_profit).sum(),
+        chip_luck: spots.iter().map(|spot| spot.luck_chips()).sum(),
+        profit: periods.iter().map(|period| period.profit).sum(),
+        luck: periods.iter().map(|period| period.luck).sum(),
+        parse_errors: parsed.errors.len(),
+        all_in_errors: found.errors,
+    })
+}
+
+/// Run `pokercraft luck`.
+pub fn run(args: LuckArgs, format: OutputFormat) -> Result<(), PokercraftLocalError> {
+    build_report(&args)?.print(format)
+}
@@ -0,0 +1,92 @@
+//! `pokercraft range`: Explore a hand range on the 13x13 grid.
+
+use clap::Args;
+use serde::Deserialize;
+
+use pokercraft_core::card::parse_cards;
+use pokercraft_core::equity::RangeEquityResult;
+use pokercraft_core::range::HandRange;
+use pokercraft_core::PokercraftLocalError;
+
+use crate::default_trials;
+use crate::report::{OutputFormat, RangeReport, RangeVersus, Report};
+
+#[derive(Args, Deserialize, Debug)]
+pub struct RangeArgs {
+    /// Range to explore, like `QQ+,AKs,A5s-A2s`.
+    pub range: String,
+
+    /// Community cards, like `2c7d9h` or `2c,7d,9h`; Blocked combos are removed.
+    #[arg(long, default_value = "")]
+    #[serde(default)]
+    pub board: String,
+
+    /// Other known cards like `AhKh`, such as your hand; Blocked combos are removed.
+    #[arg(long, default_value = "")]
+    #[serde(default)]
+    pub dead: String,
+
+    /// Another range to calculate equity against.
+    #[arg(long)]
+    #[serde(default)]
+    pub versus: Option<String>,
+
+    /// Number of Monte Carlo trials for `--versus`.
+    #[arg(long, default_value_t = default_trials())]
+    #[serde(default = "default_trials")]
+    pub trials: u64,
+}
+
+/// Explore the range for `pokercraft range`.
+pub fn build_report(args: &RangeArgs) -> Result<RangeReport, PokercraftLocalError> {
+    let board = parse_cards(&args.board)?;
+    let dead_cards = parse_cards(&args.dead)?;
+    let known_cards = board
+        .iter()
+        .chain(dead_cards.iter())
+        .copied()
+        .collect::<Vec<_>>();
+    let range = HandRange::try_from(args.range.as_str())?;
+    let live = range.without_cards(&known_cards);
+
+    let versus = match &args.versus {
+        Some(other) => {
+            let other_range = HandRange::try_from(other.as_str())?;
+            #[cfg(not(feature = "gpu"))]
+            let result = RangeEquityResult::new_with_dead_cards(
+                &[range.clone(), other_range.clone()],
+                &board,
+                &dead_cards,
+                args.trials,
+            )?;
+            #[cfg(feature = "gpu")]
+            let result = RangeEquityResult::new_gpu(
+                &[range.clone(), other_range.clone()],
+                &board,
+                &dead_cards,
+                args.trials,
+            )?;
+            let outcome = result.outcome(0)?;
+            Some(RangeVersus {
+                range: other.clone(),
+                combos: other_range.without_cards(&known_cards).len(),
+                equity: outcome.equity(),
+                tie: outcome.tie_rate(),
+            })
+        }
+        None => None,
+    };
+
+    Ok(RangeReport {
+        range: args.range.clone(),
+        total_combos: range.len(),
+        live_combos: live.len(),
+        grid: live.grid_counts().iter().map(|row| row.to_vec()).collect(),
+        versus,
+    })
+}
+
+/// Run `pokercraft range`.
+pub fn run(args: RangeArgs, format: OutputFormat) -> Result<(), PokercraftLocalError> {
+    build_report(&args)?.print(format)
+}
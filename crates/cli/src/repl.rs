@@ -0,0 +1,240 @@
+//! `pokercraft repl`: Interactive session to define ranges and boards and query equities.
+//! Parsed ranges, equity results and the shared caches of the core library
+//! stay in memory between lines, so repeated queries are answered instantly.
+
+use std::collections::HashMap;
+use std::io::{BufRead, IsTerminal, Write};
+
+use clap::{Args, Parser, Subcommand};
+
+use pokercraft_core::card::{parse_cards, Card, CardStyle};
+use pokercraft_core::range::HandRange;
+use pokercraft_core::PokercraftLocalError;
+
+use crate::default_trials;
+use crate::equity::{self, EquityArgs};
+use crate::range::{self, RangeArgs};
+use crate::report::{EquityReport, OutputFormat, Report};
+use crate::shove::{self, ShoveArgs};
+
+#[derive(Args, Debug)]
+pub struct ReplArgs {
+    /// Initial number of Monte Carlo trials, used when any player has a range.
+    #[arg(long, default_value_t = default_trials())]
+    pub trials: u64,
+
+    /// Style of displaying cards in the table format: `ascii`, `unicode` or `colored`.
+    #[arg(long, default_value = "ascii")]
+    pub card_style: CardStyle,
+}
+
+/// Commands of the REPL, where names of defined ranges can be used in place of ranges.
+#[derive(Parser, Debug)]
+#[command(multicall = true)]
+struct ReplLine {
+    #[command(subcommand)]
+    command: ReplCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum ReplCommand {
+    /// Define a named range, like `range villain QQ+,AK`.
+    Range { name: String, range: String },
+    /// List defined ranges.
+    Ranges,
+    /// Set community cards like `2c7d9h`, or clear them without cards.
+    Board {
+        #[arg(default_value = "")]
+        cards: String,
+    },
+    /// Set the number of Monte Carlo trials.
+    Trials { trials: u64 },
+    /// Set the output format.
+    Format {
+        #[arg(value_enum)]
+        format: OutputFormat,
+    },
+    /// Calculate equities between hands, ranges, or names of defined ranges on the board.
+    Equity {
+        #[arg(required = true, num_args = 1..)]
+        players: Vec<String>,
+    },
+    /// Explore a range or a defined range on the 13x13 grid.
+    Grid {
+        range: String,
+        /// Another range or defined range to calculate equity against.
+        #[arg(long)]
+        versus: Option<String>,
+    },
+    /// Calculate the EV of jamming all-in against a calling range on the board.
+    Shove {
+        /// Hole cards of the hero, like `AsKs`.
+        hand: String,
+        /// Range or defined range the villain calls with.
+        calling_range: String,
+        /// Chips in the middle before jamming, including blinds and antes.
+        pot: f64,
+        /// Chips behind of the hero.
+        hero_stack: f64,
+        /// Chips behind of the villain.
+        villain_stack: f64,
+    },
+    /// Exit the REPL.
+    #[command(alias = "exit")]
+    Quit,
+}
+
+/// State of a REPL session.
+struct Session {
+    ranges: HashMap<String, String>,
+    board: Vec<Card>,
+    trials: u64,
+    card_style: CardStyle,
+    format: OutputFormat,
+    /// Equity reports by resolved ranges, board and trials.
+    equities: HashMap<(Vec<String>, Vec<Card>, u64), EquityReport>,
+}
+
+impl Session {
+    /// Get the range defined by the given name, or the given string itself.
+    fn resolve(&self, range: &str) -> String {
+        self.ranges
+            .get(range)
+            .cloned()
+            .unwrap_or_else(|| range.to_string())
+    }
+
+    /// Get the board as a string accepted by command arguments.
+    fn board_string(&self) -> String {
+        self.board.iter().map(|card| card.to_string()).collect()
+    }
+
+    /// Execute a single command. Returns `false` to exit the REPL.
+    fn execute(&mut self, command: ReplCommand) -> Result<bool, PokercraftLocalError> {
+        match command {
+            ReplCommand::Range { name, range } => {
+                let combos = HandRange::try_from(range.as_str())?.len();
+                println!("{} = {} ({} combos)", name, range, combos);
+                self.ranges.insert(name, range);
+            }
+            ReplCommand::Ranges => {
+                let mut names = self.ranges.keys().collect::<Vec<_>>();
+                names.sort();
+                for name in names {
+                    println!("{} = {}", name, self.ranges[name]);
+                }
+            }
+            ReplCommand::Board { cards } => {
+                self.board = parse_cards(&cards)?;
+                if self.board.is_empty() {
+                    println!("Board cleared");
+                } else {
+                    println!("Board: {}", Card::display_all(&self.board, self.card_style));
+                }
+            }
+            ReplCommand::Trials { trials } => {
+                if trials == 0 {
+                    return Err(PokercraftLocalError::GeneralError(
+                        "Trials should be positive".to_string(),
+                    ));
+                }
+                self.trials = trials;
+            }
+            ReplCommand::Format { format } => self.format = format,
+            ReplCommand::Equity { players } => {
+                let players = players
+                    .iter()
+                    .map(|player| self.resolve(player))
+                    .collect::<Vec<_>>();
+                let key = (players.clone(), self.board.clone(), self.trials);
+                let report = match self.equities.get(&key) {
+                    Some(report) => report,
+                    None => {
+                        let report = equity::build_report(&EquityArgs {
+                            players,
+                            board: self.board_string(),
+                            trials: self.trials,
+                            card_style: self.card_style,
+                        })?;
+                        self.equities.entry(key).or_insert(report)
+                    }
+                };
+                report.print(self.format)?;
+            }
+            ReplCommand::Grid { range, versus } => {
+                range::build_report(&RangeArgs {
+                    range: self.resolve(&range),
+                    board: self.board_string(),
+                    dead: String::new(),
+                    versus: versus.map(|versus| self.resolve(&versus)),
+                    trials: self.trials,
+                })?
+                .print(self.format)?;
+            }
+            ReplCommand::Shove {
+                hand,
+                calling_range,
+                pot,
+                hero_stack,
+                villain_stack,
+            } => {
+                shove::build_report(&ShoveArgs {
+                    hand,
+                    calling_range: self.resolve(&calling_range),
+                    board: self.board_string(),
+                    pot,
+                    hero_stack,
+                    villain_stack,
+                    trials: self.trials,
+                })?
+                .print(self.format)?;
+            }
+            ReplCommand::Quit => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// Run `pokercraft repl`, reading commands from stdin until `quit` or EOF.
+/// Failed commands print errors and the session continues.
+pub fn run(args: ReplArgs, format: OutputFormat) -> Result<(), PokercraftLocalError> {
+    let mut session = Session {
+        ranges: HashMap::new(),
+        board: vec![],
+        trials: args.trials,
+        card_style: args.card_style,
+        format,
+        equities: HashMap::new(),
+    };
+    let stdin = std::io::stdin();
+    let interactive = stdin.is_terminal();
+    if interactive {
+        println!("Pokercraft REPL; Type `help` for commands, or `quit` to exit.");
+    }
+    let mut lines = stdin.lock().lines();
+    loop {
+        if interactive {
+            print!("pokercraft> ");
+            std::io::stdout().flush()?;
+        }
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line?;
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        if words.is_empty() || words[0].starts_with('#') {
+            continue;
+        }
+        match ReplLine::try_parse_from(words) {
+            Ok(parsed) => match session.execute(parsed.command) {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(err) => eprintln!("{}", err),
+            },
+            Err(err) => {
+                let _ = err.print();
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,778 @@
+//! Output types of CLI commands, printable as a table, JSON, or CSV.
+//! These are plain data, so they can be reused by other frontends.
+
+use std::fmt;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use pokercraft_core::card::{Card, CardStyle, NUM_OF_NUMBERS};
+use pokercraft_core::range::grid_class;
+use pokercraft_core::PokercraftLocalError;
+
+use crate::percent;
+
+/// Output formats of CLI commands.
+#[derive(ValueEnum, PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+/// A command output which can be printed in every `OutputFormat`.
+/// `Display` renders the table format.
+pub trait Report: Serialize + fmt::Display {
+    /// Get CSV records, starting with the header.
+    fn csv_records(&self) -> Vec<Vec<String>>;
+
+    /// Render this report in the given format.
+    fn render(&self, format: OutputFormat) -> Result<String, PokercraftLocalError> {
+        match format {
+            OutputFormat::Table => Ok(self.to_string()),
+            OutputFormat::Json => serde_json::to_string_pretty(self)
+                .map(|json| json + "\n")
+                .map_err(|e| PokercraftLocalError::GeneralError(e.to_string())),
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                for record in self.csv_records() {
+                    writer
+                        .write_record(&record)
+                        .map_err(|e| PokercraftLocalError::GeneralError(e.to_string()))?;
+                }
+                let bytes = writer
+                    .into_inner()
+                    .map_err(|e| PokercraftLocalError::GeneralError(e.to_string()))?;
+                String::from_utf8(bytes)
+                    .map_err(|e| PokercraftLocalError::GeneralError(e.to_string()))
+            }
+        }
+    }
+
+    /// Print this report to stdout in the given format.
+    fn print(&self, format: OutputFormat) -> Result<(), PokercraftLocalError> {
+        print!("{}", self.render(format)?);
+        Ok(())
+    }
+}
+
+/// Equity of a single player of `EquityReport`.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerEquity {
+    pub range: String,
+    pub combos: usize,
+    pub equity: f64,
+    pub win: f64,
+    pub tie: f64,
+    /// Ratios of hand rank categories, in the order of `EquityReport::categories`.
+    pub categories: Vec<f64>,
+}
+
+/// Output of `pokercraft equity`.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EquityReport {
+    pub board: Vec<Card>,
+    /// Style of displaying cards in the table format.
+    #[serde(skip)]
+    pub card_style: CardStyle,
+    pub exact: bool,
+    pub boards: u64,
+    pub categories: Vec<String>,
+    pub players: Vec<PlayerEquity>,
+}
+
+impl fmt::Display for EquityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let board = if self.board.is_empty() {
+            "(preflop)".to_string()
+        } else {
+            Card::display_all(&self.board, self.card_style).to_string()
+        };
+        let method = if self.exact {
+            "exact enumeration"
+        } else {
+            "Monte Carlo"
+        };
+        writeln!(f, "Board: {}, {} of {} boards", board, method, self.boards)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<8}{:<24}{:>8}{:>10}{:>10}{:>10}",
+            "Player", "Range", "Combos", "Equity", "Win", "Tie"
+        )?;
+        for (i, player) in self.players.iter().enumerate() {
+            writeln!(
+                f,
+                "{:<8}{:<24}{:>8}{:>10}{:>10}{:>10}",
+                i + 1,
+                player.range,
+                player.combos,
+                percent(player.equity),
+                percent(player.win),
+                percent(player.tie)
+            )?;
+        }
+        writeln!(f)?;
+        write!(f, "{:<16}", "Category")?;
+        for i in 0..self.players.len() {
+            write!(f, "{:>10}", i + 1)?;
+        }
+        writeln!(f)?;
+        for (category, name) in self.categories.iter().enumerate() {
+            write!(f, "{:<16}", name)?;
+            for player in self.players.iter() {
+                write!(f, "{:>10}", percent(player.categories[category]))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Report for EquityReport {
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        let mut header: Vec<String> = ["player", "range", "combos", "equity", "win", "tie"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        header.extend(self.categories.iter().cloned());
+        let mut records = vec![header];
+        for (i, player) in self.players.iter().enumerate() {
+            let mut record = vec![
+                (i + 1).to_string(),
+                player.range.clone(),
+                player.combos.to_string(),
+                player.equity.to_string(),
+                player.win.to_string(),
+                player.tie.to_string(),
+            ];
+            record.extend(player.categories.iter().map(|ratio| ratio.to_string()));
+            records.push(record);
+        }
+        records
+    }
+}
+
+/// A quantile of final capitals of `BankrollReport`.
+#[derive(Serialize, Clone, Debug)]
+pub struct Quantile {
+    pub quantile: f64,
+    pub capital: f64,
+}
+
+/// Output of `pokercraft bankroll`.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BankrollReport {
+    pub initial_capital: f64,
+    pub max_iterations: u32,
+    pub results: usize,
+    pub simulations: usize,
+    pub bankruptcy_rate: f64,
+    pub survival_rate: f64,
+    pub profitable_rate: f64,
+    /// Rate of simulations reaching the exit multiplier.
+    pub target_reached_rate: f64,
+    /// Median tournaments to reach the exit multiplier, among simulations reaching it.
+    pub median_time_to_target: Option<f64>,
+    pub final_capital_quantiles: Vec<Quantile>,
+}
+
+impl fmt::Display for BankrollReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} simulations of up to {} tournaments from {} results, starting with {:.2}",
+            self.simulations, self.max_iterations, self.results, self.initial_capital
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<18}{:>10}",
+            "Bankruptcy rate",
+            percent(self.bankruptcy_rate)
+        )?;
+        writeln!(
+            f,
+            "{:<18}{:>10}",
+            "Survival rate",
+            percent(self.survival_rate)
+        )?;
+        writeln!(
+            f,
+            "{:<18}{:>10}",
+            "Profitable rate",
+            percent(self.profitable_rate)
+        )?;
+        if let Some(median) = self.median_time_to_target {
+            writeln!(
+                f,
+                "{:<18}{:>10}",
+                "Target rate",
+                percent(self.target_reached_rate)
+            )?;
+            writeln!(f, "{:<18}{:>10.1}", "Median to target", median)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "Final capital quantiles")?;
+        for quantile in self.final_capital_quantiles.iter() {
+            writeln!(
+                f,
+                "{:<18}{:>10.2}",
+                format!("{:.0}%", quantile.quantile * 100.0),
+                quantile.capital
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Report for BankrollReport {
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        let mut header: Vec<String> = [
+            "initial_capital",
+            "max_iterations",
+            "results",
+            "simulations",
+            "bankruptcy_rate",
+            "survival_rate",
+            "profitable_rate",
+            "target_reached_rate",
+            "median_time_to_target",
+        ]
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+        let mut record = vec![
+            self.initial_capital.to_string(),
+            self.max_iterations.to_string(),
+            self.results.to_string(),
+            self.simulations.to_string(),
+            self.bankruptcy_rate.to_string(),
+            self.survival_rate.to_string(),
+            self.profitable_rate.to_string(),
+            self.target_reached_rate.to_string(),
+            self.median_time_to_target
+                .map(|median| median.to_string())
+                .unwrap_or_default(),
+        ];
+        for quantile in self.final_capital_quantiles.iter() {
+            header.push(format!("final_capital_p{}", quantile.quantile * 100.0));
+            record.push(quantile.capital.to_string());
+        }
+        vec![header, record]
+    }
+}
+
+/// Output of `pokercraft import`.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub path: String,
+    pub database: String,
+    pub new_hands: usize,
+    pub duplicate_hands: usize,
+    pub new_tournaments: usize,
+    pub duplicate_tournaments: usize,
+    pub total_hands: u64,
+    pub errors: Vec<String>,
+    /// Number of errors shown in the table format.
+    #[serde(skip)]
+    pub errors_shown: usize,
+    /// Random salt of `--anonymize hash`, if not given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generated_salt: Option<String>,
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Imported {} into {}", self.path, self.database)?;
+        writeln!(f)?;
+        writeln!(f, "{:<24}{:>10}", "New hands", self.new_hands)?;
+        writeln!(f, "{:<24}{:>10}", "Duplicate hands", self.duplicate_hands)?;
+        writeln!(f, "{:<24}{:>10}", "New tournaments", self.new_tournaments)?;
+        writeln!(
+            f,
+            "{:<24}{:>10}",
+            "Duplicate tournaments", self.duplicate_tournaments
+        )?;
+        writeln!(f, "{:<24}{:>10}", "Parse errors", self.errors.len())?;
+        writeln!(f, "{:<24}{:>10}", "Hands in database", self.total_hands)?;
+        if let Some(salt) = self.generated_salt.as_ref() {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "Generated salt {}; Pass it as --anonymize-salt to link opponents with later imports",
+                salt
+            )?;
+        }
+
+        if !self.errors.is_empty() {
+            writeln!(f)?;
+            for error in self.errors.iter().take(self.errors_shown) {
+                writeln!(f, "{}", error)?;
+            }
+            if self.errors.len() > self.errors_shown {
+                writeln!(
+                    f,
+                    "... and {} more errors; Use --verbose to see all",
+                    self.errors.len() - self.errors_shown
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Report for ImportReport {
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        let header = [
+            "path",
+            "database",
+            "new_hands",
+            "duplicate_hands",
+            "new_tournaments",
+            "duplicate_tournaments",
+            "parse_errors",
+            "total_hands",
+            "generated_salt",
+        ];
+        let record = vec![
+            self.path.clone(),
+            self.database.clone(),
+            self.new_hands.to_string(),
+            self.duplicate_hands.to_string(),
+            self.new_tournaments.to_string(),
+            self.duplicate_tournaments.to_string(),
+            self.errors.len().to_string(),
+            self.total_hands.to_string(),
+            self.generated_salt.clone().unwrap_or_default(),
+        ];
+        vec![header.iter().map(|name| name.to_string()).collect(), record]
+    }
+}
+
+/// Equity against another range of `RangeReport`.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeVersus {
+    pub range: String,
+    pub combos: usize,
+    pub equity: f64,
+    pub tie: f64,
+}
+
+/// Output of `pokercraft range`.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeReport {
+    pub range: String,
+    /// Combos before removing blocked ones.
+    pub total_combos: usize,
+    /// Combos left after removing blocked ones.
+    pub live_combos: usize,
+    /// Live combo counts of the grid; See `pokercraft_core::range::grid_class`.
+    pub grid: Vec<Vec<usize>>,
+    pub versus: Option<RangeVersus>,
+}
+
+impl RangeReport {
+    /// Number of all starting hand combos.
+    const ALL_COMBOS: usize = 1326;
+
+    /// Get the number of all combos of the given grid cell.
+    fn full_combos(row: usize, col: usize) -> usize {
+        match row.cmp(&col) {
+            std::cmp::Ordering::Equal => 6,
+            std::cmp::Ordering::Less => 4,
+            std::cmp::Ordering::Greater => 12,
+        }
+    }
+
+    /// Get (row, column, live combos) of non-empty grid cells.
+    fn live_cells(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.grid.iter().enumerate().flat_map(|(row, counts)| {
+            counts
+                .iter()
+                .enumerate()
+                .filter(|(_, &count)| count > 0)
+                .map(move |(col, &count)| (row, col, count))
+        })
+    }
+}
+
+impl fmt::Display for RangeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Each cell shows the class name if any combo is live, or a dot otherwise.
+        for row in 0..NUM_OF_NUMBERS {
+            let line = (0..NUM_OF_NUMBERS)
+                .map(|col| match self.grid[row][col] {
+                    0 => format!("{:<4}", "."),
+                    _ => format!("{:<4}", grid_class(row, col).unwrap_or_default()),
+                })
+                .collect::<String>();
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        writeln!(f)?;
+
+        writeln!(
+            f,
+            "Combos: {} of {} ({})",
+            self.total_combos,
+            Self::ALL_COMBOS,
+            percent(self.total_combos as f64 / Self::ALL_COMBOS as f64)
+        )?;
+        if self.live_combos != self.total_combos {
+            writeln!(
+                f,
+                "Live combos after blockers: {} ({} blocked)",
+                self.live_combos,
+                self.total_combos - self.live_combos
+            )?;
+        }
+        let partial = self
+            .live_cells()
+            .filter(|&(row, col, count)| count < Self::full_combos(row, col))
+            .map(|(row, col, count)| {
+                format!(
+                    "{} {}/{}",
+                    grid_class(row, col).unwrap_or_default(),
+                    count,
+                    Self::full_combos(row, col)
+                )
+            })
+            .collect::<Vec<_>>();
+        if !partial.is_empty() {
+            writeln!(f, "Partial classes: {}", partial.join(", "))?;
+        }
+
+        if let Some(versus) = &self.versus {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "Equity versus {} ({} combos): {} (tie {})",
+                versus.range,
+                versus.combos,
+                percent(versus.equity),
+                percent(versus.tie)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Report for RangeReport {
+    /// One record per grid cell with live combos.
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        let mut records = vec![vec!["class".to_string(), "combos".to_string()]];
+        records.extend(self.live_cells().map(|(row, col, count)| {
+            vec![grid_class(row, col).unwrap_or_default(), count.to_string()]
+        }));
+        records
+    }
+}
+
+/// ICM equity of a single player of `IcmReport`.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IcmPlayer {
+    pub stack: f64,
+    /// Ratio of chips of this player to all chips.
+    pub chip_share: f64,
+    /// Expected prize.
+    pub equity: f64,
+    /// Ratio of the expected prize to the total prize pool.
+    pub equity_share: f64,
+}
+
+/// Output of `pokercraft icm`.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IcmReport {
+    pub payouts: Vec<f64>,
+    pub players: Vec<IcmPlayer>,
+}
+
+impl fmt::Display for IcmReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let payouts = self
+            .payouts
+            .iter()
+            .map(|payout| format!("{:.2}", payout))
+            .collect::<Vec<_>>();
+        writeln!(f, "Payouts: {}", payouts.join(", "))?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<8}{:>14}{:>10}{:>14}{:>10}",
+            "Player", "Stack", "Chips", "Equity", "Prizes"
+        )?;
+        for (i, player) in self.players.iter().enumerate() {
+            writeln!(
+                f,
+                "{:<8}{:>14.0}{:>10}{:>14.2}{:>10}",
+                i + 1,
+                player.stack,
+                percent(player.chip_share),
+                player.equity,
+                percent(player.equity_share)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Report for IcmReport {
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        let header = ["player", "stack", "chip_share", "equity", "equity_share"];
+        let mut records = vec![header.iter().map(|name| name.to_string()).collect()];
+        for (i, player) in self.players.iter().enumerate() {
+            records.push(vec![
+                (i + 1).to_string(),
+                player.stack.to_string(),
+                player.chip_share.to_string(),
+                player.equity.to_string(),
+                player.equity_share.to_string(),
+            ]);
+        }
+        records
+    }
+}
+
+/// Output of `pokercraft shove`, where EVs are in chips relative to folding.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ShoveReport {
+    pub hand: String,
+    pub calling_range: String,
+    pub board: Vec<Card>,
+    pub pot: f64,
+    pub fold_frequency: f64,
+    /// Equity of the hand against the calling range.
+    pub called_equity: f64,
+    /// Chips at risk when called.
+    pub risk: f64,
+    pub called_ev: f64,
+    pub ev: f64,
+}
+
+impl fmt::Display for ShoveReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let board = if self.board.is_empty() {
+            "(preflop)".to_string()
+        } else {
+            Card::display_all(&self.board, CardStyle::Ascii).to_string()
+        };
+        writeln!(
+            f,
+            "Jamming {} into {:.2} on {}, risking {:.2}",
+            self.hand, self.pot, board, self.risk
+        )?;
+        writeln!(f, "Calling range: {}", self.calling_range)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<16}{:>12}",
+            "Fold frequency",
+            percent(self.fold_frequency)
+        )?;
+        writeln!(
+            f,
+            "{:<16}{:>12}",
+            "Called equity",
+            percent(self.called_equity)
+        )?;
+        writeln!(f, "{:<16}{:>12.2}", "Called EV", self.called_ev)?;
+        writeln!(f, "{:<16}{:>12.2}", "EV", self.ev)?;
+        Ok(())
+    }
+}
+
+impl Report for ShoveReport {
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        let header = [
+            "hand",
+            "calling_range",
+            "board",
+            "pot",
+            "fold_frequency",
+            "called_equity",
+            "risk",
+            "called_ev",
+            "ev",
+        ];
+        vec![
+            header.iter().map(|name| name.to_string()).collect(),
+            vec![
+                self.hand.clone(),
+                self.calling_range.clone(),
+                self.board.iter().map(|card| card.to_string()).collect(),
+                self.pot.to_string(),
+                self.fold_frequency.to_string(),
+                self.called_equity.to_string(),
+                self.risk.to_string(),
+                self.called_ev.to_string(),
+                self.ev.to_string(),
+            ],
+        ]
+    }
+}
+
+/// Output of `pokercraft luck`.
+/// Chip amounts are summed over all hands, and money amounts are summed over
+/// tournaments with summaries, in the currencies of the tournaments.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LuckReport {
+    pub path: String,
+    pub hero: String,
+    pub hands: usize,
+    pub tournaments: usize,
+    /// Number of all-in spots of the hero with known equity.
+    pub all_ins: usize,
+    /// Sum of all-in equities of the hero.
+    pub expected_wins: f64,
+    /// Sum of pot shares won by the hero in all-ins.
+    pub actual_wins: f64,
+    /// Z-score of actual wins against expected wins; Positive if the hero ran above equity.
+    pub luck_score: Option<f64>,
+    pub upper_tail: Option<f64>,
+    pub lower_tail: Option<f64>,
+    pub two_sided: Option<f64>,
+    pub chip_profit: i64,
+    /// Chips won over expected in all-ins.
+    pub chip_luck: f64,
+    pub profit: f64,
+    /// Money won over expected in all-ins.
+    pub luck: f64,
+    pub parse_errors: usize,
+    /// Errors of hands skipped as their all-ins are inconsistent, prefixed by hand IDs.
+    pub all_in_errors: Vec<String>,
+}
+
+impl LuckReport {
+    /// Get the chip profit expected given all-in equities.
+    pub fn expected_chip_profit(&self) -> f64 {
+        self.chip_profit as f64 - self.chip_luck
+    }
+
+    /// Get the profit expected given all-in equities.
+    pub fn expected_profit(&self) -> f64 {
+        self.profit - self.luck
+    }
+}
+
+impl fmt::Display for LuckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} all-ins of {} over {} hands and {} tournaments from {}",
+            self.all_ins, self.hero, self.hands, self.tournaments, self.path
+        )?;
+        writeln!(f)?;
+        writeln!(f, "{:<22}{:>12.2}", "Expected wins", self.expected_wins)?;
+        writeln!(f, "{:<22}{:>12.2}", "Actual wins", self.actual_wins)?;
+        match (
+            self.luck_score,
+            self.upper_tail,
+            self.lower_tail,
+            self.two_sided,
+        ) {
+            (Some(luck_score), Some(upper), Some(lower), Some(two_sided)) => {
+                writeln!(f, "{:<22}{:>12.3}", "Luck score", luck_score)?;
+                writeln!(f, "{:<22}{:>12}", "Upper tail", percent(upper))?;
+                writeln!(f, "{:<22}{:>12}", "Lower tail", percent(lower))?;
+                writeln!(f, "{:<22}{:>12}", "Two-sided", percent(two_sided))?;
+            }
+            _ => writeln!(f, "{:<22}{:>12}", "Luck score", "-")?,
+        }
+        writeln!(f)?;
+        writeln!(f, "{:<22}{:>12}", "Chip profit", self.chip_profit)?;
+        writeln!(f, "{:<22}{:>12.0}", "Chip luck", self.chip_luck)?;
+        writeln!(
+            f,
+            "{:<22}{:>12.0}",
+            "Expected chip profit",
+            self.expected_chip_profit()
+        )?;
+        writeln!(f, "{:<22}{:>12.2}", "Profit", self.profit)?;
+        writeln!(f, "{:<22}{:>12.2}", "Luck", self.luck)?;
+        writeln!(
+            f,
+            "{:<22}{:>12.2}",
+            "Expected profit",
+            self.expected_profit()
+        )?;
+        if self.parse_errors > 0 {
+            writeln!(f)?;
+            writeln!(f, "{} files or sections failed to parse", self.parse_errors)?;
+        }
+        if !self.all_in_errors.is_empty() {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "{} hands with inconsistent all-ins were skipped:",
+                self.all_in_errors.len()
+            )?;
+            for error in self.all_in_errors.iter() {
+                writeln!(f, "{}", error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Report for LuckReport {
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        let header = [
+            "path",
+            "hero",
+            "hands",
+            "tournaments",
+            "all_ins",
+            "expected_wins",
+            "actual_wins",
+            "luck_score",
+            "upper_tail",
+            "lower_tail",
+            "two_sided",
+            "chip_profit",
+            "chip_luck",
+            "expected_chip_profit",
+            "profit",
+            "luck",
+            "expected_profit",
+            "parse_errors",
+            "all_in_errors",
+        ];
+        let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        vec![
+            header.iter().map(|name| name.to_string()).collect(),
+            vec![
+                self.path.clone(),
+                self.hero.clone(),
+                self.hands.to_string(),
+                self.tournaments.to_string(),
+                self.all_ins.to_string(),
+                self.expected_wins.to_string(),
+                self.actual_wins.to_string(),
+                optional(self.luck_score),
+                optional(self.upper_tail),
+                optional(self.lower_tail),
+                optional(self.two_sided),
+                self.chip_profit.to_string(),
+                self.chip_luck.to_string(),
+                self.expected_chip_profit().to_string(),
+                self.profit.to_string(),
+                self.luck.to_string(),
+                self.expected_profit().to_string(),
+                self.parse_errors.to_string(),
+                self.all_in_errors.len().to_string(),
+            ],
+        ]
+    }
+}
@@ -0,0 +1,102 @@
+//! HTTP server exposing analyses of the CLI commands as JSON endpoints.
+//! Request bodies are arguments of the commands, and responses are their reports:
+//! - `POST /equity`: `EquityArgs` to `EquityReport`
+//! - `POST /range`: `RangeArgs` to `RangeReport`
+//! - `POST /icm`: `IcmArgs` to `IcmReport`
+//! - `POST /shove`: `ShoveArgs` to `ShoveReport`
+//! - `POST /bankroll`: `BankrollRequest` to `BankrollReport`
+//! - `GET /version`: The library version
+//!
+//! Failed analyses respond with `400 Bad Request` and `{"error": message}`.
+
+use axum::extract::Json;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use serde::Serialize;
+
+use pokercraft_core::PokercraftLocalError;
+
+use crate::bankroll::{self, BankrollRequest};
+use crate::equity::{self, EquityArgs};
+use crate::icm::{self, IcmArgs};
+use crate::range::{self, RangeArgs};
+use crate::shove::{self, ShoveArgs};
+
+/// Error response of an endpoint.
+#[derive(Serialize, Debug)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Run the given analysis on a blocking thread, and respond with its report as JSON.
+async fn respond<T, F>(analysis: F) -> Response
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce() -> Result<T, PokercraftLocalError> + Send + 'static,
+{
+    match tokio::task::spawn_blocking(analysis).await {
+        Ok(Ok(report)) => Json(report).into_response(),
+        Ok(Err(err)) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorBody {
+                error: err.to_string(),
+            }),
+        )
+            .into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorBody {
+                error: err.to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+async fn equity_handler(Json(args): Json<EquityArgs>) -> Response {
+    respond(move || equity::build_report(&args)).await
+}
+
+async fn range_handler(Json(args): Json<RangeArgs>) -> Response {
+    respond(move || range::build_report(&args)).await
+}
+
+async fn icm_handler(Json(args): Json<IcmArgs>) -> Response {
+    respond(move || icm::build_report(&args)).await
+}
+
+async fn shove_handler(Json(args): Json<ShoveArgs>) -> Response {
+    respond(move || shove::build_report(&args)).await
+}
+
+async fn bankroll_handler(Json(request): Json<BankrollRequest>) -> Response {
+    respond(move || bankroll::build_report_from_results(request.results, &request.simulation)).await
+}
+
+async fn version_handler() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// Get the router of all endpoints.
+pub fn router() -> Router {
+    Router::new()
+        .route("/equity", post(equity_handler))
+        .route("/range", post(range_handler))
+        .route("/icm", post(icm_handler))
+        .route("/shove", post(shove_handler))
+        .route("/bankroll", post(bankroll_handler))
+        .route("/version", get(version_handler))
+}
+
+/// Serve all endpoints on the given address, like `127.0.0.1:8080`, until interrupted.
+pub async fn serve(address: &str) -> Result<(), PokercraftLocalError> {
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    axum::serve(listener, router())
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
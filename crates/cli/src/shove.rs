@@ -0,0 +1,75 @@
+//! `pokercraft shove`: Expected value of jamming all-in with fold equity and showdown equity.
+
+use clap::Args;
+use serde::Deserialize;
+
+use pokercraft_core::card::{parse_cards, parse_cards_exact};
+use pokercraft_core::math::shove_ev;
+use pokercraft_core::range::HandRange;
+use pokercraft_core::PokercraftLocalError;
+
+use crate::default_trials;
+use crate::report::{OutputFormat, Report, ShoveReport};
+
+#[derive(Args, Deserialize, Debug)]
+pub struct ShoveArgs {
+    /// Hole cards of the hero, like `AsKs`.
+    pub hand: String,
+
+    /// Range the villain calls with, like `QQ+,AK`; Everything else folds.
+    #[arg(long, required = true)]
+    pub calling_range: String,
+
+    /// Community cards, like `2c7d9h` or `2c,7d,9h`.
+    #[arg(long, default_value = "")]
+    #[serde(default)]
+    pub board: String,
+
+    /// Chips in the middle before jamming, including blinds and antes.
+    #[arg(long)]
+    pub pot: f64,
+
+    /// Chips behind of the hero.
+    #[arg(long)]
+    pub hero_stack: f64,
+
+    /// Chips behind of the villain.
+    #[arg(long)]
+    pub villain_stack: f64,
+
+    /// Number of Monte Carlo trials against each calling combo.
+    #[arg(long, default_value_t = default_trials())]
+    #[serde(default = "default_trials")]
+    pub trials: u64,
+}
+
+/// Calculate the EV of jamming for `pokercraft shove`.
+pub fn build_report(args: &ShoveArgs) -> Result<ShoveReport, PokercraftLocalError> {
+    let [card1, card2] = parse_cards_exact::<2>(&args.hand)?;
+    let board = parse_cards(&args.board)?;
+    let result = shove_ev(
+        (card1, card2),
+        &HandRange::try_from(args.calling_range.as_str())?,
+        &board,
+        args.pot,
+        args.hero_stack,
+        args.villain_stack,
+        args.trials,
+    )?;
+    Ok(ShoveReport {
+        hand: args.hand.clone(),
+        calling_range: args.calling_range.clone(),
+        board,
+        pot: args.pot,
+        fold_frequency: result.fold_frequency,
+        called_equity: result.called_equity,
+        risk: result.risk,
+        called_ev: result.called_ev,
+        ev: result.ev,
+    })
+}
+
+/// Run `pokercraft shove`.
+pub fn run(args: ShoveArgs, format: OutputFormat) -> Result<(), PokercraftLocalError> {
+    build_report(&args)?.print(format)
+}
@@ -0,0 +1,287 @@
+//! Range advantage and nut advantage of two ranges on a board,
+//! the standard metrics for deciding which player bets more often.
+
+use rayon::prelude::*;
+
+use crate::card::{Card, Hand, HandRank};
+use crate::errors::PokercraftLocalError;
+use crate::evaluator::evaluate_batch;
+use crate::range::HandRange;
+use crate::utils::{in_thread_pool, FixedSizedCombinationIterator};
+
+/// Equities of two ranges on a flop, turn or river, with every runout enumerated.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct RangeAdvantage {
+    /// Equity of each combo of each range against the other range,
+    /// except combos blocked by the board.
+    pub combo_equities: [Vec<(Hand, f64)>; 2],
+    /// Equity of each range against the other range.
+    pub equities: [f64; 2],
+    /// Number of combos of each range holding the nuts on the current board.
+    pub nut_combos: [usize; 2],
+}
+
+/// Wins and showdowns of each combo, with ties counted as half wins.
+type Tally = [Vec<(f64, f64)>; 2];
+
+/// Add showdowns of the given combos on a complete board to the tally.
+/// `same[side][i]` tells if the other range has the same combo.
+fn tally_runout(
+    combos: &[Vec<Hand>; 2],
+    same: &[Vec<bool>; 2],
+    full_board: &[Card],
+    tally: &mut Tally,
+) {
+    let sorted = [0, 1].map(|side| {
+        let sevens: Vec<([Card; 7], usize)> = combos[side]
+            .iter()
+            .enumerate()
+            .filter(|(_, (c1, c2))| !full_board.contains(c1) && !full_board.contains(c2))
+            .map(|(i, &(c1, c2))| {
+                let mut seven = [c1; 7];
+                seven[1] = c2;
+                seven[2..].copy_from_slice(full_board);
+                (seven, i)
+            })
+            .collect();
+        let boards: Vec<[Card; 7]> = sevens.iter().map(|(seven, _)| *seven).collect();
+        let mut sorted: Vec<_> = evaluate_batch(&boards)
+            .into_iter()
+            .zip(sevens.into_iter().map(|(_, i)| i))
+            .collect();
+        sorted.sort();
+        sorted
+    });
+
+    for side in 0..2 {
+        let (own, other) = (&sorted[side], &sorted[1 - side]);
+        let other_combos = &combos[1 - side];
+        // Count combos of the other side weaker (or stronger) than each combo,
+        // except ones sharing cards with it
+        let count_beaten = |stronger: bool| {
+            let mut counts = vec![0.0; own.len()];
+            let mut total = 0.0;
+            let mut per_card = [0.0; 52];
+            let mut k = 0;
+            for step in 0..own.len() {
+                let at = if stronger { own.len() - 1 - step } else { step };
+                let (rank, i) = own[at];
+                while k < other.len() {
+                    let (other_rank, j) = other[if stronger { other.len() - 1 - k } else { k }];
+                    if (stronger && other_rank <= rank) || (!stronger && other_rank >= rank) {
+                        break;
+                    }
+                    let (c1, c2) = other_combos[j];
+                    total += 1.0;
+                    per_card[c1.index()] += 1.0;
+                    per_card[c2.index()] += 1.0;
+                    k += 1;
+                }
+                let (c1, c2) = combos[side][i];
+                counts[at] = total - per_card[c1.index()] - per_card[c2.index()];
+            }
+            counts
+        };
+        let weaker = count_beaten(false);
+        let stronger = count_beaten(true);
+
+        let mut per_card = [0.0; 52];
+        for &(_, j) in other.iter() {
+            let (c1, c2) = other_combos[j];
+            per_card[c1.index()] += 1.0;
+            per_card[c2.index()] += 1.0;
+        }
+        for (at, &(_, i)) in own.iter().enumerate() {
+            let (c1, c2) = combos[side][i];
+            let showdowns = other.len() as f64 - per_card[c1.index()] - per_card[c2.index()]
+                + same[side][i] as u8 as f64;
+            let ties = showdowns - weaker[at] - stronger[at];
+            tally[side][i].0 += weaker[at] + ties / 2.0;
+            tally[side][i].1 += showdowns;
+        }
+    }
+}
+
+impl RangeAdvantage {
+    /// Calculate equities of the given ranges on the board of 3 to 5 cards,
+    /// enumerating runouts in parallel. Combos blocked by the board are removed first.
+    pub fn new(ranges: [&HandRange; 2], board: &[Card]) -> Result<Self, PokercraftLocalError> {
+        if !(3..=5).contains(&board.len()) {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Board should have 3 to 5 cards, got {}",
+                board.len()
+            )));
+        } else if (1..board.len()).any(|i| board[..i].contains(&board[i])) {
+            return Err(PokercraftLocalError::GeneralError(
+                "Board has duplicate cards".to_string(),
+            ));
+        }
+        let combos = ranges.map(|range| range.without_cards(board).combos().to_vec());
+        if combos.iter().any(|combos| combos.is_empty()) {
+            return Err(PokercraftLocalError::GeneralError(
+                "Every range should have at least 1 combo not blocked by the board".to_string(),
+            ));
+        }
+
+        let same = [0, 1].map(|side| {
+            combos[side]
+                .iter()
+                .map(|combo| combos[1 - side].contains(combo))
+                .collect()
+        });
+
+        let remaining: Vec<Card> = Card::all()
+            .into_iter()
+            .filter(|card| !board.contains(card))
+            .collect();
+        let runouts: Vec<Vec<Card>> = match board.len() {
+            5 => vec![Vec::new()],
+            4 => remaining.iter().map(|card| vec![*card]).collect(),
+            _ => FixedSizedCombinationIterator::<Card, 2>::new(remaining.into_iter())
+                .map(|cards| cards.to_vec())
+                .collect(),
+        };
+        let empty = || combos.clone().map(|combos| vec![(0.0, 0.0); combos.len()]);
+        let tally = in_thread_pool(|| {
+            runouts
+                .par_iter()
+                .fold(empty, |mut tally, runout| {
+                    tally_runout(&combos, &same, &[board, runout].concat(), &mut tally);
+                    tally
+                })
+                .reduce(empty, |mut a, b| {
+                    for (a, b) in a.iter_mut().zip(b.iter()) {
+                        for (a, b) in a.iter_mut().zip(b.iter()) {
+                            a.0 += b.0;
+                            a.1 += b.1;
+                        }
+                    }
+                    a
+                })
+        });
+
+        let equities = [0, 1].map(|side| {
+            let (wins, showdowns) = tally[side]
+                .iter()
+                .fold((0.0, 0.0), |(w, s), (wins, showdowns)| {
+                    (w + wins, s + showdowns)
+                });
+            if showdowns > 0.0 {
+                wins / showdowns
+            } else {
+                0.0
+            }
+        });
+        let combo_equities = [0, 1].map(|side| {
+            combos[side]
+                .iter()
+                .zip(tally[side].iter())
+                .filter(|(_, (_, showdowns))| *showdowns > 0.0)
+                .map(|(combo, (wins, showdowns))| (*combo, wins / showdowns))
+                .collect()
+        });
+
+        // The nuts is the best hand of any hole cards on the current board
+        let rank_of = |(c1, c2): Hand| {
+            HandRank::find_best5(&[board, &[c1, c2]].concat()).map(|(_, rank)| rank)
+        };
+        let mut nuts: Option<HandRank> = None;
+        for combo in HandRange::try_from("random")?.without_cards(board).combos() {
+            let rank = rank_of(*combo)?;
+            if nuts.as_ref().is_none_or(|nuts| rank > *nuts) {
+                nuts = Some(rank);
+            }
+        }
+        let mut nut_combos = [0; 2];
+        for side in 0..2 {
+            for combo in combos[side].iter() {
+                if Some(rank_of(*combo)?) == nuts {
+                    nut_combos[side] += 1;
+                }
+            }
+        }
+
+        Ok(RangeAdvantage {
+            combo_equities,
+            equities,
+            nut_combos,
+        })
+    }
+
+    /// Get the equity of the first range minus the equity of the second range.
+    pub fn equity_difference(&self) -> f64 {
+        self.equities[0] - self.equities[1]
+    }
+
+    /// Get the share of each range in the top `fraction` of combos of both ranges,
+    /// ranked by equities against the other range.
+    pub fn top_share(&self, fraction: f64) -> Result<[f64; 2], PokercraftLocalError> {
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Fraction should be in (0, 1], got {}",
+                fraction
+            )));
+        }
+        let mut pooled: Vec<(f64, usize)> = (0..2)
+            .flat_map(|side| {
+                self.combo_equities[side]
+                    .iter()
+                    .map(move |(_, equity)| (*equity, side))
+            })
+            .collect();
+        pooled.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let top = ((pooled.len() as f64 * fraction).ceil() as usize).max(1);
+        let mut counts = [0; 2];
+        for (_, side) in pooled.iter().take(top) {
+            counts[*side] += 1;
+        }
+        let top = counts[0] + counts[1];
+        Ok(counts.map(|count| count as f64 / top as f64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+    use crate::equity::RangeEquityResult;
+
+    #[test]
+    fn test_range_advantage() -> Result<(), PokercraftLocalError> {
+        // Sets of kings are the nuts on a dry river
+        let board = parse_cards("Ks 9h 7d 3c 2s")?;
+        let ranges = [HandRange::try_from("KK,99")?, HandRange::try_from("AK,QQ")?];
+        let advantage = RangeAdvantage::new([&ranges[0], &ranges[1]], &board)?;
+        assert_eq!(advantage.nut_combos, [3, 0]);
+        assert_eq!(advantage.equities, [1.0, 0.0]);
+        assert_eq!(advantage.equity_difference(), 1.0);
+        assert_eq!(advantage.combo_equities[0].len(), 6);
+        assert_eq!(advantage.combo_equities[1].len(), 12 + 6);
+        assert_eq!(advantage.top_share(0.25)?, [1.0, 0.0]);
+        assert_eq!(advantage.top_share(1.0)?, [0.25, 0.75]);
+        assert!(advantage.top_share(0.0).is_err());
+
+        // Exact equities on the flop, where both players have the same combo sometimes
+        let board = parse_cards("2c 7d 9h")?;
+        let ranges = [
+            HandRange::try_from("AsAh")?,
+            HandRange::try_from("KdKc,AsAh")?,
+        ];
+        let advantage = RangeAdvantage::new([&ranges[0], &ranges[1]], &board)?;
+        let exact = RangeEquityResult::new(
+            &[ranges[0].clone(), HandRange::try_from("KdKc")?],
+            &board,
+            0,
+        )?;
+        assert!((advantage.equities[0] - exact.outcome(0)?.equity()).abs() < 1e-9);
+        assert!((advantage.equities[0] + advantage.equities[1] - 1.0).abs() < 1e-9);
+        assert_eq!(advantage.combo_equities[1].len(), 1);
+        assert_eq!(advantage.nut_combos, [0, 0]);
+
+        let ranges = [HandRange::try_from("Ks9s")?, HandRange::try_from("QQ")?];
+        assert!(RangeAdvantage::new([&ranges[0], &ranges[1]], &parse_cards("Ks 9s 2c")?).is_err());
+        assert!(RangeAdvantage::new([&ranges[0], &ranges[1]], &parse_cards("2c 3c")?).is_err());
+        Ok(())
+    }
+}
@@ -3,18 +3,26 @@
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 #[cfg(feature = "wasm")]
+use wasm_bindgen::JsCast;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::JsValue;
 
-use rand::{thread_rng, Rng};
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
 use rayon::prelude::*;
 
 use crate::errors::PokercraftLocalError;
+#[cfg(feature = "wasm")]
+use crate::utils::JsProgress;
+use crate::utils::{in_thread_pool, Progress};
 
 /// Represents a bankruptcy metric.
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub struct BankruptcyMetric {
     /// Holds `(relative_return, iteration)` tuples.
     /// (Relative return = final capital / initial capital)
+    /// Iteration is where the simulation stopped early by bankruptcy
+    /// or reaching the profit exit capital, and 0 if it played all iterations.
     simulated_results: Vec<(f64, u32)>,
 }
 
@@ -39,6 +47,13 @@ impl BankruptcyMetric {
         self.simulated_results.len()
     }
 
+    /// Get all `(relative_return, iteration)` results of simulations,
+    /// where iteration is the iteration number of going bankrupt (relative return `0`)
+    /// or reaching the profit exit capital, and 0 if neither happened.
+    pub fn simulated_results(&self) -> &[(f64, u32)] {
+        &self.simulated_results
+    }
+
     /// Get the bankruptcy rate. This is not cached.
     pub fn get_bankruptcy_rate(&self) -> f64 {
         if self.simulated_results.is_empty() {
@@ -77,6 +92,61 @@ impl BankruptcyMetric {
             .count() as f64)
             / (self.len() as f64)
     }
+
+    /// Get the rate of simulations reaching the profit exit capital. This is not cached.
+    pub fn get_target_reached_rate(&self) -> f64 {
+        if self.simulated_results.is_empty() {
+            return 0.0;
+        }
+        self.target_iterations().len() as f64 / self.len() as f64
+    }
+
+    /// Get iteration numbers of reaching the profit exit capital,
+    /// of simulations which reached it.
+    pub fn target_iterations(&self) -> Vec<u32> {
+        self.simulated_results
+            .iter()
+            .filter(|(capital, it)| *capital > 0.0 && *it > 0)
+            .map(|(_capital, it)| *it)
+            .collect()
+    }
+
+    /// Get the `q`-quantile (`0.0 <= q <= 1.0`) of iterations to reach the profit exit
+    /// capital, like the median time to double, among simulations which reached it.
+    /// Returns `None` if no simulation reached it. This is not cached.
+    pub fn get_time_to_target_quantile(&self, q: f64) -> Option<f64> {
+        let iterations = self.target_iterations();
+        (!iterations.is_empty())
+            .then(|| quantile(iterations.into_iter().map(f64::from).collect(), q))
+    }
+
+    /// Get the `q`-quantile (`0.0 <= q <= 1.0`) of relative returns,
+    /// interpolating linearly between simulations. This is not cached.
+    pub fn get_relative_return_quantile(&self, q: f64) -> f64 {
+        if self.simulated_results.is_empty() {
+            return 0.0;
+        }
+        quantile(
+            self.simulated_results
+                .iter()
+                .map(|(capital, _it)| *capital)
+                .collect(),
+            q,
+        )
+    }
+
+    /// Get rates and the median relative return of all simulations.
+    pub fn summary(&self) -> BankruptcySummary {
+        BankruptcySummary {
+            simulations: self.len(),
+            bankruptcy_rate: self.get_bankruptcy_rate(),
+            survival_rate: self.get_survival_rate(),
+            profitable_rate: self.get_profitable_rate(),
+            median_relative_return: self.get_relative_return_quantile(0.5),
+            target_reached_rate: self.get_target_reached_rate(),
+            median_time_to_target: self.get_time_to_target_quantile(0.5),
+        }
+    }
 }
 
 #[cfg(feature = "wasm")]
@@ -105,6 +175,287 @@ impl BankruptcyMetric {
     pub fn profitable_rate_wasm(&self) -> f64 {
         self.get_profitable_rate()
     }
+
+    /// Get the rate of simulations reaching the profit exit capital.
+    #[wasm_bindgen(getter, js_name = targetReachedRate)]
+    pub fn target_reached_rate_wasm(&self) -> f64 {
+        self.get_target_reached_rate()
+    }
+
+    /// Get the `q`-quantile of iterations to reach the profit exit capital,
+    /// or `undefined` if no simulation reached it.
+    #[wasm_bindgen(js_name = timeToTargetQuantile)]
+    pub fn time_to_target_quantile_wasm(&self, q: f64) -> Option<f64> {
+        self.get_time_to_target_quantile(q)
+    }
+
+    /// Get relative returns (final capital / initial capital) of all simulations.
+    #[wasm_bindgen(js_name = relativeReturns)]
+    pub fn relative_returns_wasm(&self) -> Vec<f64> {
+        self.simulated_results
+            .iter()
+            .map(|(relative_return, _iteration)| *relative_return)
+            .collect()
+    }
+
+    /// Get bankrupted iteration numbers (0 if not bankrupted) of all simulations.
+    #[wasm_bindgen(js_name = bankruptIterations)]
+    pub fn bankrupt_iterations_wasm(&self) -> Vec<u32> {
+        self.simulated_results
+            .iter()
+            .map(
+                |&(relative_return, iteration)| {
+                    if relative_return > 0.0 {
+                        0
+                    } else {
+                        iteration
+                    }
+                },
+            )
+            .collect()
+    }
+
+    /// Get rates and the median relative return as a plain object.
+    #[wasm_bindgen(js_name = toObject, unchecked_return_type = "BankruptcySummary")]
+    pub fn to_object_wasm(&self) -> Result<JsValue, JsValue> {
+        crate::utils::to_js_object(&self.summary())
+    }
+}
+
+/// Summary of bankroll simulations.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct BankruptcySummary {
+    /// Number of simulations performed.
+    pub simulations: usize,
+    pub bankruptcy_rate: f64,
+    pub survival_rate: f64,
+    pub profitable_rate: f64,
+    /// Median of final capital / initial capital.
+    pub median_relative_return: f64,
+    /// Rate of simulations reaching the profit exit capital.
+    pub target_reached_rate: f64,
+    /// Median iterations to reach the profit exit capital,
+    /// or `None` if no simulation reached it.
+    pub median_time_to_target: Option<f64>,
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(typescript_custom_section)]
+const TS_BANKRUPTCY_SUMMARY: &'static str = r#"
+/** Summary of bankroll simulations. */
+export interface BankruptcySummary {
+    /** Number of simulations performed. */
+    simulations: number;
+    bankruptcyRate: number;
+    survivalRate: number;
+    profitableRate: number;
+    /** Median of final capital / initial capital. */
+    medianRelativeReturn: number;
+    /** Rate of simulations reaching the profit exit capital. */
+    targetReachedRate: number;
+    /** Median iterations to reach the profit exit capital, or `null` if none reached it. */
+    medianTimeToTarget: number | null;
+}
+"#;
+
+/// Get the `q`-quantile of non-empty values, interpolating linearly.
+fn quantile(mut values: Vec<f64>, q: f64) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let position = q.clamp(0.0, 1.0) * (values.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    values[lower] + (values[upper] - values[lower]) * (position - lower as f64)
+}
+
+/// Growth rates of bankroll simulations where each buy-in is
+/// a fixed fraction of the current capital, so the capital never goes bankrupt.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct GrowthMetric {
+    /// Log growth of the capital per tournament of each simulation.
+    growth_rates: Vec<f64>,
+    /// Number of tournaments of each simulation.
+    iterations: u32,
+}
+
+impl GrowthMetric {
+    /// Get the number of simulations.
+    pub fn len(&self) -> usize {
+        self.growth_rates.len()
+    }
+
+    /// Check if there is no simulation.
+    pub fn is_empty(&self) -> bool {
+        self.growth_rates.is_empty()
+    }
+
+    /// Get the log growth of the capital per tournament of all simulations,
+    /// which is `ln(final capital / initial capital) / tournaments`.
+    pub fn growth_rates(&self) -> &[f64] {
+        &self.growth_rates
+    }
+
+    /// Get the average growth rate of all simulations.
+    pub fn get_mean_growth_rate(&self) -> f64 {
+        if self.growth_rates.is_empty() {
+            return 0.0;
+        }
+        self.growth_rates.iter().sum::<f64>() / self.len() as f64
+    }
+
+    /// Get the `q`-quantile (`0.0 <= q <= 1.0`) of growth rates,
+    /// interpolating linearly between simulations. This is not cached.
+    pub fn get_growth_rate_quantile(&self, q: f64) -> f64 {
+        if self.growth_rates.is_empty() {
+            return 0.0;
+        }
+        quantile(self.growth_rates.clone(), q)
+    }
+
+    /// Get the `q`-quantile of final capital / initial capital.
+    pub fn get_relative_return_quantile(&self, q: f64) -> f64 {
+        (self.get_growth_rate_quantile(q) * self.iterations as f64).exp()
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+impl GrowthMetric {
+    /// Get the number of simulations performed.
+    #[wasm_bindgen(getter, js_name = length)]
+    pub fn len_wasm(&self) -> usize {
+        self.len()
+    }
+
+    /// Get the average growth rate per tournament.
+    #[wasm_bindgen(getter, js_name = meanGrowthRate)]
+    pub fn mean_growth_rate_wasm(&self) -> f64 {
+        self.get_mean_growth_rate()
+    }
+
+    /// Get the `q`-quantile of growth rates per tournament.
+    #[wasm_bindgen(js_name = growthRateQuantile)]
+    pub fn growth_rate_quantile_wasm(&self, q: f64) -> f64 {
+        self.get_growth_rate_quantile(q)
+    }
+
+    /// Get the `q`-quantile of final capital / initial capital.
+    #[wasm_bindgen(js_name = relativeReturnQuantile)]
+    pub fn relative_return_quantile_wasm(&self, q: f64) -> f64 {
+        self.get_relative_return_quantile(q)
+    }
+
+    /// Get growth rates per tournament of all simulations.
+    #[wasm_bindgen(js_name = growthRates)]
+    pub fn growth_rates_wasm(&self) -> Vec<f64> {
+        self.growth_rates.clone()
+    }
+}
+
+/// Get the expected log growth of the capital per tournament, when each buy-in is
+/// `buy_in_fraction` of the current capital and results are equally likely
+/// net results in buy-ins, like `-1` for not cashing.
+pub fn expected_growth_rate(
+    results_in_buy_ins: &[f64],
+    buy_in_fraction: f64,
+) -> Result<f64, PokercraftLocalError> {
+    validate_proportional(results_in_buy_ins, buy_in_fraction)?;
+    Ok(results_in_buy_ins
+        .iter()
+        .map(|result| (1.0 + buy_in_fraction * result).ln())
+        .sum::<f64>()
+        / results_in_buy_ins.len() as f64)
+}
+
+/// Check parameters of proportional buy-in simulations.
+fn validate_proportional(
+    results_in_buy_ins: &[f64],
+    buy_in_fraction: f64,
+) -> Result<(), PokercraftLocalError> {
+    if !(buy_in_fraction > 0.0 && buy_in_fraction <= 1.0) {
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "Buy-in fraction should be in (0, 1], got {}",
+            buy_in_fraction
+        )));
+    } else if results_in_buy_ins.is_empty() {
+        return Err(PokercraftLocalError::GeneralError(
+            "Relative return results must not be empty".to_string(),
+        ));
+    } else if results_in_buy_ins
+        .iter()
+        .any(|result| !(1.0 + buy_in_fraction * result > 0.0 && result.is_finite()))
+    {
+        return Err(PokercraftLocalError::GeneralError(
+            "Every result should leave some capital with the buy-in fraction".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Simulate growth of the capital where each buy-in is `buy_in_fraction`
+/// of the current capital, like 1% of the roll, instead of a fixed amount.
+/// Each result is drawn from `results_in_buy_ins`, net results in buy-ins
+/// like `-1` for not cashing, and all simulations play `max_iteration` tournaments.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(max_iteration = max_iteration, simulation_count = simulation_count))
+)]
+pub fn simulate_proportional(
+    results_in_buy_ins: &[f64],
+    buy_in_fraction: f64,
+    max_iteration: u32,
+    simulation_count: u32,
+) -> Result<GrowthMetric, PokercraftLocalError> {
+    validate_proportional(results_in_buy_ins, buy_in_fraction)?;
+    if max_iteration < 1 {
+        return Err(PokercraftLocalError::GeneralError(
+            "Max iteration must be positive".to_string(),
+        ));
+    } else if simulation_count < 1 {
+        return Err(PokercraftLocalError::GeneralError(
+            "Simulation count must be positive".to_string(),
+        ));
+    }
+    let log_growths = results_in_buy_ins
+        .iter()
+        .map(|result| (1.0 + buy_in_fraction * result).ln())
+        .collect::<Vec<_>>();
+
+    let growth_rates = in_thread_pool(|| {
+        (0..simulation_count)
+            .into_par_iter()
+            .map(|_| {
+                let mut rng = thread_rng();
+                (0..max_iteration)
+                    .map(|_| log_growths[rng.gen_range(0..log_growths.len())])
+                    .sum::<f64>()
+                    / max_iteration as f64
+            })
+            .collect::<Vec<_>>()
+    });
+    Ok(GrowthMetric {
+        growth_rates,
+        iterations: max_iteration,
+    })
+}
+
+/// Simulate growth of the capital with proportional buy-ins (WASM interface).
+/// See `simulate_proportional` for details.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = simulateProportional)]
+pub fn simulate_proportional_wasm(
+    results_in_buy_ins: &[f64],
+    buy_in_fraction: f64,
+    max_iteration: u32,
+    simulation_count: u32,
+) -> Result<GrowthMetric, JsValue> {
+    simulate_proportional(
+        results_in_buy_ins,
+        buy_in_fraction,
+        max_iteration,
+        simulation_count,
+    )
+    .map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
 impl Default for BankruptcyMetric {
@@ -114,13 +465,507 @@ impl Default for BankruptcyMetric {
 }
 
 /// Simulate the bankruptcy metric (core implementation).
-pub fn simulate_core(
+/// Relative return results can be either owned or borrowed.
+pub fn simulate_core<R>(
     initial_capital: f64,
-    relative_return_results: Vec<f64>,
+    relative_return_results: R,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+) -> Result<BankruptcyMetric, PokercraftLocalError>
+where
+    R: AsRef<[f64]>,
+{
+    simulate_with_progress(
+        initial_capital,
+        relative_return_results,
+        max_iteration,
+        profit_exit_multiplier,
+        simulation_count,
+        &|_, _| true,
+    )
+}
+
+/// Same as `simulate_core`, reporting `(simulations done, simulation count)` to `on_progress`.
+/// The simulation is cancelled if `on_progress` returns `false`.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(max_iteration = max_iteration, simulation_count = simulation_count))
+)]
+pub fn simulate_with_progress<R>(
+    initial_capital: f64,
+    relative_return_results: R,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+    on_progress: &(dyn Fn(u64, u64) -> bool + Sync),
+) -> Result<BankruptcyMetric, PokercraftLocalError>
+where
+    R: AsRef<[f64]>,
+{
+    let relative_return_results = relative_return_results.as_ref();
+    validate_simulation(
+        initial_capital,
+        relative_return_results,
+        max_iteration,
+        simulation_count,
+    )?;
+
+    let progress = Progress::new(simulation_count as u64, on_progress);
+    let metric = BankruptcyMetric::new(in_thread_pool(|| {
+        (0..simulation_count)
+            .into_par_iter()
+            .map(|_| {
+                progress.tick()?;
+                Ok(simple_monte_carlo_loop(
+                    initial_capital,
+                    relative_return_results,
+                    max_iteration,
+                    Some(profit_exit_multiplier),
+                    &mut thread_rng(),
+                ))
+            })
+            .collect::<Result<Vec<_>, PokercraftLocalError>>()
+    })?);
+    Ok(metric)
+}
+
+/// Get the bankruptcy rate in `max_iteration` tournaments without exiting on profit,
+/// where the `i`-th simulation draws from a random generator seeded with `seed + i`.
+fn seeded_ruin_rate(
+    initial_capital: f64,
+    relative_return_results: &[f64],
+    max_iteration: u32,
+    simulation_count: u32,
+    seed: u64,
+) -> Result<f64, PokercraftLocalError> {
+    validate_simulation(
+        initial_capital,
+        relative_return_results,
+        max_iteration,
+        simulation_count,
+    )?;
+    let metric = BankruptcyMetric::new(in_thread_pool(|| {
+        (0..simulation_count as u64)
+            .into_par_iter()
+            .map(|simulation| {
+                simple_monte_carlo_loop(
+                    initial_capital,
+                    relative_return_results,
+                    max_iteration,
+                    None,
+                    &mut StdRng::seed_from_u64(seed.wrapping_add(simulation)),
+                )
+            })
+            .collect::<Vec<_>>()
+    }));
+    Ok(metric.get_bankruptcy_rate())
+}
+
+/// Find the minimum initial capital whose bankruptcy rate in `max_iteration` tournaments
+/// is at most `target_ruin_rate`, by bisection over simulations without exiting on profit.
+/// Every step replays the same `simulation_count` random sequences,
+/// so the ruin rate never increases with the capital and the bisection is consistent.
+/// The result is within 1% above the minimum for those sequences.
+pub fn required_bankroll(
+    relative_return_results: &[f64],
+    target_ruin_rate: f64,
+    max_iteration: u32,
+    simulation_count: u32,
+) -> Result<f64, PokercraftLocalError> {
+    if !(target_ruin_rate > 0.0 && target_ruin_rate < 1.0) {
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "Target ruin rate should be in (0, 1), got {}",
+            target_ruin_rate
+        )));
+    }
+    let seed = thread_rng().gen::<u64>();
+    let ruin_rate = |capital: f64| {
+        seeded_ruin_rate(
+            capital,
+            relative_return_results,
+            max_iteration,
+            simulation_count,
+            seed,
+        )
+    };
+
+    // Start from the biggest loss, doubling until the ruin rate is low enough
+    let mut upper = relative_return_results
+        .iter()
+        .fold(0.0, |biggest: f64, result| biggest.max(-result))
+        .max(f64::MIN_POSITIVE);
+    let mut lower = 0.0;
+    while ruin_rate(upper)? > target_ruin_rate {
+        lower = upper;
+        upper *= 2.0;
+        if !upper.is_finite() {
+            return Err(PokercraftLocalError::GeneralError(
+                "No bankroll reaches the target ruin rate".to_string(),
+            ));
+        }
+    }
+    while upper - lower > upper * 0.01 {
+        let middle = (lower + upper) / 2.0;
+        if ruin_rate(middle)? > target_ruin_rate {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+    Ok(upper)
+}
+
+/// Find the minimum initial capital for the target bankruptcy rate (WASM interface).
+/// See `required_bankroll` for details.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = requiredBankroll)]
+pub fn required_bankroll_wasm(
+    relative_return_results: &[f64],
+    target_ruin_rate: f64,
+    max_iteration: u32,
+    simulation_count: u32,
+) -> Result<f64, JsValue> {
+    required_bankroll(
+        relative_return_results,
+        target_ruin_rate,
+        max_iteration,
+        simulation_count,
+    )
+    .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Cash regularly moved in or out of the bankroll, like deposits or living expenses.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct CashFlow {
+    /// Number of tournaments between cash flows, like tournaments played per month.
+    pub every: u32,
+    /// Fixed amount added each time; Negative for withdrawals.
+    pub amount: f64,
+    /// Fraction of the current capital added each time; Negative for withdrawals.
+    pub fraction: f64,
+}
+
+impl CashFlow {
+    fn validate(&self) -> Result<(), PokercraftLocalError> {
+        if self.every < 1 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Cash flow interval must be positive".to_string(),
+            ));
+        } else if !self.amount.is_finite() {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Invalid cash flow amount {}",
+                self.amount
+            )));
+        } else if !(self.fraction > -1.0 && self.fraction.is_finite()) {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Cash flow fraction must be greater than -1, got {}",
+                self.fraction
+            )));
+        }
+        Ok(())
+    }
+
+    /// Get the cash flow after the given number of tournaments played.
+    fn after(&self, played: u32, capital: f64) -> f64 {
+        if played.is_multiple_of(self.every) {
+            self.amount + self.fraction * capital
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Same as `simulate_core`, but the given cash flows are applied after every
+/// scheduled number of tournaments. Withdrawing below zero capital is a bankruptcy,
+/// and final capitals do not include withdrawn cash.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(max_iteration = max_iteration, simulation_count = simulation_count))
+)]
+pub fn simulate_with_cash_flows<R>(
+    initial_capital: f64,
+    relative_return_results: R,
+    cash_flows: &[CashFlow],
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+) -> Result<BankruptcyMetric, PokercraftLocalError>
+where
+    R: AsRef<[f64]>,
+{
+    let relative_return_results = relative_return_results.as_ref();
+    validate_simulation(
+        initial_capital,
+        relative_return_results,
+        max_iteration,
+        simulation_count,
+    )?;
+    for cash_flow in cash_flows {
+        cash_flow.validate()?;
+    }
+
+    let metric = BankruptcyMetric::new(in_thread_pool(|| {
+        (0..simulation_count)
+            .into_par_iter()
+            .map(|_| {
+                monte_carlo_loop(
+                    initial_capital,
+                    max_iteration,
+                    Some(profit_exit_multiplier),
+                    cash_flows,
+                    &mut thread_rng(),
+                    |rng| relative_return_results[rng.gen_range(0..relative_return_results.len())],
+                )
+            })
+            .collect::<Vec<_>>()
+    }));
+    Ok(metric)
+}
+
+/// Same as `simulate_core`, but each relative return is drawn from
+/// `(relative return, weight)` outcomes with probability proportional to the weight.
+/// Useful when some outcomes are too rare to appear in a list of results,
+/// like jackpots of lottery sit & go formats.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(max_iteration = max_iteration, simulation_count = simulation_count))
+)]
+pub fn simulate_weighted(
+    initial_capital: f64,
+    outcomes: &[(f64, f64)],
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+) -> Result<BankruptcyMetric, PokercraftLocalError> {
+    if outcomes
+        .iter()
+        .any(|&(_, weight)| !(weight >= 0.0 && weight.is_finite()))
+    {
+        return Err(PokercraftLocalError::GeneralError(
+            "Weights must not be negative".to_string(),
+        ));
+    }
+    let mut cumulative_weights = Vec::with_capacity(outcomes.len());
+    let mut total_weight = 0.0;
+    for &(_, weight) in outcomes.iter() {
+        total_weight += weight;
+        cumulative_weights.push(total_weight);
+    }
+    if total_weight <= 0.0 {
+        return Err(PokercraftLocalError::GeneralError(
+            "Total weight must be positive".to_string(),
+        ));
+    }
+    // The expected relative return stands for all outcomes on validation
+    let expected = outcomes
+        .iter()
+        .map(|&(relative_return, weight)| relative_return * weight)
+        .sum::<f64>()
+        / total_weight;
+    validate_simulation(
+        initial_capital,
+        &[expected],
+        max_iteration,
+        simulation_count,
+    )?;
+
+    let metric = BankruptcyMetric::new(in_thread_pool(|| {
+        (0..simulation_count)
+            .into_par_iter()
+            .map(|_| {
+                monte_carlo_loop(
+                    initial_capital,
+                    max_iteration,
+                    Some(profit_exit_multiplier),
+                    &[],
+                    &mut thread_rng(),
+                    |rng| {
+                        let target = rng.gen::<f64>() * total_weight;
+                        let idx = cumulative_weights
+                            .partition_point(|&cumulative| cumulative <= target)
+                            .min(outcomes.len() - 1);
+                        outcomes[idx].0
+                    },
+                )
+            })
+            .collect::<Vec<_>>()
+    }));
+    Ok(metric)
+}
+
+/// Buy-in and prizes of a tournament, to simulate bankroll by finish positions.
+#[derive(PartialEq, Clone, Debug)]
+pub struct TournamentStructure {
+    /// Buy-in including rake.
+    pub buy_in: f64,
+    /// Number of entries.
+    pub entries: u32,
+    /// Prizes of the 1st, 2nd, ... places; Later places win nothing.
+    pub payouts: Vec<f64>,
+}
+
+impl TournamentStructure {
+    fn validate(&self) -> Result<(), PokercraftLocalError> {
+        if !(self.buy_in > 0.0 && self.buy_in.is_finite()) {
+            return Err(PokercraftLocalError::GeneralError(
+                "Buy-in must be positive".to_string(),
+            ));
+        } else if self.entries < 1 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Entries must be positive".to_string(),
+            ));
+        } else if self.payouts.len() > self.entries as usize {
+            return Err(PokercraftLocalError::GeneralError(
+                "More payouts than entries".to_string(),
+            ));
+        } else if self
+            .payouts
+            .iter()
+            .any(|&payout| !(payout >= 0.0 && payout.is_finite()))
+        {
+            return Err(PokercraftLocalError::GeneralError(
+                "Payouts must not be negative".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the prize minus the buy-in of the given place (1-based).
+    pub fn relative_return(&self, place: u32) -> f64 {
+        let prize = match place {
+            0 => 0.0,
+            place => self
+                .payouts
+                .get(place as usize - 1)
+                .copied()
+                .unwrap_or_default(),
+        };
+        prize - self.buy_in
+    }
+
+    /// Get places (1-based) of this structure at the same percentile as
+    /// `rank` (1-based) among `total_players` of another tournament.
+    /// For example, the 1st of 100 players is one of the top 10 of 1000 entries.
+    pub fn scaled_places(
+        &self,
+        rank: u32,
+        total_players: u32,
+    ) -> Result<std::ops::RangeInclusive<u32>, PokercraftLocalError> {
+        if rank < 1 || rank > total_players {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Rank {} is out of 1 to {} players",
+                rank, total_players
+            )));
+        }
+        let (rank, total_players, entries) =
+            (rank as u64, total_players as u64, self.entries as u64);
+        let first = (rank - 1) * entries / total_players + 1;
+        let last = (rank * entries).div_ceil(total_players).max(first);
+        Ok(first as u32..=last as u32)
+    }
+}
+
+/// Same as `simulate_core`, but each tournament is played in the given structure,
+/// with a finish position resampled from `(rank, total players)` of past tournaments
+/// and scaled to the same percentile by `TournamentStructure::scaled_places`.
+/// Unlike resampling net results, big prizes of large fields are kept in returns.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(max_iteration = max_iteration, simulation_count = simulation_count))
+)]
+pub fn simulate_finishes(
+    initial_capital: f64,
+    finishes: &[(u32, u32)],
+    structure: &TournamentStructure,
     max_iteration: u32,
     profit_exit_multiplier: f64,
     simulation_count: u32,
 ) -> Result<BankruptcyMetric, PokercraftLocalError> {
+    structure.validate()?;
+    let places = finishes
+        .iter()
+        .map(|&(rank, total_players)| structure.scaled_places(rank, total_players))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Average returns of scaled places stand for finishes on validation
+    let expected = places
+        .iter()
+        .map(|places| {
+            places
+                .clone()
+                .map(|place| structure.relative_return(place))
+                .sum::<f64>()
+                / (places.end() - places.start() + 1) as f64
+        })
+        .collect::<Vec<_>>();
+    validate_simulation(initial_capital, &expected, max_iteration, simulation_count)?;
+
+    let metric = BankruptcyMetric::new(in_thread_pool(|| {
+        (0..simulation_count)
+            .into_par_iter()
+            .map(|_| {
+                monte_carlo_loop(
+                    initial_capital,
+                    max_iteration,
+                    Some(profit_exit_multiplier),
+                    &[],
+                    &mut thread_rng(),
+                    |rng| {
+                        let places = &places[rng.gen_range(0..places.len())];
+                        structure.relative_return(rng.gen_range(places.clone()))
+                    },
+                )
+            })
+            .collect::<Vec<_>>()
+    }));
+    Ok(metric)
+}
+
+/// Same as `simulate_core`, but simulations run on GPU in single precision.
+/// Falls back to `simulate_core` if there is no GPU adapter.
+#[cfg(feature = "gpu")]
+pub fn simulate_gpu<R>(
+    initial_capital: f64,
+    relative_return_results: R,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+) -> Result<BankruptcyMetric, PokercraftLocalError>
+where
+    R: AsRef<[f64]>,
+{
+    let Some(gpu) = crate::gpu::GpuContext::shared() else {
+        return simulate_core(
+            initial_capital,
+            relative_return_results,
+            max_iteration,
+            profit_exit_multiplier,
+            simulation_count,
+        );
+    };
+    let relative_return_results = relative_return_results.as_ref();
+    validate_simulation(
+        initial_capital,
+        relative_return_results,
+        max_iteration,
+        simulation_count,
+    )?;
+    Ok(BankruptcyMetric::new(gpu.bankroll(
+        initial_capital,
+        relative_return_results,
+        max_iteration,
+        exit_capital(initial_capital, Some(profit_exit_multiplier)),
+        simulation_count,
+    )?))
+}
+
+/// Check parameters of bankroll simulations.
+fn validate_simulation(
+    initial_capital: f64,
+    relative_return_results: &[f64],
+    max_iteration: u32,
+    simulation_count: u32,
+) -> Result<(), PokercraftLocalError> {
     if initial_capital <= 0.0 {
         return Err(PokercraftLocalError::GeneralError(
             "Initial capital must be positive".to_string(),
@@ -142,55 +987,241 @@ pub fn simulate_core(
             "Simulation count must be positive".to_string(),
         ));
     }
-
-    let metric = BankruptcyMetric::new(
-        (0..simulation_count)
-            .into_par_iter()
-            .map(|_| {
-                simple_monte_carlo_loop(
-                    initial_capital,
-                    &relative_return_results,
-                    max_iteration,
-                    Some(profit_exit_multiplier),
-                )
-            })
-            .collect::<Vec<_>>(),
-    );
-    Ok(metric)
+    Ok(())
 }
 
 /// Simulate the bankruptcy metric (WASM interface).
+/// `progress(done, total)` is called about every 1% of simulations,
+/// and returning `false` from it cancels the simulation.
 /// Note: Uses sequential iteration since rayon doesn't work in WASM without special setup.
 #[cfg(feature = "wasm")]
 #[wasm_bindgen(js_name = simulate)]
 pub fn simulate_wasm(
     initial_capital: f64,
-    relative_return_results: Vec<f64>,
+    relative_return_results: &[f64],
     max_iteration: u32,
     profit_exit_multiplier: f64,
     simulation_count: u32,
+    #[wasm_bindgen(unchecked_param_type = "(done: number, total: number) => boolean | void")]
+    progress: Option<js_sys::Function>,
 ) -> Result<BankruptcyMetric, JsValue> {
-    simulate_core(
+    let progress = JsProgress::new(progress);
+    simulate_with_progress(
         initial_capital,
         relative_return_results,
         max_iteration,
         profit_exit_multiplier,
         simulation_count,
+        &|done, total| progress.report(done, total),
+    )
+    .map_err(|e| progress.convert_error(e))
+}
+
+/// Simulate the bankruptcy metric by finish positions (WASM interface).
+/// `ranks[i]` of `total_players[i]` is a finish of a past tournament;
+/// See `simulate_finishes` for details.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = simulateFinishes)]
+#[allow(clippy::too_many_arguments)]
+pub fn simulate_finishes_wasm(
+    initial_capital: f64,
+    ranks: Vec<u32>,
+    total_players: Vec<u32>,
+    buy_in: f64,
+    entries: u32,
+    payouts: Vec<f64>,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+) -> Result<BankruptcyMetric, JsValue> {
+    if ranks.len() != total_players.len() {
+        return Err(JsValue::from_str(
+            "Ranks and total players must have the same length",
+        ));
+    }
+    let finishes = ranks.into_iter().zip(total_players).collect::<Vec<_>>();
+    let structure = TournamentStructure {
+        buy_in,
+        entries,
+        payouts,
+    };
+    simulate_finishes(
+        initial_capital,
+        &finishes,
+        &structure,
+        max_iteration,
+        profit_exit_multiplier,
+        simulation_count,
     )
     .map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
+/// Simulate the bankruptcy metric and get its summary as a plain object (WASM interface).
+/// Unlike `simulate`, there is no handle to free.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = simulateSummary, unchecked_return_type = "BankruptcySummary")]
+pub fn simulate_summary_wasm(
+    initial_capital: f64,
+    relative_return_results: &[f64],
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+    #[wasm_bindgen(unchecked_param_type = "(done: number, total: number) => boolean | void")]
+    progress: Option<js_sys::Function>,
+) -> Result<JsValue, JsValue> {
+    let metric = simulate_wasm(
+        initial_capital,
+        relative_return_results,
+        max_iteration,
+        profit_exit_multiplier,
+        simulation_count,
+        progress,
+    )?;
+    crate::utils::to_js_object(&metric.summary())
+}
+
+/// Bankroll simulations of `simulate_stream_wasm` done so far.
+#[cfg(feature = "wasm")]
+struct SimulationStream {
+    initial_capital: f64,
+    relative_return_results: Vec<f64>,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    remaining: u32,
+    snapshot_every: u32,
+    metric: BankruptcyMetric,
+}
+
+#[cfg(feature = "wasm")]
+impl SimulationStream {
+    /// Run the next `snapshot_every` simulations;
+    /// Returns `false` if all simulations were already done.
+    fn advance(&mut self) -> Result<bool, PokercraftLocalError> {
+        if self.remaining == 0 {
+            return Ok(false);
+        }
+        let count = self.snapshot_every.min(self.remaining);
+        let chunk = simulate_core(
+            self.initial_capital,
+            &self.relative_return_results,
+            self.max_iteration,
+            self.profit_exit_multiplier,
+            count,
+        )?;
+        for &result in chunk.simulated_results() {
+            self.metric.push(result);
+        }
+        self.remaining -= count;
+        Ok(true)
+    }
+}
+
+/// Wait for the next macrotask, so the browser can render between chunks.
+#[cfg(feature = "wasm")]
+async fn yield_to_event_loop() -> Result<(), JsValue> {
+    let global = js_sys::global();
+    let set_timeout: js_sys::Function =
+        js_sys::Reflect::get(&global, &"setTimeout".into())?.dyn_into()?;
+    let mut schedule = |resolve: js_sys::Function, _reject: js_sys::Function| {
+        let _ = set_timeout.call2(&global, &resolve, &0.into());
+    };
+    wasm_bindgen_futures::JsFuture::from(js_sys::Promise::new(&mut schedule)).await?;
+    Ok(())
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(typescript_custom_section)]
+const TS_SIMULATE_STREAM: &'static str = r#"
+export type BankruptcySummaryStream = AsyncIterableIterator<BankruptcySummary>;
+"#;
+
+/// Simulate the bankruptcy metric in chunks of `snapshot_every` simulations (WASM interface).
+/// Returns an async iterator yielding summaries of all simulations done so far,
+/// so estimates can be rendered while converging:
+/// `for await (const summary of simulateStream(...)) { ... }`
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = simulateStream, unchecked_return_type = "BankruptcySummaryStream")]
+pub fn simulate_stream_wasm(
+    initial_capital: f64,
+    relative_return_results: &[f64],
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+    snapshot_every: u32,
+) -> Result<JsValue, JsValue> {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use wasm_bindgen::closure::Closure;
+
+    if snapshot_every < 1 {
+        return Err(JsValue::from_str("Snapshot interval must be positive"));
+    }
+    let stream = Rc::new(RefCell::new(SimulationStream {
+        initial_capital,
+        relative_return_results: relative_return_results.to_vec(),
+        max_iteration,
+        profit_exit_multiplier,
+        remaining: simulation_count,
+        snapshot_every,
+        metric: BankruptcyMetric::default(),
+    }));
+
+    let next = Closure::<dyn FnMut() -> js_sys::Promise>::new(move || {
+        let stream = stream.clone();
+        wasm_bindgen_futures::future_to_promise(async move {
+            yield_to_event_loop().await?;
+            let mut stream = stream.borrow_mut();
+            let advanced = stream.advance()?;
+            let result = js_sys::Object::new();
+            js_sys::Reflect::set(&result, &"done".into(), &(!advanced).into())?;
+            if advanced {
+                let summary = crate::utils::to_js_object(&stream.metric.summary())?;
+                js_sys::Reflect::set(&result, &"value".into(), &summary)?;
+            }
+            Ok(result.into())
+        })
+    });
+    let iterator = js_sys::Object::new();
+    js_sys::Reflect::set(&iterator, &"next".into(), &next.into_js_value())?;
+    let this = iterator.clone();
+    let async_iterator =
+        Closure::<dyn FnMut() -> js_sys::Object>::new(move || this.clone()).into_js_value();
+    js_sys::Reflect::set(
+        &iterator,
+        &js_sys::Symbol::async_iterator(),
+        &async_iterator,
+    )?;
+    Ok(iterator.into())
+}
+
+/// Get the capital to exit simulations with profit;
+/// `f64::MAX` if the multiplier is not given or less than `1.0`.
+fn exit_capital(initial_capital: f64, profit_exit_multiplier: Option<f64>) -> f64 {
+    match profit_exit_multiplier {
+        Some(profit_exit_multiplier) => {
+            if profit_exit_multiplier >= 1.0 {
+                initial_capital * profit_exit_multiplier
+            } else {
+                f64::MAX
+            }
+        }
+        None => f64::MAX,
+    }
+}
+
 /// Simple Monte Carlo simulation loop;
 /// Returns the final value of the portfolio (0.0 if bankrupted)
-/// and bankrupted iteration number (0 if not bankrupted).
+/// and the iteration number of going bankrupt or reaching the profit exit capital
+/// (0 if neither happened).
 /// If there is an error on value of parameters,
 /// no simulation will be done
 /// and the function will return `(0.0, 0)`.
-fn simple_monte_carlo_loop(
+fn simple_monte_carlo_loop<R: Rng>(
     initial_capital: f64,
-    relative_return_results: &Vec<f64>,
+    relative_return_results: &[f64],
     max_iteration: u32,
     profit_exit_multiplier: Option<f64>,
+    rng: &mut R,
 ) -> (f64, u32) {
     if initial_capital <= 0.0
         || relative_return_results.is_empty()
@@ -199,28 +1230,234 @@ fn simple_monte_carlo_loop(
     {
         return (0.0, 0);
     }
-    let exit_capital: f64 = match profit_exit_multiplier {
-        Some(profit_exit_multiplier) => {
-            if profit_exit_multiplier >= 1.0 {
-                initial_capital * profit_exit_multiplier
-            } else {
-                f64::MAX
-            }
-        }
-        None => f64::MAX,
-    };
-    let mut rng = thread_rng();
+    monte_carlo_loop(
+        initial_capital,
+        max_iteration,
+        profit_exit_multiplier,
+        &[],
+        rng,
+        |rng| relative_return_results[rng.gen_range(0..relative_return_results.len())],
+    )
+}
+
+/// Monte Carlo simulation loop drawing each relative return from `sample` with `rng`,
+/// and applying `cash_flows` after each relative return;
+/// Returns the same as `simple_monte_carlo_loop`.
+fn monte_carlo_loop<R, F>(
+    initial_capital: f64,
+    max_iteration: u32,
+    profit_exit_multiplier: Option<f64>,
+    cash_flows: &[CashFlow],
+    rng: &mut R,
+    mut sample: F,
+) -> (f64, u32)
+where
+    R: Rng,
+    F: FnMut(&mut R) -> f64,
+{
+    let exit_capital = exit_capital(initial_capital, profit_exit_multiplier);
     let mut capital = initial_capital;
     for i in 0..max_iteration {
-        let idx: usize = rng.gen_range(0..relative_return_results.len());
-        capital += relative_return_results[idx];
+        capital += sample(rng);
+        if capital > 0.0 {
+            capital += cash_flows
+                .iter()
+                .map(|cash_flow| cash_flow.after(i + 1, capital))
+                .sum::<f64>();
+        }
         if capital <= 0.0 {
             // Bankrupted
             return (0.0, i + 1);
         } else if capital >= exit_capital {
             // Exit if profit is reached
-            return (capital / initial_capital, 0);
+            return (capital / initial_capital, i + 1);
         }
     }
     (f64::max(capital / initial_capital, 0.0), 0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantile() -> Result<(), PokercraftLocalError> {
+        let metric = BankruptcyMetric::new([(0.0, 3), (2.0, 0), (1.5, 0), (1.0, 0), (0.5, 0)]);
+        assert_eq!(metric.get_relative_return_quantile(0.0), 0.0);
+        assert_eq!(metric.get_relative_return_quantile(0.5), 1.0);
+        assert_eq!(metric.get_relative_return_quantile(0.875), 1.75);
+        assert_eq!(metric.get_relative_return_quantile(1.0), 2.0);
+        assert_eq!(
+            BankruptcyMetric::default().get_relative_return_quantile(0.5),
+            0.0
+        );
+        let summary = metric.summary();
+        assert_eq!(
+            (summary.simulations, summary.median_relative_return),
+            (5, 1.0)
+        );
+        assert_eq!(summary.bankruptcy_rate, 0.2);
+        assert_eq!(summary.median_time_to_target, None);
+
+        // Doubling 10 by winning 1 every tournament takes 10 tournaments
+        let metric = simulate_core(10.0, [1.0], 100, 2.0, 20)?;
+        assert_eq!(metric.get_target_reached_rate(), 1.0);
+        assert_eq!(metric.get_time_to_target_quantile(0.5), Some(10.0));
+        assert_eq!(metric.get_bankruptcy_rate(), 0.0);
+        let metric = BankruptcyMetric::new([(2.0, 10), (2.1, 30), (0.0, 5), (1.5, 0)]);
+        assert_eq!(metric.target_iterations(), vec![10, 30]);
+        assert_eq!(metric.get_target_reached_rate(), 0.5);
+        assert_eq!(metric.get_time_to_target_quantile(0.5), Some(20.0));
+        assert_eq!(metric.summary().median_time_to_target, Some(20.0));
+
+        let metric = simulate_core(10.0, vec![1.0, -1.0, 3.0], 100, 2.0, 200)?;
+        assert_eq!(metric.len(), 200);
+        let reports = std::sync::Mutex::new(Vec::new());
+        simulate_with_progress(10.0, [1.0, -1.0, 3.0], 100, 2.0, 200, &|done, total| {
+            reports.lock().unwrap().push((done, total));
+            true
+        })?;
+        assert_eq!(reports.lock().unwrap().len(), 100);
+        assert!(matches!(
+            simulate_with_progress(10.0, [1.0], 100, 2.0, 200, &|done, _| done < 50),
+            Err(PokercraftLocalError::Cancelled)
+        ));
+        assert!(
+            metric.get_relative_return_quantile(0.1) <= metric.get_relative_return_quantile(0.9)
+        );
+
+        // Outcomes of zero weight are never drawn
+        let metric = simulate_weighted(10.0, &[(1.0, 1.0), (-100.0, 0.0)], 100, 2.0, 200)?;
+        assert_eq!(metric.get_bankruptcy_rate(), 0.0);
+        assert_eq!(metric.get_profitable_rate(), 1.0);
+        let metric = simulate_weighted(1.0, &[(-1.0, 1.0), (3.0, 1.0)], 100, 2.0, 1000)?;
+        assert!(metric.get_bankruptcy_rate() > 0.3 && metric.get_bankruptcy_rate() < 0.7);
+        assert!(simulate_weighted(10.0, &[(1.0, -1.0)], 100, 2.0, 200).is_err());
+        assert!(simulate_weighted(10.0, &[(1.0, 0.0)], 100, 2.0, 200).is_err());
+        assert!(simulate_weighted(10.0, &[(-1.0, 1.0)], 100, 2.0, 200).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_required_bankroll() -> Result<(), PokercraftLocalError> {
+        // Any capital above 10 survives 10 tournaments losing 1 at most
+        let capital = required_bankroll(&[-1.0, 3.0], 0.5, 10, 200)?;
+        assert!(capital > 0.0 && capital < 10.0);
+        let safer = required_bankroll(&[-1.0, 3.0], 0.01, 10, 200)?;
+        assert!(safer > capital && safer < 11.0);
+        assert!(required_bankroll(&[-1.0], 0.5, 10, 200).is_err());
+        assert!(required_bankroll(&[-1.0, 3.0], 0.0, 10, 200).is_err());
+        assert!(required_bankroll(&[-1.0, 3.0], 1.0, 10, 200).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeded_ruin_rate() -> Result<(), PokercraftLocalError> {
+        let results = [-1.0, -1.0, 2.5];
+        let rates = (1..=20)
+            .map(|capital| seeded_ruin_rate(capital as f64 / 2.0, &results, 20, 200, 2170))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(rates.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert!(rates[0] > rates[19]);
+        assert_eq!(
+            seeded_ruin_rate(3.0, &results, 20, 200, 2170)?,
+            seeded_ruin_rate(3.0, &results, 20, 200, 2170)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_simulate_proportional() -> Result<(), PokercraftLocalError> {
+        // Doubling or losing the buy-in, with 10% of the capital in each buy-in
+        let results = [1.0, -1.0];
+        let expected = expected_growth_rate(&results, 0.1)?;
+        assert!((expected - (1.1f64.ln() + 0.9f64.ln()) / 2.0).abs() < 1e-12);
+        assert!(expected < 0.0);
+
+        let metric = simulate_proportional(&results, 0.1, 1000, 200)?;
+        assert_eq!(metric.len(), 200);
+        assert!((metric.get_mean_growth_rate() - expected).abs() < 1e-3);
+        assert!(metric.get_growth_rate_quantile(0.1) <= metric.get_growth_rate_quantile(0.9));
+        let median = metric.get_relative_return_quantile(0.5);
+        assert!(median > 0.0 && median < 1.0);
+
+        // Always winning grows exactly
+        let metric = simulate_proportional(&[2.0], 0.5, 10, 5)?;
+        assert!(metric
+            .growth_rates()
+            .iter()
+            .all(|&rate| (rate - 2f64.ln()).abs() < 1e-12));
+        assert!((metric.get_relative_return_quantile(0.5) - 1024.0).abs() < 1e-6);
+
+        assert!(simulate_proportional(&results, 0.0, 10, 5).is_err());
+        assert!(simulate_proportional(&[-1.0], 1.0, 10, 5).is_err());
+        assert!(simulate_proportional(&results, 0.1, 0, 5).is_err());
+        assert!(expected_growth_rate(&[], 0.1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cash_flows() -> Result<(), PokercraftLocalError> {
+        let flow = |every, amount, fraction| CashFlow {
+            every,
+            amount,
+            fraction,
+        };
+        // Withdrawing 2 after winning 1 every tournament
+        let metric = simulate_with_cash_flows(10.0, [1.0], &[flow(1, -2.0, 0.0)], 100, 0.0, 10)?;
+        assert!(metric.simulated_results().iter().all(|&r| r == (0.0, 10)));
+
+        // Halving the capital every tournament and depositing 5 every 2 tournaments,
+        // like 10 -> 5 -> 7.5 -> 3.75
+        let flows = [flow(1, 0.0, -0.5), flow(2, 5.0, 0.0)];
+        let metric = simulate_with_cash_flows(10.0, [0.0], &flows, 3, 0.0, 10)?;
+        assert!(metric.simulated_results().iter().all(|&r| r == (0.375, 0)));
+
+        // Growing the capital to the exit
+        let metric = simulate_with_cash_flows(10.0, [0.0], &[flow(1, 0.0, 0.5)], 100, 2.0, 10)?;
+        assert!(metric.simulated_results().iter().all(|&r| r == (2.25, 2)));
+
+        assert!(simulate_with_cash_flows(10.0, [1.0], &[flow(0, 1.0, 0.0)], 100, 0.0, 10).is_err());
+        assert!(
+            simulate_with_cash_flows(10.0, [1.0], &[flow(1, 0.0, -1.0)], 100, 0.0, 10).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_simulate_finishes() -> Result<(), PokercraftLocalError> {
+        let structure = TournamentStructure {
+            buy_in: 10.0,
+            entries: 1000,
+            payouts: vec![3000.0, 2000.0, 1000.0],
+        };
+        assert_eq!(structure.scaled_places(1, 100)?, 1..=10);
+        assert_eq!(structure.scaled_places(100, 100)?, 991..=1000);
+        assert_eq!(structure.scaled_places(7, 7000)?, 1..=1);
+        assert_eq!(structure.scaled_places(1000, 1000)?, 1000..=1000);
+        assert!(structure.scaled_places(0, 100).is_err());
+        assert!(structure.scaled_places(101, 100).is_err());
+        assert_eq!(structure.relative_return(2), 1990.0);
+        assert_eq!(structure.relative_return(4), -10.0);
+
+        // Always winning the 1st place of 10 players is one of the top 100 places,
+        // paying 6000 per 100 tournaments on average
+        let metric = simulate_finishes(100.0, &[(1, 10)], &structure, 50, 0.0, 200)?;
+        assert_eq!(metric.len(), 200);
+        assert!(metric.get_bankruptcy_rate() > 0.0 && metric.get_profitable_rate() > 0.0);
+        // Going broke takes at least 10 buy-ins
+        assert!(metric
+            .simulated_results()
+            .iter()
+            .all(|&(_, iteration)| iteration == 0 || iteration >= 10));
+
+        assert!(simulate_finishes(100.0, &[(10, 10)], &structure, 50, 0.0, 200).is_err());
+        assert!(simulate_finishes(100.0, &[], &structure, 50, 0.0, 200).is_err());
+        let no_entries = TournamentStructure {
+            entries: 2,
+            ..structure
+        };
+        assert!(simulate_finishes(100.0, &[(1, 10)], &no_entries, 50, 0.0, 200).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+//! Blocker effects; How holding a combo changes the folding frequency of the villain,
+//! by removing some of their continuing and folding combos.
+//!
+//! Good bluffs block continuing combos and unblock folding combos.
+
+use crate::card::{Card, Hand};
+use crate::errors::PokercraftLocalError;
+use crate::range::HandRange;
+
+/// Blocker effect of a single candidate combo of the hero.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct BlockerScore {
+    pub combo: Hand,
+    /// Continuing combos of the villain sharing a card with the combo.
+    pub blocked_continues: usize,
+    /// Folding combos of the villain sharing a card with the combo.
+    pub blocked_folds: usize,
+    /// Folding frequency of the villain when the hero holds the combo.
+    pub fold_frequency: f64,
+    /// `fold_frequency` minus the folding frequency without knowing hole cards of the hero;
+    /// Positive if the combo blocks more of the continuing range.
+    pub score: f64,
+}
+
+/// Score blocker effects of candidate combos on the board, where the villain
+/// continues with combos of `range` which are also in `continuing`, and folds the rest.
+/// Candidates blocked by the board are skipped,
+/// and the result is sorted from the best bluff to the worst.
+pub fn blocker_scores(
+    candidates: &HandRange,
+    range: &HandRange,
+    continuing: &HandRange,
+    board: &[Card],
+) -> Result<Vec<BlockerScore>, PokercraftLocalError> {
+    if board.len() > 5 {
+        return Err(PokercraftLocalError::GeneralError(
+            "Too many community cards; Should have at most 5 cards".to_string(),
+        ));
+    }
+    let range = range.without_cards(board);
+    let (continues, folds): (Vec<Hand>, Vec<Hand>) = range
+        .combos()
+        .iter()
+        .partition(|combo| continuing.contains(combo));
+    if continues.is_empty() || folds.is_empty() {
+        return Err(PokercraftLocalError::GeneralError(
+            "Villain range should have both continuing and folding combos".to_string(),
+        ));
+    }
+    let baseline = folds.len() as f64 / range.len() as f64;
+
+    let blocked = |combos: &[Hand], (c1, c2): Hand| {
+        combos
+            .iter()
+            .filter(|(v1, v2)| [c1, c2].iter().any(|card| card == v1 || card == v2))
+            .count()
+    };
+    let mut scores = candidates
+        .without_cards(board)
+        .combos()
+        .iter()
+        .filter_map(|&combo| {
+            let blocked_continues = blocked(&continues, combo);
+            let blocked_folds = blocked(&folds, combo);
+            let remaining = range.len() - blocked_continues - blocked_folds;
+            // Nothing to bet against if the hero blocks the whole range
+            (remaining > 0).then(|| {
+                let fold_frequency = (folds.len() - blocked_folds) as f64 / remaining as f64;
+                BlockerScore {
+                    combo,
+                    blocked_continues,
+                    blocked_folds,
+                    fold_frequency,
+                    score: fold_frequency - baseline,
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+
+    #[test]
+    fn test_blocker_scores() -> Result<(), PokercraftLocalError> {
+        // The villain calls with top pair or better, and folds draws and underpairs
+        let board = parse_cards("Ah 9h 4c 2d")?;
+        let range = HandRange::try_from("AK,AQ,99,44,KhQh,JhTh,88,77")?;
+        let continuing = HandRange::try_from("AK,AQ,99,44")?;
+        let candidates = HandRange::try_from("AsKs,QdJd,KsQs,7c6c")?;
+        let scores = blocker_scores(&candidates, &range, &continuing, &board)?;
+        assert_eq!(scores.len(), 4);
+
+        // 12 + 12 + 3 + 3 = 30 continues, 1 + 1 + 6 + 6 = 14 folds
+        let baseline = 14.0 / 44.0;
+        let by_combo = |combo: &str| -> Result<BlockerScore, PokercraftLocalError> {
+            let combo = HandRange::try_from(combo)?.combos()[0];
+            scores
+                .iter()
+                .find(|score| score.combo == combo)
+                .copied()
+                .ok_or_else(|| PokercraftLocalError::GeneralError("Missing combo".to_string()))
+        };
+        // AsKs blocks 4 + 2 combos of AK and 4 combos of AQ
+        let ace_king = by_combo("AsKs")?;
+        assert_eq!(ace_king.blocked_continues, 10);
+        assert_eq!(ace_king.blocked_folds, 0);
+        assert!((ace_king.fold_frequency - 14.0 / 34.0).abs() < 1e-9);
+        assert!((ace_king.score - (14.0 / 34.0 - baseline)).abs() < 1e-9);
+        assert_eq!(scores[0].combo, ace_king.combo);
+        assert_eq!(by_combo("KsQs")?.blocked_continues, 6);
+        assert_eq!(scores[1].combo, by_combo("KsQs")?.combo);
+
+        // QdJd blocks 3 combos of AQ, and leaves draws unblocked
+        let queen_jack = by_combo("QdJd")?;
+        assert_eq!(
+            (queen_jack.blocked_continues, queen_jack.blocked_folds),
+            (3, 0)
+        );
+        assert!(queen_jack.score > 0.0);
+
+        // 7c6c blocks only folding sevens
+        let seven_six = by_combo("7c6c")?;
+        assert_eq!(
+            (seven_six.blocked_continues, seven_six.blocked_folds),
+            (0, 3)
+        );
+        assert!(seven_six.score < 0.0);
+        assert_eq!(scores[3].combo, seven_six.combo);
+
+        assert!(blocker_scores(&candidates, &continuing, &continuing, &board).is_err());
+        assert!(blocker_scores(
+            &candidates,
+            &range,
+            &continuing,
+            &parse_cards("Ah 9h 4c 2d 3s 5s")?
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -7,14 +7,28 @@ use wasm_bindgen::prelude::*;
 #[cfg(feature = "wasm")]
 use wasm_bindgen::JsValue;
 
-use crate::{errors::PokercraftLocalError, utils::FixedSizedCombinationIterator};
+use crate::{errors::PokercraftLocalError, utils::next_combination};
 
 pub const NUM_OF_SHAPES: usize = 4;
 pub const NUM_OF_NUMBERS: usize = 13;
+/// Names of hand rank categories, indexed by the category from `HandRank::numerize`.
+pub const HAND_RANK_CATEGORIES: [&str; 9] = [
+    "High Card",
+    "One Pair",
+    "Two Pairs",
+    "Triple",
+    "Straight",
+    "Flush",
+    "Full House",
+    "Quads",
+    "Straight Flush",
+];
 
 /// Card shapes (suits) in a standard deck of playing cards.
+/// Shapes are ordered as declared; Spade, Heart, Diamond, then Club.
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
 pub enum CardShape {
     Spade,
     Heart,
@@ -32,6 +46,26 @@ impl CardShape {
             CardShape::Club,
         ]
     }
+
+    /// Get the unicode symbol of this shape, like `'♠'`.
+    pub const fn symbol(&self) -> char {
+        match self {
+            CardShape::Spade => '♠',
+            CardShape::Heart => '♥',
+            CardShape::Diamond => '♦',
+            CardShape::Club => '♣',
+        }
+    }
+
+    /// Get the ANSI color code of this shape in the four-color deck.
+    const fn ansi_color(&self) -> &'static str {
+        match self {
+            CardShape::Spade => "1",
+            CardShape::Heart => "31",
+            CardShape::Diamond => "34",
+            CardShape::Club => "32",
+        }
+    }
 }
 
 impl std::fmt::Display for CardShape {
@@ -66,9 +100,10 @@ impl From<CardShape> for char {
 impl TryFrom<char> for CardShape {
     type Error = PokercraftLocalError;
 
+    /// Both ASCII characters like `'s'` and unicode symbols like `'♠'` are accepted.
     fn try_from(value: char) -> Result<Self, Self::Error> {
         for shape in Self::all() {
-            if char::from(shape) == value.to_ascii_lowercase() {
+            if char::from(shape) == value.to_ascii_lowercase() || shape.symbol() == value {
                 return Ok(shape);
             }
         }
@@ -81,6 +116,7 @@ impl TryFrom<char> for CardShape {
 
 /// Card numbers (ranks) in a standard deck of playing cards.
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
 pub enum CardNumber {
     Two = 2,
@@ -139,6 +175,33 @@ impl CardNumber {
         }
     }
 
+    /// Get the English name of this card number, like `"Ace"`.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            CardNumber::Two => "Two",
+            CardNumber::Three => "Three",
+            CardNumber::Four => "Four",
+            CardNumber::Five => "Five",
+            CardNumber::Six => "Six",
+            CardNumber::Seven => "Seven",
+            CardNumber::Eight => "Eight",
+            CardNumber::Nine => "Nine",
+            CardNumber::Ten => "Ten",
+            CardNumber::Jack => "Jack",
+            CardNumber::Queen => "Queen",
+            CardNumber::King => "King",
+            CardNumber::Ace => "Ace",
+        }
+    }
+
+    /// Get the plural English name of this card number, like `"Aces"`.
+    pub fn plural_name(&self) -> String {
+        match self {
+            CardNumber::Six => "Sixes".to_string(),
+            _ => format!("{}s", self.name()),
+        }
+    }
+
     /// Check if this card number is the next of the
     /// given previous card number, in straight order. (A234567...QKA)
     pub fn is_next_in_cycle(&self, previous: &CardNumber) -> bool {
@@ -214,6 +277,8 @@ impl TryFrom<char> for CardNumber {
 }
 
 /// A playing card in a standard deck of 52 cards.
+/// Cards are ordered by numbers first, then by shapes,
+/// so `2s < 2h < 2d < 2c < 3s < ... < Ac`.
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug, Default)]
 pub struct Card {
@@ -239,10 +304,76 @@ impl Card {
         }
         cards
     }
+
+    /// Get the index of this card in `Card::all()`, from 0 to 51.
+    pub const fn index(&self) -> usize {
+        self.shape as usize * NUM_OF_NUMBERS + (self.number as usize - 2)
+    }
+
+    /// Get the card of the given index in `Card::all()`.
+    pub const fn from_index(index: usize) -> Option<Card> {
+        if index < NUM_OF_NUMBERS * NUM_OF_SHAPES {
+            Some(Card::all()[index])
+        } else {
+            None
+        }
+    }
+}
+
+/// Cards are encoded as single bytes of their indices in `Card::all()`.
+impl From<Card> for u8 {
+    fn from(card: Card) -> Self {
+        card.index() as u8
+    }
 }
 
+impl TryFrom<u8> for Card {
+    type Error = PokercraftLocalError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Card::from_index(value as usize).ok_or_else(|| {
+            PokercraftLocalError::GeneralError(format!("Invalid card byte: {}", value))
+        })
+    }
+}
+
+/// Encode the given cards as bytes, one byte per card.
+pub fn encode_cards(cards: &[Card]) -> Vec<u8> {
+    cards.iter().map(|&card| u8::from(card)).collect()
+}
+
+/// Decode cards from bytes of `encode_cards`.
+pub fn decode_cards(bytes: &[u8]) -> Result<Vec<Card>, PokercraftLocalError> {
+    bytes.iter().map(|&byte| Card::try_from(byte)).collect()
+}
+
+impl PartialOrd for Card {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Card {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.number, self.shape).cmp(&(other.number, other.shape))
+    }
+}
+
+/// Hole cards of a player.
+/// Use `normalize_hand` before ordering or hashing,
+/// since the same hole cards can be given in two orders.
 pub type Hand = (Card, Card);
 
+/// Put the higher card of the given hole cards first,
+/// so the same hole cards have a single representation.
+pub fn normalize_hand((c1, c2): Hand) -> Hand {
+    if c2 > c1 {
+        (c2, c1)
+    } else {
+        (c1, c2)
+    }
+}
+
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
 impl Card {
@@ -260,6 +391,72 @@ impl Card {
     // Note: shape and number getters are auto-generated from public struct fields
 }
 
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(typescript_custom_section)]
+const TS_BEST_HAND: &'static str = r#"
+/** Best 5-card hand found from 5 or more cards. */
+export interface BestHand {
+    /** Card strings of the best 5 cards, like `"As"`. */
+    cards: string[];
+    /** Hand rank category, from 0 (High Card) to 8 (Straight Flush). */
+    category: number;
+    /** Like `"Full House, Aces full of Kings"`. */
+    description: string;
+    /** Card numbers from the most significant one, like `"AAAKK"`. */
+    short: string;
+}
+"#;
+
+/// Best 5-card hand of `find_best5_wasm`.
+#[cfg(feature = "wasm")]
+#[derive(serde::Serialize)]
+struct BestHand {
+    cards: [Card; 5],
+    category: u8,
+    description: String,
+    short: String,
+}
+
+/// Find the best 5-card hand from 5 or more cards like `"AsKd2c7h9sTd"` (WASM interface).
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = findBest5, unchecked_return_type = "BestHand")]
+pub fn find_best5_wasm(cards: &str) -> Result<JsValue, JsValue> {
+    let to_js_error = |e: PokercraftLocalError| JsValue::from_str(&e.to_string());
+    let (best5, rank) =
+        HandRank::find_best5(&parse_cards(cards).map_err(to_js_error)?).map_err(to_js_error)?;
+    crate::utils::to_js_object(&BestHand {
+        cards: best5,
+        category: rank.numerize().0,
+        description: rank.describe(),
+        short: rank.describe_short(),
+    })
+}
+
+/// Parse a list of cards like `"As Kd 2c"` or `"AsKd2c"` (WASM interface).
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = parseCards)]
+pub fn parse_cards_wasm(value: &str) -> Result<Vec<Card>, JsValue> {
+    parse_cards(value).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Encode a list of cards like `"As Kd 2c"` into card bytes (WASM interface),
+/// which are indices in the deck accepted by typed-array interfaces like `evaluateMany`.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = encodeCards)]
+pub fn encode_cards_wasm(value: &str) -> Result<Vec<u8>, JsValue> {
+    Ok(encode_cards(&parse_cards(value)?))
+}
+
+/// Decode card bytes into card strings like `"As"` (WASM interface).
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = decodeCards)]
+pub fn decode_cards_wasm(bytes: &[u8]) -> Result<Vec<String>, JsValue> {
+    Ok(decode_cards(bytes)?
+        .iter()
+        .map(|card| card.to_string())
+        .collect())
+}
+
 impl std::fmt::Display for Card {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let number_char: char = self.number.into();
@@ -268,6 +465,93 @@ impl std::fmt::Display for Card {
     }
 }
 
+/// Styles of displaying cards.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum CardStyle {
+    /// ASCII characters, like `As`. Same as `Display` of `Card`.
+    #[default]
+    Ascii,
+    /// Unicode shape symbols, like `A♠`.
+    Unicode,
+    /// Unicode shape symbols colored by ANSI escape codes of the four-color deck,
+    /// for terminals.
+    Colored,
+}
+
+impl std::str::FromStr for CardStyle {
+    type Err = PokercraftLocalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ascii" => Ok(CardStyle::Ascii),
+            "unicode" => Ok(CardStyle::Unicode),
+            "colored" => Ok(CardStyle::Colored),
+            _ => Err(PokercraftLocalError::GeneralError(format!(
+                "Invalid card style: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// `Display` adapter of a card in the given style, from `Card::display`.
+#[derive(Clone, Copy, Debug)]
+pub struct CardDisplay {
+    card: Card,
+    style: CardStyle,
+}
+
+impl std::fmt::Display for CardDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let number_char: char = self.card.number.into();
+        let shape = self.card.shape;
+        match self.style {
+            CardStyle::Ascii => write!(f, "{}", self.card),
+            CardStyle::Unicode => write!(f, "{}{}", number_char, shape.symbol()),
+            CardStyle::Colored => write!(
+                f,
+                "\x1b[{}m{}{}\x1b[0m",
+                shape.ansi_color(),
+                number_char,
+                shape.symbol()
+            ),
+        }
+    }
+}
+
+/// `Display` adapter of space-separated cards in the given style, from `Card::display_all`.
+#[derive(Clone, Copy, Debug)]
+pub struct CardsDisplay<'a> {
+    cards: &'a [Card],
+    style: CardStyle,
+}
+
+impl std::fmt::Display for CardsDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, card) in self.cards.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", card.display(self.style))?;
+        }
+        Ok(())
+    }
+}
+
+impl Card {
+    /// Display this card in the given style.
+    pub fn display(&self, style: CardStyle) -> CardDisplay {
+        CardDisplay { card: *self, style }
+    }
+
+    /// Display the given cards separated by spaces in the given style.
+    pub fn display_all(cards: &[Card], style: CardStyle) -> CardsDisplay<'_> {
+        CardsDisplay { cards, style }
+    }
+}
+
 impl TryFrom<&str> for Card {
     type Error = PokercraftLocalError;
 
@@ -281,7 +565,7 @@ impl TryFrom<&str> for Card {
     /// - "Td" -> Ten of Diamonds
     /// - "5h" -> Five of Hearts
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if value.len() != 2 {
+        if value.chars().count() != 2 {
             return Err(PokercraftLocalError::GeneralError(format!(
                 "Invalid card string: {}",
                 value
@@ -296,10 +580,91 @@ impl TryFrom<&str> for Card {
     }
 }
 
+impl std::str::FromStr for Card {
+    type Err = PokercraftLocalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Card::try_from(s)
+    }
+}
+
+/// Parse a list of cards like `"As Kd 2c"`, `"As,Kd,2c"` or `"AsKd2c"`.
+/// Whitespaces and commas between cards are ignored,
+/// but two characters of each card should be adjacent.
+/// Errors tell the 1-based character position of the invalid card.
+pub fn parse_cards(value: &str) -> Result<Vec<Card>, PokercraftLocalError> {
+    let mut cards = Vec::new();
+    let mut chars = value.chars().enumerate().peekable();
+    while let Some((position, first)) = chars.next() {
+        if first.is_whitespace() || first == ',' {
+            continue;
+        }
+        let second = match chars.next_if(|(_, c)| !c.is_whitespace() && *c != ',') {
+            Some((_, second)) => second,
+            None => {
+                return Err(PokercraftLocalError::GeneralError(format!(
+                    "Incomplete card \"{}\" at position {} of \"{}\"",
+                    first,
+                    position + 1,
+                    value
+                )))
+            }
+        };
+        let card = CardNumber::try_from(first)
+            .and_then(|number| {
+                let shape = CardShape::try_from(second)?;
+                Ok(Card { shape, number })
+            })
+            .map_err(|e| {
+                PokercraftLocalError::GeneralError(format!(
+                    "Invalid card \"{}{}\" at position {} of \"{}\": {}",
+                    first,
+                    second,
+                    position + 1,
+                    value,
+                    e
+                ))
+            })?;
+        cards.push(card);
+    }
+    Ok(cards)
+}
+
+/// Parse exactly `N` cards like `"As Kd 2c"`, same as `parse_cards`.
+pub fn parse_cards_exact<const N: usize>(value: &str) -> Result<[Card; N], PokercraftLocalError> {
+    let cards = parse_cards(value)?;
+    let length = cards.len();
+    cards.try_into().map_err(|_| {
+        PokercraftLocalError::GeneralError(format!(
+            "Expected {} cards but got {} cards from \"{}\"",
+            N, length, value
+        ))
+    })
+}
+
+/// Cards are serialized as 2-character strings like `"As"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Card {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Card {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Card::try_from(value.as_ref()).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Represents the rank of a poker hand.
 /// `Eq` and `Ord` are intentionally not implemented for this enum,
 /// and also `PartialEq` and `PartialOrd` are manually implemented
 /// because we do not differentiate between same rank with different suits.
+/// With the `serde` feature, this is serialized in the structured form
+/// like `{"FullHouse": ["Ace", "King"]}`, where cards are strings like `"As"`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Copy, Clone, Debug)]
 pub enum HandRank {
     HighCard([Card; 5]),
@@ -314,48 +679,102 @@ pub enum HandRank {
 }
 
 impl HandRank {
-    /// Numerize the given kicker/high-card information
+    /// Numerize the given kicker/high-card numbers
     /// into an integer for easy comparison.
     /// The early number has higher priority.
-    fn numerize_kickers(front_numbers: &[&CardNumber], cards: &[Card]) -> u64 {
-        let mut result: u64 = 0;
-        for number in front_numbers.into_iter() {
-            result *= NUM_OF_NUMBERS as u64;
-            result += **number as u64;
-        }
-        for card in cards.iter() {
-            result *= NUM_OF_NUMBERS as u64;
-            result += card.number as u64;
-        }
+    fn numerize_kickers<I: IntoIterator<Item = CardNumber>>(numbers: I) -> u64 {
+        numbers.into_iter().fold(0, |result, number| {
+            result * NUM_OF_NUMBERS as u64 + number as u64
+        })
+    }
+
+    /// Get the totally ordered key of this hand rank.
+    pub fn rank_key(&self) -> RankKey {
+        RankKey::from(self)
+    }
+
+    /// Describe this hand rank in English, like `"Full House, Aces full of Kings"`.
+    pub fn describe(&self) -> String {
+        let category = HAND_RANK_CATEGORIES[self.numerize().0 as usize];
+        let detail = match self {
+            Self::HighCard(_) | Self::Flush(_, _) => {
+                format!("{} high", self.numbers()[0].name())
+            }
+            Self::OnePair(number, _) | Self::Triple(number, _) | Self::Quads(number, _) => {
+                number.plural_name()
+            }
+            Self::TwoPairs(high, low, _) => {
+                format!("{} and {}", high.plural_name(), low.plural_name())
+            }
+            Self::Straight(high) => format!("{} high", high.name()),
+            Self::StraightFlush(card) => format!("{} high", card.number.name()),
+            Self::FullHouse(three, pair) => {
+                format!("{} full of {}", three.plural_name(), pair.plural_name())
+            }
+        };
+        format!("{}, {}", category, detail)
+    }
+
+    /// Describe this hand rank by its 5 card numbers from the most significant one,
+    /// like `"AAAKK"` for Aces full of Kings, or `"5432A"` for the wheel.
+    pub fn describe_short(&self) -> String {
+        self.numbers()
+            .iter()
+            .map(|&number| char::from(number))
+            .collect()
+    }
+
+    /// Get the 5 card numbers of this hand rank from the most significant one.
+    fn numbers(&self) -> [CardNumber; 5] {
+        // Kickers are sorted in descending order
+        let kickers = |cards: &[Card]| cards.iter().map(|c| c.number).sorted().rev();
+        // The wheel ends with Ace, since `CardNumber::new(1)` is Ace
+        let straight = |high: CardNumber| {
+            (0..5)
+                .filter_map(|i| CardNumber::new(high as i32 - i))
+                .collect()
+        };
+        let numbers: Vec<CardNumber> = match self {
+            Self::HighCard(cards) => kickers(cards).collect(),
+            Self::OnePair(number, cards) => {
+                [*number; 2].into_iter().chain(kickers(cards)).collect()
+            }
+            Self::TwoPairs(high, low, kicker) => vec![*high, *high, *low, *low, kicker.number],
+            Self::Triple(number, cards) => [*number; 3].into_iter().chain(kickers(cards)).collect(),
+            Self::Straight(high) => straight(*high),
+            Self::Flush(_, numbers) => numbers.iter().copied().sorted().rev().collect(),
+            Self::FullHouse(three, pair) => vec![*three, *three, *three, *pair, *pair],
+            Self::Quads(number, kicker) => vec![*number, *number, *number, *number, kicker.number],
+            Self::StraightFlush(card) => straight(card.number),
+        };
+        let mut result = [CardNumber::default(); 5];
+        result.copy_from_slice(&numbers);
         result
     }
 
     /// Numerize the given cards for easy comparison.
     pub fn numerize(&self) -> (u8, u64) {
         match self {
-            Self::HighCard(cards) => (0, Self::numerize_kickers(&[], cards)),
-            Self::OnePair(number, cards) => (1, Self::numerize_kickers(&[number], cards)),
+            Self::HighCard(cards) => (0, Self::numerize_kickers(cards.map(|c| c.number))),
+            Self::OnePair(number, cards) => (
+                1,
+                Self::numerize_kickers(
+                    std::iter::once(*number).chain(cards.iter().map(|c| c.number)),
+                ),
+            ),
             Self::TwoPairs(high, low, kicker) => {
-                (2, Self::numerize_kickers(&[high, low], &[*kicker]))
+                (2, Self::numerize_kickers([*high, *low, kicker.number]))
             }
-            Self::Triple(number, cards) => (3, Self::numerize_kickers(&[number], cards)),
-            Self::Straight(high) => (4, *high as u64),
-            Self::Flush(_, cards) => (
-                5,
+            Self::Triple(number, cards) => (
+                3,
                 Self::numerize_kickers(
-                    &[],
-                    cards
-                        .iter()
-                        .map(|n| Card {
-                            shape: CardShape::Spade,
-                            number: *n,
-                        })
-                        .collect::<Vec<_>>()
-                        .as_slice(),
+                    std::iter::once(*number).chain(cards.iter().map(|c| c.number)),
                 ),
             ),
-            Self::FullHouse(three, pair) => (6, Self::numerize_kickers(&[three, pair], &[])),
-            Self::Quads(number, card) => (7, Self::numerize_kickers(&[number], &[*card])),
+            Self::Straight(high) => (4, *high as u64),
+            Self::Flush(_, numbers) => (5, Self::numerize_kickers(*numbers)),
+            Self::FullHouse(three, pair) => (6, Self::numerize_kickers([*three, *pair])),
+            Self::Quads(number, card) => (7, Self::numerize_kickers([*number, card.number])),
             Self::StraightFlush(card) => (8, card.number as u64),
         }
     }
@@ -461,12 +880,16 @@ impl HandRank {
             .enumerate()
             .find(|(_, &count)| count == 3)
             .map(|(num, _)| num as i32);
-        let pairs: Vec<i32> = frequencies
-            .iter()
-            .enumerate()
-            .filter(|(_, &count)| count == 2)
-            .map(|(num, _)| num as i32)
-            .collect();
+        // At most 2 pairs in 5 cards, in increasing order
+        let mut pairs = [0i32; 2];
+        let mut num_pairs = 0;
+        for (num, &count) in frequencies.iter().enumerate() {
+            if count == 2 {
+                pairs[num_pairs] = num as i32;
+                num_pairs += 1;
+            }
+        }
+        let pairs = &pairs[..num_pairs];
         if let Some(quad_num) = quad {
             // Quads
             let quad_num = CardNumber::try_from(quad_num).unwrap();
@@ -487,26 +910,15 @@ impl HandRank {
             }
         } else if pairs.len() == 2 {
             // Two pairs
-            let high_pair = pairs.iter().max().unwrap();
-            let high_pair = CardNumber::try_from(*high_pair).unwrap();
-            let low_pair = pairs.iter().min().unwrap();
-            let low_pair = CardNumber::try_from(*low_pair).unwrap();
+            let high_pair = CardNumber::try_from(pairs[1]).unwrap();
+            let low_pair = CardNumber::try_from(pairs[0]).unwrap();
             let kicker: [Card; 1] = Self::get_cards_except(&cards, &[high_pair, low_pair]);
             return HandRank::TwoPairs(high_pair, low_pair, kicker[0]);
         } else if pairs.len() == 1 {
             // One pair
-            let pair_num = pairs[0];
-            let mut kickers: Vec<Card> = vec![];
-            for card in cards.iter() {
-                if card.number as i32 != pair_num {
-                    kickers.push(*card);
-                }
-            }
-            kickers.sort_by(|a, b| b.number.cmp(&a.number));
-            return HandRank::OnePair(
-                CardNumber::try_from(pair_num).unwrap(),
-                [kickers[0], kickers[1], kickers[2]],
-            );
+            let pair_num = CardNumber::try_from(pairs[0]).unwrap();
+            let kickers: [Card; 3] = Self::get_cards_except(&cards, &[pair_num]);
+            return HandRank::OnePair(pair_num, kickers);
         } else if pairs.is_empty() {
             // High card; Already sorted in decreasing order
             return HandRank::HighCard(cards);
@@ -515,7 +927,8 @@ impl HandRank {
         }
     }
 
-    /// Find the best 5-card hand from the given cards.
+    /// Find the best 5-card hand from the given 5 or more cards,
+    /// like 6 cards on the turn, 7 cards on the river or more for other variants.
     /// If `cards` has less than 5 cards, return an error.
     pub fn find_best5(cards: &[Card]) -> Result<([Card; 5], HandRank), PokercraftLocalError> {
         if cards.len() < 5 {
@@ -523,9 +936,11 @@ impl HandRank {
                 "Not enough cards; Should have at least 5 cards".to_string(),
             ));
         }
-        let mut best_card5 = [cards[0], cards[1], cards[2], cards[3], cards[4]];
+        let mut idxes = [0, 1, 2, 3, 4];
+        let mut best_card5 = idxes.map(|i| cards[i]);
         let mut best_rank = Self::evaluate(best_card5);
-        for this_card5 in FixedSizedCombinationIterator::<Card, 5>::new(cards.iter().copied()) {
+        while next_combination(&mut idxes, cards.len()) {
+            let this_card5 = idxes.map(|i| cards[i]);
             let this_rank = Self::evaluate(this_card5);
             if this_rank > best_rank {
                 best_card5 = this_card5;
@@ -598,6 +1013,67 @@ impl PartialEq for HandRank {
     }
 }
 
+/// A totally ordered key of a hand rank, comparing like `HandRank`.
+/// The category is stored in the top bits, followed by 14-bit masks
+/// of the deciding card numbers, where number `n` is the bit `n - 1`.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
+pub struct RankKey(pub u32);
+
+impl RankKey {
+    /// Bit offset of the hand rank category.
+    pub(crate) const CATEGORY_SHIFT: u32 = 28;
+    /// Bit offset of the primary card numbers, like pairs or triples.
+    pub(crate) const PRIMARY_SHIFT: u32 = 14;
+
+    /// Get the category of this key, same as the category from `HandRank::numerize`.
+    pub fn category(&self) -> u8 {
+        (self.0 >> Self::CATEGORY_SHIFT) as u8
+    }
+
+    /// Get the bit mask of the given card numbers.
+    pub(crate) fn mask<I: IntoIterator<Item = CardNumber>>(numbers: I) -> u32 {
+        numbers
+            .into_iter()
+            .fold(0, |mask, number| mask | (1 << (number as u32 - 1)))
+    }
+
+    /// Create a key from the category, primary numbers and kicker numbers.
+    fn new<P, K>(category: u8, primary: P, kickers: K) -> Self
+    where
+        P: IntoIterator<Item = CardNumber>,
+        K: IntoIterator<Item = CardNumber>,
+    {
+        RankKey(
+            ((category as u32) << Self::CATEGORY_SHIFT)
+                | (Self::mask(primary) << Self::PRIMARY_SHIFT)
+                | Self::mask(kickers),
+        )
+    }
+}
+
+impl From<&HandRank> for RankKey {
+    fn from(rank: &HandRank) -> Self {
+        match rank {
+            HandRank::HighCard(cards) => RankKey::new(0, [], cards.map(|c| c.number)),
+            HandRank::OnePair(number, cards) => RankKey::new(1, [*number], cards.map(|c| c.number)),
+            HandRank::TwoPairs(high, low, kicker) => {
+                RankKey::new(2, [*high, *low], [kicker.number])
+            }
+            HandRank::Triple(number, cards) => RankKey::new(3, [*number], cards.map(|c| c.number)),
+            HandRank::Straight(high) => RankKey::new(4, [], [*high]),
+            HandRank::Flush(_, numbers) => RankKey::new(5, [], *numbers),
+            HandRank::FullHouse(three, pair) => RankKey::new(6, [*three], [*pair]),
+            HandRank::Quads(number, card) => RankKey::new(7, [*number], [card.number]),
+            HandRank::StraightFlush(card) => RankKey::new(8, [], [card.number]),
+        }
+    }
+}
+
 /// Represents a mapping from each card shape to another shape.
 /// This does not use `HashMap` because there are only 4 shapes in total.
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
@@ -1019,6 +1495,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_find_best5_matches_all_combinations() -> Result<(), PokercraftLocalError> {
+        // Every 5 to 8 consecutive cards when sorted by shape (straight flushes, flushes)
+        // and when sorted by number (pairs, full houses, quads)
+        let by_shape = Card::all();
+        let mut by_number = Card::all();
+        by_number.sort_by_key(|card| card.number);
+        for size in 5..=8 {
+            for cards in by_shape.windows(size).chain(by_number.windows(size)) {
+                let (best5, best_rank) = HandRank::find_best5(cards)?;
+                let expected = cards
+                    .iter()
+                    .copied()
+                    .combinations(5)
+                    .map(|cards| {
+                        HandRank::evaluate([cards[0], cards[1], cards[2], cards[3], cards[4]])
+                    })
+                    .max_by(|a, b| a.partial_cmp(b).unwrap())
+                    .unwrap();
+                assert_eq!(best_rank.numerize(), expected.numerize());
+                assert_eq!(HandRank::evaluate(best5).numerize(), expected.numerize());
+            }
+        }
+        assert!(HandRank::find_best5(&by_shape[..4]).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_canonical_shape_mappings() -> Result<(), PokercraftLocalError> {
         let mappings = get_canonical_shape_mappings();
@@ -1056,4 +1559,166 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_parse_cards() -> Result<(), PokercraftLocalError> {
+        let expected = create_cards_slice(["As", "Kd", "2c"])?;
+        for value in ["As Kd 2c", "As,Kd,2c", "AsKd2c", " as, KD\t2C "] {
+            assert_eq!(parse_cards(value)?, expected.to_vec(), "{:?}", value);
+            assert_eq!(parse_cards_exact::<3>(value)?, expected);
+        }
+        assert_eq!(parse_cards("")?, vec![]);
+        assert!(" , ".parse::<Card>().is_err());
+        assert_eq!("Th".parse::<Card>()?, Card::try_from("Th")?);
+
+        let error = parse_cards("As Kx 2c").unwrap_err().to_string();
+        assert!(error.contains("\"Kx\" at position 4"), "{}", error);
+        let error = parse_cards("AsKd2").unwrap_err().to_string();
+        assert!(error.contains("\"2\" at position 5"), "{}", error);
+        let error = parse_cards("As K d").unwrap_err().to_string();
+        assert!(error.contains("\"K\" at position 4"), "{}", error);
+        assert!(parse_cards_exact::<2>("As Kd 2c").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_card_style() -> Result<(), PokercraftLocalError> {
+        let card = Card::try_from("Th")?;
+        assert_eq!(card.display(CardStyle::Ascii).to_string(), "Th");
+        assert_eq!(card.display(CardStyle::Unicode).to_string(), "T♥");
+        assert_eq!(
+            card.display(CardStyle::Colored).to_string(),
+            "\x1b[31mT♥\x1b[0m"
+        );
+
+        let cards = parse_cards("As Kd 2c")?;
+        assert_eq!(
+            Card::display_all(&cards, CardStyle::Unicode).to_string(),
+            "A♠ K♦ 2♣"
+        );
+        assert_eq!(Card::display_all(&[], CardStyle::Unicode).to_string(), "");
+        // Unicode symbols are also parsed back
+        assert_eq!(parse_cards("A♠ K♦ 2♣")?, cards);
+        assert_eq!("Colored".parse::<CardStyle>()?, CardStyle::Colored);
+        assert!("fancy".parse::<CardStyle>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_card_codecs() -> Result<(), PokercraftLocalError> {
+        let all = Card::all();
+        for (i, card) in all.iter().enumerate() {
+            assert_eq!(card.index(), i);
+            assert_eq!(Card::from_index(i), Some(*card));
+            assert_eq!(Card::try_from(u8::from(*card))?, *card);
+        }
+        assert_eq!(Card::from_index(all.len()), None);
+        assert!(Card::try_from(52u8).is_err());
+        assert!(Card::try_from(u8::MAX).is_err());
+
+        let bytes = encode_cards(&all);
+        assert_eq!(bytes, (0..52).collect::<Vec<u8>>());
+        assert_eq!(decode_cards(&bytes)?, all.to_vec());
+        assert!(decode_cards(&[0, 52]).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() -> Result<(), PokercraftLocalError> {
+        let json_error = |e: serde_json::Error| PokercraftLocalError::GeneralError(e.to_string());
+
+        let hand: Hand = (Card::try_from("As")?, Card::try_from("Kd")?);
+        let json = serde_json::to_string(&hand).map_err(json_error)?;
+        assert_eq!(json, r#"["As","Kd"]"#);
+        assert_eq!(
+            serde_json::from_str::<Hand>(&json).map_err(json_error)?,
+            hand
+        );
+        assert!(serde_json::from_str::<Card>(r#""Ax""#).is_err());
+
+        let cards = create_cards_slice(["Ah", "Ad", "As", "Kc", "Kh", "2d", "3c"])?;
+        let (_, rank) = HandRank::find_best5(&cards)?;
+        let json = serde_json::to_string(&rank).map_err(json_error)?;
+        assert_eq!(json, r#"{"FullHouse":["Ace","King"]}"#);
+        let restored: HandRank = serde_json::from_str(&json).map_err(json_error)?;
+        assert_eq!(format!("{:?}", restored), format!("{:?}", rank));
+
+        let (_, rank) = HandRank::find_best5(&create_cards_slice(["9s", "7s", "5d", "4c", "2h"])?)?;
+        let json = serde_json::to_string(&rank).map_err(json_error)?;
+        assert_eq!(json, r#"{"HighCard":["9s","7s","5d","4c","2h"]}"#);
+        let restored: HandRank = serde_json::from_str(&json).map_err(json_error)?;
+        assert_eq!(format!("{:?}", restored), format!("{:?}", rank));
+
+        let key = rank.rank_key();
+        let json = serde_json::to_string(&key).map_err(json_error)?;
+        assert_eq!(json, key.0.to_string());
+        assert_eq!(
+            serde_json::from_str::<RankKey>(&json).map_err(json_error)?,
+            key
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_describe() -> Result<(), PokercraftLocalError> {
+        let cases = [
+            (
+                ["Ah", "Ad", "As", "Kc", "Kh"],
+                "Full House, Aces full of Kings",
+                "AAAKK",
+            ),
+            (
+                ["9s", "7s", "5d", "4c", "2h"],
+                "High Card, Nine high",
+                "97542",
+            ),
+            (["6s", "6d", "Ac", "3h", "Th"], "One Pair, Sixes", "66AT3"),
+            (
+                ["Js", "Jd", "4c", "4h", "Kh"],
+                "Two Pairs, Jacks and Fours",
+                "JJ44K",
+            ),
+            (["8s", "8d", "8c", "Qh", "2h"], "Triple, Eights", "888Q2"),
+            (
+                ["As", "2d", "3c", "4h", "5h"],
+                "Straight, Five high",
+                "5432A",
+            ),
+            (
+                ["Ts", "Js", "Qs", "Ks", "As"],
+                "Straight Flush, Ace high",
+                "AKQJT",
+            ),
+            (["2d", "9d", "Jd", "4d", "Kd"], "Flush, King high", "KJ942"),
+            (["3s", "3d", "3c", "3h", "Th"], "Quads, Threes", "3333T"),
+        ];
+        for (cards, description, short) in cases {
+            let rank = HandRank::evaluate(create_cards_slice(cards)?);
+            assert_eq!(rank.describe(), description);
+            assert_eq!(rank.describe_short(), short);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_card_order() -> Result<(), PokercraftLocalError> {
+        let mut cards = Card::all();
+        cards.sort();
+        assert_eq!(cards[0], Card::try_from("2s")?);
+        assert_eq!(cards[1], Card::try_from("2h")?);
+        assert_eq!(cards[4], Card::try_from("3s")?);
+        assert_eq!(cards[51], Card::try_from("Ac")?);
+        assert!(cards.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let (ace, king) = (Card::try_from("Ac")?, Card::try_from("Ks")?);
+        assert_eq!(normalize_hand((king, ace)), (ace, king));
+        assert_eq!(normalize_hand((ace, king)), (ace, king));
+        let hands: std::collections::HashSet<Hand> = [(king, ace), (ace, king)]
+            .into_iter()
+            .map(normalize_hand)
+            .collect();
+        assert_eq!(hands.len(), 1);
+        Ok(())
+    }
 }
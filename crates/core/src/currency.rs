@@ -0,0 +1,261 @@
+//! Currencies used in GGPoker exports and conversion between them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+use crate::errors::PokercraftLocalError;
+
+/// Currencies which can appear in tournament summaries.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum Currency {
+    USD,
+    CNY,
+    THB,
+    VND,
+    PHP,
+    KRW,
+}
+
+impl Currency {
+    /// Return all supported currencies.
+    pub const fn all() -> [Currency; 6] {
+        [
+            Currency::USD,
+            Currency::CNY,
+            Currency::THB,
+            Currency::VND,
+            Currency::PHP,
+            Currency::KRW,
+        ]
+    }
+
+    /// Get the symbol used in exported files.
+    pub const fn symbol(&self) -> char {
+        match self {
+            Currency::USD => '$',
+            Currency::CNY => '¥',
+            Currency::THB => '฿',
+            Currency::VND => '₫',
+            Currency::PHP => '₱',
+            Currency::KRW => '₩',
+        }
+    }
+
+    /// Get the currency of the given symbol.
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|currency| currency.symbol() == symbol)
+    }
+
+    /// Get the default exchange rate; How many units equal to 1 USD.
+    pub const fn default_usd_rate(&self) -> f64 {
+        match self {
+            Currency::USD => 1.0,
+            Currency::CNY => 7.25,
+            Currency::THB => 34.61,
+            Currency::VND => 25420.0,
+            Currency::PHP => 58.98,
+            Currency::KRW => 1399.58,
+        }
+    }
+}
+
+impl std::fmt::Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for Currency {
+    type Err = PokercraftLocalError;
+
+    /// Parse an ISO code like `"USD"` or a symbol like `"$"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Self::all()
+            .into_iter()
+            .find(|currency| {
+                currency.to_string().eq_ignore_ascii_case(s)
+                    || s.chars().eq(std::iter::once(currency.symbol()))
+            })
+            .ok_or_else(|| PokercraftLocalError::GeneralError(format!("Unknown currency: {}", s)))
+    }
+}
+
+/// Converts money between currencies.
+/// Rates are how many units of the currency equal to 1 USD.
+/// Dated rates are preferred when converting money of a specific date;
+/// The latest rate on or before the date is used,
+/// or the earliest rate if the date precedes all of them.
+/// Otherwise static rates are used.
+#[derive(Clone, Debug)]
+pub struct CurrencyRateConverter {
+    static_rates: HashMap<Currency, f64>,
+    dated_rates: HashMap<Currency, BTreeMap<NaiveDate, f64>>,
+}
+
+impl CurrencyRateConverter {
+    /// Create a new converter with default static rates.
+    pub fn new() -> Self {
+        CurrencyRateConverter {
+            static_rates: Currency::all()
+                .into_iter()
+                .map(|currency| (currency, currency.default_usd_rate()))
+                .collect(),
+            dated_rates: HashMap::new(),
+        }
+    }
+
+    fn check_rate(currency: Currency, rate: f64) -> Result<(), PokercraftLocalError> {
+        if rate.is_finite() && rate > 0.0 {
+            Ok(())
+        } else {
+            Err(PokercraftLocalError::GeneralError(format!(
+                "Invalid rate {} of {}",
+                rate, currency
+            )))
+        }
+    }
+
+    /// Override the static rate of the given currency.
+    pub fn set_rate(
+        &mut self,
+        currency: Currency,
+        usd_rate: f64,
+    ) -> Result<(), PokercraftLocalError> {
+        Self::check_rate(currency, usd_rate)?;
+        self.static_rates.insert(currency, usd_rate);
+        Ok(())
+    }
+
+    /// Add a rate of the given currency effective from the given date.
+    pub fn set_rate_on(
+        &mut self,
+        currency: Currency,
+        date: NaiveDate,
+        usd_rate: f64,
+    ) -> Result<(), PokercraftLocalError> {
+        Self::check_rate(currency, usd_rate)?;
+        self.dated_rates
+            .entry(currency)
+            .or_default()
+            .insert(date, usd_rate);
+        Ok(())
+    }
+
+    /// Create a converter from a user-supplied rate table.
+    /// Each line is either `CURRENCY,RATE` for a static rate
+    /// or `CURRENCY,YYYY-MM-DD,RATE` for a dated rate.
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn from_rate_table(table: &str) -> Result<Self, PokercraftLocalError> {
+        let mut converter = Self::new();
+        for (i, line) in table.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| {
+                PokercraftLocalError::GeneralError(format!(
+                    "Line {} of rate table: {}: {}",
+                    i + 1,
+                    message,
+                    line
+                ))
+            };
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let parse_rate = |s: &str| s.parse::<f64>().map_err(|_| error("Invalid rate"));
+            match fields.as_slice() {
+                [currency, rate] => {
+                    converter.set_rate(currency.parse()?, parse_rate(rate)?)?;
+                }
+                [currency, date, rate] => {
+                    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                        .map_err(|_| error("Invalid date"))?;
+                    converter.set_rate_on(currency.parse()?, date, parse_rate(rate)?)?;
+                }
+                _ => return Err(error("Expected 2 or 3 fields")),
+            }
+        }
+        Ok(converter)
+    }
+
+    /// Get the rate of the given currency at the given date.
+    pub fn usd_rate(&self, currency: Currency, date: Option<NaiveDate>) -> f64 {
+        if let (Some(date), Some(rates)) = (date, self.dated_rates.get(&currency)) {
+            if let Some((_, &rate)) = rates
+                .range(..=date)
+                .next_back()
+                .or_else(|| rates.iter().next())
+            {
+                return rate;
+            }
+        }
+        self.static_rates
+            .get(&currency)
+            .copied()
+            .unwrap_or_else(|| currency.default_usd_rate())
+    }
+
+    /// Convert the amount of money between currencies.
+    pub fn convert(
+        &self,
+        amount: f64,
+        from: Currency,
+        to: Currency,
+        date: Option<NaiveDate>,
+    ) -> f64 {
+        if from == to {
+            amount
+        } else {
+            amount / self.usd_rate(from, date) * self.usd_rate(to, date)
+        }
+    }
+}
+
+impl Default for CurrencyRateConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currency_conversion() -> Result<(), PokercraftLocalError> {
+        assert_eq!(Currency::from_symbol('¥'), Some(Currency::CNY));
+        assert_eq!("krw".parse::<Currency>()?, Currency::KRW);
+        assert_eq!("₱".parse::<Currency>()?, Currency::PHP);
+        assert!("EUR".parse::<Currency>().is_err());
+
+        let converter = CurrencyRateConverter::from_rate_table(
+            "# currency,date,rate
+CNY,7.0
+CNY,2025-01-01,7.2
+CNY,2025-06-01,7.1
+",
+        )?;
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+        assert_eq!(converter.usd_rate(Currency::CNY, None), 7.0);
+        assert_eq!(converter.usd_rate(Currency::CNY, date("2024-12-01")), 7.2);
+        assert_eq!(converter.usd_rate(Currency::CNY, date("2025-03-01")), 7.2);
+        assert_eq!(converter.usd_rate(Currency::CNY, date("2025-08-01")), 7.1);
+        assert!(
+            (converter.convert(71.0, Currency::CNY, Currency::USD, date("2025-08-01")) - 10.0)
+                .abs()
+                < 1e-9
+        );
+        assert!(
+            (converter.convert(1.0, Currency::USD, Currency::KRW, None) - 1399.58).abs() < 1e-9
+        );
+
+        assert!(CurrencyRateConverter::from_rate_table("CNY,-1").is_err());
+        assert!(CurrencyRateConverter::from_rate_table("CNY,2025/01/01,7.0").is_err());
+        Ok(())
+    }
+}
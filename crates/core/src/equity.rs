@@ -1,9 +1,14 @@
 //! Equity calculations and relative analysis.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::BufRead;
+use std::sync::Mutex;
 
 use flate2::read::GzDecoder;
+#[cfg(feature = "embedded-tables")]
+use once_cell::sync::OnceCell;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
 use rayon::prelude::*;
 use rustfft::{num_complex::Complex, FftPlanner};
 use statrs::distribution::{ContinuousCDF, Normal};
@@ -14,19 +19,130 @@ use wasm_bindgen::JsCast;
 #[cfg(feature = "wasm")]
 use wasm_bindgen::JsValue;
 
-use crate::card::{Card, Hand, HandRank};
+#[cfg(feature = "wasm")]
+use crate::card::{decode_cards, parse_cards, parse_cards_exact};
+use crate::card::{
+    get_canonical_shape_mappings, Card, CardShape, Hand, HandRank, NUM_OF_NUMBERS, NUM_OF_SHAPES,
+};
 use crate::errors::PokercraftLocalError;
-use crate::utils::{FixedSizedCombinationIterator, IterWrapper};
+use crate::evaluator::{evaluate_shape_masks, shape_masks};
+use crate::generator::Spot;
+use crate::range::HandRange;
+#[cfg(feature = "wasm")]
+use crate::utils::JsProgress;
+use crate::utils::{binomial, in_thread_pool, FixedSizedCombinationIterator, Progress};
+
+/// Number of boards enumerated sequentially in each parallel chunk.
+const BOARDS_PER_CHUNK: u64 = 1 << 12;
+
+/// Maximum number of players of an `EquityResult`, as 23 players use all 52 cards
+/// with 5 community cards and one card left.
+pub const MAX_PLAYERS: usize = 23;
+
+/// Win, tie and lose counts of a single player of an `EquityResult`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct PlayerOutcome {
+    /// Number of boards won without ties.
+    pub wins_outright: u64,
+    /// `ties_by_way[c]` is number of boards tied with `c` other players,
+    /// so `ties_by_way[0]` is always `0`.
+    pub ties_by_way: [u64; MAX_PLAYERS],
+    /// Number of boards lost.
+    pub losses: u64,
+}
+
+impl Default for PlayerOutcome {
+    fn default() -> Self {
+        PlayerOutcome {
+            wins_outright: 0,
+            ties_by_way: [0; MAX_PLAYERS],
+            losses: 0,
+        }
+    }
+}
+
+impl PlayerOutcome {
+    /// Add a win shared with `others` other players, or a win outright if `0`.
+    fn add_win(&mut self, others: usize) {
+        if others == 0 {
+            self.wins_outright += 1;
+        } else {
+            self.ties_by_way[others] += 1;
+        }
+    }
+
+    /// Add counts of the other outcome to this.
+    fn merge(&mut self, other: &PlayerOutcome) {
+        self.wins_outright += other.wins_outright;
+        for (ties, other_ties) in self.ties_by_way.iter_mut().zip(other.ties_by_way.iter()) {
+            *ties += other_ties;
+        }
+        self.losses += other.losses;
+    }
+
+    /// Get the number of boards chopped by exactly `way` players including this player,
+    /// like `2` for a chop with one other player.
+    pub fn ties(&self, way: usize) -> u64 {
+        match way {
+            2..=MAX_PLAYERS => self.ties_by_way[way - 1],
+            _ => 0,
+        }
+    }
+
+    /// Get the number of boards tied with any number of players.
+    pub fn total_ties(&self) -> u64 {
+        self.ties_by_way.iter().sum()
+    }
+
+    /// Get the number of all boards.
+    pub fn total(&self) -> u64 {
+        self.wins_outright + self.total_ties() + self.losses
+    }
+
+    /// Get the share of pots over all boards, where ties split pots evenly.
+    /// Returns `None` without any boards.
+    pub fn equity(&self) -> Option<f64> {
+        let total = self.total();
+        (total > 0).then(|| {
+            let shares = self
+                .ties_by_way
+                .iter()
+                .enumerate()
+                .skip(1)
+                .fold(self.wins_outright as f64, |acc, (others, &count)| {
+                    acc + count as f64 / (others + 1) as f64
+                });
+            shares / total as f64
+        })
+    }
+
+    /// Get win and tie counts structured as `EquityResult::get_winlosses`,
+    /// for `num_players` players.
+    fn win_counts(&self, num_players: usize) -> Vec<u64> {
+        let mut counts = self.ties_by_way[..num_players].to_vec();
+        counts[0] = self.wins_outright;
+        counts
+    }
+}
+
+/// Probabilities of winning outright, chopping and losing of a player of an `EquityResult`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct OutcomeRates {
+    pub win_outright: f64,
+    /// `chops[k]` is the probability of chopping `k + 2` ways,
+    /// for up to the number of players.
+    pub chops: Vec<f64>,
+    pub lose: f64,
+}
 
 /// Result of single equity calculation.
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[derive(Debug, Clone)]
 pub struct EquityResult {
-    /// `wins[i][c]` is number of `i`-th player wins
-    /// with `c` other players having the same rank.
-    wins: Vec<Vec<u64>>,
-    /// `loses[i]` is number of `i`-th player loses.
-    loses: Vec<u64>,
+    /// Outcomes of each player.
+    outcomes: Vec<PlayerOutcome>,
 }
 
 impl EquityResult {
@@ -46,36 +162,40 @@ impl EquityResult {
         result
     }
 
-    /// Get an iterator over all possible 5-card flops.
+    /// Get an iterator over all possible 5-card flops,
+    /// starting from the `start`-th (0-based) flop.
     /// This is a helper function, do not call this directly.
     fn get_flop_iter(
-        remaining_cards: Vec<Card>,
-        fixed_communities: Vec<Card>,
+        remaining_cards: &[Card],
+        fixed_communities: &[Card],
+        start: u64,
     ) -> Result<Box<dyn Iterator<Item = [Card; 5]> + Send>, PokercraftLocalError> {
+        let remaining_cards = remaining_cards.iter().copied();
+        let fixed_communities = fixed_communities.to_vec();
         match fixed_communities.len() {
-            0 => Ok(Box::new(FixedSizedCombinationIterator::<Card, 5>::new(
-                remaining_cards.into_iter(),
-            ))),
+            0 => Ok(Box::new(
+                FixedSizedCombinationIterator::<Card, 5>::new_from(remaining_cards, start),
+            )),
             1 => Ok(Box::new(
-                FixedSizedCombinationIterator::<Card, 4>::new(remaining_cards.into_iter())
+                FixedSizedCombinationIterator::<Card, 4>::new_from(remaining_cards, start)
                     .map(move |arr| Self::chain5(arr, &fixed_communities)),
             )),
             2 => Ok(Box::new(
-                FixedSizedCombinationIterator::<Card, 3>::new(remaining_cards.into_iter())
+                FixedSizedCombinationIterator::<Card, 3>::new_from(remaining_cards, start)
                     .map(move |arr| Self::chain5(arr, &fixed_communities)),
             )),
             3 => Ok(Box::new(
-                FixedSizedCombinationIterator::<Card, 2>::new(remaining_cards.into_iter())
+                FixedSizedCombinationIterator::<Card, 2>::new_from(remaining_cards, start)
                     .map(move |arr| Self::chain5(arr, &fixed_communities)),
             )),
             4 => Ok(Box::new(
-                FixedSizedCombinationIterator::<Card, 1>::new(remaining_cards.into_iter())
+                FixedSizedCombinationIterator::<Card, 1>::new_from(remaining_cards, start)
                     .map(move |arr| Self::chain5(arr, &fixed_communities)),
             )),
-            5 => Ok(Box::new(std::iter::once(Self::chain5(
-                [Card::default(); 0],
-                &fixed_communities,
-            )))),
+            5 => Ok(Box::new(
+                std::iter::once(Self::chain5([Card::default(); 0], &fixed_communities))
+                    .skip(start as usize),
+            )),
             len => Err(PokercraftLocalError::GeneralError(format!(
                 "Given {} fixed community cards; Cannot generate the 5-cards flop.",
                 len
@@ -140,32 +260,54 @@ impl EquityResult {
         Ok(this_result)
     }
 
+    /// Enumerate all boards in parallel, folding each fixed-size chunk of boards
+    /// sequentially and merging chunk results with `merge`.
+    /// Chunks are split by board indices, so the partitioning is deterministic.
+    fn fold_boards_in_chunks<T, F, M>(
+        remaining_cards: &[Card],
+        fixed_communities: &[Card],
+        empty: impl Fn() -> T + Sync + Send,
+        fold: F,
+        merge: M,
+    ) -> Result<T, PokercraftLocalError>
+    where
+        T: Send,
+        F: Fn(T, [Card; 5]) -> Result<T, PokercraftLocalError> + Sync + Send,
+        M: Fn(T, T) -> Result<T, PokercraftLocalError> + Sync + Send,
+    {
+        let total_boards = binomial(
+            remaining_cards.len() as u64,
+            5 - fixed_communities.len() as u64,
+        );
+        in_thread_pool(|| {
+            (0..total_boards.div_ceil(BOARDS_PER_CHUNK))
+                .into_par_iter()
+                .map(|chunk| {
+                    Self::get_flop_iter(
+                        remaining_cards,
+                        fixed_communities,
+                        chunk * BOARDS_PER_CHUNK,
+                    )?
+                    .take(BOARDS_PER_CHUNK as usize)
+                    .try_fold(empty(), &fold)
+                })
+                .try_reduce(&empty, &merge)
+        })
+    }
+
     /// A helper function for `try_fold` in folding results.
     fn folding_fn(
-        (mut win_acc, mut lose_acc): (Vec<Vec<u64>>, Vec<u64>),
+        mut acc: Vec<PlayerOutcome>,
         res: Result<Vec<i32>, PokercraftLocalError>,
-    ) -> Result<(Vec<Vec<u64>>, Vec<u64>), PokercraftLocalError> {
-        match res {
-            Ok(this_result) => {
-                for (i, &val) in this_result.iter().enumerate() {
-                    if val >= 0 {
-                        win_acc[i][val as usize] += 1;
-                    } else {
-                        lose_acc[i] += 1;
-                    }
-                }
-                Ok((win_acc, lose_acc))
+    ) -> Result<Vec<PlayerOutcome>, PokercraftLocalError> {
+        for (outcome, &val) in acc.iter_mut().zip(res?.iter()) {
+            if val >= 0 {
+                outcome.add_win(val as usize);
+            } else {
+                outcome.losses += 1;
             }
-            Err(e) => Err(e),
         }
-    }
-
-    /// Helper function to create empty win/lose counts.
-    fn get_empty_winloses(num_players: usize) -> (Vec<Vec<u64>>, Vec<u64>) {
-        (
-            vec![vec![0; num_players]; num_players],
-            vec![0; num_players],
-        )
+        Ok(acc)
     }
 
     /// Create a new `EquityResult` by calculating the win/loss
@@ -175,14 +317,70 @@ impl EquityResult {
         cards_community: Vec<Card>,
         parallel_calculation: bool,
     ) -> Result<Self, PokercraftLocalError> {
-        let remaining_cards = Card::all()
-            .into_iter()
-            .filter(|card| {
-                !cards_people.iter().any(|(c1, c2)| card == c1 || card == c2)
-                    && !cards_community.iter().any(|c| card == c)
-            })
-            .collect::<Vec<_>>();
+        Self::new_with_progress(
+            cards_people,
+            cards_community,
+            parallel_calculation,
+            &|_, _| true,
+        )
+    }
+
+    /// Same as `new`, reporting `(boards done, total boards)` to `on_progress`.
+    /// The calculation is cancelled if `on_progress` returns `false`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(players = cards_people.len(), community = cards_community.len())
+        )
+    )]
+    pub fn new_with_progress(
+        cards_people: Vec<Hand>,
+        cards_community: Vec<Card>,
+        parallel_calculation: bool,
+        on_progress: &(dyn Fn(u64, u64) -> bool + Sync),
+    ) -> Result<Self, PokercraftLocalError> {
+        let remaining_cards = Self::get_remaining_cards(&cards_people, &cards_community)?;
+        let total_boards = binomial(
+            remaining_cards.len() as u64,
+            5 - cards_community.len() as u64,
+        );
+        #[cfg(feature = "embedded-tables")]
+        if let ([hand1, hand2], []) = (cards_people.as_slice(), cards_community.as_slice()) {
+            if let Some(result) = Self::from_embedded_preflop_cache(*hand1, *hand2) {
+                on_progress(total_boards, total_boards);
+                return Ok(result);
+            }
+        }
 
+        let progress = Progress::new(total_boards, on_progress);
+        if let [hand1, hand2] = cards_people.as_slice() {
+            let cards = [hand1.0, hand1.1, hand2.0, hand2.1];
+            if (1..cards.len()).all(|i| !cards[..i].contains(&cards[i])) {
+                return Self::heads_up_calculation(
+                    [*hand1, *hand2],
+                    &cards_community,
+                    &remaining_cards,
+                    parallel_calculation,
+                    &progress,
+                );
+            }
+        }
+        Self::enumerate_boards(
+            &cards_people,
+            &cards_community,
+            &remaining_cards,
+            parallel_calculation,
+            &progress,
+        )
+    }
+
+    /// Validate players and community cards, and get cards not used by them.
+    fn get_remaining_cards(
+        cards_people: &[Hand],
+        cards_community: &[Card],
+    ) -> Result<Vec<Card>, PokercraftLocalError> {
         if cards_community.len() > 5 {
             return Err(PokercraftLocalError::GeneralError(
                 "Too many community cards; Should have at most 5 cards".to_string(),
@@ -191,134 +389,441 @@ impl EquityResult {
             return Err(PokercraftLocalError::GeneralError(
                 "No player cards given".to_string(),
             ));
-        } else if cards_people.len() > 23 {
-            return Err(PokercraftLocalError::GeneralError(
-                "Too many players; Should have at most 23 players".to_string(),
-            ));
+        } else if cards_people.len() > MAX_PLAYERS {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Too many players; Should have at most {} players",
+                MAX_PLAYERS
+            )));
+        }
+        Ok(Card::all()
+            .into_iter()
+            .filter(|card| {
+                !cards_people.iter().any(|(c1, c2)| card == c1 || card == c2)
+                    && !cards_community.iter().any(|c| card == c)
+            })
+            .collect())
+    }
+
+    /// Get the number of boards enumerated for the given players and community cards,
+    /// to split them into partitions for `new_partial`.
+    pub fn count_boards(
+        cards_people: &[Hand],
+        cards_community: &[Card],
+    ) -> Result<u64, PokercraftLocalError> {
+        let remaining_cards = Self::get_remaining_cards(cards_people, cards_community)?;
+        Ok(binomial(
+            remaining_cards.len() as u64,
+            5 - cards_community.len() as u64,
+        ))
+    }
+
+    /// Calculate win/lose counts only on boards from the `start`-th (0-based)
+    /// to before the `(start + len)`-th, sequentially. Partial results of
+    /// disjoint partitions covering all boards, like from other threads or machines,
+    /// are combined by `merge` into the same result as `new`.
+    pub fn new_partial(
+        cards_people: Vec<Hand>,
+        cards_community: Vec<Card>,
+        start: u64,
+        len: u64,
+    ) -> Result<Self, PokercraftLocalError> {
+        let remaining_cards = Self::get_remaining_cards(&cards_people, &cards_community)?;
+        let outcomes = Self::get_flop_iter(&remaining_cards, &cards_community, start)?
+            .take(len.min(usize::MAX as u64) as usize)
+            .map(|communities| Self::single_board_calculation(communities, &cards_people))
+            .try_fold(
+                vec![PlayerOutcome::default(); cards_people.len()],
+                Self::folding_fn,
+            )?;
+        Ok(Self { outcomes })
+    }
+
+    /// Add counts of the other result of the same players to this.
+    pub fn merge(&mut self, other: &EquityResult) -> Result<(), PokercraftLocalError> {
+        if self.num_players() != other.num_players() {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Cannot merge results of {} and {} players",
+                self.num_players(),
+                other.num_players()
+            )));
         }
+        for (outcome, other) in self.outcomes.iter_mut().zip(other.outcomes.iter()) {
+            outcome.merge(other);
+        }
+        Ok(())
+    }
 
-        let iter = IterWrapper {
-            iter: Self::get_flop_iter(remaining_cards, cards_community)?,
+    /// Calculate win/lose counts by evaluating all hands on all boards.
+    /// This is a helper function, do not call this directly.
+    fn enumerate_boards(
+        cards_people: &[Hand],
+        cards_community: &[Card],
+        remaining_cards: &[Card],
+        parallel_calculation: bool,
+        progress: &Progress,
+    ) -> Result<Self, PokercraftLocalError> {
+        let calculate = |communities: [Card; 5]| {
+            progress.tick()?;
+            Self::single_board_calculation(communities, cards_people)
         };
         let num_players = cards_people.len();
 
         let result = if parallel_calculation {
-            iter.par_bridge()
-                .map(|communities| Self::single_board_calculation(communities, &cards_people))
-                .try_fold(|| Self::get_empty_winloses(num_players), Self::folding_fn)
-                .try_reduce(
-                    || Self::get_empty_winloses(num_players),
-                    |(mut win1, mut lose1), (win2, lose2)| {
-                        for i in 0..win1.len() {
-                            for j in 0..win1[i].len() {
-                                win1[i][j] += win2[i][j];
-                            }
-                            lose1[i] += lose2[i];
-                        }
-                        Ok((win1, lose1))
-                    },
+            Self::fold_boards_in_chunks(
+                remaining_cards,
+                cards_community,
+                || vec![PlayerOutcome::default(); num_players],
+                |acc, communities| Self::folding_fn(acc, calculate(communities)),
+                |mut acc, other| {
+                    for (outcome, other) in acc.iter_mut().zip(other.iter()) {
+                        outcome.merge(other);
+                    }
+                    Ok(acc)
+                },
+            )
+        } else {
+            Self::get_flop_iter(remaining_cards, cards_community, 0)?
+                .map(calculate)
+                .try_fold(
+                    vec![PlayerOutcome::default(); num_players],
+                    Self::folding_fn,
                 )
+        }?;
+
+        Ok(Self { outcomes: result })
+    }
+
+    /// Calculate heads-up win/lose counts of hands without duplicated cards.
+    /// Boards mapped to each other by suit permutations fixing both hands and
+    /// community cards have the same result, so only the smallest board of each orbit
+    /// is evaluated and weighted by the orbit size. Hands are evaluated by bit masks
+    /// on top of the card number masks of each board.
+    /// This is a helper function, do not call this directly.
+    fn heads_up_calculation(
+        hands: [Hand; 2],
+        cards_community: &[Card],
+        remaining_cards: &[Card],
+        parallel_calculation: bool,
+        progress: &Progress,
+    ) -> Result<Self, PokercraftLocalError> {
+        // Cards as bits of `Card::index`, and suit permutations on them
+        let bits = |cards: &[Card]| {
+            cards
+                .iter()
+                .fold(0u64, |bits, card| bits | (1 << card.index()))
+        };
+        let permute = |bits: u64, permutation: &[usize; NUM_OF_SHAPES]| {
+            (0..NUM_OF_SHAPES).fold(0u64, |image, shape| {
+                let numbers = (bits >> (shape * NUM_OF_NUMBERS)) & ((1 << NUM_OF_NUMBERS) - 1);
+                image | (numbers << (permutation[shape] * NUM_OF_NUMBERS))
+            })
+        };
+        let fixed = [
+            bits(&[hands[0].0, hands[0].1]),
+            bits(&[hands[1].0, hands[1].1]),
+            bits(cards_community),
+        ];
+        let symmetries = get_canonical_shape_mappings()
+            .iter()
+            .map(|mapping| CardShape::all().map(|shape| mapping.apply_shape(&shape) as usize))
+            .filter(|permutation| fixed.iter().all(|&bits| permute(bits, permutation) == bits))
+            .collect::<Vec<_>>();
+        let hand_masks = hands.map(|(c1, c2)| shape_masks(&[c1, c2]));
+
+        // `[player 1 wins, player 2 wins, ties]`
+        let fold = |mut counts: [u64; 3], board: [Card; 5]| {
+            progress.tick()?;
+            let board_bits = bits(&board);
+            let mut stabilizers = 0;
+            for permutation in symmetries.iter() {
+                let image = permute(board_bits, permutation);
+                if image < board_bits {
+                    return Ok(counts);
+                }
+                stabilizers += (image == board_bits) as u64;
+            }
+            let weight = symmetries.len() as u64 / stabilizers;
+
+            let board_masks = shape_masks(&board);
+            let [key1, key2] = hand_masks.map(|masks| {
+                evaluate_shape_masks(std::array::from_fn(|i| masks[i] | board_masks[i]))
+            });
+            counts[match key1.cmp(&key2) {
+                std::cmp::Ordering::Greater => 0,
+                std::cmp::Ordering::Less => 1,
+                std::cmp::Ordering::Equal => 2,
+            }] += weight;
+            Ok(counts)
+        };
+        let [win1, win2, tie] = if parallel_calculation {
+            Self::fold_boards_in_chunks(
+                remaining_cards,
+                cards_community,
+                || [0; 3],
+                fold,
+                |a, b| Ok(std::array::from_fn(|i| a[i] + b[i])),
+            )
         } else {
-            iter.map(|communities| Self::single_board_calculation(communities, &cards_people))
-                .try_fold(Self::get_empty_winloses(num_players), Self::folding_fn)
+            Self::get_flop_iter(remaining_cards, cards_community, 0)?.try_fold([0; 3], fold)
         }?;
 
-        Ok(Self {
-            wins: result.0,
-            loses: result.1,
+        Ok(Self::heads_up(win1, win2, tie))
+    }
+
+    /// Get the heads-up preflop result from the embedded cache,
+    /// or `None` if hands share any card.
+    #[cfg(feature = "embedded-tables")]
+    fn from_embedded_preflop_cache(hand1: Hand, hand2: Hand) -> Option<Self> {
+        let cards = [hand1.0, hand1.1, hand2.0, hand2.1];
+        if (1..cards.len()).any(|i| cards[..i].contains(&cards[i])) {
+            return None;
+        }
+        let (win1, win2, tie) = HUPreflopEquityCache::embedded()
+            .ok()?
+            .get_winlose(hand1, hand2)
+            .ok()?;
+        Some(Self::heads_up(win1, win2, tie))
+    }
+
+    /// Create a heads-up result from wins of each player and ties.
+    fn heads_up(win1: u64, win2: u64, tie: u64) -> Self {
+        let outcome = |wins: u64, losses: u64| {
+            let mut outcome = PlayerOutcome {
+                wins_outright: wins,
+                losses,
+                ..Default::default()
+            };
+            outcome.ties_by_way[1] = tie;
+            outcome
+        };
+        Self {
+            outcomes: vec![outcome(win1, win2), outcome(win2, win1)],
+        }
+    }
+
+    /// Create a result from win and lose counts of each player,
+    /// which are structured same as `get_winlosses`.
+    pub fn from_counts(wins: Vec<Vec<u64>>, loses: Vec<u64>) -> Result<Self, PokercraftLocalError> {
+        if wins.len() != loses.len() || wins.iter().any(|counts| counts.len() != wins.len()) {
+            return Err(PokercraftLocalError::GeneralError(
+                "Win and lose counts should be given for each player".to_string(),
+            ));
+        } else if wins.len() > MAX_PLAYERS {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Too many players; Should have at most {} players",
+                MAX_PLAYERS
+            )));
+        }
+        let outcomes = wins
+            .iter()
+            .zip(loses)
+            .map(|(counts, losses)| {
+                let mut outcome = PlayerOutcome {
+                    losses,
+                    ..Default::default()
+                };
+                for (others, &count) in counts.iter().enumerate() {
+                    outcome.ties_by_way[others] = count;
+                }
+                outcome.wins_outright = std::mem::take(&mut outcome.ties_by_way[0]);
+                outcome
+            })
+            .collect();
+        Ok(Self { outcomes })
+    }
+
+    /// Get the number of players.
+    pub fn num_players(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Get the outcome of the given player index (0-based).
+    pub fn outcome(&self, player_index: usize) -> Result<&PlayerOutcome, PokercraftLocalError> {
+        self.outcomes.get(player_index).ok_or_else(|| {
+            PokercraftLocalError::GeneralError("Player index out of range".to_string())
         })
     }
 
+    /// Get outcomes of all players.
+    pub fn outcomes(&self) -> &[PlayerOutcome] {
+        &self.outcomes
+    }
+
     /// Get the equity of the given player index (0-based).
     pub fn get_equity(&self, player_index: usize) -> Result<f64, PokercraftLocalError> {
-        if player_index >= self.wins.len() {
+        self.outcome(player_index)?.equity().ok_or_else(|| {
+            PokercraftLocalError::GeneralError(
+                "No games played; Cannot calculate equity".to_string(),
+            )
+        })
+    }
+
+    /// Get probabilities of winning outright, chopping each number of ways and losing
+    /// of the given player index (0-based).
+    pub fn outcome_rates(&self, player_index: usize) -> Result<OutcomeRates, PokercraftLocalError> {
+        let outcome = self.outcome(player_index)?;
+        let total = outcome.total();
+        if total == 0 {
             return Err(PokercraftLocalError::GeneralError(
-                "Player index out of range".to_string(),
+                "No games played; Cannot calculate rates".to_string(),
             ));
         }
-        let total_wins: u64 = self.wins[player_index].iter().sum();
-        let total_games: u64 = total_wins + self.loses[player_index];
-        if total_games == 0 {
-            Err(PokercraftLocalError::GeneralError(
-                "No games played; Cannot calculate equity".to_string(),
-            ))
-        } else {
-            Ok(self.wins[player_index]
-                .iter()
-                .enumerate()
-                .fold(0.0, |acc, (ties, &count)| {
-                    acc + (count as f64) / ((ties + 1) as f64)
-                })
-                / (total_games as f64))
-        }
+        let rate = |count: u64| count as f64 / total as f64;
+        Ok(OutcomeRates {
+            win_outright: rate(outcome.wins_outright),
+            chops: (2..=self.num_players())
+                .map(|way| rate(outcome.ties(way)))
+                .collect(),
+            lose: rate(outcome.losses),
+        })
     }
 
-    /// Get the (win/tie counts, lose count)
-    /// of the given player index (0-based).
-    /// The structure of return values are exactly same as
-    /// `self.wins[player_index]` and `self.loses[player_index]`.
+    /// Get the (win/tie counts, lose count) of the given player index (0-based),
+    /// where `counts[c]` is the number of wins with `c` other players having the same rank.
     pub fn get_winlosses(
         &self,
         player_index: usize,
     ) -> Result<(Vec<u64>, u64), PokercraftLocalError> {
-        if player_index >= self.wins.len() {
-            return Err(PokercraftLocalError::GeneralError(
-                "Player index out of range".to_string(),
-            ));
+        let outcome = self.outcome(player_index)?;
+        Ok((outcome.win_counts(self.num_players()), outcome.losses))
+    }
+
+    /// Get equities, win and lose counts of all players at once.
+    pub fn breakdown(&self) -> Result<EquityBreakdown, PokercraftLocalError> {
+        Ok(EquityBreakdown {
+            equities: (0..self.num_players())
+                .map(|i| self.get_equity(i))
+                .collect::<Result<_, _>>()?,
+            wins: self
+                .outcomes
+                .iter()
+                .map(|outcome| outcome.win_counts(self.num_players()))
+                .collect(),
+            loses: self.outcomes.iter().map(|outcome| outcome.losses).collect(),
+            never_lost: self
+                .outcomes
+                .iter()
+                .map(|outcome| outcome.losses == 0)
+                .collect(),
+        })
+    }
+}
+
+/// Equities, win and lose counts of all players of an `EquityResult`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct EquityBreakdown {
+    pub equities: Vec<f64>,
+    /// `wins[i][c]` is number of `i`-th player wins
+    /// with `c` other players having the same rank.
+    pub wins: Vec<Vec<u64>>,
+    pub loses: Vec<u64>,
+    pub never_lost: Vec<bool>,
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(typescript_custom_section)]
+const TS_EQUITY_BREAKDOWN: &'static str = r#"
+/** Equities, win and lose counts of all players. */
+export interface EquityBreakdown {
+    equities: number[];
+    /** `wins[i][c]` is number of `i`-th player wins with `c` other players having the same rank. */
+    wins: number[][];
+    loses: number[];
+    neverLost: boolean[];
+}
+
+/** Probabilities of winning outright, chopping and losing of a player. */
+export interface OutcomeRates {
+    winOutright: number;
+    /** `chops[k]` is the probability of chopping `k + 2` ways. */
+    chops: number[];
+    lose: number;
+}
+"#;
+
+/// Parse JS arrays of hands and community card strings.
+/// Each hand is either a pair of card strings like `["As", "Kh"]` or a string like `"AsKh"`,
+/// and each community card string may contain multiple cards like `"2c3d4h"`.
+#[cfg(feature = "wasm")]
+fn parse_js_cards(
+    hands: js_sys::Array,
+    community: js_sys::Array,
+) -> Result<(Vec<Hand>, Vec<Card>), JsValue> {
+    let to_js_error = |e: PokercraftLocalError| JsValue::from_str(&e.to_string());
+
+    // Parse hands
+    let mut cards_people: Vec<Hand> = Vec::new();
+    for hand in hands.iter() {
+        if let Some(hand_str) = hand.as_string() {
+            let [card1, card2] = parse_cards_exact::<2>(&hand_str).map_err(to_js_error)?;
+            cards_people.push((card1, card2));
+            continue;
         }
-        Ok((self.wins[player_index].clone(), self.loses[player_index]))
+        let hand_arr: js_sys::Array = hand
+            .dyn_into()
+            .map_err(|_| JsValue::from_str("Each hand must be an array or a string"))?;
+        if hand_arr.length() != 2 {
+            return Err(JsValue::from_str("Each hand must have exactly 2 cards"));
+        }
+        let card1_str: String = hand_arr
+            .get(0)
+            .as_string()
+            .ok_or_else(|| JsValue::from_str("Card must be a string"))?;
+        let card2_str: String = hand_arr
+            .get(1)
+            .as_string()
+            .ok_or_else(|| JsValue::from_str("Card must be a string"))?;
+        let card1 = Card::try_from(card1_str.as_str()).map_err(to_js_error)?;
+        let card2 = Card::try_from(card2_str.as_str()).map_err(to_js_error)?;
+        cards_people.push((card1, card2));
+    }
+
+    // Parse community cards
+    let mut cards_community: Vec<Card> = Vec::new();
+    for card in community.iter() {
+        let card_str: String = card
+            .as_string()
+            .ok_or_else(|| JsValue::from_str("Community card must be a string"))?;
+        cards_community.extend(parse_cards(&card_str).map_err(to_js_error)?);
     }
+
+    Ok((cards_people, cards_community))
 }
 
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
 impl EquityResult {
     /// Create a new EquityResult by calculating equities.
-    /// `hands` is an array of card string pairs, e.g., [["As", "Kh"], ["Qd", "Jc"]]
-    /// `community` is an array of card strings, e.g., ["2c", "3d", "4h"]
+    /// `hands` is an array of card string pairs or strings, e.g., [["As", "Kh"], "QdJc"]
+    /// `community` is an array of card strings, e.g., ["2c", "3d", "4h"] or ["2c3d4h"]
+    /// `progress(done, total)` is called about every 1% of boards,
+    /// and returning `false` from it cancels the calculation.
     #[wasm_bindgen(constructor)]
     pub fn new_wasm(
         hands: js_sys::Array,
         community: js_sys::Array,
+        #[wasm_bindgen(unchecked_param_type = "(done: number, total: number) => boolean | void")]
+        progress: Option<js_sys::Function>,
     ) -> Result<EquityResult, JsValue> {
-        // Parse hands
-        let mut cards_people: Vec<Hand> = Vec::new();
-        for hand in hands.iter() {
-            let hand_arr: js_sys::Array = hand
-                .dyn_into()
-                .map_err(|_| JsValue::from_str("Each hand must be an array"))?;
-            if hand_arr.length() != 2 {
-                return Err(JsValue::from_str("Each hand must have exactly 2 cards"));
-            }
-            let card1_str: String = hand_arr
-                .get(0)
-                .as_string()
-                .ok_or_else(|| JsValue::from_str("Card must be a string"))?;
-            let card2_str: String = hand_arr
-                .get(1)
-                .as_string()
-                .ok_or_else(|| JsValue::from_str("Card must be a string"))?;
-            let card1 = Card::try_from(card1_str.as_str())
-                .map_err(|e| JsValue::from_str(&e.to_string()))?;
-            let card2 = Card::try_from(card2_str.as_str())
-                .map_err(|e| JsValue::from_str(&e.to_string()))?;
-            cards_people.push((card1, card2));
-        }
-
-        // Parse community cards
-        let mut cards_community: Vec<Card> = Vec::new();
-        for card in community.iter() {
-            let card_str: String = card
-                .as_string()
-                .ok_or_else(|| JsValue::from_str("Community card must be a string"))?;
-            let card =
-                Card::try_from(card_str.as_str()).map_err(|e| JsValue::from_str(&e.to_string()))?;
-            cards_community.push(card);
+        let (cards_people, cards_community) = parse_js_cards(hands, community)?;
+        let cache = EquityCache::shared();
+        if let Some(result) = cache.get(&cards_people, &cards_community) {
+            return Ok(result);
         }
 
         // Calculate equity (non-parallel for WASM single-threaded environment)
-        Self::new(cards_people, cards_community, false)
-            .map_err(|e| JsValue::from_str(&e.to_string()))
+        let progress = JsProgress::new(progress);
+        let result = Self::new_with_progress(
+            cards_people.clone(),
+            cards_community.clone(),
+            false,
+            &|done, total| progress.report(done, total),
+        )
+        .map_err(|e| progress.convert_error(e))?;
+        cache.insert(&cards_people, &cards_community, result.clone());
+        Ok(result)
     }
 
     /// Get the equity of the given player index (0-based).
@@ -331,13 +836,96 @@ impl EquityResult {
     /// Check if the given player index (0-based) has never lost in all scenarios.
     #[wasm_bindgen(js_name = neverLost)]
     pub fn never_lost_wasm(&self, player_index: usize) -> Result<bool, JsValue> {
-        if player_index >= self.wins.len() {
-            return Err(JsValue::from_str("Player index out of range"));
+        self.outcome(player_index)
+            .map(|outcome| outcome.losses == 0)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Get probabilities of winning outright, chopping each number of ways and losing
+    /// of the given player index (0-based) as a plain object.
+    #[wasm_bindgen(js_name = outcomeRates, unchecked_return_type = "OutcomeRates")]
+    pub fn outcome_rates_wasm(&self, player_index: usize) -> Result<JsValue, JsValue> {
+        let rates = self
+            .outcome_rates(player_index)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        crate::utils::to_js_object(&rates)
+    }
+
+    /// Get the number of boards enumerated for the given hands and community cards,
+    /// which are the same as the constructor.
+    #[wasm_bindgen(js_name = countBoards)]
+    pub fn count_boards_wasm(
+        hands: js_sys::Array,
+        community: js_sys::Array,
+    ) -> Result<f64, JsValue> {
+        let (cards_people, cards_community) = parse_js_cards(hands, community)?;
+        Self::count_boards(&cards_people, &cards_community)
+            .map(|boards| boards as f64)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Calculate only on `length` boards from the `start`-th (0-based),
+    /// to split the calculation over workers or refine it incrementally with `merge`.
+    #[wasm_bindgen(js_name = partial)]
+    pub fn new_partial_wasm(
+        hands: js_sys::Array,
+        community: js_sys::Array,
+        start: f64,
+        length: f64,
+    ) -> Result<EquityResult, JsValue> {
+        let (cards_people, cards_community) = parse_js_cards(hands, community)?;
+        Self::new_partial(cards_people, cards_community, start as u64, length as u64)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Create a new EquityResult from card bytes (see `encodeCards`),
+    /// where `hands` has 2 bytes per player, like `encodeCards("AsKh QdJc")`,
+    /// without converting card strings of JS arrays.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes_wasm(hands: &[u8], community: &[u8]) -> Result<EquityResult, JsValue> {
+        if !hands.len().is_multiple_of(2) {
+            return Err(JsValue::from_str("Each hand must have exactly 2 cards"));
         }
-        Ok(self.loses[player_index] == 0)
+        let cards_people = decode_cards(hands)?
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect();
+        Ok(
+            EquityCache::shared().get_or_calculate(
+                cards_people,
+                decode_cards(community)?,
+                false,
+            )?,
+        )
+    }
+
+    /// Add counts of the other result of the same players to this.
+    #[wasm_bindgen(js_name = merge)]
+    pub fn merge_wasm(&mut self, other: &EquityResult) -> Result<(), JsValue> {
+        self.merge(other)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Get equities, win and lose counts of all players as a plain object.
+    #[wasm_bindgen(js_name = toObject, unchecked_return_type = "EquityBreakdown")]
+    pub fn to_object_wasm(&self) -> Result<JsValue, JsValue> {
+        crate::utils::to_js_object(&self.breakdown()?)
     }
 }
 
+/// Calculate equities of the given hands on the given community cards (WASM interface).
+/// Unlike `new EquityResult(...)`, returns a plain object without a handle to free.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = calculateEquity, unchecked_return_type = "EquityBreakdown")]
+pub fn calculate_equity_wasm(
+    hands: js_sys::Array,
+    community: js_sys::Array,
+    #[wasm_bindgen(unchecked_param_type = "(done: number, total: number) => boolean | void")]
+    progress: Option<js_sys::Function>,
+) -> Result<JsValue, JsValue> {
+    crate::utils::to_js_object(&EquityResult::new_wasm(hands, community, progress)?.breakdown()?)
+}
+
 /// Preflop equity cache for heads-up situations.
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub struct HUPreflopEquityCache {
@@ -488,7 +1076,6 @@ impl HUPreflopEquityCache {
     }
 }
 
-#[cfg(feature = "wasm")]
 impl HUPreflopEquityCache {
     /// Create a `HUPreflopEquityCache` from gzip-compressed cache bytes.
     pub fn from_gzip_bytes(bytes: &[u8]) -> Result<Self, PokercraftLocalError> {
@@ -543,6 +1130,17 @@ impl HUPreflopEquityCache {
         }
         Ok(Self { cache })
     }
+
+    /// Get the cache embedded in the binary, which is decompressed on the first call.
+    #[cfg(feature = "embedded-tables")]
+    pub fn embedded() -> Result<&'static Self, PokercraftLocalError> {
+        static EMBEDDED_CACHE: &[u8] = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tables/hu_preflop_cache.bin"
+        ));
+        static CACHE: OnceCell<HUPreflopEquityCache> = OnceCell::new();
+        CACHE.get_or_try_init(|| Self::from_gzip_bytes(EMBEDDED_CACHE))
+    }
 }
 
 #[cfg(feature = "wasm")]
@@ -579,56 +1177,1426 @@ impl HUPreflopEquityCache {
     }
 }
 
-/// Luck calculator using equity values and results.
-/// Results have two `f64` values: equity (0.0 ~ 1.0) and win/lose (0.0 ~ 1.0).
-/// Win/lose is represented as `1.0` for win and `0.0` for lose.
-/// If there are ties, use fractional values (e.g., `0.5` for a two-way tie).
-#[cfg_attr(feature = "wasm", wasm_bindgen)]
-#[derive(Debug, Clone)]
-pub struct LuckCalculator {
-    results: Vec<(f64, f64)>, // (equity, winlose: 0.0 ~ 1.0)
+/// Canonical key of an equity spot; Cards are bytes of `Card::index`,
+/// hole cards of each player and community cards are sorted.
+type SpotKey = (Vec<[u8; 2]>, Vec<u8>);
+
+/// Cached results of `EquityCache` with their last used ticks.
+#[derive(Default)]
+struct CacheEntries {
+    results: HashMap<SpotKey, (EquityResult, u64)>,
+    /// Keys of `results` by their last used ticks, oldest first.
+    recency: BTreeMap<u64, SpotKey>,
+    tick: u64,
 }
 
-impl LuckCalculator {
-    /// Create a new empty `LuckCalculator`.
-    pub fn new() -> Self {
-        LuckCalculator { results: vec![] }
-    }
+/// Least recently used cache of `EquityResult`s, so repeated spots
+/// are not enumerated again. Spots are keyed on their suit-isomorphic canonical forms,
+/// so `AsKs vs QhQd` and `AhKh vs QsQd` share the same entry,
+/// while players keep their order.
+pub struct EquityCache {
+    capacity: usize,
+    entries: Mutex<CacheEntries>,
+}
 
-    /// Add a new result to the calculator.
-    pub fn add_result(&mut self, equity: f64, actual: f64) -> Result<(), PokercraftLocalError> {
-        if equity < 0.0 || equity > 1.0 {
-            return Err(PokercraftLocalError::GeneralError(
-                "Equity must be between 0.0 and 1.0".to_string(),
-            ));
-        } else if equity == 0.0 && actual > 0.0 {
-            return Err(PokercraftLocalError::GeneralError(
-                "Cannot win with 0% equity".to_string(),
-            ));
-        } else if equity == 1.0 && actual < 1.0 {
-            return Err(PokercraftLocalError::GeneralError(
-                "Cannot lose with 100% equity".to_string(),
-            ));
-        } else {
-            self.results.push((equity, actual));
+static SHARED_EQUITY_CACHE: once_cell::sync::Lazy<EquityCache> =
+    once_cell::sync::Lazy::new(|| EquityCache::new(EquityCache::DEFAULT_CAPACITY));
+
+impl EquityCache {
+    /// Default capacity of the shared cache.
+    pub const DEFAULT_CAPACITY: usize = 4096;
+
+    /// Create an empty cache holding at most `capacity` results.
+    pub fn new(capacity: usize) -> Self {
+        EquityCache {
+            capacity,
+            entries: Mutex::new(CacheEntries::default()),
         }
-        Ok(())
     }
 
-    /// Get an iterator over all equity values on both winning and losing.
-    fn get_all_equity_iter<'a>(&'a self) -> impl Iterator<Item = &'a f64> {
-        self.results.iter().map(|(equity, _actual)| equity)
+    /// Get the cache shared by all-in extractions and WASM equity calculations.
+    pub fn shared() -> &'static Self {
+        &SHARED_EQUITY_CACHE
     }
 
-    /// Number of actual wincount.
-    fn actual_wincount(&self) -> f64 {
-        self.results
-            .iter()
-            .map(|(_equity, actual)| actual)
-            .sum::<f64>()
+    /// Get the maximum number of results.
+    pub fn capacity(&self) -> usize {
+        self.capacity
     }
 
-    /// Calculate the Luck-score of the results.
+    /// Get the number of cached results.
+    pub fn len(&self) -> usize {
+        self.lock().results.len()
+    }
+
+    /// Check if there is no cached result.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all cached results.
+    pub fn clear(&self) {
+        *self.lock() = CacheEntries::default();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheEntries> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Get the canonical key of the given spot,
+    /// which is the smallest one among all suit symmetries.
+    fn canonical_key(cards_people: &[Hand], cards_community: &[Card]) -> SpotKey {
+        get_canonical_shape_mappings()
+            .iter()
+            .map(|mapping| {
+                let hands = cards_people
+                    .iter()
+                    .map(|(c1, c2)| {
+                        let (i1, i2) = (
+                            u8::from(mapping.apply_card(c1)),
+                            u8::from(mapping.apply_card(c2)),
+                        );
+                        [i1.min(i2), i1.max(i2)]
+                    })
+                    .collect::<Vec<_>>();
+                let mut community = cards_community
+                    .iter()
+                    .map(|card| u8::from(mapping.apply_card(card)))
+                    .collect::<Vec<_>>();
+                community.sort_unstable();
+                (hands, community)
+            })
+            .min()
+            .unwrap() // There are always 24 mappings
+    }
+
+    /// Get the cached result of the given spot.
+    pub fn get(&self, cards_people: &[Hand], cards_community: &[Card]) -> Option<EquityResult> {
+        let key = Self::canonical_key(cards_people, cards_community);
+        let mut entries = self.lock();
+        entries.tick += 1;
+        let tick = entries.tick;
+        let (result, last_used) = entries.results.get_mut(&key)?;
+        let result = result.clone();
+        let previous = std::mem::replace(last_used, tick);
+        entries.recency.remove(&previous);
+        entries.recency.insert(tick, key);
+        Some(result)
+    }
+
+    /// Cache the result of the given spot, evicting the least recently used one if full.
+    pub fn insert(&self, cards_people: &[Hand], cards_community: &[Card], result: EquityResult) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = Self::canonical_key(cards_people, cards_community);
+        let mut entries = self.lock();
+        entries.tick += 1;
+        let tick = entries.tick;
+        if let Some((_, previous)) = entries.results.insert(key.clone(), (result, tick)) {
+            entries.recency.remove(&previous);
+        }
+        entries.recency.insert(tick, key);
+        while entries.results.len() > self.capacity {
+            let Some((_, oldest)) = entries.recency.pop_first() else {
+                break;
+            };
+            entries.results.remove(&oldest);
+        }
+    }
+
+    /// Get the cached result of the given spot, or calculate it by `EquityResult::new`.
+    /// The cache is not locked during calculations.
+    pub fn get_or_calculate(
+        &self,
+        cards_people: Vec<Hand>,
+        cards_community: Vec<Card>,
+        parallel_calculation: bool,
+    ) -> Result<EquityResult, PokercraftLocalError> {
+        if let Some(result) = self.get(&cards_people, &cards_community) {
+            return Ok(result);
+        }
+        let result = EquityResult::new(
+            cards_people.clone(),
+            cards_community.clone(),
+            parallel_calculation,
+        )?;
+        self.insert(&cards_people, &cards_community, result.clone());
+        Ok(result)
+    }
+}
+
+/// Calculate equities of many independent spots in one call, like all-ins loaded from a database.
+/// Spots are scheduled over the thread pool set by `set_parallelism`, each enumerated
+/// sequentially, and results are shared with `EquityCache::shared`.
+/// Returns results in the same order as `spots`, or the error of the first failed spot.
+pub fn equity_batch(spots: Vec<Spot>) -> Result<Vec<EquityResult>, PokercraftLocalError> {
+    in_thread_pool(|| {
+        spots
+            .into_par_iter()
+            .enumerate()
+            .map(|(index, spot)| {
+                EquityCache::shared()
+                    .get_or_calculate(spot.hands, spot.board, false)
+                    .map_err(|e| match e {
+                        PokercraftLocalError::GeneralError(message) => {
+                            PokercraftLocalError::GeneralError(format!(
+                                "Spot {}: {}",
+                                index, message
+                            ))
+                        }
+                        e => e,
+                    })
+            })
+            .collect()
+    })
+}
+
+/// Calculate equities of many independent spots in one call (WASM interface).
+/// Each spot is `{hands, board}`, where hands and board are the same as `calculateEquity`.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = equityBatch, unchecked_return_type = "EquityBreakdown[]")]
+pub fn equity_batch_wasm(
+    #[wasm_bindgen(unchecked_param_type = "Spot[]")] spots: js_sys::Array,
+) -> Result<JsValue, JsValue> {
+    let field = |spot: &JsValue, name: &str| -> Result<js_sys::Array, JsValue> {
+        js_sys::Reflect::get(spot, &JsValue::from_str(name))?
+            .dyn_into::<js_sys::Array>()
+            .map_err(|_| JsValue::from_str(&format!("Spot should have an array `{}`", name)))
+    };
+    let spots = spots
+        .iter()
+        .map(|spot| {
+            let (hands, board) = parse_js_cards(field(&spot, "hands")?, field(&spot, "board")?)?;
+            Ok(Spot { hands, board })
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+    let breakdowns = equity_batch(spots)?
+        .iter()
+        .map(EquityResult::breakdown)
+        .collect::<Result<Vec<_>, _>>()?;
+    crate::utils::to_js_object(&breakdowns)
+}
+
+/// Showdown outcomes of a single player over many boards.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct ShowdownOutcome {
+    /// Number of boards won alone.
+    pub wins: u64,
+    /// Number of boards split with other players.
+    pub ties: u64,
+    pub losses: u64,
+    /// Sum of pot shares won; `0.5` for a two-way tie.
+    pub pot_shares: f64,
+    /// `categories[c]` is number of boards finished with
+    /// the hand rank category `c`, as in `HAND_RANK_CATEGORIES`.
+    pub categories: [u64; 9],
+}
+
+impl ShowdownOutcome {
+    /// Get the number of boards.
+    pub fn total(&self) -> u64 {
+        self.wins + self.ties + self.losses
+    }
+
+    /// Get the ratio of the given count over all boards.
+    fn rate(&self, count: u64) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => count as f64 / total as f64,
+        }
+    }
+
+    /// Get the equity, counting ties as pot shares.
+    pub fn equity(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.pot_shares / total as f64,
+        }
+    }
+
+    /// Get the ratio of boards won alone.
+    pub fn win_rate(&self) -> f64 {
+        self.rate(self.wins)
+    }
+
+    /// Get the ratio of boards split.
+    pub fn tie_rate(&self) -> f64 {
+        self.rate(self.ties)
+    }
+
+    /// Get the ratio of boards finished with the given hand rank category.
+    pub fn category_rate(&self, category: usize) -> f64 {
+        self.rate(self.categories.get(category).copied().unwrap_or(0))
+    }
+
+    /// Accumulate other outcomes into this.
+    fn merge(&mut self, other: &Self) {
+        self.wins += other.wins;
+        self.ties += other.ties;
+        self.losses += other.losses;
+        self.pot_shares += other.pot_shares;
+        for (mine, theirs) in self.categories.iter_mut().zip(other.categories.iter()) {
+            *mine += theirs;
+        }
+    }
+}
+
+/// Equity calculation between hand ranges, with tie and hand category breakdowns.
+/// Boards are enumerated exactly if every range is a single combo,
+/// otherwise combos and boards are sampled by Monte Carlo.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct RangeEquityResult {
+    outcomes: Vec<ShowdownOutcome>,
+    exact: bool,
+}
+
+impl RangeEquityResult {
+    /// Maximum number of rejected combo draws per Monte Carlo trial.
+    const MAX_DRAWS_PER_TRIAL: usize = 1000;
+
+    /// Evaluate a single showdown and accumulate it into `outcomes`.
+    fn showdown(
+        hands: &[Hand],
+        board: &[Card],
+        outcomes: &mut [ShowdownOutcome],
+    ) -> Result<(), PokercraftLocalError> {
+        let mut card7: [Card; 7] = [Card::default(); 7];
+        card7[..5].copy_from_slice(board);
+        let mut ranks: Vec<HandRank> = Vec::with_capacity(hands.len());
+        for &(c1, c2) in hands.iter() {
+            card7[5] = c1;
+            card7[6] = c2;
+            ranks.push(HandRank::find_best5(&card7)?.1);
+        }
+        Self::accumulate(&ranks, outcomes);
+        Ok(())
+    }
+
+    /// Accumulate a showdown of the given final ranks into `outcomes`.
+    fn accumulate(ranks: &[HandRank], outcomes: &mut [ShowdownOutcome]) {
+        for (outcome, rank) in outcomes.iter_mut().zip(ranks.iter()) {
+            outcome.categories[rank.numerize().0 as usize] += 1;
+        }
+        let mut best = 0;
+        for i in 1..ranks.len() {
+            if ranks[i] > ranks[best] {
+                best = i;
+            }
+        }
+        let winners = (0..ranks.len())
+            .filter(|&i| ranks[i] == ranks[best])
+            .collect::<Vec<_>>();
+        for (i, outcome) in outcomes.iter_mut().enumerate() {
+            if !winners.contains(&i) {
+                outcome.losses += 1;
+            } else if winners.len() == 1 {
+                outcome.wins += 1;
+                outcome.pot_shares += 1.0;
+            } else {
+                outcome.ties += 1;
+                outcome.pot_shares += 1.0 / winners.len() as f64;
+            }
+        }
+    }
+
+    /// Draw one combo from each range without card conflicts,
+    /// and complete the board with random cards other than `known_cards`.
+    fn draw_trial<R: Rng>(
+        ranges: &[HandRange],
+        board: &[Card],
+        known_cards: &[Card],
+        rng: &mut R,
+    ) -> Result<(Vec<Hand>, Vec<Card>), PokercraftLocalError> {
+        for _ in 0..Self::MAX_DRAWS_PER_TRIAL {
+            let mut used: Vec<Card> = known_cards.to_vec();
+            let mut hands: Vec<Hand> = Vec::with_capacity(ranges.len());
+            for range in ranges {
+                let &(c1, c2) = range.combos().choose(rng).ok_or_else(|| {
+                    PokercraftLocalError::GeneralError("Empty range given".to_string())
+                })?;
+                if used.contains(&c1) || used.contains(&c2) {
+                    break;
+                }
+                used.extend([c1, c2]);
+                hands.push((c1, c2));
+            }
+            if hands.len() < ranges.len() {
+                continue;
+            }
+            let remaining = Card::all()
+                .into_iter()
+                .filter(|card| !used.contains(card))
+                .collect::<Vec<_>>();
+            let mut full_board = board.to_vec();
+            full_board.extend(remaining.choose_multiple(rng, 5 - board.len()));
+            return Ok((hands, full_board));
+        }
+        Err(PokercraftLocalError::GeneralError(
+            "Failed to draw non-conflicting combos; Ranges overlap too much".to_string(),
+        ))
+    }
+
+    /// Calculate equities of the given ranges on the given board (0 to 5 cards).
+    /// Combos conflicting with the board are removed from ranges first.
+    /// `trials` is the number of Monte Carlo samples,
+    /// which is ignored if every range is a single combo.
+    pub fn new(
+        ranges: &[HandRange],
+        board: &[Card],
+        trials: u64,
+    ) -> Result<Self, PokercraftLocalError> {
+        Self::new_with_dead_cards(ranges, board, &[], trials)
+    }
+
+    /// Same as `new`, but `dead_cards` are removed
+    /// from both ranges and remaining boards, like folded or exposed cards.
+    pub fn new_with_dead_cards(
+        ranges: &[HandRange],
+        board: &[Card],
+        dead_cards: &[Card],
+        trials: u64,
+    ) -> Result<Self, PokercraftLocalError> {
+        Self::calculate(ranges, board, dead_cards, trials, false)
+    }
+
+    /// Same as `new_with_dead_cards`, but Monte Carlo trials run on GPU.
+    /// Exact enumerations, and all calculations without any GPU adapter, run on CPU.
+    #[cfg(feature = "gpu")]
+    pub fn new_gpu(
+        ranges: &[HandRange],
+        board: &[Card],
+        dead_cards: &[Card],
+        trials: u64,
+    ) -> Result<Self, PokercraftLocalError> {
+        Self::calculate(ranges, board, dead_cards, trials, true)
+    }
+
+    /// Implementation of `new_with_dead_cards` and `new_gpu`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(players = ranges.len(), board = board.len(), trials = trials)
+        )
+    )]
+    fn calculate(
+        ranges: &[HandRange],
+        board: &[Card],
+        dead_cards: &[Card],
+        trials: u64,
+        #[cfg_attr(not(feature = "gpu"), allow(unused_variables))] on_gpu: bool,
+    ) -> Result<Self, PokercraftLocalError> {
+        if board.len() > 5 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Too many community cards; Should have at most 5 cards".to_string(),
+            ));
+        } else if ranges.is_empty() {
+            return Err(PokercraftLocalError::GeneralError(
+                "No player ranges given".to_string(),
+            ));
+        } else if ranges.len() > 23 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Too many players; Should have at most 23 players".to_string(),
+            ));
+        }
+        let known_cards = board
+            .iter()
+            .chain(dead_cards.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        for (i, card) in known_cards.iter().enumerate() {
+            if known_cards[..i].contains(card) {
+                return Err(PokercraftLocalError::GeneralError(format!(
+                    "Duplicated card {}",
+                    card
+                )));
+            }
+        }
+        let ranges = ranges
+            .iter()
+            .map(|range| range.without_cards(&known_cards))
+            .collect::<Vec<_>>();
+        if let Some(i) = ranges.iter().position(|range| range.is_empty()) {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Range of player {} has no combos left without known cards",
+                i + 1
+            )));
+        }
+        let num_players = ranges.len();
+        let empty = || vec![ShowdownOutcome::default(); num_players];
+        let merge = |mut acc: Vec<ShowdownOutcome>,
+                     other: Vec<ShowdownOutcome>|
+         -> Result<Vec<ShowdownOutcome>, PokercraftLocalError> {
+            for (mine, theirs) in acc.iter_mut().zip(other.iter()) {
+                mine.merge(theirs);
+            }
+            Ok(acc)
+        };
+
+        if ranges.iter().all(|range| range.len() == 1) {
+            let hands = ranges
+                .iter()
+                .map(|range| range.combos()[0])
+                .collect::<Vec<_>>();
+            let mut used: Vec<Card> = known_cards.clone();
+            for &(c1, c2) in hands.iter() {
+                if used.contains(&c1) || used.contains(&c2) {
+                    return Err(PokercraftLocalError::GeneralError(format!(
+                        "Duplicated card in {}{}",
+                        c1, c2
+                    )));
+                }
+                used.extend([c1, c2]);
+            }
+            let remaining = Card::all()
+                .into_iter()
+                .filter(|card| !used.contains(card))
+                .collect::<Vec<_>>();
+            let outcomes = EquityResult::fold_boards_in_chunks(
+                &remaining,
+                board,
+                empty,
+                |mut acc, full_board| {
+                    Self::showdown(&hands, &full_board, &mut acc)?;
+                    Ok(acc)
+                },
+                merge,
+            )?;
+            return Ok(Self {
+                outcomes,
+                exact: true,
+            });
+        }
+
+        if trials == 0 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Number of trials should be positive".to_string(),
+            ));
+        }
+        #[cfg(feature = "gpu")]
+        if let Some(gpu) = crate::gpu::GpuContext::shared().filter(|_| on_gpu) {
+            return Ok(Self {
+                outcomes: gpu.range_equity(&ranges, board, &known_cards, trials)?,
+                exact: false,
+            });
+        }
+        let outcomes = in_thread_pool(|| {
+            (0..trials)
+                .into_par_iter()
+                .try_fold(empty, |mut acc, _| {
+                    let (hands, full_board) =
+                        Self::draw_trial(&ranges, board, &known_cards, &mut thread_rng())?;
+                    Self::showdown(&hands, &full_board, &mut acc)?;
+                    Ok(acc)
+                })
+                .try_reduce(empty, merge)
+        })?;
+        Ok(Self {
+            outcomes,
+            exact: false,
+        })
+    }
+
+    /// Check if boards were enumerated exactly, rather than sampled.
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    /// Get the number of players.
+    pub fn num_players(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Get the showdown outcomes of the given player index (0-based).
+    pub fn outcome(&self, player_index: usize) -> Result<&ShowdownOutcome, PokercraftLocalError> {
+        self.outcomes.get(player_index).ok_or_else(|| {
+            PokercraftLocalError::GeneralError("Player index out of range".to_string())
+        })
+    }
+}
+
+/// Showdown outcomes of a single player on two boards,
+/// where each board wins half of the pot.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct DoubleBoardOutcome {
+    /// Number of runouts winning the whole pot, alone on both boards.
+    pub scoops: u64,
+    /// Number of runouts winning some but not all of the pot.
+    pub splits: u64,
+    /// Number of runouts winning nothing.
+    pub losses: u64,
+    /// Sum of pot shares won; `0.5` for winning a single board alone.
+    pub pot_shares: f64,
+}
+
+impl DoubleBoardOutcome {
+    /// Get the number of runouts.
+    pub fn total(&self) -> u64 {
+        self.scoops + self.splits + self.losses
+    }
+
+    /// Get the ratio of the given count over all runouts.
+    fn rate(&self, count: u64) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => count as f64 / total as f64,
+        }
+    }
+
+    /// Get the equity, which is the average pot share.
+    pub fn equity(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.pot_shares / total as f64,
+        }
+    }
+
+    /// Get the ratio of runouts scooping the whole pot.
+    pub fn scoop_rate(&self) -> f64 {
+        self.rate(self.scoops)
+    }
+
+    /// Get the ratio of runouts winning a part of the pot.
+    pub fn split_rate(&self) -> f64 {
+        self.rate(self.splits)
+    }
+
+    /// Get the ratio of runouts winning nothing.
+    pub fn lose_rate(&self) -> f64 {
+        self.rate(self.losses)
+    }
+
+    /// Accumulate other outcomes into this.
+    fn merge(&mut self, other: &Self) {
+        self.scoops += other.scoops;
+        self.splits += other.splits;
+        self.losses += other.losses;
+        self.pot_shares += other.pot_shares;
+    }
+}
+
+/// Equity calculation of double board pots like bomb pots, where two boards
+/// are dealt at once and the pot is split in half for each board.
+/// Runouts are enumerated exactly if there are at most `trials` of them,
+/// otherwise `trials` runouts are sampled by Monte Carlo.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct DoubleBoardEquityResult {
+    outcomes: Vec<DoubleBoardOutcome>,
+    exact: bool,
+}
+
+impl DoubleBoardEquityResult {
+    /// Evaluate a single runout of both boards and accumulate it into `outcomes`.
+    fn showdown(
+        hands: &[Hand],
+        boards: [&[Card]; 2],
+        outcomes: &mut [DoubleBoardOutcome],
+    ) -> Result<(), PokercraftLocalError> {
+        let mut shares = vec![0.0; hands.len()];
+        for board in boards {
+            let mut card7: [Card; 7] = [Card::default(); 7];
+            card7[..5].copy_from_slice(board);
+            let mut ranks: Vec<HandRank> = Vec::with_capacity(hands.len());
+            for &(c1, c2) in hands.iter() {
+                card7[5] = c1;
+                card7[6] = c2;
+                ranks.push(HandRank::find_best5(&card7)?.1);
+            }
+            let mut best = 0;
+            for i in 1..ranks.len() {
+                if ranks[i] > ranks[best] {
+                    best = i;
+                }
+            }
+            let winners = (0..ranks.len())
+                .filter(|&i| ranks[i] == ranks[best])
+                .collect::<Vec<_>>();
+            for &i in winners.iter() {
+                shares[i] += 0.5 / winners.len() as f64;
+            }
+        }
+        for (outcome, share) in outcomes.iter_mut().zip(shares) {
+            if share >= 1.0 {
+                outcome.scoops += 1;
+            } else if share > 0.0 {
+                outcome.splits += 1;
+            } else {
+                outcome.losses += 1;
+            }
+            outcome.pot_shares += share;
+        }
+        Ok(())
+    }
+
+    /// Calculate equities of the given hands on two boards of 0 to 5 cards each.
+    pub fn new(
+        hands: &[Hand],
+        boards: [&[Card]; 2],
+        trials: u64,
+    ) -> Result<Self, PokercraftLocalError> {
+        if hands.len() < 2 {
+            return Err(PokercraftLocalError::GeneralError(
+                "At least 2 players are required".to_string(),
+            ));
+        } else if boards.iter().any(|board| board.len() > 5) {
+            return Err(PokercraftLocalError::GeneralError(
+                "Too many community cards; Should have at most 5 cards per board".to_string(),
+            ));
+        }
+        let mut used: Vec<Card> = Vec::new();
+        for card in hands
+            .iter()
+            .flat_map(|&(c1, c2)| [c1, c2])
+            .chain(boards[0].iter().chain(boards[1].iter()).copied())
+        {
+            if used.contains(&card) {
+                return Err(PokercraftLocalError::GeneralError(format!(
+                    "Duplicated card {}",
+                    card
+                )));
+            }
+            used.push(card);
+        }
+        let remaining = Card::all()
+            .into_iter()
+            .filter(|card| !used.contains(card))
+            .collect::<Vec<_>>();
+        let missing = [5 - boards[0].len(), 5 - boards[1].len()];
+        if missing[0] + missing[1] > remaining.len() {
+            return Err(PokercraftLocalError::GeneralError(
+                "Not enough cards left to complete both boards".to_string(),
+            ));
+        }
+
+        let num_players = hands.len();
+        let empty = || vec![DoubleBoardOutcome::default(); num_players];
+        let merge = |mut acc: Vec<DoubleBoardOutcome>,
+                     other: Vec<DoubleBoardOutcome>|
+         -> Result<Vec<DoubleBoardOutcome>, PokercraftLocalError> {
+            for (mine, theirs) in acc.iter_mut().zip(other.iter()) {
+                mine.merge(theirs);
+            }
+            Ok(acc)
+        };
+
+        let runouts = binomial(remaining.len() as u64, missing[0] as u64)
+            * binomial((remaining.len() - missing[0]) as u64, missing[1] as u64);
+        if runouts <= trials.max(1) {
+            let first_boards: Vec<[Card; 5]> =
+                EquityResult::get_flop_iter(&remaining, boards[0], 0)?.collect();
+            let outcomes = in_thread_pool(|| {
+                first_boards
+                    .par_iter()
+                    .try_fold(empty, |mut acc, first| {
+                        let rest = remaining
+                            .iter()
+                            .filter(|card| !first.contains(card))
+                            .copied()
+                            .collect::<Vec<_>>();
+                        for second in EquityResult::get_flop_iter(&rest, boards[1], 0)? {
+                            Self::showdown(hands, [first, &second], &mut acc)?;
+                        }
+                        Ok(acc)
+                    })
+                    .try_reduce(empty, merge)
+            })?;
+            return Ok(Self {
+                outcomes,
+                exact: true,
+            });
+        }
+
+        let outcomes = in_thread_pool(|| {
+            (0..trials)
+                .into_par_iter()
+                .try_fold(empty, |mut acc, _| {
+                    let mut drawn = remaining
+                        .choose_multiple(&mut thread_rng(), missing[0] + missing[1])
+                        .copied();
+                    let full_boards = [0, 1].map(|i| {
+                        let mut full_board = boards[i].to_vec();
+                        full_board.extend(drawn.by_ref().take(missing[i]));
+                        full_board
+                    });
+                    Self::showdown(hands, [&full_boards[0], &full_boards[1]], &mut acc)?;
+                    Ok(acc)
+                })
+                .try_reduce(empty, merge)
+        })?;
+        Ok(Self {
+            outcomes,
+            exact: false,
+        })
+    }
+
+    /// Check if runouts were enumerated exactly, rather than sampled.
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    /// Get the number of players.
+    pub fn num_players(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Get the outcomes of the given player index (0-based).
+    pub fn outcome(
+        &self,
+        player_index: usize,
+    ) -> Result<&DoubleBoardOutcome, PokercraftLocalError> {
+        self.outcomes.get(player_index).ok_or_else(|| {
+            PokercraftLocalError::GeneralError("Player index out of range".to_string())
+        })
+    }
+}
+
+/// Equity of a single hand against each combo of a weighted range.
+/// Boards against each combo are enumerated exactly if there are at most `trials` of them,
+/// otherwise `trials` boards are sampled by Monte Carlo.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct HandVsRangeResult {
+    hand: Hand,
+    board: Vec<Card>,
+    /// `(combo, weight, equity of the hand)`, except combos blocked by the hand or the board.
+    combos: Vec<(Hand, f64, f64)>,
+}
+
+impl HandVsRangeResult {
+    /// Get the showdown outcome of `hands[0]` against `hands[1]` on the board.
+    fn heads_up(
+        hands: [Hand; 2],
+        board: &[Card],
+        trials: u64,
+    ) -> Result<ShowdownOutcome, PokercraftLocalError> {
+        let used = [hands[0].0, hands[0].1, hands[1].0, hands[1].1];
+        let remaining = Card::all()
+            .into_iter()
+            .filter(|card| !used.contains(card) && !board.contains(card))
+            .collect::<Vec<_>>();
+        let mut outcomes = vec![ShowdownOutcome::default(); 2];
+        if binomial(remaining.len() as u64, 5 - board.len() as u64) <= trials.max(1) {
+            for full_board in EquityResult::get_flop_iter(&remaining, board, 0)? {
+                RangeEquityResult::showdown(&hands, &full_board, &mut outcomes)?;
+            }
+        } else {
+            let mut rng = thread_rng();
+            for _ in 0..trials {
+                let mut full_board = board.to_vec();
+                full_board.extend(remaining.choose_multiple(&mut rng, 5 - board.len()));
+                RangeEquityResult::showdown(&hands, &full_board, &mut outcomes)?;
+            }
+        }
+        Ok(outcomes.swap_remove(0))
+    }
+
+    /// Calculate equities of the hand against each combo of the weighted range
+    /// on the board (0 to 5 cards), in parallel.
+    pub fn new(
+        hand: Hand,
+        range: &[(Hand, f64)],
+        board: &[Card],
+        trials: u64,
+    ) -> Result<Self, PokercraftLocalError> {
+        let (c1, c2) = hand;
+        if board.len() > 5 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Too many community cards; Should have at most 5 cards".to_string(),
+            ));
+        } else if c1 == c2 || board.contains(&c1) || board.contains(&c2) {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Duplicated card in {}{}",
+                c1, c2
+            )));
+        } else if let Some((_, weight)) = range
+            .iter()
+            .find(|(_, weight)| !(*weight >= 0.0 && weight.is_finite()))
+        {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Weight must not be negative, got {}",
+                weight
+            )));
+        }
+        let known_cards = [board, &[c1, c2]].concat();
+        let combos = range
+            .iter()
+            .filter(|((v1, v2), weight)| {
+                *weight > 0.0 && !known_cards.contains(v1) && !known_cards.contains(v2)
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        if combos.is_empty() {
+            return Err(PokercraftLocalError::GeneralError(
+                "Range has no combos left without known cards".to_string(),
+            ));
+        }
+        let combos = in_thread_pool(|| {
+            combos
+                .into_par_iter()
+                .map(|(combo, weight)| {
+                    let outcome = Self::heads_up([hand, combo], board, trials)?;
+                    Ok((combo, weight, outcome.equity()))
+                })
+                .collect::<Result<Vec<_>, PokercraftLocalError>>()
+        })?;
+        Ok(Self {
+            hand,
+            board: board.to_vec(),
+            combos,
+        })
+    }
+
+    /// Get `(combo, weight, equity of the hand)` of each combo of the range,
+    /// except combos blocked by the hand or the board.
+    pub fn combos(&self) -> &[(Hand, f64, f64)] {
+        &self.combos
+    }
+
+    /// Get the equity against the whole range, weighted by weights of combos.
+    pub fn equity(&self) -> f64 {
+        let (total, weighted) = self
+            .combos
+            .iter()
+            .fold((0.0, 0.0), |(total, weighted), (_, weight, equity)| {
+                (total + weight, weighted + weight * equity)
+            });
+        if total > 0.0 {
+            weighted / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Keep only the continuing part of the range, which is the strongest `fraction`
+    /// of its weight, like calling a bet. Strength of each combo is its equity
+    /// against a random hand on the board, with `trials` Monte Carlo samples.
+    /// The weakest continuing combo is partially kept to match the fraction exactly.
+    pub fn continuing(&self, fraction: f64, trials: u64) -> Result<Self, PokercraftLocalError> {
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Fraction should be in (0, 1], got {}",
+                fraction
+            )));
+        }
+        let random = HandRange::try_from("random")?;
+        let mut strengths = self
+            .combos
+            .iter()
+            .map(|&(combo, weight, equity)| {
+                let ranges = [HandRange::from_combos([combo]), random.clone()];
+                let strength = RangeEquityResult::new(&ranges, &self.board, trials)?
+                    .outcome(0)?
+                    .equity();
+                Ok((strength, (combo, weight, equity)))
+            })
+            .collect::<Result<Vec<_>, PokercraftLocalError>>()?;
+        strengths.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut left = fraction * self.combos.iter().map(|(_, w, _)| w).sum::<f64>();
+        let mut combos = Vec::new();
+        for (_, (combo, weight, equity)) in strengths {
+            if left <= 0.0 {
+                break;
+            }
+            combos.push((combo, weight.min(left), equity));
+            left -= weight;
+        }
+        Ok(Self {
+            hand: self.hand,
+            board: self.board.clone(),
+            combos,
+        })
+    }
+}
+
+/// Equities of all players after each street of a complete runout,
+/// to draw an equity graph of a hand.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(PartialEq, Clone, Debug)]
+pub struct EquityEvolution {
+    pub preflop: Vec<f64>,
+    pub flop: Vec<f64>,
+    pub turn: Vec<f64>,
+    pub river: Vec<f64>,
+}
+
+impl EquityEvolution {
+    /// Calculate exact equities of the hands before the flop, and after the first 3,
+    /// 4 and 5 cards of the runout. Results are shared with `EquityCache::shared`.
+    pub fn new(
+        cards_people: Vec<Hand>,
+        runout: &[Card],
+        parallel_calculation: bool,
+    ) -> Result<Self, PokercraftLocalError> {
+        if runout.len() != 5 {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Runout should have 5 cards, got {}",
+                runout.len()
+            )));
+        }
+        let mut known_cards = runout.to_vec();
+        known_cards.extend(cards_people.iter().flat_map(|(c1, c2)| [*c1, *c2]));
+        if let Some(card) = (1..known_cards.len())
+            .find(|&i| known_cards[..i].contains(&known_cards[i]))
+            .map(|i| known_cards[i])
+        {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Duplicated card {}",
+                card
+            )));
+        }
+
+        let [preflop, flop, turn, river] = [0, 3, 4, 5].map(|street| {
+            let result = EquityCache::shared().get_or_calculate(
+                cards_people.clone(),
+                runout[..street].to_vec(),
+                parallel_calculation,
+            )?;
+            (0..result.num_players())
+                .map(|i| result.get_equity(i))
+                .collect::<Result<Vec<_>, _>>()
+        });
+        Ok(Self {
+            preflop: preflop?,
+            flop: flop?,
+            turn: turn?,
+            river: river?,
+        })
+    }
+
+    /// Get equities of the given player (0-based) on preflop, flop, turn and river.
+    pub fn player(&self, player_index: usize) -> Result<[f64; 4], PokercraftLocalError> {
+        if player_index >= self.preflop.len() {
+            return Err(PokercraftLocalError::GeneralError(
+                "Player index out of range".to_string(),
+            ));
+        }
+        Ok([&self.preflop, &self.flop, &self.turn, &self.river]
+            .map(|equities| equities[player_index]))
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(typescript_custom_section)]
+const TS_EQUITY_EVOLUTION: &'static str = r#"
+/** Equities of all players after each street of a runout. */
+export interface EquityEvolution {
+    preflop: number[];
+    flop: number[];
+    turn: number[];
+    river: number[];
+}
+"#;
+
+/// Calculate equities of the given hands after each street of the 5-card runout (WASM interface).
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = equityEvolution, unchecked_return_type = "EquityEvolution")]
+pub fn equity_evolution_wasm(
+    hands: js_sys::Array,
+    runout: js_sys::Array,
+) -> Result<JsValue, JsValue> {
+    let (cards_people, runout) = parse_js_cards(hands, runout)?;
+    let evolution = EquityEvolution::new(cards_people, &runout, false)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    crate::utils::to_js_object(&evolution)
+}
+
+/// Known cards of a seven-card stud player; Other cards are dealt at random.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct StudHand {
+    /// Known down cards, at most 3.
+    pub down: Vec<Card>,
+    /// Up cards, at most 4.
+    pub up: Vec<Card>,
+}
+
+impl StudHand {
+    /// Get the number of cards not dealt or not known yet.
+    fn missing(&self) -> usize {
+        7 - self.down.len() - self.up.len()
+    }
+}
+
+/// Call `f` with every `k`-card combination of `cards`, in the order of `cards`.
+fn for_each_combination<F>(
+    cards: &[Card],
+    k: usize,
+    chosen: &mut Vec<Card>,
+    f: &mut F,
+) -> Result<(), PokercraftLocalError>
+where
+    F: FnMut(&[Card]) -> Result<(), PokercraftLocalError>,
+{
+    if chosen.len() == k {
+        return f(chosen);
+    }
+    for i in 0..cards.len() {
+        if cards.len() - i < k - chosen.len() {
+            break;
+        }
+        chosen.push(cards[i]);
+        for_each_combination(&cards[i + 1..], k, chosen, f)?;
+        chosen.pop();
+    }
+    Ok(())
+}
+
+/// Equity calculation of seven-card stud (high only), where each player
+/// gets 7 cards of their own and missing cards are dealt from the rest of the deck.
+/// Runouts are enumerated exactly if there are at most `trials` of them,
+/// otherwise `trials` runouts are sampled by Monte Carlo.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct StudEquityResult {
+    outcomes: Vec<ShowdownOutcome>,
+    exact: bool,
+}
+
+impl StudEquityResult {
+    /// Evaluate a single runout, where `dealt[i]` is the missing cards of player `i`.
+    fn showdown(
+        players: &[StudHand],
+        dealt: &[Vec<Card>],
+        outcomes: &mut [ShowdownOutcome],
+    ) -> Result<(), PokercraftLocalError> {
+        let mut ranks: Vec<HandRank> = Vec::with_capacity(players.len());
+        for (player, dealt) in players.iter().zip(dealt.iter()) {
+            let cards = [player.down.as_slice(), &player.up, dealt].concat();
+            ranks.push(HandRank::find_best5(&cards)?.1);
+        }
+        RangeEquityResult::accumulate(&ranks, outcomes);
+        Ok(())
+    }
+
+    /// Deal missing cards of players from `index` in every possible way.
+    fn enumerate(
+        players: &[StudHand],
+        remaining: &[Card],
+        index: usize,
+        dealt: &mut Vec<Vec<Card>>,
+        outcomes: &mut [ShowdownOutcome],
+    ) -> Result<(), PokercraftLocalError> {
+        if index == players.len() {
+            return Self::showdown(players, dealt, outcomes);
+        }
+        for_each_combination(
+            remaining,
+            players[index].missing(),
+            &mut Vec::new(),
+            &mut |cards| {
+                let rest = remaining
+                    .iter()
+                    .filter(|card| !cards.contains(card))
+                    .copied()
+                    .collect::<Vec<_>>();
+                dealt.push(cards.to_vec());
+                Self::enumerate(players, &rest, index + 1, dealt, outcomes)?;
+                dealt.pop();
+                Ok(())
+            },
+        )
+    }
+
+    /// Calculate equities of the given players.
+    /// `dead_cards` are removed from the deck, like up cards of folded players.
+    pub fn new(
+        players: &[StudHand],
+        dead_cards: &[Card],
+        trials: u64,
+    ) -> Result<Self, PokercraftLocalError> {
+        if players.len() < 2 {
+            return Err(PokercraftLocalError::GeneralError(
+                "At least 2 players are required".to_string(),
+            ));
+        } else if players
+            .iter()
+            .any(|player| player.down.len() > 3 || player.up.len() > 4)
+        {
+            return Err(PokercraftLocalError::GeneralError(
+                "Each player should have at most 3 down cards and 4 up cards".to_string(),
+            ));
+        }
+        let mut used: Vec<Card> = Vec::new();
+        for &card in players
+            .iter()
+            .flat_map(|player| player.down.iter().chain(player.up.iter()))
+            .chain(dead_cards.iter())
+        {
+            if used.contains(&card) {
+                return Err(PokercraftLocalError::GeneralError(format!(
+                    "Duplicated card {}",
+                    card
+                )));
+            }
+            used.push(card);
+        }
+        let remaining = Card::all()
+            .into_iter()
+            .filter(|card| !used.contains(card))
+            .collect::<Vec<_>>();
+        let missing = players.iter().map(StudHand::missing).sum::<usize>();
+        if missing > remaining.len() {
+            return Err(PokercraftLocalError::GeneralError(
+                "Not enough cards left to deal every player".to_string(),
+            ));
+        }
+
+        let num_players = players.len();
+        let mut runouts: u64 = 1;
+        let mut left = remaining.len() as u64;
+        for player in players.iter() {
+            runouts = runouts.saturating_mul(binomial(left, player.missing() as u64));
+            left -= player.missing() as u64;
+        }
+        if runouts <= trials.max(1) {
+            let mut outcomes = vec![ShowdownOutcome::default(); num_players];
+            Self::enumerate(players, &remaining, 0, &mut Vec::new(), &mut outcomes)?;
+            return Ok(Self {
+                outcomes,
+                exact: true,
+            });
+        }
+
+        let empty = || vec![ShowdownOutcome::default(); num_players];
+        let merge = |mut acc: Vec<ShowdownOutcome>,
+                     other: Vec<ShowdownOutcome>|
+         -> Result<Vec<ShowdownOutcome>, PokercraftLocalError> {
+            for (mine, theirs) in acc.iter_mut().zip(other.iter()) {
+                mine.merge(theirs);
+            }
+            Ok(acc)
+        };
+        let outcomes = in_thread_pool(|| {
+            (0..trials)
+                .into_par_iter()
+                .try_fold(empty, |mut acc, _| {
+                    let mut drawn = remaining
+                        .choose_multiple(&mut thread_rng(), missing)
+                        .copied();
+                    let dealt = players
+                        .iter()
+                        .map(|player| drawn.by_ref().take(player.missing()).collect())
+                        .collect::<Vec<_>>();
+                    Self::showdown(players, &dealt, &mut acc)?;
+                    Ok(acc)
+                })
+                .try_reduce(empty, merge)
+        })?;
+        Ok(Self {
+            outcomes,
+            exact: false,
+        })
+    }
+
+    /// Check if runouts were enumerated exactly, rather than sampled.
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    /// Get the number of players.
+    pub fn num_players(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Get the showdown outcomes of the given player index (0-based).
+    pub fn outcome(&self, player_index: usize) -> Result<&ShowdownOutcome, PokercraftLocalError> {
+        self.outcomes.get(player_index).ok_or_else(|| {
+            PokercraftLocalError::GeneralError("Player index out of range".to_string())
+        })
+    }
+}
+
+/// Luck calculator using equity values and results.
+/// Results have two `f64` values: equity (0.0 ~ 1.0) and win/lose (0.0 ~ 1.0).
+/// Win/lose is represented as `1.0` for win and `0.0` for lose.
+/// If there are ties, use fractional values (e.g., `0.5` for a two-way tie).
+/// Results can be tagged with group keys like stakes, tournament stages or months,
+/// to get statistics of each group as well as overall.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone)]
+pub struct LuckCalculator {
+    results: Vec<(f64, f64)>, // (equity, winlose: 0.0 ~ 1.0)
+    /// Group keys in the order of first appearance, with indices of their results.
+    groups: Vec<(String, Vec<usize>)>,
+}
+
+/// Luck statistics of a group of results of `LuckCalculator`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct LuckGroupStats {
+    pub group: String,
+    pub results: usize,
+    /// Sum of equities.
+    pub expected_wins: f64,
+    pub actual_wins: f64,
+    /// See `LuckCalculator::luck_score`.
+    pub luck_score: Option<f64>,
+    /// See `LuckCalculator::tails`.
+    pub tails: Option<(f64, f64, f64)>,
+}
+
+/// Distribution of the number of wins of `LuckCalculator`,
+/// to plot where the actual results landed.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct LuckDistribution {
+    /// `pmf[k]` is the probability of winning exactly `k` times.
+    pub pmf: Vec<f64>,
+    /// Actual number of wins, where ties count as fractional wins.
+    pub observed_wins: f64,
+}
+
+impl LuckCalculator {
+    /// Create a new empty `LuckCalculator`.
+    pub fn new() -> Self {
+        LuckCalculator {
+            results: vec![],
+            groups: vec![],
+        }
+    }
+
+    /// Add a new result tagged with the group key.
+    /// The result also counts for overall statistics.
+    pub fn add_grouped_result(
+        &mut self,
+        group: &str,
+        equity: f64,
+        actual: f64,
+    ) -> Result<(), PokercraftLocalError> {
+        self.add_result(equity, actual)?;
+        let index = self.results.len() - 1;
+        match self.groups.iter_mut().find(|(key, _)| key == group) {
+            Some((_, indices)) => indices.push(index),
+            None => self.groups.push((group.to_string(), vec![index])),
+        }
+        Ok(())
+    }
+
+    /// Get group keys in the order of first appearance.
+    pub fn groups(&self) -> Vec<&str> {
+        self.groups.iter().map(|(key, _)| key.as_str()).collect()
+    }
+
+    /// Get a new calculator with results of the given group only,
+    /// or `None` if there is no such group.
+    pub fn group(&self, group: &str) -> Option<LuckCalculator> {
+        let (_, indices) = self.groups.iter().find(|(key, _)| key == group)?;
+        Some(LuckCalculator {
+            results: indices.iter().map(|&i| self.results[i]).collect(),
+            groups: vec![],
+        })
+    }
+
+    /// Get statistics of each group, sorted from the unluckiest group,
+    /// to find where bad luck concentrated.
+    pub fn group_stats(&self) -> Vec<LuckGroupStats> {
+        let mut stats = self
+            .groups
+            .iter()
+            .filter_map(|(key, _)| {
+                let calculator = self.group(key)?;
+                Some(LuckGroupStats {
+                    group: key.clone(),
+                    results: calculator.results.len(),
+                    expected_wins: calculator.get_all_equity_iter().sum(),
+                    actual_wins: calculator.actual_wincount(),
+                    luck_score: calculator.luck_score(),
+                    tails: calculator.tails(),
+                })
+            })
+            .collect::<Vec<_>>();
+        stats.sort_by(|a, b| {
+            let luck = |stat: &LuckGroupStats| stat.luck_score.unwrap_or(0.0);
+            luck(a).total_cmp(&luck(b))
+        });
+        stats
+    }
+
+    /// Add a new result to the calculator.
+    pub fn add_result(&mut self, equity: f64, actual: f64) -> Result<(), PokercraftLocalError> {
+        Self::validate_result(equity, actual)?;
+        self.results.push((equity, actual));
+        Ok(())
+    }
+
+    /// Add `(equity, actual)` results at once.
+    /// Nothing is added if any of them is invalid.
+    pub fn add_results<I>(&mut self, results: I) -> Result<(), PokercraftLocalError>
+    where
+        I: IntoIterator<Item = (f64, f64)>,
+    {
+        let results = results.into_iter().collect::<Vec<_>>();
+        for &(equity, actual) in results.iter() {
+            Self::validate_result(equity, actual)?;
+        }
+        self.results.extend(results);
+        Ok(())
+    }
+
+    /// Remove the last result and return it as `(equity, actual)`.
+    pub fn pop(&mut self) -> Option<(f64, f64)> {
+        let result = self.results.pop()?;
+        self.remove_from_groups(self.results.len());
+        Some(result)
+    }
+
+    /// Keep only the first `len` results, removing the rest.
+    pub fn truncate(&mut self, len: usize) {
+        self.results.truncate(len);
+        self.remove_from_groups(len);
+    }
+
+    /// Remove indices of results from `len` on from groups, with groups left empty.
+    fn remove_from_groups(&mut self, len: usize) {
+        for (_, indices) in self.groups.iter_mut() {
+            indices.retain(|&i| i < len);
+        }
+        self.groups.retain(|(_, indices)| !indices.is_empty());
+    }
+
+    /// Check that the result is possible with the equity.
+    pub(crate) fn validate_result(equity: f64, actual: f64) -> Result<(), PokercraftLocalError> {
+        if equity < 0.0 || equity > 1.0 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Equity must be between 0.0 and 1.0".to_string(),
+            ));
+        } else if equity == 0.0 && actual > 0.0 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Cannot win with 0% equity".to_string(),
+            ));
+        } else if equity == 1.0 && actual < 1.0 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Cannot lose with 100% equity".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get an iterator over all equity values on both winning and losing.
+    fn get_all_equity_iter<'a>(&'a self) -> impl Iterator<Item = &'a f64> {
+        self.results.iter().map(|(equity, _actual)| equity)
+    }
+
+    /// Number of actual wincount.
+    fn actual_wincount(&self) -> f64 {
+        self.results
+            .iter()
+            .map(|(_equity, actual)| actual)
+            .sum::<f64>()
+    }
+
+    /// Calculate the Luck-score of the results.
     /// `Luck = sign(actual - expected) * GaussianCDF^{-1}(1 - p_tail)`
     pub fn luck_score(&self) -> Option<f64> {
         let (_upper, lower, _two_sided) = match self.tails() {
@@ -742,6 +2710,16 @@ impl LuckCalculator {
         (upper, lower, two_sided)
     }
 
+    /// Get the Poisson-binomial distribution of the number of wins
+    /// and the actual number of wins. Without results, `W = 0` always.
+    pub fn pmf(&self) -> LuckDistribution {
+        let ps: Vec<f64> = self.get_all_equity_iter().copied().collect();
+        LuckDistribution {
+            pmf: Self::poisson_binomial_pmf(&ps),
+            observed_wins: self.actual_wincount(),
+        }
+    }
+
     /// The public interface to get the tail p-values;
     /// Upper-tail, lower-tail, and two-sided p-values.
     pub fn tails(&self) -> Option<(f64, f64, f64)> {
@@ -755,6 +2733,18 @@ impl LuckCalculator {
     }
 }
 
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(typescript_custom_section)]
+const TS_LUCK_DISTRIBUTION: &'static str = r#"
+/** Distribution of the number of wins, to plot where the actual results landed. */
+export interface LuckDistribution {
+    /** `pmf[k]` is the probability of winning exactly `k` times. */
+    pmf: number[];
+    /** Actual number of wins, where ties count as fractional wins. */
+    observedWins: number;
+}
+"#;
+
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
 impl LuckCalculator {
@@ -771,6 +2761,49 @@ impl LuckCalculator {
             .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
+    /// Create a new LuckCalculator filled with all-in spots
+    /// of the given player from the hand history text.
+    #[wasm_bindgen(js_name = fromHandHistoryText)]
+    pub fn from_hand_history_text_wasm(
+        hand_history_text: &str,
+        player_id: &str,
+    ) -> Result<LuckCalculator, JsValue> {
+        let hands = crate::parser::parse_hands(hand_history_text.as_bytes())
+            .flatten()
+            .collect::<Vec<_>>();
+        Self::from_hands(&hands, player_id).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Get the distribution of the number of wins and the actual number of wins.
+    #[wasm_bindgen(js_name = pmf, unchecked_return_type = "LuckDistribution")]
+    pub fn pmf_wasm(&self) -> Result<JsValue, JsValue> {
+        crate::utils::to_js_object(&self.pmf())
+    }
+
+    /// Add results at once, where `actuals[i]` is the result with `equities[i]`.
+    #[wasm_bindgen(js_name = addResults)]
+    pub fn add_results_wasm(&mut self, equities: &[f64], actuals: &[f64]) -> Result<(), JsValue> {
+        if equities.len() != actuals.len() {
+            return Err(JsValue::from_str(
+                "Equities and actuals should have the same length",
+            ));
+        }
+        self.add_results(equities.iter().copied().zip(actuals.iter().copied()))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Remove the last result and return it as `[equity, actual]`.
+    #[wasm_bindgen(js_name = pop)]
+    pub fn pop_wasm(&mut self) -> Option<Vec<f64>> {
+        self.pop().map(|(equity, actual)| vec![equity, actual])
+    }
+
+    /// Keep only the first `len` results, removing the rest.
+    #[wasm_bindgen(js_name = truncate)]
+    pub fn truncate_wasm(&mut self, len: usize) {
+        self.truncate(len)
+    }
+
     /// Calculate the Luck-score of the results.
     #[wasm_bindgen(js_name = luckScore)]
     pub fn luck_score_wasm(&self) -> Result<f64, JsValue> {
@@ -784,6 +2817,7 @@ impl LuckCalculator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::card::parse_cards;
 
     /// Helper function to assert the equity results.
     fn assert_equity(
@@ -835,6 +2869,279 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_equity_breakdown() -> Result<(), PokercraftLocalError> {
+        // 45 choose 2 boards remain on the flop
+        let hands = vec![
+            ("Ac".try_into()?, "Kc".try_into()?),
+            ("6h".try_into()?, "7h".try_into()?),
+        ];
+        let flop = vec!["9d".try_into()?, "Td".try_into()?, "Jd".try_into()?];
+        let breakdown = EquityResult::new(hands, flop, false)?.breakdown()?;
+        assert_eq!(breakdown.loses.len(), 2);
+        assert_eq!(breakdown.never_lost, vec![false, false]);
+        assert_almost_equal(breakdown.equities[0], 0.6495 + 0.0566 / 2.0);
+        assert_eq!(
+            breakdown.wins[0].iter().sum::<u64>() + breakdown.loses[0],
+            990
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_equity_progress() -> Result<(), PokercraftLocalError> {
+        // 45 choose 2 boards remain on the flop
+        let hands = vec![
+            ("Ac".try_into()?, "Kc".try_into()?),
+            ("6h".try_into()?, "7h".try_into()?),
+        ];
+        let flop = vec!["9d".try_into()?, "Td".try_into()?, "Jd".try_into()?];
+        let last = std::sync::atomic::AtomicU64::new(0);
+        EquityResult::new_with_progress(hands.clone(), flop.clone(), true, &|done, total| {
+            assert_eq!(total, 990);
+            last.fetch_max(done, std::sync::atomic::Ordering::Relaxed);
+            true
+        })?;
+        assert_eq!(last.into_inner(), 990);
+        assert!(matches!(
+            EquityResult::new_with_progress(hands, flop, false, &|done, _| done < 100),
+            Err(PokercraftLocalError::Cancelled)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_player_outcome() -> Result<(), PokercraftLocalError> {
+        let hands = [("Ah", "Kd"), ("Ac", "Kh"), ("3c", "4c")]
+            .map(|(c1, c2)| -> Result<Hand, PokercraftLocalError> {
+                Ok((c1.try_into()?, c2.try_into()?))
+            })
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Both ace-kings chop the river
+        let result = EquityResult::new(
+            hands.clone(),
+            crate::card::parse_cards("Qs Jh 9c 7d 2s")?,
+            false,
+        )?;
+        let outcome = result.outcome(0)?;
+        assert_eq!((outcome.wins_outright, outcome.losses), (0, 0));
+        assert_eq!(
+            (outcome.ties(2), outcome.ties(3), outcome.total()),
+            (1, 0, 1)
+        );
+        assert_eq!(outcome.equity(), Some(0.5));
+        assert_eq!(result.outcome(2)?.losses, 1);
+        assert_eq!(result.get_winlosses(0)?, (vec![0, 1, 0], 0));
+        assert!(result.outcome(3).is_err());
+        let rates = result.outcome_rates(1)?;
+        assert_eq!(
+            (rates.win_outright, rates.chops, rates.lose),
+            (0.0, vec![1.0, 0.0], 0.0)
+        );
+        assert!(result.outcome_rates(3).is_err());
+
+        // Royal flush on board chops 3 ways
+        let result = EquityResult::new(hands, crate::card::parse_cards("Ts Js Qs Ks As")?, false)?;
+        assert!(result.outcomes().iter().all(|outcome| outcome.ties(3) == 1));
+        assert_eq!(result.get_equity(2)?, 1.0 / 3.0);
+        assert_eq!(result.outcome_rates(2)?.chops, vec![0.0, 1.0]);
+
+        let restored = EquityResult::from_counts(
+            (0..3)
+                .map(|i| Ok(result.get_winlosses(i)?.0))
+                .collect::<Result<_, PokercraftLocalError>>()?,
+            vec![0; 3],
+        )?;
+        assert_eq!(restored.outcomes(), result.outcomes());
+        assert_eq!(PlayerOutcome::default().equity(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_equity() -> Result<(), PokercraftLocalError> {
+        let hands = [("As", "Ah"), ("Kd", "Kc"), ("7s", "8s")]
+            .map(|(c1, c2)| -> Result<Hand, PokercraftLocalError> {
+                Ok((c1.try_into()?, c2.try_into()?))
+            })
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let board = crate::card::parse_cards("2c 9h 6d")?;
+        let total = EquityResult::count_boards(&hands, &board)?;
+        assert_eq!(total, binomial(43, 2));
+
+        // Merging disjoint partitions in any order gives the full result
+        let full = EquityResult::new(hands.clone(), board.clone(), true)?;
+        let mut merged = EquityResult::new_partial(hands.clone(), board.clone(), 500, total)?;
+        for start in [400, 300, 200, 100, 0] {
+            let part = EquityResult::new_partial(hands.clone(), board.clone(), start, 100)?;
+            merged.merge(&part)?;
+        }
+        assert_eq!(merged.outcomes(), full.outcomes());
+        assert_eq!(
+            EquityResult::new_partial(hands.clone(), board.clone(), total, 10)?
+                .outcome(0)?
+                .total(),
+            0
+        );
+
+        let heads_up = EquityResult::new_partial(hands[..2].to_vec(), board, 0, 1)?;
+        assert!(merged.merge(&heads_up).is_err());
+        assert!(EquityResult::count_boards(&[], &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_equity_evolution() -> Result<(), PokercraftLocalError> {
+        let hands = vec![
+            (Card::try_from("As")?, Card::try_from("Ah")?),
+            (Card::try_from("Kd")?, Card::try_from("Kc")?),
+        ];
+        // Kings hit a set on the turn
+        let runout = crate::card::parse_cards("2c 7d 9h Ks 3s")?;
+        let evolution = EquityEvolution::new(hands.clone(), &runout, true)?;
+        let expected = [0, 3, 4]
+            .map(|street| EquityResult::new(hands.clone(), runout[..street].to_vec(), true));
+        for (equities, result) in [&evolution.preflop, &evolution.flop, &evolution.turn]
+            .into_iter()
+            .zip(expected)
+        {
+            let result = result?;
+            assert_eq!(
+                *equities,
+                vec![result.get_equity(0)?, result.get_equity(1)?]
+            );
+        }
+        assert!(evolution.preflop[0] > 0.8 && evolution.flop[0] > evolution.preflop[0]);
+        // Two aces left to hit on the river
+        assert!((evolution.turn[0] - 2.0 / 44.0).abs() < 1e-9);
+        assert_eq!(evolution.river, vec![0.0, 1.0]);
+        assert_eq!(evolution.player(1)?[3], 1.0);
+        assert!(evolution.player(2).is_err());
+
+        assert!(EquityEvolution::new(hands.clone(), &runout[..4], true).is_err());
+        let duplicated = crate::card::parse_cards("2c 7d 9h Ks As")?;
+        assert!(EquityEvolution::new(hands, &duplicated, true).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_heads_up_calculation() -> Result<(), PokercraftLocalError> {
+        let spots: Vec<(Vec<Hand>, Vec<Card>)> = vec![
+            (
+                vec![
+                    ("As".try_into()?, "Ah".try_into()?),
+                    ("Kd".try_into()?, "Kc".try_into()?),
+                ],
+                vec!["2s".try_into()?, "7h".try_into()?, "9c".try_into()?],
+            ),
+            (
+                vec![
+                    ("As".try_into()?, "Ks".try_into()?),
+                    ("Qh".try_into()?, "Jh".try_into()?),
+                ],
+                vec!["2d".try_into()?, "3c".try_into()?, "4d".try_into()?],
+            ),
+            (
+                vec![
+                    ("5c".try_into()?, "5d".try_into()?),
+                    ("Ah".try_into()?, "2s".try_into()?),
+                ],
+                vec![
+                    "3h".try_into()?,
+                    "4s".try_into()?,
+                    "Kd".try_into()?,
+                    "Kc".try_into()?,
+                ],
+            ),
+        ];
+        for (hands, community) in spots {
+            let remaining_cards = Card::all()
+                .into_iter()
+                .filter(|card| {
+                    !hands.iter().any(|(c1, c2)| card == c1 || card == c2)
+                        && !community.contains(card)
+                })
+                .collect::<Vec<_>>();
+            let progress = Progress::new(1, &|_, _| true);
+            let expected = EquityResult::enumerate_boards(
+                &hands,
+                &community,
+                &remaining_cards,
+                false,
+                &progress,
+            )?;
+            for parallel_calculation in [false, true] {
+                let result =
+                    EquityResult::new(hands.clone(), community.clone(), parallel_calculation)?;
+                assert_eq!(result.outcomes, expected.outcomes);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_equity_cache() -> Result<(), PokercraftLocalError> {
+        let hand = |a: &str, b: &str| -> Result<Hand, PokercraftLocalError> {
+            Ok((a.try_into()?, b.try_into()?))
+        };
+        let flop = |cards: [&str; 3]| {
+            cards
+                .into_iter()
+                .map(Card::try_from)
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let cache = EquityCache::new(2);
+        let spot1 = vec![hand("As", "Ks")?, hand("Qh", "Qd")?];
+        let result = cache.get_or_calculate(spot1.clone(), flop(["2s", "7c", "9h"])?, false)?;
+        assert_eq!(cache.len(), 1);
+
+        // Suit-isomorphic spot with different card orders hits the cache
+        let isomorphic = vec![hand("Kh", "Ah")?, hand("Qs", "Qd")?];
+        let cached = cache
+            .get(&isomorphic, &flop(["9s", "2h", "7c"])?)
+            .expect("Isomorphic spot should be cached");
+        assert_eq!(cached.get_winlosses(0)?, result.get_winlosses(0)?);
+        // Swapping players is a different spot
+        assert!(cache
+            .get(&[spot1[1], spot1[0]], &flop(["2s", "7c", "9h"])?)
+            .is_none());
+
+        // The least recently used spot is evicted
+        let spot2 = vec![hand("2c", "2d")?, hand("Ac", "Kd")?];
+        let spot3 = vec![hand("Jc", "Td")?, hand("8c", "8d")?];
+        cache.get_or_calculate(spot2.clone(), flop(["2s", "7c", "9h"])?, false)?;
+        assert!(cache.get(&spot1, &flop(["2s", "7c", "9h"])?).is_some());
+        cache.get_or_calculate(spot3, flop(["2s", "7c", "9h"])?, false)?;
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&spot1, &flop(["2s", "7c", "9h"])?).is_some());
+        assert!(cache.get(&spot2, &flop(["2s", "7c", "9h"])?).is_none());
+
+        cache.clear();
+        assert!(cache.is_empty());
+        Ok(())
+    }
+
+    #[cfg(feature = "embedded-tables")]
+    #[test]
+    fn test_embedded_preflop_cache() -> Result<(), PokercraftLocalError> {
+        let cache = HUPreflopEquityCache::embedded()?;
+        let (aces, kings) = (
+            ("As".try_into()?, "Ad".try_into()?),
+            ("Ks".try_into()?, "Kd".try_into()?),
+        );
+        let (win1, win2, tie) = cache.get_winlose(aces, kings)?;
+        assert_eq!(win1 + win2 + tie, 1712304);
+        assert_eq!(cache.get_winlose(kings, aces)?, (win2, win1, tie));
+
+        // Heads-up preflop results come from the cache
+        let breakdown = EquityResult::new(vec![aces, kings], vec![], false)?.breakdown()?;
+        assert_eq!(breakdown.wins, vec![vec![win1, tie], vec![win2, tie]]);
+        assert_almost_equal(breakdown.equities[0], 0.8236 + 0.0054 / 2.0);
+        Ok(())
+    }
+
     fn assert_almost_equal(actual: f64, expected: f64) {
         assert!(
             (actual - expected).abs() < 1e-4,
@@ -844,6 +3151,236 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_range_equity() -> Result<(), PokercraftLocalError> {
+        let board: Vec<Card> = vec![
+            "2c".try_into()?,
+            "7d".try_into()?,
+            "9h".try_into()?,
+            "4s".try_into()?,
+        ];
+
+        let exact = RangeEquityResult::new(
+            &[HandRange::try_from("AsAh")?, HandRange::try_from("KdKc")?],
+            &board,
+            0,
+        )?;
+        assert!(exact.is_exact());
+        let aces = exact.outcome(0)?;
+        assert_eq!((aces.wins, aces.ties, aces.losses), (42, 0, 2));
+        assert!((aces.equity() - 42.0 / 44.0).abs() < 1e-9);
+        assert_eq!(aces.categories.iter().sum::<u64>(), 44);
+        assert_eq!(aces.categories[3], 2);
+        let dead = RangeEquityResult::new_with_dead_cards(
+            &[HandRange::try_from("AsAh")?, HandRange::try_from("KdKc")?],
+            &board,
+            &["Ks".try_into()?],
+            0,
+        )?;
+        assert_eq!(dead.outcome(0)?.losses, 1);
+        assert_eq!(dead.outcome(0)?.total(), 43);
+
+        let sampled = RangeEquityResult::new(
+            &[HandRange::try_from("AA")?, HandRange::try_from("KK")?],
+            &board,
+            20000,
+        )?;
+        assert!(!sampled.is_exact());
+        let aces = sampled.outcome(0)?;
+        assert_eq!(aces.total(), 20000);
+        assert!((aces.equity() - 42.0 / 44.0).abs() < 0.01);
+        assert!((aces.equity() + sampled.outcome(1)?.equity() - 1.0).abs() < 1e-9);
+        assert!(sampled.outcome(2).is_err());
+
+        assert!(RangeEquityResult::new(&[HandRange::try_from("2c2d")?], &board, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_equity_duplicated_cards() -> Result<(), PokercraftLocalError> {
+        let ranges = [HandRange::try_from("AsKs")?, HandRange::try_from("QQ")?];
+        let board = parse_cards("2c2c2d")?;
+        let error = RangeEquityResult::new(&ranges, &board, 1000).unwrap_err();
+        assert!(error.to_string().contains("Duplicated card 2c"));
+        let error = RangeEquityResult::new_with_dead_cards(
+            &ranges,
+            &parse_cards("2c7d9h")?,
+            &parse_cards("9h")?,
+            1000,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("Duplicated card 9h"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_equity_batch() -> Result<(), PokercraftLocalError> {
+        let hand = |c1: &str, c2: &str| -> Result<Hand, PokercraftLocalError> {
+            Ok((c1.try_into()?, c2.try_into()?))
+        };
+        let spots = vec![
+            Spot {
+                hands: vec![hand("As", "Ah")?, hand("Kd", "Kc")?],
+                board: vec!["2c".try_into()?, "7d".try_into()?, "9h".try_into()?],
+            },
+            Spot {
+                hands: vec![hand("Qs", "Qh")?, hand("Jd", "Tc")?, hand("8s", "8h")?],
+                board: vec!["2d".try_into()?, "9c".try_into()?, "Kh".try_into()?],
+            },
+        ];
+        let results = equity_batch(spots.clone())?;
+        assert_eq!(results.len(), 2);
+        for (spot, result) in spots.into_iter().zip(results.iter()) {
+            let expected = EquityResult::new(spot.hands, spot.board, false)?;
+            for i in 0..result.num_players() {
+                assert!((result.get_equity(i)? - expected.get_equity(i)?).abs() < 1e-12);
+            }
+        }
+
+        let invalid = Spot {
+            hands: vec![hand("As", "Ah")?],
+            board: vec!["2c".try_into()?; 6],
+        };
+        let error = equity_batch(vec![invalid]).unwrap_err();
+        assert!(error.to_string().contains("Spot 0"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_double_board_equity() -> Result<(), PokercraftLocalError> {
+        let hands = [
+            ("As".try_into()?, "Ah".try_into()?),
+            ("Kd".try_into()?, "Kc".try_into()?),
+        ];
+        // Aces win the first board, and kings need a king on the river of the second board
+        let first = crate::card::parse_cards("2c 7d 9h 4s 3h")?;
+        let second = crate::card::parse_cards("2d 8d Ts 5c")?;
+        let exact = DoubleBoardEquityResult::new(&hands, [&first, &second], 100)?;
+        assert!(exact.is_exact());
+        let (aces, kings) = (exact.outcome(0)?, exact.outcome(1)?);
+        assert_eq!(aces.total(), 39);
+        assert_eq!((aces.scoops, aces.splits, aces.losses), (37, 2, 0));
+        assert_eq!((kings.scoops, kings.splits, kings.losses), (0, 2, 37));
+        assert!((aces.equity() - (0.5 + 0.5 * 37.0 / 39.0)).abs() < 1e-9);
+        assert!((aces.equity() + kings.equity() - 1.0).abs() < 1e-9);
+        assert!((aces.scoop_rate() - 37.0 / 39.0).abs() < 1e-9);
+        assert!((kings.split_rate() - 2.0 / 39.0).abs() < 1e-9);
+        assert!((kings.lose_rate() - 37.0 / 39.0).abs() < 1e-9);
+
+        let sampled = DoubleBoardEquityResult::new(&hands, [&[], &[]], 5000)?;
+        assert!(!sampled.is_exact());
+        assert_eq!(sampled.num_players(), 2);
+        let aces = sampled.outcome(0)?;
+        assert_eq!(aces.total(), 5000);
+        assert!((aces.equity() - 0.82).abs() < 0.03);
+        assert!(aces.scoop_rate() > 0.6);
+        assert!(sampled.outcome(2).is_err());
+
+        assert!(DoubleBoardEquityResult::new(&hands[..1], [&first, &second], 100).is_err());
+        assert!(DoubleBoardEquityResult::new(&hands, [&first, &first], 100).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_stud_equity() -> Result<(), PokercraftLocalError> {
+        let cards = |value: &str| crate::card::parse_cards(value);
+        // Trip aces against a made flush on 6th street, with a dead ace
+        let players = [
+            StudHand {
+                down: cards("As Ah")?,
+                up: cards("Ad 2c 7h 9s")?,
+            },
+            StudHand {
+                down: cards("Kh Qh")?,
+                up: cards("3h 5h 8h Jc")?,
+            },
+        ];
+        let exact = StudEquityResult::new(&players, &cards("Ac")?, 2000)?;
+        assert!(exact.is_exact());
+        let trips = exact.outcome(0)?;
+        assert_eq!(trips.total(), 39 * 38);
+        // Trips fill up with any of 2, 7 or 9 left (9 cards), where the flush is never full
+        assert_eq!(trips.wins, 9 * 38);
+        assert_eq!(trips.categories[6], 9 * 38);
+        assert!((trips.equity() + exact.outcome(1)?.equity() - 1.0).abs() < 1e-9);
+
+        // Unknown down cards of the opponent are dealt at random
+        let players = [
+            StudHand {
+                down: cards("As Ah")?,
+                up: cards("Ad")?,
+            },
+            StudHand {
+                down: vec![],
+                up: cards("Kc")?,
+            },
+        ];
+        let sampled = StudEquityResult::new(&players, &[], 5000)?;
+        assert!(!sampled.is_exact());
+        assert_eq!(sampled.num_players(), 2);
+        assert_eq!(sampled.outcome(0)?.total(), 5000);
+        assert!(sampled.outcome(0)?.equity() > 0.75);
+        assert!(sampled.outcome(2).is_err());
+
+        let too_many = StudHand {
+            down: cards("2c 3c 4c 5c")?,
+            up: vec![],
+        };
+        assert!(StudEquityResult::new(&[too_many, players[1].clone()], &[], 100).is_err());
+        assert!(StudEquityResult::new(&players, &cards("Kc")?, 100).is_err());
+        assert!(StudEquityResult::new(&players[..1], &[], 100).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hand_vs_range() -> Result<(), PokercraftLocalError> {
+        use crate::card::CardNumber;
+
+        let board = crate::card::parse_cards("2c 7d 9h 4s")?;
+        let hand = ("As".try_into()?, "Ah".try_into()?);
+        let mut range: Vec<(Hand, f64)> = HandRange::try_from("KK")?
+            .combos()
+            .iter()
+            .map(|combo| (*combo, 1.0))
+            .collect();
+        range.extend(
+            HandRange::try_from("77,AKs")?
+                .combos()
+                .iter()
+                .map(|combo| (*combo, 2.0)),
+        );
+
+        // Sets of sevens leave 2 outs, and AsKs and AhKh are blocked
+        let result = HandVsRangeResult::new(hand, &range, &board, 1000)?;
+        assert_eq!(result.combos().len(), 6 + 3 + 2);
+        let equity_against = |result: &HandVsRangeResult, number: CardNumber| {
+            result
+                .combos()
+                .iter()
+                .find(|((c1, _), _, _)| c1.number == number)
+                .map(|(_, _, equity)| *equity)
+                .unwrap()
+        };
+        assert!((equity_against(&result, CardNumber::Seven) - 2.0 / 44.0).abs() < 1e-9);
+        assert!((equity_against(&result, CardNumber::King) - 42.0 / 44.0).abs() < 1e-9);
+        assert_eq!(equity_against(&result, CardNumber::Ace), 1.0);
+        let expected = (6.0 * 42.0 + 6.0 * 2.0 + 4.0 * 44.0) / (16.0 * 44.0);
+        assert!((result.equity() - expected).abs() < 1e-9);
+
+        // Only sets continue as the strongest 6 of 16 weights
+        let continuing = result.continuing(6.0 / 16.0, 1000)?;
+        assert_eq!(continuing.combos().len(), 3);
+        assert!((continuing.equity() - 2.0 / 44.0).abs() < 1e-9);
+        assert!(result.continuing(0.0, 1000).is_err());
+
+        // Aces against kings preflop
+        let sampled = HandVsRangeResult::new(hand, &range, &[], 1000)?;
+        assert!((equity_against(&sampled, CardNumber::King) - 0.82).abs() < 0.06);
+        assert!(HandVsRangeResult::new(hand, &[(hand, 1.0)], &board, 100).is_err());
+        assert!(HandVsRangeResult::new(hand, &[(range[0].0, -1.0)], &board, 100).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_tails() -> Result<(), PokercraftLocalError> {
         let mut luck_calc = LuckCalculator::new();
@@ -875,4 +3412,87 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_pmf() -> Result<(), PokercraftLocalError> {
+        let mut luck_calc = LuckCalculator::new();
+        luck_calc.add_result(0.2, 1.0)?;
+        luck_calc.add_result(0.5, 0.0)?;
+        luck_calc.add_result(0.8, 1.0)?;
+
+        // Tails are sums of the distribution around the observed wins
+        let (upper, _, _) = luck_calc.tails().unwrap();
+        let distribution = luck_calc.pmf();
+        assert_eq!(distribution.pmf.len(), 4);
+        assert_eq!(distribution.observed_wins, 2.0);
+        assert_almost_equal(distribution.pmf[0], 0.8 * 0.5 * 0.2);
+        assert_almost_equal(distribution.pmf[3], 0.2 * 0.5 * 0.8);
+        assert_almost_equal(distribution.pmf[2..].iter().sum(), upper);
+        assert_eq!(LuckCalculator::new().pmf().pmf, vec![1.0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_grouped_luck() -> Result<(), PokercraftLocalError> {
+        let mut luck_calc = LuckCalculator::new();
+        luck_calc.add_grouped_result("2025-01", 0.2, 1.0)?;
+        luck_calc.add_grouped_result("2025-02", 0.8, 0.0)?;
+        luck_calc.add_grouped_result("2025-01", 0.5, 0.0)?;
+        luck_calc.add_result(0.5, 1.0)?;
+        assert_eq!(luck_calc.groups(), vec!["2025-01", "2025-02"]);
+        assert!(luck_calc.group("2025-03").is_none());
+
+        // Groups have the same statistics as separate calculators
+        let january = luck_calc.group("2025-01").unwrap();
+        let (upper, lower, _) = january.tails().unwrap();
+        assert_almost_equal(upper, 0.6);
+        assert_almost_equal(lower, 0.9);
+        assert_almost_equal(january.luck_score().unwrap(), 1.2816);
+
+        // Losing with 80% equity is the unluckiest
+        let stats = luck_calc.group_stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].group, "2025-02");
+        assert_eq!(stats[0].results, 1);
+        assert_almost_equal(stats[0].expected_wins, 0.8);
+        assert_eq!(stats[0].actual_wins, 0.0);
+        assert_almost_equal(stats[0].tails.unwrap().1, 0.2);
+        assert_eq!(stats[1].group, "2025-01");
+        assert_almost_equal(stats[1].expected_wins, 0.7);
+
+        // Every result counts for overall statistics; Pr(W >= 2) = 1 - 0.04 - 0.25
+        assert_almost_equal(luck_calc.tails().unwrap().0, 0.71);
+        assert!(luck_calc.add_grouped_result("2025-02", 1.5, 1.0).is_err());
+        assert_eq!(luck_calc.group("2025-02").unwrap().results.len(), 1);
+
+        // Removing results also removes them from groups
+        assert_eq!(luck_calc.pop(), Some((0.5, 1.0)));
+        luck_calc.truncate(2);
+        assert_eq!(
+            luck_calc.group("2025-01").unwrap().results,
+            vec![(0.2, 1.0)]
+        );
+        luck_calc.truncate(1);
+        assert_eq!(luck_calc.groups(), vec!["2025-01"]);
+        assert_eq!(luck_calc.pop(), Some((0.2, 1.0)));
+        assert_eq!(luck_calc.pop(), None);
+        assert!(luck_calc.groups().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bulk_luck_results() -> Result<(), PokercraftLocalError> {
+        let mut luck_calc = LuckCalculator::new();
+        luck_calc.add_results([(0.2, 1.0), (0.5, 0.0), (0.8, 1.0)])?;
+        let (upper, lower, _) = luck_calc.tails().unwrap();
+        assert_almost_equal(upper, 0.5);
+        assert_almost_equal(lower, 0.92);
+
+        // Invalid results are never partially added
+        assert!(luck_calc.add_results([(0.5, 1.0), (0.0, 1.0)]).is_err());
+        assert_eq!(luck_calc.results.len(), 3);
+        luck_calc.truncate(10);
+        assert_eq!(luck_calc.results.len(), 3);
+        Ok(())
+    }
 }
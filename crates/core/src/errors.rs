@@ -7,6 +7,9 @@ pub enum PokercraftLocalError {
     GeneralError(String),
     #[error("IO Error: {0}")]
     IoError(std::io::Error),
+    /// A long computation was cancelled by its progress callback.
+    #[error("Cancelled")]
+    Cancelled,
 }
 
 impl From<std::io::Error> for PokercraftLocalError {
@@ -0,0 +1,250 @@
+//! Expected values of small decision trees, for checking lines without a full solver.
+//!
+//! Amounts are measured from the start of the tree; `pot` of a leaf is the whole pot,
+//! including chips already in the pot at the start, and `hero_invested` is
+//! the chips the hero put in since the start. So EV of a leaf is
+//! the hero's share of the pot minus `hero_invested`.
+
+use crate::card::Card;
+use crate::equity::RangeEquityResult;
+use crate::errors::PokercraftLocalError;
+use crate::range::HandRange;
+
+/// Equity of the hero at a showdown leaf.
+#[derive(Clone, Debug)]
+pub enum LeafEquity {
+    /// Known equity, like from a separate calculation.
+    Fixed(f64),
+    /// Equity of the hero range against the villain range on the board,
+    /// with Monte Carlo `trials` unless both ranges are single combos.
+    Ranges {
+        hero: HandRange,
+        villain: HandRange,
+        board: Vec<Card>,
+        trials: u64,
+    },
+}
+
+impl LeafEquity {
+    /// Get the equity of the hero.
+    pub fn equity(&self) -> Result<f64, PokercraftLocalError> {
+        match self {
+            LeafEquity::Fixed(equity) if (0.0..=1.0).contains(equity) => Ok(*equity),
+            LeafEquity::Fixed(equity) => Err(PokercraftLocalError::GeneralError(format!(
+                "Equity should be in [0, 1], got {}",
+                equity
+            ))),
+            LeafEquity::Ranges {
+                hero,
+                villain,
+                board,
+                trials,
+            } => RangeEquityResult::new(&[hero.clone(), villain.clone()], board, *trials)?
+                .outcome(0)
+                .map(|outcome| outcome.equity()),
+        }
+    }
+}
+
+/// A node of a decision tree between the hero and a single villain.
+#[derive(Clone, Debug)]
+pub enum DecisionNode {
+    /// The hero takes the action with the highest EV.
+    Hero(Vec<(String, DecisionNode)>),
+    /// The villain takes each action with the given frequency,
+    /// like the share of the villain's range taking that action.
+    /// Frequencies should sum up to 1.
+    Villain(Vec<(String, f64, DecisionNode)>),
+    /// Someone folds; The hero wins the pot unless `hero_folds`.
+    Fold {
+        pot: f64,
+        hero_invested: f64,
+        hero_folds: bool,
+    },
+    /// Showdown of the pot.
+    Showdown {
+        pot: f64,
+        hero_invested: f64,
+        equity: LeafEquity,
+    },
+}
+
+/// EV of a node, with EVs of all actions from it.
+#[derive(PartialEq, Clone, Debug)]
+pub struct EvResult {
+    pub ev: f64,
+    /// Whether the hero chooses an action here.
+    pub hero_to_act: bool,
+    /// Evaluated actions, in the order of the tree; Empty on leaves.
+    pub actions: Vec<(String, EvResult)>,
+}
+
+impl EvResult {
+    /// Get the name and the result of the best action of the hero,
+    /// or `None` if the hero does not act here.
+    pub fn best_action(&self) -> Option<(&str, &EvResult)> {
+        if !self.hero_to_act {
+            return None;
+        }
+        self.actions
+            .iter()
+            .max_by(|(_, a), (_, b)| a.ev.total_cmp(&b.ev))
+            .map(|(name, result)| (name.as_str(), result))
+    }
+}
+
+impl DecisionNode {
+    /// Evaluate EVs of this node and all nodes below it.
+    pub fn evaluate(&self) -> Result<EvResult, PokercraftLocalError> {
+        let leaf = |ev: f64| EvResult {
+            ev,
+            hero_to_act: false,
+            actions: Vec::new(),
+        };
+        match self {
+            DecisionNode::Hero(actions) => {
+                let actions = actions
+                    .iter()
+                    .map(|(name, node)| Ok((name.clone(), node.evaluate()?)))
+                    .collect::<Result<Vec<_>, PokercraftLocalError>>()?;
+                let ev = actions
+                    .iter()
+                    .map(|(_, result)| result.ev)
+                    .max_by(f64::total_cmp)
+                    .ok_or_else(|| {
+                        PokercraftLocalError::GeneralError(
+                            "Hero node should have at least 1 action".to_string(),
+                        )
+                    })?;
+                Ok(EvResult {
+                    ev,
+                    hero_to_act: true,
+                    actions,
+                })
+            }
+            DecisionNode::Villain(actions) => {
+                let total = actions
+                    .iter()
+                    .map(|(_, frequency, _)| frequency)
+                    .sum::<f64>();
+                if actions.iter().any(|(_, frequency, _)| *frequency < 0.0)
+                    || (total - 1.0).abs() > 1e-6
+                {
+                    return Err(PokercraftLocalError::GeneralError(format!(
+                        "Villain frequencies should be non-negative and sum up to 1, got {}",
+                        total
+                    )));
+                }
+                let mut ev = 0.0;
+                let mut results = Vec::with_capacity(actions.len());
+                for (name, frequency, node) in actions.iter() {
+                    let result = node.evaluate()?;
+                    ev += frequency * result.ev;
+                    results.push((name.clone(), result));
+                }
+                Ok(EvResult {
+                    ev,
+                    hero_to_act: false,
+                    actions: results,
+                })
+            }
+            DecisionNode::Fold {
+                pot,
+                hero_invested,
+                hero_folds,
+            } => Ok(leaf(if *hero_folds {
+                -hero_invested
+            } else {
+                pot - hero_invested
+            })),
+            DecisionNode::Showdown {
+                pot,
+                hero_invested,
+                equity,
+            } => Ok(leaf(equity.equity()? * pot - hero_invested)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+
+    #[test]
+    fn test_decision_tree() -> Result<(), PokercraftLocalError> {
+        // Betting 50 into 100 with 60% equity, called by 40% of the villain's range
+        let tree = DecisionNode::Hero(vec![
+            (
+                "Check".to_string(),
+                DecisionNode::Showdown {
+                    pot: 100.0,
+                    hero_invested: 0.0,
+                    equity: LeafEquity::Fixed(0.6),
+                },
+            ),
+            (
+                "Bet 50".to_string(),
+                DecisionNode::Villain(vec![
+                    (
+                        "Call".to_string(),
+                        0.4,
+                        DecisionNode::Showdown {
+                            pot: 200.0,
+                            hero_invested: 50.0,
+                            equity: LeafEquity::Fixed(0.6),
+                        },
+                    ),
+                    (
+                        "Fold".to_string(),
+                        0.6,
+                        DecisionNode::Fold {
+                            pot: 150.0,
+                            hero_invested: 50.0,
+                            hero_folds: false,
+                        },
+                    ),
+                ]),
+            ),
+        ]);
+        let result = tree.evaluate()?;
+        assert!((result.actions[0].1.ev - 60.0).abs() < 1e-9);
+        assert!((result.actions[1].1.ev - (0.4 * 70.0 + 0.6 * 100.0)).abs() < 1e-9);
+        assert_eq!(result.ev, result.actions[1].1.ev);
+        assert_eq!(result.best_action().unwrap().0, "Bet 50");
+        assert_eq!(result.actions[1].1.best_action(), None);
+
+        // Aces against kings on a dry river board
+        let showdown = DecisionNode::Showdown {
+            pot: 100.0,
+            hero_invested: 50.0,
+            equity: LeafEquity::Ranges {
+                hero: HandRange::try_from("AsAh")?,
+                villain: HandRange::try_from("KdKc")?,
+                board: parse_cards("2c 7d 9h Js 3s")?,
+                trials: 0,
+            },
+        };
+        assert_eq!(showdown.evaluate()?.ev, 50.0);
+        let folding = DecisionNode::Fold {
+            pot: 100.0,
+            hero_invested: 20.0,
+            hero_folds: true,
+        };
+        assert_eq!(folding.evaluate()?.ev, -20.0);
+
+        assert!(DecisionNode::Hero(vec![]).evaluate().is_err());
+        assert!(
+            DecisionNode::Villain(vec![("Call".to_string(), 0.5, folding)])
+                .evaluate()
+                .is_err()
+        );
+        let invalid = DecisionNode::Showdown {
+            pot: 100.0,
+            hero_invested: 0.0,
+            equity: LeafEquity::Fixed(1.5),
+        };
+        assert!(invalid.evaluate().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,551 @@
+//! Batch evaluation of many 7-card boards at once.
+//!
+//! Each board is evaluated with bit masks of card numbers per shape,
+//! without branches depending on the cards, so multiple boards are evaluated
+//! in lockstep lanes. With the `simd` feature, lanes are portable SIMD vectors.
+//!
+//! Boards of 5 and 6 cards are looked up from `RankTables` instead
+//! when the tables are embedded with the `embedded-tables` feature.
+
+use std::io::{Read, Write};
+use std::ops::{BitAnd, BitOr, Not, Shl, Shr};
+#[cfg(feature = "simd")]
+use std::simd::{num::SimdUint, Simd};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use itertools::Itertools;
+#[cfg(feature = "embedded-tables")]
+use once_cell::sync::OnceCell;
+use rayon::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::card::{Card, RankKey, NUM_OF_NUMBERS, NUM_OF_SHAPES};
+use crate::errors::PokercraftLocalError;
+use crate::utils::in_thread_pool;
+
+/// Number of boards evaluated sequentially in each parallel chunk of `evaluate_many`.
+const BOARDS_PER_CHUNK: usize = 1 << 14;
+
+/// Number of boards evaluated per SIMD instruction.
+#[cfg(feature = "simd")]
+const LANES: usize = 8;
+
+/// Lanes of `u32`s evaluated in lockstep.
+/// Masks are lanes with all bits set for true and no bits set for false.
+trait Lanes:
+    Copy
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + Not<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    /// Create lanes having the same value.
+    fn splat(value: u32) -> Self;
+
+    /// Mask of lanes which are not zero.
+    fn nonzero(self) -> Self;
+
+    /// Mask of lanes having at least `n` bits set.
+    fn at_least_bits(self, n: u32) -> Self;
+
+    /// Keep only the highest set bit of each lane.
+    fn highest_bit(self) -> Self;
+}
+
+impl Lanes for u32 {
+    fn splat(value: u32) -> Self {
+        value
+    }
+
+    fn nonzero(self) -> Self {
+        if self != 0 {
+            !0
+        } else {
+            0
+        }
+    }
+
+    fn at_least_bits(self, n: u32) -> Self {
+        if self.count_ones() >= n {
+            !0
+        } else {
+            0
+        }
+    }
+
+    fn highest_bit(self) -> Self {
+        if self != 0 {
+            1 << (31 - self.leading_zeros())
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(feature = "simd")]
+impl Lanes for Simd<u32, LANES> {
+    fn splat(value: u32) -> Self {
+        Simd::splat(value)
+    }
+
+    fn nonzero(self) -> Self {
+        // The sign bit of `x | -x` is set only if `x` is not zero
+        Simd::splat(0) - ((self | (Simd::splat(0) - self)) >> 31)
+    }
+
+    fn at_least_bits(self, n: u32) -> Self {
+        // `count + 32 - n` reaches 32 only if `count >= n`
+        Simd::splat(0) - ((self.count_ones() + Simd::splat(32 - n)) >> 5)
+    }
+
+    fn highest_bit(self) -> Self {
+        // Shifting by 32 wraps around, which is masked out by `nonzero`
+        (Simd::splat(1 << 31) >> self.leading_zeros()) & self.nonzero()
+    }
+}
+
+/// Pick `a` in lanes where `mask` is set, otherwise `b`.
+fn select<L: Lanes>(mask: L, a: L, b: L) -> L {
+    (a & mask) | (b & !mask)
+}
+
+/// Keep only the highest `K` set bits of each lane.
+fn highest_bits<L: Lanes, const K: usize>(mut numbers: L) -> L {
+    let mut result = L::splat(0);
+    for _ in 0..K {
+        let highest = numbers.highest_bit();
+        result = result | highest;
+        numbers = numbers & !highest;
+    }
+    result
+}
+
+/// Get the highest card number of straights of each lane as a mask.
+fn straight_high<L: Lanes>(numbers: L) -> L {
+    // Ace also counts as the lowest number, at bit 0
+    let numbers = numbers | ((numbers >> 13) & L::splat(1));
+    (numbers & (numbers << 1) & (numbers << 2) & (numbers << 3) & (numbers << 4)).highest_bit()
+}
+
+/// Create a key of each lane from the category, primary and kicker masks.
+fn key<L: Lanes>(category: u32, primary: L, kickers: L) -> L {
+    L::splat(category << RankKey::CATEGORY_SHIFT) | (primary << RankKey::PRIMARY_SHIFT) | kickers
+}
+
+/// Evaluate the rank keys of the given card number masks per shape.
+fn evaluate_lanes<L: Lanes>([s0, s1, s2, s3]: [L; NUM_OF_SHAPES]) -> L {
+    // Card numbers appearing at least 1, 2, 3 and 4 times
+    let any = s0 | s1 | s2 | s3;
+    let two = (s0 & s1) | (s0 & s2) | (s0 & s3) | (s1 & s2) | (s1 & s3) | (s2 & s3);
+    let three = (s0 & s1 & s2) | (s0 & s1 & s3) | (s0 & s2 & s3) | (s1 & s2 & s3);
+    let four = s0 & s1 & s2 & s3;
+
+    // At most one shape has 5 or more cards among 7 cards
+    let flush = (s0 & s0.at_least_bits(5))
+        | (s1 & s1.at_least_bits(5))
+        | (s2 & s2.at_least_bits(5))
+        | (s3 & s3.at_least_bits(5));
+    let straight_flush = straight_high(flush);
+    let straight = straight_high(any);
+    let triple = three.highest_bit();
+    let full_house_pair = (two & !triple).highest_bit();
+    let two_pairs = highest_bits::<L, 2>(two);
+
+    // Override from the lowest category to the highest one
+    let mut result = key(0, L::splat(0), highest_bits::<L, 5>(any));
+    result = select(
+        two.nonzero(),
+        key(1, two, highest_bits::<L, 3>(any & !two)),
+        result,
+    );
+    result = select(
+        two.at_least_bits(2),
+        key(2, two_pairs, (any & !two_pairs).highest_bit()),
+        result,
+    );
+    result = select(
+        three.nonzero(),
+        key(3, triple, highest_bits::<L, 2>(any & !triple)),
+        result,
+    );
+    result = select(straight.nonzero(), key(4, L::splat(0), straight), result);
+    result = select(
+        flush.nonzero(),
+        key(5, L::splat(0), highest_bits::<L, 5>(flush)),
+        result,
+    );
+    result = select(
+        triple.nonzero() & full_house_pair.nonzero(),
+        key(6, triple, full_house_pair),
+        result,
+    );
+    result = select(
+        four.nonzero(),
+        key(7, four, (any & !four).highest_bit()),
+        result,
+    );
+    select(
+        straight_flush.nonzero(),
+        key(8, L::splat(0), straight_flush),
+        result,
+    )
+}
+
+/// Get the card number masks per shape of the given cards.
+pub(crate) fn shape_masks(cards: &[Card]) -> [u32; NUM_OF_SHAPES] {
+    let mut masks = [0; NUM_OF_SHAPES];
+    for card in cards.iter() {
+        masks[card.shape as usize] |= RankKey::mask([card.number]);
+    }
+    masks
+}
+
+/// Evaluate the best 5-card hand rank of 7 cards, given as card number masks per shape.
+pub(crate) fn evaluate_shape_masks(masks: [u32; NUM_OF_SHAPES]) -> RankKey {
+    RankKey(evaluate_lanes(masks))
+}
+
+/// Primes of card numbers from two to ace,
+/// whose products identify multisets of card numbers.
+const NUMBER_PRIMES: [u32; NUM_OF_NUMBERS] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Number of 13-bit masks of card numbers from two to ace.
+const NUMBER_MASKS: usize = 1 << NUM_OF_NUMBERS;
+
+/// Lookup tables of 5-card hand ranks, like Cactus Kev's evaluator.
+/// Flushes and hands of 5 distinct numbers are indexed by 13-bit masks of card numbers,
+/// and other hands are searched by products of primes of card numbers.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct RankTables {
+    /// Keys of flushes by masks of numbers; Zero for masks without exactly 5 bits.
+    flushes: Vec<RankKey>,
+    /// Keys of 5 distinct numbers not in a single shape by masks of numbers;
+    /// Zero for masks without exactly 5 bits.
+    uniques: Vec<RankKey>,
+    /// `(product of number primes, key)` of hands with duplicate numbers, sorted by products.
+    products: Vec<(u32, RankKey)>,
+}
+
+impl RankTables {
+    /// Generate all tables by evaluating every 5-card hand class.
+    pub fn generate() -> Self {
+        let mut flushes = vec![RankKey(0); NUMBER_MASKS];
+        let mut uniques = vec![RankKey(0); NUMBER_MASKS];
+        for mask in 0..NUMBER_MASKS as u32 {
+            if mask.count_ones() != 5 {
+                continue;
+            }
+            // Shape masks have the number `n` at the bit `n - 1`
+            flushes[mask as usize] = evaluate_shape_masks([mask << 1, 0, 0, 0]);
+            let lowest = mask & mask.wrapping_neg();
+            uniques[mask as usize] =
+                evaluate_shape_masks([(mask & !lowest) << 1, lowest << 1, 0, 0]);
+        }
+
+        let mut products = Vec::new();
+        for numbers in (0..NUM_OF_NUMBERS).combinations_with_replacement(5) {
+            let counts = numbers.iter().dedup_with_count().collect::<Vec<_>>();
+            if counts.len() == 5 || counts.iter().any(|&(count, _)| count > NUM_OF_SHAPES) {
+                continue;
+            }
+            // The k-th copy of each number goes to the k-th shape
+            let mut masks = [0; NUM_OF_SHAPES];
+            for (count, &number) in counts {
+                for mask in masks.iter_mut().take(count) {
+                    *mask |= 1 << (number + 1);
+                }
+            }
+            let product = numbers
+                .iter()
+                .map(|&number| NUMBER_PRIMES[number])
+                .product();
+            products.push((product, evaluate_shape_masks(masks)));
+        }
+        products.sort_unstable();
+        RankTables {
+            flushes,
+            uniques,
+            products,
+        }
+    }
+
+    /// Get the tables embedded in the binary, which are decompressed on the first call.
+    #[cfg(feature = "embedded-tables")]
+    pub fn embedded() -> Result<&'static Self, PokercraftLocalError> {
+        static EMBEDDED_TABLES: &[u8] = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tables/rank_tables.bin"
+        ));
+        static TABLES: OnceCell<RankTables> = OnceCell::new();
+        TABLES.get_or_try_init(|| Self::from_gzip_bytes(EMBEDDED_TABLES))
+    }
+
+    /// Serialize into gzip-compressed bytes of little-endian `u32`s;
+    /// Flushes, uniques, the number of products, and `(product, key)` pairs.
+    pub fn to_gzip_bytes(&self) -> Result<Vec<u8>, PokercraftLocalError> {
+        let mut values = Vec::with_capacity(2 * NUMBER_MASKS + 1 + 2 * self.products.len());
+        values.extend(self.flushes.iter().map(|key| key.0));
+        values.extend(self.uniques.iter().map(|key| key.0));
+        values.push(self.products.len() as u32);
+        for &(product, key) in self.products.iter() {
+            values.extend([product, key.0]);
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        for value in values {
+            encoder.write_all(&value.to_le_bytes())?;
+        }
+        Ok(encoder.finish()?)
+    }
+
+    /// Deserialize from bytes of `to_gzip_bytes`.
+    pub fn from_gzip_bytes(bytes: &[u8]) -> Result<Self, PokercraftLocalError> {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+        let values = decompressed
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect::<Vec<_>>();
+        let invalid = || PokercraftLocalError::GeneralError("Invalid rank tables".to_string());
+        if !decompressed.len().is_multiple_of(4) || values.len() <= 2 * NUMBER_MASKS {
+            return Err(invalid());
+        }
+        let (masks, rest) = values.split_at(2 * NUMBER_MASKS);
+        let (flushes, uniques) = masks.split_at(NUMBER_MASKS);
+        let products = &rest[1..];
+        if products.len() != 2 * rest[0] as usize {
+            return Err(invalid());
+        }
+        Ok(RankTables {
+            flushes: flushes.iter().map(|&key| RankKey(key)).collect(),
+            uniques: uniques.iter().map(|&key| RankKey(key)).collect(),
+            products: products
+                .chunks_exact(2)
+                .map(|pair| (pair[0], RankKey(pair[1])))
+                .collect(),
+        })
+    }
+
+    /// Look up the hand rank of exactly 5 cards.
+    /// This does not check if there is any duplicate cards.
+    pub fn evaluate5(&self, cards: &[Card; 5]) -> RankKey {
+        let mask = cards
+            .iter()
+            .fold(0, |mask, card| mask | (1 << (card.number as u32 - 2)));
+        if cards.iter().all(|card| card.shape == cards[0].shape) {
+            self.flushes[mask]
+        } else if mask.count_ones() == 5 {
+            self.uniques[mask]
+        } else {
+            let product = cards
+                .iter()
+                .map(|card| NUMBER_PRIMES[card.number as usize - 2])
+                .product::<u32>();
+            match self
+                .products
+                .binary_search_by_key(&product, |&(product, _)| product)
+            {
+                Ok(i) => self.products[i].1,
+                // Only possible with duplicate cards
+                Err(_) => evaluate_shape_masks(shape_masks(cards)),
+            }
+        }
+    }
+
+    /// Look up the best 5-card hand rank of 5 or more cards.
+    pub fn evaluate_best(&self, cards: &[Card]) -> RankKey {
+        cards
+            .iter()
+            .copied()
+            .combinations(5)
+            .map(|hand| self.evaluate5(&[hand[0], hand[1], hand[2], hand[3], hand[4]]))
+            .max()
+            .unwrap_or(RankKey(0))
+    }
+}
+
+/// Evaluate the best 5-card hand rank of each given 7-card board,
+/// as keys comparing like `HandRank`s from `HandRank::find_best5`.
+/// This does not check if there is any duplicate cards.
+pub fn evaluate_batch(boards: &[[Card; 7]]) -> Vec<RankKey> {
+    let mut results = Vec::with_capacity(boards.len());
+
+    #[cfg(feature = "simd")]
+    let boards = {
+        let mut chunks = boards.chunks_exact(LANES);
+        for chunk in chunks.by_ref() {
+            let mut masks = [[0; LANES]; NUM_OF_SHAPES];
+            for (lane, board) in chunk.iter().enumerate() {
+                for (shape, mask) in shape_masks(board).into_iter().enumerate() {
+                    masks[shape][lane] = mask;
+                }
+            }
+            let keys = evaluate_lanes(masks.map(Simd::from_array));
+            results.extend(keys.to_array().map(RankKey));
+        }
+        chunks.remainder()
+    };
+
+    results.extend(
+        boards
+            .iter()
+            .map(|board| RankKey(evaluate_lanes(shape_masks(board)))),
+    );
+    results
+}
+
+/// Evaluate the best 5-card hand rank of each board of 5, 6 or 7 cards in parallel,
+/// where `cards` are boards of `cards_per_board` cards concatenated.
+/// This does not check if there is any duplicate cards.
+pub fn evaluate_many(
+    cards: &[Card],
+    cards_per_board: usize,
+) -> Result<Vec<RankKey>, PokercraftLocalError> {
+    if !(5..=7).contains(&cards_per_board) {
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "Boards should have 5, 6 or 7 cards, got {}",
+            cards_per_board
+        )));
+    } else if !cards.len().is_multiple_of(cards_per_board) {
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "{} cards cannot be split into boards of {} cards",
+            cards.len(),
+            cards_per_board
+        )));
+    }
+    #[cfg(feature = "embedded-tables")]
+    let tables = RankTables::embedded()?;
+    Ok(in_thread_pool(|| {
+        cards
+            .par_chunks(cards_per_board * BOARDS_PER_CHUNK)
+            .flat_map_iter(|chunk| {
+                let boards = chunk.chunks_exact(cards_per_board);
+                if cards_per_board == 7 {
+                    let boards = boards
+                        .map(|board| {
+                            let mut board7 = [Card::default(); 7];
+                            board7.copy_from_slice(board);
+                            board7
+                        })
+                        .collect::<Vec<_>>();
+                    evaluate_batch(&boards)
+                } else {
+                    #[cfg(feature = "embedded-tables")]
+                    let evaluate = |board: &[Card]| tables.evaluate_best(board);
+                    #[cfg(not(feature = "embedded-tables"))]
+                    let evaluate = |board: &[Card]| evaluate_shape_masks(shape_masks(board));
+                    boards.map(evaluate).collect()
+                }
+            })
+            .collect()
+    }))
+}
+
+/// Evaluate the best 5-card hand rank of each board of 5, 6 or 7 cards (WASM interface).
+/// `cards` are card bytes (indices in the deck) of boards of `cardsPerBoard` cards concatenated.
+/// Returns rank keys, where a larger key is a stronger hand.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = evaluateMany)]
+pub fn evaluate_many_wasm(cards: &[u8], cards_per_board: usize) -> Result<Vec<u32>, JsValue> {
+    let cards = cards
+        .iter()
+        .map(|&byte| Card::try_from(byte))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(evaluate_many(&cards, cards_per_board)?
+        .into_iter()
+        .map(|key| key.0)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::HandRank;
+    use crate::errors::PokercraftLocalError;
+    use crate::generator::CardGenerator;
+
+    #[test]
+    fn test_evaluate_batch_matches_find_best5() -> Result<(), PokercraftLocalError> {
+        let mut generator = CardGenerator::new(2128);
+        let mut boards = Vec::new();
+        for _ in 0..5000 {
+            let mut board = [Card::default(); 7];
+            board.copy_from_slice(&generator.cards(7)?);
+            boards.push(board);
+        }
+        // Straight flushes, quads and wheels are rare in random boards,
+        // so also add consecutive cards sorted by shape and by number
+        let by_shape = Card::all();
+        let mut by_number = Card::all();
+        by_number.sort_by_key(|card| card.number);
+        for window in by_shape.windows(7).chain(by_number.windows(7)) {
+            let mut board = [Card::default(); 7];
+            board.copy_from_slice(window);
+            boards.push(board);
+        }
+
+        let keys = evaluate_batch(&boards);
+        assert_eq!(keys.len(), boards.len());
+        for (board, key) in boards.iter().zip(keys) {
+            let (_, rank) = HandRank::find_best5(board)?;
+            assert_eq!(key, rank.rank_key(), "Key mismatch for {:?}", board);
+            assert_eq!(key.category(), rank.numerize().0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_evaluate_many() -> Result<(), PokercraftLocalError> {
+        let mut generator = CardGenerator::new(2181);
+        for cards_per_board in 5..=7 {
+            let cards = generator.cards(cards_per_board * 7)?;
+            let keys = evaluate_many(&cards, cards_per_board)?;
+            assert_eq!(keys.len(), 7);
+            for (board, key) in cards.chunks_exact(cards_per_board).zip(keys) {
+                let (_, rank) = HandRank::find_best5(board)?;
+                assert_eq!(key, rank.rank_key(), "Key mismatch for {:?}", board);
+            }
+        }
+        assert!(evaluate_many(&Card::all()[..8], 4).is_err());
+        assert!(evaluate_many(&Card::all()[..8], 5).is_err());
+        assert!(evaluate_many(&[], 7)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rank_tables() -> Result<(), PokercraftLocalError> {
+        let tables = RankTables::generate();
+        assert_eq!(tables.products.len(), 4888);
+        assert_eq!(
+            RankTables::from_gzip_bytes(&tables.to_gzip_bytes()?)?,
+            tables
+        );
+        assert!(RankTables::from_gzip_bytes(&[]).is_err());
+        #[cfg(feature = "embedded-tables")]
+        assert_eq!(RankTables::embedded()?, &tables);
+
+        // Every 5-card hand
+        let cards = Card::all();
+        for a in 0..cards.len() {
+            for b in (a + 1)..cards.len() {
+                for c in (b + 1)..cards.len() {
+                    for d in (c + 1)..cards.len() {
+                        for e in (d + 1)..cards.len() {
+                            let hand = [cards[a], cards[b], cards[c], cards[d], cards[e]];
+                            assert_eq!(
+                                tables.evaluate5(&hand),
+                                evaluate_shape_masks(shape_masks(&hand)),
+                                "Key mismatch for {:?}",
+                                hand
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,480 @@
+//! Columnar (Parquet and Arrow IPC) export of parsed hands and tournaments,
+//! so they can be analyzed with polars, pandas, or DuckDB without re-parsing.
+//! Batches can also be streamed in the Arrow IPC streaming format,
+//! to exchange large results with other processes without JSON overhead.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, Float64Array, Int64Array, StringArray, TimestampMillisecondArray, UInt32Array,
+    UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::writer::{FileWriter, StreamWriter};
+use arrow::record_batch::RecordBatch;
+use chrono::NaiveDateTime;
+use parquet::arrow::ArrowWriter;
+
+use crate::errors::PokercraftLocalError;
+use crate::parser::{HandHistory, TournamentSummary};
+use crate::stats::{PlayerStats, TournamentVariant};
+
+/// Player ID of the hero in hand histories.
+const HERO: &str = "Hero";
+
+/// File formats to export record batches into.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum ColumnarFormat {
+    Parquet,
+    /// Arrow IPC file format, also known as Feather v2.
+    ArrowIpc,
+    /// Arrow IPC streaming format, which can be read without seeking,
+    /// so it can be sent through sockets and pipes.
+    ArrowIpcStream,
+}
+
+impl ColumnarFormat {
+    /// Get the conventional file extension, without the leading dot.
+    pub const fn extension(&self) -> &'static str {
+        match self {
+            ColumnarFormat::Parquet => "parquet",
+            ColumnarFormat::ArrowIpc => "arrow",
+            ColumnarFormat::ArrowIpcStream => "arrows",
+        }
+    }
+}
+
+fn export_error(e: impl std::fmt::Display) -> PokercraftLocalError {
+    PokercraftLocalError::GeneralError(format!("Failed to export columnar data: {}", e))
+}
+
+/// Timestamps in exports have no timezone,
+/// so those are stored as naive timestamps.
+fn timestamp_column<I>(datetimes: I) -> ArrayRef
+where
+    I: IntoIterator<Item = NaiveDateTime>,
+{
+    Arc::new(TimestampMillisecondArray::from_iter_values(
+        datetimes
+            .into_iter()
+            .map(|datetime| datetime.and_utc().timestamp_millis()),
+    ))
+}
+
+/// Convert hands into a record batch, one row per hand.
+/// Hero-related columns are null if Hero is not in the hand.
+pub fn hands_to_record_batch(hands: &[HandHistory]) -> Result<RecordBatch, PokercraftLocalError> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("tournament_id", DataType::UInt64, true),
+        Field::new("tournament_name", DataType::Utf8, true),
+        Field::new("level", DataType::UInt32, false),
+        Field::new("sb", DataType::UInt64, false),
+        Field::new("bb", DataType::UInt64, false),
+        Field::new(
+            "datetime",
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+            false,
+        ),
+        Field::new("table_id", DataType::Utf8, false),
+        Field::new("max_seats", DataType::UInt32, false),
+        Field::new("players", DataType::UInt32, false),
+        Field::new("board", DataType::Utf8, false),
+        Field::new("total_pot", DataType::UInt64, false),
+        Field::new("hero_seat", DataType::UInt32, true),
+        Field::new("hero_initial_chips", DataType::UInt64, true),
+        Field::new("hero_cards", DataType::Utf8, true),
+        Field::new("hero_net_profit", DataType::Int64, true),
+    ]);
+
+    let hero_seats: Vec<Option<u32>> = hands
+        .iter()
+        .map(|hand| hand.seat_number(HERO).ok())
+        .collect();
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            hands.iter().map(|hand| hand.id.as_str()),
+        )),
+        Arc::new(UInt64Array::from_iter(
+            hands.iter().map(|hand| hand.tournament_id),
+        )),
+        Arc::new(StringArray::from_iter(
+            hands.iter().map(|hand| hand.tournament_name.as_deref()),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            hands.iter().map(|hand| hand.level),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            hands.iter().map(|hand| hand.sb),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            hands.iter().map(|hand| hand.bb),
+        )),
+        timestamp_column(hands.iter().map(|hand| hand.datetime)),
+        Arc::new(StringArray::from_iter_values(
+            hands.iter().map(|hand| hand.table_id.as_str()),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            hands.iter().map(|hand| hand.max_seats),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            hands.iter().map(|hand| hand.seats.len() as u32),
+        )),
+        Arc::new(StringArray::from_iter_values(hands.iter().map(|hand| {
+            hand.community_cards
+                .iter()
+                .map(|card| card.to_string())
+                .collect::<String>()
+        }))),
+        Arc::new(UInt64Array::from_iter_values(
+            hands.iter().map(|hand| hand.total_pot()),
+        )),
+        Arc::new(UInt32Array::from(hero_seats.clone())),
+        Arc::new(UInt64Array::from_iter(
+            hands.iter().map(|hand| hand.initial_chips(HERO).ok()),
+        )),
+        Arc::new(StringArray::from_iter(hands.iter().map(|hand| {
+            hand.known_cards
+                .get(HERO)
+                .map(|(card1, card2)| format!("{}{}", card1, card2))
+        }))),
+        Arc::new(Int64Array::from_iter(
+            hands
+                .iter()
+                .zip(hero_seats.iter())
+                .map(|(hand, seat)| seat.map(|_| hand.net_profit(HERO))),
+        )),
+    ];
+    RecordBatch::try_new(Arc::new(schema), columns).map_err(export_error)
+}
+
+/// Convert tournaments into a record batch, one row per tournament.
+/// Money amounts are in the currency of each tournament, given in the `currency` column.
+pub fn tournaments_to_record_batch(
+    tournaments: &[TournamentSummary],
+) -> Result<RecordBatch, PokercraftLocalError> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("variant", DataType::Utf8, false),
+        Field::new("currency", DataType::Utf8, false),
+        Field::new("buy_in_pure", DataType::Float64, false),
+        Field::new("rake", DataType::Float64, false),
+        Field::new("total_prize_pool", DataType::Float64, false),
+        Field::new(
+            "start_time",
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+            false,
+        ),
+        Field::new("my_rank", DataType::UInt32, false),
+        Field::new("total_players", DataType::UInt32, false),
+        Field::new("my_prize", DataType::Float64, false),
+        Field::new("my_bounty", DataType::Float64, false),
+        Field::new("my_entries", DataType::UInt32, false),
+        Field::new("profit", DataType::Float64, false),
+    ]);
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values(
+            tournaments.iter().map(|t| t.id),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            tournaments.iter().map(|t| t.name.as_str()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            tournaments
+                .iter()
+                .map(|t| TournamentVariant::from_name(&t.name).to_string()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            tournaments.iter().map(|t| t.currency.to_string()),
+        )),
+        Arc::new(Float64Array::from_iter_values(
+            tournaments.iter().map(|t| t.buy_in_pure),
+        )),
+        Arc::new(Float64Array::from_iter_values(
+            tournaments.iter().map(|t| t.rake),
+        )),
+        Arc::new(Float64Array::from_iter_values(
+            tournaments.iter().map(|t| t.total_prize_pool),
+        )),
+        timestamp_column(tournaments.iter().map(|t| t.start_time)),
+        Arc::new(UInt32Array::from_iter_values(
+            tournaments.iter().map(|t| t.my_rank),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            tournaments.iter().map(|t| t.total_players),
+        )),
+        Arc::new(Float64Array::from_iter_values(
+            tournaments.iter().map(|t| t.my_prize),
+        )),
+        Arc::new(Float64Array::from_iter_values(
+            tournaments.iter().map(|t| t.my_bounty),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            tournaments.iter().map(|t| t.my_entries),
+        )),
+        Arc::new(Float64Array::from_iter_values(
+            tournaments.iter().map(|t| t.profit()),
+        )),
+    ];
+    RecordBatch::try_new(Arc::new(schema), columns).map_err(export_error)
+}
+
+/// Convert HUD stats into a record batch, one row per player and stat,
+/// sorted by player IDs and stat names.
+/// `ratio` is null if the player had no opportunity.
+pub fn hud_stats_to_record_batch(
+    players: &HashMap<String, PlayerStats>,
+) -> Result<RecordBatch, PokercraftLocalError> {
+    let schema = Schema::new(vec![
+        Field::new("player_id", DataType::Utf8, false),
+        Field::new("hands", DataType::UInt64, false),
+        Field::new("stat", DataType::Utf8, false),
+        Field::new("opportunities", DataType::UInt64, false),
+        Field::new("attempts", DataType::UInt64, false),
+        Field::new("ratio", DataType::Float64, true),
+    ]);
+
+    let mut player_ids = players.keys().collect::<Vec<_>>();
+    player_ids.sort();
+    let rows = player_ids
+        .into_iter()
+        .flat_map(|player_id| {
+            let player = &players[player_id];
+            player
+                .stats
+                .iter()
+                .map(move |(stat, counter)| (player_id, player.hands, stat, counter))
+        })
+        .collect::<Vec<_>>();
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|(player_id, _, _, _)| player_id.as_str()),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            rows.iter().map(|(_, hands, _, _)| *hands),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|(_, _, stat, _)| stat.as_str()),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            rows.iter().map(|(_, _, _, counter)| counter.opportunities),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            rows.iter().map(|(_, _, _, counter)| counter.attempts),
+        )),
+        Arc::new(Float64Array::from_iter(
+            rows.iter().map(|(_, _, _, counter)| counter.ratio()),
+        )),
+    ];
+    RecordBatch::try_new(Arc::new(schema), columns).map_err(export_error)
+}
+
+/// Write the given record batch into the writer in the given format.
+pub fn write_record_batch<W: Write + Send>(
+    batch: &RecordBatch,
+    format: ColumnarFormat,
+    writer: W,
+) -> Result<(), PokercraftLocalError> {
+    match format {
+        ColumnarFormat::Parquet => {
+            let mut writer =
+                ArrowWriter::try_new(writer, batch.schema(), None).map_err(export_error)?;
+            writer.write(batch).map_err(export_error)?;
+            writer.close().map_err(export_error)?;
+        }
+        ColumnarFormat::ArrowIpc => {
+            let mut writer =
+                FileWriter::try_new(writer, batch.schema_ref()).map_err(export_error)?;
+            writer.write(batch).map_err(export_error)?;
+            writer.finish().map_err(export_error)?;
+        }
+        ColumnarFormat::ArrowIpcStream => {
+            let mut writer =
+                StreamWriter::try_new(writer, batch.schema_ref()).map_err(export_error)?;
+            writer.write(batch).map_err(export_error)?;
+            writer.finish().map_err(export_error)?;
+        }
+    }
+    Ok(())
+}
+
+/// Incremental encoder of record batches in the Arrow IPC streaming format,
+/// so large results can be sent in chunks as those are produced.
+/// Concatenating all returned chunks, in order, gives a complete stream.
+pub struct ArrowStreamEncoder {
+    writer: StreamWriter<Vec<u8>>,
+}
+
+impl ArrowStreamEncoder {
+    /// Create a new encoder of batches with the given schema.
+    pub fn new(schema: &Schema) -> Result<Self, PokercraftLocalError> {
+        Ok(ArrowStreamEncoder {
+            writer: StreamWriter::try_new(Vec::new(), schema).map_err(export_error)?,
+        })
+    }
+
+    /// Encode the given batch, and get bytes written since the last call;
+    /// The first chunk also contains the schema.
+    pub fn encode(&mut self, batch: &RecordBatch) -> Result<Vec<u8>, PokercraftLocalError> {
+        self.writer.write(batch).map_err(export_error)?;
+        Ok(std::mem::take(self.writer.get_mut()))
+    }
+
+    /// Finish the stream, and get the remaining bytes.
+    pub fn finish(mut self) -> Result<Vec<u8>, PokercraftLocalError> {
+        self.writer.finish().map_err(export_error)?;
+        Ok(std::mem::take(self.writer.get_mut()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use arrow::array::Array;
+    use arrow::ipc::reader::{FileReader, StreamReader};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+    use crate::parser::hand_history::tests::SAMPLE_HAND;
+    use crate::parser::tournament_summary::tests::SAMPLE_SUMMARY;
+    use crate::parser::{parse_hands, parse_tournament_summary};
+    use crate::stats::HudStatsEngine;
+
+    #[test]
+    fn test_hands_to_record_batch() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HAND.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let batch = hands_to_record_batch(&hands)?;
+        assert_eq!(batch.num_rows(), 1);
+
+        let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+        let board = column("board");
+        let board = board.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(board.value(0), "4c3dAsJd");
+        let cards = column("hero_cards");
+        let cards = cards.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(cards.value(0), "Jh8c");
+        let profit = column("hero_net_profit");
+        let profit = profit.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(profit.value(0), -150);
+        let pot = column("total_pot");
+        let pot = pot.as_any().downcast_ref::<UInt64Array>().unwrap();
+        assert_eq!(pot.value(0), 9210);
+
+        // Hero-related columns are null without Hero
+        let mut anonymous = hands[0].clone();
+        let (_, hero_chips) = anonymous.seats.remove(&7).unwrap();
+        anonymous
+            .seats
+            .insert(7, ("abcdef".to_string(), hero_chips));
+        let batch = hands_to_record_batch(&[anonymous])?;
+        assert!(batch.column_by_name("hero_seat").unwrap().is_null(0));
+        assert!(batch.column_by_name("hero_net_profit").unwrap().is_null(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_hud_stats_to_record_batch() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HAND.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let mut engine = HudStatsEngine::new();
+        engine.add_hands(&hands);
+        let batch = hud_stats_to_record_batch(engine.players())?;
+
+        let hero = engine.player("Hero").unwrap();
+        let player_ids = batch.column_by_name("player_id").unwrap();
+        let player_ids = player_ids.as_any().downcast_ref::<StringArray>().unwrap();
+        let stats = batch.column_by_name("stat").unwrap();
+        let stats = stats.as_any().downcast_ref::<StringArray>().unwrap();
+        let row = (0..batch.num_rows())
+            .find(|&i| player_ids.value(i) == "Hero" && stats.value(i) == "VPIP")
+            .unwrap();
+        let ratios = batch.column_by_name("ratio").unwrap();
+        let ratios = ratios.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(Some(ratios.value(row)), hero.get("VPIP").ratio());
+
+        let total_stats = engine
+            .players()
+            .values()
+            .map(|player| player.stats.len())
+            .sum::<usize>();
+        assert_eq!(batch.num_rows(), total_stats);
+        assert!(player_ids
+            .iter()
+            .zip(player_ids.iter().skip(1))
+            .all(|(a, b)| a <= b));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_arrow_ipc() -> Result<(), PokercraftLocalError> {
+        let summary = parse_tournament_summary(SAMPLE_SUMMARY, false)?.unwrap();
+        let batch = tournaments_to_record_batch(&[summary.clone(), summary])?;
+
+        let mut buffer = Vec::new();
+        write_record_batch(&batch, ColumnarFormat::ArrowIpc, &mut buffer)?;
+        let reader = FileReader::try_new(Cursor::new(buffer), None).map_err(export_error)?;
+        let batches = reader
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(export_error)?;
+        assert_eq!(batches, vec![batch]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrow_stream_encoder() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HAND.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let batch = hands_to_record_batch(&hands)?;
+        let empty = hands_to_record_batch(&[])?;
+
+        let mut encoder = ArrowStreamEncoder::new(batch.schema_ref())?;
+        let mut buffer = encoder.encode(&batch)?;
+        buffer.extend(encoder.encode(&empty)?);
+        buffer.extend(encoder.encode(&batch)?);
+        buffer.extend(encoder.finish()?);
+
+        let reader =
+            StreamReader::try_new(Cursor::new(buffer.clone()), None).map_err(export_error)?;
+        let batches = reader
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(export_error)?;
+        assert_eq!(batches, vec![batch.clone(), empty, batch.clone()]);
+
+        // Same as writing a single batch at once
+        let mut single = Vec::new();
+        write_record_batch(&batch, ColumnarFormat::ArrowIpcStream, &mut single)?;
+        let reader = StreamReader::try_new(Cursor::new(single), None).map_err(export_error)?;
+        assert_eq!(reader.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_parquet() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HAND.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let batch = hands_to_record_batch(&hands)?;
+
+        let path = std::env::temp_dir().join(format!(
+            "pokercraft-export-test-{}.{}",
+            std::process::id(),
+            ColumnarFormat::Parquet.extension()
+        ));
+        write_record_batch(
+            &batch,
+            ColumnarFormat::Parquet,
+            std::fs::File::create(&path)?,
+        )?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path)?)
+            .and_then(|builder| builder.build())
+            .map_err(export_error)?;
+        let batches = reader
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(export_error)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(batches, vec![batch]);
+        Ok(())
+    }
+}
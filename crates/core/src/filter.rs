@@ -0,0 +1,308 @@
+//! Composable filters to select hands for downstream stats.
+
+use std::ops::RangeInclusive;
+
+use chrono::NaiveDateTime;
+use rayon::prelude::*;
+
+use crate::parser::{BetActionType, HandHistory};
+use crate::stats::Position;
+use crate::utils::in_thread_pool;
+
+/// Default blind levels where the middle and the late stage of a tournament start.
+pub const DEFAULT_STAGE_LEVEL_BOUNDARIES: [u32; 2] = [9, 17];
+
+/// Stages of a tournament, decided by the blind level.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum TournamentStage {
+    Early,
+    Middle,
+    Late,
+}
+
+impl TournamentStage {
+    /// Return all stages in order.
+    pub const fn all() -> [TournamentStage; 3] {
+        [
+            TournamentStage::Early,
+            TournamentStage::Middle,
+            TournamentStage::Late,
+        ]
+    }
+
+    /// Get the stage of the given blind level,
+    /// by `DEFAULT_STAGE_LEVEL_BOUNDARIES`.
+    pub fn of_level(level: u32) -> Self {
+        let [middle, late] = DEFAULT_STAGE_LEVEL_BOUNDARIES;
+        if level >= late {
+            TournamentStage::Late
+        } else if level >= middle {
+            TournamentStage::Middle
+        } else {
+            TournamentStage::Early
+        }
+    }
+}
+
+impl std::fmt::Display for TournamentStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            TournamentStage::Early => "Early",
+            TournamentStage::Middle => "Middle",
+            TournamentStage::Late => "Late",
+        };
+        write!(f, "{}", repr)
+    }
+}
+
+/// The most aggressive preflop line taken by a player.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum PreflopAction {
+    Fold,
+    /// Checked the option from the big blind.
+    Check,
+    /// Called the big blind without any raise.
+    Limp,
+    /// Called a raise.
+    Call,
+    /// Raised first in.
+    OpenRaise,
+    /// Re-raised against exactly one raise.
+    ThreeBet,
+    /// Re-raised against two or more raises.
+    FourBetPlus,
+}
+
+impl PreflopAction {
+    /// Get the preflop line of the given player in the given hand.
+    /// Returns `None` if the player made no voluntary preflop decision.
+    pub fn of(hand: &HandHistory, player_id: &str) -> Option<Self> {
+        let mut raises = 0;
+        let mut line: Option<PreflopAction> = None;
+        for action in hand
+            .actions_preflop
+            .iter()
+            .filter(|action| !matches!(action.action, BetActionType::Ante | BetActionType::Blind))
+        {
+            let is_raise = matches!(action.action, BetActionType::Bet | BetActionType::Raise);
+            if action.player_id == player_id {
+                let current = match action.action {
+                    BetActionType::Fold => PreflopAction::Fold,
+                    BetActionType::Check => PreflopAction::Check,
+                    BetActionType::Call if raises == 0 => PreflopAction::Limp,
+                    BetActionType::Call => PreflopAction::Call,
+                    _ if raises == 0 => PreflopAction::OpenRaise,
+                    _ if raises == 1 => PreflopAction::ThreeBet,
+                    _ => PreflopAction::FourBetPlus,
+                };
+                line = Some(line.map_or(current, |previous| previous.max(current)));
+            }
+            if is_raise {
+                raises += 1;
+            }
+        }
+        line
+    }
+}
+
+/// A custom predicate over hands.
+pub type HandPredicate = Box<dyn Fn(&HandHistory) -> bool + Send + Sync>;
+
+/// Composable filter of hands, seen from a single player.
+/// Every criterion is optional, and a hand is selected
+/// only if the player is in the hand and all given criteria match.
+pub struct HandFilter {
+    player_id: String,
+    datetime_range: (Option<NaiveDateTime>, Option<NaiveDateTime>),
+    stakes: Option<(u64, u64)>,
+    positions: Option<Vec<Position>>,
+    preflop_actions: Option<Vec<PreflopAction>>,
+    pot_size_bb: Option<RangeInclusive<f64>>,
+    levels: Option<RangeInclusive<u32>>,
+    predicates: Vec<HandPredicate>,
+}
+
+impl HandFilter {
+    /// Create a new filter which selects every hand the given player was dealt in.
+    pub fn new(player_id: &str) -> Self {
+        HandFilter {
+            player_id: player_id.to_string(),
+            datetime_range: (None, None),
+            stakes: None,
+            positions: None,
+            preflop_actions: None,
+            pot_size_bb: None,
+            levels: None,
+            predicates: Vec::new(),
+        }
+    }
+
+    /// Select hands played in `[start, end)`; `None` means unbounded.
+    pub fn with_datetime_range(
+        mut self,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+    ) -> Self {
+        self.datetime_range = (start, end);
+        self
+    }
+
+    /// Select hands played at the given blinds.
+    pub fn with_stakes(mut self, sb: u64, bb: u64) -> Self {
+        self.stakes = Some((sb, bb));
+        self
+    }
+
+    /// Select hands where the player is in any of the given positions.
+    pub fn with_positions(mut self, positions: &[Position]) -> Self {
+        self.positions = Some(positions.to_vec());
+        self
+    }
+
+    /// Select hands where the player took any of the given preflop lines.
+    pub fn with_preflop_actions(mut self, actions: &[PreflopAction]) -> Self {
+        self.preflop_actions = Some(actions.to_vec());
+        self
+    }
+
+    /// Select hands whose total pot is in the given range, in big blinds.
+    pub fn with_pot_size_bb(mut self, range: RangeInclusive<f64>) -> Self {
+        self.pot_size_bb = Some(range);
+        self
+    }
+
+    /// Select hands played in the given blind levels.
+    pub fn with_levels(mut self, levels: RangeInclusive<u32>) -> Self {
+        self.levels = Some(levels);
+        self
+    }
+
+    /// Select hands played in the given tournament stage.
+    /// This replaces blind levels set by `with_levels`.
+    pub fn with_stage(mut self, stage: TournamentStage) -> Self {
+        let [middle, late] = DEFAULT_STAGE_LEVEL_BOUNDARIES;
+        self.levels = Some(match stage {
+            TournamentStage::Early => 0..=middle - 1,
+            TournamentStage::Middle => middle..=late - 1,
+            TournamentStage::Late => late..=u32::MAX,
+        });
+        self
+    }
+
+    /// Select hands matching the given custom predicate too.
+    pub fn with_predicate(mut self, predicate: HandPredicate) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+
+    /// Check if the given hand is selected by this filter.
+    pub fn matches(&self, hand: &HandHistory) -> bool {
+        if !hand.seats.values().any(|(pid, _)| *pid == self.player_id) {
+            return false;
+        }
+        let (start, end) = self.datetime_range;
+        if start.is_some_and(|start| hand.datetime < start)
+            || end.is_some_and(|end| hand.datetime >= end)
+        {
+            return false;
+        }
+        if self
+            .stakes
+            .is_some_and(|stakes| stakes != (hand.sb, hand.bb))
+        {
+            return false;
+        }
+        if let Some(levels) = &self.levels {
+            if !levels.contains(&hand.level) {
+                return false;
+            }
+        }
+        if let Some(range) = &self.pot_size_bb {
+            if hand.bb == 0 || !range.contains(&(hand.total_pot() as f64 / hand.bb as f64)) {
+                return false;
+            }
+        }
+        if let Some(positions) = &self.positions {
+            match Position::of(hand, &self.player_id) {
+                Ok(position) if positions.contains(&position) => {}
+                _ => return false,
+            }
+        }
+        if let Some(actions) = &self.preflop_actions {
+            match PreflopAction::of(hand, &self.player_id) {
+                Some(action) if actions.contains(&action) => {}
+                _ => return false,
+            }
+        }
+        self.predicates.iter().all(|predicate| predicate(hand))
+    }
+
+    /// Select hands matching this filter, keeping the given order.
+    pub fn select<'a>(&self, hands: &'a [HandHistory]) -> Vec<&'a HandHistory> {
+        in_thread_pool(|| hands.par_iter().filter(|hand| self.matches(hand)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::errors::PokercraftLocalError;
+    use crate::parser::hand_history::tests::SAMPLE_HAND;
+    use crate::parser::parse_hands;
+
+    #[test]
+    fn test_preflop_actions() -> Result<(), PokercraftLocalError> {
+        let hand = parse_hands(SAMPLE_HAND.as_bytes()).next().unwrap()?;
+        assert_eq!(PreflopAction::of(&hand, "Hero"), Some(PreflopAction::Fold));
+        assert_eq!(
+            PreflopAction::of(&hand, "afc7064f"),
+            Some(PreflopAction::OpenRaise)
+        );
+        assert_eq!(
+            PreflopAction::of(&hand, "392ff24f"),
+            Some(PreflopAction::Call)
+        );
+        assert_eq!(PreflopAction::of(&hand, "nobody"), None);
+        assert_eq!(TournamentStage::of_level(16), TournamentStage::Middle);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hand_filter() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HAND.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let day = |d: u32| {
+            NaiveDate::from_ymd_opt(2025, 8, d)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+        };
+
+        assert_eq!(HandFilter::new("Hero").select(&hands).len(), 1);
+        assert!(HandFilter::new("nobody").select(&hands).is_empty());
+
+        let filter = HandFilter::new("Hero")
+            .with_datetime_range(day(1), day(2))
+            .with_stakes(600, 1200)
+            .with_positions(&[Position::BTN, Position::CO])
+            .with_preflop_actions(&[PreflopAction::Fold])
+            .with_pot_size_bb(5.0..=10.0)
+            .with_stage(TournamentStage::Middle)
+            .with_predicate(Box::new(|hand| hand.community_cards.len() == 4));
+        assert_eq!(filter.select(&hands).len(), 1);
+
+        let rejections = [
+            HandFilter::new("Hero").with_datetime_range(day(2), None),
+            HandFilter::new("Hero").with_stakes(100, 200),
+            HandFilter::new("Hero").with_positions(&[Position::BB]),
+            HandFilter::new("Hero").with_preflop_actions(&[PreflopAction::ThreeBet]),
+            HandFilter::new("Hero").with_pot_size_bb(10.0..=f64::INFINITY),
+            HandFilter::new("Hero").with_stage(TournamentStage::Late),
+            HandFilter::new("Hero").with_predicate(Box::new(|_| false)),
+        ];
+        for filter in rejections.iter() {
+            assert!(!filter.matches(&hands[0]));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+//! Seeded random generation of hole cards, boards and multiway spots,
+//! for test fixtures, trainers and Monte Carlo simulations.
+//! The same seed generates the same cards on every target.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "wasm")]
+use crate::card::parse_cards;
+use crate::card::{normalize_hand, Card, Hand};
+use crate::errors::PokercraftLocalError;
+
+/// Hole cards of each player and community cards.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Spot {
+    pub hands: Vec<Hand>,
+    pub board: Vec<Card>,
+}
+
+/// Random card generator from a seed, which never generates dead cards.
+#[derive(Clone, Debug)]
+pub struct CardGenerator {
+    rng: StdRng,
+    dead_cards: Vec<Card>,
+}
+
+impl CardGenerator {
+    /// Create a new generator from the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            dead_cards: Vec::new(),
+        }
+    }
+
+    /// Never generate the given cards, like known hole cards of the hero.
+    pub fn with_dead_cards(mut self, dead_cards: &[Card]) -> Self {
+        self.dead_cards.extend_from_slice(dead_cards);
+        self
+    }
+
+    /// Generate the given number of distinct cards.
+    pub fn cards(&mut self, count: usize) -> Result<Vec<Card>, PokercraftLocalError> {
+        let mut deck = Card::all()
+            .into_iter()
+            .filter(|card| !self.dead_cards.contains(card))
+            .collect::<Vec<_>>();
+        if count > deck.len() {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Cannot generate {} cards from {} live cards",
+                count,
+                deck.len()
+            )));
+        }
+        let (cards, _) = deck.partial_shuffle(&mut self.rng, count);
+        Ok(cards.to_vec())
+    }
+
+    /// Generate hole cards, with the higher card first.
+    pub fn hand(&mut self) -> Result<Hand, PokercraftLocalError> {
+        let cards = self.cards(2)?;
+        Ok(normalize_hand((cards[0], cards[1])))
+    }
+
+    /// Generate community cards of the given size; 0 (preflop), 3, 4 or 5.
+    pub fn board(&mut self, board_size: usize) -> Result<Vec<Card>, PokercraftLocalError> {
+        Self::check_board_size(board_size)?;
+        self.cards(board_size)
+    }
+
+    /// Generate a spot of the given number of players and board size,
+    /// where no card appears twice.
+    pub fn spot(
+        &mut self,
+        num_players: usize,
+        board_size: usize,
+    ) -> Result<Spot, PokercraftLocalError> {
+        Self::check_board_size(board_size)?;
+        if num_players < 2 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Spot should have at least 2 players".to_string(),
+            ));
+        }
+        let cards = self.cards(num_players * 2 + board_size)?;
+        let (holes, board) = cards.split_at(num_players * 2);
+        Ok(Spot {
+            hands: holes
+                .chunks_exact(2)
+                .map(|pair| normalize_hand((pair[0], pair[1])))
+                .collect(),
+            board: board.to_vec(),
+        })
+    }
+
+    fn check_board_size(board_size: usize) -> Result<(), PokercraftLocalError> {
+        match board_size {
+            0 | 3 | 4 | 5 => Ok(()),
+            _ => Err(PokercraftLocalError::GeneralError(format!(
+                "Invalid board size: {}",
+                board_size
+            ))),
+        }
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(typescript_custom_section)]
+const TS_SPOT: &'static str = r#"
+/** Hole cards of each player and community cards. */
+export interface Spot {
+    /** Hole card strings of each player, like `["As", "Kd"]`. */
+    hands: [string, string][];
+    board: string[];
+}
+"#;
+
+/// Generate a random spot from the given seed (WASM interface).
+/// `deadCards` are never generated, like `"AsKd"`.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = randomSpot, unchecked_return_type = "Spot")]
+pub fn random_spot_wasm(
+    seed: u32,
+    num_players: usize,
+    board_size: usize,
+    dead_cards: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let to_js_error = |e: PokercraftLocalError| JsValue::from_str(&e.to_string());
+    let dead_cards = parse_cards(dead_cards.as_deref().unwrap_or_default()).map_err(to_js_error)?;
+    let spot = CardGenerator::new(seed as u64)
+        .with_dead_cards(&dead_cards)
+        .spot(num_players, board_size)
+        .map_err(to_js_error)?;
+    crate::utils::to_js_object(&spot)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_card_generator() -> Result<(), PokercraftLocalError> {
+        // Same seeds generate same spots
+        let spot = CardGenerator::new(2139).spot(6, 5)?;
+        assert_eq!(spot, CardGenerator::new(2139).spot(6, 5)?);
+        assert_ne!(spot, CardGenerator::new(2140).spot(6, 5)?);
+        assert_eq!(spot.hands.len(), 6);
+        assert_eq!(spot.board.len(), 5);
+        let mut seen = HashSet::new();
+        for &(c1, c2) in spot.hands.iter() {
+            assert!(c1 > c2);
+            assert!(seen.insert(c1) && seen.insert(c2));
+        }
+        assert!(spot.board.iter().all(|&card| seen.insert(card)));
+
+        // Dead cards are never generated
+        let dead_cards = Card::all()[..40].to_vec();
+        let mut generator = CardGenerator::new(0).with_dead_cards(&dead_cards);
+        for _ in 0..100 {
+            let (c1, c2) = generator.hand()?;
+            assert!(!dead_cards.contains(&c1) && !dead_cards.contains(&c2));
+        }
+        assert_eq!(generator.cards(12)?.len(), 12);
+        assert!(generator.cards(13).is_err());
+        assert!(generator.board(2).is_err());
+        assert!(generator.spot(1, 5).is_err());
+        assert!(generator.spot(4, 5).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+// Monte Carlo bankroll simulations; One simulation per invocation.
+// Capitals are computed in single precision.
+
+// `[simulations, max_iteration, seed]`
+@group(0) @binding(0) var<storage, read> params: array<u32>;
+// `[initial_capital, exit_capital]`
+@group(0) @binding(1) var<storage, read> capitals: array<f32>;
+@group(0) @binding(2) var<storage, read> relative_returns: array<f32>;
+// `(relative return bits, iteration of going bankrupt or reaching the exit capital)`
+// of each simulation.
+@group(0) @binding(3) var<storage, read_write> results: array<vec2<u32>>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params[0] {
+        return;
+    }
+    seed_rng(params[2], id.x);
+
+    let initial_capital = capitals[0];
+    let exit_capital = capitals[1];
+    let num_returns = arrayLength(&relative_returns);
+    var capital = initial_capital;
+    for (var i = 0u; i < params[1]; i++) {
+        capital += relative_returns[random_below(num_returns)];
+        if capital <= 0.0 {
+            results[id.x] = vec2<u32>(bitcast<u32>(0.0), i + 1u);
+            return;
+        } else if capital >= exit_capital {
+            results[id.x] = vec2<u32>(bitcast<u32>(capital / initial_capital), i + 1u);
+            return;
+        }
+    }
+    results[id.x] = vec2<u32>(bitcast<u32>(max(capital / initial_capital, 0.0)), 0u);
+}
@@ -0,0 +1,373 @@
+//! GPU backend of Monte Carlo range equities and bankroll simulations, via wgpu.
+//!
+//! Each Monte Carlo trial or simulation runs in its own compute shader invocation.
+//! Use `GpuContext::shared` to get the GPU, which is `None` if there is no GPU adapter;
+//! `RangeEquityResult::new_gpu` and `bankroll::simulate_gpu` fall back to CPU in that case.
+
+use once_cell::sync::Lazy;
+use rand::{thread_rng, Rng};
+use wgpu::util::DeviceExt;
+
+use crate::card::Card;
+use crate::equity::ShowdownOutcome;
+use crate::errors::PokercraftLocalError;
+use crate::range::HandRange;
+
+/// Number of invocations per workgroup, same as `@workgroup_size` of shaders.
+const WORKGROUP_SIZE: u32 = 64;
+/// Maximum number of trials or simulations per dispatch,
+/// small enough to fit in workgroup limits and `u32` counters.
+const MAX_INVOCATIONS_PER_DISPATCH: u64 = 1 << 21;
+/// Layout of counts per player in `range_equity.wgsl`.
+const TIES_OFFSET: usize = 2;
+const CATEGORIES_OFFSET: usize = 26;
+const COUNTS_STRIDE: usize = 35;
+
+static SHARED: Lazy<Option<GpuContext>> = Lazy::new(|| GpuContext::new().ok());
+
+/// A GPU device with compiled Monte Carlo pipelines.
+pub struct GpuContext {
+    adapter_name: String,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    range_equity: wgpu::ComputePipeline,
+    bankroll: wgpu::ComputePipeline,
+}
+
+impl GpuContext {
+    /// Request the default GPU adapter and compile pipelines on it.
+    /// Returns an error if there is no GPU adapter.
+    pub fn new() -> Result<Self, PokercraftLocalError> {
+        let instance = wgpu::Instance::default();
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .map_err(|e| PokercraftLocalError::GeneralError(format!("No GPU adapter: {}", e)))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+            label: Some("pokercraft"),
+            ..Default::default()
+        }))
+        .map_err(|e| {
+            PokercraftLocalError::GeneralError(format!("Failed to request GPU device: {}", e))
+        })?;
+
+        let range_equity = Self::create_pipeline(
+            &device,
+            "range_equity",
+            concat!(
+                include_str!("random.wgsl"),
+                include_str!("range_equity.wgsl")
+            ),
+        );
+        let bankroll = Self::create_pipeline(
+            &device,
+            "bankroll",
+            concat!(include_str!("random.wgsl"), include_str!("bankroll.wgsl")),
+        );
+        Ok(GpuContext {
+            adapter_name: adapter.get_info().name,
+            device,
+            queue,
+            range_equity,
+            bankroll,
+        })
+    }
+
+    /// Get the GPU shared by all computations, created on the first call.
+    /// Returns `None` if there is no GPU adapter.
+    pub fn shared() -> Option<&'static GpuContext> {
+        SHARED.as_ref()
+    }
+
+    /// Get the name of the GPU adapter.
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+
+    fn create_pipeline(device: &wgpu::Device, label: &str, source: &str) -> wgpu::ComputePipeline {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(label),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        })
+    }
+
+    /// Run `invocations` invocations of the given pipeline,
+    /// binding `inputs` in order and then a zeroed output of `output_size` bytes.
+    /// Returns the output after all invocations are finished.
+    fn dispatch(
+        &self,
+        pipeline: &wgpu::ComputePipeline,
+        inputs: &[Vec<u8>],
+        output_size: u64,
+        invocations: u32,
+    ) -> Result<Vec<u8>, PokercraftLocalError> {
+        let input_buffers = inputs
+            .iter()
+            .map(|contents| {
+                self.device
+                    .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                        label: None,
+                        contents,
+                        usage: wgpu::BufferUsages::STORAGE,
+                    })
+            })
+            .collect::<Vec<_>>();
+        let output = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: output_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: output_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let entries = input_buffers
+            .iter()
+            .chain(std::iter::once(&output))
+            .enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect::<Vec<_>>();
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(invocations.div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&output, 0, &staging, 0, output_size);
+        self.queue.submit([encoder.finish()]);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        staging
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|e| PokercraftLocalError::GeneralError(format!("GPU failed: {}", e)))?;
+        receiver
+            .recv()
+            .map_err(|e| PokercraftLocalError::GeneralError(e.to_string()))?
+            .map_err(|e| PokercraftLocalError::GeneralError(format!("GPU failed: {}", e)))?;
+        let result = staging.slice(..).get_mapped_range().to_vec();
+        Ok(result)
+    }
+
+    /// Run `trials` Monte Carlo trials of showdowns between the given ranges,
+    /// which should not have any combos conflicting with `known_cards`.
+    /// `board` and dead cards are both in `known_cards`.
+    pub(crate) fn range_equity(
+        &self,
+        ranges: &[HandRange],
+        board: &[Card],
+        known_cards: &[Card],
+        trials: u64,
+    ) -> Result<Vec<ShowdownOutcome>, PokercraftLocalError> {
+        let num_players = ranges.len();
+        let known = known_cards
+            .iter()
+            .fold(0u64, |mask, &card| mask | (1 << card.index()));
+        let mut range_offsets = vec![0u32];
+        let mut combos = Vec::new();
+        for range in ranges {
+            combos.extend(
+                range
+                    .combos()
+                    .iter()
+                    .map(|&(c1, c2)| c1.index() as u32 | ((c2.index() as u32) << 8)),
+            );
+            range_offsets.push(combos.len() as u32);
+        }
+
+        let mut outcomes = vec![ShowdownOutcome::default(); num_players];
+        let mut remaining = trials;
+        while remaining > 0 {
+            let batch = remaining.min(MAX_INVOCATIONS_PER_DISPATCH) as u32;
+            let mut params = vec![
+                num_players as u32,
+                board.len() as u32,
+                batch,
+                thread_rng().gen(),
+                known as u32,
+                (known >> 32) as u32,
+            ];
+            params.extend(board.iter().map(|&card| card.index() as u32));
+            let counts = from_bytes(&self.dispatch(
+                &self.range_equity,
+                &[
+                    to_bytes(&params),
+                    to_bytes(&range_offsets),
+                    to_bytes(&combos),
+                ],
+                ((num_players * COUNTS_STRIDE + 1) * 4) as u64,
+                batch,
+            )?);
+            if counts[num_players * COUNTS_STRIDE] > 0 {
+                return Err(PokercraftLocalError::GeneralError(
+                    "Failed to draw non-conflicting combos; Ranges overlap too much".to_string(),
+                ));
+            }
+
+            for (outcome, counts) in outcomes.iter_mut().zip(counts.chunks(COUNTS_STRIDE)) {
+                outcome.wins += counts[0] as u64;
+                outcome.losses += counts[1] as u64;
+                outcome.pot_shares += counts[0] as f64;
+                for winners in 2..=num_players {
+                    let ties = counts[TIES_OFFSET + winners] as u64;
+                    outcome.ties += ties;
+                    outcome.pot_shares += ties as f64 / winners as f64;
+                }
+                for (category, &count) in outcome
+                    .categories
+                    .iter_mut()
+                    .zip(counts[CATEGORIES_OFFSET..].iter())
+                {
+                    *category += count as u64;
+                }
+            }
+            remaining -= batch as u64;
+        }
+        Ok(outcomes)
+    }
+
+    /// Run `simulation_count` bankroll simulations in single precision,
+    /// returning `(relative_return, iteration of going bankrupt or reaching the exit capital)`
+    /// of each simulation.
+    pub(crate) fn bankroll(
+        &self,
+        initial_capital: f64,
+        relative_return_results: &[f64],
+        max_iteration: u32,
+        exit_capital: f64,
+        simulation_count: u32,
+    ) -> Result<Vec<(f64, u32)>, PokercraftLocalError> {
+        let capitals = to_bytes(&[initial_capital as f32, exit_capital as f32]);
+        let relative_returns = to_bytes(
+            &relative_return_results
+                .iter()
+                .map(|&r| r as f32)
+                .collect::<Vec<_>>(),
+        );
+
+        let mut results = Vec::with_capacity(simulation_count as usize);
+        let mut remaining = simulation_count as u64;
+        while remaining > 0 {
+            let batch = remaining.min(MAX_INVOCATIONS_PER_DISPATCH) as u32;
+            let params = [batch, max_iteration, thread_rng().gen()];
+            let output = from_bytes(&self.dispatch(
+                &self.bankroll,
+                &[
+                    to_bytes(&params),
+                    capitals.clone(),
+                    relative_returns.clone(),
+                ],
+                batch as u64 * 8,
+                batch,
+            )?);
+            results.extend(
+                output
+                    .chunks_exact(2)
+                    .map(|pair| (f32::from_bits(pair[0]) as f64, pair[1])),
+            );
+            remaining -= batch as u64;
+        }
+        Ok(results)
+    }
+}
+
+/// Little-endian bytes of the given 32-bit values for GPU buffers.
+fn to_bytes<T: Copy + Into<Bits32>>(values: &[T]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|&value| value.into().0.to_le_bytes())
+        .collect()
+}
+
+/// Read 32-bit values from little-endian bytes of GPU buffers.
+fn from_bytes(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+/// Bits of a 32-bit value stored in GPU buffers.
+struct Bits32(u32);
+
+impl From<u32> for Bits32 {
+    fn from(value: u32) -> Self {
+        Bits32(value)
+    }
+}
+
+impl From<f32> for Bits32 {
+    fn from(value: f32) -> Self {
+        Bits32(value.to_bits())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bankroll::simulate_gpu;
+    use crate::equity::RangeEquityResult;
+
+    /// These run on CPU if there is no GPU adapter.
+    #[test]
+    fn test_gpu_monte_carlo() -> Result<(), PokercraftLocalError> {
+        let board: Vec<Card> = vec!["2c".try_into()?, "7d".try_into()?, "9h".try_into()?];
+        let ranges = [
+            HandRange::try_from("AA")?,
+            HandRange::try_from("KK")?,
+            HandRange::try_from("QQ,JJ")?,
+        ];
+        let exact = RangeEquityResult::new(&ranges, &board, 200000)?;
+        let sampled = RangeEquityResult::new_gpu(&ranges, &board, &[], 200000)?;
+        assert!(!sampled.is_exact());
+        for i in 0..ranges.len() {
+            let (expected, got) = (exact.outcome(i)?, sampled.outcome(i)?);
+            assert_eq!(got.total(), 200000);
+            assert_eq!(got.categories.iter().sum::<u64>(), 200000);
+            assert!((got.equity() - expected.equity()).abs() < 0.01);
+        }
+        assert!(RangeEquityResult::new_gpu(
+            &[HandRange::try_from("AsAh")?, HandRange::try_from("AsAh")?],
+            &[],
+            &[],
+            100,
+        )
+        .is_err());
+
+        let expected = crate::bankroll::simulate_core(10.0, [1.0, -1.0], 100, 2.0, 20000)?;
+        let metric = simulate_gpu(10.0, [1.0, -1.0], 100, 2.0, 20000)?;
+        assert_eq!(metric.len(), 20000);
+        assert!((metric.get_bankruptcy_rate() - expected.get_bankruptcy_rate()).abs() < 0.02);
+        assert!((metric.get_profitable_rate() - expected.get_profitable_rate()).abs() < 0.02);
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+// PCG random number generator, seeded per invocation.
+
+var<private> rng_state: u32;
+
+fn seed_rng(seed: u32, invocation: u32) {
+    rng_state = seed ^ (invocation * 2654435769u);
+    _ = next_u32();
+    rng_state += invocation;
+}
+
+fn next_u32() -> u32 {
+    rng_state = rng_state * 747796405u + 2891336453u;
+    let word = ((rng_state >> ((rng_state >> 28u) + 4u)) ^ rng_state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+// Uniform random integer in `0..n`.
+fn random_below(n: u32) -> u32 {
+    return next_u32() % n;
+}
@@ -0,0 +1,181 @@
+// Monte Carlo equity between hand ranges; One trial per invocation.
+// Cards are indices `shape * 13 + (number - 2)`, as in `Card::all()`.
+
+const MAX_PLAYERS: u32 = 23u;
+const MAX_DRAWS_PER_TRIAL: u32 = 1000u;
+// Counts per player: wins, losses, ties by number of winners, hand rank categories.
+const TIES_OFFSET: u32 = 2u;
+const CATEGORIES_OFFSET: u32 = 26u;
+const COUNTS_STRIDE: u32 = 35u;
+
+// `[num_players, board_len, trials, seed, known_lo, known_hi, board...]`
+@group(0) @binding(0) var<storage, read> params: array<u32>;
+// Combos of player `p` are `combos[range_offsets[p]..range_offsets[p + 1]]`.
+@group(0) @binding(1) var<storage, read> range_offsets: array<u32>;
+// Combos packed as `card1 | (card2 << 8)`.
+@group(0) @binding(2) var<storage, read> combos: array<u32>;
+// Counts of each player, followed by the number of failed trials.
+@group(0) @binding(3) var<storage, read_write> counts: array<atomic<u32>>;
+
+var<private> used_lo: u32;
+var<private> used_hi: u32;
+
+// Mark the given card as used; Returns `false` if it was already used.
+fn use_card(card: u32) -> bool {
+    let bit = 1u << (card % 32u);
+    if card < 32u {
+        if (used_lo & bit) != 0u {
+            return false;
+        }
+        used_lo |= bit;
+    } else {
+        if (used_hi & bit) != 0u {
+            return false;
+        }
+        used_hi |= bit;
+    }
+    return true;
+}
+
+fn highest_bit(numbers: u32) -> u32 {
+    return select(0u, 1u << firstLeadingBit(numbers), numbers != 0u);
+}
+
+fn highest_bits(numbers: u32, k: u32) -> u32 {
+    var remaining = numbers;
+    var result = 0u;
+    for (var i = 0u; i < k; i++) {
+        let highest = highest_bit(remaining);
+        result |= highest;
+        remaining &= ~highest;
+    }
+    return result;
+}
+
+fn straight_high(numbers: u32) -> u32 {
+    // Ace also counts as the lowest number, at bit 0
+    let n = numbers | ((numbers >> 13u) & 1u);
+    return highest_bit(n & (n << 1u) & (n << 2u) & (n << 3u) & (n << 4u));
+}
+
+fn rank_key(category: u32, primary: u32, kickers: u32) -> u32 {
+    return (category << 28u) | (primary << 14u) | kickers;
+}
+
+// Same as `evaluate_lanes` in `evaluator.rs`, for a single lane.
+fn evaluate(s0: u32, s1: u32, s2: u32, s3: u32) -> u32 {
+    let any = s0 | s1 | s2 | s3;
+    let two = (s0 & s1) | (s0 & s2) | (s0 & s3) | (s1 & s2) | (s1 & s3) | (s2 & s3);
+    let three = (s0 & s1 & s2) | (s0 & s1 & s3) | (s0 & s2 & s3) | (s1 & s2 & s3);
+    let four = s0 & s1 & s2 & s3;
+
+    var flush = 0u;
+    flush |= select(0u, s0, countOneBits(s0) >= 5u);
+    flush |= select(0u, s1, countOneBits(s1) >= 5u);
+    flush |= select(0u, s2, countOneBits(s2) >= 5u);
+    flush |= select(0u, s3, countOneBits(s3) >= 5u);
+    let straight_flush = straight_high(flush);
+    if straight_flush != 0u {
+        return rank_key(8u, 0u, straight_flush);
+    }
+    if four != 0u {
+        return rank_key(7u, four, highest_bit(any & ~four));
+    }
+    let triple = highest_bit(three);
+    let full_house_pair = highest_bit(two & ~triple);
+    if triple != 0u && full_house_pair != 0u {
+        return rank_key(6u, triple, full_house_pair);
+    }
+    if flush != 0u {
+        return rank_key(5u, 0u, highest_bits(flush, 5u));
+    }
+    let straight = straight_high(any);
+    if straight != 0u {
+        return rank_key(4u, 0u, straight);
+    }
+    if triple != 0u {
+        return rank_key(3u, triple, highest_bits(any & ~triple, 2u));
+    }
+    if countOneBits(two) >= 2u {
+        let two_pairs = highest_bits(two, 2u);
+        return rank_key(2u, two_pairs, highest_bit(any & ~two_pairs));
+    }
+    if two != 0u {
+        return rank_key(1u, two, highest_bits(any & ~two, 3u));
+    }
+    return rank_key(0u, 0u, highest_bits(any, 5u));
+}
+
+// Add the bit of the given card into the masks per shape.
+fn add_card(masks: ptr<function, array<u32, 4>>, card: u32) {
+    (*masks)[card / 13u] |= 1u << (card % 13u + 1u);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let num_players = params[0];
+    let board_len = params[1];
+    if id.x >= params[2] {
+        return;
+    }
+    seed_rng(params[3], id.x);
+
+    var hands: array<u32, MAX_PLAYERS>;
+    for (var draw = 0u; draw < MAX_DRAWS_PER_TRIAL; draw++) {
+        used_lo = params[4];
+        used_hi = params[5];
+        var drawn = true;
+        for (var p = 0u; p < num_players; p++) {
+            let start = range_offsets[p];
+            let combo = combos[start + random_below(range_offsets[p + 1u] - start)];
+            if !use_card(combo & 0xFFu) || !use_card(combo >> 8u) {
+                drawn = false;
+                break;
+            }
+            hands[p] = combo;
+        }
+        if !drawn {
+            continue;
+        }
+
+        // Complete the board with random unused cards
+        var board: array<u32, 4>;
+        for (var i = 0u; i < board_len; i++) {
+            add_card(&board, params[6u + i]);
+        }
+        for (var i = board_len; i < 5u; i++) {
+            var card = random_below(52u);
+            while !use_card(card) {
+                card = random_below(52u);
+            }
+            add_card(&board, card);
+        }
+
+        var keys: array<u32, MAX_PLAYERS>;
+        var best = 0u;
+        for (var p = 0u; p < num_players; p++) {
+            var masks = board;
+            add_card(&masks, hands[p] & 0xFFu);
+            add_card(&masks, hands[p] >> 8u);
+            keys[p] = evaluate(masks[0], masks[1], masks[2], masks[3]);
+            best = max(best, keys[p]);
+        }
+        var winners = 0u;
+        for (var p = 0u; p < num_players; p++) {
+            winners += select(0u, 1u, keys[p] == best);
+        }
+        for (var p = 0u; p < num_players; p++) {
+            let base = p * COUNTS_STRIDE;
+            if keys[p] != best {
+                atomicAdd(&counts[base + 1u], 1u);
+            } else if winners == 1u {
+                atomicAdd(&counts[base], 1u);
+            } else {
+                atomicAdd(&counts[base + TIES_OFFSET + winners], 1u);
+            }
+            atomicAdd(&counts[base + CATEGORIES_OFFSET + (keys[p] >> 28u)], 1u);
+        }
+        return;
+    }
+    atomicAdd(&counts[num_players * COUNTS_STRIDE], 1u);
+}
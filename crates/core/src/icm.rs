@@ -0,0 +1,229 @@
+//! Independent Chip Model (ICM) for tournament equities of chip stacks,
+//! including satellites where the top places win the same ticket.
+
+use crate::errors::PokercraftLocalError;
+
+/// Maximum number of players, as the calculation iterates over all subsets of players.
+pub const MAX_ICM_PLAYERS: usize = 20;
+
+/// Calculate the expected prize of each player with the Malmuth-Harville model,
+/// where a player finishes in the next best place with probability
+/// proportional to the chip stack among remaining players.
+/// `payouts[k]` is the prize of `k+1`-th place; Extra places are ignored.
+pub fn icm_equities(stacks: &[f64], payouts: &[f64]) -> Result<Vec<f64>, PokercraftLocalError> {
+    if stacks.is_empty() || stacks.len() > MAX_ICM_PLAYERS {
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "Number of players must be between 1 and {}",
+            MAX_ICM_PLAYERS
+        )));
+    } else if stacks
+        .iter()
+        .any(|&stack| !(stack > 0.0 && stack.is_finite()))
+    {
+        return Err(PokercraftLocalError::GeneralError(
+            "Stacks must be positive".to_string(),
+        ));
+    } else if payouts
+        .iter()
+        .any(|&payout| !(payout >= 0.0 && payout.is_finite()))
+    {
+        return Err(PokercraftLocalError::GeneralError(
+            "Payouts must not be negative".to_string(),
+        ));
+    }
+
+    let n = stacks.len();
+    let places = payouts.len().min(n);
+    let total: f64 = stacks.iter().sum();
+    let mut equities = vec![0.0; n];
+
+    // `probabilities[mask]` is the probability that players in `mask`
+    // take the first `mask.count_ones()` places, in any order.
+    let mut probabilities = vec![0.0; 1 << n];
+    let mut taken_chips = vec![0.0; 1 << n];
+    probabilities[0] = 1.0;
+    for mask in 0..(1usize << n) {
+        let place = mask.count_ones() as usize;
+        if probabilities[mask] == 0.0 || place >= places {
+            continue;
+        }
+        let remaining = total - taken_chips[mask];
+        for (i, &stack) in stacks.iter().enumerate() {
+            if mask & (1 << i) != 0 {
+                continue;
+            }
+            let probability = probabilities[mask] * stack / remaining;
+            equities[i] += probability * payouts[place];
+            let next = mask | (1 << i);
+            probabilities[next] += probability;
+            taken_chips[next] = taken_chips[mask] + stack;
+        }
+    }
+    Ok(equities)
+}
+
+/// Calculate the probability of each player winning a ticket in a satellite,
+/// where the top `tickets` places get the same ticket.
+/// Busted players with empty stacks never win a ticket.
+pub fn satellite_equities(
+    stacks: &[f64],
+    tickets: usize,
+) -> Result<Vec<f64>, PokercraftLocalError> {
+    if tickets < 1 {
+        return Err(PokercraftLocalError::GeneralError(
+            "Satellite must have at least 1 ticket".to_string(),
+        ));
+    }
+    let alive: Vec<f64> = stacks
+        .iter()
+        .copied()
+        .filter(|&stack| stack != 0.0)
+        .collect();
+    let mut alive_equities = icm_equities(&alive, &vec![1.0; tickets])?.into_iter();
+    Ok(stacks
+        .iter()
+        .map(|&stack| {
+            if stack != 0.0 {
+                alive_equities.next().unwrap_or(0.0)
+            } else {
+                0.0
+            }
+        })
+        .collect())
+}
+
+/// Get the stack size which locks a ticket in a satellite,
+/// even if the player folds every hand until the end.
+/// A stack bigger than this can never be passed by `tickets` other players at once.
+/// Blinds and antes paid while folding are not considered.
+pub fn ticket_lock_threshold(stacks: &[f64], tickets: usize) -> f64 {
+    stacks.iter().sum::<f64>() / (tickets + 1) as f64
+}
+
+/// Probabilities of winning a ticket by folding, or by an all-in against a single opponent.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct SatelliteAllIn {
+    /// Probability of winning a ticket after folding.
+    pub fold: f64,
+    /// Probability of winning a ticket after winning the all-in.
+    pub win: f64,
+    /// Probability of winning a ticket after losing the all-in.
+    pub lose: f64,
+}
+
+impl SatelliteAllIn {
+    /// Calculate probabilities of the given player (`hero`)
+    /// on an all-in against `villain`, where the smaller stack is at risk.
+    pub fn new(
+        stacks: &[f64],
+        tickets: usize,
+        hero: usize,
+        villain: usize,
+    ) -> Result<Self, PokercraftLocalError> {
+        if hero >= stacks.len() || villain >= stacks.len() || hero == villain {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Invalid players {} and {} of {} players",
+                hero,
+                villain,
+                stacks.len()
+            )));
+        }
+        let at_risk = stacks[hero].min(stacks[villain]);
+        let after = |hero_delta: f64| {
+            let mut stacks = stacks.to_vec();
+            stacks[hero] += hero_delta;
+            stacks[villain] -= hero_delta;
+            satellite_equities(&stacks, tickets).map(|equities| equities[hero])
+        };
+        Ok(SatelliteAllIn {
+            fold: satellite_equities(stacks, tickets)?[hero],
+            win: after(at_risk)?,
+            lose: after(-at_risk)?,
+        })
+    }
+
+    /// Get the probability of winning a ticket by calling with the given equity.
+    pub fn call(&self, equity: f64) -> f64 {
+        equity * self.win + (1.0 - equity) * self.lose
+    }
+
+    /// Get the minimum equity to call rather than fold; `None` if calling never helps.
+    pub fn required_equity(&self) -> Option<f64> {
+        if self.win <= self.fold {
+            None
+        } else {
+            Some(((self.fold - self.lose) / (self.win - self.lose)).max(0.0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_almost_equal(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "Expected {} but got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_icm_equities() -> Result<(), PokercraftLocalError> {
+        let equities = icm_equities(&[100.0, 100.0], &[70.0, 30.0])?;
+        assert_almost_equal(equities[0], 50.0);
+        assert_almost_equal(equities[1], 50.0);
+
+        // Chip leader: 0.5 * 50 + (0.3 * 0.5 / 0.7 + 0.2 * 0.5 / 0.8) * 30
+        let equities = icm_equities(&[5000.0, 3000.0, 2000.0], &[50.0, 30.0])?;
+        assert_almost_equal(
+            equities[0],
+            25.0 + (0.3 * 0.5 / 0.7 + 0.2 * 0.5 / 0.8) * 30.0,
+        );
+        assert_almost_equal(equities.iter().sum::<f64>(), 80.0);
+        assert!(equities[0] > equities[1] && equities[1] > equities[2]);
+
+        // Extra places are ignored
+        let equities = icm_equities(&[1.0], &[10.0, 5.0])?;
+        assert_eq!(equities, vec![10.0]);
+
+        assert!(icm_equities(&[], &[1.0]).is_err());
+        assert!(icm_equities(&[1.0, 0.0], &[1.0]).is_err());
+        assert!(icm_equities(&[1.0, 1.0], &[-1.0]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_satellite() -> Result<(), PokercraftLocalError> {
+        // 2 tickets among 3 players; Only the shortest stack can miss the ticket
+        let stacks = [6000.0, 3000.0, 1000.0];
+        let equities = satellite_equities(&stacks, 2)?;
+        assert_almost_equal(equities.iter().sum::<f64>(), 2.0);
+        assert!(equities[0] > equities[1] && equities[1] > equities[2]);
+        assert_eq!(satellite_equities(&[1.0, 0.0], 1)?, vec![1.0, 0.0]);
+        assert!(satellite_equities(&stacks, 0).is_err());
+
+        // Nobody can pass 6,000 chips of 10,000 with 2 tickets
+        assert_almost_equal(ticket_lock_threshold(&stacks, 2), 10000.0 / 3.0);
+        assert!(stacks[0] > ticket_lock_threshold(&stacks, 2));
+
+        // The chip leader needs far more than a coin flip against the second stack;
+        // Folding gives 0.6 + 0.3 * 6 / 7 + 0.1 * 6 / 9, and losing gives 0.3 + 0.6 * 3 / 4 + 0.1 * 3 / 9
+        let leader = SatelliteAllIn::new(&stacks, 2, 0, 1)?;
+        assert_almost_equal(leader.fold, 0.6 + 0.3 * 6.0 / 7.0 + 0.1 * 6.0 / 9.0);
+        assert_almost_equal(leader.lose, 0.3 + 0.6 * 3.0 / 4.0 + 0.1 * 3.0 / 9.0);
+        assert_almost_equal(leader.win, 1.0);
+        let required = leader.required_equity().unwrap();
+        assert!(required > 0.6);
+        assert_almost_equal(leader.call(required), leader.fold);
+
+        // Busting the shortest stack wins both tickets
+        let second = SatelliteAllIn::new(&stacks, 2, 1, 2)?;
+        assert_almost_equal(second.win, 1.0);
+        assert!(SatelliteAllIn::new(&stacks, 2, 1, 1).is_err());
+        assert!(SatelliteAllIn::new(&stacks, 2, 0, 3).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+//! Jackpot sit & go formats like Spin & Go, where the prize pool
+//! is a random multiple of the buy-in drawn before the game starts.
+//!
+//! Multipliers are relative to the buy-in including rake,
+//! and the whole prize pool goes to the winner.
+
+use crate::bankroll::{simulate_weighted, BankruptcyMetric};
+use crate::errors::PokercraftLocalError;
+
+/// A single row of the multiplier table, with the win rate of the player on it.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct MultiplierTier {
+    /// Prize pool relative to the buy-in, like `2.0` for the smallest spins.
+    pub multiplier: f64,
+    /// Probability of drawing this multiplier.
+    pub probability: f64,
+    /// Probability of the player winning the game on this multiplier.
+    pub win_rate: f64,
+}
+
+/// A jackpot sit & go format with win rates of the player on each multiplier.
+#[derive(PartialEq, Clone, Debug)]
+pub struct JackpotFormat {
+    tiers: Vec<MultiplierTier>,
+}
+
+impl JackpotFormat {
+    /// Create a new format from the given tiers,
+    /// whose probabilities should sum up to 1.
+    pub fn new(tiers: Vec<MultiplierTier>) -> Result<Self, PokercraftLocalError> {
+        if tiers.is_empty() {
+            return Err(PokercraftLocalError::GeneralError(
+                "Multiplier table must not be empty".to_string(),
+            ));
+        }
+        for tier in tiers.iter() {
+            if !(tier.multiplier > 0.0 && tier.multiplier.is_finite()) {
+                return Err(PokercraftLocalError::GeneralError(format!(
+                    "Multiplier must be positive, got {}",
+                    tier.multiplier
+                )));
+            } else if !(0.0..=1.0).contains(&tier.probability)
+                || !(0.0..=1.0).contains(&tier.win_rate)
+            {
+                return Err(PokercraftLocalError::GeneralError(format!(
+                    "Probability and win rate of {}x should be in [0, 1]",
+                    tier.multiplier
+                )));
+            }
+        }
+        let total = tiers.iter().map(|tier| tier.probability).sum::<f64>();
+        if (total - 1.0).abs() > 1e-6 {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Multiplier probabilities should sum up to 1, got {}",
+                total
+            )));
+        }
+        Ok(JackpotFormat { tiers })
+    }
+
+    /// Get all tiers.
+    pub fn tiers(&self) -> &[MultiplierTier] {
+        &self.tiers
+    }
+
+    /// Get `(relative return, probability)` of every outcome of a single game;
+    /// Winning on each multiplier, and losing on any multiplier.
+    pub fn outcomes(&self) -> Vec<(f64, f64)> {
+        let mut outcomes: Vec<(f64, f64)> = self
+            .tiers
+            .iter()
+            .map(|tier| (tier.multiplier - 1.0, tier.probability * tier.win_rate))
+            .collect();
+        let win = outcomes.iter().map(|(_, p)| p).sum::<f64>();
+        outcomes.push((-1.0, 1.0 - win));
+        outcomes
+    }
+
+    /// Get the expected ROI of a single game.
+    pub fn roi(&self) -> f64 {
+        self.outcomes().iter().map(|(r, p)| r * p).sum()
+    }
+
+    /// Get the variance of relative returns of a single game.
+    pub fn variance(&self) -> f64 {
+        let roi = self.roi();
+        self.outcomes()
+            .iter()
+            .map(|(r, p)| (r - roi).powi(2) * p)
+            .sum()
+    }
+
+    /// Get the standard deviation of relative returns of a single game.
+    pub fn standard_deviation(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Get the bankroll in buy-ins to keep the risk of ruin under `risk_of_ruin`,
+    /// with the diffusion approximation `-variance / (2 * roi) * ln(risk_of_ruin)`.
+    /// Returns `None` if ROI is not positive, since ruin is certain in the long run.
+    pub fn required_bankroll(
+        &self,
+        risk_of_ruin: f64,
+    ) -> Result<Option<f64>, PokercraftLocalError> {
+        if !(risk_of_ruin > 0.0 && risk_of_ruin < 1.0) {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Risk of ruin should be in (0, 1), got {}",
+                risk_of_ruin
+            )));
+        }
+        let roi = self.roi();
+        Ok((roi > 0.0).then(|| -self.variance() / (2.0 * roi) * risk_of_ruin.ln()))
+    }
+
+    /// Simulate bankrolls of `initial_capital` buy-ins playing this format,
+    /// like `bankroll::simulate_core` with outcomes of this format.
+    pub fn simulate_bankroll(
+        &self,
+        initial_capital: f64,
+        max_iteration: u32,
+        profit_exit_multiplier: f64,
+        simulation_count: u32,
+    ) -> Result<BankruptcyMetric, PokercraftLocalError> {
+        simulate_weighted(
+            initial_capital,
+            &self.outcomes(),
+            max_iteration,
+            profit_exit_multiplier,
+            simulation_count,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tier(multiplier: f64, probability: f64, win_rate: f64) -> MultiplierTier {
+        MultiplierTier {
+            multiplier,
+            probability,
+            win_rate,
+        }
+    }
+
+    #[test]
+    fn test_jackpot_format() -> Result<(), PokercraftLocalError> {
+        // Expected prize pool is 2.79 buy-ins of 3 players with 7% rake,
+        // and a 40% win rate beats it
+        let format = JackpotFormat::new(vec![
+            tier(2.0, 0.75, 0.4),
+            tier(3.0, 0.2, 0.4),
+            tier(10.0, 0.049, 0.4),
+            tier(200.0, 0.001, 0.4),
+        ])?;
+        let prize_pool = 2.0 * 0.75 + 3.0 * 0.2 + 10.0 * 0.049 + 200.0 * 0.001;
+        assert!((format.roi() - (0.4 * prize_pool - 1.0)).abs() < 1e-9);
+        assert_eq!(format.outcomes().len(), 5);
+        assert!((format.outcomes()[4].1 - 0.6).abs() < 1e-9);
+
+        // Jackpots dominate the variance
+        let second_moment = 0.4 * (4.0 * 0.75 + 9.0 * 0.2 + 100.0 * 0.049 + 4e4 * 0.001);
+        let variance = second_moment - (format.roi() + 1.0).powi(2);
+        assert!((format.variance() - variance).abs() < 1e-6);
+        assert!(format.standard_deviation() > 4.0);
+
+        let bankroll = format.required_bankroll(0.05)?.unwrap();
+        assert!((bankroll - variance / (2.0 * format.roi()) * 20f64.ln()).abs() < 1e-6);
+        let losing = JackpotFormat::new(vec![tier(3.0, 1.0, 0.3)])?;
+        assert_eq!(losing.required_bankroll(0.05)?, None);
+        assert!(format.required_bankroll(1.0).is_err());
+
+        let metric = format.simulate_bankroll(100.0, 200, 3.0, 200)?;
+        assert_eq!(metric.len(), 200);
+        assert!(losing.simulate_bankroll(100.0, 200, 3.0, 200).is_err());
+
+        assert!(JackpotFormat::new(vec![]).is_err());
+        assert!(JackpotFormat::new(vec![tier(2.0, 0.5, 0.4)]).is_err());
+        assert!(JackpotFormat::new(vec![tier(0.0, 1.0, 0.4)]).is_err());
+        assert!(JackpotFormat::new(vec![tier(2.0, 1.0, 1.5)]).is_err());
+        Ok(())
+    }
+}
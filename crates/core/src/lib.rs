@@ -4,13 +4,58 @@
 //! for multiple targets:
 //! - Native Rust library
 //! - WebAssembly module (via wasm-bindgen) with `wasm` feature
+//! - Parquet and Arrow IPC export with `columnar` feature
+//! - Serde (de)serialization of cards, hand ranks and parsed data,
+//!   and standalone HTML reports with `serde` feature
+//! - Embedded SQLite storage with `store` feature
+//! - Embedded heads-up preflop equity cache and 5-card rank tables with `embedded-tables` feature
+//! - Versioned protobuf messages of results with `protobuf` feature
+//! - Portable SIMD batch evaluation of boards with `simd` feature (nightly only)
+//! - GPU (wgpu) Monte Carlo range equities and bankroll simulations with `gpu` feature
+//! - Tracing spans of parsing, enumeration and simulation, logged to stderr, with `tracing` feature
 
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
+pub mod advantage;
 pub mod bankroll;
+pub mod blockers;
 pub mod card;
+pub mod currency;
 pub mod equity;
 pub mod errors;
+pub mod ev;
+pub mod evaluator;
+#[cfg(feature = "columnar")]
+pub mod export;
+pub mod filter;
+pub mod generator;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod icm;
+pub mod jackpot;
+#[cfg(feature = "tracing")]
+pub mod logging;
+pub mod math;
+pub mod parser;
+pub mod pokerstars;
+#[cfg(feature = "protobuf")]
+pub mod proto;
+pub mod range;
+pub mod realization;
+pub mod replay;
+#[cfg(feature = "serde")]
+pub mod report;
+pub mod solver;
+pub mod staking;
+pub mod stats;
+#[cfg(feature = "store")]
+pub mod store;
+pub mod tail;
+pub mod timezone;
 pub mod utils;
 
 // Re-export commonly used types
-pub use card::{Card, CardNumber, CardShape, Hand, HandRank};
+pub use card::{Card, CardNumber, CardShape, CardStyle, Hand, HandRank, RankKey};
+pub use currency::{Currency, CurrencyRateConverter};
 pub use errors::PokercraftLocalError;
+pub use utils::{parallelism, set_parallelism};
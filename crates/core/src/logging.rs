@@ -0,0 +1,74 @@
+//! Logging of `tracing` spans and events of this library to stderr.
+//! Parsing, equity enumeration and bankroll simulation are instrumented,
+//! and spans are logged with their durations when they close,
+//! to diagnose slow imports or mis-parsed files.
+
+use std::io::IsTerminal;
+
+use once_cell::sync::OnceCell;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, Registry};
+
+use crate::errors::PokercraftLocalError;
+
+/// Handle to change the level of the installed subscriber.
+static LEVEL: OnceCell<reload::Handle<LevelFilter, Registry>> = OnceCell::new();
+
+/// Parse a verbosity level: `off`, `error`, `warn`, `info`, `debug` or `trace`.
+pub fn parse_level(level: &str) -> Result<LevelFilter, PokercraftLocalError> {
+    level.parse::<LevelFilter>().map_err(|_| {
+        PokercraftLocalError::GeneralError(format!(
+            "Unknown log level \"{}\"; Should be one of off, error, warn, info, debug or trace",
+            level
+        ))
+    })
+}
+
+/// Log spans and events of the given level or more severe to stderr.
+/// Calling again changes the level of the already installed subscriber.
+/// Fails if another global subscriber was installed by someone else.
+pub fn enable_logging(level: &str) -> Result<(), PokercraftLocalError> {
+    let level = parse_level(level)?;
+    if let Some(handle) = LEVEL.get() {
+        return handle
+            .reload(level)
+            .map_err(|e| PokercraftLocalError::GeneralError(e.to_string()));
+    }
+    let (filter, handle) = reload::Layer::new(level);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_ansi(std::io::stderr().is_terminal())
+                .with_span_events(FmtSpan::CLOSE),
+        )
+        .try_init()
+        .map_err(|e| {
+            PokercraftLocalError::GeneralError(format!("Failed to enable logging: {}", e))
+        })?;
+    let _ = LEVEL.set(handle);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enable_logging() -> Result<(), PokercraftLocalError> {
+        assert_eq!(parse_level("debug")?, LevelFilter::DEBUG);
+        assert_eq!(parse_level("OFF")?, LevelFilter::OFF);
+        assert!(parse_level("verbose").is_err());
+
+        enable_logging("off")?;
+        assert!(!tracing::enabled!(tracing::Level::ERROR));
+        enable_logging("warn")?;
+        assert!(tracing::enabled!(tracing::Level::WARN));
+        assert!(!tracing::enabled!(tracing::Level::INFO));
+        enable_logging("off")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+//! Common math of bet sizings; Pot odds, minimum defense frequency, stack-to-pot ratio
+//! and the expected value of jamming.
+//!
+//! `pot` is always the pot before the bet of concern.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::card::{Card, Hand};
+use crate::equity::HandVsRangeResult;
+use crate::errors::PokercraftLocalError;
+use crate::range::HandRange;
+use crate::utils::binomial;
+
+/// Check that the pot is positive and the bet is not negative.
+fn validate(bet: f64, pot: f64) -> Result<(), PokercraftLocalError> {
+    if !(pot > 0.0 && pot.is_finite()) {
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "Pot must be positive, got {}",
+            pot
+        )));
+    } else if !(bet >= 0.0 && bet.is_finite()) {
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "Bet must not be negative, got {}",
+            bet
+        )));
+    }
+    Ok(())
+}
+
+/// Get the minimum equity to call `bet` into `pot`, which is `bet / (pot + 2 * bet)`.
+/// For example, calling a pot-sized bet requires 33% equity.
+#[cfg_attr(feature = "wasm", wasm_bindgen(js_name = requiredEquity))]
+pub fn required_equity(bet: f64, pot: f64) -> Result<f64, PokercraftLocalError> {
+    validate(bet, pot)?;
+    Ok(bet / (pot + 2.0 * bet))
+}
+
+/// Get the minimum defense frequency against `bet` into `pot`, which is `pot / (pot + bet)`.
+/// Folding more than `1 - MDF` lets any bluff profit immediately.
+#[cfg_attr(feature = "wasm", wasm_bindgen(js_name = minimumDefenseFrequency))]
+pub fn minimum_defense_frequency(bet: f64, pot: f64) -> Result<f64, PokercraftLocalError> {
+    validate(bet, pot)?;
+    Ok(pot / (pot + bet))
+}
+
+/// Get the stack-to-pot ratio of the effective stack.
+#[cfg_attr(feature = "wasm", wasm_bindgen(js_name = stackToPotRatio))]
+pub fn stack_to_pot_ratio(effective_stack: f64, pot: f64) -> Result<f64, PokercraftLocalError> {
+    validate(effective_stack, pot)?;
+    Ok(effective_stack / pot)
+}
+
+/// Expected value of jamming all-in against a single player, in chips relative to folding.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ShoveEv {
+    /// Probability that the villain folds, where the villain holds
+    /// any combo not blocked by the hero hand or the board.
+    pub fold_frequency: f64,
+    /// Equity of the hero hand against the calling range, or `0` if nothing calls.
+    pub called_equity: f64,
+    /// Chips at risk when called, which is the smaller stack.
+    pub risk: f64,
+    /// Expected value when called, `called_equity * (pot + 2 * risk) - risk`.
+    pub called_ev: f64,
+    /// Expected value of jamming, `fold_frequency * pot + (1 - fold_frequency) * called_ev`.
+    pub ev: f64,
+}
+
+/// Get the expected value of jamming `hand` all-in into `pot` on the board (0 to 5 cards),
+/// against a villain calling with `calling_range` and folding everything else.
+/// Stacks are chips behind, excluding chips already in `pot`.
+/// Equities against each calling combo are enumerated exactly if there are
+/// at most `trials` boards, otherwise `trials` boards are sampled.
+pub fn shove_ev(
+    hand: Hand,
+    calling_range: &HandRange,
+    board: &[Card],
+    pot: f64,
+    hero_stack: f64,
+    villain_stack: f64,
+    trials: u64,
+) -> Result<ShoveEv, PokercraftLocalError> {
+    let risk = hero_stack.min(villain_stack);
+    validate(hero_stack, pot)?;
+    validate(villain_stack, pot)?;
+    if risk <= 0.0 {
+        return Err(PokercraftLocalError::GeneralError(
+            "Stacks must be positive to jam".to_string(),
+        ));
+    }
+    let known_cards = [board, &[hand.0, hand.1]].concat();
+    if board.len() > 5 || (1..known_cards.len()).any(|i| known_cards[..i].contains(&known_cards[i]))
+    {
+        return Err(PokercraftLocalError::GeneralError(
+            "Board should have at most 5 cards, without duplicates with the hand".to_string(),
+        ));
+    }
+
+    let callers = calling_range.without_cards(&known_cards);
+    let villain_combos = binomial(52 - known_cards.len() as u64, 2) as f64;
+    let fold_frequency = 1.0 - callers.len() as f64 / villain_combos;
+    let called_equity = if callers.is_empty() {
+        0.0
+    } else {
+        let combos = callers
+            .combos()
+            .iter()
+            .map(|combo| (*combo, 1.0))
+            .collect::<Vec<_>>();
+        HandVsRangeResult::new(hand, &combos, board, trials)?.equity()
+    };
+    let called_ev = called_equity * (pot + 2.0 * risk) - risk;
+    Ok(ShoveEv {
+        fold_frequency,
+        called_equity,
+        risk,
+        called_ev,
+        ev: fold_frequency * pot + (1.0 - fold_frequency) * called_ev,
+    })
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(typescript_custom_section)]
+const TS_SHOVE_EV: &'static str = r#"
+/** Expected value of jamming all-in against a single player, in chips relative to folding. */
+export interface ShoveEv {
+    foldFrequency: number;
+    calledEquity: number;
+    /** Chips at risk when called, which is the smaller stack. */
+    risk: number;
+    calledEv: number;
+    ev: number;
+}
+"#;
+
+/// Get the expected value of jamming `hand` like `"AsKd"` into `pot` on `board` like `"2c7d9h"`,
+/// against a villain calling with `callingRange` like `"QQ+,AK"` (WASM interface).
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = shoveEv, unchecked_return_type = "ShoveEv")]
+pub fn shove_ev_wasm(
+    hand: &str,
+    calling_range: &str,
+    board: &str,
+    pot: f64,
+    hero_stack: f64,
+    villain_stack: f64,
+    trials: u32,
+) -> Result<JsValue, JsValue> {
+    let to_js_error = |e: PokercraftLocalError| JsValue::from_str(&e.to_string());
+    let [c1, c2] = crate::card::parse_cards_exact::<2>(hand).map_err(to_js_error)?;
+    let calling_range = HandRange::try_from(calling_range).map_err(to_js_error)?;
+    let board = crate::card::parse_cards(board).map_err(to_js_error)?;
+    let result = shove_ev(
+        (c1, c2),
+        &calling_range,
+        &board,
+        pot,
+        hero_stack,
+        villain_stack,
+        trials as u64,
+    )
+    .map_err(to_js_error)?;
+    crate::utils::to_js_object(&result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+
+    #[test]
+    fn test_bet_math() -> Result<(), PokercraftLocalError> {
+        assert!((required_equity(100.0, 100.0)? - 1.0 / 3.0).abs() < 1e-9);
+        assert!((required_equity(50.0, 100.0)? - 0.25).abs() < 1e-9);
+        assert_eq!(required_equity(0.0, 100.0)?, 0.0);
+        assert!((minimum_defense_frequency(100.0, 100.0)? - 0.5).abs() < 1e-9);
+        assert!((minimum_defense_frequency(50.0, 100.0)? - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stack_to_pot_ratio(450.0, 100.0)?, 4.5);
+
+        assert!(required_equity(100.0, 0.0).is_err());
+        assert!(minimum_defense_frequency(-1.0, 100.0).is_err());
+        assert!(stack_to_pot_ratio(f64::NAN, 100.0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_shove_ev() -> Result<(), PokercraftLocalError> {
+        let hand = |combo: &str| -> Result<Hand, PokercraftLocalError> {
+            let cards = parse_cards(combo)?;
+            Ok((cards[0], cards[1]))
+        };
+
+        // Aces win 42 of 44 rivers against kings, which are 6 of 1035 villain combos
+        let board = parse_cards("2c 7d 9h 4s")?;
+        let result = shove_ev(
+            hand("AsAh")?,
+            &HandRange::try_from("KK")?,
+            &board,
+            100.0,
+            300.0,
+            200.0,
+            1000,
+        )?;
+        assert!((result.fold_frequency - 1029.0 / 1035.0).abs() < 1e-9);
+        assert!((result.called_equity - 42.0 / 44.0).abs() < 1e-9);
+        assert_eq!(result.risk, 200.0);
+        assert!((result.called_ev - (42.0 / 44.0 * 500.0 - 200.0)).abs() < 1e-9);
+        let expected = 1029.0 / 1035.0 * 100.0 + 6.0 / 1035.0 * result.called_ev;
+        assert!((result.ev - expected).abs() < 1e-9);
+
+        // Nothing calls but the nuts, which are blocked
+        let result = shove_ev(
+            hand("AsAh")?,
+            &HandRange::try_from("AA")?,
+            &parse_cards("Ac Ad 2c 7d 9h")?,
+            100.0,
+            500.0,
+            500.0,
+            0,
+        )?;
+        assert_eq!(result.fold_frequency, 1.0);
+        assert_eq!(result.ev, 100.0);
+
+        // Jamming trash into a deep calling range loses chips
+        let result = shove_ev(
+            hand("7c2d")?,
+            &HandRange::try_from("TT+,AQ+")?,
+            &[],
+            15.0,
+            1000.0,
+            1000.0,
+            500,
+        )?;
+        assert!(result.called_equity < 0.35);
+        assert!(result.ev < 0.0);
+
+        let kings = HandRange::try_from("KK")?;
+        assert!(shove_ev(hand("AsAh")?, &kings, &board, 0.0, 1.0, 1.0, 10).is_err());
+        assert!(shove_ev(hand("AsAh")?, &kings, &board, 1.0, 0.0, 1.0, 10).is_err());
+        assert!(shove_ev(
+            hand("AsAh")?,
+            &kings,
+            &parse_cards("As")?,
+            1.0,
+            1.0,
+            1.0,
+            10
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+//! Anonymization of parsed hands, to share databases or bug reproductions
+//! without leaking identities of opponents.
+//!
+//! Anonymized player IDs are still lowercase hex and table IDs are still digits,
+//! so anonymized hands can be written back and parsed like original exports.
+
+use std::collections::BTreeSet;
+
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::Sha256;
+
+use super::hero::DEFAULT_HERO;
+use super::HandHistory;
+use crate::errors::PokercraftLocalError;
+
+/// How player IDs and table IDs are anonymized.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum AnonymizeMode {
+    /// Replace players by their seat numbers and tables by `0`,
+    /// so IDs don't link the same opponent between hands.
+    Strip,
+    /// Replace players and tables by HMAC-SHA256 hashes keyed by a secret salt,
+    /// so the same opponent is linked between hands but not identified.
+    Hash,
+}
+
+/// Anonymizer of player IDs and table IDs of hands.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Anonymizer {
+    mode: AnonymizeMode,
+    salt: String,
+    kept: BTreeSet<String>,
+}
+
+impl Anonymizer {
+    /// Create an anonymizer keeping the hero, with a random salt of 128 bits.
+    /// Use `with_salt` to link opponents between separately anonymized hands.
+    pub fn new(mode: AnonymizeMode) -> Self {
+        let salt = rand::thread_rng().gen::<[u8; 16]>();
+        Anonymizer {
+            mode,
+            salt: salt.iter().map(|byte| format!("{:02x}", byte)).collect(),
+            kept: BTreeSet::from([DEFAULT_HERO.to_string()]),
+        }
+    }
+
+    /// Key hashes with the given secret salt instead of the random one.
+    /// Anonymized IDs can't be reversed without the salt, so keep it private.
+    /// Ignored in `AnonymizeMode::Strip`.
+    pub fn with_salt(mut self, salt: &str) -> Result<Self, PokercraftLocalError> {
+        if salt.is_empty() {
+            return Err(PokercraftLocalError::GeneralError(
+                "Salt of anonymization should not be empty".to_string(),
+            ));
+        }
+        self.salt = salt.to_string();
+        Ok(self)
+    }
+
+    /// Get the salt keying hashes.
+    pub fn salt(&self) -> &str {
+        &self.salt
+    }
+
+    /// Keep the given player ID as it is, like a canonical ID of the hero.
+    pub fn keeping(mut self, player_id: &str) -> Self {
+        self.kept.insert(player_id.to_string());
+        self
+    }
+
+    /// Anonymize the hero too.
+    pub fn without_kept(mut self) -> Self {
+        self.kept.clear();
+        self
+    }
+
+    /// Get the first 64 bits of HMAC-SHA256 of the given ID, keyed by the salt.
+    fn hash(&self, kind: &str, id: &str) -> u64 {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.salt.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(kind.as_bytes());
+        mac.update(b"\0");
+        mac.update(id.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    }
+
+    /// Get the anonymized ID of the player seated at `seat`.
+    pub fn player_id(&self, player_id: &str, seat: Option<u32>) -> String {
+        if self.kept.contains(player_id) {
+            return player_id.to_string();
+        }
+        match (self.mode, seat) {
+            (AnonymizeMode::Strip, Some(seat)) => format!("{:08x}", seat),
+            // Players not seated don't appear in valid hands
+            (AnonymizeMode::Strip, None) => "0".to_string(),
+            (AnonymizeMode::Hash, _) => format!("{:012x}", self.hash("player", player_id) >> 16),
+        }
+    }
+
+    /// Get the anonymized table ID.
+    pub fn table_id(&self, table_id: &str) -> String {
+        match self.mode {
+            AnonymizeMode::Strip => "0".to_string(),
+            AnonymizeMode::Hash => (self.hash("table", table_id) % 100_000_000).to_string(),
+        }
+    }
+
+    /// Anonymize player IDs and the table ID of the given hand.
+    pub fn apply(&self, hand: &mut HandHistory) {
+        let seats = hand
+            .seats
+            .iter()
+            .map(|(&seat, (player_id, _))| (player_id.clone(), seat))
+            .collect::<Vec<_>>();
+        hand.rename_players(|player_id| {
+            let seat = seats
+                .iter()
+                .find(|(id, _)| id == player_id)
+                .map(|(_, seat)| *seat);
+            self.player_id(player_id, seat)
+        });
+        hand.table_id = self.table_id(&hand.table_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::hand_history::tests::SAMPLE_HAND;
+    use crate::parser::parse_hands;
+    use crate::PokercraftLocalError;
+
+    #[test]
+    fn test_anonymizer() -> Result<(), PokercraftLocalError> {
+        let hand = parse_hands(SAMPLE_HAND.as_bytes())
+            .next()
+            .ok_or_else(|| PokercraftLocalError::GeneralError("No hand".to_string()))??;
+
+        let mut stripped = hand.clone();
+        Anonymizer::new(AnonymizeMode::Strip).apply(&mut stripped);
+        assert_eq!(stripped.table_id, "0");
+        assert_eq!(stripped.seats[&1].0, "00000001");
+        assert_eq!(stripped.seats[&7].0, "Hero");
+        assert_eq!(stripped.net_profit("00000006"), hand.net_profit("471910c"));
+        assert_eq!(stripped.total_pot(), hand.total_pot());
+        assert!(stripped
+            .actions_preflop
+            .iter()
+            .all(|action| action.player_id != "f123395"));
+
+        // Hashes are stable, and depend on the salt
+        let anonymizer = Anonymizer::new(AnonymizeMode::Hash).with_salt("secret")?;
+        let mut hashed = hand.clone();
+        anonymizer.apply(&mut hashed);
+        let id = anonymizer.player_id("471910c", None);
+        assert_eq!(id.len(), 12);
+        assert_eq!(hashed.seat_number(&id)?, 6);
+        assert_eq!(hashed.wons.get(&id), hand.wons.get("471910c"));
+        assert_eq!(
+            Anonymizer::new(AnonymizeMode::Hash)
+                .with_salt("secret")?
+                .player_id("471910c", None),
+            id
+        );
+        assert_ne!(
+            Anonymizer::new(AnonymizeMode::Hash).player_id("471910c", None),
+            id
+        );
+        assert_ne!(
+            Anonymizer::new(AnonymizeMode::Hash).salt(),
+            Anonymizer::new(AnonymizeMode::Hash).salt()
+        );
+        assert!(Anonymizer::new(AnonymizeMode::Hash).with_salt("").is_err());
+        assert_ne!(hashed.table_id, hand.table_id);
+        assert!(hashed.table_id.chars().all(|c| c.is_ascii_digit()));
+        let mut hero_hashed = hand.clone();
+        anonymizer.clone().without_kept().apply(&mut hero_hashed);
+        assert!(hero_hashed.seat_number("Hero").is_err());
+        Ok(())
+    }
+}
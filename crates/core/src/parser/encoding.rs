@@ -0,0 +1,106 @@
+//! Encoding detection for exported files.
+//! Exports differ by client locale; UTF-8 (with or without BOM),
+//! UTF-16 and CP949 (Korean Windows locale) are seen in the wild.
+
+use std::io::{Cursor, Read};
+
+use encoding_rs::{Encoding, EUC_KR, UTF_16BE, UTF_16LE, UTF_8};
+use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
+
+/// A reader which yields UTF-8 bytes decoded from the wrapped reader.
+pub type DecodingReader<R> = DecodeReaderBytes<std::io::Chain<Cursor<Vec<u8>>, R>, Vec<u8>>;
+
+/// Number of leading bytes inspected to detect the encoding.
+const SNIFF_LENGTH: usize = 4096;
+
+/// Detect the encoding of the given leading bytes of a file.
+/// BOM is respected first. Without BOM, UTF-16 is detected by the
+/// distribution of zero bytes, then UTF-8 validity is checked,
+/// and CP949 is used as the last fallback.
+pub fn detect_encoding(prefix: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _bom_length)) = Encoding::for_bom(prefix) {
+        return encoding;
+    }
+
+    // ASCII-heavy text in UTF-16 has zero bytes on every other position
+    let pairs = prefix.len() / 2;
+    if pairs > 0 {
+        let even_zeros = prefix.iter().step_by(2).filter(|&&b| b == 0).count();
+        let odd_zeros = prefix
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .filter(|&&b| b == 0)
+            .count();
+        if odd_zeros * 10 >= pairs * 3 && even_zeros * 10 < pairs {
+            return UTF_16LE;
+        } else if even_zeros * 10 >= pairs * 3 && odd_zeros * 10 < pairs {
+            return UTF_16BE;
+        }
+    }
+
+    match std::str::from_utf8(prefix) {
+        Ok(_) => UTF_8,
+        // Prefix may be truncated in the middle of a multi-byte sequence
+        Err(err) if err.error_len().is_none() => UTF_8,
+        Err(_) => EUC_KR, // `encoding_rs` implements EUC-KR as CP949
+    }
+}
+
+/// Decode the whole bytes into a string, detecting the encoding.
+/// Malformed sequences are replaced with `U+FFFD`.
+pub fn decode_bytes(bytes: &[u8]) -> String {
+    let encoding = detect_encoding(&bytes[..bytes.len().min(SNIFF_LENGTH)]);
+    let (decoded, _encoding, _had_errors) = encoding.decode(bytes);
+    decoded.into_owned()
+}
+
+/// Wrap the given reader so that it yields UTF-8 bytes,
+/// detecting the encoding from the leading bytes.
+/// The BOM is stripped, and malformed sequences are replaced with `U+FFFD`.
+pub fn decoding_reader<R: Read>(mut reader: R) -> std::io::Result<DecodingReader<R>> {
+    let mut prefix = Vec::with_capacity(SNIFF_LENGTH);
+    (&mut reader)
+        .take(SNIFF_LENGTH as u64)
+        .read_to_end(&mut prefix)?;
+    let encoding = detect_encoding(&prefix);
+    Ok(DecodeReaderBytesBuilder::new()
+        .encoding(Some(encoding))
+        .strip_bom(true)
+        .build(Cursor::new(prefix).chain(reader)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "Poker Hand #TM1: 홀덤 토너먼트\nSeat 1: Hero (1,000 in chips)\n";
+
+    #[test]
+    fn test_decode_all_encodings() -> std::io::Result<()> {
+        let utf8 = SAMPLE.as_bytes().to_vec();
+        let utf8_bom = [&[0xEF, 0xBB, 0xBF][..], SAMPLE.as_bytes()].concat();
+        let utf16le_no_bom = SAMPLE
+            .encode_utf16()
+            .flat_map(|u| u.to_le_bytes())
+            .collect::<Vec<_>>();
+        let utf16le = [&[0xFF, 0xFE][..], &utf16le_no_bom].concat();
+        let (cp949, _, had_errors) = EUC_KR.encode(SAMPLE);
+        assert!(!had_errors);
+
+        for (bytes, expected_encoding) in [
+            (utf8, UTF_8),
+            (utf8_bom, UTF_8),
+            (utf16le, UTF_16LE),
+            (utf16le_no_bom, UTF_16LE),
+            (cp949.into_owned(), EUC_KR),
+        ] {
+            assert_eq!(detect_encoding(&bytes), expected_encoding);
+            assert_eq!(decode_bytes(&bytes), SAMPLE);
+            let mut decoded = String::new();
+            decoding_reader(bytes.as_slice())?.read_to_string(&mut decoded)?;
+            assert_eq!(decoded, SAMPLE);
+        }
+        Ok(())
+    }
+}
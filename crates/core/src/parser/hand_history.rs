@@ -0,0 +1,866 @@
+//! Streaming parser for GGPoker hand history files.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::BufRead;
+
+use chrono::NaiveDateTime;
+use once_cell::sync::Lazy;
+use regex::Regex;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+use super::locale::{NumberFormat, NUMBER_PATTERN};
+use crate::card::{Card, Hand};
+use crate::errors::PokercraftLocalError;
+
+static LINE1_INTRO: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^Poker Hand #(TM|BR|SG)(\d+): Tournament #(\d+), (.+) - Level(\d+)\(({NUMBER_PATTERN})/({NUMBER_PATTERN})(?:\({NUMBER_PATTERN}\))?\) - (\d{{4}}/\d{{2}}/\d{{2}} \d{{2}}:\d{{2}}:\d{{2}})$",
+    ))
+    .unwrap()
+});
+static LINE2_TABLE_NUM: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^Table '(\d+)' (\d+)-max Seat #(\d+) is the button$").unwrap());
+static LINE3_SEAT_INFO: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^Seat (\d+): ([0-9a-f]+|Hero) \(({NUMBER_PATTERN}) in chips\)$"
+    ))
+    .unwrap()
+});
+static LINE4_POSTS_DEAD_MONEY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^([0-9a-f]+|Hero): posts (?:the )?(ante|big blind|small blind) ({NUMBER_PATTERN})$"
+    ))
+    .unwrap()
+});
+const LINE5_HOLE_CARDS: &str = "*** HOLE CARDS ***";
+static LINE5_DEALT_TO: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Dealt to ([0-9a-f]+|Hero)(?: \[([2-9AKQJT][sdch]) ([2-9AKQJT][sdch])\])?$")
+        .unwrap()
+});
+static LINE6_HEADER_FLOP: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\*\*\* FLOP \*\*\* \[([2-9AKQJT][sdch]) ([2-9AKQJT][sdch]) ([2-9AKQJT][sdch])\]$")
+        .unwrap()
+});
+static LINE6_HEADER_TURN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\*\*\* TURN \*\*\* \[(?:[2-9AKQJT][sdch] ?){3}\] \[([2-9AKQJT][sdch])\]$")
+        .unwrap()
+});
+static LINE6_HEADER_RIVER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\*\*\* RIVER \*\*\* \[(?:[2-9AKQJT][sdch] ?){4}\] \[([2-9AKQJT][sdch])\]$")
+        .unwrap()
+});
+static LINE6_BETTING_ACTION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^([0-9a-f]+|Hero): (folds|checks|calls ({NUMBER_PATTERN})|raises ({NUMBER_PATTERN}) to ({NUMBER_PATTERN})|bets ({NUMBER_PATTERN}))( and is all-in)?$",
+    ))
+    .unwrap()
+});
+static LINE6_RETURNED_UNCALLED_BET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^Uncalled bet \(({NUMBER_PATTERN})\) returned to ([0-9a-f]+|Hero)$"
+    ))
+    .unwrap()
+});
+static LINE6_SHOWS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([0-9a-f]+|Hero): shows \[([2-9AKQJT][sdch])(?: ([2-9AKQJT][sdch]))?\]").unwrap()
+});
+const LINE7_HEADER_SHOWDOWN: &str = "*** SHOWDOWN ***";
+static LINE7_COLLECTED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^([0-9a-f]+|Hero) collected ({NUMBER_PATTERN}) from pot$"
+    ))
+    .unwrap()
+});
+const LINE8_HEADER_SUMMARY: &str = "*** SUMMARY ***";
+
+/// Check if the given filename is a hand history file.
+/// Short Deck and Omaha hand histories are not supported.
+pub fn is_hand_history_file(filename: &str) -> bool {
+    static FILENAME_PATTERN: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^GG\d{8}-\d{4} - .*\.txt$").unwrap());
+    FILENAME_PATTERN.is_match(filename)
+        && !filename.contains("Short Deck")
+        && !filename.contains("Omaha")
+}
+
+/// Number of continuous blank lines which terminates an unfinished hand.
+const MAX_CONTINUOUS_NEWLINES: usize = 3;
+
+/// Streets of a hand where betting actions can happen.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum HandStage {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl HandStage {
+    /// Return all hand stages in order.
+    pub const fn all() -> [HandStage; 4] {
+        [
+            HandStage::Preflop,
+            HandStage::Flop,
+            HandStage::Turn,
+            HandStage::River,
+        ]
+    }
+}
+
+impl std::fmt::Display for HandStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            HandStage::Preflop => "Preflop",
+            HandStage::Flop => "Flop",
+            HandStage::Turn => "Turn",
+            HandStage::River => "River",
+        };
+        write!(f, "{}", repr)
+    }
+}
+
+/// Types of betting actions.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum BetActionType {
+    Fold,
+    Check,
+    Call,
+    Bet,
+    /// Amount of raise actions is the total "raise to" amount.
+    Raise,
+    Ante,
+    Blind,
+}
+
+/// A single betting action of a player.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct BetAction {
+    /// Player ID or `"Hero"`.
+    pub player_id: String,
+    pub action: BetActionType,
+    pub amount: u64,
+    pub is_all_in: bool,
+}
+
+/// A single parsed hand.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug)]
+pub struct HandHistory {
+    /// Hand ID, like `"TM4832872904"`.
+    pub id: String,
+    pub tournament_id: Option<u64>,
+    pub tournament_name: Option<String>,
+    pub level: u32,
+    pub sb: u64,
+    pub bb: u64,
+    pub datetime: NaiveDateTime,
+    pub button_seat: u32,
+    /// SB seat is optional because of heads-up or dead small blind.
+    pub sb_seat: Option<u32>,
+    pub bb_seat: Option<u32>,
+    pub max_seats: u32,
+    pub table_id: String,
+    /// `{seat number: (player ID, initial chips)}`
+    pub seats: BTreeMap<u32, (String, u64)>,
+    /// `{player ID: hole cards}`, only for the known cards.
+    pub known_cards: HashMap<String, Hand>,
+    /// `{player ID: total amount collected from pots}`
+    pub wons: HashMap<String, u64>,
+    pub community_cards: Vec<Card>,
+    pub actions_preflop: Vec<BetAction>,
+    pub actions_flop: Vec<BetAction>,
+    pub actions_turn: Vec<BetAction>,
+    pub actions_river: Vec<BetAction>,
+    /// `(player ID, amount)` of the uncalled bet returned.
+    pub uncalled_returned: Option<(String, u64)>,
+    /// `{player ID: street where the player went all-in}`
+    pub all_ined: HashMap<String, HandStage>,
+}
+
+impl HandHistory {
+    /// Create an empty hand with given header informations.
+    fn new_empty(
+        id: String,
+        tournament_id: Option<u64>,
+        tournament_name: Option<String>,
+        level: u32,
+        sb: u64,
+        bb: u64,
+        datetime: NaiveDateTime,
+    ) -> Self {
+        HandHistory {
+            id,
+            tournament_id,
+            tournament_name,
+            level,
+            sb,
+            bb,
+            datetime,
+            button_seat: 0,
+            sb_seat: None,
+            bb_seat: None,
+            max_seats: 0,
+            table_id: String::new(),
+            seats: BTreeMap::new(),
+            known_cards: HashMap::new(),
+            wons: HashMap::new(),
+            community_cards: Vec::new(),
+            actions_preflop: Vec::new(),
+            actions_flop: Vec::new(),
+            actions_turn: Vec::new(),
+            actions_river: Vec::new(),
+            uncalled_returned: None,
+            all_ined: HashMap::new(),
+        }
+    }
+
+    /// Get the betting actions on the given street.
+    pub fn actions(&self, stage: HandStage) -> &[BetAction] {
+        match stage {
+            HandStage::Preflop => &self.actions_preflop,
+            HandStage::Flop => &self.actions_flop,
+            HandStage::Turn => &self.actions_turn,
+            HandStage::River => &self.actions_river,
+        }
+    }
+
+    /// Get the seat number of the given player.
+    pub fn seat_number(&self, player_id: &str) -> Result<u32, PokercraftLocalError> {
+        self.seats
+            .iter()
+            .find(|(_, (pid, _))| pid == player_id)
+            .map(|(&seat, _)| seat)
+            .ok_or_else(|| {
+                PokercraftLocalError::GeneralError(format!(
+                    "Player {} is not in hand {}",
+                    player_id, self.id
+                ))
+            })
+    }
+
+    /// Get the initial chips of the given player.
+    pub fn initial_chips(&self, player_id: &str) -> Result<u64, PokercraftLocalError> {
+        let seat = self.seat_number(player_id)?;
+        Ok(self.seats[&seat].1)
+    }
+
+    /// Get the offset of the given player from the button.
+    /// Button is 0, SB is 1, BB is 2, and other seats are negative
+    /// counting counter-clockwise from the button (CO is -1, and so on).
+    pub fn offset_from_button(&self, player_id: &str) -> Result<i32, PokercraftLocalError> {
+        let player_seat = self.seat_number(player_id)?;
+        if Some(player_seat) == self.sb_seat {
+            return Ok(1);
+        } else if Some(player_seat) == self.bb_seat {
+            return Ok(2);
+        }
+
+        // Button seat may be empty (dead button), so start from the button
+        // and visit occupied seats only
+        let mut offset = 0;
+        let mut current_seat = self.button_seat;
+        for _ in 0..=self.max_seats.max(self.seats.len() as u32) {
+            if current_seat == player_seat {
+                return Ok(offset);
+            }
+            current_seat = self
+                .seats
+                .range(..current_seat)
+                .next_back()
+                .or_else(|| self.seats.iter().next_back())
+                .map(|(&seat, _)| seat)
+                .unwrap_or(player_seat);
+            offset -= 1;
+        }
+        Err(PokercraftLocalError::GeneralError(format!(
+            "Failed to find offset of {} from the button in hand {}",
+            player_id, self.id
+        )))
+    }
+
+    /// Get the effective stack of the given player at the start of this hand;
+    /// The smaller one of the player's stack and the biggest opponent stack.
+    pub fn effective_stack(&self, player_id: &str) -> Result<u64, PokercraftLocalError> {
+        let my_chips = self.initial_chips(player_id)?;
+        let biggest_opponent = self
+            .seats
+            .values()
+            .filter(|(pid, _)| pid != player_id)
+            .map(|(_, chips)| *chips)
+            .max()
+            .unwrap_or(0);
+        Ok(my_chips.min(biggest_opponent))
+    }
+
+    /// Get the total amount of chips collected from pots.
+    pub fn total_pot(&self) -> u64 {
+        self.wons.values().sum()
+    }
+
+    /// Get the total amount of chips the given player put into pots,
+    /// excluding the returned uncalled bet.
+    pub fn total_chips_put(&self, player_id: &str) -> u64 {
+        let mut total: u64 = 0;
+        for stage in HandStage::all() {
+            let mut latest_bet: u64 = 0;
+            for action in self
+                .actions(stage)
+                .iter()
+                .filter(|action| action.player_id == player_id)
+            {
+                match action.action {
+                    BetActionType::Fold | BetActionType::Check => {}
+                    BetActionType::Ante => total += action.amount,
+                    BetActionType::Call | BetActionType::Bet | BetActionType::Blind => {
+                        latest_bet += action.amount
+                    }
+                    BetActionType::Raise => latest_bet = action.amount,
+                }
+            }
+            total += latest_bet;
+        }
+        if let Some((pid, amount)) = &self.uncalled_returned {
+            if pid == player_id {
+                total = total.saturating_sub(*amount);
+            }
+        }
+        total
+    }
+
+    /// Get the net profit of the given player in this hand.
+    pub fn net_profit(&self, player_id: &str) -> i64 {
+        self.wons.get(player_id).copied().unwrap_or(0) as i64
+            - self.total_chips_put(player_id) as i64
+    }
+
+    /// Rename every player of the hand by the given function of the player ID.
+    /// The function should map different players to different IDs.
+    pub fn rename_players<F>(&mut self, mut rename: F)
+    where
+        F: FnMut(&str) -> String,
+    {
+        for (player_id, _) in self.seats.values_mut() {
+            *player_id = rename(player_id);
+        }
+        for action in self
+            .actions_preflop
+            .iter_mut()
+            .chain(self.actions_flop.iter_mut())
+            .chain(self.actions_turn.iter_mut())
+            .chain(self.actions_river.iter_mut())
+        {
+            action.player_id = rename(&action.player_id);
+        }
+        if let Some((player_id, _)) = self.uncalled_returned.as_mut() {
+            *player_id = rename(player_id);
+        }
+        self.known_cards = std::mem::take(&mut self.known_cards)
+            .into_iter()
+            .map(|(player_id, cards)| (rename(&player_id), cards))
+            .collect();
+        self.wons = std::mem::take(&mut self.wons)
+            .into_iter()
+            .map(|(player_id, won)| (rename(&player_id), won))
+            .collect();
+        self.all_ined = std::mem::take(&mut self.all_ined)
+            .into_iter()
+            .map(|(player_id, stage)| (rename(&player_id), stage))
+            .collect();
+    }
+
+    /// Fix all-in flags of short antes and blinds,
+    /// since those are not marked as all-in in the raw text.
+    fn postprocess(&mut self) {
+        let max_ante = self
+            .actions_preflop
+            .iter()
+            .filter(|action| action.action == BetActionType::Ante)
+            .map(|action| action.amount)
+            .max()
+            .unwrap_or(0);
+
+        let mut short_blinds: Vec<usize> = Vec::new();
+        for (i, action) in self.actions_preflop.iter().enumerate() {
+            if action.action == BetActionType::Blind {
+                if let Ok(seat) = self.seat_number(&action.player_id) {
+                    let is_sb_short = self.sb_seat == Some(seat) && self.sb > action.amount;
+                    let is_bb_short = self.bb_seat == Some(seat) && self.bb > action.amount;
+                    if is_sb_short || is_bb_short {
+                        short_blinds.push(i);
+                    }
+                }
+            }
+        }
+
+        for (i, action) in self.actions_preflop.iter_mut().enumerate() {
+            if (action.action == BetActionType::Ante && action.amount < max_ante)
+                || short_blinds.contains(&i)
+            {
+                action.is_all_in = true;
+            }
+        }
+    }
+}
+
+/// Parsing stages of a single hand.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum ParsingStage {
+    Street(HandStage),
+    Showdown,
+}
+
+/// An iterator which parses hands from a reader line by line.
+/// Only a single hand is held in memory at a time,
+/// so this can be used on arbitrarily large files.
+///
+/// When a line in a hand is malformed, an error is yielded
+/// and the hand is discarded; Parsing resumes from the next hand.
+/// Unfinished hands (without summary section) are silently discarded.
+pub struct HandHistoryIterator<R> {
+    reader: R,
+    buffer: String,
+    line_number: usize,
+    current: Option<HandHistory>,
+    stage: ParsingStage,
+    continuous_newlines: usize,
+    finished: bool,
+    number_format: NumberFormat,
+}
+
+impl<R: BufRead> HandHistoryIterator<R> {
+    /// Create a new iterator over the given reader.
+    pub fn new(reader: R) -> Self {
+        HandHistoryIterator {
+            reader,
+            buffer: String::new(),
+            line_number: 0,
+            current: None,
+            stage: ParsingStage::Street(HandStage::Preflop),
+            continuous_newlines: 0,
+            finished: false,
+            number_format: NumberFormat::default(),
+        }
+    }
+
+    /// Parse chip amounts in the given number format,
+    /// for exports from clients in other locales.
+    pub fn with_number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = number_format;
+        self
+    }
+
+    /// Create an error describing the current line.
+    fn line_error(&self, message: &str) -> PokercraftLocalError {
+        PokercraftLocalError::GeneralError(format!("{}: {}", message, self.buffer.trim()))
+    }
+
+    /// Process the current line in the buffer.
+    /// Returns a complete hand if the line finishes the hand.
+    fn process_line(&mut self) -> Result<Option<HandHistory>, PokercraftLocalError> {
+        let line = self.buffer.trim();
+        if line.is_empty() {
+            self.continuous_newlines += 1;
+            if self.continuous_newlines >= MAX_CONTINUOUS_NEWLINES {
+                self.current = None;
+            }
+            return Ok(None);
+        }
+        self.continuous_newlines = 0;
+
+        if let Some(caps) = LINE1_INTRO.captures(line) {
+            let datetime = NaiveDateTime::parse_from_str(&caps[8], "%Y/%m/%d %H:%M:%S")
+                .map_err(|_| self.line_error("Invalid datetime"))?;
+            let level = caps[5]
+                .parse::<u32>()
+                .map_err(|_| self.line_error("Invalid level"))?;
+            self.current = Some(HandHistory::new_empty(
+                format!("{}{}", &caps[1], &caps[2]),
+                Some(self.number_format.parse_int(&caps[3])?),
+                Some(caps[4].to_string()),
+                level,
+                self.number_format.parse_int(&caps[6])?,
+                self.number_format.parse_int(&caps[7])?,
+                datetime,
+            ));
+            self.stage = ParsingStage::Street(HandStage::Preflop);
+            return Ok(None);
+        }
+
+        let stage = self.stage;
+        let Some(hand) = self.current.as_mut() else {
+            // Lines outside of hands are ignored
+            return Ok(None);
+        };
+        let is_preflop = stage == ParsingStage::Street(HandStage::Preflop);
+
+        if let Some(caps) = LINE2_TABLE_NUM.captures(line) {
+            if is_preflop {
+                hand.table_id = caps[1].to_string();
+                hand.max_seats = self.number_format.parse_int(&caps[2])? as u32;
+                hand.button_seat = self.number_format.parse_int(&caps[3])? as u32;
+            }
+        } else if let Some(caps) = LINE3_SEAT_INFO.captures(line) {
+            if is_preflop {
+                let seat = self.number_format.parse_int(&caps[1])? as u32;
+                let chips = self.number_format.parse_int(&caps[3])?;
+                hand.seats.insert(seat, (caps[2].to_string(), chips));
+            }
+        } else if let Some(caps) = LINE4_POSTS_DEAD_MONEY.captures(line) {
+            if is_preflop {
+                let player_id = caps[1].to_string();
+                let amount = self.number_format.parse_int(&caps[3])?;
+                let action = match &caps[2] {
+                    "ante" => BetActionType::Ante,
+                    _ => BetActionType::Blind,
+                };
+                match &caps[2] {
+                    "small blind" => hand.sb_seat = Some(hand.seat_number(&player_id)?),
+                    "big blind" => hand.bb_seat = Some(hand.seat_number(&player_id)?),
+                    _ => {}
+                }
+                hand.actions_preflop.push(BetAction {
+                    player_id,
+                    action,
+                    amount,
+                    is_all_in: false,
+                });
+            }
+        } else if line == LINE5_HOLE_CARDS {
+            // Just a marker
+        } else if let Some(caps) = LINE5_DEALT_TO.captures(line) {
+            if is_preflop {
+                if let (Some(c1), Some(c2)) = (caps.get(2), caps.get(3)) {
+                    let hole = (Card::try_from(c1.as_str())?, Card::try_from(c2.as_str())?);
+                    hand.known_cards.insert(caps[1].to_string(), hole);
+                }
+            }
+        } else if let Some(caps) = LINE6_HEADER_FLOP.captures(line) {
+            if is_preflop {
+                for i in 1..=3 {
+                    hand.community_cards.push(Card::try_from(&caps[i])?);
+                }
+                self.stage = ParsingStage::Street(HandStage::Flop);
+            }
+        } else if let Some(caps) = LINE6_HEADER_TURN.captures(line) {
+            if stage == ParsingStage::Street(HandStage::Flop) {
+                hand.community_cards.push(Card::try_from(&caps[1])?);
+                self.stage = ParsingStage::Street(HandStage::Turn);
+            }
+        } else if let Some(caps) = LINE6_HEADER_RIVER.captures(line) {
+            if stage == ParsingStage::Street(HandStage::Turn) {
+                hand.community_cards.push(Card::try_from(&caps[1])?);
+                self.stage = ParsingStage::Street(HandStage::River);
+            }
+        } else if let Some(caps) = LINE6_BETTING_ACTION.captures(line) {
+            if let ParsingStage::Street(street) = stage {
+                let player_id = caps[1].to_string();
+                let is_all_in = caps.get(7).is_some();
+                let (action, amount) = if let Some(amount) = caps.get(3) {
+                    (
+                        BetActionType::Call,
+                        self.number_format.parse_int(amount.as_str())?,
+                    )
+                } else if let Some(amount) = caps.get(5) {
+                    (
+                        BetActionType::Raise,
+                        self.number_format.parse_int(amount.as_str())?,
+                    )
+                } else if let Some(amount) = caps.get(6) {
+                    (
+                        BetActionType::Bet,
+                        self.number_format.parse_int(amount.as_str())?,
+                    )
+                } else if &caps[2] == "folds" {
+                    (BetActionType::Fold, 0)
+                } else {
+                    (BetActionType::Check, 0)
+                };
+                if is_all_in {
+                    hand.all_ined.entry(player_id.clone()).or_insert(street);
+                }
+                let action = BetAction {
+                    player_id,
+                    action,
+                    amount,
+                    is_all_in,
+                };
+                match street {
+                    HandStage::Preflop => hand.actions_preflop.push(action),
+                    HandStage::Flop => hand.actions_flop.push(action),
+                    HandStage::Turn => hand.actions_turn.push(action),
+                    HandStage::River => hand.actions_river.push(action),
+                }
+            }
+        } else if let Some(caps) = LINE6_RETURNED_UNCALLED_BET.captures(line) {
+            let amount = self.number_format.parse_int(&caps[1])?;
+            hand.uncalled_returned = Some((caps[2].to_string(), amount));
+        } else if let Some(caps) = LINE6_SHOWS.captures(line) {
+            if let (Some(c1), Some(c2)) = (caps.get(2), caps.get(3)) {
+                if !hand.known_cards.contains_key(&caps[1]) {
+                    let hole = (Card::try_from(c1.as_str())?, Card::try_from(c2.as_str())?);
+                    hand.known_cards.insert(caps[1].to_string(), hole);
+                }
+            }
+        } else if line == LINE7_HEADER_SHOWDOWN {
+            self.stage = ParsingStage::Showdown;
+        } else if let Some(caps) = LINE7_COLLECTED.captures(line) {
+            if stage == ParsingStage::Showdown {
+                let amount = self.number_format.parse_int(&caps[2])?;
+                *hand.wons.entry(caps[1].to_string()).or_insert(0) += amount;
+            }
+        } else if line == LINE8_HEADER_SUMMARY {
+            let mut hand = self.current.take().unwrap();
+            hand.postprocess();
+            return Ok(Some(hand));
+        }
+        // Other lines (including summary details) are ignored
+        Ok(None)
+    }
+}
+
+impl<R: BufRead> Iterator for HandHistoryIterator<R> {
+    type Item = Result<HandHistory, PokercraftLocalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            self.buffer.clear();
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => {
+                    self.finished = true;
+                }
+                Ok(_) => {
+                    self.line_number += 1;
+                    match self.process_line() {
+                        Ok(Some(hand)) => return Some(Ok(hand)),
+                        Ok(None) => {}
+                        Err(PokercraftLocalError::GeneralError(message)) => {
+                            self.current = None;
+                            return Some(Err(PokercraftLocalError::GeneralError(format!(
+                                "Line {}: {}",
+                                self.line_number, message
+                            ))));
+                        }
+                        Err(err) => {
+                            self.current = None;
+                            return Some(Err(err));
+                        }
+                    }
+                }
+                Err(err) => {
+                    self.finished = true;
+                    return Some(Err(err.into()));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Parse hands from the given reader lazily.
+/// See `HandHistoryIterator` for details.
+pub fn parse_hands<R: BufRead>(reader: R) -> HandHistoryIterator<R> {
+    HandHistoryIterator::new(reader)
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(typescript_custom_section)]
+const TS_HAND_HISTORY: &'static str = r#"
+export type HandStage = "Preflop" | "Flop" | "Turn" | "River";
+export type BetActionType = "Fold" | "Check" | "Call" | "Bet" | "Raise" | "Ante" | "Blind";
+
+/** A single betting action of a player. */
+export interface BetAction {
+    /** Player ID or `"Hero"`. */
+    player_id: string;
+    action: BetActionType;
+    /** Total "raise to" amount for raises. */
+    amount: number;
+    is_all_in: boolean;
+}
+
+/** A single parsed hand. */
+export interface HandHistory {
+    /** Hand ID, like `"TM4832872904"`. */
+    id: string;
+    tournament_id: number | null;
+    tournament_name: string | null;
+    level: number;
+    sb: number;
+    bb: number;
+    /** Local datetime, like `"2025-08-01T00:53:29"`. */
+    datetime: string;
+    button_seat: number;
+    sb_seat: number | null;
+    bb_seat: number | null;
+    max_seats: number;
+    table_id: string;
+    /** `{seat number: [player ID, initial chips]}` */
+    seats: Record<string, [string, number]>;
+    /** `{player ID: hole cards}`, only for the known cards. */
+    known_cards: Record<string, [string, string]>;
+    /** `{player ID: total amount collected from pots}` */
+    wons: Record<string, number>;
+    community_cards: string[];
+    actions_preflop: BetAction[];
+    actions_flop: BetAction[];
+    actions_turn: BetAction[];
+    actions_river: BetAction[];
+    /** `[player ID, amount]` of the uncalled bet returned. */
+    uncalled_returned: [string, number] | null;
+    /** `{player ID: street where the player went all-in}` */
+    all_ined: Record<string, HandStage>;
+}
+"#;
+
+/// Parse all hands from the hand history text into plain objects (WASM interface).
+/// Unparsable hands are skipped.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = parseHands, unchecked_return_type = "HandHistory[]")]
+pub fn parse_hands_wasm(hand_history_text: &str) -> Result<JsValue, JsValue> {
+    let hands = parse_hands(hand_history_text.as_bytes())
+        .flatten()
+        .collect::<Vec<_>>();
+    crate::utils::to_js_object(&hands)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const SAMPLE_HAND: &str = "Poker Hand #TM4832872904: Tournament #220597937, Zodiac Dog Ultra Deepstack 7-Max ¥110 [Turbo] Hold'em No Limit - Level16(600/1,200) - 2025/08/01 00:53:29
+Table '25' 7-max Seat #7 is the button
+Seat 1: f123395 (43,493 in chips)
+Seat 2: 392ff24f (17,160 in chips)
+Seat 3: 84466c0e (59,654 in chips)
+Seat 4: afc7064f (36,309 in chips)
+Seat 5: de10679c (20,504 in chips)
+Seat 6: 471910c (31,717 in chips)
+Seat 7: Hero (22,175 in chips)
+392ff24f: posts the ante 150
+84466c0e: posts the ante 150
+afc7064f: posts the ante 150
+471910c: posts the ante 150
+de10679c: posts the ante 150
+Hero: posts the ante 150
+f123395: posts the ante 150
+f123395: posts small blind 600
+392ff24f: posts big blind 1,200
+*** HOLE CARDS ***
+Dealt to f123395
+Dealt to 392ff24f
+Dealt to 84466c0e
+Dealt to afc7064f
+Dealt to de10679c
+Dealt to 471910c
+Dealt to Hero [Jh 8c]
+84466c0e: folds
+afc7064f: raises 1,320 to 2,520
+de10679c: folds
+471910c: calls 2,520
+Hero: folds
+f123395: folds
+392ff24f: calls 1,320
+*** FLOP *** [4c 3d As]
+392ff24f: checks
+afc7064f: checks
+471910c: checks
+*** TURN *** [4c 3d As] [Jd]
+392ff24f: checks
+afc7064f: checks
+471910c: bets 4,800
+392ff24f: folds
+afc7064f: folds
+Uncalled bet (4,800) returned to 471910c
+*** SHOWDOWN ***
+471910c collected 9,210 from pot
+*** SUMMARY ***
+Total pot 9,210 | Rake 0 | Jackpot 0 | Bingo 0 | Fortune 0 | Tax 0
+Board [4c 3d As Jd]
+Seat 1: f123395 (small blind) folded before Flop
+Seat 6: 471910c won (9,210)
+Seat 7: Hero (button) folded before Flop
+";
+
+    #[test]
+    fn test_is_hand_history_file() {
+        assert!(is_hand_history_file(
+            "GG20250801-0012 - Some Tournament.txt"
+        ));
+        assert!(!is_hand_history_file(
+            "GG20250801-0012 - Short Deck Tournament.txt"
+        ));
+        assert!(!is_hand_history_file(
+            "GG20250801-0012 - Omaha Tournament.txt"
+        ));
+        assert!(!is_hand_history_file(
+            "GG20250801 - Tournament #12345 - Summary.txt"
+        ));
+    }
+
+    #[test]
+    fn test_parse_single_hand() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HAND.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(hands.len(), 1);
+        let hand = &hands[0];
+        assert_eq!(hand.id, "TM4832872904");
+        assert_eq!(hand.tournament_id, Some(220597937));
+        assert_eq!((hand.level, hand.sb, hand.bb), (16, 600, 1200));
+        assert_eq!(hand.button_seat, 7);
+        assert_eq!((hand.sb_seat, hand.bb_seat), (Some(1), Some(2)));
+        assert_eq!(hand.seats.len(), 7);
+        assert_eq!(hand.initial_chips("Hero")?, 22175);
+        assert_eq!(
+            hand.known_cards.get("Hero"),
+            Some(&(Card::try_from("Jh")?, Card::try_from("8c")?))
+        );
+        assert_eq!(hand.community_cards.len(), 4);
+        assert_eq!(hand.actions_preflop.len(), 9 + 7);
+        assert_eq!(hand.actions_flop.len(), 3);
+        assert_eq!(hand.actions_turn.len(), 5);
+        assert!(hand.actions_river.is_empty());
+        assert_eq!(hand.total_pot(), 9210);
+        assert_eq!(hand.net_profit("471910c"), 9210 - 150 - 2520);
+        assert_eq!(hand.net_profit("392ff24f"), -150 - 2520);
+        assert_eq!(hand.net_profit("Hero"), -150);
+
+        // Exports of clients in European locales
+        let european = Regex::new(r"(\d),(\d)")
+            .unwrap()
+            .replace_all(SAMPLE_HAND, "$1.$2");
+        assert!(parse_hands(european.as_bytes()).all(|result| result.is_err()));
+        let hands = parse_hands(european.as_bytes())
+            .with_number_format(NumberFormat::european())
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(hands.len(), 1);
+        assert_eq!((hands[0].sb, hands[0].bb), (600, 1200));
+        assert_eq!(hands[0].initial_chips("Hero")?, 22175);
+        assert_eq!(hands[0].total_pot(), hand.total_pot());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_multiple_hands_with_garbage() -> Result<(), PokercraftLocalError> {
+        let content = format!(
+            "{}\n\n{}\n\n\nsome garbage\n{}",
+            SAMPLE_HAND,
+            SAMPLE_HAND.split("*** SUMMARY ***").next().unwrap(),
+            SAMPLE_HAND
+        );
+        let hands = parse_hands(content.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(hands.len(), 2);
+
+        let broken = SAMPLE_HAND.replace("Seat 2: 392ff24f (17,160 in chips)\n", "");
+        let content = format!("{}\n\n{}", broken, SAMPLE_HAND);
+        let results = parse_hands(content.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_err());
+        assert!(results[1].is_ok());
+        Ok(())
+    }
+}
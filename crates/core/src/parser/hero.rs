@@ -0,0 +1,176 @@
+//! Detection of the hero in hand histories, and merging of multiple accounts of the hero.
+//!
+//! Pokercraft exports name the hero `"Hero"`, but hands from other accounts
+//! or other sources may name the same user differently. Renaming every alias
+//! to a single canonical ID lets all stats and aggregations merge those hands.
+
+use std::collections::{BTreeSet, HashMap};
+
+use super::HandHistory;
+use crate::errors::PokercraftLocalError;
+
+/// Player ID of the hero in Pokercraft exports.
+pub const DEFAULT_HERO: &str = "Hero";
+
+/// Detect player IDs of the hero from the given hands,
+/// ordered by the number of hands the player was dealt in, descending.
+///
+/// Hole cards of the hero are known in every hand, while hole cards of
+/// opponents are known only at showdowns, where the hero's cards are known too.
+/// So heroes are players whose hole cards are the only known cards in some hand.
+pub fn detect_heroes(hands: &[HandHistory]) -> Vec<String> {
+    let mut sole_known: HashMap<&str, usize> = HashMap::new();
+    for hand in hands.iter() {
+        if hand.known_cards.len() == 1 {
+            for player_id in hand.known_cards.keys() {
+                *sole_known.entry(player_id.as_str()).or_default() += 1;
+            }
+        }
+    }
+    let mut dealt: HashMap<&str, usize> = HashMap::new();
+    for hand in hands.iter() {
+        for (player_id, _) in hand.seats.values() {
+            if sole_known.contains_key(player_id.as_str()) {
+                *dealt.entry(player_id.as_str()).or_default() += 1;
+            }
+        }
+    }
+    let mut heroes = dealt.into_iter().collect::<Vec<_>>();
+    heroes.sort_by(|(id1, count1), (id2, count2)| count2.cmp(count1).then(id1.cmp(id2)));
+    heroes.into_iter().map(|(id, _)| id.to_string()).collect()
+}
+
+/// Player IDs of all accounts of the hero, merged into a single canonical ID.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct HeroAliases {
+    canonical: String,
+    aliases: BTreeSet<String>,
+}
+
+impl Default for HeroAliases {
+    fn default() -> Self {
+        HeroAliases::new(DEFAULT_HERO)
+    }
+}
+
+impl HeroAliases {
+    /// Create without any aliases.
+    pub fn new(canonical: &str) -> Self {
+        HeroAliases {
+            canonical: canonical.to_string(),
+            aliases: BTreeSet::new(),
+        }
+    }
+
+    /// Add an alias of the hero.
+    pub fn with_alias(mut self, alias: &str) -> Self {
+        if alias != self.canonical {
+            self.aliases.insert(alias.to_string());
+        }
+        self
+    }
+
+    /// Add heroes detected from the given hands as aliases.
+    /// See `detect_heroes` for details.
+    pub fn with_detected(self, hands: &[HandHistory]) -> Self {
+        detect_heroes(hands)
+            .iter()
+            .fold(self, |aliases, hero| aliases.with_alias(hero))
+    }
+
+    /// Get the canonical player ID of the hero.
+    pub fn canonical(&self) -> &str {
+        &self.canonical
+    }
+
+    /// Get aliases of the hero, except the canonical ID.
+    pub fn aliases(&self) -> impl Iterator<Item = &str> {
+        self.aliases.iter().map(String::as_str)
+    }
+
+    /// Check if the given player ID belongs to the hero.
+    pub fn is_hero(&self, player_id: &str) -> bool {
+        player_id == self.canonical || self.aliases.contains(player_id)
+    }
+
+    /// Rename all aliases of the hero in the given hand to the canonical ID.
+    /// Fails if multiple accounts of the hero are seated in the hand,
+    /// in which case the hand is not modified.
+    pub fn apply(&self, hand: &mut HandHistory) -> Result<(), PokercraftLocalError> {
+        let accounts = hand
+            .seats
+            .values()
+            .filter(|(player_id, _)| self.is_hero(player_id))
+            .count();
+        if accounts > 1 {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Multiple accounts of the hero are seated in hand {}",
+                hand.id
+            )));
+        }
+
+        hand.rename_players(|player_id| {
+            if self.aliases.contains(player_id) {
+                self.canonical.clone()
+            } else {
+                player_id.to_string()
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::hand_history::tests::SAMPLE_HAND;
+    use crate::parser::{parse_hands, ParseResult};
+
+    #[test]
+    fn test_hero_aliases() -> Result<(), PokercraftLocalError> {
+        let hand = parse_hands(SAMPLE_HAND.as_bytes())
+            .next()
+            .ok_or_else(|| PokercraftLocalError::GeneralError("No hand".to_string()))??;
+        assert_eq!(detect_heroes(std::slice::from_ref(&hand)), vec!["Hero"]);
+
+        // Another account of the hero, which is seen as a hashed ID
+        let mut other = hand.clone();
+        HeroAliases::new("abcdef")
+            .with_alias("Hero")
+            .apply(&mut other)?;
+        assert!(other.known_cards.contains_key("abcdef"));
+        assert_eq!(other.seat_number("abcdef")?, 7);
+        assert_eq!(detect_heroes(&[hand.clone(), other.clone()]).len(), 2);
+
+        let aliases = HeroAliases::default().with_detected(&[hand.clone(), other.clone()]);
+        assert_eq!(aliases.aliases().collect::<Vec<_>>(), vec!["abcdef"]);
+        assert!(aliases.is_hero("abcdef") && aliases.is_hero("Hero"));
+        assert!(!aliases.is_hero("f123395"));
+        aliases.apply(&mut other)?;
+        assert_eq!(other.seat_number("Hero")?, 7);
+        assert!(other.seat_number("abcdef").is_err());
+        assert_eq!(other.net_profit("Hero"), hand.net_profit("Hero"));
+        assert_eq!(other.known_cards.get("Hero"), hand.known_cards.get("Hero"));
+
+        // Two accounts of the hero can't be seated at the same table
+        let mut conflicting = hand.clone();
+        let before = conflicting.seats.clone();
+        assert!(HeroAliases::default()
+            .with_alias("f123395")
+            .apply(&mut conflicting)
+            .is_err());
+        assert_eq!(conflicting.seats, before);
+
+        // Such hands are moved into errors
+        let mut conflicting = hand.clone();
+        conflicting.seats.entry(1).or_default().0 = "abcdef".to_string();
+        let mut result = ParseResult {
+            hand_histories: vec![hand.clone(), other, conflicting],
+            ..Default::default()
+        };
+        result.merge_hero_accounts(&HeroAliases::default().with_alias("abcdef"));
+        assert_eq!(result.hand_histories.len(), 2);
+        assert_eq!(result.errors.len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,582 @@
+//! Ingestion of Pokercraft exports from `.zip` bundles, `.txt` files, and directories.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+use super::anonymize::Anonymizer;
+use super::encoding::{decode_bytes, decoding_reader};
+use super::hand_history::{is_hand_history_file, parse_hands, HandHistory};
+use super::hero::HeroAliases;
+use super::locale::NumberFormat;
+use super::registry::PlayerRegistry;
+use super::tournament_summary::{
+    is_tournament_summary_file, parse_tournament_summary_with, TournamentSummary,
+};
+use crate::errors::PokercraftLocalError;
+
+/// Kinds of files exported from Pokercraft.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum ExportFileKind {
+    HandHistory,
+    TournamentSummary,
+}
+
+impl ExportFileKind {
+    /// Detect the kind of the given file from its name (not a full path).
+    pub fn detect(filename: &str) -> Option<Self> {
+        if is_tournament_summary_file(filename) {
+            Some(ExportFileKind::TournamentSummary)
+        } else if is_hand_history_file(filename) {
+            Some(ExportFileKind::HandHistory)
+        } else {
+            None
+        }
+    }
+
+    /// Detect the kind of the given export from its decoded content,
+    /// for files whose names do not tell it, like renamed exports.
+    pub fn detect_content(content: &str) -> Option<Self> {
+        let first_line = content
+            .lines()
+            .map(|line| line.trim_start_matches('\u{feff}').trim())
+            .find(|line| !line.is_empty())?;
+        if first_line.starts_with("Poker Hand #")
+            && !first_line.contains("Short Deck")
+            && !first_line.contains("Omaha")
+        {
+            Some(ExportFileKind::HandHistory)
+        } else if first_line.starts_with("Tournament #") {
+            Some(ExportFileKind::TournamentSummary)
+        } else {
+            None
+        }
+    }
+}
+
+/// A single item produced while ingesting exports.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug)]
+pub enum ParsedItem {
+    HandHistory(Box<HandHistory>),
+    TournamentSummary(TournamentSummary),
+    /// Non-fatal error on a single file or hand, prefixed by the file name.
+    Error(String),
+}
+
+/// Collected results of ingesting exports.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Default)]
+pub struct ParseResult {
+    pub tournaments: Vec<TournamentSummary>,
+    pub hand_histories: Vec<HandHistory>,
+    pub errors: Vec<String>,
+}
+
+impl ParseResult {
+    /// Push a single parsed item into the corresponding list.
+    pub fn push(&mut self, item: ParsedItem) {
+        match item {
+            ParsedItem::HandHistory(hand) => self.hand_histories.push(*hand),
+            ParsedItem::TournamentSummary(summary) => self.tournaments.push(summary),
+            ParsedItem::Error(error) => self.errors.push(error),
+        }
+    }
+
+    /// Rename all accounts of the hero to the canonical ID, so stats merge them.
+    /// Hands where multiple accounts of the hero are seated are moved into errors.
+    pub fn merge_hero_accounts(&mut self, aliases: &HeroAliases) {
+        let mut errors = Vec::new();
+        self.hand_histories
+            .retain_mut(|hand| match aliases.apply(hand) {
+                Ok(()) => true,
+                Err(err) => {
+                    errors.push(err.to_string());
+                    false
+                }
+            });
+        self.errors.extend(errors);
+    }
+
+    /// Anonymize player IDs and table IDs of all hands, to share the result.
+    pub fn anonymize(&mut self, anonymizer: &Anonymizer) {
+        for hand in self.hand_histories.iter_mut() {
+            anonymizer.apply(hand);
+        }
+    }
+
+    /// Rename players of all hands from the given site to their internal player IDs
+    /// of the registry, so stats of opponents survive nickname changes.
+    pub fn register_players(&mut self, registry: &mut PlayerRegistry, site: &str) {
+        for hand in self.hand_histories.iter_mut() {
+            registry.apply(site, hand);
+        }
+    }
+
+    /// Serialize into a JSON string, to cache parsed results.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, PokercraftLocalError> {
+        serde_json::to_string(self).map_err(|e| {
+            PokercraftLocalError::GeneralError(format!("Failed to serialize into JSON: {}", e))
+        })
+    }
+
+    /// Deserialize from a JSON string made by `to_json`.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, PokercraftLocalError> {
+        serde_json::from_str(json).map_err(|e| {
+            PokercraftLocalError::GeneralError(format!("Failed to deserialize from JSON: {}", e))
+        })
+    }
+}
+
+/// Parse a single export file of given kind from the reader,
+/// and feed the results to `on_item`.
+/// The encoding of the file is detected automatically.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(filename = %filename, kind = ?kind))
+)]
+fn ingest_file<R, F>(
+    filename: &str,
+    kind: ExportFileKind,
+    mut reader: R,
+    allow_freerolls: bool,
+    number_format: &NumberFormat,
+    on_item: &mut F,
+) where
+    R: Read,
+    F: FnMut(ParsedItem),
+{
+    match kind {
+        ExportFileKind::HandHistory => {
+            let decoded = match decoding_reader(reader) {
+                Ok(decoded) => decoded,
+                Err(err) => {
+                    on_item(ParsedItem::Error(format!("{}: {}", filename, err)));
+                    return;
+                }
+            };
+            for result in parse_hands(BufReader::new(decoded)).with_number_format(*number_format) {
+                match result {
+                    Ok(hand) => on_item(ParsedItem::HandHistory(Box::new(hand))),
+                    Err(err) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(%err, "Failed to parse a hand");
+                        on_item(ParsedItem::Error(format!("{}: {}", filename, err)))
+                    }
+                }
+            }
+        }
+        ExportFileKind::TournamentSummary => {
+            let mut bytes = Vec::new();
+            let result = reader
+                .read_to_end(&mut bytes)
+                .map_err(PokercraftLocalError::from)
+                .and_then(|_| {
+                    parse_tournament_summary_with(
+                        &decode_bytes(&bytes),
+                        allow_freerolls,
+                        number_format,
+                    )
+                });
+            match result {
+                Ok(Some(summary)) => on_item(ParsedItem::TournamentSummary(summary)),
+                // Freerolls are skipped unless allowed
+                Ok(None) => {}
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(%err, "Failed to parse a tournament summary");
+                    on_item(ParsedItem::Error(format!("{}: {}", filename, err)))
+                }
+            }
+        }
+    }
+}
+
+/// Parse a `.txt` file whose name does not tell its kind, like a renamed export,
+/// detecting the kind from its content. Files of other contents are ignored.
+fn ingest_file_by_content<R, F>(
+    filename: &str,
+    mut reader: R,
+    allow_freerolls: bool,
+    number_format: &NumberFormat,
+    on_item: &mut F,
+) where
+    R: Read,
+    F: FnMut(ParsedItem),
+{
+    let mut bytes = Vec::new();
+    if let Err(err) = reader.read_to_end(&mut bytes) {
+        on_item(ParsedItem::Error(format!("{}: {}", filename, err)));
+        return;
+    }
+    if let Some(kind) = ExportFileKind::detect_content(&decode_bytes(&bytes)) {
+        ingest_file(
+            filename,
+            kind,
+            bytes.as_slice(),
+            allow_freerolls,
+            number_format,
+            on_item,
+        );
+    }
+}
+
+/// Ingest all export files inside the given zip archive.
+/// Entries in nested folders are also processed.
+pub fn ingest_zip_with<R, F>(
+    reader: R,
+    allow_freerolls: bool,
+    number_format: &NumberFormat,
+    on_item: &mut F,
+) -> Result<(), PokercraftLocalError>
+where
+    R: Read + Seek,
+    F: FnMut(ParsedItem),
+{
+    let mut archive = zip::ZipArchive::new(reader).map_err(|e| {
+        PokercraftLocalError::GeneralError(format!("Failed to open zip archive: {}", e))
+    })?;
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(|e| {
+            PokercraftLocalError::GeneralError(format!("Failed to read zip entry: {}", e))
+        })?;
+        if entry.is_dir() {
+            continue;
+        }
+        let full_name = entry.name().to_string();
+        let filename = full_name.rsplit('/').next().unwrap_or(&full_name);
+        if let Some(kind) = ExportFileKind::detect(filename) {
+            ingest_file(
+                filename,
+                kind,
+                entry,
+                allow_freerolls,
+                number_format,
+                on_item,
+            );
+        } else if filename.ends_with(".txt") {
+            ingest_file_by_content(filename, entry, allow_freerolls, number_format, on_item);
+        }
+    }
+    Ok(())
+}
+
+/// Ingest exports from the given path, feeding every parsed item to `on_item`.
+/// The path can be a `.zip` bundle, a single `.txt` export,
+/// or a directory which is searched recursively.
+/// Kinds of `.txt` files are detected from their names, or from their contents
+/// if their names are not of Pokercraft exports.
+/// Amounts are parsed in the given number format.
+/// Unreadable files are reported as `ParsedItem::Error`
+/// so that a single broken file does not stop the whole ingestion.
+pub fn ingest_path_with<F>(
+    path: &Path,
+    allow_freerolls: bool,
+    number_format: &NumberFormat,
+    on_item: &mut F,
+) -> Result<(), PokercraftLocalError>
+where
+    F: FnMut(ParsedItem),
+{
+    if path.is_dir() {
+        let mut children = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        children.sort();
+        for child in children {
+            ingest_path_with(&child, allow_freerolls, number_format, on_item)?;
+        }
+        return Ok(());
+    }
+
+    let filename = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if filename.ends_with(".zip") {
+        let result = File::open(path)
+            .map_err(PokercraftLocalError::from)
+            .and_then(|file| ingest_zip_with(file, allow_freerolls, number_format, on_item));
+        if let Err(err) = result {
+            on_item(ParsedItem::Error(format!("{}: {}", filename, err)));
+        }
+    } else if let Some(kind) = ExportFileKind::detect(&filename) {
+        match File::open(path) {
+            Ok(file) => ingest_file(
+                &filename,
+                kind,
+                file,
+                allow_freerolls,
+                number_format,
+                on_item,
+            ),
+            Err(err) => on_item(ParsedItem::Error(format!("{}: {}", filename, err))),
+        }
+    } else if filename.ends_with(".txt") {
+        match File::open(path) {
+            Ok(file) => {
+                ingest_file_by_content(&filename, file, allow_freerolls, number_format, on_item)
+            }
+            Err(err) => on_item(ParsedItem::Error(format!("{}: {}", filename, err))),
+        }
+    }
+    Ok(())
+}
+
+/// Ingest exports from the given path and collect all results.
+/// See `ingest_path_with` for details.
+pub fn ingest_path(
+    path: &Path,
+    allow_freerolls: bool,
+) -> Result<ParseResult, PokercraftLocalError> {
+    ingest_path_localized(path, allow_freerolls, &NumberFormat::default())
+}
+
+/// Ingest exports of clients in other locales from the given path,
+/// with amounts in the given number format, and collect all results.
+/// See `ingest_path_with` for details.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(path = %path.display()))
+)]
+pub fn ingest_path_localized(
+    path: &Path,
+    allow_freerolls: bool,
+    number_format: &NumberFormat,
+) -> Result<ParseResult, PokercraftLocalError> {
+    number_format.validate()?;
+    let mut result = ParseResult::default();
+    ingest_path_with(path, allow_freerolls, number_format, &mut |item| {
+        result.push(item)
+    })?;
+    #[cfg(feature = "tracing")]
+    tracing::info!(
+        hands = result.hand_histories.len(),
+        tournaments = result.tournaments.len(),
+        errors = result.errors.len(),
+        "Ingested exports"
+    );
+    Ok(result)
+}
+
+/// Combined results of importing both hand histories and tournament summaries,
+/// with tournaments linked to their hands.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub result: ParseResult,
+    /// `{tournament ID: indices of hands in result.hand_histories}`, in the imported order.
+    pub hands_by_tournament: BTreeMap<u64, Vec<usize>>,
+}
+
+impl ImportReport {
+    /// Link tournaments of the parse result to their hands.
+    pub fn new(result: ParseResult) -> Self {
+        let mut hands_by_tournament: BTreeMap<u64, Vec<usize>> = BTreeMap::new();
+        for (index, hand) in result.hand_histories.iter().enumerate() {
+            if let Some(tournament_id) = hand.tournament_id {
+                hands_by_tournament
+                    .entry(tournament_id)
+                    .or_default()
+                    .push(index);
+            }
+        }
+        ImportReport {
+            result,
+            hands_by_tournament,
+        }
+    }
+
+    /// Get the summary of the given tournament.
+    pub fn summary_of(&self, tournament_id: u64) -> Option<&TournamentSummary> {
+        self.result
+            .tournaments
+            .iter()
+            .find(|summary| summary.id == tournament_id)
+    }
+
+    /// Get hands of the given tournament.
+    pub fn hands_of(&self, tournament_id: u64) -> Vec<&HandHistory> {
+        self.hands_by_tournament
+            .get(&tournament_id)
+            .map(|indices| {
+                indices
+                    .iter()
+                    .map(|&index| &self.result.hand_histories[index])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get IDs of tournaments with both a summary and hands.
+    pub fn linked_tournaments(&self) -> Vec<u64> {
+        self.result
+            .tournaments
+            .iter()
+            .map(|summary| summary.id)
+            .filter(|id| self.hands_by_tournament.contains_key(id))
+            .collect()
+    }
+
+    /// Get IDs of tournaments with a summary but without any hands.
+    pub fn summaries_without_hands(&self) -> Vec<u64> {
+        self.result
+            .tournaments
+            .iter()
+            .map(|summary| summary.id)
+            .filter(|id| !self.hands_by_tournament.contains_key(id))
+            .collect()
+    }
+
+    /// Get IDs of tournaments with hands but without a summary.
+    pub fn hands_without_summary(&self) -> Vec<u64> {
+        self.hands_by_tournament
+            .keys()
+            .copied()
+            .filter(|&id| self.summary_of(id).is_none())
+            .collect()
+    }
+}
+
+/// Import both hand histories and tournament summaries from the given path,
+/// telling them apart automatically, and link tournaments to their hands.
+/// See `ingest_path_with` for supported paths.
+pub fn import_path(
+    path: &Path,
+    allow_freerolls: bool,
+) -> Result<ImportReport, PokercraftLocalError> {
+    Ok(ImportReport::new(ingest_path(path, allow_freerolls)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use super::*;
+
+    #[test]
+    fn test_ingest_zip() -> Result<(), PokercraftLocalError> {
+        let summary = "Tournament #1, $1 Buy-in, Hold'em No Limit
+Buy-in: $1
+10 Players
+Total Prize Pool: $10
+Tournament started 2025/08/01 12:00:00
+3rd : Hero, $2
+
+You received a total of $2
+";
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut writer = zip::ZipWriter::new(&mut buffer);
+            let options = zip::write::SimpleFileOptions::default();
+            writer
+                .start_file("export/GG20250801 - Tournament #1 - Summary.txt", options)
+                .unwrap();
+            writer.write_all(summary.as_bytes())?;
+            writer
+                .start_file("GG20250801 - Tournament #2 - Summary.txt", options)
+                .unwrap();
+            writer.write_all(b"Tournament #2, broken")?;
+            writer.start_file("readme.md", options).unwrap();
+            writer.write_all(b"ignored")?;
+            writer.finish().unwrap();
+        }
+        buffer.set_position(0);
+
+        let mut result = ParseResult::default();
+        ingest_zip_with(buffer, false, &NumberFormat::default(), &mut |item| {
+            result.push(item)
+        })?;
+        assert_eq!(result.tournaments.len(), 1);
+        assert_eq!(result.tournaments[0].my_rank, 3);
+        assert!(result.hand_histories.is_empty());
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].starts_with("GG20250801 - Tournament #2 - Summary.txt: "));
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_path() -> Result<(), PokercraftLocalError> {
+        use crate::parser::hand_history::tests::SAMPLE_HAND;
+
+        let summary = |id: u64| {
+            format!(
+                "Tournament #{}, Zodiac Dog Ultra Deepstack 7-Max ¥110, Hold'em No Limit
+Buy-in: ¥100+¥10
+100 Players
+Total Prize Pool: ¥10,000
+Tournament started 2025/08/01 00:00:00
+12th : Hero, ¥200
+
+You received a total of ¥200
+",
+                id
+            )
+        };
+        let directory =
+            std::env::temp_dir().join(format!("pokercraft-import-test-{}", std::process::id()));
+        std::fs::create_dir_all(directory.join("renamed"))?;
+        std::fs::write(
+            directory.join("GG20250801 - Tournament #220597937 - Summary.txt"),
+            summary(220597937),
+        )?;
+        std::fs::write(
+            directory.join("GG20250802 - Tournament #1 - Summary.txt"),
+            summary(1),
+        )?;
+        // Kinds of renamed exports are detected from their contents
+        std::fs::write(directory.join("renamed").join("hands.txt"), SAMPLE_HAND)?;
+        std::fs::write(directory.join("renamed").join("notes.txt"), "Not an export")?;
+        let report = import_path(&directory, false);
+        std::fs::remove_dir_all(&directory)?;
+        let report = report?;
+
+        assert!(report.result.errors.is_empty());
+        assert_eq!(report.result.tournaments.len(), 2);
+        assert_eq!(report.linked_tournaments(), vec![220597937]);
+        assert_eq!(report.summaries_without_hands(), vec![1]);
+        assert!(report.hands_without_summary().is_empty());
+        let hands = report.hands_of(220597937);
+        assert_eq!(hands.len(), report.result.hand_histories.len());
+        assert_eq!(hands[0].id, "TM4832872904");
+        assert_eq!(report.summary_of(220597937).map(|s| s.my_rank), Some(12));
+        assert!(report.hands_of(1).is_empty());
+
+        assert_eq!(
+            ExportFileKind::detect_content("\u{feff}\nTournament #1, Test"),
+            Some(ExportFileKind::TournamentSummary)
+        );
+        assert_eq!(
+            ExportFileKind::detect_content("Poker Hand #SD1: Tournament #1, Short Deck"),
+            None
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_roundtrip() -> Result<(), PokercraftLocalError> {
+        use crate::parser::hand_history::tests::SAMPLE_HAND;
+
+        let mut result = ParseResult::default();
+        for hand in parse_hands(SAMPLE_HAND.as_bytes()) {
+            result.push(ParsedItem::HandHistory(Box::new(hand?)));
+        }
+        result.push(ParsedItem::Error("broken.txt: Line 1: error".to_string()));
+
+        let json = result.to_json()?;
+        assert!(json.contains("\"Jh\""));
+        let restored = ParseResult::from_json(&json)?;
+        let (original, restored_hand) = (&result.hand_histories[0], &restored.hand_histories[0]);
+        assert_eq!(restored_hand.id, original.id);
+        assert_eq!(restored_hand.datetime, original.datetime);
+        assert_eq!(restored_hand.seats, original.seats);
+        assert_eq!(restored_hand.known_cards, original.known_cards);
+        assert_eq!(restored_hand.actions_preflop, original.actions_preflop);
+        assert_eq!(restored.errors, result.errors);
+        assert!(ParseResult::from_json("{").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+//! Number formats of exports, which depend on the locale of the Pokercraft client.
+//!
+//! Clients in European locales write `"1.000,50 $"` for `"$1,000.50"`,
+//! so amounts are parsed with a configurable `NumberFormat`.
+
+use crate::currency::Currency;
+use crate::errors::PokercraftLocalError;
+
+/// Regex pattern of a number with any separators, which starts and ends with a digit.
+/// Numbers matching this are validated by `NumberFormat` when parsed.
+pub(crate) const NUMBER_PATTERN: &str = r"\d(?:[\d,.'\u{a0}\u{202f} ]*\d)?";
+
+/// Where currency symbols are placed around amounts.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub enum CurrencyPosition {
+    /// Like `"$1,000"`.
+    #[default]
+    Prefix,
+    /// Like `"1.000 $"`.
+    Suffix,
+}
+
+/// Separators and currency symbol placement of numbers in exports.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct NumberFormat {
+    pub decimal_separator: char,
+    /// Separator between groups of 3 digits, or `None` if digits are not grouped.
+    /// A space also accepts no-break spaces, which some locales use.
+    pub thousands_separator: Option<char>,
+    pub currency_position: CurrencyPosition,
+}
+
+impl Default for NumberFormat {
+    /// English format, like `"$1,000.50"`.
+    fn default() -> Self {
+        NumberFormat {
+            decimal_separator: '.',
+            thousands_separator: Some(','),
+            currency_position: CurrencyPosition::Prefix,
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Create a new number format after validating separators.
+    pub fn new(
+        decimal_separator: char,
+        thousands_separator: Option<char>,
+        currency_position: CurrencyPosition,
+    ) -> Result<Self, PokercraftLocalError> {
+        let format = NumberFormat {
+            decimal_separator,
+            thousands_separator,
+            currency_position,
+        };
+        format.validate()?;
+        Ok(format)
+    }
+
+    /// Continental European format, like `"1.000,50 $"`.
+    pub const fn european() -> Self {
+        NumberFormat {
+            decimal_separator: ',',
+            thousands_separator: Some('.'),
+            currency_position: CurrencyPosition::Suffix,
+        }
+    }
+
+    /// Check if separators can be told apart from digits and each other.
+    pub fn validate(&self) -> Result<(), PokercraftLocalError> {
+        let allowed = [',', '.', '\'', ' ', '\u{a0}', '\u{202f}'];
+        if !allowed.contains(&self.decimal_separator) || self.decimal_separator.is_whitespace() {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Unsupported decimal separator {:?}",
+                self.decimal_separator
+            )));
+        }
+        match self.thousands_separator {
+            Some(separator) if !allowed.contains(&separator) => {
+                Err(PokercraftLocalError::GeneralError(format!(
+                    "Unsupported thousands separator {:?}",
+                    separator
+                )))
+            }
+            Some(separator) if separator == self.decimal_separator => {
+                Err(PokercraftLocalError::GeneralError(
+                    "Decimal and thousands separators should be different".to_string(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check if the given character separates thousands.
+    fn is_thousands_separator(&self, c: char) -> bool {
+        match self.thousands_separator {
+            Some(' ') => c.is_whitespace(),
+            Some(separator) => c == separator,
+            None => false,
+        }
+    }
+
+    /// Get the integer part without thousands separators,
+    /// after checking that every group has 3 digits.
+    fn integer_digits(&self, s: &str) -> Option<String> {
+        let groups = s
+            .split(|c| self.is_thousands_separator(c))
+            .collect::<Vec<_>>();
+        let is_digits =
+            |group: &str| !group.is_empty() && group.chars().all(|c| c.is_ascii_digit());
+        let valid = groups.iter().all(|group| is_digits(group))
+            && (groups.len() == 1
+                || (groups[0].len() <= 3 && groups[1..].iter().all(|group| group.len() == 3)));
+        valid.then(|| groups.concat())
+    }
+
+    /// Parse an integer like `"1,200"`.
+    pub fn parse_int(&self, s: &str) -> Result<u64, PokercraftLocalError> {
+        self.integer_digits(s.trim())
+            .and_then(|digits| digits.parse::<u64>().ok())
+            .ok_or_else(|| {
+                PokercraftLocalError::GeneralError(format!("Failed to parse \"{}\" as integer", s))
+            })
+    }
+
+    /// Parse a decimal number like `"1,000.50"`.
+    pub fn parse_decimal(&self, s: &str) -> Result<f64, PokercraftLocalError> {
+        let s = s.trim();
+        let (integer, fraction) = match s.split_once(self.decimal_separator) {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (s, None),
+        };
+        let fraction_valid = fraction.is_none_or(|fraction| {
+            !fraction.is_empty() && fraction.chars().all(|c| c.is_ascii_digit())
+        });
+        self.integer_digits(integer)
+            .filter(|_| fraction_valid)
+            .and_then(|digits| match fraction {
+                Some(fraction) => format!("{}.{}", digits, fraction).parse::<f64>().ok(),
+                None => digits.parse::<f64>().ok(),
+            })
+            .ok_or_else(|| {
+                PokercraftLocalError::GeneralError(format!("Failed to parse \"{}\" as number", s))
+            })
+    }
+
+    /// Parse a money string like `"$1,000.50"` into its currency and amount.
+    pub fn parse_money(&self, s: &str) -> Result<(Currency, f64), PokercraftLocalError> {
+        let s = s.trim();
+        let error =
+            || PokercraftLocalError::GeneralError(format!("Failed to parse \"{}\" as money", s));
+        let (currency, amount) = match self.currency_position {
+            CurrencyPosition::Prefix => {
+                let mut chars = s.chars();
+                (chars.next().and_then(Currency::from_symbol), chars.as_str())
+            }
+            CurrencyPosition::Suffix => {
+                let mut chars = s.chars();
+                (
+                    chars.next_back().and_then(Currency::from_symbol),
+                    chars.as_str(),
+                )
+            }
+        };
+        let currency = currency.ok_or_else(error)?;
+        let amount = self.parse_decimal(amount).map_err(|_| error())?;
+        Ok((currency, amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_format() -> Result<(), PokercraftLocalError> {
+        let english = NumberFormat::default();
+        assert_eq!(english.parse_int("1,200")?, 1200);
+        assert_eq!(english.parse_decimal("1,000.50")?, 1000.5);
+        assert_eq!(english.parse_money("$1,000.50")?, (Currency::USD, 1000.5));
+        // European amounts are rejected instead of misread
+        assert!(english.parse_int("1.200").is_err());
+        assert!(english.parse_decimal("1.000,50").is_err());
+        assert!(english.parse_decimal("10,00").is_err());
+
+        let european = NumberFormat::european();
+        assert_eq!(european.parse_int("1.200")?, 1200);
+        assert_eq!(european.parse_decimal("1.000,50")?, 1000.5);
+        assert_eq!(european.parse_money("1.000,50 ¥")?, (Currency::CNY, 1000.5));
+        assert!(european.parse_money("$1.000,50").is_err());
+
+        let swiss = NumberFormat::new('.', Some('\''), CurrencyPosition::Prefix)?;
+        assert_eq!(swiss.parse_decimal("12'345.6")?, 12345.6);
+        let french = NumberFormat::new(',', Some(' '), CurrencyPosition::Suffix)?;
+        assert_eq!(
+            french.parse_money("1\u{202f}000,5 $")?,
+            (Currency::USD, 1000.5)
+        );
+        let ungrouped = NumberFormat::new('.', None, CurrencyPosition::Prefix)?;
+        assert_eq!(ungrouped.parse_int("1200")?, 1200);
+        assert!(ungrouped.parse_int("1,200").is_err());
+
+        assert!(NumberFormat::new(',', Some(','), CurrencyPosition::Prefix).is_err());
+        assert!(NumberFormat::new('x', None, CurrencyPosition::Prefix).is_err());
+        assert!(NumberFormat::new(' ', Some('.'), CurrencyPosition::Prefix).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+//! Parsers for files exported from Pokercraft.
+
+pub mod anonymize;
+pub mod encoding;
+pub mod hand_history;
+pub mod hero;
+pub mod ingest;
+pub mod locale;
+pub mod registry;
+pub mod tournament_summary;
+
+pub use anonymize::{AnonymizeMode, Anonymizer};
+pub use hand_history::{parse_hands, BetAction, BetActionType, HandHistory, HandStage};
+pub use hero::{detect_heroes, HeroAliases};
+pub use ingest::{
+    import_path, ingest_path, ingest_path_localized, ingest_path_with, ExportFileKind,
+    ImportReport, ParseResult, ParsedItem,
+};
+pub use locale::{CurrencyPosition, NumberFormat};
+pub use registry::{PlayerRegistry, SitePlayer, GGPOKER};
+pub use tournament_summary::{
+    parse_tournament_summary, parse_tournament_summary_with, TournamentSummary,
+};
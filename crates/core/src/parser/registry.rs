@@ -0,0 +1,269 @@
+//! Registry of players, mapping site-specific player IDs and nicknames
+//! to stable internal IDs.
+//!
+//! Per-opponent stats are keyed by player IDs, which change when a player
+//! changes the nickname or plays on other sites. Renaming players of hands
+//! to internal IDs keeps stats of all aliases of a player together.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::hero::DEFAULT_HERO;
+use super::HandHistory;
+use crate::errors::PokercraftLocalError;
+
+/// Site name of player IDs in Pokercraft exports.
+pub const GGPOKER: &str = "GGPoker";
+
+/// A player ID on a site.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
+pub struct SitePlayer {
+    pub site: String,
+    pub player_id: String,
+}
+
+impl SitePlayer {
+    pub fn new(site: &str, player_id: &str) -> Self {
+        SitePlayer {
+            site: site.to_string(),
+            player_id: player_id.to_string(),
+        }
+    }
+}
+
+/// Registry of players and their aliases on each site.
+/// Each player has a stable internal ID, which survives merges of players.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct PlayerRegistry {
+    /// `{internal ID: aliases}` of registered players.
+    players: BTreeMap<u64, Vec<SitePlayer>>,
+    /// `{merged internal ID: internal ID it was merged into}`
+    merged: BTreeMap<u64, u64>,
+    next_id: u64,
+    /// `{alias: internal ID}`, rebuilt from `players`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    index: HashMap<SitePlayer, u64>,
+}
+
+impl PlayerRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the player ID used in hands for the given internal ID.
+    /// These are lowercase hex, like player IDs of Pokercraft exports,
+    /// but longer so they are not confused with them.
+    pub fn player_id_of(internal_id: u64) -> String {
+        format!("{:016x}", internal_id)
+    }
+
+    /// Get the internal ID of the given alias, if registered.
+    pub fn get(&self, site: &str, player_id: &str) -> Option<u64> {
+        self.index.get(&SitePlayer::new(site, player_id)).copied()
+    }
+
+    /// Get the internal ID of the given alias, registering a new player if not registered.
+    pub fn register(&mut self, site: &str, player_id: &str) -> u64 {
+        if let Some(internal_id) = self.get(site, player_id) {
+            return internal_id;
+        }
+        let internal_id = self.next_id;
+        self.next_id += 1;
+        let alias = SitePlayer::new(site, player_id);
+        self.players.insert(internal_id, vec![alias.clone()]);
+        self.index.insert(alias, internal_id);
+        internal_id
+    }
+
+    /// Follow merges of the given internal ID to the current one.
+    pub fn resolve(&self, mut internal_id: u64) -> u64 {
+        while let Some(&merged_into) = self.merged.get(&internal_id) {
+            internal_id = merged_into;
+        }
+        internal_id
+    }
+
+    /// Add an alias to the player, like a new nickname or an account on another site.
+    /// Fails if the alias belongs to another player; Use `merge` for that.
+    pub fn link(
+        &mut self,
+        internal_id: u64,
+        site: &str,
+        player_id: &str,
+    ) -> Result<(), PokercraftLocalError> {
+        let internal_id = self.resolve(internal_id);
+        let Some(aliases) = self.players.get_mut(&internal_id) else {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Player {} is not registered",
+                internal_id
+            )));
+        };
+        match self.index.get(&SitePlayer::new(site, player_id)) {
+            Some(&existing) if existing == internal_id => Ok(()),
+            Some(&existing) => Err(PokercraftLocalError::GeneralError(format!(
+                "{} on {} already belongs to player {}",
+                player_id, site, existing
+            ))),
+            None => {
+                let alias = SitePlayer::new(site, player_id);
+                aliases.push(alias.clone());
+                self.index.insert(alias, internal_id);
+                Ok(())
+            }
+        }
+    }
+
+    /// Merge the player `other` into the player `into`, moving all aliases of `other`.
+    /// Returns the internal ID of the merged player.
+    pub fn merge(&mut self, into: u64, other: u64) -> Result<u64, PokercraftLocalError> {
+        let (into, other) = (self.resolve(into), self.resolve(other));
+        if into == other {
+            return Ok(into);
+        }
+        if !self.players.contains_key(&into) {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Player {} is not registered",
+                into
+            )));
+        }
+        let Some(aliases) = self.players.remove(&other) else {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Player {} is not registered",
+                other
+            )));
+        };
+        for alias in aliases.iter() {
+            self.index.insert(alias.clone(), into);
+        }
+        self.players.entry(into).or_default().extend(aliases);
+        self.merged.insert(other, into);
+        Ok(into)
+    }
+
+    /// Get all aliases of the player.
+    pub fn aliases(&self, internal_id: u64) -> &[SitePlayer] {
+        self.players
+            .get(&self.resolve(internal_id))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Get the number of registered players.
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Check if no players are registered.
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Rename players of the hand from the given site to their internal player IDs,
+    /// registering new players. The hero is kept as it is.
+    pub fn apply(&mut self, site: &str, hand: &mut HandHistory) {
+        let internal_ids = hand
+            .seats
+            .values()
+            .filter(|(player_id, _)| player_id != DEFAULT_HERO)
+            .map(|(player_id, _)| {
+                let internal_id = self.register(site, player_id);
+                (player_id.clone(), Self::player_id_of(internal_id))
+            })
+            .collect::<HashMap<_, _>>();
+        hand.rename_players(|player_id| {
+            internal_ids
+                .get(player_id)
+                .cloned()
+                .unwrap_or_else(|| player_id.to_string())
+        });
+    }
+
+    /// Rebuild the alias index, after deserialization.
+    #[cfg(feature = "serde")]
+    fn rebuild_index(&mut self) {
+        self.index = self
+            .players
+            .iter()
+            .flat_map(|(&internal_id, aliases)| {
+                aliases
+                    .iter()
+                    .map(move |alias| (alias.clone(), internal_id))
+            })
+            .collect();
+    }
+
+    /// Serialize into a JSON string, to persist the registry between imports.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, PokercraftLocalError> {
+        serde_json::to_string(self).map_err(|e| {
+            PokercraftLocalError::GeneralError(format!("Failed to serialize into JSON: {}", e))
+        })
+    }
+
+    /// Deserialize from a JSON string made by `to_json`.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, PokercraftLocalError> {
+        let mut registry: Self = serde_json::from_str(json).map_err(|e| {
+            PokercraftLocalError::GeneralError(format!("Failed to deserialize from JSON: {}", e))
+        })?;
+        registry.rebuild_index();
+        Ok(registry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::hand_history::tests::SAMPLE_HAND;
+    use crate::parser::parse_hands;
+
+    #[test]
+    fn test_player_registry() -> Result<(), PokercraftLocalError> {
+        let mut registry = PlayerRegistry::new();
+        let villain = registry.register(GGPOKER, "471910c");
+        assert_eq!(registry.register(GGPOKER, "471910c"), villain);
+        let other_site = registry.register("Other", "nickname");
+        assert_ne!(other_site, villain);
+        assert_eq!(registry.len(), 2);
+
+        // The same player on another site, and after a nickname change
+        registry.merge(villain, other_site)?;
+        registry.link(other_site, "Other", "new_nickname")?;
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.resolve(other_site), villain);
+        assert_eq!(registry.get("Other", "new_nickname"), Some(villain));
+        assert_eq!(registry.aliases(other_site).len(), 3);
+        let another = registry.register(GGPOKER, "f123395");
+        assert!(registry.link(another, GGPOKER, "471910c").is_err());
+        assert!(registry.merge(villain, 100).is_err());
+
+        let hand = parse_hands(SAMPLE_HAND.as_bytes())
+            .next()
+            .ok_or_else(|| PokercraftLocalError::GeneralError("No hand".to_string()))??;
+        let mut renamed = hand.clone();
+        registry.apply(GGPOKER, &mut renamed);
+        let player_id = PlayerRegistry::player_id_of(villain);
+        assert_eq!(renamed.seat_number(&player_id)?, 6);
+        assert_eq!(renamed.net_profit(&player_id), hand.net_profit("471910c"));
+        assert_eq!(renamed.seat_number("Hero")?, 7);
+        assert_eq!(registry.len(), 6);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_roundtrip() -> Result<(), PokercraftLocalError> {
+        let mut registry = PlayerRegistry::new();
+        let villain = registry.register(GGPOKER, "471910c");
+        let other_site = registry.register("Other", "nickname");
+        registry.merge(villain, other_site)?;
+
+        let restored = PlayerRegistry::from_json(&registry.to_json()?)?;
+        assert_eq!(restored, registry);
+        assert_eq!(restored.get("Other", "nickname"), Some(villain));
+        assert_eq!(restored.resolve(other_site), villain);
+        Ok(())
+    }
+}
@@ -0,0 +1,426 @@
+//! Parser for GGPoker tournament summary files.
+
+use chrono::NaiveDateTime;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::locale::{CurrencyPosition, NumberFormat, NUMBER_PATTERN};
+use crate::currency::{Currency, CurrencyRateConverter};
+use crate::errors::PokercraftLocalError;
+
+static LINE1_ID_NAME: Lazy<Regex> = Lazy::new(|| Regex::new(r"^Tournament #\d+, .+, .+$").unwrap());
+static LINE2_BUYIN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^Buy-in: .+$").unwrap());
+static LINE3_ENTRIES: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"^({NUMBER_PATTERN}) Players$")).unwrap());
+static LINE4_PRIZEPOOL: Lazy<Regex> = Lazy::new(|| Regex::new(r"^Total Prize Pool: .+$").unwrap());
+static LINE5_START_TIME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Tournament started (\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2})$").unwrap()
+});
+static LINE6_MY_RANK_AND_PRIZE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\d+)(?:st|nd|rd|th) : Hero, .+$").unwrap());
+static LINE8_MY_PRIZE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^You (?:made (\d+)(?: re)?-entries and )?received a total of .+$").unwrap()
+});
+/// Money of any separators with the currency symbol placed after the amount.
+static ANY_MONEY_SUFFIX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"{NUMBER_PATTERN}\s?[\$¥฿₫₱₩]")).unwrap());
+static BOUNTY_MONEY_SUFFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)({NUMBER_PATTERN}\s?[\$¥฿₫₱₩])\s*\(?\s*bounty"
+    ))
+    .unwrap()
+});
+/// Money of any separators with the currency symbol placed before the amount.
+static ANY_MONEY_PREFIX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"[\$¥฿₫₱₩]\s?{NUMBER_PATTERN}")).unwrap());
+static BOUNTY_MONEY_PREFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)([\$¥฿₫₱₩]\s?{NUMBER_PATTERN})\s*\(?\s*bounty"
+    ))
+    .unwrap()
+});
+static ANY_INT: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+
+/// Check if the given filename is a tournament summary file.
+pub fn is_tournament_summary_file(filename: &str) -> bool {
+    static FILENAME_PATTERN: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^GG\d{8} - Tournament #\d+ - ").unwrap());
+    filename.ends_with(".txt") && FILENAME_PATTERN.is_match(filename)
+}
+
+/// A single parsed tournament summary.
+/// All money amounts are in the currency of `currency`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TournamentSummary {
+    pub id: u64,
+    pub name: String,
+    /// Currency detected from the file.
+    pub currency: Currency,
+    /// Buy-in without rake.
+    pub buy_in_pure: f64,
+    pub rake: f64,
+    pub total_prize_pool: f64,
+    pub start_time: NaiveDateTime,
+    pub my_rank: u32,
+    pub total_players: u32,
+    /// Total prize including bounties.
+    pub my_prize: f64,
+    /// Part of `my_prize` won from bounties.
+    pub my_bounty: f64,
+    /// Number of entries including re-entries.
+    pub my_entries: u32,
+}
+
+impl TournamentSummary {
+    /// Get the buy-in including rake.
+    pub fn buy_in(&self) -> f64 {
+        self.buy_in_pure + self.rake
+    }
+
+    /// Get a copy of this tournament with all money amounts
+    /// converted into the given currency, at the rate of the start date.
+    pub fn converted(
+        &self,
+        converter: &CurrencyRateConverter,
+        currency: Currency,
+    ) -> TournamentSummary {
+        let date = Some(self.start_time.date());
+        let convert = |amount: f64| converter.convert(amount, self.currency, currency, date);
+        TournamentSummary {
+            currency,
+            buy_in_pure: convert(self.buy_in_pure),
+            rake: convert(self.rake),
+            total_prize_pool: convert(self.total_prize_pool),
+            my_prize: convert(self.my_prize),
+            my_bounty: convert(self.my_bounty),
+            ..self.clone()
+        }
+    }
+
+    /// Get the prize excluding bounties.
+    pub fn regular_prize(&self) -> f64 {
+        self.my_prize - self.my_bounty
+    }
+
+    /// Get the net profit including all re-entries.
+    pub fn profit(&self) -> f64 {
+        self.my_prize - self.buy_in() * self.my_entries as f64
+    }
+
+    /// Get the relative return with re-entries.
+    /// For example, $5 prize from $1 buy-in with 3 re-entries returns 1.25.
+    /// Returns `None` on freerolls.
+    pub fn rre(&self) -> Option<f64> {
+        let buy_in = self.buy_in();
+        if buy_in > 0.0 {
+            Some(self.my_prize / buy_in / self.my_entries as f64)
+        } else {
+            None
+        }
+    }
+
+    /// Get the relative returns of each entry.
+    /// Unlike RRE, each element is subtracted by 1.
+    /// Returns an empty vector on freerolls.
+    pub fn relative_returns(&self) -> Vec<f64> {
+        let buy_in = self.buy_in();
+        if buy_in <= 0.0 {
+            return vec![];
+        }
+        let mut result = vec![-1.0; self.my_entries.saturating_sub(1) as usize];
+        result.push(self.my_prize / buy_in - 1.0);
+        result
+    }
+}
+
+/// Get regexes of money amounts and bounties in the given number format.
+fn money_regexes(number_format: &NumberFormat) -> (&'static Regex, &'static Regex) {
+    match number_format.currency_position {
+        CurrencyPosition::Prefix => (&ANY_MONEY_PREFIX, &BOUNTY_MONEY_PREFIX),
+        CurrencyPosition::Suffix => (&ANY_MONEY_SUFFIX, &BOUNTY_MONEY_SUFFIX),
+    }
+}
+
+/// Take all money amounts from the given line.
+/// If `currency` is given, all amounts should be in that currency.
+fn take_all_money(
+    line: &str,
+    currency: Option<Currency>,
+    number_format: &NumberFormat,
+) -> Result<Vec<f64>, PokercraftLocalError> {
+    money_regexes(number_format)
+        .0
+        .find_iter(line)
+        .map(|m| {
+            let (actual, amount) = number_format.parse_money(m.as_str())?;
+            match currency {
+                Some(expected) if expected != actual => {
+                    Err(PokercraftLocalError::GeneralError(format!(
+                        "Expected currency {} but got {}: {}",
+                        expected, actual, line
+                    )))
+                }
+                _ => Ok(amount),
+            }
+        })
+        .collect()
+}
+
+/// Take the first integer from the given line.
+fn take_first_int(line: &str) -> Result<u32, PokercraftLocalError> {
+    ANY_INT
+        .find(line)
+        .and_then(|m| m.as_str().parse::<u32>().ok())
+        .ok_or_else(|| {
+            PokercraftLocalError::GeneralError(format!("Failed to find integer in \"{}\"", line))
+        })
+}
+
+/// Parse a tournament summary from the content of a summary file.
+/// Returns `Ok(None)` if it is a freeroll and `allow_freerolls` is `false`,
+/// and an error if required fields are missing.
+pub fn parse_tournament_summary(
+    content: &str,
+    allow_freerolls: bool,
+) -> Result<Option<TournamentSummary>, PokercraftLocalError> {
+    parse_tournament_summary_with(content, allow_freerolls, &NumberFormat::default())
+}
+
+/// Parse a tournament summary with amounts in the given number format,
+/// for exports from clients in other locales.
+/// See `parse_tournament_summary` for details.
+pub fn parse_tournament_summary_with(
+    content: &str,
+    allow_freerolls: bool,
+    number_format: &NumberFormat,
+) -> Result<Option<TournamentSummary>, PokercraftLocalError> {
+    let mut id: Option<u64> = None;
+    let mut name: Option<String> = None;
+    let mut buy_in: Option<(f64, f64)> = None; // (pure, rake)
+    let mut total_prize_pool: Option<f64> = None;
+    let mut start_time: Option<NaiveDateTime> = None;
+    let mut my_rank_and_prize: Option<(u32, f64)> = None;
+    let mut my_bounty: f64 = 0.0;
+    let mut total_players: Option<u32> = None;
+    let mut my_entries: u32 = 1;
+    let mut currency: Option<Currency> = None;
+
+    for line in content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        if currency.is_none() {
+            currency = line.chars().find_map(Currency::from_symbol);
+        }
+
+        if LINE1_ID_NAME.is_match(line) {
+            let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+            id = ANY_INT
+                .find(parts[0])
+                .and_then(|m| m.as_str().parse::<u64>().ok());
+            // Name is everything between the first and the last comma
+            name = Some(parts[1..parts.len() - 1].join(","));
+        } else if LINE2_BUYIN.is_match(line) {
+            let mut buy_ins = take_all_money(line, currency, number_format)?;
+            buy_ins.sort_by(|a, b| a.total_cmp(b));
+            buy_in = Some(if let Some(&rake) = buy_ins.first() {
+                let pure = buy_ins.iter().sum::<f64>() - rake;
+                if rake >= 0.3 * (pure + rake) {
+                    // If rake is too big, probably no rake is specified
+                    (pure + rake, 0.0)
+                } else {
+                    (pure, rake)
+                }
+            } else {
+                (0.0, 0.0) // Freeroll
+            });
+        } else if let Some(caps) = LINE3_ENTRIES.captures(line) {
+            total_players = Some(number_format.parse_int(&caps[1])? as u32);
+        } else if LINE4_PRIZEPOOL.is_match(line) {
+            total_prize_pool = take_all_money(line, currency, number_format)?
+                .first()
+                .copied();
+        } else if let Some(caps) = LINE5_START_TIME.captures(line) {
+            start_time = Some(
+                NaiveDateTime::parse_from_str(&caps[1], "%Y/%m/%d %H:%M:%S").map_err(|_| {
+                    PokercraftLocalError::GeneralError(format!("Invalid datetime: {}", line))
+                })?,
+            );
+        } else if let Some(caps) = LINE6_MY_RANK_AND_PRIZE.captures(line) {
+            let rank = take_first_int(&caps[1])?;
+            let mut prize: f64 = take_all_money(line, currency, number_format)?.iter().sum();
+            if prize <= 0.0 && line.contains("$0 Entry") {
+                // Flip & Go displays "$0 Entry" as prize
+                if let Some((pure, rake)) = buy_in {
+                    prize = pure + rake;
+                }
+            }
+            my_rank_and_prize = Some((rank, prize));
+            my_bounty = money_regexes(number_format)
+                .1
+                .captures_iter(line)
+                .map(|caps| {
+                    number_format
+                        .parse_money(&caps[1])
+                        .map(|(_, amount)| amount)
+                })
+                .sum::<Result<f64, _>>()?;
+        } else if let Some(caps) = LINE8_MY_PRIZE.captures(line) {
+            if let Some(reentries) = caps.get(1) {
+                my_entries += take_first_int(reentries.as_str())?;
+            }
+        }
+    }
+
+    let (
+        Some(id),
+        Some(name),
+        Some((buy_in_pure, rake)),
+        Some(total_prize_pool),
+        Some(start_time),
+        Some((my_rank, my_prize)),
+        Some(total_players),
+    ) = (
+        id,
+        name.as_ref(),
+        buy_in,
+        total_prize_pool,
+        start_time,
+        my_rank_and_prize,
+        total_players,
+    )
+    else {
+        let missing = [
+            ("tournament ID", id.is_none()),
+            ("name", name.is_none()),
+            ("buy-in", buy_in.is_none()),
+            ("total prize pool", total_prize_pool.is_none()),
+            ("start time", start_time.is_none()),
+            ("rank and prize", my_rank_and_prize.is_none()),
+            ("number of players", total_players.is_none()),
+        ]
+        .into_iter()
+        .filter(|(_, missing)| *missing)
+        .map(|(field, _)| field)
+        .collect::<Vec<_>>();
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "Missing {} in tournament summary",
+            missing.join(", ")
+        )));
+    };
+    let name = name.clone();
+    if !allow_freerolls && buy_in_pure + rake <= 0.0 {
+        return Ok(None);
+    }
+
+    Ok(Some(TournamentSummary {
+        id,
+        name,
+        currency: currency.unwrap_or(Currency::USD),
+        buy_in_pure,
+        rake,
+        total_prize_pool,
+        start_time,
+        my_rank,
+        total_players,
+        my_prize,
+        my_bounty,
+        my_entries,
+    }))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const SAMPLE_SUMMARY: &str =
+        "Tournament #123456789, $10 + $1 Buy-in, Hold'em No Limit
+Buy-in: $10 + $1
+100 Players
+Total Prize Pool: $1,000
+Tournament started 2025/08/01 12:00:00
+5th : Hero, $300
+
+You made 2 re-entries and received a total of $300
+";
+
+    #[test]
+    fn test_is_tournament_summary_file() {
+        assert!(is_tournament_summary_file(
+            "GG20250801 - Tournament #123456 - Summary.txt"
+        ));
+        assert!(!is_tournament_summary_file("GG20250801-0012 - Hold'em.txt"));
+        assert!(!is_tournament_summary_file(
+            "GG20250801 - Tournament #123456 - Summary.csv"
+        ));
+    }
+
+    #[test]
+    fn test_parse_tournament_summary() -> Result<(), PokercraftLocalError> {
+        let summary = parse_tournament_summary(SAMPLE_SUMMARY, false)?.unwrap();
+        assert_eq!(summary.id, 123456789);
+        assert_eq!(summary.name, "$10 + $1 Buy-in");
+        assert_eq!(summary.currency, Currency::USD);
+        assert_eq!((summary.buy_in_pure, summary.rake), (10.0, 1.0));
+        assert_eq!(summary.total_prize_pool, 1000.0);
+        assert_eq!((summary.my_rank, summary.total_players), (5, 100));
+        assert_eq!((summary.my_prize, summary.my_entries), (300.0, 3));
+        assert_eq!(summary.my_bounty, 0.0);
+        assert_eq!(summary.profit(), 300.0 - 33.0);
+        assert_eq!(summary.relative_returns().len(), 3);
+
+        let freeroll = SAMPLE_SUMMARY.replace("Buy-in: $10 + $1", "Buy-in: Free");
+        assert!(parse_tournament_summary(&freeroll, false)?.is_none());
+        assert!(parse_tournament_summary(&freeroll, true)?.is_some());
+
+        let bounty = SAMPLE_SUMMARY.replace("5th : Hero, $300", "5th : Hero, $200, $100 Bounty");
+        let summary = parse_tournament_summary(&bounty, false)?.unwrap();
+        assert_eq!((summary.my_prize, summary.my_bounty), (300.0, 100.0));
+        assert_eq!(summary.regular_prize(), 200.0);
+
+        let cny = SAMPLE_SUMMARY.replace('$', "¥");
+        let summary = parse_tournament_summary(&cny, false)?.unwrap();
+        assert_eq!(summary.currency, Currency::CNY);
+        assert_eq!(summary.my_prize, 300.0);
+        let mut converter = CurrencyRateConverter::new();
+        converter.set_rate(Currency::CNY, 7.5)?;
+        let in_usd = summary.converted(&converter, Currency::USD);
+        assert_eq!(in_usd.currency, Currency::USD);
+        assert_eq!(in_usd.my_prize, 40.0);
+        assert!((in_usd.buy_in() - 11.0 / 7.5).abs() < 1e-9);
+
+        // Exports of clients in European locales
+        let european = "Tournament #123456789, 10 $ + 1 $ Buy-in, Hold'em No Limit
+Buy-in: 10,50 $ + 1 $
+1.200 Players
+Total Prize Pool: 12.000,50 $
+Tournament started 2025/08/01 12:00:00
+5th : Hero, 1.000,25 $, 100 $ Bounty
+";
+        let summary =
+            parse_tournament_summary_with(european, false, &NumberFormat::european())?.unwrap();
+        assert_eq!((summary.buy_in_pure, summary.rake), (10.5, 1.0));
+        assert_eq!(summary.total_players, 1200);
+        assert_eq!(summary.total_prize_pool, 12000.5);
+        assert_eq!((summary.my_prize, summary.my_bounty), (1100.25, 100.0));
+        // Amounts of other locales are rejected instead of misread
+        let misread = SAMPLE_SUMMARY.replace("$1,000", "$1.000,50");
+        assert!(parse_tournament_summary(&misread, false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_fields() {
+        let error = parse_tournament_summary("Tournament #2, broken", false).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Missing tournament ID, name, buy-in, "));
+        let no_rank = SAMPLE_SUMMARY.replace("5th : Hero, $300", "");
+        let error = parse_tournament_summary(&no_rank, true).unwrap_err();
+        assert!(error
+            .to_string()
+            .ends_with("Missing rank and prize in tournament summary"));
+    }
+}
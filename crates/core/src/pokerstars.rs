@@ -0,0 +1,416 @@
+//! Writer of hands in the PokerStars hand history format,
+//! which third-party trackers like Hold'em Manager and Hand2Note can import.
+//!
+//! Headers carry buy-ins of tournaments like `$10+$1 USD`, taken from tournament summaries,
+//! and datetimes converted into Eastern Time like PokerStars clients write them.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use chrono_tz::Tz;
+
+use crate::card::Card;
+use crate::errors::PokercraftLocalError;
+use crate::parser::{BetActionType, HandHistory, HandStage, TournamentSummary};
+use crate::timezone::TimeNormalizer;
+
+/// Get the level in Roman numerals, like `"XVI"`.
+fn roman_numeral(mut number: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut result = String::new();
+    for (value, numeral) in NUMERALS {
+        while number >= value {
+            result.push_str(numeral);
+            number -= value;
+        }
+    }
+    result
+}
+
+/// Join cards with spaces, like `"4c 3d As"`.
+fn join_cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|card| card.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Format a money amount of a buy-in, like `10` or `0.98`.
+fn format_amount(amount: f64) -> String {
+    if amount.fract() == 0.0 {
+        format!("{:.0}", amount)
+    } else {
+        format!("{:.2}", amount)
+    }
+}
+
+/// Writer of hands in the PokerStars format.
+#[derive(Clone, Debug)]
+pub struct PokerStarsWriter<'a> {
+    /// Player ID of the hero, whose hole cards are dealt.
+    hero: &'a str,
+    /// Tournaments by their IDs, to write buy-ins.
+    tournaments: HashMap<u64, &'a TournamentSummary>,
+    /// Converts datetimes of exports into Eastern Time.
+    normalizer: TimeNormalizer,
+}
+
+impl<'a> PokerStarsWriter<'a> {
+    /// Create a writer of hands of the given hero, assuming datetimes of exports are in UTC.
+    pub fn new(hero: &'a str) -> Self {
+        PokerStarsWriter {
+            hero,
+            tournaments: HashMap::new(),
+            normalizer: TimeNormalizer::new(Tz::UTC, Tz::America__New_York),
+        }
+    }
+
+    /// Write buy-ins of the given tournaments in headers of their hands.
+    /// Hands of other tournaments are written as freerolls.
+    pub fn with_tournaments<I>(mut self, tournaments: I) -> Self
+    where
+        I: IntoIterator<Item = &'a TournamentSummary>,
+    {
+        self.tournaments.extend(
+            tournaments
+                .into_iter()
+                .map(|tournament| (tournament.id, tournament)),
+        );
+        self
+    }
+
+    /// Set the timezone which datetimes of exports are written in.
+    pub fn with_source_timezone(mut self, source: Tz) -> Self {
+        self.normalizer = TimeNormalizer::new(source, Tz::America__New_York);
+        self
+    }
+
+    /// Get the buy-in of the header, like `$10+$1 USD`, or `Freeroll ` if unknown.
+    fn buy_in(&self, tournament_id: u64) -> String {
+        match self.tournaments.get(&tournament_id) {
+            Some(tournament) => {
+                let symbol = tournament.currency.symbol();
+                format!(
+                    "{}{}+{}{} {}",
+                    symbol,
+                    format_amount(tournament.buy_in_pure),
+                    symbol,
+                    format_amount(tournament.rake),
+                    tournament.currency
+                )
+            }
+            // PokerStars writes two spaces after freerolls
+            None => "Freeroll ".to_string(),
+        }
+    }
+
+    /// Write the given hand in the PokerStars format.
+    pub fn write_hand(&self, hand: &HandHistory) -> Result<String, PokercraftLocalError> {
+        let hero = self.hero;
+        let hand_number = hand.id.trim_start_matches(|c: char| !c.is_ascii_digit());
+        let Some(tournament_id) = hand.tournament_id else {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Hand {} is not a tournament hand",
+                hand.id
+            )));
+        };
+        let mut out = String::new();
+        let mut line = |text: String| {
+            out.push_str(&text);
+            out.push('\n');
+        };
+
+        line(format!(
+            "PokerStars Hand #{}: Tournament #{}, {} Hold'em No Limit - Level {} ({}/{}) - {} ET",
+            hand_number,
+            tournament_id,
+            self.buy_in(tournament_id),
+            roman_numeral(hand.level),
+            hand.sb,
+            hand.bb,
+            self.normalizer
+                .to_target(hand.datetime)?
+                .format("%Y/%m/%d %-H:%M:%S"),
+        ));
+        line(format!(
+            "Table '{} {}' {}-max Seat #{} is the button",
+            tournament_id, hand.table_id, hand.max_seats, hand.button_seat
+        ));
+        for (seat, (player_id, chips)) in hand.seats.iter() {
+            line(format!("Seat {}: {} ({} in chips)", seat, player_id, chips));
+        }
+
+        let all_in = |is_all_in: bool| if is_all_in { " and is all-in" } else { "" };
+        let mut folded: HashMap<&str, HandStage> = HashMap::new();
+        for stage in HandStage::all() {
+            let cards = match stage {
+                HandStage::Preflop => 0,
+                HandStage::Flop => 3,
+                HandStage::Turn => 4,
+                HandStage::River => 5,
+            };
+            match stage {
+                HandStage::Preflop => {}
+                _ if hand.community_cards.len() < cards => break,
+                HandStage::Flop => line(format!(
+                    "*** FLOP *** [{}]",
+                    join_cards(&hand.community_cards[..3])
+                )),
+                _ => line(format!(
+                    "*** {} *** [{}] [{}]",
+                    stage.to_string().to_uppercase(),
+                    join_cards(&hand.community_cards[..cards - 1]),
+                    hand.community_cards[cards - 1]
+                )),
+            }
+
+            // Chips committed on this street, to tell how much each raise adds
+            let mut committed: HashMap<&str, u64> = HashMap::new();
+            let mut level: u64 = 0;
+            // Hole cards are dealt after antes and blinds
+            let actions = hand.actions(stage);
+            let (forced, voluntary) = actions.split_at(
+                actions
+                    .iter()
+                    .take_while(|action| {
+                        matches!(action.action, BetActionType::Ante | BetActionType::Blind)
+                    })
+                    .count(),
+            );
+            for (part, actions) in [forced, voluntary].into_iter().enumerate() {
+                if stage == HandStage::Preflop && part == 1 {
+                    line("*** HOLE CARDS ***".to_string());
+                    if let Some((c1, c2)) = hand.known_cards.get(hero) {
+                        line(format!("Dealt to {} [{} {}]", hero, c1, c2));
+                    }
+                }
+                for action in actions {
+                    let player_id = action.player_id.as_str();
+                    let put = committed.entry(player_id).or_default();
+                    let text = match action.action {
+                        BetActionType::Fold => {
+                            folded.insert(player_id, stage);
+                            "folds".to_string()
+                        }
+                        BetActionType::Check => "checks".to_string(),
+                        BetActionType::Ante => format!("posts the ante {}", action.amount),
+                        BetActionType::Blind => {
+                            *put += action.amount;
+                            level = level.max(*put);
+                            let blind = if hand.seat_number(player_id).ok() == hand.sb_seat {
+                                "small blind"
+                            } else {
+                                "big blind"
+                            };
+                            format!("posts {} {}", blind, action.amount)
+                        }
+                        BetActionType::Call => {
+                            *put += action.amount;
+                            format!("calls {}", action.amount)
+                        }
+                        BetActionType::Bet => {
+                            *put += action.amount;
+                            level = level.max(*put);
+                            format!("bets {}", action.amount)
+                        }
+                        BetActionType::Raise => {
+                            let raised = action.amount.saturating_sub(level);
+                            *put = action.amount;
+                            level = level.max(action.amount);
+                            format!("raises {} to {}", raised, action.amount)
+                        }
+                    };
+                    line(format!(
+                        "{}: {}{}",
+                        player_id,
+                        text,
+                        all_in(action.is_all_in)
+                    ));
+                }
+            }
+        }
+        if let Some((player_id, amount)) = &hand.uncalled_returned {
+            line(format!(
+                "Uncalled bet ({}) returned to {}",
+                amount, player_id
+            ));
+        }
+
+        // Players who did not fold go to the showdown, if there are at least 2 of them
+        let remaining = hand
+            .seats
+            .values()
+            .map(|(player_id, _)| player_id.as_str())
+            .filter(|player_id| !folded.contains_key(player_id))
+            .collect::<Vec<_>>();
+        let showdown = remaining.len() >= 2;
+        if showdown {
+            line("*** SHOW DOWN ***".to_string());
+            for player_id in remaining.iter() {
+                if let Some((c1, c2)) = hand.known_cards.get(*player_id) {
+                    line(format!("{}: shows [{} {}]", player_id, c1, c2));
+                }
+            }
+        }
+        for (_, (player_id, _)) in hand.seats.iter() {
+            if let Some(won) = hand.wons.get(player_id) {
+                line(format!("{} collected {} from pot", player_id, won));
+            }
+        }
+
+        line("*** SUMMARY ***".to_string());
+        line(format!("Total pot {} | Rake 0", hand.total_pot()));
+        if !hand.community_cards.is_empty() {
+            line(format!("Board [{}]", join_cards(&hand.community_cards)));
+        }
+        for (&seat, (player_id, _)) in hand.seats.iter() {
+            let role = if seat == hand.button_seat {
+                " (button)"
+            } else if Some(seat) == hand.sb_seat {
+                " (small blind)"
+            } else if Some(seat) == hand.bb_seat {
+                " (big blind)"
+            } else {
+                ""
+            };
+            let shown = hand
+                .known_cards
+                .get(player_id)
+                .filter(|_| showdown && !folded.contains_key(player_id.as_str()));
+            let won = hand.wons.get(player_id);
+            let result = match (folded.get(player_id.as_str()), shown, won) {
+                (Some(HandStage::Preflop), _, _) => "folded before Flop".to_string(),
+                (Some(stage), _, _) => format!("folded on the {}", stage),
+                (None, Some((c1, c2)), Some(won)) => {
+                    format!("showed [{} {}] and won ({})", c1, c2, won)
+                }
+                (None, Some((c1, c2)), None) => format!("showed [{} {}] and lost", c1, c2),
+                (None, None, Some(won)) => format!("collected ({})", won),
+                (None, None, None) => "mucked".to_string(),
+            };
+            line(format!("Seat {}: {}{} {}", seat, player_id, role, result));
+        }
+        Ok(out)
+    }
+
+    /// Write the given hands in the PokerStars format, separated by blank lines.
+    pub fn write_hands<W: Write>(
+        &self,
+        hands: &[HandHistory],
+        mut writer: W,
+    ) -> Result<(), PokercraftLocalError> {
+        let mut text = String::new();
+        for hand in hands {
+            text.push_str(&self.write_hand(hand)?);
+            text.push_str("\n\n");
+        }
+        writer.write_all(text.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::hand_history::tests::SAMPLE_HAND;
+    use crate::parser::tournament_summary::tests::SAMPLE_SUMMARY;
+    use crate::parser::{parse_hands, parse_tournament_summary};
+
+    #[test]
+    fn test_write_hand() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HAND.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let summary = SAMPLE_SUMMARY
+            .replace("#123456789", "#220597937")
+            .replace('$', "¥")
+            .replace("¥10 + ¥1", "¥100 + ¥10");
+        let tournament = parse_tournament_summary(&summary, false)?.unwrap();
+        let writer = PokerStarsWriter::new("Hero").with_tournaments([&tournament]);
+        let text = writer.write_hand(&hands[0])?;
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "PokerStars Hand #4832872904: Tournament #220597937, ¥100+¥10 CNY Hold'em No Limit - Level XVI (600/1200) - 2025/07/31 20:53:29 ET"
+        );
+        assert_eq!(lines[1], "Table '220597937 25' 7-max Seat #7 is the button");
+        assert_eq!(lines[2], "Seat 1: f123395 (43493 in chips)");
+        for expected in [
+            "392ff24f: posts big blind 1200",
+            "*** HOLE CARDS ***",
+            "Dealt to Hero [Jh 8c]",
+            "afc7064f: raises 1320 to 2520",
+            "392ff24f: calls 1320",
+            "*** FLOP *** [4c 3d As]",
+            "*** TURN *** [4c 3d As] [Jd]",
+            "471910c: bets 4800",
+            "Uncalled bet (4800) returned to 471910c",
+            "471910c collected 9210 from pot",
+            "Total pot 9210 | Rake 0",
+            "Seat 1: f123395 (small blind) folded before Flop",
+            "Seat 4: afc7064f folded on the Turn",
+            "Seat 6: 471910c collected (9210)",
+            "Seat 7: Hero (button) folded before Flop",
+        ] {
+            assert!(lines.contains(&expected), "Missing line: {}", expected);
+        }
+        assert!(!text.contains("SHOW DOWN"));
+        let position = |expected: &str| lines.iter().position(|line| *line == expected);
+        assert!(position("f123395: posts small blind 600") < position("*** HOLE CARDS ***"));
+        assert!(position("*** HOLE CARDS ***") < position("84466c0e: folds"));
+
+        let mut buffer = Vec::new();
+        writer.write_hands(&hands, &mut buffer)?;
+        assert_eq!(String::from_utf8_lossy(&buffer), format!("{}\n\n", text));
+        assert_eq!(roman_numeral(1994), "MCMXCIV");
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_header() -> Result<(), PokercraftLocalError> {
+        // Header pattern of trackers, matching headers written by PokerStars clients
+        let header = regex::Regex::new(
+            r"^PokerStars Hand #\d+: Tournament #\d+, (Freeroll |\S?[\d.]+\+\S?[\d.]+ [A-Z]{3}) Hold'em No Limit - Level [IVXLCDM]+ \(\d+/\d+\) - \d{4}/\d{2}/\d{2} \d{1,2}:\d{2}:\d{2} ET$",
+        )
+        .unwrap();
+        for real in [
+            "PokerStars Hand #208257326540: Tournament #2896845785, $10+$1 USD Hold'em No Limit - Level I (10/20) - 2020/03/01 6:00:00 ET",
+            "PokerStars Hand #171234567890: Tournament #2024681357, $0.98+$0.02 USD Hold'em No Limit - Level IV (25/50) - 2017/06/11 14:05:12 ET",
+            "PokerStars Hand #175551234567: Tournament #2071234567, Freeroll  Hold'em No Limit - Level II (15/30) - 2017/09/03 8:30:45 ET",
+        ] {
+            assert!(header.is_match(real), "Unmatched real header: {}", real);
+        }
+
+        let hands = parse_hands(SAMPLE_HAND.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let tournament =
+            parse_tournament_summary(&SAMPLE_SUMMARY.replace("#123456789", "#220597937"), false)?
+                .unwrap();
+        let paid = PokerStarsWriter::new("Hero")
+            .with_tournaments([&tournament])
+            .with_source_timezone(chrono_tz::Asia::Seoul)
+            .write_hand(&hands[0])?;
+        let paid = paid.lines().next().unwrap();
+        assert!(header.is_match(paid), "Unmatched header: {}", paid);
+        assert!(paid.contains(", $10+$1 USD Hold'em No Limit - "));
+        assert!(paid.ends_with(" - 2025/07/31 11:53:29 ET"));
+
+        let freeroll = PokerStarsWriter::new("Hero").write_hand(&hands[0])?;
+        let freeroll = freeroll.lines().next().unwrap();
+        assert!(header.is_match(freeroll), "Unmatched header: {}", freeroll);
+        assert!(freeroll.contains("Tournament #220597937, Freeroll  Hold'em No Limit"));
+        Ok(())
+    }
+}
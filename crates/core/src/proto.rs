@@ -0,0 +1,532 @@
+//! Protobuf messages of core result types, in package `pokercraft.v1`
+//! defined by `proto/pokercraft/v1/results.proto`,
+//! so results can be persisted and exchanged in a stable format.
+//! Encode and decode messages with `prost::Message`.
+
+use std::collections::HashMap;
+
+use chrono::DateTime;
+
+use crate::bankroll;
+use crate::card::Card;
+use crate::equity;
+use crate::errors::PokercraftLocalError;
+use crate::parser::{self, HandStage};
+use crate::stats;
+
+fn invalid(message: &str) -> PokercraftLocalError {
+    PokercraftLocalError::GeneralError(format!("Invalid protobuf message: {}", message))
+}
+
+/// See `PlayerWinLoses` of the proto file.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PlayerWinLoses {
+    #[prost(uint64, repeated, tag = "1")]
+    pub wins: Vec<u64>,
+    #[prost(uint64, tag = "2")]
+    pub loses: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EquityResult {
+    #[prost(message, repeated, tag = "1")]
+    pub players: Vec<PlayerWinLoses>,
+}
+
+/// See `BankruptcyMetric` of the proto file.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BankruptcyMetric {
+    #[prost(double, repeated, tag = "1")]
+    pub relative_returns: Vec<f64>,
+    #[prost(uint32, repeated, tag = "2")]
+    pub iterations: Vec<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, prost::Enumeration)]
+#[repr(i32)]
+pub enum Street {
+    Unspecified = 0,
+    Preflop = 1,
+    Flop = 2,
+    Turn = 3,
+    River = 4,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, prost::Enumeration)]
+#[repr(i32)]
+pub enum BetActionType {
+    Unspecified = 0,
+    Fold = 1,
+    Check = 2,
+    Call = 3,
+    Bet = 4,
+    Raise = 5,
+    Ante = 6,
+    Blind = 7,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BetAction {
+    #[prost(string, tag = "1")]
+    pub player_id: String,
+    #[prost(enumeration = "BetActionType", tag = "2")]
+    pub action: i32,
+    #[prost(uint64, tag = "3")]
+    pub amount: u64,
+    #[prost(bool, tag = "4")]
+    pub is_all_in: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Seat {
+    #[prost(uint32, tag = "1")]
+    pub seat: u32,
+    #[prost(string, tag = "2")]
+    pub player_id: String,
+    #[prost(uint64, tag = "3")]
+    pub initial_chips: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct UncalledBet {
+    #[prost(string, tag = "1")]
+    pub player_id: String,
+    #[prost(uint64, tag = "2")]
+    pub amount: u64,
+}
+
+/// See `HandHistory` of the proto file.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HandHistory {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(uint64, optional, tag = "2")]
+    pub tournament_id: Option<u64>,
+    #[prost(string, optional, tag = "3")]
+    pub tournament_name: Option<String>,
+    #[prost(uint32, tag = "4")]
+    pub level: u32,
+    #[prost(uint64, tag = "5")]
+    pub sb: u64,
+    #[prost(uint64, tag = "6")]
+    pub bb: u64,
+    #[prost(int64, tag = "7")]
+    pub datetime_millis: i64,
+    #[prost(uint32, tag = "8")]
+    pub button_seat: u32,
+    #[prost(uint32, optional, tag = "9")]
+    pub sb_seat: Option<u32>,
+    #[prost(uint32, optional, tag = "10")]
+    pub bb_seat: Option<u32>,
+    #[prost(uint32, tag = "11")]
+    pub max_seats: u32,
+    #[prost(string, tag = "12")]
+    pub table_id: String,
+    #[prost(message, repeated, tag = "13")]
+    pub seats: Vec<Seat>,
+    #[prost(map = "string, string", tag = "14")]
+    pub known_cards: HashMap<String, String>,
+    #[prost(map = "string, uint64", tag = "15")]
+    pub wons: HashMap<String, u64>,
+    #[prost(string, repeated, tag = "16")]
+    pub community_cards: Vec<String>,
+    #[prost(message, repeated, tag = "17")]
+    pub actions_preflop: Vec<BetAction>,
+    #[prost(message, repeated, tag = "18")]
+    pub actions_flop: Vec<BetAction>,
+    #[prost(message, repeated, tag = "19")]
+    pub actions_turn: Vec<BetAction>,
+    #[prost(message, repeated, tag = "20")]
+    pub actions_river: Vec<BetAction>,
+    #[prost(message, optional, tag = "21")]
+    pub uncalled_returned: Option<UncalledBet>,
+    #[prost(map = "string, enumeration(Street)", tag = "22")]
+    pub all_ined: HashMap<String, i32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TournamentAggregate {
+    #[prost(uint64, tag = "1")]
+    pub tournaments: u64,
+    #[prost(uint64, tag = "2")]
+    pub entries: u64,
+    #[prost(double, tag = "3")]
+    pub total_buy_in: f64,
+    #[prost(double, tag = "4")]
+    pub total_rake: f64,
+    #[prost(double, tag = "5")]
+    pub total_prize: f64,
+    #[prost(double, tag = "6")]
+    pub total_bounty: f64,
+    #[prost(uint64, tag = "7")]
+    pub bounty_tournaments: u64,
+    #[prost(uint64, tag = "8")]
+    pub itm_count: u64,
+    #[prost(double, optional, tag = "9")]
+    pub best_rre: Option<f64>,
+    #[prost(double, tag = "10")]
+    pub biggest_prize: f64,
+}
+
+impl From<&equity::EquityResult> for EquityResult {
+    fn from(result: &equity::EquityResult) -> Self {
+        EquityResult {
+            players: (0..result.num_players())
+                .filter_map(|i| result.get_winlosses(i).ok())
+                .map(|(wins, loses)| PlayerWinLoses { wins, loses })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<EquityResult> for equity::EquityResult {
+    type Error = PokercraftLocalError;
+
+    fn try_from(message: EquityResult) -> Result<Self, Self::Error> {
+        let (wins, loses) = message
+            .players
+            .into_iter()
+            .map(|player| (player.wins, player.loses))
+            .unzip();
+        equity::EquityResult::from_counts(wins, loses)
+    }
+}
+
+impl From<&bankroll::BankruptcyMetric> for BankruptcyMetric {
+    fn from(metric: &bankroll::BankruptcyMetric) -> Self {
+        let (relative_returns, iterations) = metric.simulated_results().iter().copied().unzip();
+        BankruptcyMetric {
+            relative_returns,
+            iterations,
+        }
+    }
+}
+
+impl TryFrom<BankruptcyMetric> for bankroll::BankruptcyMetric {
+    type Error = PokercraftLocalError;
+
+    fn try_from(message: BankruptcyMetric) -> Result<Self, Self::Error> {
+        if message.relative_returns.len() != message.iterations.len() {
+            return Err(invalid(
+                "Lengths of relative returns and iterations are different",
+            ));
+        }
+        Ok(bankroll::BankruptcyMetric::new(
+            message.relative_returns.into_iter().zip(message.iterations),
+        ))
+    }
+}
+
+impl From<HandStage> for Street {
+    fn from(stage: HandStage) -> Self {
+        match stage {
+            HandStage::Preflop => Street::Preflop,
+            HandStage::Flop => Street::Flop,
+            HandStage::Turn => Street::Turn,
+            HandStage::River => Street::River,
+        }
+    }
+}
+
+impl TryFrom<Street> for HandStage {
+    type Error = PokercraftLocalError;
+
+    fn try_from(street: Street) -> Result<Self, Self::Error> {
+        match street {
+            Street::Unspecified => Err(invalid("Unspecified street")),
+            Street::Preflop => Ok(HandStage::Preflop),
+            Street::Flop => Ok(HandStage::Flop),
+            Street::Turn => Ok(HandStage::Turn),
+            Street::River => Ok(HandStage::River),
+        }
+    }
+}
+
+impl From<&parser::BetAction> for BetAction {
+    fn from(action: &parser::BetAction) -> Self {
+        let action_type = match action.action {
+            parser::BetActionType::Fold => BetActionType::Fold,
+            parser::BetActionType::Check => BetActionType::Check,
+            parser::BetActionType::Call => BetActionType::Call,
+            parser::BetActionType::Bet => BetActionType::Bet,
+            parser::BetActionType::Raise => BetActionType::Raise,
+            parser::BetActionType::Ante => BetActionType::Ante,
+            parser::BetActionType::Blind => BetActionType::Blind,
+        };
+        BetAction {
+            player_id: action.player_id.clone(),
+            action: action_type as i32,
+            amount: action.amount,
+            is_all_in: action.is_all_in,
+        }
+    }
+}
+
+impl TryFrom<BetAction> for parser::BetAction {
+    type Error = PokercraftLocalError;
+
+    fn try_from(message: BetAction) -> Result<Self, Self::Error> {
+        let action = match BetActionType::try_from(message.action) {
+            Ok(BetActionType::Fold) => parser::BetActionType::Fold,
+            Ok(BetActionType::Check) => parser::BetActionType::Check,
+            Ok(BetActionType::Call) => parser::BetActionType::Call,
+            Ok(BetActionType::Bet) => parser::BetActionType::Bet,
+            Ok(BetActionType::Raise) => parser::BetActionType::Raise,
+            Ok(BetActionType::Ante) => parser::BetActionType::Ante,
+            Ok(BetActionType::Blind) => parser::BetActionType::Blind,
+            Ok(BetActionType::Unspecified) | Err(_) => {
+                return Err(invalid("Unknown bet action type"))
+            }
+        };
+        Ok(parser::BetAction {
+            player_id: message.player_id,
+            action,
+            amount: message.amount,
+            is_all_in: message.is_all_in,
+        })
+    }
+}
+
+fn to_actions(actions: Vec<BetAction>) -> Result<Vec<parser::BetAction>, PokercraftLocalError> {
+    actions.into_iter().map(TryInto::try_into).collect()
+}
+
+impl From<&parser::HandHistory> for HandHistory {
+    fn from(hand: &parser::HandHistory) -> Self {
+        let actions = |actions: &[parser::BetAction]| actions.iter().map(Into::into).collect();
+        HandHistory {
+            id: hand.id.clone(),
+            tournament_id: hand.tournament_id,
+            tournament_name: hand.tournament_name.clone(),
+            level: hand.level,
+            sb: hand.sb,
+            bb: hand.bb,
+            datetime_millis: hand.datetime.and_utc().timestamp_millis(),
+            button_seat: hand.button_seat,
+            sb_seat: hand.sb_seat,
+            bb_seat: hand.bb_seat,
+            max_seats: hand.max_seats,
+            table_id: hand.table_id.clone(),
+            seats: hand
+                .seats
+                .iter()
+                .map(|(&seat, (player_id, chips))| Seat {
+                    seat,
+                    player_id: player_id.clone(),
+                    initial_chips: *chips,
+                })
+                .collect(),
+            known_cards: hand
+                .known_cards
+                .iter()
+                .map(|(player_id, (card1, card2))| {
+                    (player_id.clone(), format!("{}{}", card1, card2))
+                })
+                .collect(),
+            wons: hand.wons.clone(),
+            community_cards: hand
+                .community_cards
+                .iter()
+                .map(|card| card.to_string())
+                .collect(),
+            actions_preflop: actions(&hand.actions_preflop),
+            actions_flop: actions(&hand.actions_flop),
+            actions_turn: actions(&hand.actions_turn),
+            actions_river: actions(&hand.actions_river),
+            uncalled_returned: hand.uncalled_returned.as_ref().map(|(player_id, amount)| {
+                UncalledBet {
+                    player_id: player_id.clone(),
+                    amount: *amount,
+                }
+            }),
+            all_ined: hand
+                .all_ined
+                .iter()
+                .map(|(player_id, &stage)| (player_id.clone(), Street::from(stage) as i32))
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<HandHistory> for parser::HandHistory {
+    type Error = PokercraftLocalError;
+
+    fn try_from(message: HandHistory) -> Result<Self, Self::Error> {
+        let datetime = DateTime::from_timestamp_millis(message.datetime_millis)
+            .ok_or_else(|| invalid("Datetime out of range"))?
+            .naive_utc();
+        let known_cards = message
+            .known_cards
+            .into_iter()
+            .map(|(player_id, cards)| {
+                if cards.len() != 4 || !cards.is_char_boundary(2) {
+                    return Err(invalid("Hole cards should be like \"AsKd\""));
+                }
+                let hole_cards = (Card::try_from(&cards[..2])?, Card::try_from(&cards[2..])?);
+                Ok((player_id, hole_cards))
+            })
+            .collect::<Result<_, PokercraftLocalError>>()?;
+        let all_ined = message
+            .all_ined
+            .into_iter()
+            .map(|(player_id, street)| {
+                let street = Street::try_from(street).map_err(|_| invalid("Unknown street"))?;
+                Ok((player_id, HandStage::try_from(street)?))
+            })
+            .collect::<Result<_, PokercraftLocalError>>()?;
+        Ok(parser::HandHistory {
+            id: message.id,
+            tournament_id: message.tournament_id,
+            tournament_name: message.tournament_name,
+            level: message.level,
+            sb: message.sb,
+            bb: message.bb,
+            datetime,
+            button_seat: message.button_seat,
+            sb_seat: message.sb_seat,
+            bb_seat: message.bb_seat,
+            max_seats: message.max_seats,
+            table_id: message.table_id,
+            seats: message
+                .seats
+                .into_iter()
+                .map(|seat| (seat.seat, (seat.player_id, seat.initial_chips)))
+                .collect(),
+            known_cards,
+            wons: message.wons,
+            community_cards: message
+                .community_cards
+                .iter()
+                .map(|card| Card::try_from(card.as_str()))
+                .collect::<Result<_, _>>()?,
+            actions_preflop: to_actions(message.actions_preflop)?,
+            actions_flop: to_actions(message.actions_flop)?,
+            actions_turn: to_actions(message.actions_turn)?,
+            actions_river: to_actions(message.actions_river)?,
+            uncalled_returned: message
+                .uncalled_returned
+                .map(|bet| (bet.player_id, bet.amount)),
+            all_ined,
+        })
+    }
+}
+
+impl From<&stats::TournamentAggregate> for TournamentAggregate {
+    fn from(aggregate: &stats::TournamentAggregate) -> Self {
+        TournamentAggregate {
+            tournaments: aggregate.tournaments,
+            entries: aggregate.entries,
+            total_buy_in: aggregate.total_buy_in,
+            total_rake: aggregate.total_rake,
+            total_prize: aggregate.total_prize,
+            total_bounty: aggregate.total_bounty,
+            bounty_tournaments: aggregate.bounty_tournaments,
+            itm_count: aggregate.itm_count,
+            best_rre: aggregate.best_rre,
+            biggest_prize: aggregate.biggest_prize,
+        }
+    }
+}
+
+impl From<TournamentAggregate> for stats::TournamentAggregate {
+    fn from(message: TournamentAggregate) -> Self {
+        stats::TournamentAggregate {
+            tournaments: message.tournaments,
+            entries: message.entries,
+            total_buy_in: message.total_buy_in,
+            total_rake: message.total_rake,
+            total_prize: message.total_prize,
+            total_bounty: message.total_bounty,
+            bounty_tournaments: message.bounty_tournaments,
+            itm_count: message.itm_count,
+            best_rre: message.best_rre,
+            biggest_prize: message.biggest_prize,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::*;
+    use crate::parser::hand_history::tests::SAMPLE_HAND;
+    use crate::parser::parse_hands;
+    use crate::parser::tournament_summary::tests::SAMPLE_SUMMARY;
+
+    fn card(value: &str) -> Card {
+        Card::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn test_equity_result_roundtrip() -> Result<(), PokercraftLocalError> {
+        let result = equity::EquityResult::new(
+            vec![(card("As"), card("Ah")), (card("Kd"), card("Kc"))],
+            vec![card("2c"), card("7d"), card("9h"), card("4s")],
+            false,
+        )?;
+        let bytes = EquityResult::from(&result).encode_to_vec();
+        let decoded = equity::EquityResult::try_from(
+            EquityResult::decode(bytes.as_slice()).map_err(|e| invalid(&e.to_string()))?,
+        )?;
+        assert_eq!(decoded.breakdown()?, result.breakdown()?);
+
+        let mismatched = EquityResult {
+            players: vec![PlayerWinLoses {
+                wins: vec![1, 0],
+                loses: 0,
+            }],
+        };
+        assert!(equity::EquityResult::try_from(mismatched).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bankruptcy_metric_roundtrip() -> Result<(), PokercraftLocalError> {
+        let metric = bankroll::BankruptcyMetric::new([(0.0, 10), (1.5, 100), (3.0, 42)]);
+        let bytes = BankruptcyMetric::from(&metric).encode_to_vec();
+        let decoded = bankroll::BankruptcyMetric::try_from(
+            BankruptcyMetric::decode(bytes.as_slice()).map_err(|e| invalid(&e.to_string()))?,
+        )?;
+        assert_eq!(decoded.simulated_results(), metric.simulated_results());
+
+        let mismatched = BankruptcyMetric {
+            relative_returns: vec![1.0],
+            iterations: vec![],
+        };
+        assert!(bankroll::BankruptcyMetric::try_from(mismatched).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hand_history_roundtrip() -> Result<(), PokercraftLocalError> {
+        let hand = parse_hands(SAMPLE_HAND.as_bytes()).next().unwrap()?;
+        let bytes = HandHistory::from(&hand).encode_to_vec();
+        let decoded = parser::HandHistory::try_from(
+            HandHistory::decode(bytes.as_slice()).map_err(|e| invalid(&e.to_string()))?,
+        )?;
+        assert_eq!(HandHistory::from(&decoded), HandHistory::from(&hand));
+        assert_eq!(decoded.datetime, hand.datetime);
+        assert_eq!(decoded.seats, hand.seats);
+        assert_eq!(decoded.known_cards, hand.known_cards);
+        assert_eq!(decoded.actions_preflop, hand.actions_preflop);
+        assert_eq!(decoded.net_profit("Hero"), hand.net_profit("Hero"));
+
+        let mut message = HandHistory::from(&hand);
+        message.community_cards.push("1x".to_string());
+        assert!(parser::HandHistory::try_from(message).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tournament_aggregate_roundtrip() -> Result<(), PokercraftLocalError> {
+        let summary = crate::parser::parse_tournament_summary(SAMPLE_SUMMARY, false)?.unwrap();
+        let aggregate = stats::aggregate_tournaments([&summary]);
+        let bytes = TournamentAggregate::from(&aggregate).encode_to_vec();
+        let decoded = stats::TournamentAggregate::from(
+            TournamentAggregate::decode(bytes.as_slice()).map_err(|e| invalid(&e.to_string()))?,
+        );
+        assert_eq!(decoded, aggregate);
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+//! Hand ranges in the common text notation, like `"QQ+,AKs,A5s-A2s,KdQd"`.
+
+use std::collections::HashSet;
+
+use crate::card::{normalize_hand, Card, CardNumber, CardShape, Hand, NUM_OF_NUMBERS};
+use crate::errors::PokercraftLocalError;
+
+/// Get the row (or column) of the given card number
+/// in the starting hand grid, from Ace (0) down to Two (12).
+fn grid_index(number: CardNumber) -> usize {
+    CardNumber::Ace as usize - number as usize
+}
+
+/// Get the hand class of the given cell of the 13x13 starting hand grid, like `"AKs"`.
+/// Rows and columns go from Ace down to Two; Cells above the diagonal are suited,
+/// cells below the diagonal are offsuit, and the diagonal has pairs.
+pub fn grid_class(row: usize, col: usize) -> Option<String> {
+    if row >= NUM_OF_NUMBERS || col >= NUM_OF_NUMBERS {
+        return None;
+    }
+    let numbers = CardNumber::all();
+    let high = char::from(numbers[NUM_OF_NUMBERS - 1 - row.min(col)]);
+    let low = char::from(numbers[NUM_OF_NUMBERS - 1 - row.max(col)]);
+    Some(match row.cmp(&col) {
+        std::cmp::Ordering::Equal => format!("{}{}", high, low),
+        std::cmp::Ordering::Less => format!("{}{}s", high, low),
+        std::cmp::Ordering::Greater => format!("{}{}o", high, low),
+    })
+}
+
+/// A set of starting hand combinations.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct HandRange {
+    /// Unique combos, with the higher card first.
+    combos: Vec<Hand>,
+}
+
+/// Get all combos of the given card numbers;
+/// `suited` of `None` means both suited and offsuit.
+fn class_combos(high: CardNumber, low: CardNumber, suited: Option<bool>) -> Vec<Hand> {
+    let shapes = CardShape::all();
+    let mut combos = Vec::new();
+    for (i, &shape1) in shapes.iter().enumerate() {
+        for (j, &shape2) in shapes.iter().enumerate() {
+            if (high == low && i >= j) || (high != low && suited.is_some_and(|s| s != (i == j))) {
+                continue;
+            }
+            combos.push((
+                Card {
+                    shape: shape1,
+                    number: high,
+                },
+                Card {
+                    shape: shape2,
+                    number: low,
+                },
+            ));
+        }
+    }
+    combos
+}
+
+/// Parse a hand class like `"AKs"`, `"T9o"`, `"QJ"` or `"77"`
+/// into (high, low, suitedness).
+fn parse_class(
+    token: &str,
+) -> Result<(CardNumber, CardNumber, Option<bool>), PokercraftLocalError> {
+    let invalid = || PokercraftLocalError::GeneralError(format!("Invalid hand class: {}", token));
+    let chars: Vec<char> = token.chars().collect();
+    let (first, second, suited) = match chars.as_slice() {
+        [first, second] => (*first, *second, None),
+        [first, second, 's'] | [first, second, 'S'] => (*first, *second, Some(true)),
+        [first, second, 'o'] | [first, second, 'O'] => (*first, *second, Some(false)),
+        _ => return Err(invalid()),
+    };
+    let (first, second) = (CardNumber::try_from(first)?, CardNumber::try_from(second)?);
+    if first == second && suited.is_some() {
+        return Err(invalid());
+    }
+    Ok((first.max(second), first.min(second), suited))
+}
+
+/// Get card numbers between the given numbers, inclusive.
+fn numbers_between(a: CardNumber, b: CardNumber) -> impl Iterator<Item = CardNumber> {
+    let (low, high) = (a.min(b), a.max(b));
+    CardNumber::all()
+        .into_iter()
+        .filter(move |&number| low <= number && number <= high)
+}
+
+/// Parse a single comma-separated token of a range.
+fn parse_token(token: &str) -> Result<Vec<Hand>, PokercraftLocalError> {
+    if token.eq_ignore_ascii_case("random") {
+        let cards = Card::all();
+        let mut combos = Vec::with_capacity(1326);
+        for (i, &c1) in cards.iter().enumerate() {
+            for &c2 in cards.iter().skip(i + 1) {
+                combos.push((c1, c2));
+            }
+        }
+        return Ok(combos);
+    }
+
+    // Exact combo like "AsKd"
+    if token.len() == 4 && token.is_char_boundary(2) {
+        if let (Ok(c1), Ok(c2)) = (Card::try_from(&token[..2]), Card::try_from(&token[2..])) {
+            if c1 == c2 {
+                return Err(PokercraftLocalError::GeneralError(format!(
+                    "Duplicated card in combo: {}",
+                    token
+                )));
+            }
+            return Ok(vec![(c1, c2)]);
+        }
+    }
+
+    // Span like "22-55" or "A5s-A2s"
+    if let Some((from, to)) = token.split_once('-') {
+        let (high1, low1, suited1) = parse_class(from.trim())?;
+        let (high2, low2, suited2) = parse_class(to.trim())?;
+        if high1 == low1 && high2 == low2 {
+            return Ok(numbers_between(high1, high2)
+                .flat_map(|number| class_combos(number, number, None))
+                .collect());
+        } else if high1 == high2 && suited1 == suited2 && high1 != low1 && high2 != low2 {
+            return Ok(numbers_between(low1, low2)
+                .flat_map(|low| class_combos(high1, low, suited1))
+                .collect());
+        }
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "Invalid range span: {}",
+            token
+        )));
+    }
+
+    // Class like "AKs", optionally followed by "+"
+    match token.strip_suffix('+') {
+        Some(class) => {
+            let (high, low, suited) = parse_class(class.trim())?;
+            if high == low {
+                Ok(numbers_between(high, CardNumber::Ace)
+                    .flat_map(|number| class_combos(number, number, None))
+                    .collect())
+            } else {
+                Ok(numbers_between(low, high)
+                    .filter(|&kicker| kicker < high)
+                    .flat_map(|kicker| class_combos(high, kicker, suited))
+                    .collect())
+            }
+        }
+        None => {
+            let (high, low, suited) = parse_class(token)?;
+            Ok(class_combos(high, low, suited))
+        }
+    }
+}
+
+impl HandRange {
+    /// Create a new range from the given combos, ignoring duplicates.
+    pub fn from_combos<I>(combos: I) -> Self
+    where
+        I: IntoIterator<Item = Hand>,
+    {
+        let mut seen: HashSet<Hand> = HashSet::new();
+        let combos = combos
+            .into_iter()
+            .map(normalize_hand)
+            .filter(|combo| seen.insert(*combo))
+            .collect();
+        HandRange { combos }
+    }
+
+    /// Get all combos of this range.
+    pub fn combos(&self) -> &[Hand] {
+        &self.combos
+    }
+
+    /// Get the number of combos.
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    /// Check if this range has no combos.
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// Check if this range contains the given combo, in any card order.
+    pub fn contains(&self, hand: &Hand) -> bool {
+        self.combos.contains(&normalize_hand(*hand))
+    }
+
+    /// Count combos in each cell of the 13x13 starting hand grid.
+    /// See `grid_class` for the layout.
+    pub fn grid_counts(&self) -> [[usize; NUM_OF_NUMBERS]; NUM_OF_NUMBERS] {
+        let mut counts = [[0; NUM_OF_NUMBERS]; NUM_OF_NUMBERS];
+        for (high, low) in self.combos.iter() {
+            let (row, col) = (grid_index(high.number), grid_index(low.number));
+            if high.shape == low.shape {
+                counts[row][col] += 1;
+            } else {
+                counts[col][row] += 1;
+            }
+        }
+        counts
+    }
+
+    /// Get a new range without combos using any of the given dead cards.
+    pub fn without_cards(&self, dead_cards: &[Card]) -> Self {
+        HandRange {
+            combos: self
+                .combos
+                .iter()
+                .filter(|(c1, c2)| !dead_cards.contains(c1) && !dead_cards.contains(c2))
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<&str> for HandRange {
+    type Error = PokercraftLocalError;
+
+    /// Parse a comma-separated range. Each token is one of:
+    /// - An exact combo like `"AsKd"`
+    /// - A hand class like `"AKs"`, `"AKo"`, `"AK"` or `"TT"`
+    /// - A class with `+`, like `"TT+"` (TT to AA) or `"ATs+"` (ATs to AKs)
+    /// - A span like `"22-55"` or `"A5s-A2s"`
+    /// - `"random"` for all 1326 combos
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut combos = Vec::new();
+        for token in value.split(',').map(str::trim) {
+            if token.is_empty() {
+                continue;
+            }
+            combos.extend(parse_token(token)?);
+        }
+        if combos.is_empty() {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Empty range: \"{}\"",
+                value
+            )));
+        }
+        Ok(Self::from_combos(combos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(value: &str) -> Card {
+        Card::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn test_range_parsing() -> Result<(), PokercraftLocalError> {
+        let cases = [
+            ("AA", 6),
+            ("AKs", 4),
+            ("AKo", 12),
+            ("AK", 16),
+            ("TT+", 30),
+            ("ATs+", 16),
+            ("22-55", 24),
+            ("A5s-A2s", 16),
+            ("KdQd", 1),
+            ("QQ+, AKs, AKo", 34),
+            ("AA,AsAh,AA", 6),
+            ("random", 1326),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(HandRange::try_from(text)?.len(), expected, "{}", text);
+        }
+
+        let range = HandRange::try_from("ATs+")?;
+        assert!(range.contains(&(card("Js"), card("As"))));
+        assert!(!range.contains(&(card("Ks"), card("Kh"))));
+        assert!(!range.contains(&(card("As"), card("Jh"))));
+
+        for invalid in ["", "AAs", "AXs", "AKs-QJs", "AsAs", "A"] {
+            assert!(HandRange::try_from(invalid).is_err(), "{}", invalid);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_grid() -> Result<(), PokercraftLocalError> {
+        assert_eq!(grid_class(0, 0).as_deref(), Some("AA"));
+        assert_eq!(grid_class(0, 1).as_deref(), Some("AKs"));
+        assert_eq!(grid_class(12, 0).as_deref(), Some("A2o"));
+        assert_eq!(grid_class(13, 0), None);
+
+        let counts = HandRange::try_from("AA,AKs,A2o,KdQd")?.grid_counts();
+        assert_eq!((counts[0][0], counts[0][1], counts[12][0]), (6, 4, 12));
+        assert_eq!((counts[1][2], counts[2][1]), (1, 0));
+        assert_eq!(counts.iter().flatten().sum::<usize>(), 23);
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_dead_cards() -> Result<(), PokercraftLocalError> {
+        let range = HandRange::try_from("AA,KK")?;
+        assert_eq!(range.without_cards(&[card("As")]).len(), 9);
+        assert_eq!(range.without_cards(&[card("As"), card("Kd")]).len(), 6);
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+//! Equity realization; How much of the raw equity a hand turns into its share of the pot,
+//! after future betting like being bluffed off or extracting more bets.
+//!
+//! The realization factor is the expected share of the pot divided by the raw equity,
+//! taken from solver results or estimated by configurable heuristics.
+
+use crate::card::{CardNumber, Hand};
+use crate::equity::RangeEquityResult;
+use crate::errors::PokercraftLocalError;
+use crate::range::HandRange;
+
+/// Raw equity and realized equity of a hand or a range.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct RealizedEquity {
+    /// Share of the pot when all remaining cards are dealt without betting.
+    pub raw: f64,
+    /// Expected share of the pot with future betting,
+    /// which can exceed 1 by winning more bets than the pot.
+    pub realized: f64,
+}
+
+impl RealizedEquity {
+    /// Get the realization factor, or `None` without any raw equity.
+    pub fn factor(&self) -> Option<f64> {
+        (self.raw > 0.0).then(|| self.realized / self.raw)
+    }
+}
+
+/// Structure of starting hands, which decides how well hands realize equity.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum RealizationClass {
+    Pair,
+    Suited,
+    /// Offsuit hands of two cards of Ten or higher.
+    OffsuitBroadway,
+    Offsuit,
+}
+
+impl RealizationClass {
+    /// Return all classes.
+    pub const fn all() -> [RealizationClass; 4] {
+        [
+            RealizationClass::Pair,
+            RealizationClass::Suited,
+            RealizationClass::OffsuitBroadway,
+            RealizationClass::Offsuit,
+        ]
+    }
+
+    /// Get the class of the given combo.
+    pub fn of((c1, c2): Hand) -> Self {
+        if c1.number == c2.number {
+            RealizationClass::Pair
+        } else if c1.shape == c2.shape {
+            RealizationClass::Suited
+        } else if c1.number >= CardNumber::Ten && c2.number >= CardNumber::Ten {
+            RealizationClass::OffsuitBroadway
+        } else {
+            RealizationClass::Offsuit
+        }
+    }
+}
+
+/// Heuristic realization factors of each class, in and out of position.
+///
+/// Factors are `1` when all-in, and approach the given factors of each class
+/// as the stack-to-pot ratio grows, like `1 + (factor - 1) * (1 - exp(-spr / spr_scale))`.
+#[derive(PartialEq, Clone, Debug)]
+pub struct RealizationModel {
+    /// Factors in position, in the order of `RealizationClass::all`.
+    pub in_position: [f64; 4],
+    /// Factors out of position, in the order of `RealizationClass::all`.
+    pub out_of_position: [f64; 4],
+    /// SPR where factors move about 63% of the way from `1`.
+    pub spr_scale: f64,
+}
+
+impl Default for RealizationModel {
+    fn default() -> Self {
+        RealizationModel {
+            in_position: [1.0, 1.05, 1.0, 0.85],
+            out_of_position: [0.85, 0.85, 0.8, 0.65],
+            spr_scale: 3.0,
+        }
+    }
+}
+
+impl RealizationModel {
+    /// Get the factor of the class at the given stack-to-pot ratio.
+    pub fn factor(&self, class: RealizationClass, in_position: bool, spr: f64) -> f64 {
+        let factors = if in_position {
+            &self.in_position
+        } else {
+            &self.out_of_position
+        };
+        let index = RealizationClass::all()
+            .iter()
+            .position(|c| *c == class)
+            .unwrap_or_default();
+        1.0 + (factors[index] - 1.0) * (1.0 - (-spr.max(0.0) / self.spr_scale).exp())
+    }
+
+    /// Get the average factor of combos of the range.
+    pub fn range_factor(
+        &self,
+        range: &HandRange,
+        in_position: bool,
+        spr: f64,
+    ) -> Result<f64, PokercraftLocalError> {
+        if range.is_empty() {
+            return Err(PokercraftLocalError::GeneralError(
+                "Empty range given".to_string(),
+            ));
+        }
+        Ok(range
+            .combos()
+            .iter()
+            .map(|combo| self.factor(RealizationClass::of(*combo), in_position, spr))
+            .sum::<f64>()
+            / range.len() as f64)
+    }
+
+    /// Get the raw and realized equity of the player of the range equity result,
+    /// where `range` is the range of that player.
+    pub fn realize(
+        &self,
+        result: &RangeEquityResult,
+        player_index: usize,
+        range: &HandRange,
+        in_position: bool,
+        spr: f64,
+    ) -> Result<RealizedEquity, PokercraftLocalError> {
+        let raw = result.outcome(player_index)?.equity();
+        Ok(RealizedEquity {
+            raw,
+            realized: raw * self.range_factor(range, in_position, spr)?,
+        })
+    }
+}
+
+/// Get the realization factor of each class from equities of combos, like from
+/// `Solver::realized_equities`, as the total realized equity over the total raw equity.
+/// Classes without any raw equity are skipped.
+pub fn class_factors(equities: &[(Hand, RealizedEquity)]) -> Vec<(RealizationClass, f64)> {
+    RealizationClass::all()
+        .into_iter()
+        .filter_map(|class| {
+            let (raw, realized) = equities
+                .iter()
+                .filter(|(hand, _)| RealizationClass::of(*hand) == class)
+                .fold((0.0, 0.0), |(raw, realized), (_, equity)| {
+                    (raw + equity.raw, realized + equity.realized)
+                });
+            (raw > 0.0).then(|| (class, realized / raw))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+
+    #[test]
+    fn test_realization_model() -> Result<(), PokercraftLocalError> {
+        let classes = ["QdQc", "9s8s", "KhTd", "9c4h"].map(|combo| {
+            parse_cards(combo).map(|cards| RealizationClass::of((cards[0], cards[1])))
+        });
+        assert_eq!(
+            classes.into_iter().collect::<Result<Vec<_>, _>>()?,
+            RealizationClass::all()
+        );
+
+        let model = RealizationModel::default();
+        assert_eq!(model.factor(RealizationClass::Offsuit, false, 0.0), 1.0);
+        let deep = model.factor(RealizationClass::Offsuit, false, 100.0);
+        assert!((deep - 0.65).abs() < 1e-9);
+        let shallow = model.factor(RealizationClass::Offsuit, false, 3.0);
+        assert!((shallow - (1.0 - 0.35 * (1.0 - (-1f64).exp()))).abs() < 1e-9);
+        assert!(model.factor(RealizationClass::Suited, true, 10.0) > 1.0);
+
+        // Aces realize less out of position, against a wide range on the flop
+        let ranges = [HandRange::try_from("AA")?, HandRange::try_from("random")?];
+        let result = RangeEquityResult::new(&ranges, &parse_cards("2c 7d 9h")?, 2000)?;
+        let realized = model.realize(&result, 0, &ranges[0], false, 100.0)?;
+        assert!(realized.raw > 0.7);
+        assert!((realized.factor().unwrap() - 0.85).abs() < 1e-9);
+        assert!(model
+            .range_factor(&HandRange::default(), true, 1.0)
+            .is_err());
+        assert!(model.realize(&result, 2, &ranges[0], true, 1.0).is_err());
+
+        let hand = |combo: &str| -> Result<Hand, PokercraftLocalError> {
+            let cards = parse_cards(combo)?;
+            Ok((cards[0], cards[1]))
+        };
+        let equities = [
+            (
+                hand("AsAd")?,
+                RealizedEquity {
+                    raw: 0.8,
+                    realized: 0.9,
+                },
+            ),
+            (
+                hand("KsKd")?,
+                RealizedEquity {
+                    raw: 0.2,
+                    realized: 0.2,
+                },
+            ),
+            (
+                hand("9c4h")?,
+                RealizedEquity {
+                    raw: 0.0,
+                    realized: 0.0,
+                },
+            ),
+        ];
+        let factors = class_factors(&equities);
+        assert_eq!(factors.len(), 1);
+        assert_eq!(factors[0].0, RealizationClass::Pair);
+        assert!((factors[0].1 - 1.1).abs() < 1e-9);
+        assert_eq!(equities[2].1.factor(), None);
+        Ok(())
+    }
+}
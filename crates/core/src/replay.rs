@@ -0,0 +1,395 @@
+//! Replay of parsed hands, tracking stacks, committed chips, and pots.
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::errors::PokercraftLocalError;
+use crate::parser::{BetAction, BetActionType, HandHistory, HandStage};
+
+/// A single pot; Main pot or side pot.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Pot {
+    pub amount: u64,
+    /// Players who can win this pot, sorted by player ID.
+    pub eligible_players: Vec<String>,
+}
+
+/// State of the hand at the end of a single street.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct StreetState {
+    pub stage: HandStage,
+    /// `{player ID: chips committed on this street}`, excluding antes.
+    pub committed: BTreeMap<String, u64>,
+    /// `{player ID: remaining stack}` at the end of this street.
+    pub stacks: BTreeMap<String, u64>,
+    /// Main pot first, then side pots, including all previous streets.
+    pub pots: Vec<Pot>,
+}
+
+/// Result of replaying a single hand.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct HandReplay {
+    /// States after each street, from preflop to the last dealt street.
+    pub streets: Vec<StreetState>,
+    /// `{player ID: total chips committed}`, including antes
+    /// and excluding the returned uncalled bet.
+    pub total_committed: BTreeMap<String, u64>,
+}
+
+/// Running state of the replay.
+struct ReplayState<'a> {
+    hand: &'a HandHistory,
+    stacks: BTreeMap<String, u64>,
+    total_committed: BTreeMap<String, u64>,
+    street_committed: BTreeMap<String, u64>,
+    folded: HashSet<String>,
+    all_ined: HashSet<String>,
+    current_bet: u64,
+}
+
+impl<'a> ReplayState<'a> {
+    fn new(hand: &'a HandHistory) -> Self {
+        ReplayState {
+            hand,
+            stacks: hand.seats.values().cloned().collect(),
+            total_committed: hand
+                .seats
+                .values()
+                .map(|(pid, _)| (pid.clone(), 0))
+                .collect(),
+            street_committed: BTreeMap::new(),
+            folded: HashSet::new(),
+            all_ined: HashSet::new(),
+            current_bet: 0,
+        }
+    }
+
+    fn error(&self, stage: HandStage, action: &BetAction, message: &str) -> PokercraftLocalError {
+        PokercraftLocalError::GeneralError(format!(
+            "Inconsistent hand {} on {} ({} {:?} {}): {}",
+            self.hand.id, stage, action.player_id, action.action, action.amount, message
+        ))
+    }
+
+    /// Move `amount` chips from the stack of the player into the pot.
+    fn commit(
+        &mut self,
+        stage: HandStage,
+        action: &BetAction,
+        amount: u64,
+        to_street: bool,
+    ) -> Result<(), PokercraftLocalError> {
+        let stack = self.stacks[&action.player_id];
+        if amount > stack {
+            return Err(self.error(
+                stage,
+                action,
+                &format!("Commits {} chips with only {} chips behind", amount, stack),
+            ));
+        }
+        self.stacks.insert(action.player_id.clone(), stack - amount);
+        if amount == stack {
+            self.all_ined.insert(action.player_id.clone());
+        }
+        *self
+            .total_committed
+            .get_mut(&action.player_id)
+            .expect("All players are initialized") += amount;
+        if to_street {
+            *self
+                .street_committed
+                .entry(action.player_id.clone())
+                .or_default() += amount;
+        }
+        Ok(())
+    }
+
+    /// Apply a single action.
+    fn apply(&mut self, stage: HandStage, action: &BetAction) -> Result<(), PokercraftLocalError> {
+        if !self.stacks.contains_key(&action.player_id) {
+            return Err(self.error(stage, action, "Player is not seated"));
+        } else if self.folded.contains(&action.player_id) {
+            return Err(self.error(stage, action, "Player already folded"));
+        } else if self.all_ined.contains(&action.player_id) {
+            return Err(self.error(stage, action, "Player is already all-in"));
+        }
+
+        let committed = self
+            .street_committed
+            .get(&action.player_id)
+            .copied()
+            .unwrap_or(0);
+        let stack = self.stacks[&action.player_id];
+        match action.action {
+            BetActionType::Ante => {
+                if stage != HandStage::Preflop {
+                    return Err(self.error(stage, action, "Ante after preflop"));
+                }
+                self.commit(stage, action, action.amount, false)?;
+            }
+            BetActionType::Blind => {
+                if stage != HandStage::Preflop {
+                    return Err(self.error(stage, action, "Blind after preflop"));
+                }
+                self.commit(stage, action, action.amount, true)?;
+                self.current_bet = self.current_bet.max(committed + action.amount);
+            }
+            BetActionType::Fold => {
+                self.folded.insert(action.player_id.clone());
+            }
+            BetActionType::Check => {
+                if committed < self.current_bet {
+                    return Err(self.error(stage, action, "Checks while facing a bet"));
+                }
+            }
+            BetActionType::Call => {
+                let to_call = self.current_bet.saturating_sub(committed);
+                if action.amount > to_call {
+                    return Err(self.error(
+                        stage,
+                        action,
+                        &format!("Calls more than {} to call", to_call),
+                    ));
+                } else if action.amount < to_call && action.amount < stack {
+                    return Err(self.error(stage, action, "Calls less without being all-in"));
+                }
+                self.commit(stage, action, action.amount, true)?;
+            }
+            BetActionType::Bet => {
+                if self.current_bet > 0 {
+                    return Err(self.error(stage, action, "Bets while facing a bet"));
+                }
+                self.commit(stage, action, action.amount, true)?;
+                self.current_bet = action.amount;
+            }
+            BetActionType::Raise => {
+                if action.amount <= self.current_bet {
+                    return Err(self.error(
+                        stage,
+                        action,
+                        &format!("Raises to no more than current bet {}", self.current_bet),
+                    ));
+                }
+                self.commit(stage, action, action.amount - committed, true)?;
+                self.current_bet = action.amount;
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the uncalled bet to the player.
+    fn return_uncalled(
+        &mut self,
+        player_id: &str,
+        amount: u64,
+    ) -> Result<(), PokercraftLocalError> {
+        let street_committed = self.street_committed.get(player_id).copied().unwrap_or(0);
+        let second_biggest = self
+            .street_committed
+            .iter()
+            .filter(|(pid, _)| pid.as_str() != player_id)
+            .map(|(_, &amount)| amount)
+            .max()
+            .unwrap_or(0);
+        if street_committed < second_biggest + amount {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Inconsistent hand {}: Uncalled bet {} returned to {} is bigger than the excess",
+                self.hand.id, amount, player_id
+            )));
+        }
+        self.street_committed
+            .insert(player_id.to_string(), street_committed - amount);
+        *self.stacks.get_mut(player_id).expect("Checked above") += amount;
+        *self
+            .total_committed
+            .get_mut(player_id)
+            .expect("Checked above") -= amount;
+        self.all_ined.remove(player_id);
+        Ok(())
+    }
+
+    /// Build main and side pots from total committed chips.
+    fn pots(&self) -> Vec<Pot> {
+        let mut levels: Vec<u64> = self
+            .total_committed
+            .iter()
+            .filter(|(pid, _)| self.all_ined.contains(*pid) && !self.folded.contains(*pid))
+            .map(|(_, &amount)| amount)
+            .chain(self.total_committed.values().max().copied())
+            .filter(|&amount| amount > 0)
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots: Vec<Pot> = Vec::new();
+        let mut previous_level = 0;
+        for level in levels {
+            let amount: u64 = self
+                .total_committed
+                .values()
+                .map(|&c| c.min(level) - c.min(previous_level))
+                .sum();
+            let eligible_players: Vec<String> = self
+                .total_committed
+                .iter()
+                .filter(|(pid, &c)| c >= level && !self.folded.contains(*pid))
+                .map(|(pid, _)| pid.clone())
+                .collect();
+            previous_level = level;
+            match pots.last_mut() {
+                // Dead money over every remaining player goes to the last pot
+                Some(last)
+                    if last.eligible_players == eligible_players || eligible_players.is_empty() =>
+                {
+                    last.amount += amount
+                }
+                _ => pots.push(Pot {
+                    amount,
+                    eligible_players,
+                }),
+            }
+        }
+        pots
+    }
+
+    /// Finish the current street and take a snapshot.
+    fn finish_street(&mut self, stage: HandStage) -> StreetState {
+        let state = StreetState {
+            stage,
+            committed: std::mem::take(&mut self.street_committed),
+            stacks: self.stacks.clone(),
+            pots: self.pots(),
+        };
+        self.current_bet = 0;
+        state
+    }
+}
+
+impl HandReplay {
+    /// Replay the given hand.
+    /// Returns an error if the actions are inconsistent,
+    /// for example a player calls more than the bet or acts after folding.
+    pub fn new(hand: &HandHistory) -> Result<Self, PokercraftLocalError> {
+        let mut state = ReplayState::new(hand);
+        let last_betting_stage = HandStage::all()
+            .into_iter()
+            .rfind(|&stage| !hand.actions(stage).is_empty())
+            .unwrap_or(HandStage::Preflop);
+
+        let mut streets = Vec::new();
+        for (i, stage) in HandStage::all().into_iter().enumerate() {
+            let dealt = i == 0 || hand.community_cards.len() >= i + 2;
+            if !dealt && stage > last_betting_stage {
+                break;
+            }
+            for action in hand.actions(stage) {
+                state.apply(stage, action)?;
+            }
+            if stage == last_betting_stage {
+                if let Some((player_id, amount)) = &hand.uncalled_returned {
+                    state.return_uncalled(player_id, *amount)?;
+                }
+            }
+            streets.push(state.finish_street(stage));
+        }
+
+        let total_pot: u64 = state.total_committed.values().sum();
+        let total_won = hand.total_pot();
+        if total_won > total_pot {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Inconsistent hand {}: Collected {} chips from {} chips of pots",
+                hand.id, total_won, total_pot
+            )));
+        }
+
+        Ok(HandReplay {
+            streets,
+            total_committed: state.total_committed,
+        })
+    }
+
+    /// Get the final pots of the hand.
+    pub fn final_pots(&self) -> &[Pot] {
+        self.streets
+            .last()
+            .map(|street| street.pots.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Get the total amount of all pots.
+    pub fn total_pot(&self) -> u64 {
+        self.total_committed.values().sum()
+    }
+
+    /// Get the amount of chips not collected by anyone, like rake.
+    pub fn uncollected(&self, hand: &HandHistory) -> u64 {
+        self.total_pot().saturating_sub(hand.total_pot())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_hands;
+
+    const SIDE_POT_HAND: &str = "Poker Hand #TM1: Tournament #1, Test Hold'em No Limit - Level1(100/200) - 2025/08/01 00:00:00
+Table '1' 3-max Seat #1 is the button
+Seat 1: Hero (5,000 in chips)
+Seat 2: aaaa (1,000 in chips)
+Seat 3: bbbb (3,000 in chips)
+Hero: posts the ante 20
+aaaa: posts the ante 20
+bbbb: posts the ante 20
+aaaa: posts small blind 100
+bbbb: posts big blind 200
+*** HOLE CARDS ***
+Dealt to Hero [As Ad]
+Hero: raises 4,780 to 4,980 and is all-in
+aaaa: calls 880 and is all-in
+bbbb: calls 2,780 and is all-in
+Uncalled bet (2,000) returned to Hero
+*** FLOP *** [2c 7d 9h]
+*** TURN *** [2c 7d 9h] [Kd]
+*** RIVER *** [2c 7d 9h Kd] [3s]
+aaaa: shows [Kc Kh]
+bbbb: shows [Qc Qh]
+Hero: shows [As Ad]
+*** SHOWDOWN ***
+aaaa collected 3,000 from pot
+Hero collected 4,000 from pot
+*** SUMMARY ***
+";
+
+    #[test]
+    fn test_replay_side_pots() -> Result<(), PokercraftLocalError> {
+        let hand = parse_hands(SIDE_POT_HAND.as_bytes()).next().unwrap()?;
+        let replay = HandReplay::new(&hand)?;
+        assert_eq!(replay.streets.len(), 4);
+        assert_eq!(replay.streets[0].committed["Hero"], 2980);
+        assert_eq!(replay.streets[0].stacks["Hero"], 2000);
+        assert_eq!(replay.total_pot(), 7000);
+        assert_eq!(replay.uncollected(&hand), 0);
+        for pid in ["Hero", "aaaa", "bbbb"] {
+            assert_eq!(replay.total_committed[pid], hand.total_chips_put(pid));
+        }
+
+        let pots = replay.final_pots();
+        assert_eq!(pots.len(), 2);
+        assert_eq!(pots[0].amount, 3000);
+        assert_eq!(pots[0].eligible_players, vec!["Hero", "aaaa", "bbbb"]);
+        assert_eq!(pots[1].amount, 4000);
+        assert_eq!(pots[1].eligible_players, vec!["Hero", "bbbb"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_inconsistent() -> Result<(), PokercraftLocalError> {
+        let broken = SIDE_POT_HAND.replace("bbbb: calls 2,780", "bbbb: calls 2,000");
+        let hand = parse_hands(broken.as_bytes()).next().unwrap()?;
+        assert!(HandReplay::new(&hand).is_err());
+
+        let over_collected = SIDE_POT_HAND.replace("Hero collected 4,000", "Hero collected 5,000");
+        let hand = parse_hands(over_collected.as_bytes()).next().unwrap()?;
+        assert!(HandReplay::new(&hand).is_err());
+        Ok(())
+    }
+}
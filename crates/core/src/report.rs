@@ -0,0 +1,459 @@
+//! Standalone HTML reports of parsed Pokercraft exports,
+//! with summary tables and Plotly charts embedded as JSON.
+//!
+//! The page is laid out like charts exported from the web app and loads Plotly from its CDN,
+//! so the CLI and the WASM module generate the same report without the web app.
+//! Charts therefore need network access when the page is opened;
+//! Summary tables are plain HTML and are shown offline too.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+
+use crate::equity::LuckCalculator;
+use crate::errors::PokercraftLocalError;
+use crate::parser::{HandHistory, TournamentSummary};
+use crate::stats::all_in::find_all_in_spots;
+use crate::stats::{aggregate_tournaments, decompose_winnings, Period};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const PLOTLY_CDN: &str = "https://cdn.plot.ly/plotly-3.3.1.min.js";
+
+/// Shown in place of charts when Plotly could not be loaded from `PLOTLY_CDN`.
+const PLOTLY_UNAVAILABLE: &str = "Charts need network access to load Plotly.";
+
+const THEME_CSS: &str = "
+  * { margin: 0; padding: 0; box-sizing: border-box; }
+  body {
+    background: #111;
+    color: #ddd;
+    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+    padding: 2rem;
+  }
+  .export-header {
+    text-align: center;
+    margin-bottom: 2rem;
+    padding-bottom: 1rem;
+    border-bottom: 1px solid #333;
+  }
+  .export-header h1 { font-size: 1.75rem; color: #fff; margin-bottom: 0.25rem; }
+  .export-header .meta { color: #666; font-size: 0.8rem; }
+  .section-title {
+    font-size: 1.25rem;
+    color: #aaa;
+    margin: 2rem 0 1rem 0;
+    padding-bottom: 0.5rem;
+    border-bottom: 1px solid #282828;
+  }
+  .summary-table { border-collapse: collapse; margin-bottom: 1.5rem; font-size: 0.9rem; }
+  .summary-table th, .summary-table td { padding: 0.35rem 1rem; border-bottom: 1px solid #282828; }
+  .summary-table th { text-align: left; color: #888; font-weight: 500; }
+  .summary-table td { text-align: right; font-variant-numeric: tabular-nums; }
+  .chart-title {
+    font-size: 1rem;
+    font-weight: 500;
+    color: #888;
+    margin: 1.5rem 0 0.5rem 0;
+  }
+  .chart-container {
+    background: #fff;
+    border-radius: 12px;
+    padding: 1rem;
+    margin-bottom: 1.5rem;
+    overflow: hidden;
+  }
+";
+
+/// A single Plotly chart of a report section.
+struct Chart {
+    name: String,
+    traces: Value,
+    layout: Value,
+}
+
+/// A heading of the report, with a summary table and charts.
+struct Section {
+    title: String,
+    rows: Vec<(String, String)>,
+    charts: Vec<Chart>,
+}
+
+/// Escape special characters of HTML text and attributes.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Serialize the value for embedding inside an inline `<script>` element,
+/// escaping `<` so strings like `</script>` cannot close the element.
+fn embed_json(value: &Value) -> Result<String, PokercraftLocalError> {
+    serde_json::to_string(value)
+        .map(|json| json.replace('<', "\\u003c"))
+        .map_err(|e| PokercraftLocalError::GeneralError(e.to_string()))
+}
+
+/// Format the given ratio as a percentage, or `-` if absent.
+fn percent(ratio: Option<f64>) -> String {
+    ratio.map_or("-".to_string(), |ratio| format!("{:.2}%", ratio * 100.0))
+}
+
+/// Get the layout of a chart with the given axis titles.
+fn layout(x_title: &str, y_title: &str) -> Value {
+    json!({
+        "xaxis": {"title": {"text": x_title}},
+        "yaxis": {"title": {"text": y_title}},
+        "hovermode": "x unified",
+        "margin": {"t": 30},
+    })
+}
+
+/// Summarize tournaments of the report.
+fn tournament_section(tournaments: &[TournamentSummary]) -> Section {
+    let aggregate = aggregate_tournaments(tournaments);
+    let rows = vec![
+        ("Tournaments", aggregate.tournaments.to_string()),
+        ("Entries", aggregate.entries.to_string()),
+        ("Total buy-in", format!("{:.2}", aggregate.total_buy_in)),
+        ("Total rake", format!("{:.2}", aggregate.total_rake)),
+        ("Total prize", format!("{:.2}", aggregate.total_prize)),
+        ("Bounties", format!("{:.2}", aggregate.total_bounty)),
+        ("Profit", format!("{:.2}", aggregate.profit())),
+        ("ROI", percent(aggregate.roi())),
+        ("ITM", percent(aggregate.itm_ratio())),
+        ("Biggest prize", format!("{:.2}", aggregate.biggest_prize)),
+        (
+            "Best RRE",
+            aggregate
+                .best_rre
+                .map_or("-".to_string(), |rre| format!("{:.2}", rre)),
+        ),
+    ];
+
+    let mut sorted = tournaments.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|tournament| (tournament.start_time, tournament.id));
+    let (mut profit, mut rake) = (0.0, 0.0);
+    let (mut times, mut profits, mut rakes, mut names) = (vec![], vec![], vec![], vec![]);
+    let mut monthly: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for tournament in sorted {
+        profit += tournament.profit();
+        rake += tournament.rake * tournament.my_entries as f64;
+        times.push(
+            tournament
+                .start_time
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        );
+        profits.push(profit);
+        rakes.push(rake);
+        names.push(tournament.name.clone());
+        *monthly
+            .entry(Period::Month.start_of(tournament.start_time))
+            .or_default() += tournament.profit();
+    }
+
+    let charts = vec![
+        Chart {
+            name: "Historical Performance".to_string(),
+            traces: json!([
+                {
+                    "type": "scatter",
+                    "mode": "lines",
+                    "name": "Net profit",
+                    "x": times,
+                    "y": profits,
+                    "text": names,
+                },
+                {
+                    "type": "scatter",
+                    "mode": "lines",
+                    "name": "Rake paid",
+                    "x": times,
+                    "y": rakes,
+                },
+            ]),
+            layout: layout("Start time", "Cumulative amount"),
+        },
+        Chart {
+            name: "Profit by Month".to_string(),
+            traces: json!([{
+                "type": "bar",
+                "name": "Profit",
+                "x": monthly.keys().map(|date| date.to_string()).collect::<Vec<_>>(),
+                "y": monthly.values().collect::<Vec<_>>(),
+            }]),
+            layout: layout("Month", "Profit"),
+        },
+    ];
+    Section {
+        title: "Tournament Summary".to_string(),
+        rows: rows
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+        charts,
+    }
+}
+
+/// Summarize hands and all-in luck of the hero.
+fn hand_section(
+    hands: &[HandHistory],
+    tournaments: &[TournamentSummary],
+    hero_id: &str,
+) -> Result<Section, PokercraftLocalError> {
+    let found = find_all_in_spots(hands, hero_id);
+    let spots = found.spots;
+    let mut calculator = LuckCalculator::new();
+    for spot in spots.iter() {
+        calculator.add_result(spot.equity, spot.actual)?;
+    }
+    let periods = decompose_winnings(hands, tournaments, hero_id, Period::Month)?;
+    let chip_profit: i64 = periods.iter().map(|period| period.chip_profit).sum();
+    let chip_luck: f64 = spots.iter().map(|spot| spot.luck_chips()).sum();
+
+    let mut rows = vec![
+        ("Hands", hands.len().to_string()),
+        ("All-ins", spots.len().to_string()),
+        (
+            "Expected wins",
+            format!("{:.2}", spots.iter().map(|spot| spot.equity).sum::<f64>()),
+        ),
+        (
+            "Actual wins",
+            format!("{:.2}", spots.iter().map(|spot| spot.actual).sum::<f64>()),
+        ),
+        (
+            "Luck score",
+            calculator
+                .luck_score()
+                .map_or("-".to_string(), |score| format!("{:.3}", score)),
+        ),
+        (
+            "Two-sided p-value",
+            percent(calculator.tails().map(|(_, _, two_sided)| two_sided)),
+        ),
+        ("Chip profit", chip_profit.to_string()),
+        ("Chip luck", format!("{:.0}", chip_luck)),
+        (
+            "Expected chip profit",
+            format!("{:.0}", chip_profit as f64 - chip_luck),
+        ),
+    ];
+    if !tournaments.is_empty() {
+        let luck: f64 = periods.iter().map(|period| period.luck).sum();
+        rows.push(("Luck in money", format!("{:.2}", luck)));
+    }
+    if !found.errors.is_empty() {
+        rows.push(("Skipped inconsistent hands", found.errors.len().to_string()));
+    }
+
+    let mut luck = 0.0;
+    let cumulative_luck = spots
+        .iter()
+        .map(|spot| {
+            luck += spot.luck_chips();
+            luck
+        })
+        .collect::<Vec<_>>();
+    let months = periods
+        .iter()
+        .map(|period| period.start.to_string())
+        .collect::<Vec<_>>();
+    let charts = vec![
+        Chart {
+            name: "All-in Luck".to_string(),
+            traces: json!([{
+                "type": "scatter",
+                "mode": "lines",
+                "name": "Chips over equity",
+                "x": (1..=spots.len()).collect::<Vec<_>>(),
+                "y": cumulative_luck,
+                "text": spots.iter().map(|spot| spot.hand_id.clone()).collect::<Vec<_>>(),
+            }]),
+            layout: layout("All-ins", "Cumulative chips over equity"),
+        },
+        Chart {
+            name: "EV-adjusted Chip Winnings by Month".to_string(),
+            traces: json!([
+                {
+                    "type": "bar",
+                    "name": "Chip profit",
+                    "x": months,
+                    "y": periods.iter().map(|period| period.chip_profit).collect::<Vec<_>>(),
+                },
+                {
+                    "type": "bar",
+                    "name": "Expected chip profit",
+                    "x": months,
+                    "y": periods
+                        .iter()
+                        .map(|period| period.expected_chip_profit())
+                        .collect::<Vec<_>>(),
+                },
+            ]),
+            layout: layout("Month", "Chips"),
+        },
+    ];
+    Ok(Section {
+        title: "Hand History".to_string(),
+        rows: rows
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+        charts,
+    })
+}
+
+/// Generate a standalone HTML report of the given tournaments and hands of the hero.
+/// Sections without data are omitted.
+/// Charts are drawn by Plotly loaded from its CDN, and replaced by a notice if it fails to load.
+/// Money amounts are summed as they are, in the currencies of the tournaments.
+pub fn generate_html_report(
+    tournaments: &[TournamentSummary],
+    hands: &[HandHistory],
+    hero_id: &str,
+) -> Result<String, PokercraftLocalError> {
+    let mut sections = vec![];
+    if !tournaments.is_empty() {
+        sections.push(tournament_section(tournaments));
+    }
+    if !hands.is_empty() {
+        sections.push(hand_section(hands, tournaments, hero_id)?);
+    }
+
+    let mut body = String::new();
+    let mut plots = String::new();
+    for (i, section) in sections.iter().enumerate() {
+        body += &format!(
+            "  <h2 class=\"section-title\">{}</h2>\n",
+            escape_html(&section.title)
+        );
+        body += "  <table class=\"summary-table\">\n";
+        for (name, value) in section.rows.iter() {
+            body += &format!(
+                "    <tr><th>{}</th><td>{}</td></tr>\n",
+                escape_html(name),
+                escape_html(value)
+            );
+        }
+        body += "  </table>\n";
+        for (j, chart) in section.charts.iter().enumerate() {
+            let id = format!("section{}-{}", i, j);
+            body += &format!(
+                "  <h3 class=\"chart-title\">{}</h3>\n",
+                escape_html(&chart.name)
+            );
+            body += &format!(
+                "  <div class=\"chart-container\"><div id=\"{}\" style=\"width:100%;\"></div></div>\n",
+                id
+            );
+            plots += &format!(
+                "      Plotly.newPlot({}, {}, {}, {{responsive: true}});\n",
+                embed_json(&Value::String(id))?,
+                embed_json(&chart.traces)?,
+                embed_json(&chart.layout)?,
+            );
+        }
+    }
+    if sections.is_empty() {
+        body += "  <p>No tournaments or hands to report.</p>\n";
+    }
+
+    Ok(format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+  <meta charset=\"UTF-8\" />
+  <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\" />
+  <title>Pokercraft Local - Report</title>
+  <script src=\"{}\"></script>
+  <style>{}</style>
+</head>
+<body>
+  <div class=\"export-header\">
+    <h1>Pokercraft Local</h1>
+    <div class=\"meta\">Report of {} &middot; v{}</div>
+  </div>
+{}  <script>
+    document.addEventListener('DOMContentLoaded', function() {{
+      if (typeof Plotly === 'undefined') {{
+        document.querySelectorAll('.chart-container').forEach(function(container) {{
+          container.textContent = '{}';
+        }});
+        return;
+      }}
+{}    }});
+  </script>
+</body>
+</html>
+",
+        PLOTLY_CDN,
+        THEME_CSS,
+        escape_html(hero_id),
+        env!("CARGO_PKG_VERSION"),
+        body,
+        PLOTLY_UNAVAILABLE,
+        plots,
+    ))
+}
+
+/// Generate a standalone HTML report from the given file contents (WASM interface).
+/// Unparsable summaries, unparsable hands and freerolls are skipped.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = generateHtmlReport)]
+pub fn generate_html_report_wasm(
+    summary_texts: Vec<String>,
+    hand_history_texts: Vec<String>,
+    hero_id: Option<String>,
+) -> Result<String, JsValue> {
+    let tournaments = summary_texts
+        .iter()
+        .filter_map(|text| {
+            crate::parser::parse_tournament_summary(text, false)
+                .ok()
+                .flatten()
+        })
+        .collect::<Vec<_>>();
+    let hands = hand_history_texts
+        .iter()
+        .flat_map(|text| crate::parser::parse_hands(text.as_bytes()).flatten())
+        .collect::<Vec<_>>();
+    Ok(generate_html_report(
+        &tournaments,
+        &hands,
+        hero_id.as_deref().unwrap_or("Hero"),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::hand_history::tests::SAMPLE_HAND;
+    use crate::parser::parse_hands;
+    use crate::parser::tournament_summary::tests::SAMPLE_SUMMARY;
+
+    #[test]
+    fn test_generate_html_report() {
+        let tournament = crate::parser::parse_tournament_summary(SAMPLE_SUMMARY, false)
+            .unwrap()
+            .unwrap();
+        let hands = parse_hands(SAMPLE_HAND.as_bytes())
+            .flatten()
+            .collect::<Vec<_>>();
+        let html = generate_html_report(&[tournament], &hands, "Hero").unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Tournament Summary"));
+        assert!(html.contains("Hand History"));
+        assert_eq!(html.matches("Plotly.newPlot(").count(), 4);
+        assert!(html.contains(PLOTLY_UNAVAILABLE));
+
+        let html = generate_html_report(&[], &[], "<Hero>").unwrap();
+        assert!(html.contains("No tournaments or hands to report."));
+        assert!(html.contains("&lt;Hero&gt;"));
+        assert!(!html.contains("<Hero>"));
+    }
+}
@@ -0,0 +1,824 @@
+//! A toy CFR+ solver of heads-up river and turn spots with fixed bet sizings,
+//! for studying strategies of small spots rather than replacing real solvers.
+//!
+//! Player 0 is out of position and acts first on each street.
+//! Values are chips won from the starting pot, net of chips put in afterwards;
+//! Like winning a called pot-sized bet is worth twice the starting pot.
+
+mod tree;
+
+use crate::card::{normalize_hand, Card, Hand, RankKey};
+use crate::errors::PokercraftLocalError;
+use crate::evaluator::evaluate_batch;
+use crate::realization::RealizedEquity;
+
+use tree::{Node, TreeBuilder};
+
+/// An action in the game tree.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum SolverAction {
+    Check,
+    /// Bet of the given amount.
+    Bet(f64),
+    Call,
+    Fold,
+    /// Raise to the given amount on the current street.
+    Raise(f64),
+    /// The river card dealt after the turn.
+    Deal(Card),
+}
+
+impl std::fmt::Display for SolverAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverAction::Check => write!(f, "Check"),
+            SolverAction::Bet(amount) => write!(f, "Bet {}", amount),
+            SolverAction::Call => write!(f, "Call"),
+            SolverAction::Fold => write!(f, "Fold"),
+            SolverAction::Raise(amount) => write!(f, "Raise to {}", amount),
+            SolverAction::Deal(card) => write!(f, "Deal {}", card),
+        }
+    }
+}
+
+/// A heads-up spot to solve.
+#[derive(PartialEq, Clone, Debug)]
+pub struct SolverConfig {
+    /// 4 cards for a turn spot, or 5 cards for a river spot.
+    pub board: Vec<Card>,
+    pub pot: f64,
+    pub effective_stack: f64,
+    /// Bet sizes relative to the pot, like `[0.33, 0.75]`.
+    /// Sizes larger than the stack become all-in.
+    pub bet_sizes: Vec<f64>,
+    /// Raise sizes relative to the pot after calling.
+    pub raise_sizes: Vec<f64>,
+    /// Maximum number of raises on each street.
+    pub max_raises: u32,
+    /// Weighted combos of player 0 (out of position) and player 1.
+    /// Combos using any card of the board are ignored.
+    pub ranges: [Vec<(Hand, f64)>; 2],
+}
+
+/// Strategy of a decision, averaged over all iterations so far.
+#[derive(PartialEq, Clone, Debug)]
+pub struct NodeStrategy {
+    /// The player to act.
+    pub player: usize,
+    pub actions: Vec<SolverAction>,
+    /// Frequencies of `actions` for each combo of the player to act,
+    /// except combos blocked by the board.
+    pub combos: Vec<(Hand, Vec<f64>)>,
+}
+
+/// Combos of each player sorted by their ranks on a complete board,
+/// except combos blocked by the board.
+struct Board {
+    sorted: [Vec<(RankKey, usize)>; 2],
+}
+
+/// A CFR+ solver of a single spot.
+pub struct Solver {
+    pot: f64,
+    combos: [Vec<Hand>; 2],
+    weights: [Vec<f64>; 2],
+    /// Card indices of each combo.
+    cards: [Vec<[usize; 2]>; 2],
+    /// Index of the same combo in the range of the other player.
+    same: [Vec<Option<usize>>; 2],
+    boards: Vec<Board>,
+    /// Number of possible river cards for each pair of combos; 1 for a river spot.
+    rivers: f64,
+    root: Node,
+    /// Showdown without any betting, for raw equities.
+    showdown: Node,
+    /// Regrets and strategy sums of each decision, indexed by `action * combos + combo`.
+    regrets: Vec<Vec<f64>>,
+    strategy_sums: Vec<Vec<f64>>,
+    iterations: u32,
+}
+
+/// Get the regret matching strategy, or the uniform strategy if there is no regret.
+/// `values` and the result are indexed by `action * combos + combo`.
+fn normalize(values: &[f64], actions: usize) -> Vec<f64> {
+    let combos = values.len() / actions;
+    let mut strategy = vec![1.0 / actions as f64; values.len()];
+    for combo in 0..combos {
+        let total = (0..actions)
+            .map(|action| values[action * combos + combo].max(0.0))
+            .sum::<f64>();
+        if total > 0.0 {
+            for action in 0..actions {
+                strategy[action * combos + combo] =
+                    values[action * combos + combo].max(0.0) / total;
+            }
+        }
+    }
+    strategy
+}
+
+impl Solver {
+    /// Create a new solver of the spot, checking the config and building the game tree.
+    pub fn new(config: &SolverConfig) -> Result<Self, PokercraftLocalError> {
+        let board = &config.board;
+        if board.len() != 4 && board.len() != 5 {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Board should have 4 or 5 cards, got {}",
+                board.len()
+            )));
+        } else if (1..board.len()).any(|i| board[..i].contains(&board[i])) {
+            return Err(PokercraftLocalError::GeneralError(
+                "Board has duplicate cards".to_string(),
+            ));
+        } else if !(config.pot > 0.0 && config.pot.is_finite()) {
+            return Err(PokercraftLocalError::GeneralError(
+                "Pot must be positive".to_string(),
+            ));
+        } else if !(config.effective_stack >= 0.0 && config.effective_stack.is_finite()) {
+            return Err(PokercraftLocalError::GeneralError(
+                "Effective stack must not be negative".to_string(),
+            ));
+        } else if config
+            .bet_sizes
+            .iter()
+            .chain(config.raise_sizes.iter())
+            .any(|size| !(*size > 0.0 && size.is_finite()))
+        {
+            return Err(PokercraftLocalError::GeneralError(
+                "Bet and raise sizes must be positive".to_string(),
+            ));
+        }
+
+        let mut combos: [Vec<Hand>; 2] = Default::default();
+        let mut weights: [Vec<f64>; 2] = Default::default();
+        for player in 0..2 {
+            for &(hand, weight) in config.ranges[player].iter() {
+                let hand = normalize_hand(hand);
+                if !(weight >= 0.0 && weight.is_finite()) {
+                    return Err(PokercraftLocalError::GeneralError(format!(
+                        "Weight of {}{} must not be negative",
+                        hand.0, hand.1
+                    )));
+                } else if hand.0 == hand.1 || combos[player].contains(&hand) {
+                    return Err(PokercraftLocalError::GeneralError(format!(
+                        "Combo {}{} is invalid or duplicated",
+                        hand.0, hand.1
+                    )));
+                } else if weight > 0.0 && !board.contains(&hand.0) && !board.contains(&hand.1) {
+                    combos[player].push(hand);
+                    weights[player].push(weight);
+                }
+            }
+        }
+        let cards = combos.clone().map(|combos| {
+            combos
+                .iter()
+                .map(|(c1, c2)| [c1.index(), c2.index()])
+                .collect()
+        });
+        let same = [0, 1].map(|player| {
+            combos[player]
+                .iter()
+                .map(|hand| combos[1 - player].iter().position(|other| other == hand))
+                .collect()
+        });
+
+        let rivers: Vec<Card> = if board.len() == 4 {
+            Card::all()
+                .into_iter()
+                .filter(|card| !board.contains(card))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let full_boards: Vec<Vec<Card>> = if rivers.is_empty() {
+            vec![board.clone()]
+        } else {
+            rivers
+                .iter()
+                .map(|river| [board.as_slice(), &[*river]].concat())
+                .collect()
+        };
+        let boards = full_boards
+            .iter()
+            .map(|full_board| Board {
+                sorted: [0, 1].map(|player| {
+                    let alive: Vec<usize> = (0..combos[player].len())
+                        .filter(|&i| {
+                            let (c1, c2) = combos[player][i];
+                            !full_board.contains(&c1) && !full_board.contains(&c2)
+                        })
+                        .collect();
+                    let sevens: Vec<[Card; 7]> = alive
+                        .iter()
+                        .map(|&i| {
+                            let (c1, c2) = combos[player][i];
+                            let mut seven = [c1; 7];
+                            seven[1] = c2;
+                            seven[2..].copy_from_slice(full_board);
+                            seven
+                        })
+                        .collect();
+                    let mut sorted: Vec<(RankKey, usize)> =
+                        evaluate_batch(&sevens).into_iter().zip(alive).collect();
+                    sorted.sort();
+                    sorted
+                }),
+            })
+            .collect();
+
+        let showdown = if rivers.is_empty() {
+            Node::Showdown {
+                board: 0,
+                loss: 0.5,
+            }
+        } else {
+            Node::Chance {
+                cards: rivers.clone(),
+                children: (0..rivers.len())
+                    .map(|board| Node::Showdown { board, loss: 0.5 })
+                    .collect(),
+            }
+        };
+        let mut builder = TreeBuilder::new(config, &rivers);
+        let root = builder.build();
+        let tables: Vec<Vec<f64>> = builder
+            .decisions
+            .iter()
+            .map(|&(player, actions)| vec![0.0; actions * combos[player].len()])
+            .collect();
+
+        let solver = Solver {
+            pot: config.pot,
+            combos,
+            weights,
+            cards,
+            same,
+            boards,
+            rivers: if rivers.is_empty() {
+                1.0
+            } else {
+                (rivers.len() - 4) as f64
+            },
+            root,
+            showdown,
+            regrets: tables.clone(),
+            strategy_sums: tables,
+            iterations: 0,
+        };
+        if solver.normalizer() <= 0.0 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Ranges should have at least 1 pair of non-overlapping combos".to_string(),
+            ));
+        }
+        Ok(solver)
+    }
+
+    /// Get the number of iterations run so far.
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// Get the combos of the given player, except combos blocked by the board.
+    pub fn combos(&self, player: usize) -> &[Hand] {
+        &self.combos[player]
+    }
+
+    /// Run more iterations of CFR+, updating each player alternately.
+    pub fn run(&mut self, iterations: u32) {
+        for _ in 0..iterations {
+            self.iterations += 1;
+            for traverser in 0..2 {
+                let root = std::mem::replace(
+                    &mut self.root,
+                    Node::Fold {
+                        folder: 0,
+                        loss: 0.0,
+                    },
+                );
+                let own = self.weights[traverser].clone();
+                let opponent = self.weights[1 - traverser].clone();
+                self.train(&root, traverser, &own, &opponent);
+                self.root = root;
+            }
+        }
+    }
+
+    /// Get the average strategy of the decision after the given actions from the start,
+    /// including `SolverAction::Deal` of the river on a turn spot.
+    pub fn strategy(&self, history: &[SolverAction]) -> Result<NodeStrategy, PokercraftLocalError> {
+        let mut node = &self.root;
+        let mut dealt = None;
+        for action in history.iter() {
+            node = match (node, action) {
+                (
+                    Node::Decision {
+                        actions, children, ..
+                    },
+                    _,
+                ) => actions
+                    .iter()
+                    .position(|a| a == action)
+                    .map(|i| &children[i]),
+                (Node::Chance { cards, children }, SolverAction::Deal(card)) => {
+                    dealt = Some(*card);
+                    cards.iter().position(|c| c == card).map(|i| &children[i])
+                }
+                _ => None,
+            }
+            .ok_or_else(|| {
+                PokercraftLocalError::GeneralError(format!("Action {} is not available", action))
+            })?;
+        }
+        let Node::Decision {
+            player,
+            index,
+            actions,
+            ..
+        } = node
+        else {
+            return Err(PokercraftLocalError::GeneralError(
+                "History does not lead to a decision".to_string(),
+            ));
+        };
+        let strategy = normalize(&self.strategy_sums[*index], actions.len());
+        let combos = self.combos[*player].len();
+        Ok(NodeStrategy {
+            player: *player,
+            actions: actions.clone(),
+            combos: (0..combos)
+                .filter(|&i| {
+                    let (c1, c2) = self.combos[*player][i];
+                    dealt.is_none_or(|card| c1 != card && c2 != card)
+                })
+                .map(|i| {
+                    let frequencies = (0..actions.len())
+                        .map(|action| strategy[action * combos + i])
+                        .collect();
+                    (self.combos[*player][i], frequencies)
+                })
+                .collect(),
+        })
+    }
+
+    /// Get the expected value of each combo of the given player
+    /// when both players follow the average strategies.
+    pub fn expected_values(&self, player: usize) -> Vec<(Hand, f64)> {
+        let values = self.evaluate(&self.root, player, &self.weights[1 - player], false);
+        let compatible = self.compatible_sums(player, &self.weights[1 - player]);
+        (0..self.combos[player].len())
+            .filter(|&i| compatible[i] > 0.0)
+            .map(|i| {
+                (
+                    self.combos[player][i],
+                    self.pot / 2.0 + values[i] / compatible[i],
+                )
+            })
+            .collect()
+    }
+
+    /// Get the raw equity of each combo of the given player against the range
+    /// of the opponent, and its realized equity when both players follow
+    /// the average strategies, which is the expected value over the pot.
+    pub fn realized_equities(&self, player: usize) -> Vec<(Hand, RealizedEquity)> {
+        let raw = self.evaluate(&self.showdown, player, &self.weights[1 - player], false);
+        let compatible = self.compatible_sums(player, &self.weights[1 - player]);
+        self.expected_values(player)
+            .into_iter()
+            .map(|(hand, value)| {
+                let i = self.combos[player]
+                    .iter()
+                    .position(|combo| *combo == hand)
+                    .unwrap_or_default();
+                let equity = RealizedEquity {
+                    raw: 0.5 + raw[i] / compatible[i],
+                    realized: value / self.pot,
+                };
+                (hand, equity)
+            })
+            .collect()
+    }
+
+    /// Get the exploitability of the average strategies, which is the average
+    /// of the chips each player would gain by switching to the best response.
+    /// Divide this by the pot to get the exploitability in pot percentages.
+    pub fn exploitability(&self) -> f64 {
+        let normalizer = self.normalizer();
+        let best_responses = [0, 1].map(|player| {
+            self.evaluate(&self.root, player, &self.weights[1 - player], true)
+                .iter()
+                .zip(self.weights[player].iter())
+                .map(|(value, weight)| value * weight)
+                .sum::<f64>()
+                / normalizer
+        });
+        (best_responses[0] + best_responses[1]) / 2.0
+    }
+
+    /// Get the total weight of all pairs of non-overlapping combos.
+    fn normalizer(&self) -> f64 {
+        self.compatible_sums(0, &self.weights[1])
+            .iter()
+            .zip(self.weights[0].iter())
+            .map(|(sum, weight)| sum * weight)
+            .sum()
+    }
+
+    /// Get the sum of `reach` of opponent combos not overlapping with each combo of `player`.
+    fn compatible_sums(&self, player: usize, reach: &[f64]) -> Vec<f64> {
+        let mut total = 0.0;
+        let mut per_card = [0.0; 52];
+        for (j, [c1, c2]) in self.cards[1 - player].iter().enumerate() {
+            total += reach[j];
+            per_card[*c1] += reach[j];
+            per_card[*c2] += reach[j];
+        }
+        self.cards[player]
+            .iter()
+            .zip(self.same[player].iter())
+            .map(|([c1, c2], same)| {
+                total - per_card[*c1] - per_card[*c2] + same.map_or(0.0, |j| reach[j])
+            })
+            .collect()
+    }
+
+    /// Get values of `player` at a showdown, as sums of `reach` of opponent combos
+    /// weaker than each combo minus ones stronger than it, multiplied by `loss`.
+    fn showdown_values(&self, player: usize, reach: &[f64], board: usize, loss: f64) -> Vec<f64> {
+        let own = &self.boards[board].sorted[player];
+        let opponent = &self.boards[board].sorted[1 - player];
+        let mut values = vec![0.0; self.combos[player].len()];
+
+        let mut total = 0.0;
+        let mut per_card = [0.0; 52];
+        let mut k = 0;
+        for &(rank, i) in own.iter() {
+            while k < opponent.len() && opponent[k].0 < rank {
+                let j = opponent[k].1;
+                let [c1, c2] = self.cards[1 - player][j];
+                total += reach[j];
+                per_card[c1] += reach[j];
+                per_card[c2] += reach[j];
+                k += 1;
+            }
+            let [c1, c2] = self.cards[player][i];
+            values[i] += loss * (total - per_card[c1] - per_card[c2]);
+        }
+
+        let mut total = 0.0;
+        let mut per_card = [0.0; 52];
+        let mut k = opponent.len();
+        for &(rank, i) in own.iter().rev() {
+            while k > 0 && opponent[k - 1].0 > rank {
+                let j = opponent[k - 1].1;
+                let [c1, c2] = self.cards[1 - player][j];
+                total += reach[j];
+                per_card[c1] += reach[j];
+                per_card[c2] += reach[j];
+                k -= 1;
+            }
+            let [c1, c2] = self.cards[player][i];
+            values[i] -= loss * (total - per_card[c1] - per_card[c2]);
+        }
+        values
+    }
+
+    /// Get values of `player` at a fold.
+    fn fold_values(&self, player: usize, reach: &[f64], folder: usize, loss: f64) -> Vec<f64> {
+        let sign = if folder == player { -loss } else { loss };
+        self.compatible_sums(player, reach)
+            .into_iter()
+            .map(|sum| sign * sum)
+            .collect()
+    }
+
+    /// Remove combos of the player using the river card from the reach probabilities.
+    fn without_card(&self, player: usize, reach: &[f64], card: Card) -> Vec<f64> {
+        let mut reach = reach.to_vec();
+        for (i, cards) in self.cards[player].iter().enumerate() {
+            if cards.contains(&card.index()) {
+                reach[i] = 0.0;
+            }
+        }
+        reach
+    }
+
+    /// Get values of each combo of `traverser` at the chance node,
+    /// averaging values of children from `child_values`.
+    fn chance_values<F>(&self, traverser: usize, cards: &[Card], mut child_values: F) -> Vec<f64>
+    where
+        F: FnMut(usize, Card) -> Vec<f64>,
+    {
+        let mut values = vec![0.0; self.combos[traverser].len()];
+        for (k, card) in cards.iter().enumerate() {
+            let child = child_values(k, *card);
+            for (i, cards) in self.cards[traverser].iter().enumerate() {
+                if !cards.contains(&card.index()) {
+                    values[i] += child[i] / self.rivers;
+                }
+            }
+        }
+        values
+    }
+
+    /// Run a single iteration of CFR+ for `traverser` on the node, updating regrets
+    /// and strategy sums of its decisions, and return counterfactual values of its combos.
+    fn train(&mut self, node: &Node, traverser: usize, own: &[f64], opponent: &[f64]) -> Vec<f64> {
+        match node {
+            Node::Decision {
+                player,
+                index,
+                actions,
+                children,
+            } => {
+                let combos = self.combos[*player].len();
+                let strategy = normalize(&self.regrets[*index], actions.len());
+                if *player == traverser {
+                    let mut values = vec![0.0; combos];
+                    let mut action_values = Vec::with_capacity(actions.len());
+                    for (action, child) in children.iter().enumerate() {
+                        let frequencies = &strategy[action * combos..(action + 1) * combos];
+                        let reach: Vec<f64> =
+                            own.iter().zip(frequencies).map(|(r, f)| r * f).collect();
+                        let child_values = self.train(child, traverser, &reach, opponent);
+                        for i in 0..combos {
+                            values[i] += frequencies[i] * child_values[i];
+                        }
+                        action_values.push(child_values);
+                    }
+                    let weight = self.iterations as f64;
+                    for (action, child_values) in action_values.iter().enumerate() {
+                        for i in 0..combos {
+                            let at = action * combos + i;
+                            self.regrets[*index][at] =
+                                (self.regrets[*index][at] + child_values[i] - values[i]).max(0.0);
+                            self.strategy_sums[*index][at] += weight * own[i] * strategy[at];
+                        }
+                    }
+                    values
+                } else {
+                    let mut values = vec![0.0; self.combos[traverser].len()];
+                    for (action, child) in children.iter().enumerate() {
+                        let frequencies = &strategy[action * combos..(action + 1) * combos];
+                        let reach: Vec<f64> = opponent
+                            .iter()
+                            .zip(frequencies)
+                            .map(|(r, f)| r * f)
+                            .collect();
+                        for (value, child_value) in values
+                            .iter_mut()
+                            .zip(self.train(child, traverser, own, &reach))
+                        {
+                            *value += child_value;
+                        }
+                    }
+                    values
+                }
+            }
+            Node::Fold { folder, loss } => self.fold_values(traverser, opponent, *folder, *loss),
+            Node::Showdown { board, loss } => {
+                self.showdown_values(traverser, opponent, *board, *loss)
+            }
+            Node::Chance { cards, children } => {
+                let mut child_values = Vec::with_capacity(cards.len());
+                for (k, card) in cards.iter().enumerate() {
+                    let own = self.without_card(traverser, own, *card);
+                    let opponent = self.without_card(1 - traverser, opponent, *card);
+                    child_values.push(self.train(&children[k], traverser, &own, &opponent));
+                }
+                self.chance_values(traverser, cards, |k, _| {
+                    std::mem::take(&mut child_values[k])
+                })
+            }
+        }
+    }
+
+    /// Get values of each combo of `player` on the node against the average strategy
+    /// of the opponent, when `player` follows the average strategy too or the best response.
+    fn evaluate(
+        &self,
+        node: &Node,
+        player: usize,
+        opponent: &[f64],
+        best_response: bool,
+    ) -> Vec<f64> {
+        match node {
+            Node::Decision {
+                player: actor,
+                index,
+                actions,
+                children,
+            } => {
+                let combos = self.combos[*actor].len();
+                let strategy = normalize(&self.strategy_sums[*index], actions.len());
+                let mut values = vec![
+                    if *actor == player && best_response {
+                        f64::NEG_INFINITY
+                    } else {
+                        0.0
+                    };
+                    self.combos[player].len()
+                ];
+                for (action, child) in children.iter().enumerate() {
+                    let frequencies = &strategy[action * combos..(action + 1) * combos];
+                    if *actor == player {
+                        let child_values = self.evaluate(child, player, opponent, best_response);
+                        for i in 0..combos {
+                            if best_response {
+                                values[i] = values[i].max(child_values[i]);
+                            } else {
+                                values[i] += frequencies[i] * child_values[i];
+                            }
+                        }
+                    } else {
+                        let reach: Vec<f64> = opponent
+                            .iter()
+                            .zip(frequencies)
+                            .map(|(r, f)| r * f)
+                            .collect();
+                        for (value, child_value) in values.iter_mut().zip(self.evaluate(
+                            child,
+                            player,
+                            &reach,
+                            best_response,
+                        )) {
+                            *value += child_value;
+                        }
+                    }
+                }
+                values
+            }
+            Node::Fold { folder, loss } => self.fold_values(player, opponent, *folder, *loss),
+            Node::Showdown { board, loss } => self.showdown_values(player, opponent, *board, *loss),
+            Node::Chance { cards, children } => self.chance_values(player, cards, |k, card| {
+                let opponent = self.without_card(1 - player, opponent, card);
+                self.evaluate(&children[k], player, &opponent, best_response)
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+    use crate::range::HandRange;
+
+    fn weighted(range: &str, weight: f64) -> Result<Vec<(Hand, f64)>, PokercraftLocalError> {
+        Ok(HandRange::try_from(range)?
+            .combos()
+            .iter()
+            .map(|combo| (*combo, weight))
+            .collect())
+    }
+
+    #[test]
+    fn test_river_bluff_catching() -> Result<(), PokercraftLocalError> {
+        // Nut sets and air against bluff catchers, with a single pot-sized bet
+        let config = SolverConfig {
+            board: parse_cards("Ks 9h 7d 3c 2s")?,
+            pot: 100.0,
+            effective_stack: 100.0,
+            bet_sizes: vec![1.0],
+            raise_sizes: vec![],
+            max_raises: 0,
+            ranges: [
+                [weighted("99", 1.0)?, weighted("5c4c,5d4d,5h4h", 1.0)?].concat(),
+                weighted("QQ", 1.0)?,
+            ],
+        };
+        let mut solver = Solver::new(&config)?;
+        solver.run(1000);
+        assert_eq!(solver.iterations(), 1000);
+        assert!(solver.exploitability() < 1.0);
+
+        // Value bets always, and bluffs make a third of bets
+        let root = solver.strategy(&[])?;
+        assert_eq!(root.player, 0);
+        assert_eq!(
+            root.actions,
+            vec![SolverAction::Check, SolverAction::Bet(100.0)]
+        );
+        let bets = |class: u8| {
+            root.combos
+                .iter()
+                .filter(|((c1, _), _)| c1.number as u8 == class)
+                .map(|(_, frequencies)| frequencies[1])
+                .sum::<f64>()
+        };
+        assert!((bets(9) - 3.0).abs() < 0.05);
+        assert!((bets(5) - 1.5).abs() < 0.1);
+
+        // Bluff catchers call as often as the minimum defense frequency
+        let facing_bet = solver.strategy(&[SolverAction::Bet(100.0)])?;
+        assert_eq!(facing_bet.player, 1);
+        assert_eq!(
+            facing_bet.actions,
+            vec![SolverAction::Fold, SolverAction::Call]
+        );
+        let calls = facing_bet
+            .combos
+            .iter()
+            .map(|(_, frequencies)| frequencies[1])
+            .sum::<f64>()
+            / facing_bet.combos.len() as f64;
+        assert!((calls - 0.5).abs() < 0.05);
+
+        let values = solver.expected_values(0);
+        for ((c1, _), value) in values {
+            let expected = if c1.number as u8 == 9 { 150.0 } else { 0.0 };
+            assert!((value - expected).abs() < 5.0);
+        }
+
+        // Bluff catchers only win the pot when checked to, realizing half of the equity
+        for (hand, equity) in solver.realized_equities(1) {
+            assert!((equity.raw - 0.5).abs() < 1e-9, "{:?}", hand);
+            assert!((equity.realized - 0.25).abs() < 0.02);
+        }
+        let sets = solver.realized_equities(0)[0].1;
+        assert_eq!(sets.raw, 1.0);
+        assert!((sets.realized - 1.5).abs() < 0.05);
+
+        assert!(solver.strategy(&[SolverAction::Bet(50.0)]).is_err());
+        assert!(solver
+            .strategy(&[SolverAction::Bet(100.0), SolverAction::Fold])
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_turn_solver() -> Result<(), PokercraftLocalError> {
+        let config = SolverConfig {
+            board: parse_cards("Ks 9h 7d 3c")?,
+            pot: 100.0,
+            effective_stack: 150.0,
+            bet_sizes: vec![0.5, 2.0],
+            raise_sizes: vec![1.0],
+            max_raises: 1,
+            ranges: [weighted("99,AK", 1.0)?, weighted("KQs,QQ,T8s", 0.5)?],
+        };
+        let mut solver = Solver::new(&config)?;
+        solver.run(30);
+        assert!(solver.exploitability() < 10.0);
+        assert_eq!(solver.combos(0).len(), 3 + 12);
+
+        // Half pot bet, and the overbet is capped at the stack
+        let root = solver.strategy(&[])?;
+        assert_eq!(
+            root.actions,
+            vec![
+                SolverAction::Check,
+                SolverAction::Bet(50.0),
+                SolverAction::Bet(150.0)
+            ]
+        );
+        for (_, frequencies) in root.combos.iter() {
+            assert!((frequencies.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+        let raise = solver.strategy(&[SolverAction::Bet(50.0)])?;
+        assert_eq!(raise.actions[2], SolverAction::Raise(150.0));
+
+        // Combos blocked by the river are not shown
+        let river = solver.strategy(&[
+            SolverAction::Check,
+            SolverAction::Check,
+            SolverAction::Deal(Card::try_from("Qh")?),
+        ])?;
+        assert_eq!(river.player, 0);
+        assert_eq!(river.actions.len(), 3);
+        assert_eq!(river.combos.len(), 15);
+        let river = solver.strategy(&[
+            SolverAction::Check,
+            SolverAction::Check,
+            SolverAction::Deal(Card::try_from("Ah")?),
+        ])?;
+        assert_eq!(river.combos.len(), 3 + 9);
+        assert!(solver
+            .strategy(&[
+                SolverAction::Check,
+                SolverAction::Check,
+                SolverAction::Deal(Card::try_from("Ks")?),
+            ])
+            .is_err());
+        assert!(solver
+            .strategy(&[SolverAction::Bet(150.0), SolverAction::Call])
+            .is_err());
+
+        let invalid = SolverConfig {
+            board: parse_cards("Ks 9h 7d")?,
+            ..config.clone()
+        };
+        assert!(Solver::new(&invalid).is_err());
+        let invalid = SolverConfig {
+            bet_sizes: vec![0.0],
+            ..config.clone()
+        };
+        assert!(Solver::new(&invalid).is_err());
+        let invalid = SolverConfig {
+            ranges: [weighted("Ks9s", 1.0)?, weighted("QQ", 1.0)?],
+            ..config
+        };
+        assert!(Solver::new(&invalid).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+//! Game trees of the solver, built from bet sizings.
+
+use crate::card::Card;
+
+use super::{SolverAction, SolverConfig};
+
+/// Tolerance of comparing chip amounts.
+const EPSILON: f64 = 1e-9;
+
+/// A node of the game tree.
+pub(super) enum Node {
+    /// `player` takes one of `actions`, whose regrets and strategies
+    /// are stored at `index` of the solver tables.
+    Decision {
+        player: usize,
+        index: usize,
+        actions: Vec<SolverAction>,
+        children: Vec<Node>,
+    },
+    /// `folder` folded, losing `loss` to the other player.
+    Fold { folder: usize, loss: f64 },
+    /// Showdown on the `board`th board of the solver, where the loser loses `loss`.
+    Showdown { board: usize, loss: f64 },
+    /// The river is dealt; `children[k]` continues on the `k`th board of the solver,
+    /// whose river is `cards[k]`.
+    Chance {
+        cards: Vec<Card>,
+        children: Vec<Node>,
+    },
+}
+
+/// Betting state while building the tree.
+#[derive(Clone, Copy)]
+struct State {
+    /// Chips put in by each player since the start of the spot.
+    invested: [f64; 2],
+    /// Chips put in by each player on the current street.
+    street: [f64; 2],
+    player: usize,
+    raises: u32,
+    /// Index of the river board, or `None` on the turn.
+    board: Option<usize>,
+}
+
+/// Builds the game tree, counting decisions of each player.
+pub(super) struct TreeBuilder<'a> {
+    config: &'a SolverConfig,
+    /// River cards of each board; Empty for a river spot.
+    rivers: &'a [Card],
+    /// Acting player and the number of actions of each decision.
+    pub(super) decisions: Vec<(usize, usize)>,
+}
+
+impl<'a> TreeBuilder<'a> {
+    pub(super) fn new(config: &'a SolverConfig, rivers: &'a [Card]) -> Self {
+        TreeBuilder {
+            config,
+            rivers,
+            decisions: Vec::new(),
+        }
+    }
+
+    /// Build the tree from the start of the spot.
+    pub(super) fn build(&mut self) -> Node {
+        self.street(State {
+            invested: [0.0; 2],
+            street: [0.0; 2],
+            player: 0,
+            raises: 0,
+            board: self.rivers.is_empty().then_some(0),
+        })
+    }
+
+    /// Start a new street, or go to the river if both players are all-in.
+    fn street(&mut self, state: State) -> Node {
+        if self.config.effective_stack - state.invested[0] > EPSILON {
+            self.decision(state)
+        } else {
+            self.close(state)
+        }
+    }
+
+    /// Add all actions of the player to act.
+    fn decision(&mut self, state: State) -> Node {
+        let player = state.player;
+        let pot = self.config.pot + state.invested[0] + state.invested[1];
+        let to_call = state.street[1 - player] - state.street[player];
+        let left = self.config.effective_stack - state.invested[player];
+
+        let mut actions = Vec::new();
+        let mut push_unique = |action: SolverAction, amount: f64| {
+            if amount > EPSILON
+                && !actions.iter().any(|previous| match (previous, &action) {
+                    (SolverAction::Bet(a), SolverAction::Bet(b))
+                    | (SolverAction::Raise(a), SolverAction::Raise(b)) => (a - b).abs() < EPSILON,
+                    _ => false,
+                })
+            {
+                actions.push(action);
+            }
+        };
+        if to_call < EPSILON {
+            push_unique(SolverAction::Check, 1.0);
+            for size in self.config.bet_sizes.iter() {
+                let amount = (size * pot).min(left);
+                push_unique(SolverAction::Bet(amount), amount);
+            }
+        } else {
+            push_unique(SolverAction::Fold, 1.0);
+            push_unique(SolverAction::Call, 1.0);
+            if state.raises < self.config.max_raises && left > to_call + EPSILON {
+                for size in self.config.raise_sizes.iter() {
+                    let raise_to = (state.street[1 - player] + size * (pot + to_call))
+                        .min(state.street[player] + left);
+                    push_unique(SolverAction::Raise(raise_to), raise_to);
+                }
+            }
+        }
+
+        let index = self.decisions.len();
+        self.decisions.push((player, actions.len()));
+        let children = actions
+            .iter()
+            .map(|action| match *action {
+                SolverAction::Check if player == 1 => self.close(state),
+                SolverAction::Check => self.decision(State { player: 1, ..state }),
+                SolverAction::Fold => Node::Fold {
+                    folder: player,
+                    loss: self.config.pot / 2.0 + state.invested[player],
+                },
+                SolverAction::Call => self.close(Self::put(state, to_call, 0)),
+                SolverAction::Bet(amount) => self.decision(Self::put(state, amount, 0)),
+                SolverAction::Raise(raise_to) => {
+                    self.decision(Self::put(state, raise_to - state.street[player], 1))
+                }
+                SolverAction::Deal(_) => unreachable!("Players do not deal cards"),
+            })
+            .collect();
+        Node::Decision {
+            player,
+            index,
+            actions,
+            children,
+        }
+    }
+
+    /// Put chips of the player to act, and pass the action to the other player.
+    fn put(mut state: State, amount: f64, raises: u32) -> State {
+        state.invested[state.player] += amount;
+        state.street[state.player] += amount;
+        state.player = 1 - state.player;
+        state.raises += raises;
+        state
+    }
+
+    /// Close the current street, going to the showdown or the river.
+    fn close(&mut self, state: State) -> Node {
+        match state.board {
+            Some(board) => Node::Showdown {
+                board,
+                loss: self.config.pot / 2.0 + state.invested[0],
+            },
+            None => Node::Chance {
+                cards: self.rivers.to_vec(),
+                children: (0..self.rivers.len())
+                    .map(|board| {
+                        self.street(State {
+                            street: [0.0; 2],
+                            player: 0,
+                            raises: 0,
+                            board: Some(board),
+                            ..state
+                        })
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
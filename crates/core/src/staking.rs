@@ -0,0 +1,177 @@
+//! Staking packages, where a player sells shares of the action with markup.
+//!
+//! All amounts are in buy-ins. Buyers pay `share_sold * markup` per tournament
+//! and receive `share_sold` of all prizes, and the player pays everything else.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::errors::PokercraftLocalError;
+use crate::utils::in_thread_pool;
+
+/// A staking package of multiple tournaments of the same buy-in.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct StakingPackage {
+    /// Price of the action relative to the buy-in, like `1.2` for 20% markup.
+    pub markup: f64,
+    /// Share of the action sold to buyers, in `(0, 1]`.
+    pub share_sold: f64,
+    /// Number of tournaments in the package.
+    pub tournaments: u32,
+}
+
+/// Expected values and risks of a staking package.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct StakingSummary {
+    /// Number of simulations performed.
+    pub simulations: usize,
+    /// ROI of the player per tournament, without staking.
+    pub player_roi: f64,
+    /// Expected profit of all buyers over the package, in buy-ins.
+    pub buyer_ev: f64,
+    /// Expected profit of the player over the package, in buy-ins.
+    pub seller_ev: f64,
+    /// Expected profit of buyers over the price they paid.
+    pub buyer_roi: f64,
+    /// Probability that buyers get back less than they paid.
+    pub buyer_loss_probability: f64,
+    /// Probability that the player loses money after selling action.
+    pub seller_loss_probability: f64,
+}
+
+impl StakingPackage {
+    /// Create a new package, checking all parameters.
+    pub fn new(
+        markup: f64,
+        share_sold: f64,
+        tournaments: u32,
+    ) -> Result<Self, PokercraftLocalError> {
+        if !(markup > 0.0 && markup.is_finite()) {
+            return Err(PokercraftLocalError::GeneralError(
+                "Markup must be positive".to_string(),
+            ));
+        } else if !(share_sold > 0.0 && share_sold <= 1.0) {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Share sold should be in (0, 1], got {}",
+                share_sold
+            )));
+        } else if tournaments < 1 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Package must have at least 1 tournament".to_string(),
+            ));
+        }
+        Ok(StakingPackage {
+            markup,
+            share_sold,
+            tournaments,
+        })
+    }
+
+    /// Get the markup where buyers break even against a player of the given ROI.
+    pub fn break_even_markup(player_roi: f64) -> f64 {
+        1.0 + player_roi
+    }
+
+    /// Get the price paid by buyers, in buy-ins.
+    pub fn price(&self) -> f64 {
+        self.share_sold * self.markup * self.tournaments as f64
+    }
+
+    /// Get profits of buyers and the player from the total prizes of the package.
+    fn profits(&self, prizes: f64) -> (f64, f64) {
+        let buyer = self.share_sold * prizes - self.price();
+        let seller = prizes - self.tournaments as f64 - buyer;
+        (buyer, seller)
+    }
+
+    /// Evaluate this package against a player whose results of each tournament
+    /// follow the given relative returns (prize / buy-in - 1), like
+    /// `TournamentSummary::relative_returns`. Expected values are exact,
+    /// and loss probabilities come from `simulation_count` packages
+    /// of tournaments resampled in parallel; The same seed gives the same result.
+    pub fn evaluate(
+        &self,
+        relative_returns: &[f64],
+        simulation_count: u32,
+        seed: u64,
+    ) -> Result<StakingSummary, PokercraftLocalError> {
+        if relative_returns.is_empty() {
+            return Err(PokercraftLocalError::GeneralError(
+                "Relative return results must not be empty".to_string(),
+            ));
+        } else if simulation_count < 1 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Simulation count must be positive".to_string(),
+            ));
+        }
+
+        let player_roi = relative_returns.iter().sum::<f64>() / relative_returns.len() as f64;
+        let expected_prizes = (1.0 + player_roi) * self.tournaments as f64;
+        let (buyer_ev, seller_ev) = self.profits(expected_prizes);
+
+        let (buyer_losses, seller_losses) = in_thread_pool(|| {
+            (0..simulation_count as u64)
+                .into_par_iter()
+                .map(|simulation| {
+                    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(simulation));
+                    let prizes = (0..self.tournaments)
+                        .map(|_| 1.0 + relative_returns[rng.gen_range(0..relative_returns.len())])
+                        .sum::<f64>();
+                    let (buyer, seller) = self.profits(prizes);
+                    ((buyer < 0.0) as u32, (seller < 0.0) as u32)
+                })
+                .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1))
+        });
+
+        Ok(StakingSummary {
+            simulations: simulation_count as usize,
+            player_roi,
+            buyer_ev,
+            seller_ev,
+            buyer_roi: buyer_ev / self.price(),
+            buyer_loss_probability: buyer_losses as f64 / simulation_count as f64,
+            seller_loss_probability: seller_losses as f64 / simulation_count as f64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staking_package() -> Result<(), PokercraftLocalError> {
+        // 50% ROI with 1 win of 15 buy-ins in every 10 tournaments
+        let mut relative_returns = vec![-1.0; 9];
+        relative_returns.push(14.0);
+        let package = StakingPackage::new(1.2, 0.5, 100)?;
+        assert!((package.price() - 60.0).abs() < 1e-9);
+
+        let summary = package.evaluate(&relative_returns, 5000, 2147)?;
+        assert_eq!(summary, package.evaluate(&relative_returns, 5000, 2147)?);
+        assert!((summary.player_roi - 0.5).abs() < 1e-9);
+        assert!((summary.buyer_ev - 15.0).abs() < 1e-9);
+        assert!((summary.seller_ev - 35.0).abs() < 1e-9);
+        assert!((summary.buyer_roi - 0.25).abs() < 1e-9);
+        // Buyers lose with 7 or fewer wins, and the player loses with 5 or fewer wins
+        assert!((summary.buyer_loss_probability - 0.206).abs() < 0.02);
+        assert!((summary.seller_loss_probability - 0.058).abs() < 0.01);
+
+        // Buyers break even at the markup of 1 + ROI
+        let fair = StakingPackage::new(StakingPackage::break_even_markup(0.5), 1.0, 100)?;
+        let summary = fair.evaluate(&relative_returns, 100, 0)?;
+        assert!(summary.buyer_ev.abs() < 1e-9);
+        assert!((summary.seller_ev - 50.0).abs() < 1e-9);
+        assert_eq!(summary.seller_loss_probability, 0.0);
+
+        assert!(StakingPackage::new(0.0, 0.5, 100).is_err());
+        assert!(StakingPackage::new(1.2, 1.5, 100).is_err());
+        assert!(StakingPackage::new(1.2, 0.5, 0).is_err());
+        assert!(package.evaluate(&[], 100, 0).is_err());
+        assert!(package.evaluate(&relative_returns, 0, 0).is_err());
+        Ok(())
+    }
+}
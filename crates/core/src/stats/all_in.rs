@@ -0,0 +1,266 @@
+//! All-in situations with known equities.
+
+use std::collections::HashSet;
+
+use rayon::prelude::*;
+
+use crate::card::Card;
+use crate::equity::{EquityCache, LuckCalculator};
+use crate::errors::PokercraftLocalError;
+use crate::parser::{BetActionType, HandHistory, HandStage};
+use crate::utils::in_thread_pool;
+
+/// A single all-in before the river, where cards of the player
+/// and at least one opponent were revealed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllInSpot {
+    pub hand_id: String,
+    /// Street where the player went all-in.
+    pub stage: HandStage,
+    /// Equity of the player at the moment of all-in.
+    pub equity: f64,
+    /// Actual result; `1.0` on win, `0.0` on lose,
+    /// and fractional values on ties.
+    pub actual: f64,
+    /// Total chips collected from pots of the hand.
+    pub pot: u64,
+}
+
+/// Get players who did not fold until the end of the hand.
+pub(crate) fn showdown_players(hand: &HandHistory) -> HashSet<&str> {
+    let mut players: HashSet<&str> = hand.seats.values().map(|(pid, _)| pid.as_str()).collect();
+    for stage in HandStage::all() {
+        for action in hand.actions(stage) {
+            if action.action == BetActionType::Fold {
+                players.remove(action.player_id.as_str());
+            }
+        }
+    }
+    players
+}
+
+impl AllInSpot {
+    /// Find the all-in spot of the given player in the given hand.
+    /// Returns `Ok(None)` if the player did not go all-in before the river,
+    /// or if there is no revealed opponent at showdown.
+    pub fn from_hand(
+        hand: &HandHistory,
+        player_id: &str,
+        parallel_calculation: bool,
+    ) -> Result<Option<Self>, PokercraftLocalError> {
+        let stage = match hand.all_ined.get(player_id) {
+            Some(&stage) if stage != HandStage::River => stage,
+            _ => return Ok(None),
+        };
+        let showdown = showdown_players(hand);
+        if showdown.len() < 2 || !showdown.contains(player_id) {
+            return Ok(None);
+        }
+        let Some(&my_cards) = hand.known_cards.get(player_id) else {
+            return Ok(None);
+        };
+
+        let mut cards_people = vec![my_cards];
+        // Sort opponents to make the calculation deterministic
+        let mut opponents: Vec<&str> = showdown
+            .iter()
+            .copied()
+            .filter(|&pid| pid != player_id)
+            .collect();
+        opponents.sort_unstable();
+        cards_people.extend(
+            opponents
+                .iter()
+                .filter_map(|&pid| hand.known_cards.get(pid).copied()),
+        );
+        if cards_people.len() < 2 {
+            return Ok(None);
+        }
+
+        let num_community = match stage {
+            HandStage::Preflop => 0,
+            HandStage::Flop => 3,
+            HandStage::Turn => 4,
+            HandStage::River => 5,
+        };
+        if hand.community_cards.len() < num_community {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Not enough community cards for all-in on {} in hand {}",
+                stage, hand.id
+            )));
+        }
+        let community: Vec<Card> = hand.community_cards[..num_community].to_vec();
+        let equity = EquityCache::shared()
+            .get_or_calculate(cards_people, community, parallel_calculation)?
+            .get_equity(0)?;
+
+        Ok(Some(AllInSpot {
+            hand_id: hand.id.clone(),
+            stage,
+            equity,
+            actual: Self::actual_result(hand, player_id, &showdown),
+            pot: hand.total_pot(),
+        }))
+    }
+
+    /// Get the chips won over the chips expected from the equity.
+    /// Side pots are not distinguished, so this is an approximation on multiway all-ins.
+    pub fn luck_chips(&self) -> f64 {
+        (self.actual - self.equity) * self.pot as f64
+    }
+
+    /// Get the actual share of the biggest pot won by the given player.
+    fn actual_result(hand: &HandHistory, player_id: &str, showdown: &HashSet<&str>) -> f64 {
+        let my_won = hand.wons.get(player_id).copied().unwrap_or(0);
+        if my_won == 0 {
+            return 0.0;
+        }
+        let winners: Vec<u64> = hand
+            .wons
+            .iter()
+            .filter(|(pid, &amount)| showdown.contains(pid.as_str()) && amount > 0)
+            .map(|(_, &amount)| amount)
+            .collect();
+        let max_won = winners.iter().copied().max().unwrap_or(0);
+        if my_won == max_won {
+            1.0 / winners.iter().filter(|&&amount| amount == max_won).count() as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// All-in spots found from hands, with errors of inconsistent hands which were skipped.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AllInSpots {
+    pub spots: Vec<AllInSpot>,
+    /// Non-fatal error on a single hand, prefixed by the hand ID.
+    pub errors: Vec<String>,
+}
+
+/// Find all-in spots of the given player from all given hands,
+/// in the same order of hands. Hands are processed in parallel.
+/// Inconsistent hands, like all-ins without enough community cards
+/// or results impossible with the equity, are skipped and reported in `errors`.
+pub fn find_all_in_spots<'a, I>(hands: I, player_id: &str) -> AllInSpots
+where
+    I: IntoParallelIterator<Item = &'a HandHistory>,
+{
+    let hands = hands.into_par_iter();
+    let results = in_thread_pool(|| {
+        hands
+            .map(|hand| {
+                AllInSpot::from_hand(hand, player_id, false)
+                    .and_then(|spot| {
+                        if let Some(spot) = spot.as_ref() {
+                            LuckCalculator::validate_result(spot.equity, spot.actual)?;
+                        }
+                        Ok(spot)
+                    })
+                    .map_err(|err| format!("{}: {}", hand.id, err))
+            })
+            .collect::<Vec<_>>()
+    });
+    let mut found = AllInSpots::default();
+    for result in results {
+        match result {
+            Ok(Some(spot)) => found.spots.push(spot),
+            Ok(None) => {}
+            Err(error) => found.errors.push(error),
+        }
+    }
+    found
+}
+
+impl LuckCalculator {
+    /// Create a new `LuckCalculator` filled with
+    /// all all-in spots of the given player from the given hands.
+    /// Inconsistent hands are skipped; See `find_all_in_spots`.
+    pub fn from_hands<'a, I>(hands: I, player_id: &str) -> Result<Self, PokercraftLocalError>
+    where
+        I: IntoParallelIterator<Item = &'a HandHistory>,
+    {
+        let mut calculator = LuckCalculator::new();
+        calculator.add_results(
+            find_all_in_spots(hands, player_id)
+                .spots
+                .iter()
+                .map(|spot| (spot.equity, spot.actual)),
+        )?;
+        Ok(calculator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_hands;
+
+    const FLOP_ALL_IN: &str = "Poker Hand #TM1: Tournament #1, Test Hold'em No Limit - Level1(100/200) - 2025/08/01 00:00:00
+Table '1' 2-max Seat #1 is the button
+Seat 1: Hero (1,000 in chips)
+Seat 2: aaaa (1,000 in chips)
+Hero: posts small blind 100
+aaaa: posts big blind 200
+*** HOLE CARDS ***
+Dealt to Hero [As Ad]
+Dealt to aaaa
+Hero: calls 100
+aaaa: checks
+*** FLOP *** [2c 7d 9h]
+aaaa: bets 800 and is all-in
+Hero: calls 800 and is all-in
+*** TURN *** [2c 7d 9h] [Jd]
+*** RIVER *** [2c 7d 9h Jd] [3s]
+aaaa: shows [Kc Kh]
+Hero: shows [As Ad]
+*** SHOWDOWN ***
+Hero collected 2,000 from pot
+*** SUMMARY ***
+";
+
+    #[test]
+    fn test_all_in_spots() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(FLOP_ALL_IN.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let found = find_all_in_spots(&hands, "Hero");
+        assert!(found.errors.is_empty());
+        let spots = found.spots;
+        assert_eq!(spots.len(), 1);
+        assert_eq!(spots[0].stage, HandStage::Flop);
+        assert_eq!(spots[0].actual, 1.0);
+        // KK wins on 87 of 990 runouts with a king, except 4 runouts with a king and an ace
+        let expected = 1.0 - 83.0 / 990.0;
+        assert!((spots[0].equity - expected).abs() < 1e-9);
+        assert_eq!(spots[0].pot, 2000);
+        assert!((spots[0].luck_chips() - 2000.0 * (1.0 - expected)).abs() < 1e-6);
+
+        let opponent_spots = find_all_in_spots(&hands, "aaaa").spots;
+        assert_eq!(opponent_spots[0].actual, 0.0);
+        assert!(LuckCalculator::from_hands(&hands, "Hero")?
+            .luck_score()
+            .is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_inconsistent_all_in_skipped() -> Result<(), PokercraftLocalError> {
+        // Hero has quads on the flop but the opponent collects the pot
+        let inconsistent = FLOP_ALL_IN
+            .replace("#TM1", "#TM2")
+            .replace("2c 7d 9h", "Ac Ah 9h")
+            .replace("Hero collected", "aaaa collected");
+        let text = format!("{}\n\n{}", FLOP_ALL_IN, inconsistent);
+        let hands = parse_hands(text.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(hands.len(), 2);
+
+        let found = find_all_in_spots(&hands, "Hero");
+        assert_eq!(found.spots.len(), 1);
+        assert_eq!(found.spots[0].hand_id, hands[0].id);
+        assert_eq!(found.errors.len(), 1);
+        assert!(found.errors[0].starts_with(&format!("{}: ", hands[1].id)));
+        let mut calculator = LuckCalculator::from_hands(&hands, "Hero")?;
+        assert!(calculator.pop().is_some());
+        assert!(calculator.pop().is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+//! Bootstrap confidence intervals of any metric over per-unit results,
+//! like ROI and ITM ratio of tournaments with `aggregate_tournaments`,
+//! average finish, or custom metrics defined by closures.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::errors::PokercraftLocalError;
+use crate::utils::in_thread_pool;
+
+/// An estimated metric with its confidence interval.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ConfidenceInterval {
+    pub estimate: f64,
+    pub lower: f64,
+    pub upper: f64,
+    /// Confidence level, like `0.95`.
+    pub confidence: f64,
+    /// Number of units used.
+    pub samples: usize,
+}
+
+impl ConfidenceInterval {
+    /// Check if the interval excludes zero,
+    /// so the sign of the estimate is significant at the confidence level.
+    pub fn is_significant(&self) -> bool {
+        self.lower > 0.0 || self.upper < 0.0
+    }
+}
+
+/// Check the common conditions of all intervals.
+pub(crate) fn validate(samples: usize, confidence: f64) -> Result<(), PokercraftLocalError> {
+    if samples < 2 {
+        return Err(PokercraftLocalError::GeneralError(
+            "At least 2 results are required".to_string(),
+        ));
+    }
+    if !(confidence > 0.0 && confidence < 1.0) {
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "Confidence should be in (0, 1), got {}",
+            confidence
+        )));
+    }
+    Ok(())
+}
+
+/// Get the percentile confidence interval of the given metric,
+/// by resampling units with replacement `resamples` times in parallel.
+/// `metric` returns `None` if it is undefined on the given sample,
+/// like ROI without any buy-in; Such resamples are skipped.
+/// Each resample has its own random generator derived from `seed`,
+/// so the same seed gives the same interval regardless of the parallelism.
+///
+/// For example, the ROI interval of tournaments is
+/// `bootstrap(&tournaments, 1000, seed, 0.95, |sample| aggregate_tournaments(sample.iter().copied()).roi())`.
+pub fn bootstrap<T, F>(
+    units: &[T],
+    resamples: usize,
+    seed: u64,
+    confidence: f64,
+    metric: F,
+) -> Result<ConfidenceInterval, PokercraftLocalError>
+where
+    T: Sync,
+    F: Fn(&[&T]) -> Option<f64> + Sync,
+{
+    validate(units.len(), confidence)?;
+    let estimate = metric(&units.iter().collect::<Vec<_>>()).ok_or_else(|| {
+        PokercraftLocalError::GeneralError("Metric is undefined on given units".to_string())
+    })?;
+
+    let mut estimates = in_thread_pool(|| {
+        (0..resamples as u64)
+            .into_par_iter()
+            .filter_map(|resample| {
+                let mut rng = StdRng::seed_from_u64(seed.wrapping_add(resample));
+                let sample = (0..units.len())
+                    .map(|_| &units[rng.gen_range(0..units.len())])
+                    .collect::<Vec<_>>();
+                metric(&sample)
+            })
+            .collect::<Vec<_>>()
+    });
+    if estimates.is_empty() {
+        return Err(PokercraftLocalError::GeneralError(
+            "No valid bootstrap resamples".to_string(),
+        ));
+    }
+    estimates.sort_by(|a, b| a.total_cmp(b));
+    let percentile = |ratio: f64| {
+        let position = (ratio * (estimates.len() - 1) as f64).round() as usize;
+        estimates[position]
+    };
+    Ok(ConfidenceInterval {
+        estimate,
+        lower: percentile((1.0 - confidence) / 2.0),
+        upper: percentile((1.0 + confidence) / 2.0),
+        confidence,
+        samples: units.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_tournament_summary, TournamentSummary};
+    use crate::stats::tournament::aggregate_tournaments;
+
+    #[test]
+    fn test_bootstrap() -> Result<(), PokercraftLocalError> {
+        // Finishing 1st of 10 players in 1 of every 4 tournaments, otherwise 5th
+        let tournaments = (0..200)
+            .map(|i| {
+                let (rank, prize) = if i % 4 == 0 { (1, "$30") } else { (5, "$0") };
+                let text = format!(
+                    "Tournament #{i}, Daily $10, Hold'em No Limit
+Buy-in: $9 + $1
+10 Players
+Total Prize Pool: $90
+Tournament started 2025/08/01 12:00:00
+{rank}th : Hero, {prize}
+
+You received a total of {prize}
+"
+                );
+                parse_tournament_summary(&text, false).map(Option::unwrap)
+            })
+            .collect::<Result<Vec<TournamentSummary>, _>>()?;
+
+        let roi = bootstrap(&tournaments, 500, 2142, 0.95, |sample| {
+            aggregate_tournaments(sample.iter().copied()).roi()
+        })?;
+        assert!((roi.estimate - (0.25 * 30.0 - 10.0) / 10.0).abs() < 1e-9);
+        assert!(roi.lower < roi.estimate && roi.estimate < roi.upper);
+        assert!(roi.is_significant());
+
+        let itm = bootstrap(&tournaments, 500, 2142, 0.95, |sample| {
+            aggregate_tournaments(sample.iter().copied()).itm_ratio()
+        })?;
+        assert!((itm.estimate - 0.25).abs() < 1e-9);
+        assert!(itm.lower > 0.15 && itm.upper < 0.35);
+
+        let average_finish = |sample: &[&TournamentSummary]| {
+            let ranks = sample.iter().map(|tournament| tournament.my_rank);
+            Some(ranks.sum::<u32>() as f64 / sample.len() as f64)
+        };
+        let finish = bootstrap(&tournaments, 500, 2142, 0.95, average_finish)?;
+        assert!((finish.estimate - 4.0).abs() < 1e-9);
+        assert!(finish.lower < 4.0 && 4.0 < finish.upper);
+        assert_eq!(
+            bootstrap(&tournaments, 500, 2142, 0.95, average_finish)?,
+            finish
+        );
+
+        assert!(bootstrap(&[1.0], 500, 0, 0.95, |_| Some(0.0)).is_err());
+        assert!(bootstrap(&[1.0, 2.0], 500, 0, 0.95, |_| None).is_err());
+        Ok(())
+    }
+}
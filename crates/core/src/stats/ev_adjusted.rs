@@ -0,0 +1,236 @@
+//! Decomposition of net winnings into the part expected from all-in equities
+//! and the luck part, per period, for EV-adjusted winnings graphs.
+//!
+//! Chips are converted into money with the linear chip model of each tournament;
+//! Every chip in play is worth the same share of the total prize pool,
+//! so the expected finish prize grows linearly with the stack.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+
+use super::all_in::find_all_in_spots;
+use crate::errors::PokercraftLocalError;
+use crate::parser::{HandHistory, TournamentSummary};
+
+/// Length of periods to group results by.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Period {
+    #[default]
+    Day,
+    /// Weeks starting from Mondays.
+    Week,
+    Month,
+}
+
+impl Period {
+    /// Get the first date of the period containing the given datetime.
+    pub fn start_of(&self, datetime: NaiveDateTime) -> NaiveDate {
+        let date = datetime.date();
+        match self {
+            Period::Day => date,
+            Period::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Period::Month => date.with_day(1).unwrap(),
+        }
+    }
+}
+
+/// Net winnings of a single period, split into expected and luck parts.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WinningsPeriod {
+    /// First date of the period.
+    pub start: NaiveDate,
+    pub hands_played: usize,
+    pub tournaments_played: usize,
+    /// Number of all-in spots of the hero with known equity.
+    pub all_in_count: usize,
+    /// Sum of tournament profits, in the currencies of the tournaments.
+    pub profit: f64,
+    /// Money won over expected in all-ins, converted by chip values of tournaments.
+    /// All-ins of tournaments without summaries are not counted.
+    pub luck: f64,
+    /// Sum of net chip profits of the hero over all hands.
+    pub chip_profit: i64,
+    /// Chips won over expected in all-ins.
+    pub chip_luck: f64,
+}
+
+impl WinningsPeriod {
+    /// Get the profit expected given all-in equities.
+    pub fn expected_profit(&self) -> f64 {
+        self.profit - self.luck
+    }
+
+    /// Get the chip profit expected given all-in equities.
+    pub fn expected_chip_profit(&self) -> f64 {
+        self.chip_profit as f64 - self.chip_luck
+    }
+}
+
+/// Get the money value of a single chip in each given tournament,
+/// assuming all entries started with the first stack of the hero in that tournament.
+fn chip_values(
+    hands: &[HandHistory],
+    tournaments: &[TournamentSummary],
+    hero_id: &str,
+) -> HashMap<u64, f64> {
+    let mut starting_stacks: HashMap<u64, (NaiveDateTime, u64)> = HashMap::new();
+    for hand in hands.iter() {
+        let (Some(tournament_id), Ok(chips)) = (hand.tournament_id, hand.initial_chips(hero_id))
+        else {
+            continue;
+        };
+        let entry = starting_stacks
+            .entry(tournament_id)
+            .or_insert((hand.datetime, chips));
+        if hand.datetime < entry.0 {
+            *entry = (hand.datetime, chips);
+        }
+    }
+    tournaments
+        .iter()
+        .filter_map(|tournament| {
+            let (_, stack) = starting_stacks.get(&tournament.id)?;
+            let total_chips = *stack as f64 * tournament.total_players as f64;
+            (total_chips > 0.0).then(|| (tournament.id, tournament.total_prize_pool / total_chips))
+        })
+        .collect()
+}
+
+/// Split net winnings of the hero into expected and luck parts per period,
+/// in chronological order. Hands are placed by their datetimes,
+/// and tournaments are placed by their start times.
+/// Cumulative sums of `profit` and `expected_profit` are the EV-adjusted winnings graph.
+/// All-ins of inconsistent hands are skipped; See `find_all_in_spots`.
+pub fn decompose_winnings(
+    hands: &[HandHistory],
+    tournaments: &[TournamentSummary],
+    hero_id: &str,
+    period: Period,
+) -> Result<Vec<WinningsPeriod>, PokercraftLocalError> {
+    let chip_values = chip_values(hands, tournaments, hero_id);
+    let mut periods: BTreeMap<NaiveDate, WinningsPeriod> = BTreeMap::new();
+    fn period_of(
+        periods: &mut BTreeMap<NaiveDate, WinningsPeriod>,
+        start: NaiveDate,
+    ) -> &mut WinningsPeriod {
+        periods.entry(start).or_insert_with(|| WinningsPeriod {
+            start,
+            ..Default::default()
+        })
+    }
+
+    for tournament in tournaments.iter() {
+        let current = period_of(&mut periods, period.start_of(tournament.start_time));
+        current.tournaments_played += 1;
+        current.profit += tournament.profit();
+    }
+    for hand in hands.iter() {
+        let current = period_of(&mut periods, period.start_of(hand.datetime));
+        current.hands_played += 1;
+        current.chip_profit += hand.net_profit(hero_id);
+    }
+
+    let hands_by_id: HashMap<&str, &HandHistory> =
+        hands.iter().map(|hand| (hand.id.as_str(), hand)).collect();
+    for spot in find_all_in_spots(hands, hero_id).spots {
+        let hand = hands_by_id[spot.hand_id.as_str()];
+        let current = period_of(&mut periods, period.start_of(hand.datetime));
+        current.all_in_count += 1;
+        current.chip_luck += spot.luck_chips();
+        if let Some(value) = hand.tournament_id.and_then(|id| chip_values.get(&id)) {
+            current.luck += spot.luck_chips() * value;
+        }
+    }
+    Ok(periods.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_hands, parse_tournament_summary};
+
+    const SAMPLE_HANDS: &str = "Poker Hand #TM1: Tournament #1, Test Hold'em No Limit - Level1(100/200) - 2025/08/01 00:00:00
+Table '1' 2-max Seat #1 is the button
+Seat 1: Hero (1,000 in chips)
+Seat 2: aaaa (1,000 in chips)
+Hero: posts small blind 100
+aaaa: posts big blind 200
+*** HOLE CARDS ***
+Dealt to Hero [As Ad]
+Dealt to aaaa
+Hero: raises 800 to 1,000 and is all-in
+aaaa: calls 800 and is all-in
+*** FLOP *** [2c 7d 9h]
+*** TURN *** [2c 7d 9h] [Kd]
+*** RIVER *** [2c 7d 9h Kd] [3s]
+aaaa: shows [Kc Kh]
+Hero: shows [As Ad]
+*** SHOWDOWN ***
+aaaa collected 2,000 from pot
+*** SUMMARY ***
+
+Poker Hand #TM2: Tournament #2, Test Hold'em No Limit - Level1(100/200) - 2025/08/04 03:00:00
+Table '1' 2-max Seat #1 is the button
+Seat 1: Hero (1,000 in chips)
+Seat 2: aaaa (1,000 in chips)
+Hero: posts small blind 100
+aaaa: posts big blind 200
+*** HOLE CARDS ***
+Dealt to Hero [2s 7h]
+Hero: folds
+Uncalled bet (100) returned to aaaa
+*** SHOWDOWN ***
+aaaa collected 200 from pot
+*** SUMMARY ***
+";
+
+    const SAMPLE_SUMMARY: &str = "Tournament #1, Test, Hold'em No Limit
+Buy-in: $9 + $1
+2 Players
+Total Prize Pool: $18
+Tournament started 2025/08/01 00:00:00
+2nd : Hero, $0
+
+You received a total of $0
+";
+
+    #[test]
+    fn test_decompose_winnings() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HANDS.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let tournaments = vec![parse_tournament_summary(SAMPLE_SUMMARY, false)?.unwrap()];
+
+        let days = decompose_winnings(&hands, &tournaments, "Hero", Period::Day)?;
+        assert_eq!(days.len(), 2);
+        let first = &days[0];
+        assert_eq!(first.start, NaiveDate::from_ymd_opt(2025, 8, 1).unwrap());
+        assert_eq!((first.hands_played, first.tournaments_played), (1, 1));
+        assert_eq!(first.all_in_count, 1);
+        assert_eq!(first.chip_profit, -1000);
+        assert!((first.profit + 10.0).abs() < 1e-9);
+
+        // AA is about 82% against KK, on a pot of 2,000 chips worth $0.009 each
+        assert!(first.chip_luck < -1600.0 && first.chip_luck > -1700.0);
+        assert!((first.luck - first.chip_luck * 0.009).abs() < 1e-9);
+        assert!(first.expected_profit() > 4.0);
+        assert!(first.expected_chip_profit() > 600.0);
+
+        let second = &days[1];
+        assert_eq!(second.chip_profit, -100);
+        assert_eq!((second.all_in_count, second.luck), (0, 0.0));
+        assert_eq!(second.expected_chip_profit(), -100.0);
+
+        // 2025/08/01 is Friday and 2025/08/04 is Monday
+        let weeks = decompose_winnings(&hands, &tournaments, "Hero", Period::Week)?;
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(
+            weeks[0].start,
+            NaiveDate::from_ymd_opt(2025, 7, 28).unwrap()
+        );
+        let months = decompose_winnings(&hands, &tournaments, "Hero", Period::Month)?;
+        assert_eq!(months.len(), 1);
+        assert_eq!(months[0].hands_played, 2);
+        assert_eq!(months[0].chip_luck, first.chip_luck);
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+//! Distributions of finish positions in tournaments of any field size.
+//!
+//! Finish positions are normalized into percentiles in `(0, 1)`,
+//! where lower percentiles are better finishes. Percentiles of each band of
+//! field sizes are modeled as a Beta distribution, and the parameters are
+//! interpolated by the logarithm of the field size between bands.
+
+use rand::distributions::Distribution;
+use rand::Rng;
+use statrs::distribution::{Beta, ContinuousCDF};
+
+use crate::errors::PokercraftLocalError;
+use crate::parser::TournamentSummary;
+
+/// Get the finish percentile of the given rank in the given field size,
+/// at the middle of the rank; `1st of 100` is `0.005`.
+pub fn finish_percentile(rank: u32, field_size: u32) -> Result<f64, PokercraftLocalError> {
+    if rank == 0 || rank > field_size {
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "Invalid rank {} of {} players",
+            rank, field_size
+        )));
+    }
+    Ok((rank as f64 - 0.5) / field_size as f64)
+}
+
+/// Beta distribution parameters of finish percentiles around a field size.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct FinishBand {
+    /// Logarithm of the geometric mean of field sizes in this band.
+    pub log_field_size: f64,
+    pub alpha: f64,
+    pub beta: f64,
+    /// Number of tournaments in this band.
+    pub samples: usize,
+}
+
+/// A model of finish percentiles by field size, fitted from historical finishes.
+#[derive(PartialEq, Clone, Debug)]
+pub struct FinishModel {
+    /// Bands in ascending order of field sizes.
+    bands: Vec<FinishBand>,
+}
+
+impl FinishModel {
+    /// Fit a model from `(rank, field size)` of each tournament.
+    /// Field sizes are grouped into bands of powers of 2, like 64 to 127 players,
+    /// and bands with fewer than 2 tournaments or without variance are skipped.
+    pub fn fit(finishes: &[(u32, u32)]) -> Result<Self, PokercraftLocalError> {
+        let mut groups: Vec<(u32, Vec<(f64, f64)>)> = Vec::new();
+        for &(rank, field_size) in finishes.iter() {
+            let percentile = finish_percentile(rank, field_size)?;
+            let band = field_size.ilog2();
+            let sample = ((field_size as f64).ln(), percentile);
+            match groups.iter_mut().find(|(b, _)| *b == band) {
+                Some((_, samples)) => samples.push(sample),
+                None => groups.push((band, vec![sample])),
+            }
+        }
+        groups.sort_by_key(|(band, _)| *band);
+
+        let bands: Vec<FinishBand> = groups
+            .into_iter()
+            .filter_map(|(_, samples)| Self::fit_band(&samples))
+            .collect();
+        if bands.is_empty() {
+            return Err(PokercraftLocalError::GeneralError(
+                "Not enough finishes to fit a model".to_string(),
+            ));
+        }
+        Ok(FinishModel { bands })
+    }
+
+    /// Fit a model from finishes of the given tournaments.
+    pub fn from_tournaments<'a, I>(tournaments: I) -> Result<Self, PokercraftLocalError>
+    where
+        I: IntoIterator<Item = &'a TournamentSummary>,
+    {
+        let finishes: Vec<(u32, u32)> = tournaments
+            .into_iter()
+            .map(|tournament| (tournament.my_rank, tournament.total_players))
+            .collect();
+        Self::fit(&finishes)
+    }
+
+    /// Fit a Beta distribution with the method of moments,
+    /// from `(log field size, percentile)` of each tournament.
+    fn fit_band(samples: &[(f64, f64)]) -> Option<FinishBand> {
+        if samples.len() < 2 {
+            return None;
+        }
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(|(_, p)| p).sum::<f64>() / n;
+        let variance = samples.iter().map(|(_, p)| (p - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let common = mean * (1.0 - mean) / variance - 1.0;
+        if !(common > 0.0 && common.is_finite()) {
+            return None;
+        }
+        Some(FinishBand {
+            log_field_size: samples.iter().map(|(ln, _)| ln).sum::<f64>() / n,
+            alpha: mean * common,
+            beta: (1.0 - mean) * common,
+            samples: samples.len(),
+        })
+    }
+
+    /// Get fitted bands in ascending order of field sizes.
+    pub fn bands(&self) -> &[FinishBand] {
+        &self.bands
+    }
+
+    /// Get the Beta distribution of percentiles in the given field size.
+    /// Parameters are linearly interpolated by the logarithm of field sizes,
+    /// and field sizes out of fitted bands use the nearest band.
+    pub fn distribution(&self, field_size: u32) -> Result<Beta, PokercraftLocalError> {
+        if field_size == 0 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Field size should be positive".to_string(),
+            ));
+        }
+        let x = (field_size as f64).ln();
+        let upper = self.bands.partition_point(|band| band.log_field_size < x);
+        let (alpha, beta) = if upper == 0 {
+            (self.bands[0].alpha, self.bands[0].beta)
+        } else if upper == self.bands.len() {
+            let last = self.bands[upper - 1];
+            (last.alpha, last.beta)
+        } else {
+            let (lo, hi) = (self.bands[upper - 1], self.bands[upper]);
+            let t = (x - lo.log_field_size) / (hi.log_field_size - lo.log_field_size);
+            (
+                lo.alpha + (hi.alpha - lo.alpha) * t,
+                lo.beta + (hi.beta - lo.beta) * t,
+            )
+        };
+        Beta::new(alpha, beta).map_err(|e| PokercraftLocalError::GeneralError(e.to_string()))
+    }
+
+    /// Get the expected finish percentile in the given field size.
+    pub fn expected_percentile(&self, field_size: u32) -> Result<f64, PokercraftLocalError> {
+        let distribution = self.distribution(field_size)?;
+        Ok(distribution.shape_a() / (distribution.shape_a() + distribution.shape_b()))
+    }
+
+    /// Get the probability of finishing at each rank in the given field size,
+    /// where the `i`-th element is the probability of finishing at rank `i + 1`.
+    pub fn finish_probabilities(&self, field_size: u32) -> Result<Vec<f64>, PokercraftLocalError> {
+        let distribution = self.distribution(field_size)?;
+        let cdfs: Vec<f64> = (0..=field_size)
+            .map(|rank| distribution.cdf(rank as f64 / field_size as f64))
+            .collect();
+        Ok(cdfs.windows(2).map(|w| w[1] - w[0]).collect())
+    }
+
+    /// Sample a random finish rank in the given field size.
+    pub fn sample_rank<R: Rng>(
+        &self,
+        rng: &mut R,
+        field_size: u32,
+    ) -> Result<u32, PokercraftLocalError> {
+        let percentile = self.distribution(field_size)?.sample(rng);
+        Ok(((percentile * field_size as f64) as u32 + 1).min(field_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn test_finish_model() -> Result<(), PokercraftLocalError> {
+        assert_eq!(finish_percentile(1, 100)?, 0.005);
+        assert!(finish_percentile(0, 100).is_err());
+        assert!(finish_percentile(101, 100).is_err());
+
+        // Uniform finishes in 100-player fields, and top-heavy ones in 1000-player fields
+        let mut finishes: Vec<(u32, u32)> = (1..=100).map(|rank| (rank, 100)).collect();
+        finishes.extend((1..=100).map(|i| ((i * i / 10).max(1), 1000)));
+        let model = FinishModel::fit(&finishes)?;
+        assert_eq!(model.bands().len(), 2);
+        assert_eq!(model.bands()[0].samples, 100);
+        let uniform = model.bands()[0];
+        assert!((uniform.alpha - 1.0).abs() < 0.05 && (uniform.beta - 1.0).abs() < 0.05);
+
+        assert!((model.expected_percentile(100)? - 0.5).abs() < 1e-9);
+        assert!(model.expected_percentile(1000)? < 0.4);
+        // Interpolated between bands, and clamped out of bands
+        let middle = model.expected_percentile(300)?;
+        assert!(middle < model.expected_percentile(100)?);
+        assert!(middle > model.expected_percentile(1000)?);
+        assert!((model.expected_percentile(10)? - 0.5).abs() < 1e-9);
+
+        let probabilities = model.finish_probabilities(500)?;
+        assert_eq!(probabilities.len(), 500);
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(probabilities[0] > probabilities[499]);
+
+        let mut rng = StdRng::seed_from_u64(2145);
+        for _ in 0..1000 {
+            let rank = model.sample_rank(&mut rng, 500)?;
+            assert!((1..=500).contains(&rank));
+        }
+
+        assert!(model.finish_probabilities(0).is_err());
+        assert!(FinishModel::fit(&[(1, 100)]).is_err());
+        assert!(FinishModel::fit(&[(1, 100), (1, 100)]).is_err());
+        Ok(())
+    }
+}
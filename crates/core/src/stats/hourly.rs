@@ -0,0 +1,205 @@
+//! Hourly rate and volume of tournaments, overall and by playing windows.
+//!
+//! Each tournament is played from its start time to the last parsed hand of it,
+//! and overlapping tournaments (multi-tabling) count the same hour only once.
+
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+use crate::parser::{HandHistory, TournamentSummary};
+
+/// Results and time played of multiple tournaments.
+/// Money amounts are summed as-is, in the currencies of tournaments.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct HourlySummary {
+    pub tournaments: u64,
+    pub profit: f64,
+    /// Hours played, without counting overlapped time twice.
+    pub hours: f64,
+}
+
+impl HourlySummary {
+    /// Get the profit per hour played.
+    pub fn profit_per_hour(&self) -> Option<f64> {
+        (self.hours > 0.0).then(|| self.profit / self.hours)
+    }
+
+    /// Get the number of tournaments per hour played.
+    pub fn tournaments_per_hour(&self) -> Option<f64> {
+        (self.hours > 0.0).then(|| self.tournaments as f64 / self.hours)
+    }
+}
+
+/// Hourly rates, overall and broken down by the start time of tournaments.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct HourlyReport {
+    pub overall: HourlySummary,
+    /// Summaries of each weekday, from Monday to Sunday.
+    pub by_weekday: [HourlySummary; 7],
+    /// Summaries of each hour of the day, from 0 to 23.
+    pub by_hour: [HourlySummary; 24],
+    /// Number of tournaments skipped since none of their hands were given.
+    pub untimed: u64,
+}
+
+/// Accumulates tournaments and their playing intervals.
+#[derive(Default)]
+struct Bucket {
+    tournaments: u64,
+    profit: f64,
+    intervals: Vec<(NaiveDateTime, NaiveDateTime)>,
+}
+
+impl Bucket {
+    fn add(&mut self, tournament: &TournamentSummary, end: NaiveDateTime) {
+        self.tournaments += 1;
+        self.profit += tournament.profit();
+        self.intervals.push((tournament.start_time, end));
+    }
+
+    /// Summarize with the total length of the union of intervals.
+    fn summarize(mut self) -> HourlySummary {
+        self.intervals.sort();
+        let mut seconds = 0;
+        let mut current: Option<(NaiveDateTime, NaiveDateTime)> = None;
+        for (start, end) in self.intervals {
+            current = match current {
+                Some((s, e)) if start <= e => Some((s, e.max(end))),
+                Some((s, e)) => {
+                    seconds += (e - s).num_seconds();
+                    Some((start, end))
+                }
+                None => Some((start, end)),
+            };
+        }
+        if let Some((s, e)) = current {
+            seconds += (e - s).num_seconds();
+        }
+        HourlySummary {
+            tournaments: self.tournaments,
+            profit: self.profit,
+            hours: seconds as f64 / 3600.0,
+        }
+    }
+}
+
+/// Analyze hourly rates of the given tournaments.
+/// Given hands are matched to tournaments by tournament IDs,
+/// to find when the hero busted or won each tournament.
+pub fn analyze_hourly<'a, I>(tournaments: I, hands: &[HandHistory]) -> HourlyReport
+where
+    I: IntoIterator<Item = &'a TournamentSummary>,
+{
+    let mut last_hands: HashMap<u64, NaiveDateTime> = HashMap::new();
+    for hand in hands.iter() {
+        if let Some(tournament_id) = hand.tournament_id {
+            let last = last_hands.entry(tournament_id).or_insert(hand.datetime);
+            *last = (*last).max(hand.datetime);
+        }
+    }
+
+    let mut untimed = 0;
+    let mut overall = Bucket::default();
+    let mut by_weekday: [Bucket; 7] = Default::default();
+    let mut by_hour: [Bucket; 24] = Default::default();
+    for tournament in tournaments {
+        let Some(&end) = last_hands.get(&tournament.id) else {
+            untimed += 1;
+            continue;
+        };
+        let end = end.max(tournament.start_time);
+        overall.add(tournament, end);
+        by_weekday[tournament.start_time.weekday().num_days_from_monday() as usize]
+            .add(tournament, end);
+        by_hour[tournament.start_time.hour() as usize].add(tournament, end);
+    }
+
+    HourlyReport {
+        overall: overall.summarize(),
+        by_weekday: by_weekday.map(Bucket::summarize),
+        by_hour: by_hour.map(Bucket::summarize),
+        untimed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_hands, parse_tournament_summary};
+
+    fn summary(id: u64, start: &str, prize: &str) -> TournamentSummary {
+        let text = format!(
+            "Tournament #{id}, Test, Hold'em No Limit
+Buy-in: $9 + $1
+100 Players
+Total Prize Pool: $1,000
+Tournament started {start}
+5th : Hero, {prize}
+
+You received a total of {prize}
+"
+        );
+        parse_tournament_summary(&text, false).unwrap().unwrap()
+    }
+
+    fn hand(tournament_id: u64, datetime: &str) -> String {
+        format!(
+            "Poker Hand #TM{tournament_id}: Tournament #{tournament_id}, Test Hold'em No Limit - Level1(100/200) - {datetime}
+Table '1' 2-max Seat #1 is the button
+Seat 1: Hero (1,000 in chips)
+Seat 2: aaaa (1,000 in chips)
+Hero: posts small blind 100
+aaaa: posts big blind 200
+*** HOLE CARDS ***
+Dealt to Hero [2s 7h]
+Hero: folds
+Uncalled bet (100) returned to aaaa
+*** SHOWDOWN ***
+aaaa collected 200 from pot
+*** SUMMARY ***
+
+"
+        )
+    }
+
+    #[test]
+    fn test_hourly_rates() -> Result<(), crate::errors::PokercraftLocalError> {
+        // Friday 20:00 to 22:00 and 21:00 to 23:00 overlap, then Saturday 20:00 to 21:30
+        let tournaments = vec![
+            summary(1, "2025/08/01 20:00:00", "$70"),
+            summary(2, "2025/08/01 21:00:00", "$0"),
+            summary(3, "2025/08/02 20:00:00", "$0"),
+            summary(4, "2025/08/03 20:00:00", "$0"),
+        ];
+        let text = [
+            hand(1, "2025/08/01 21:00:00"),
+            hand(1, "2025/08/01 22:00:00"),
+            hand(2, "2025/08/01 23:00:00"),
+            hand(3, "2025/08/02 21:30:00"),
+        ]
+        .concat();
+        let hands = parse_hands(text.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+
+        let report = analyze_hourly(&tournaments, &hands);
+        assert_eq!(report.untimed, 1);
+        assert_eq!(report.overall.tournaments, 3);
+        assert!((report.overall.profit - 40.0).abs() < 1e-9);
+        assert!((report.overall.hours - 4.5).abs() < 1e-9);
+        assert!((report.overall.profit_per_hour().unwrap() - 40.0 / 4.5).abs() < 1e-9);
+        assert!((report.overall.tournaments_per_hour().unwrap() - 3.0 / 4.5).abs() < 1e-9);
+
+        let friday = &report.by_weekday[4];
+        assert_eq!(friday.tournaments, 2);
+        assert!((friday.hours - 3.0).abs() < 1e-9);
+        assert!((friday.profit_per_hour().unwrap() - 50.0 / 3.0).abs() < 1e-9);
+        assert!((report.by_weekday[5].hours - 1.5).abs() < 1e-9);
+        assert_eq!(report.by_weekday[0], HourlySummary::default());
+        assert_eq!(report.by_weekday[0].profit_per_hour(), None);
+
+        assert_eq!(report.by_hour[20].tournaments, 2);
+        assert!((report.by_hour[20].hours - 3.5).abs() < 1e-9);
+        assert_eq!(report.by_hour[21].tournaments, 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,617 @@
+//! HUD statistics engine (VPIP, PFR, 3-bet, c-bet, WTSD, W$SD).
+
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+use super::position::{Position, StackDepthBand, DEFAULT_STACK_DEPTH_BOUNDARIES};
+use crate::parser::{BetActionType, HandHistory, HandStage};
+
+/// Counts of opportunities and attempts of a single stat.
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct StatCounter {
+    pub opportunities: u64,
+    pub attempts: u64,
+}
+
+impl StatCounter {
+    /// Record a single opportunity.
+    pub fn add(&mut self, attempted: bool) {
+        self.opportunities += 1;
+        if attempted {
+            self.attempts += 1;
+        }
+    }
+
+    /// Merge another counter into this counter.
+    pub fn merge(&mut self, other: &StatCounter) {
+        self.opportunities += other.opportunities;
+        self.attempts += other.attempts;
+    }
+
+    /// Get the ratio of attempts over opportunities.
+    /// Returns `None` if there is no opportunity.
+    pub fn ratio(&self) -> Option<f64> {
+        if self.opportunities == 0 {
+            None
+        } else {
+            Some(self.attempts as f64 / self.opportunities as f64)
+        }
+    }
+}
+
+/// Definition of a single stat; When does a player have an opportunity,
+/// and does the player attempt it.
+/// Implement this to define custom stats for `HudStatsEngine`.
+pub trait StatDefinition: Send + Sync {
+    /// Name of this stat, used as the key of results.
+    fn name(&self) -> &str;
+
+    /// Evaluate this stat for the given player in the given hand.
+    /// Returns `None` if the player had no opportunity,
+    /// otherwise returns whether the player attempted it.
+    fn evaluate(&self, hand: &HandHistory, player_id: &str) -> Option<bool>;
+}
+
+/// Standard HUD stats.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum HudStat {
+    /// Voluntarily put money in pot; Opportunities are hands
+    /// where the player made any preflop decision.
+    Vpip,
+    /// Preflop raise; Same opportunities as VPIP.
+    Pfr,
+    /// Re-raise preflop when facing exactly one raise.
+    ThreeBet,
+    /// Fold preflop after raising first in and facing a 3-bet.
+    FoldToThreeBet,
+    /// Bet the flop as the last preflop raiser, when checked to.
+    ContinuationBet,
+    /// Went to showdown after seeing the flop.
+    WentToShowdown,
+    /// Won money at showdown.
+    WonAtShowdown,
+}
+
+impl HudStat {
+    /// Return all standard HUD stats.
+    pub const fn all() -> [HudStat; 7] {
+        [
+            HudStat::Vpip,
+            HudStat::Pfr,
+            HudStat::ThreeBet,
+            HudStat::FoldToThreeBet,
+            HudStat::ContinuationBet,
+            HudStat::WentToShowdown,
+            HudStat::WonAtShowdown,
+        ]
+    }
+}
+
+/// Check if the given player folded at any street.
+fn has_folded(hand: &HandHistory, player_id: &str) -> bool {
+    HandStage::all().iter().any(|&stage| {
+        hand.actions(stage)
+            .iter()
+            .any(|action| action.player_id == player_id && action.action == BetActionType::Fold)
+    })
+}
+
+/// Check if the given player saw the flop.
+fn saw_flop(hand: &HandHistory, player_id: &str) -> bool {
+    hand.community_cards.len() >= 3
+        && !hand
+            .actions_preflop
+            .iter()
+            .any(|action| action.player_id == player_id && action.action == BetActionType::Fold)
+}
+
+/// Check if the given player went to showdown.
+fn went_to_showdown(hand: &HandHistory, player_id: &str) -> bool {
+    let remaining = hand
+        .seats
+        .values()
+        .filter(|(pid, _)| !has_folded(hand, pid))
+        .count();
+    remaining >= 2 && hand.seat_number(player_id).is_ok() && !has_folded(hand, player_id)
+}
+
+/// Check if the given action is aggressive (bet or raise).
+fn is_aggressive(action: BetActionType) -> bool {
+    matches!(action, BetActionType::Bet | BetActionType::Raise)
+}
+
+impl StatDefinition for HudStat {
+    fn name(&self) -> &str {
+        match self {
+            HudStat::Vpip => "VPIP",
+            HudStat::Pfr => "PFR",
+            HudStat::ThreeBet => "3Bet",
+            HudStat::FoldToThreeBet => "Fold3Bet",
+            HudStat::ContinuationBet => "CBet",
+            HudStat::WentToShowdown => "WTSD",
+            HudStat::WonAtShowdown => "W$SD",
+        }
+    }
+
+    fn evaluate(&self, hand: &HandHistory, player_id: &str) -> Option<bool> {
+        let mut voluntary = hand
+            .actions_preflop
+            .iter()
+            .filter(|action| !matches!(action.action, BetActionType::Ante | BetActionType::Blind));
+        match self {
+            HudStat::Vpip | HudStat::Pfr => {
+                let mut decided = false;
+                let mut attempted = false;
+                for action in voluntary.filter(|action| action.player_id == player_id) {
+                    decided = true;
+                    attempted |= match self {
+                        HudStat::Vpip => {
+                            action.action != BetActionType::Fold
+                                && action.action != BetActionType::Check
+                        }
+                        _ => is_aggressive(action.action),
+                    };
+                }
+                decided.then_some(attempted)
+            }
+            HudStat::ThreeBet => {
+                let mut raises = 0;
+                for action in voluntary.by_ref() {
+                    if action.player_id == player_id && raises == 1 {
+                        return Some(is_aggressive(action.action));
+                    } else if is_aggressive(action.action) {
+                        raises += 1;
+                    }
+                }
+                None
+            }
+            HudStat::FoldToThreeBet => {
+                let mut raises = 0;
+                let mut opened = false;
+                for action in voluntary.by_ref() {
+                    if action.player_id == player_id {
+                        if opened && raises == 2 {
+                            return Some(action.action == BetActionType::Fold);
+                        }
+                        opened |= raises == 0 && is_aggressive(action.action);
+                    }
+                    if is_aggressive(action.action) {
+                        raises += 1;
+                    }
+                }
+                None
+            }
+            HudStat::ContinuationBet => {
+                let last_raiser = voluntary
+                    .rev()
+                    .find(|action| is_aggressive(action.action))
+                    .map(|action| action.player_id.as_str());
+                if last_raiser != Some(player_id) || !saw_flop(hand, player_id) {
+                    return None;
+                }
+                for action in hand.actions_flop.iter() {
+                    if action.player_id == player_id {
+                        return Some(action.action == BetActionType::Bet);
+                    } else if is_aggressive(action.action) {
+                        return None; // Facing a donk bet
+                    }
+                }
+                None
+            }
+            HudStat::WentToShowdown => {
+                saw_flop(hand, player_id).then(|| went_to_showdown(hand, player_id))
+            }
+            HudStat::WonAtShowdown => went_to_showdown(hand, player_id)
+                .then(|| hand.wons.get(player_id).copied().unwrap_or(0) > 0),
+        }
+    }
+}
+
+/// Accumulated stats of a single player.
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[derive(Clone, Debug, Default)]
+pub struct PlayerStats {
+    /// Number of hands dealt to the player.
+    pub hands: u64,
+    /// `{stat name: counter}`
+    pub stats: BTreeMap<String, StatCounter>,
+    /// `{(position, stack depth band): {stat name: counter}}`;
+    /// Hands where the position or the band can't be decided are not included.
+    /// Serialized as `{position: {band: {stat name: counter}}}`, like `{"BTN": {"20-40bb": ...}}`.
+    #[cfg_attr(feature = "wasm", serde(serialize_with = "serialize_breakdown"))]
+    pub breakdown: BTreeMap<(Position, StackDepthBand), BTreeMap<String, StatCounter>>,
+}
+
+/// Serialize breakdown buckets as nested maps, since tuple keys can't be object keys.
+#[cfg(feature = "wasm")]
+fn serialize_breakdown<S: serde::Serializer>(
+    breakdown: &BTreeMap<(Position, StackDepthBand), BTreeMap<String, StatCounter>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut nested: BTreeMap<String, BTreeMap<String, &BTreeMap<String, StatCounter>>> =
+        BTreeMap::new();
+    for ((position, band), stats) in breakdown.iter() {
+        nested
+            .entry(position.to_string())
+            .or_default()
+            .insert(band.to_string(), stats);
+    }
+    serde::Serialize::serialize(&nested, serializer)
+}
+
+impl PlayerStats {
+    /// Get the counter of the given stat name.
+    pub fn get(&self, name: &str) -> StatCounter {
+        self.stats.get(name).copied().unwrap_or_default()
+    }
+
+    /// Get the ratio of the given stat name, with its sample size (number of opportunities).
+    /// The ratio is `None` if the player had no opportunity.
+    pub fn ratio_with_sample(&self, name: &str) -> (Option<f64>, u64) {
+        let counter = self.get(name);
+        (counter.ratio(), counter.opportunities)
+    }
+
+    /// Merge stats of buckets matching the given filter.
+    fn merge_buckets<F>(&self, filter: F) -> BTreeMap<String, StatCounter>
+    where
+        F: Fn(Position, StackDepthBand) -> bool,
+    {
+        let mut merged: BTreeMap<String, StatCounter> = BTreeMap::new();
+        for ((position, band), stats) in self.breakdown.iter() {
+            if !filter(*position, *band) {
+                continue;
+            }
+            for (name, counter) in stats.iter() {
+                merged.entry(name.clone()).or_default().merge(counter);
+            }
+        }
+        merged
+    }
+
+    /// Get `{stat name: counter}` of hands played in the given position.
+    pub fn by_position(&self, position: Position) -> BTreeMap<String, StatCounter> {
+        self.merge_buckets(|pos, _| pos == position)
+    }
+
+    /// Get `{stat name: counter}` of hands played in the given stack depth band.
+    pub fn by_stack_depth(&self, band: StackDepthBand) -> BTreeMap<String, StatCounter> {
+        self.merge_buckets(|_, b| b == band)
+    }
+
+    /// Get the counter of the given stat name in the given position and stack depth band.
+    pub fn get_bucket(&self, name: &str, position: Position, band: StackDepthBand) -> StatCounter {
+        self.breakdown
+            .get(&(position, band))
+            .and_then(|stats| stats.get(name))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Computes HUD stats for every player from streams of hands.
+pub struct HudStatsEngine {
+    definitions: Vec<Box<dyn StatDefinition>>,
+    players: HashMap<String, PlayerStats>,
+    stack_depth_boundaries: Vec<u32>,
+}
+
+impl HudStatsEngine {
+    /// Create a new engine with all standard HUD stats.
+    pub fn new() -> Self {
+        Self::with_definitions(
+            HudStat::all()
+                .into_iter()
+                .map(|stat| Box::new(stat) as Box<dyn StatDefinition>)
+                .collect(),
+        )
+    }
+
+    /// Create a new engine with the given stat definitions only.
+    pub fn with_definitions(definitions: Vec<Box<dyn StatDefinition>>) -> Self {
+        HudStatsEngine {
+            definitions,
+            players: HashMap::new(),
+            stack_depth_boundaries: DEFAULT_STACK_DEPTH_BOUNDARIES.to_vec(),
+        }
+    }
+
+    /// Set ascending boundaries (in big blinds) of effective stack depth bands.
+    /// Should be called before adding any hand.
+    pub fn set_stack_depth_boundaries(&mut self, mut boundaries: Vec<u32>) {
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        self.stack_depth_boundaries = boundaries;
+    }
+
+    /// Add a stat definition. Hands added before are not re-evaluated.
+    pub fn add_definition(&mut self, definition: Box<dyn StatDefinition>) {
+        self.definitions.push(definition);
+    }
+
+    /// Accumulate stats of all players in the given hand.
+    pub fn add_hand(&mut self, hand: &HandHistory) {
+        for (player_id, _chips) in hand.seats.values() {
+            let bucket = match (
+                Position::of(hand, player_id),
+                StackDepthBand::of(hand, player_id, &self.stack_depth_boundaries),
+            ) {
+                (Ok(position), Ok(band)) => Some((position, band)),
+                _ => None,
+            };
+            let player_stats = self.players.entry(player_id.clone()).or_default();
+            player_stats.hands += 1;
+            for definition in self.definitions.iter() {
+                if let Some(attempted) = definition.evaluate(hand, player_id) {
+                    player_stats
+                        .stats
+                        .entry(definition.name().to_string())
+                        .or_default()
+                        .add(attempted);
+                    if let Some(bucket) = bucket {
+                        player_stats
+                            .breakdown
+                            .entry(bucket)
+                            .or_default()
+                            .entry(definition.name().to_string())
+                            .or_default()
+                            .add(attempted);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Accumulate stats of all given hands.
+    pub fn add_hands<'a, I>(&mut self, hands: I)
+    where
+        I: IntoIterator<Item = &'a HandHistory>,
+    {
+        for hand in hands {
+            self.add_hand(hand);
+        }
+    }
+
+    /// Get the stats of the given player.
+    pub fn player(&self, player_id: &str) -> Option<&PlayerStats> {
+        self.players.get(player_id)
+    }
+
+    /// Get the stats of all players.
+    pub fn players(&self) -> &HashMap<String, PlayerStats> {
+        &self.players
+    }
+
+    /// Get the stats of opponents of the hero who were dealt in at least `min_hands` hands,
+    /// ordered by the number of hands descending, so that the most reliable samples come first.
+    pub fn opponents(&self, hero: &str, min_hands: u64) -> Vec<(&str, &PlayerStats)> {
+        let mut opponents = self
+            .players
+            .iter()
+            .filter(|(player_id, stats)| *player_id != hero && stats.hands >= min_hands)
+            .map(|(player_id, stats)| (player_id.as_str(), stats))
+            .collect::<Vec<_>>();
+        opponents.sort_by(|(id1, stats1), (id2, stats2)| {
+            stats2.hands.cmp(&stats1.hands).then(id1.cmp(id2))
+        });
+        opponents
+    }
+}
+
+impl Default for HudStatsEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute standard HUD stats of all players from the hand history text (WASM interface).
+/// Returns an object of `{playerId: {hands, stats: {statName: {opportunities, attempts}}, breakdown}}`,
+/// where `breakdown` is `{position: {band: {statName: {opportunities, attempts}}}}`.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = computeHudStats)]
+pub fn compute_hud_stats_wasm(hand_history_text: &str) -> Result<JsValue, JsValue> {
+    let mut engine = HudStatsEngine::new();
+    for hand in crate::parser::parse_hands(hand_history_text.as_bytes()).flatten() {
+        engine.add_hand(&hand);
+    }
+    crate::utils::to_js_object(engine.players())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::PokercraftLocalError;
+    use crate::parser::parse_hands;
+
+    const SAMPLE_HANDS: &str = "Poker Hand #TM1: Tournament #1, Test Hold'em No Limit - Level1(100/200) - 2025/08/01 00:00:00
+Table '1' 4-max Seat #1 is the button
+Seat 1: Hero (10,000 in chips)
+Seat 2: aaaa (10,000 in chips)
+Seat 3: bbbb (10,000 in chips)
+Seat 4: cccc (10,000 in chips)
+aaaa: posts small blind 100
+bbbb: posts big blind 200
+*** HOLE CARDS ***
+Dealt to Hero [As Ks]
+cccc: raises 400 to 600
+Hero: raises 1,200 to 1,800
+aaaa: folds
+bbbb: folds
+cccc: calls 1,200
+*** FLOP *** [2c 7d 9h]
+cccc: checks
+Hero: bets 1,000
+cccc: calls 1,000
+*** TURN *** [2c 7d 9h] [Jd]
+cccc: checks
+Hero: checks
+*** RIVER *** [2c 7d 9h Jd] [3s]
+cccc: checks
+Hero: checks
+cccc: shows [Qd Qh]
+Hero: shows [As Ks]
+*** SHOWDOWN ***
+cccc collected 5,900 from pot
+*** SUMMARY ***
+
+Poker Hand #TM2: Tournament #1, Test Hold'em No Limit - Level1(100/200) - 2025/08/01 00:01:00
+Table '1' 4-max Seat #2 is the button
+Seat 1: Hero (7,200 in chips)
+Seat 2: aaaa (9,900 in chips)
+Seat 3: bbbb (9,800 in chips)
+Seat 4: cccc (13,100 in chips)
+bbbb: posts small blind 100
+cccc: posts big blind 200
+*** HOLE CARDS ***
+Dealt to Hero [2s 7h]
+Hero: folds
+aaaa: folds
+bbbb: folds
+*** SHOWDOWN ***
+cccc collected 300 from pot
+*** SUMMARY ***
+";
+
+    #[test]
+    fn test_hud_stats() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HANDS.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(hands.len(), 2);
+        let mut engine = HudStatsEngine::new();
+        engine.add_hands(&hands);
+
+        let hero = engine.player("Hero").unwrap();
+        assert_eq!(hero.hands, 2);
+        assert_eq!(
+            hero.get("VPIP"),
+            StatCounter {
+                opportunities: 2,
+                attempts: 1
+            }
+        );
+        assert_eq!(
+            hero.get("PFR"),
+            StatCounter {
+                opportunities: 2,
+                attempts: 1
+            }
+        );
+        assert_eq!(
+            hero.get("3Bet"),
+            StatCounter {
+                opportunities: 1,
+                attempts: 1
+            }
+        );
+        assert_eq!(
+            hero.get("CBet"),
+            StatCounter {
+                opportunities: 1,
+                attempts: 1
+            }
+        );
+        assert_eq!(
+            hero.get("WTSD"),
+            StatCounter {
+                opportunities: 1,
+                attempts: 1
+            }
+        );
+        assert_eq!(
+            hero.get("W$SD"),
+            StatCounter {
+                opportunities: 1,
+                attempts: 0
+            }
+        );
+
+        let cccc = engine.player("cccc").unwrap();
+        // Walk on the BB is not an opportunity
+        assert_eq!(
+            cccc.get("VPIP"),
+            StatCounter {
+                opportunities: 1,
+                attempts: 1
+            }
+        );
+        assert_eq!(cccc.get("3Bet").opportunities, 0);
+        assert_eq!(
+            cccc.get("Fold3Bet"),
+            StatCounter {
+                opportunities: 1,
+                attempts: 0
+            }
+        );
+        assert_eq!(hero.get("Fold3Bet").opportunities, 0);
+        assert_eq!(cccc.get("CBet").opportunities, 0);
+        assert_eq!(
+            cccc.get("W$SD"),
+            StatCounter {
+                opportunities: 1,
+                attempts: 1
+            }
+        );
+
+        // Facing two raises is not a 3-bet opportunity
+        let bbbb = engine.player("bbbb").unwrap();
+        assert_eq!(bbbb.get("3Bet").opportunities, 0);
+        assert_eq!(bbbb.get("WTSD").opportunities, 0);
+
+        // Opponents with their sample sizes
+        let opponents = engine.opponents("Hero", 1);
+        assert_eq!(
+            opponents.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec!["aaaa", "bbbb", "cccc"]
+        );
+        assert_eq!(cccc.ratio_with_sample("Fold3Bet"), (Some(0.0), 1));
+        assert_eq!(cccc.ratio_with_sample("3Bet"), (None, 0));
+        assert!(engine.opponents("Hero", 3).is_empty());
+
+        // Hero was 50bb deep on the button, then 36bb deep on the cutoff
+        let deep = StackDepthBand {
+            min_bb: 40,
+            max_bb: Some(60),
+        };
+        let shallow = StackDepthBand {
+            min_bb: 20,
+            max_bb: Some(40),
+        };
+        assert_eq!(hero.get_bucket("PFR", Position::BTN, deep).attempts, 1);
+        assert_eq!(hero.get_bucket("PFR", Position::CO, deep).opportunities, 0);
+        assert_eq!(
+            hero.by_position(Position::CO)["VPIP"],
+            StatCounter {
+                opportunities: 1,
+                attempts: 0
+            }
+        );
+        assert_eq!(hero.by_stack_depth(shallow)["VPIP"].attempts, 0);
+        assert_eq!(hero.by_stack_depth(deep)["VPIP"].attempts, 1);
+        assert_eq!(
+            hands[0].offset_from_button("cccc")?,
+            -1,
+            "Seat 4 is the cutoff of 4-max table"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_serialize_breakdown() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HANDS.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let mut engine = HudStatsEngine::new();
+        for hand in hands.iter() {
+            engine.add_hand(hand);
+        }
+        let hero = serde_json::to_value(engine.player("Hero").unwrap())
+            .map_err(|e| PokercraftLocalError::GeneralError(e.to_string()))?;
+        assert_eq!(hero["breakdown"]["BTN"]["40-60bb"]["PFR"]["attempts"], 1);
+        assert_eq!(hero["breakdown"]["CO"]["20-40bb"]["VPIP"]["attempts"], 0);
+        assert!(hero["breakdown"]["BB"].is_null());
+        Ok(())
+    }
+}
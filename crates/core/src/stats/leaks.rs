@@ -0,0 +1,257 @@
+//! Rule-based leak detection, comparing HUD stats against baseline ranges.
+
+use super::hud::{PlayerStats, StatCounter};
+use super::position::Position;
+
+/// Default minimum number of opportunities to judge a stat.
+pub const DEFAULT_MIN_SAMPLE: u64 = 30;
+
+/// Baseline range of a single stat, optionally in a single position.
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct LeakRule {
+    /// Stat name, like `"VPIP"`.
+    pub stat: String,
+    /// Position to check; `None` checks the stat over all positions.
+    pub position: Option<Position>,
+    /// Lowest healthy ratio, like `0.15` for 15%.
+    pub min: f64,
+    /// Highest healthy ratio.
+    pub max: f64,
+    /// Stats with fewer opportunities than this are not judged.
+    pub min_sample: u64,
+}
+
+impl LeakRule {
+    /// Create a new rule over all positions with the default minimum sample.
+    pub fn new(stat: &str, min: f64, max: f64) -> Self {
+        LeakRule {
+            stat: stat.to_string(),
+            position: None,
+            min,
+            max,
+            min_sample: DEFAULT_MIN_SAMPLE,
+        }
+    }
+
+    /// Restrict this rule to the given position.
+    pub fn in_position(mut self, position: Position) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Set the minimum number of opportunities to judge.
+    pub fn with_min_sample(mut self, min_sample: u64) -> Self {
+        self.min_sample = min_sample;
+        self
+    }
+
+    /// Check this rule against the given player's stats.
+    /// Returns `None` if the sample is too small or the stat is in range.
+    pub fn check(&self, stats: &PlayerStats) -> Option<Leak> {
+        let counter: StatCounter = match self.position {
+            Some(position) => stats
+                .by_position(position)
+                .get(&self.stat)
+                .copied()
+                .unwrap_or_default(),
+            None => stats.get(&self.stat),
+        };
+        if counter.opportunities < self.min_sample {
+            return None;
+        }
+        let value = counter.ratio()?;
+        let direction = if value < self.min {
+            LeakDirection::TooLow
+        } else if value > self.max {
+            LeakDirection::TooHigh
+        } else {
+            return None;
+        };
+        Some(Leak {
+            stat: self.stat.clone(),
+            position: self.position,
+            direction,
+            value,
+            sample: counter.opportunities,
+            expected_min: self.min,
+            expected_max: self.max,
+        })
+    }
+}
+
+/// Whether a stat is below or above its baseline range.
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum LeakDirection {
+    TooLow,
+    TooHigh,
+}
+
+/// A single likely leak found by a rule.
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct Leak {
+    pub stat: String,
+    pub position: Option<Position>,
+    pub direction: LeakDirection,
+    /// Observed ratio of the stat.
+    pub value: f64,
+    /// Number of opportunities of the stat.
+    pub sample: u64,
+    pub expected_min: f64,
+    pub expected_max: f64,
+}
+
+impl Leak {
+    /// Get the distance from the baseline range.
+    pub fn deviation(&self) -> f64 {
+        match self.direction {
+            LeakDirection::TooLow => self.expected_min - self.value,
+            LeakDirection::TooHigh => self.value - self.expected_max,
+        }
+    }
+}
+
+impl std::fmt::Display for Leak {
+    /// Formats like `"Fold3Bet 72.0% from BTN, sample 400 (expected 30.0%-60.0%)"`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {:.1}%", self.stat, self.value * 100.0)?;
+        if let Some(position) = self.position {
+            write!(f, " from {}", position)?;
+        }
+        write!(
+            f,
+            ", sample {} (expected {:.1}%-{:.1}%)",
+            self.sample,
+            self.expected_min * 100.0,
+            self.expected_max * 100.0
+        )
+    }
+}
+
+/// Report of likely leaks of a single player.
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct LeakReport {
+    pub player_id: String,
+    /// Number of hands the stats are computed from.
+    pub hands: u64,
+    /// Leaks sorted by deviation, the biggest first.
+    pub leaks: Vec<Leak>,
+}
+
+/// Checks players' stats against configurable baseline ranges.
+#[derive(Clone, Debug)]
+pub struct LeakDetector {
+    rules: Vec<LeakRule>,
+}
+
+impl LeakDetector {
+    /// Create a new detector with default baselines
+    /// of a solid tournament regular.
+    pub fn new() -> Self {
+        Self::with_rules(vec![
+            LeakRule::new("VPIP", 0.15, 0.35),
+            LeakRule::new("PFR", 0.10, 0.28),
+            LeakRule::new("3Bet", 0.04, 0.14),
+            LeakRule::new("Fold3Bet", 0.35, 0.65),
+            LeakRule::new("CBet", 0.45, 0.80),
+            LeakRule::new("WTSD", 0.22, 0.35),
+            LeakRule::new("W$SD", 0.45, 1.0),
+            LeakRule::new("VPIP", 0.08, 0.25).in_position(Position::EP),
+            LeakRule::new("PFR", 0.30, 0.60).in_position(Position::BTN),
+            LeakRule::new("Fold3Bet", 0.30, 0.60).in_position(Position::BTN),
+            LeakRule::new("VPIP", 0.0, 0.45).in_position(Position::SB),
+        ])
+    }
+
+    /// Create a new detector with the given rules only.
+    pub fn with_rules(rules: Vec<LeakRule>) -> Self {
+        LeakDetector { rules }
+    }
+
+    /// Add a rule.
+    pub fn add_rule(&mut self, rule: LeakRule) {
+        self.rules.push(rule);
+    }
+
+    /// Get all rules.
+    pub fn rules(&self) -> &[LeakRule] {
+        &self.rules
+    }
+
+    /// Check all rules against the given player's stats.
+    pub fn detect(&self, player_id: &str, stats: &PlayerStats) -> LeakReport {
+        let mut leaks: Vec<Leak> = self
+            .rules
+            .iter()
+            .filter_map(|rule| rule.check(stats))
+            .collect();
+        leaks.sort_by(|a, b| b.deviation().total_cmp(&a.deviation()));
+        LeakReport {
+            player_id: player_id.to_string(),
+            hands: stats.hands,
+            leaks,
+        }
+    }
+}
+
+impl Default for LeakDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::stats::StackDepthBand;
+
+    fn counter(opportunities: u64, attempts: u64) -> StatCounter {
+        StatCounter {
+            opportunities,
+            attempts,
+        }
+    }
+
+    #[test]
+    fn test_leak_detection() {
+        let band = StackDepthBand {
+            min_bb: 20,
+            max_bb: Some(40),
+        };
+        let stats = PlayerStats {
+            hands: 1000,
+            stats: BTreeMap::from([
+                ("VPIP".to_string(), counter(1000, 250)),
+                ("PFR".to_string(), counter(1000, 50)),
+                ("3Bet".to_string(), counter(10, 0)),
+                ("Fold3Bet".to_string(), counter(500, 300)),
+            ]),
+            breakdown: BTreeMap::from([(
+                (Position::BTN, band),
+                BTreeMap::from([("Fold3Bet".to_string(), counter(400, 288))]),
+            )]),
+        };
+
+        let report = LeakDetector::new().detect("Hero", &stats);
+        assert_eq!(report.hands, 1000);
+        assert_eq!(report.leaks.len(), 2, "3Bet sample is too small");
+        assert_eq!(report.leaks[0].stat, "Fold3Bet");
+        assert_eq!(report.leaks[0].direction, LeakDirection::TooHigh);
+        assert_eq!(
+            report.leaks[0].to_string(),
+            "Fold3Bet 72.0% from BTN, sample 400 (expected 30.0%-60.0%)"
+        );
+        assert_eq!(report.leaks[1].stat, "PFR");
+        assert_eq!(report.leaks[1].direction, LeakDirection::TooLow);
+        assert!((report.leaks[1].deviation() - 0.05).abs() < 1e-9);
+
+        let custom =
+            LeakDetector::with_rules(vec![LeakRule::new("3Bet", 0.04, 0.14).with_min_sample(10)]);
+        assert_eq!(custom.detect("Hero", &stats).leaks[0].value, 0.0);
+    }
+}
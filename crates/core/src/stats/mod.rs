@@ -0,0 +1,33 @@
+//! Statistics computed from parsed hand histories and tournament summaries.
+
+pub mod all_in;
+pub mod bootstrap;
+pub mod ev_adjusted;
+pub mod finish;
+pub mod hourly;
+pub mod hud;
+pub mod leaks;
+pub mod position;
+pub mod rake;
+pub mod session;
+pub mod tournament;
+pub mod win_rate;
+
+pub use all_in::AllInSpot;
+pub use bootstrap::{bootstrap, ConfidenceInterval};
+pub use ev_adjusted::{decompose_winnings, Period, WinningsPeriod};
+pub use finish::{finish_percentile, FinishBand, FinishModel};
+pub use hourly::{analyze_hourly, HourlyReport, HourlySummary};
+pub use hud::{HudStat, HudStatsEngine, PlayerStats, StatCounter, StatDefinition};
+pub use leaks::{Leak, LeakDetector, LeakDirection, LeakReport, LeakRule};
+pub use position::{Position, StackDepthBand};
+pub use rake::{analyze_rake, RakeReport, RakeSummary, Stake};
+pub use session::{Session, SessionDetector, SessionSummary};
+pub use tournament::{
+    aggregate_tournaments, aggregate_tournaments_by_variant, aggregate_tournaments_in,
+    TournamentAggregate, TournamentVariant,
+};
+pub use win_rate::{
+    bb_per_100_interval, hand_bb_per_100_interval, roi_interval, tournament_roi_interval,
+    IntervalMethod,
+};
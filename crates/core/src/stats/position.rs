@@ -0,0 +1,148 @@
+//! Position and stack depth buckets for breaking down stats.
+
+use crate::errors::PokercraftLocalError;
+use crate::parser::HandHistory;
+
+/// Table position of a player in a single hand.
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum Position {
+    /// Early position; Every seat before MP.
+    EP,
+    /// Middle position; Two seats before the cutoff.
+    MP,
+    /// Cutoff.
+    CO,
+    /// Button.
+    BTN,
+    /// Small blind. Heads-up button is also SB.
+    SB,
+    /// Big blind.
+    BB,
+}
+
+impl Position {
+    /// Return all positions in the order of preflop action.
+    pub const fn all() -> [Position; 6] {
+        [
+            Position::EP,
+            Position::MP,
+            Position::CO,
+            Position::BTN,
+            Position::SB,
+            Position::BB,
+        ]
+    }
+
+    /// Get the position from the offset from the button.
+    /// See `HandHistory::offset_from_button` for the offset.
+    pub const fn from_offset(offset: i32) -> Self {
+        match offset {
+            2 => Position::BB,
+            1 => Position::SB,
+            0 => Position::BTN,
+            -1 => Position::CO,
+            -3..=-2 => Position::MP,
+            _ => Position::EP,
+        }
+    }
+
+    /// Get the position of the given player in the given hand.
+    pub fn of(hand: &HandHistory, player_id: &str) -> Result<Self, PokercraftLocalError> {
+        Ok(Self::from_offset(hand.offset_from_button(player_id)?))
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            Position::EP => "EP",
+            Position::MP => "MP",
+            Position::CO => "CO",
+            Position::BTN => "BTN",
+            Position::SB => "SB",
+            Position::BB => "BB",
+        };
+        write!(f, "{}", repr)
+    }
+}
+
+/// Default boundaries of stack depth bands, in big blinds.
+pub const DEFAULT_STACK_DEPTH_BOUNDARIES: [u32; 5] = [10, 20, 40, 60, 100];
+
+/// A band of effective stack depth in big blinds;
+/// `min_bb <= depth < max_bb`, where `None` of `max_bb` means unbounded.
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct StackDepthBand {
+    pub min_bb: u32,
+    pub max_bb: Option<u32>,
+}
+
+impl StackDepthBand {
+    /// Find the band of the given depth (in big blinds)
+    /// from ascending boundaries.
+    pub fn from_depth(depth_bb: f64, boundaries: &[u32]) -> Self {
+        let index = boundaries
+            .iter()
+            .take_while(|&&bound| bound as f64 <= depth_bb)
+            .count();
+        StackDepthBand {
+            min_bb: if index == 0 { 0 } else { boundaries[index - 1] },
+            max_bb: boundaries.get(index).copied(),
+        }
+    }
+
+    /// Get the band of the effective stack of the given player in the given hand.
+    pub fn of(
+        hand: &HandHistory,
+        player_id: &str,
+        boundaries: &[u32],
+    ) -> Result<Self, PokercraftLocalError> {
+        if hand.bb == 0 {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Big blind is zero in hand {}",
+                hand.id
+            )));
+        }
+        let depth_bb = hand.effective_stack(player_id)? as f64 / hand.bb as f64;
+        Ok(Self::from_depth(depth_bb, boundaries))
+    }
+
+    /// Check if the given depth (in big blinds) is in this band.
+    pub fn contains(&self, depth_bb: f64) -> bool {
+        self.min_bb as f64 <= depth_bb && self.max_bb.is_none_or(|max| depth_bb < max as f64)
+    }
+}
+
+impl std::fmt::Display for StackDepthBand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.max_bb {
+            Some(max_bb) => write!(f, "{}-{}bb", self.min_bb, max_bb),
+            None => write!(f, "{}bb+", self.min_bb),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions_and_bands() {
+        assert_eq!(Position::from_offset(0), Position::BTN);
+        assert_eq!(Position::from_offset(-3), Position::MP);
+        assert_eq!(Position::from_offset(-4), Position::EP);
+
+        let boundaries = DEFAULT_STACK_DEPTH_BOUNDARIES;
+        let shortest = StackDepthBand::from_depth(3.5, &boundaries);
+        assert_eq!(shortest.to_string(), "0-10bb");
+        let middle = StackDepthBand::from_depth(20.0, &boundaries);
+        assert_eq!(middle.to_string(), "20-40bb");
+        assert!(middle.contains(39.9) && !middle.contains(40.0));
+        assert_eq!(
+            StackDepthBand::from_depth(250.0, &boundaries).to_string(),
+            "100bb+"
+        );
+    }
+}
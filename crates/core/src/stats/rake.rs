@@ -0,0 +1,274 @@
+//! Rake and fee impact analysis of tournaments.
+//!
+//! Parsed hand histories are tournament hands, which carry no chip rake;
+//! Hands are only used to spread tournament fees over hands played.
+
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+use super::tournament::TournamentVariant;
+use crate::currency::Currency;
+use crate::parser::{HandHistory, TournamentSummary};
+
+/// Buy-in level of tournaments, including rake.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct Stake {
+    pub currency: Currency,
+    /// Buy-in including rake, in cents.
+    pub buy_in_cents: u64,
+}
+
+impl Stake {
+    /// Get the stake of the given tournament.
+    pub fn of(tournament: &TournamentSummary) -> Self {
+        Stake {
+            currency: tournament.currency,
+            buy_in_cents: (tournament.buy_in() * 100.0).round() as u64,
+        }
+    }
+
+    /// Get the buy-in including rake.
+    pub fn buy_in(&self) -> f64 {
+        self.buy_in_cents as f64 / 100.0
+    }
+}
+
+impl std::fmt::Display for Stake {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{:.2}", self.currency.symbol(), self.buy_in())
+    }
+}
+
+/// Rake paid and its impact on results of multiple tournaments.
+/// Money amounts are summed as-is, in the currencies of tournaments.
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct RakeSummary {
+    pub tournaments: u64,
+    /// Number of entries including re-entries.
+    pub entries: u64,
+    /// Total buy-ins including rake and re-entries.
+    pub total_buy_in: f64,
+    /// Total rake paid, including re-entries.
+    pub total_rake: f64,
+    /// Net profit after rake.
+    pub profit: f64,
+    /// Number of parsed hands played in these tournaments.
+    pub hands: u64,
+}
+
+impl RakeSummary {
+    /// Accumulate a single tournament, with the number of hands played in it.
+    pub fn add(&mut self, tournament: &TournamentSummary, hands: u64) {
+        let entries = tournament.my_entries as f64;
+        self.tournaments += 1;
+        self.entries += tournament.my_entries as u64;
+        self.total_buy_in += tournament.buy_in() * entries;
+        self.total_rake += tournament.rake * entries;
+        self.profit += tournament.profit();
+        self.hands += hands;
+    }
+
+    /// Get the net profit as if no rake was paid.
+    pub fn profit_before_rake(&self) -> f64 {
+        self.profit + self.total_rake
+    }
+
+    /// Get the ratio of rake over buy-ins.
+    pub fn rake_ratio(&self) -> Option<f64> {
+        (self.total_buy_in > 0.0).then(|| self.total_rake / self.total_buy_in)
+    }
+
+    /// Get the return on investment after rake.
+    pub fn roi(&self) -> Option<f64> {
+        (self.total_buy_in > 0.0).then(|| self.profit / self.total_buy_in)
+    }
+
+    /// Get the return on investment as if no rake was paid.
+    pub fn roi_before_rake(&self) -> Option<f64> {
+        let pure_buy_in = self.total_buy_in - self.total_rake;
+        (pure_buy_in > 0.0).then(|| self.profit_before_rake() / pure_buy_in)
+    }
+
+    /// Get the share of rake over profit before rake;
+    /// `0.4` means rake took 40% of what would have been won.
+    /// Returns `None` if nothing would have been won.
+    pub fn rake_over_profit(&self) -> Option<f64> {
+        let before = self.profit_before_rake();
+        (before > 0.0).then(|| self.total_rake / before)
+    }
+
+    /// Get the average rake paid per parsed hand.
+    pub fn rake_per_hand(&self) -> Option<f64> {
+        (self.hands > 0).then(|| self.total_rake / self.hands as f64)
+    }
+}
+
+/// Rake impact, overall and broken down by stake and game type.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct RakeReport {
+    pub overall: RakeSummary,
+    pub by_stake: BTreeMap<Stake, RakeSummary>,
+    pub by_variant: BTreeMap<TournamentVariant, RakeSummary>,
+}
+
+/// Analyze rake of the given tournaments.
+/// Given hands are matched to tournaments by tournament IDs.
+pub fn analyze_rake<'a, I>(tournaments: I, hands: &[HandHistory]) -> RakeReport
+where
+    I: IntoIterator<Item = &'a TournamentSummary>,
+{
+    let mut hand_counts: HashMap<u64, u64> = HashMap::new();
+    for tournament_id in hands.iter().filter_map(|hand| hand.tournament_id) {
+        *hand_counts.entry(tournament_id).or_insert(0) += 1;
+    }
+
+    let mut report = RakeReport::default();
+    for tournament in tournaments {
+        let hands = hand_counts.get(&tournament.id).copied().unwrap_or(0);
+        report.overall.add(tournament, hands);
+        report
+            .by_stake
+            .entry(Stake::of(tournament))
+            .or_default()
+            .add(tournament, hands);
+        report
+            .by_variant
+            .entry(TournamentVariant::from_name(&tournament.name))
+            .or_default()
+            .add(tournament, hands);
+    }
+    report
+}
+
+/// Analyze rake of tournaments from the given summary file contents (WASM interface).
+/// All money amounts are converted into `target_currency` (USD by default) with default rates.
+/// Returns an object of `{overall: summary, byStake: {stake: summary}, byVariant: {variant: summary}}`;
+/// Unparsable summaries are skipped.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = analyzeRake)]
+pub fn analyze_rake_wasm(
+    summary_texts: Vec<String>,
+    target_currency: Option<String>,
+) -> Result<JsValue, JsValue> {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Report {
+        overall: RakeSummary,
+        by_stake: BTreeMap<String, RakeSummary>,
+        by_variant: BTreeMap<String, RakeSummary>,
+    }
+
+    let currency = match target_currency {
+        Some(code) => code
+            .parse::<Currency>()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?,
+        None => Currency::USD,
+    };
+    let converter = crate::currency::CurrencyRateConverter::new();
+    let tournaments = summary_texts
+        .iter()
+        .filter_map(|text| {
+            crate::parser::parse_tournament_summary(text, false)
+                .ok()
+                .flatten()
+        })
+        .map(|tournament| tournament.converted(&converter, currency))
+        .collect::<Vec<_>>();
+    let rake_report = analyze_rake(&tournaments, &[]);
+    let report = Report {
+        overall: rake_report.overall,
+        by_stake: rake_report
+            .by_stake
+            .into_iter()
+            .map(|(stake, summary)| (stake.to_string(), summary))
+            .collect(),
+        by_variant: rake_report
+            .by_variant
+            .into_iter()
+            .map(|(variant, summary)| (variant.to_string(), summary))
+            .collect(),
+    };
+    crate::utils::to_js_object(&report)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::errors::PokercraftLocalError;
+    use crate::parser::hand_history::tests::SAMPLE_HAND;
+    use crate::parser::parse_hands;
+
+    fn tournament(
+        id: u64,
+        name: &str,
+        buy_in_pure: f64,
+        rake: f64,
+        prize: f64,
+    ) -> TournamentSummary {
+        TournamentSummary {
+            id,
+            name: name.to_string(),
+            currency: Currency::USD,
+            buy_in_pure,
+            rake,
+            total_prize_pool: 1000.0,
+            start_time: NaiveDate::from_ymd_opt(2025, 8, 1)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap(),
+            my_rank: 1,
+            total_players: 100,
+            my_prize: prize,
+            my_bounty: 0.0,
+            my_entries: 1,
+        }
+    }
+
+    #[test]
+    fn test_rake_report() {
+        let tournaments = vec![
+            tournament(1, "Daily Main", 9.0, 1.0, 30.0),
+            tournament(2, "Daily Main", 9.0, 1.0, 0.0),
+            tournament(3, "Bounty Hunters", 4.6, 0.4, 0.0),
+        ];
+        let report = analyze_rake(&tournaments, &[]);
+
+        let overall = &report.overall;
+        assert_eq!(overall.tournaments, 3);
+        assert!((overall.total_rake - 2.4).abs() < 1e-9);
+        assert!((overall.profit - 5.0).abs() < 1e-9);
+        assert!((overall.profit_before_rake() - 7.4).abs() < 1e-9);
+        assert!((overall.rake_ratio().unwrap() - 0.096).abs() < 1e-9);
+        assert!(overall.roi_before_rake().unwrap() > overall.roi().unwrap());
+        assert_eq!(overall.rake_per_hand(), None);
+
+        let ten = Stake {
+            currency: Currency::USD,
+            buy_in_cents: 1000,
+        };
+        assert_eq!(ten.to_string(), "$10.00");
+        assert_eq!(report.by_stake[&ten].tournaments, 2);
+        assert!((report.by_stake[&ten].rake_over_profit().unwrap() - 2.0 / 12.0).abs() < 1e-9);
+        assert_eq!(report.by_variant[&TournamentVariant::Bounty].tournaments, 1);
+        assert_eq!(
+            report.by_variant[&TournamentVariant::Bounty].rake_over_profit(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rake_per_hand() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HAND.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let report = analyze_rake(&[tournament(220597937, "Zodiac", 100.0, 10.0, 0.0)], &hands);
+        assert_eq!(report.overall.hands, 1);
+        assert_eq!(report.overall.rake_per_hand(), Some(10.0));
+        Ok(())
+    }
+}
@@ -0,0 +1,264 @@
+//! Grouping of hands and tournaments into playing sessions.
+
+use chrono::{Duration, NaiveDateTime};
+
+use super::all_in::find_all_in_spots;
+use crate::currency::{Currency, CurrencyRateConverter};
+use crate::equity::LuckCalculator;
+use crate::errors::PokercraftLocalError;
+use crate::parser::{HandHistory, TournamentSummary};
+
+/// Default idle gap which splits two sessions, in minutes.
+pub const DEFAULT_IDLE_GAP_MINUTES: i64 = 60;
+
+/// A single playing session; Hands and tournaments
+/// played without an idle gap longer than the configured one.
+#[derive(Clone, Debug)]
+pub struct Session<'a> {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    /// Hands in chronological order.
+    pub hands: Vec<&'a HandHistory>,
+    /// Tournaments registered in this session, in chronological order.
+    pub tournaments: Vec<&'a TournamentSummary>,
+}
+
+/// Summary of a single session.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionSummary {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub hands_played: usize,
+    pub tournaments_played: usize,
+    /// Currency of `profit`.
+    pub currency: Currency,
+    /// Sum of tournament profits, each converted into `currency` at the rate of its start date.
+    pub profit: f64,
+    /// Sum of net chip profits of the hero over all hands.
+    pub chip_profit: i64,
+    /// Number of all-in spots of the hero with known equity.
+    pub all_in_count: usize,
+    /// Luck score of the hero's all-in spots, if any.
+    pub all_in_luck: Option<f64>,
+}
+
+impl SessionSummary {
+    /// Get the duration of the session.
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+impl Session<'_> {
+    /// Summarize this session for the given hero,
+    /// with tournament profits converted into the given currency.
+    pub fn summarize(
+        &self,
+        hero_id: &str,
+        converter: &CurrencyRateConverter,
+        currency: Currency,
+    ) -> Result<SessionSummary, PokercraftLocalError> {
+        let mut luck_calculator = LuckCalculator::new();
+        let spots = find_all_in_spots(self.hands.clone(), hero_id).spots;
+        for spot in spots.iter() {
+            luck_calculator.add_result(spot.equity, spot.actual)?;
+        }
+
+        Ok(SessionSummary {
+            start: self.start,
+            end: self.end,
+            hands_played: self.hands.len(),
+            tournaments_played: self.tournaments.len(),
+            currency,
+            profit: self
+                .tournaments
+                .iter()
+                .map(|t| t.converted(converter, currency).profit())
+                .sum(),
+            chip_profit: self.hands.iter().map(|h| h.net_profit(hero_id)).sum(),
+            all_in_count: spots.len(),
+            all_in_luck: luck_calculator.luck_score(),
+        })
+    }
+}
+
+/// A hand or a tournament, placed into a session by its time.
+#[derive(Clone, Copy, Debug)]
+enum SessionEvent<'a> {
+    Hand(&'a HandHistory),
+    Tournament(&'a TournamentSummary),
+}
+
+impl SessionEvent<'_> {
+    fn datetime(&self) -> NaiveDateTime {
+        match self {
+            SessionEvent::Hand(hand) => hand.datetime,
+            SessionEvent::Tournament(tournament) => tournament.start_time,
+        }
+    }
+}
+
+/// Splits hands and tournaments into sessions by idle gaps.
+#[derive(Clone, Copy, Debug)]
+pub struct SessionDetector {
+    idle_gap: Duration,
+}
+
+impl SessionDetector {
+    /// Create a new detector; A new session starts
+    /// when nothing happened for longer than `idle_gap`.
+    pub fn new(idle_gap: Duration) -> Self {
+        SessionDetector { idle_gap }
+    }
+
+    /// Group the given hands and tournaments into sessions, in chronological order.
+    /// Tournaments are placed by their start times.
+    pub fn detect<'a>(
+        &self,
+        hands: &'a [HandHistory],
+        tournaments: &'a [TournamentSummary],
+    ) -> Vec<Session<'a>> {
+        let mut events: Vec<SessionEvent<'a>> = hands
+            .iter()
+            .map(SessionEvent::Hand)
+            .chain(tournaments.iter().map(SessionEvent::Tournament))
+            .collect();
+        events.sort_by_key(SessionEvent::datetime);
+
+        let mut sessions: Vec<Session<'a>> = Vec::new();
+        for event in events {
+            let datetime = event.datetime();
+            let current = match sessions.last_mut() {
+                Some(session) if datetime - session.end <= self.idle_gap => session,
+                _ => {
+                    sessions.push(Session {
+                        start: datetime,
+                        end: datetime,
+                        hands: Vec::new(),
+                        tournaments: Vec::new(),
+                    });
+                    sessions.last_mut().unwrap()
+                }
+            };
+            current.end = datetime;
+            match event {
+                SessionEvent::Hand(hand) => current.hands.push(hand),
+                SessionEvent::Tournament(tournament) => current.tournaments.push(tournament),
+            }
+        }
+        sessions
+    }
+}
+
+impl Default for SessionDetector {
+    fn default() -> Self {
+        Self::new(Duration::minutes(DEFAULT_IDLE_GAP_MINUTES))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_hands, parse_tournament_summary};
+
+    const SAMPLE_HANDS: &str = "Poker Hand #TM1: Tournament #1, Test Hold'em No Limit - Level1(100/200) - 2025/08/01 00:00:00
+Table '1' 2-max Seat #1 is the button
+Seat 1: Hero (1,000 in chips)
+Seat 2: aaaa (1,000 in chips)
+Hero: posts small blind 100
+aaaa: posts big blind 200
+*** HOLE CARDS ***
+Dealt to Hero [As Ad]
+Dealt to aaaa
+Hero: raises 800 to 1,000 and is all-in
+aaaa: calls 800 and is all-in
+*** FLOP *** [2c 7d 9h]
+*** TURN *** [2c 7d 9h] [Kd]
+*** RIVER *** [2c 7d 9h Kd] [3s]
+aaaa: shows [Kc Kh]
+Hero: shows [As Ad]
+*** SHOWDOWN ***
+aaaa collected 2,000 from pot
+*** SUMMARY ***
+
+Poker Hand #TM2: Tournament #2, Test Hold'em No Limit - Level1(100/200) - 2025/08/01 03:00:00
+Table '1' 2-max Seat #1 is the button
+Seat 1: Hero (1,000 in chips)
+Seat 2: aaaa (1,000 in chips)
+Hero: posts small blind 100
+aaaa: posts big blind 200
+*** HOLE CARDS ***
+Dealt to Hero [2s 7h]
+Hero: folds
+Uncalled bet (100) returned to aaaa
+*** SHOWDOWN ***
+aaaa collected 200 from pot
+*** SUMMARY ***
+";
+
+    #[test]
+    fn test_sessions() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HANDS.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let converter = CurrencyRateConverter::new();
+        let sessions = SessionDetector::default().detect(&hands, &[]);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].hands.len(), 1);
+
+        let first = sessions[0].summarize("Hero", &converter, Currency::USD)?;
+        assert_eq!(first.hands_played, 1);
+        assert_eq!(first.chip_profit, -1000);
+        assert_eq!(first.all_in_count, 1);
+        assert!(first.all_in_luck.unwrap() < 0.0);
+
+        let second = sessions[1].summarize("Hero", &converter, Currency::USD)?;
+        assert_eq!(second.chip_profit, -100);
+        assert_eq!(second.all_in_count, 0);
+        assert_eq!(second.all_in_luck, None);
+        assert_eq!(second.duration(), Duration::zero());
+
+        let merged = SessionDetector::new(Duration::hours(4)).detect(&hands, &[]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(
+            merged[0]
+                .summarize("Hero", &converter, Currency::USD)?
+                .duration(),
+            Duration::hours(3)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_profit_in_currency() -> Result<(), PokercraftLocalError> {
+        let hands = parse_hands(SAMPLE_HANDS.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        let tournament = parse_tournament_summary(
+            "Tournament #1, Daily Main $10, Hold'em No Limit
+Buy-in: $9 + $1
+100 Players
+Total Prize Pool: $1,000
+Tournament started 2025/08/01 00:30:00
+5th : Hero, $30
+
+You received a total of $30
+",
+            false,
+        )?
+        .unwrap();
+        let converter = CurrencyRateConverter::new();
+        let tournaments = [
+            tournament.clone(),
+            tournament.converted(&converter, Currency::CNY),
+        ];
+        let sessions = SessionDetector::default().detect(&hands, &tournaments);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].tournaments.len(), 2);
+        assert_eq!(sessions[0].end, tournament.start_time);
+
+        let summary = sessions[0].summarize("Hero", &converter, Currency::USD)?;
+        assert_eq!(summary.currency, Currency::USD);
+        assert!((summary.profit - 40.0).abs() < 1e-9);
+        let summary = sessions[0].summarize("Hero", &converter, Currency::CNY)?;
+        let rate = converter.usd_rate(Currency::CNY, Some(tournament.start_time.date()));
+        assert!((summary.profit - 40.0 * rate).abs() < 1e-6);
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+//! Aggregated metrics of tournament results (ROI, ITM, average buy-in, and more).
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+use crate::currency::{Currency, CurrencyRateConverter};
+use crate::parser::TournamentSummary;
+
+/// Tournament variants, classified by the tournament name.
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum TournamentVariant {
+    /// Mystery Battle Royale.
+    BattleRoyale,
+    /// Flip & Go; Everyone starts with an all-in flip.
+    FlipAndGo,
+    /// Bounty tournaments, including PKO and mystery bounties.
+    Bounty,
+    /// Satellites and step tournaments.
+    Satellite,
+    /// Any other regular freezeout or re-entry tournament.
+    Regular,
+}
+
+impl TournamentVariant {
+    /// Classify the given tournament name.
+    pub fn from_name(name: &str) -> Self {
+        let lowered = name.to_lowercase();
+        if lowered.contains("battle royale") {
+            TournamentVariant::BattleRoyale
+        } else if lowered.contains("flip & go") || lowered.contains("flip and go") {
+            TournamentVariant::FlipAndGo
+        } else if lowered.contains("bounty") || lowered.contains("pko") {
+            TournamentVariant::Bounty
+        } else if lowered.contains("satellite") || lowered.contains("step") {
+            TournamentVariant::Satellite
+        } else {
+            TournamentVariant::Regular
+        }
+    }
+}
+
+impl std::fmt::Display for TournamentVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            TournamentVariant::BattleRoyale => "Battle Royale",
+            TournamentVariant::FlipAndGo => "Flip & Go",
+            TournamentVariant::Bounty => "Bounty",
+            TournamentVariant::Satellite => "Satellite",
+            TournamentVariant::Regular => "Regular",
+        };
+        write!(f, "{}", repr)
+    }
+}
+
+/// Aggregated metrics of multiple tournaments.
+/// Money amounts are summed as-is, in the currencies of tournaments;
+/// Use `aggregate_tournaments_in` to report in a single currency.
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct TournamentAggregate {
+    /// Number of tournaments.
+    pub tournaments: u64,
+    /// Number of entries including re-entries.
+    pub entries: u64,
+    /// Total buy-ins including rake and re-entries.
+    pub total_buy_in: f64,
+    pub total_rake: f64,
+    /// Total prize including bounties.
+    pub total_prize: f64,
+    /// Part of `total_prize` won from bounties.
+    pub total_bounty: f64,
+    /// Number of tournaments where any bounty was won.
+    pub bounty_tournaments: u64,
+    /// Number of tournaments with positive prize excluding bounties.
+    pub itm_count: u64,
+    /// Best relative return with re-entries among paid tournaments.
+    pub best_rre: Option<f64>,
+    pub biggest_prize: f64,
+}
+
+impl TournamentAggregate {
+    /// Accumulate a single tournament.
+    pub fn add(&mut self, tournament: &TournamentSummary) {
+        self.tournaments += 1;
+        self.entries += tournament.my_entries as u64;
+        self.total_buy_in += tournament.buy_in() * tournament.my_entries as f64;
+        self.total_rake += tournament.rake * tournament.my_entries as f64;
+        self.total_prize += tournament.my_prize;
+        self.total_bounty += tournament.my_bounty;
+        if tournament.my_bounty > 0.0 {
+            self.bounty_tournaments += 1;
+        }
+        if tournament.regular_prize() > 0.0 {
+            self.itm_count += 1;
+        }
+        if let Some(rre) = tournament.rre() {
+            self.best_rre = Some(self.best_rre.map_or(rre, |best| best.max(rre)));
+        }
+        self.biggest_prize = self.biggest_prize.max(tournament.my_prize);
+    }
+
+    /// Get the net profit.
+    pub fn profit(&self) -> f64 {
+        self.total_prize - self.total_buy_in
+    }
+
+    /// Get the return on investment; `0.1` means 10% of profit over buy-ins.
+    /// Returns `None` if nothing was paid.
+    pub fn roi(&self) -> Option<f64> {
+        if self.total_buy_in > 0.0 {
+            Some(self.profit() / self.total_buy_in)
+        } else {
+            None
+        }
+    }
+
+    /// Get the ratio of tournaments finished in the money.
+    pub fn itm_ratio(&self) -> Option<f64> {
+        if self.tournaments > 0 {
+            Some(self.itm_count as f64 / self.tournaments as f64)
+        } else {
+            None
+        }
+    }
+
+    /// Get the average bounty winnings per tournament.
+    pub fn bounties_per_tournament(&self) -> Option<f64> {
+        if self.tournaments > 0 {
+            Some(self.total_bounty / self.tournaments as f64)
+        } else {
+            None
+        }
+    }
+
+    /// Get the ratio of bounties over all winnings.
+    /// Returns `None` if nothing was won.
+    pub fn bounty_ratio(&self) -> Option<f64> {
+        if self.total_prize > 0.0 {
+            Some(self.total_bounty / self.total_prize)
+        } else {
+            None
+        }
+    }
+
+    /// Get the average buy-in per entry.
+    pub fn average_buy_in(&self) -> Option<f64> {
+        if self.entries > 0 {
+            Some(self.total_buy_in / self.entries as f64)
+        } else {
+            None
+        }
+    }
+}
+
+/// Aggregate all given tournaments.
+pub fn aggregate_tournaments<'a, I>(tournaments: I) -> TournamentAggregate
+where
+    I: IntoIterator<Item = &'a TournamentSummary>,
+{
+    let mut aggregate = TournamentAggregate::default();
+    for tournament in tournaments {
+        aggregate.add(tournament);
+    }
+    aggregate
+}
+
+/// Aggregate all given tournaments in the given currency,
+/// converting each tournament at the rate of its start date.
+pub fn aggregate_tournaments_in<'a, I>(
+    tournaments: I,
+    converter: &CurrencyRateConverter,
+    currency: Currency,
+) -> TournamentAggregate
+where
+    I: IntoIterator<Item = &'a TournamentSummary>,
+{
+    let mut aggregate = TournamentAggregate::default();
+    for tournament in tournaments {
+        aggregate.add(&tournament.converted(converter, currency));
+    }
+    aggregate
+}
+
+/// Aggregate all given tournaments, grouped by variants.
+pub fn aggregate_tournaments_by_variant<'a, I>(
+    tournaments: I,
+) -> BTreeMap<TournamentVariant, TournamentAggregate>
+where
+    I: IntoIterator<Item = &'a TournamentSummary>,
+{
+    let mut aggregates: BTreeMap<TournamentVariant, TournamentAggregate> = BTreeMap::new();
+    for tournament in tournaments {
+        aggregates
+            .entry(TournamentVariant::from_name(&tournament.name))
+            .or_default()
+            .add(tournament);
+    }
+    aggregates
+}
+
+/// Aggregate tournaments from the given summary file contents (WASM interface).
+/// All money amounts are converted into `target_currency` (USD by default) with default rates.
+/// Returns an object of `{overall: aggregate, byVariant: {variant: aggregate}}`;
+/// Unparsable summaries and freerolls are skipped.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = aggregateTournaments)]
+pub fn aggregate_tournaments_wasm(
+    summary_texts: Vec<String>,
+    target_currency: Option<String>,
+) -> Result<JsValue, JsValue> {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Report {
+        overall: TournamentAggregate,
+        by_variant: BTreeMap<String, TournamentAggregate>,
+    }
+
+    let currency = match target_currency {
+        Some(code) => code
+            .parse::<Currency>()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?,
+        None => Currency::USD,
+    };
+    let converter = CurrencyRateConverter::new();
+    let tournaments = summary_texts
+        .iter()
+        .filter_map(|text| {
+            crate::parser::parse_tournament_summary(text, false)
+                .ok()
+                .flatten()
+        })
+        .map(|tournament| tournament.converted(&converter, currency))
+        .collect::<Vec<_>>();
+    let report = Report {
+        overall: aggregate_tournaments(&tournaments),
+        by_variant: aggregate_tournaments_by_variant(&tournaments)
+            .into_iter()
+            .map(|(variant, aggregate)| (variant.to_string(), aggregate))
+            .collect(),
+    };
+    crate::utils::to_js_object(&report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::PokercraftLocalError;
+    use crate::parser::parse_tournament_summary;
+
+    fn summary(name: &str, buy_in: &str, prize: &str, reentries: u32) -> String {
+        format!(
+            "Tournament #1, {name}, Hold'em No Limit
+Buy-in: {buy_in}
+100 Players
+Total Prize Pool: $1,000
+Tournament started 2025/08/01 12:00:00
+5th : Hero, {prize}
+
+You made {reentries} re-entries and received a total of {prize}
+"
+        )
+    }
+
+    #[test]
+    fn test_aggregate_tournaments() -> Result<(), PokercraftLocalError> {
+        let tournaments = [
+            summary("Bounty Hunters $10", "$9 + $1", "$30, $20 Bounty", 0),
+            summary("Daily Main $10", "$9 + $1", "$0", 1),
+            summary("Mystery Battle Royale $5", "$4.6 + $0.4", "$20", 0),
+        ]
+        .iter()
+        .map(|text| parse_tournament_summary(text, false).map(Option::unwrap))
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let overall = aggregate_tournaments(&tournaments);
+        assert_eq!((overall.tournaments, overall.entries), (3, 4));
+        assert!((overall.total_buy_in - 35.0).abs() < 1e-9);
+        assert!((overall.profit() - 35.0).abs() < 1e-9);
+        assert!((overall.roi().unwrap() - 1.0).abs() < 1e-9);
+        assert!((overall.itm_ratio().unwrap() - 2.0 / 3.0).abs() < 1e-9);
+        assert!((overall.average_buy_in().unwrap() - 8.75).abs() < 1e-9);
+        assert_eq!(overall.best_rre, Some(5.0));
+        assert_eq!(overall.biggest_prize, 50.0);
+        assert_eq!(
+            (overall.total_bounty, overall.bounty_tournaments),
+            (20.0, 1)
+        );
+        assert!((overall.bounty_ratio().unwrap() - 20.0 / 70.0).abs() < 1e-9);
+        assert!((overall.bounties_per_tournament().unwrap() - 20.0 / 3.0).abs() < 1e-9);
+
+        let by_variant = aggregate_tournaments_by_variant(&tournaments);
+        assert_eq!(by_variant.len(), 3);
+        assert_eq!(by_variant[&TournamentVariant::Regular].entries, 2);
+        assert_eq!(by_variant[&TournamentVariant::Regular].roi(), Some(-1.0));
+        assert_eq!(by_variant[&TournamentVariant::BattleRoyale].itm_count, 1);
+        assert_eq!(
+            by_variant[&TournamentVariant::Bounty].bounty_ratio(),
+            Some(0.4)
+        );
+
+        let mixed = [
+            tournaments[0].clone(),
+            tournaments[0].converted(&CurrencyRateConverter::new(), Currency::CNY),
+        ];
+        let in_usd = aggregate_tournaments_in(&mixed, &CurrencyRateConverter::new(), Currency::USD);
+        assert!((in_usd.total_prize - 100.0).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bounty_only_is_not_itm() -> Result<(), PokercraftLocalError> {
+        let text = summary("Bounty Hunters $10", "$9 + $1", "$15 Bounty", 0);
+        let tournament = parse_tournament_summary(&text, false)?.unwrap();
+        assert_eq!((tournament.my_prize, tournament.my_bounty), (15.0, 15.0));
+
+        let aggregate = aggregate_tournaments(&[tournament]);
+        assert_eq!(aggregate.itm_count, 0);
+        assert_eq!(aggregate.itm_ratio(), Some(0.0));
+        assert_eq!(aggregate.bounty_tournaments, 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+//! Confidence intervals of win rates, like ROI of tournaments and bb/100 of hands,
+//! to tell how much of the observed win rate is signal rather than variance.
+
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+use super::bootstrap::{bootstrap, validate, ConfidenceInterval};
+use crate::errors::PokercraftLocalError;
+use crate::parser::{HandHistory, TournamentSummary};
+
+/// Methods of estimating confidence intervals.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum IntervalMethod {
+    /// Student's t-distribution around the estimate, assuming independent results.
+    #[default]
+    TDistribution,
+    /// Percentiles of the estimates from resampled results with replacement.
+    /// The same seed gives the same interval.
+    Bootstrap { resamples: usize, seed: u64 },
+}
+
+/// Get the interval of `estimate ± t * standard_error`.
+fn t_interval(
+    estimate: f64,
+    standard_error: f64,
+    samples: usize,
+    confidence: f64,
+) -> Result<ConfidenceInterval, PokercraftLocalError> {
+    let t = StudentsT::new(0.0, 1.0, (samples - 1) as f64)
+        .map_err(|e| PokercraftLocalError::GeneralError(e.to_string()))?
+        .inverse_cdf((1.0 + confidence) / 2.0);
+    Ok(ConfidenceInterval {
+        estimate,
+        lower: estimate - t * standard_error,
+        upper: estimate + t * standard_error,
+        confidence,
+        samples,
+    })
+}
+
+/// Get the ROI interval from `(net profit, buy-in)` of each tournament.
+/// ROI is the total profit over the total buy-in, like `TournamentAggregate::roi`,
+/// and its t-based standard error comes from the delta method of ratio estimators.
+pub fn roi_interval(
+    results: &[(f64, f64)],
+    confidence: f64,
+    method: IntervalMethod,
+) -> Result<ConfidenceInterval, PokercraftLocalError> {
+    validate(results.len(), confidence)?;
+    let ratio = |sample: &[&(f64, f64)]| {
+        let (profit, buy_in) = sample.iter().fold((0.0, 0.0), |(profit, buy_in), result| {
+            (profit + result.0, buy_in + result.1)
+        });
+        if buy_in > 0.0 {
+            Some(profit / buy_in)
+        } else {
+            None
+        }
+    };
+    let roi = ratio(&results.iter().collect::<Vec<_>>()).ok_or_else(|| {
+        PokercraftLocalError::GeneralError("Total buy-in should be positive".to_string())
+    })?;
+
+    match method {
+        IntervalMethod::TDistribution => {
+            let n = results.len() as f64;
+            let mean_buy_in = results.iter().map(|(_, buy_in)| buy_in).sum::<f64>() / n;
+            let residual_variance = results
+                .iter()
+                .map(|(profit, buy_in)| (profit - roi * buy_in).powi(2))
+                .sum::<f64>()
+                / (n - 1.0);
+            let standard_error = residual_variance.sqrt() / (n.sqrt() * mean_buy_in);
+            t_interval(roi, standard_error, results.len(), confidence)
+        }
+        IntervalMethod::Bootstrap { resamples, seed } => {
+            bootstrap(results, resamples, seed, confidence, ratio)
+        }
+    }
+}
+
+/// Get the ROI interval of the given tournaments, including re-entries.
+/// Money amounts are used as-is; Convert them into a single currency first if needed.
+pub fn tournament_roi_interval<'a, I>(
+    tournaments: I,
+    confidence: f64,
+    method: IntervalMethod,
+) -> Result<ConfidenceInterval, PokercraftLocalError>
+where
+    I: IntoIterator<Item = &'a TournamentSummary>,
+{
+    let results = tournaments
+        .into_iter()
+        .map(|tournament| {
+            (
+                tournament.profit(),
+                tournament.buy_in() * tournament.my_entries as f64,
+            )
+        })
+        .collect::<Vec<_>>();
+    roi_interval(&results, confidence, method)
+}
+
+/// Get the bb/100 interval from net results of each hand in big blinds.
+pub fn bb_per_100_interval(
+    results: &[f64],
+    confidence: f64,
+    method: IntervalMethod,
+) -> Result<ConfidenceInterval, PokercraftLocalError> {
+    validate(results.len(), confidence)?;
+
+    match method {
+        IntervalMethod::TDistribution => {
+            let n = results.len() as f64;
+            let mean = results.iter().sum::<f64>() / n;
+            let estimate = mean * 100.0;
+            let variance = results.iter().map(|bb| (bb - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            let standard_error = variance.sqrt() / n.sqrt() * 100.0;
+            t_interval(estimate, standard_error, results.len(), confidence)
+        }
+        IntervalMethod::Bootstrap { resamples, seed } => {
+            bootstrap(results, resamples, seed, confidence, |sample| {
+                Some(sample.iter().copied().sum::<f64>() / sample.len() as f64 * 100.0)
+            })
+        }
+    }
+}
+
+/// Get the bb/100 interval of the given player over the given hands.
+/// Hands without big blinds are skipped.
+pub fn hand_bb_per_100_interval<'a, I>(
+    hands: I,
+    player_id: &str,
+    confidence: f64,
+    method: IntervalMethod,
+) -> Result<ConfidenceInterval, PokercraftLocalError>
+where
+    I: IntoIterator<Item = &'a HandHistory>,
+{
+    let results = hands
+        .into_iter()
+        .filter(|hand| hand.bb > 0)
+        .map(|hand| hand.net_profit(player_id) as f64 / hand.bb as f64)
+        .collect::<Vec<_>>();
+    bb_per_100_interval(&results, confidence, method)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roi_interval() -> Result<(), PokercraftLocalError> {
+        // 50% ROI with 1 win of 15 buy-ins in every 10 tournaments
+        let results = (0..1000)
+            .map(|i| {
+                if i % 10 == 0 {
+                    (14.0, 1.0)
+                } else {
+                    (-1.0, 1.0)
+                }
+            })
+            .collect::<Vec<_>>();
+        let t = roi_interval(&results, 0.95, IntervalMethod::TDistribution)?;
+        assert!((t.estimate - 0.5).abs() < 1e-9);
+        assert!(t.lower < 0.5 && 0.5 < t.upper);
+        // Standard error is about 4.5 / sqrt(1000)
+        assert!((t.upper - t.lower - 2.0 * 1.9623 * 4.5 / 1000f64.sqrt()).abs() < 1e-3);
+        assert!(t.is_significant());
+
+        let method = IntervalMethod::Bootstrap {
+            resamples: 500,
+            seed: 2140,
+        };
+        let bootstrap = roi_interval(&results, 0.95, method)?;
+        assert_eq!(bootstrap, roi_interval(&results, 0.95, method)?);
+        assert!((bootstrap.lower - t.lower).abs() < 0.03);
+        assert!((bootstrap.upper - t.upper).abs() < 0.03);
+
+        // Fewer samples give wider intervals, not significant anymore
+        let t = roi_interval(&results[..100], 0.95, IntervalMethod::TDistribution)?;
+        assert!(!t.is_significant());
+
+        assert!(roi_interval(&results[..1], 0.95, IntervalMethod::TDistribution).is_err());
+        assert!(roi_interval(&results, 1.0, IntervalMethod::TDistribution).is_err());
+        assert!(roi_interval(&[(1.0, 0.0); 3], 0.95, IntervalMethod::TDistribution).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bb_per_100_interval() -> Result<(), PokercraftLocalError> {
+        let results = (0..500)
+            .map(|i| if i % 2 == 0 { 2.2 } else { -2.0 })
+            .collect::<Vec<_>>();
+        let t = bb_per_100_interval(&results, 0.9, IntervalMethod::TDistribution)?;
+        assert!((t.estimate - 10.0).abs() < 1e-9);
+        assert!(t.lower < 10.0 && 10.0 < t.upper);
+        assert_eq!(t.samples, 500);
+
+        let bootstrap = bb_per_100_interval(
+            &results,
+            0.9,
+            IntervalMethod::Bootstrap {
+                resamples: 2000,
+                seed: 0,
+            },
+        )?;
+        assert!((bootstrap.lower - t.lower).abs() < 2.0);
+        assert!((bootstrap.upper - t.upper).abs() < 2.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,525 @@
+//! Embedded SQLite storage of parsed hands, tournaments, and computed stats,
+//! so exports don't have to be re-parsed every time.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection, OptionalExtension, Params, Row};
+
+use crate::currency::Currency;
+use crate::errors::PokercraftLocalError;
+use crate::parser::{HandHistory, TournamentSummary};
+use crate::stats::{HudStatsEngine, PlayerStats, StatCounter};
+
+/// Schema migrations, applied in order.
+/// The number of applied migrations is recorded in `PRAGMA user_version`,
+/// so new migrations should only be appended.
+const MIGRATIONS: &[&str] = &["
+CREATE TABLE tournaments (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    buy_in_pure REAL NOT NULL,
+    rake REAL NOT NULL,
+    total_prize_pool REAL NOT NULL,
+    start_time TEXT NOT NULL,
+    my_rank INTEGER NOT NULL,
+    total_players INTEGER NOT NULL,
+    my_prize REAL NOT NULL,
+    my_bounty REAL NOT NULL,
+    my_entries INTEGER NOT NULL
+);
+CREATE INDEX idx_tournaments_start_time ON tournaments (start_time);
+
+CREATE TABLE hands (
+    id TEXT PRIMARY KEY,
+    tournament_id INTEGER,
+    datetime TEXT NOT NULL,
+    sb INTEGER NOT NULL,
+    bb INTEGER NOT NULL,
+    body TEXT NOT NULL
+);
+CREATE INDEX idx_hands_datetime ON hands (datetime);
+CREATE INDEX idx_hands_stakes ON hands (sb, bb);
+CREATE INDEX idx_hands_tournament_id ON hands (tournament_id);
+
+CREATE TABLE hand_players (
+    hand_id TEXT NOT NULL REFERENCES hands (id),
+    player_id TEXT NOT NULL,
+    seat INTEGER NOT NULL,
+    initial_chips INTEGER NOT NULL,
+    net_profit INTEGER NOT NULL,
+    PRIMARY KEY (hand_id, player_id)
+);
+CREATE INDEX idx_hand_players_player_id ON hand_players (player_id);
+
+CREATE TABLE player_stats (
+    player_id TEXT NOT NULL,
+    stat_name TEXT NOT NULL,
+    opportunities INTEGER NOT NULL,
+    attempts INTEGER NOT NULL,
+    PRIMARY KEY (player_id, stat_name)
+);
+"];
+
+fn store_error(e: impl std::fmt::Display) -> PokercraftLocalError {
+    PokercraftLocalError::GeneralError(format!("Storage error: {}", e))
+}
+
+/// Local database of parsed data, backed by SQLite.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Open or create the database file at the given path,
+    /// and migrate it to the latest schema.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PokercraftLocalError> {
+        Self::from_connection(Connection::open(path).map_err(store_error)?)
+    }
+
+    /// Create a new database in memory.
+    pub fn open_in_memory() -> Result<Self, PokercraftLocalError> {
+        Self::from_connection(Connection::open_in_memory().map_err(store_error)?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self, PokercraftLocalError> {
+        let mut store = Store { conn };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    /// Apply all migrations which are not applied yet.
+    fn migrate(&mut self) -> Result<(), PokercraftLocalError> {
+        let version = self.schema_version()?;
+        if version > MIGRATIONS.len() {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Database schema version {} is newer than supported version {}",
+                version,
+                MIGRATIONS.len()
+            )));
+        }
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let tx = self.conn.transaction().map_err(store_error)?;
+            tx.execute_batch(migration).map_err(store_error)?;
+            tx.pragma_update(None, "user_version", index + 1)
+                .map_err(store_error)?;
+            tx.commit().map_err(store_error)?;
+        }
+        Ok(())
+    }
+
+    /// Get the number of applied migrations.
+    pub fn schema_version(&self) -> Result<usize, PokercraftLocalError> {
+        self.conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(store_error)
+    }
+
+    /// Insert the given tournaments, skipping ones already stored.
+    /// Returns the number of newly inserted tournaments.
+    pub fn insert_tournaments<'a, I>(
+        &mut self,
+        tournaments: I,
+    ) -> Result<usize, PokercraftLocalError>
+    where
+        I: IntoIterator<Item = &'a TournamentSummary>,
+    {
+        let tx = self.conn.transaction().map_err(store_error)?;
+        let mut inserted = 0;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT OR IGNORE INTO tournaments VALUES
+                    (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                )
+                .map_err(store_error)?;
+            for t in tournaments {
+                inserted += stmt
+                    .execute(params![
+                        t.id as i64,
+                        t.name,
+                        t.currency.to_string(),
+                        t.buy_in_pure,
+                        t.rake,
+                        t.total_prize_pool,
+                        t.start_time,
+                        t.my_rank,
+                        t.total_players,
+                        t.my_prize,
+                        t.my_bounty,
+                        t.my_entries,
+                    ])
+                    .map_err(store_error)?;
+            }
+        }
+        tx.commit().map_err(store_error)?;
+        Ok(inserted)
+    }
+
+    /// Insert the given hands, skipping ones already stored,
+    /// and accumulate HUD stats of newly inserted hands.
+    /// Returns the number of newly inserted hands.
+    pub fn insert_hands<'a, I>(&mut self, hands: I) -> Result<usize, PokercraftLocalError>
+    where
+        I: IntoIterator<Item = &'a HandHistory>,
+    {
+        let tx = self.conn.transaction().map_err(store_error)?;
+        let mut engine = HudStatsEngine::new();
+        let mut inserted = 0;
+        {
+            let mut insert_hand = tx
+                .prepare("INSERT OR IGNORE INTO hands VALUES (?1, ?2, ?3, ?4, ?5, ?6)")
+                .map_err(store_error)?;
+            let mut insert_player = tx
+                .prepare("INSERT INTO hand_players VALUES (?1, ?2, ?3, ?4, ?5)")
+                .map_err(store_error)?;
+            for hand in hands {
+                let body = serde_json::to_string(hand).map_err(store_error)?;
+                let changed = insert_hand
+                    .execute(params![
+                        hand.id,
+                        hand.tournament_id.map(|id| id as i64),
+                        hand.datetime,
+                        hand.sb as i64,
+                        hand.bb as i64,
+                        body,
+                    ])
+                    .map_err(store_error)?;
+                if changed == 0 {
+                    continue;
+                }
+                for (seat, (player_id, chips)) in hand.seats.iter() {
+                    insert_player
+                        .execute(params![
+                            hand.id,
+                            player_id,
+                            seat,
+                            *chips as i64,
+                            hand.net_profit(player_id),
+                        ])
+                        .map_err(store_error)?;
+                }
+                engine.add_hand(hand);
+                inserted += 1;
+            }
+
+            let mut upsert_stat = tx
+                .prepare(
+                    "INSERT INTO player_stats VALUES (?1, ?2, ?3, ?4)
+                    ON CONFLICT (player_id, stat_name) DO UPDATE SET
+                    opportunities = opportunities + excluded.opportunities,
+                    attempts = attempts + excluded.attempts",
+                )
+                .map_err(store_error)?;
+            for (player_id, player_stats) in engine.players() {
+                for (name, counter) in player_stats.stats.iter() {
+                    upsert_stat
+                        .execute(params![
+                            player_id,
+                            name,
+                            counter.opportunities as i64,
+                            counter.attempts as i64,
+                        ])
+                        .map_err(store_error)?;
+                }
+            }
+        }
+        tx.commit().map_err(store_error)?;
+        Ok(inserted)
+    }
+
+    /// Get the number of stored hands.
+    pub fn hand_count(&self) -> Result<u64, PokercraftLocalError> {
+        self.conn
+            .query_row("SELECT COUNT(*) FROM hands", [], |row| row.get(0))
+            .map_err(store_error)
+    }
+
+    /// Get the stored hand of the given ID.
+    pub fn hand(&self, id: &str) -> Result<Option<HandHistory>, PokercraftLocalError> {
+        self.conn
+            .query_row("SELECT body FROM hands WHERE id = ?1", [id], |row| {
+                row.get::<_, String>(0)
+            })
+            .optional()
+            .map_err(store_error)?
+            .map(|body| serde_json::from_str(&body).map_err(store_error))
+            .transpose()
+    }
+
+    fn query_hands<P: Params>(
+        &self,
+        sql: &str,
+        params: P,
+    ) -> Result<Vec<HandHistory>, PokercraftLocalError> {
+        let mut stmt = self.conn.prepare(sql).map_err(store_error)?;
+        let bodies = stmt
+            .query_map(params, |row| row.get::<_, String>(0))
+            .map_err(store_error)?;
+        bodies
+            .map(|body| serde_json::from_str(&body.map_err(store_error)?).map_err(store_error))
+            .collect()
+    }
+
+    /// Get hands played in `[start, end)`, ordered by time.
+    pub fn hands_between(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<HandHistory>, PokercraftLocalError> {
+        self.query_hands(
+            "SELECT body FROM hands WHERE datetime >= ?1 AND datetime < ?2
+            ORDER BY datetime, id",
+            params![start, end],
+        )
+    }
+
+    /// Get hands played at the given blinds, ordered by time.
+    pub fn hands_at_stakes(
+        &self,
+        sb: u64,
+        bb: u64,
+    ) -> Result<Vec<HandHistory>, PokercraftLocalError> {
+        self.query_hands(
+            "SELECT body FROM hands WHERE sb = ?1 AND bb = ?2 ORDER BY datetime, id",
+            params![sb as i64, bb as i64],
+        )
+    }
+
+    /// Get hands the given player was dealt in, ordered by time.
+    pub fn hands_of_player(
+        &self,
+        player_id: &str,
+    ) -> Result<Vec<HandHistory>, PokercraftLocalError> {
+        self.query_hands(
+            "SELECT hands.body FROM hands
+            JOIN hand_players ON hand_players.hand_id = hands.id
+            WHERE hand_players.player_id = ?1 ORDER BY hands.datetime, hands.id",
+            [player_id],
+        )
+    }
+
+    /// Get tournaments started in `[start, end)`, ordered by start time.
+    pub fn tournaments_between(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<TournamentSummary>, PokercraftLocalError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT * FROM tournaments WHERE start_time >= ?1 AND start_time < ?2
+                ORDER BY start_time, id",
+            )
+            .map_err(store_error)?;
+        let rows = stmt
+            .query_map(params![start, end], tournament_from_row)
+            .map_err(store_error)?;
+        rows.map(|row| row.map_err(store_error)).collect()
+    }
+
+    /// Get the total net profit of the given player in chips over all stored hands.
+    pub fn player_net_profit(&self, player_id: &str) -> Result<i64, PokercraftLocalError> {
+        self.conn
+            .query_row(
+                "SELECT COALESCE(SUM(net_profit), 0) FROM hand_players WHERE player_id = ?1",
+                [player_id],
+                |row| row.get(0),
+            )
+            .map_err(store_error)
+    }
+
+    /// Get `{stat name: counter}` of the given player over all stored hands.
+    pub fn player_stats(
+        &self,
+        player_id: &str,
+    ) -> Result<BTreeMap<String, StatCounter>, PokercraftLocalError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT stat_name, opportunities, attempts FROM player_stats
+                WHERE player_id = ?1",
+            )
+            .map_err(store_error)?;
+        let rows = stmt
+            .query_map([player_id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    StatCounter {
+                        opportunities: row.get::<_, i64>(1)? as u64,
+                        attempts: row.get::<_, i64>(2)? as u64,
+                    },
+                ))
+            })
+            .map_err(store_error)?;
+        rows.map(|row| row.map_err(store_error)).collect()
+    }
+
+    /// Get the number of stored hands the given player was dealt in,
+    /// which is the sample size of stats of the player.
+    pub fn player_hand_count(&self, player_id: &str) -> Result<u64, PokercraftLocalError> {
+        self.conn
+            .query_row(
+                "SELECT COUNT(*) FROM hand_players WHERE player_id = ?1",
+                [player_id],
+                |row| row.get(0),
+            )
+            .map_err(store_error)
+    }
+
+    /// Get stats of opponents of the hero who were dealt in at least `min_hands` stored hands,
+    /// ordered by the number of hands descending.
+    /// Breakdowns by position and stack depth are not stored, so they are empty.
+    pub fn opponent_stats(
+        &self,
+        hero: &str,
+        min_hands: u64,
+    ) -> Result<Vec<(String, PlayerStats)>, PokercraftLocalError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT player_id, COUNT(*) AS hands FROM hand_players
+                WHERE player_id != ?1 GROUP BY player_id HAVING hands >= ?2
+                ORDER BY hands DESC, player_id",
+            )
+            .map_err(store_error)?;
+        let rows = stmt
+            .query_map(params![hero, min_hands as i64], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+            })
+            .map_err(store_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(store_error)?;
+        rows.into_iter()
+            .map(|(player_id, hands)| {
+                let stats = self.player_stats(&player_id)?;
+                Ok((
+                    player_id,
+                    PlayerStats {
+                        hands,
+                        stats,
+                        ..Default::default()
+                    },
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Read a tournament from a row of `tournaments`.
+fn tournament_from_row(row: &Row) -> rusqlite::Result<TournamentSummary> {
+    let currency = Currency::from_str(&row.get::<_, String>(2)?).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(TournamentSummary {
+        id: row.get::<_, i64>(0)? as u64,
+        name: row.get(1)?,
+        currency,
+        buy_in_pure: row.get(3)?,
+        rake: row.get(4)?,
+        total_prize_pool: row.get(5)?,
+        start_time: row.get(6)?,
+        my_rank: row.get(7)?,
+        total_players: row.get(8)?,
+        my_prize: row.get(9)?,
+        my_bounty: row.get(10)?,
+        my_entries: row.get(11)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::parser::hand_history::tests::SAMPLE_HAND;
+    use crate::parser::tournament_summary::tests::SAMPLE_SUMMARY;
+    use crate::parser::{parse_hands, parse_tournament_summary};
+
+    #[test]
+    fn test_migrations() -> Result<(), PokercraftLocalError> {
+        let path = std::env::temp_dir().join(format!(
+            "pokercraft-store-test-{}.sqlite3",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        {
+            let store = Store::open(&path)?;
+            assert_eq!(store.schema_version()?, MIGRATIONS.len());
+        }
+        // Reopening an up-to-date database should not migrate again
+        let store = Store::open(&path)?;
+        assert_eq!(store.schema_version()?, MIGRATIONS.len());
+        drop(store);
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_hands() -> Result<(), PokercraftLocalError> {
+        let mut store = Store::open_in_memory()?;
+        let hands = parse_hands(SAMPLE_HAND.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(store.insert_hands(&hands)?, 1);
+        assert_eq!(store.insert_hands(&hands)?, 0);
+        assert_eq!(store.hand_count()?, 1);
+
+        let restored = store.hand("TM4832872904")?.unwrap();
+        assert_eq!(restored.seats, hands[0].seats);
+        assert_eq!(restored.actions_turn, hands[0].actions_turn);
+        assert!(store.hand("TM0")?.is_none());
+
+        // Stats are not doubled by duplicated inserts
+        let stats = store.player_stats("Hero")?;
+        assert_eq!(
+            stats["VPIP"],
+            StatCounter {
+                opportunities: 1,
+                attempts: 0
+            }
+        );
+        assert_eq!(store.player_net_profit("Hero")?, -150);
+        assert_eq!(store.player_net_profit("471910c")?, 9210 - 2520 - 150);
+
+        // Villains have stats with sample sizes too
+        assert_eq!(store.player_hand_count("afc7064f")?, 1);
+        let opponents = store.opponent_stats("Hero", 1)?;
+        assert_eq!(opponents.len(), 6);
+        assert!(opponents.iter().all(|(player_id, _)| player_id != "Hero"));
+        let (_, afc7064f) = opponents
+            .iter()
+            .find(|(player_id, _)| player_id == "afc7064f")
+            .unwrap();
+        assert_eq!(afc7064f.hands, 1);
+        assert_eq!(afc7064f.ratio_with_sample("PFR"), (Some(1.0), 1));
+        assert!(store.opponent_stats("Hero", 2)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_queries() -> Result<(), PokercraftLocalError> {
+        let mut store = Store::open_in_memory()?;
+        let hands = parse_hands(SAMPLE_HAND.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        store.insert_hands(&hands)?;
+        let summary = parse_tournament_summary(SAMPLE_SUMMARY, false)?.unwrap();
+        assert_eq!(store.insert_tournaments([&summary])?, 1);
+
+        let day = |d: u32| {
+            NaiveDate::from_ymd_opt(2025, 8, d)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        };
+        assert_eq!(store.hands_between(day(1), day(2))?.len(), 1);
+        assert!(store.hands_between(day(2), day(3))?.is_empty());
+        assert_eq!(store.hands_at_stakes(600, 1200)?.len(), 1);
+        assert!(store.hands_at_stakes(100, 200)?.is_empty());
+        assert_eq!(store.hands_of_player("afc7064f")?.len(), 1);
+        assert!(store.hands_of_player("nobody")?.is_empty());
+        assert_eq!(store.tournaments_between(day(1), day(2))?, vec![summary]);
+        assert!(store.tournaments_between(day(2), day(3))?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+//! Heavy-tail fitting of tournament scores, like prizes in buy-ins.
+//!
+//! Observed samples rarely include the biggest scores of large fields,
+//! so fitted tails are used to extrapolate them in bankroll simulations.
+
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::errors::PokercraftLocalError;
+
+/// Distribution fitted to tournament scores.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum TailModel {
+    /// Pareto tail, where `Pr(X > x | X >= x_min) = (x / x_min)^-alpha`.
+    /// Fitted to scores of at least `x_min` by the Hill estimator.
+    Pareto { alpha: f64 },
+    /// Log-normal distribution, where `ln X` is normal with `mu` and `sigma`.
+    /// Fitted to all positive scores by maximum likelihood.
+    LogNormal { mu: f64, sigma: f64 },
+}
+
+/// Tail of tournament scores above a threshold fitted by a `TailModel`,
+/// with goodness-of-fit diagnostics on scores of the tail.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct TailFit {
+    pub model: TailModel,
+    /// Threshold of the tail.
+    pub x_min: f64,
+    /// Number of scores of at least `x_min`.
+    pub tail_samples: usize,
+    /// Kolmogorov-Smirnov statistic of tail scores against the fitted tail.
+    pub ks_statistic: f64,
+    /// Asymptotic p-value of `ks_statistic`, where small values reject the fit.
+    /// This is optimistic, as parameters are fitted from the same scores.
+    pub p_value: f64,
+    /// Log-likelihood of tail scores under the fitted tail,
+    /// to compare models of the same threshold.
+    pub log_likelihood: f64,
+}
+
+/// Get scores of at least `x_min` in ascending order.
+fn tail_scores(scores: &[f64], x_min: f64) -> Result<Vec<f64>, PokercraftLocalError> {
+    if !(x_min > 0.0 && x_min.is_finite()) {
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "Tail threshold must be positive, got {}",
+            x_min
+        )));
+    } else if scores.iter().any(|score| !score.is_finite()) {
+        return Err(PokercraftLocalError::GeneralError(
+            "Scores must be finite".to_string(),
+        ));
+    }
+    let mut tail = scores
+        .iter()
+        .copied()
+        .filter(|&score| score >= x_min)
+        .collect::<Vec<_>>();
+    if tail.len() < 2 {
+        return Err(PokercraftLocalError::GeneralError(format!(
+            "At least 2 scores of {} or more are required, got {}",
+            x_min,
+            tail.len()
+        )));
+    }
+    tail.sort_by(f64::total_cmp);
+    Ok(tail)
+}
+
+/// Get the asymptotic p-value of the Kolmogorov-Smirnov statistic of `n` samples.
+fn kolmogorov_p_value(statistic: f64, n: usize) -> f64 {
+    let sqrt_n = (n as f64).sqrt();
+    let lambda = (sqrt_n + 0.12 + 0.11 / sqrt_n) * statistic;
+    if lambda < 1e-3 {
+        return 1.0;
+    }
+    let sum = (1..=100)
+        .map(|k| {
+            let sign = if k % 2 == 1 { 1.0 } else { -1.0 };
+            sign * (-2.0 * (k * k) as f64 * lambda * lambda).exp()
+        })
+        .sum::<f64>();
+    (2.0 * sum).clamp(0.0, 1.0)
+}
+
+impl TailFit {
+    /// Fit a Pareto tail to scores of at least `x_min`.
+    pub fn pareto(scores: &[f64], x_min: f64) -> Result<Self, PokercraftLocalError> {
+        let tail = tail_scores(scores, x_min)?;
+        let log_excess = tail.iter().map(|x| (x / x_min).ln()).sum::<f64>();
+        if log_excess <= 0.0 {
+            return Err(PokercraftLocalError::GeneralError(
+                "All tail scores are equal to the threshold".to_string(),
+            ));
+        }
+        let alpha = tail.len() as f64 / log_excess;
+        Ok(Self::with_diagnostics(
+            TailModel::Pareto { alpha },
+            x_min,
+            &tail,
+        ))
+    }
+
+    /// Fit a log-normal distribution to all positive scores,
+    /// and evaluate its tail of at least `x_min`.
+    pub fn log_normal(scores: &[f64], x_min: f64) -> Result<Self, PokercraftLocalError> {
+        let tail = tail_scores(scores, x_min)?;
+        let logs = scores
+            .iter()
+            .filter(|&&score| score > 0.0)
+            .map(|score| score.ln())
+            .collect::<Vec<_>>();
+        let mu = logs.iter().sum::<f64>() / logs.len() as f64;
+        let sigma = (logs.iter().map(|y| (y - mu).powi(2)).sum::<f64>() / logs.len() as f64).sqrt();
+        if sigma <= 0.0 {
+            return Err(PokercraftLocalError::GeneralError(
+                "All positive scores are equal".to_string(),
+            ));
+        }
+        Ok(Self::with_diagnostics(
+            TailModel::LogNormal { mu, sigma },
+            x_min,
+            &tail,
+        ))
+    }
+
+    /// Evaluate the model on sorted tail scores.
+    fn with_diagnostics(model: TailModel, x_min: f64, tail: &[f64]) -> Self {
+        let mut fit = TailFit {
+            model,
+            x_min,
+            tail_samples: tail.len(),
+            ks_statistic: 0.0,
+            p_value: 1.0,
+            log_likelihood: 0.0,
+        };
+        let n = tail.len() as f64;
+        fit.ks_statistic = tail
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| {
+                let cdf = 1.0 - fit.survival(x);
+                f64::max((i + 1) as f64 / n - cdf, cdf - i as f64 / n)
+            })
+            .fold(0.0, f64::max);
+        fit.p_value = kolmogorov_p_value(fit.ks_statistic, tail.len());
+        fit.log_likelihood = tail.iter().map(|&x| fit.ln_density(x)).sum();
+        fit
+    }
+
+    /// Standard normal distribution and `Pr(Z >= z_min)` of the log-normal threshold.
+    fn standard_normal(mu: f64, sigma: f64, x_min: f64) -> (Normal, f64, f64) {
+        let normal = Normal::new(0.0, 1.0).unwrap(); // Always valid
+        let z_min = (x_min.ln() - mu) / sigma;
+        let tail_mass = 1.0 - normal.cdf(z_min);
+        (normal, z_min, tail_mass)
+    }
+
+    /// Get `Pr(X > x | X >= x_min)` of the fitted tail.
+    pub fn survival(&self, x: f64) -> f64 {
+        if x <= self.x_min {
+            return 1.0;
+        }
+        match self.model {
+            TailModel::Pareto { alpha } => (x / self.x_min).powf(-alpha),
+            TailModel::LogNormal { mu, sigma } => {
+                let (normal, _, tail_mass) = Self::standard_normal(mu, sigma, self.x_min);
+                (1.0 - normal.cdf((x.ln() - mu) / sigma)) / tail_mass
+            }
+        }
+    }
+
+    /// Get the `p`-quantile (`0.0 <= p < 1.0`) of the fitted tail.
+    pub fn quantile(&self, p: f64) -> f64 {
+        let p = p.clamp(0.0, 1.0);
+        match self.model {
+            TailModel::Pareto { alpha } => self.x_min * (1.0 - p).powf(-1.0 / alpha),
+            TailModel::LogNormal { mu, sigma } => {
+                let (normal, z_min, tail_mass) = Self::standard_normal(mu, sigma, self.x_min);
+                let z = normal.inverse_cdf(normal.cdf(z_min) + p * tail_mass);
+                (mu + sigma * z).exp().max(self.x_min)
+            }
+        }
+    }
+
+    /// Get the log density of the fitted tail at `x`.
+    fn ln_density(&self, x: f64) -> f64 {
+        match self.model {
+            TailModel::Pareto { alpha } => {
+                alpha.ln() + alpha * self.x_min.ln() - (alpha + 1.0) * x.ln()
+            }
+            TailModel::LogNormal { mu, sigma } => {
+                let (_, _, tail_mass) = Self::standard_normal(mu, sigma, self.x_min);
+                let z = (x.ln() - mu) / sigma;
+                -0.5 * z * z
+                    - (sigma * x * (2.0 * std::f64::consts::PI).sqrt()).ln()
+                    - tail_mass.ln()
+            }
+        }
+    }
+
+    /// Get `(score, weight)` outcomes for `bankroll::simulate_weighted`,
+    /// where scores below `x_min` keep weight `1` each, and tail scores are replaced by
+    /// `points` evenly spaced quantiles of the fitted tail sharing their total weight.
+    /// Extreme quantiles extrapolate scores beyond the observed maximum.
+    /// Subtract the buy-in from scores to get net results.
+    pub fn weighted_outcomes(
+        &self,
+        scores: &[f64],
+        points: usize,
+    ) -> Result<Vec<(f64, f64)>, PokercraftLocalError> {
+        if points < 1 {
+            return Err(PokercraftLocalError::GeneralError(
+                "Number of tail points must be positive".to_string(),
+            ));
+        }
+        let tail_samples = scores.iter().filter(|&&score| score >= self.x_min).count();
+        let weight = tail_samples as f64 / points as f64;
+        let mut outcomes = scores
+            .iter()
+            .filter(|&&score| score < self.x_min)
+            .map(|&score| (score, 1.0))
+            .collect::<Vec<_>>();
+        outcomes
+            .extend((0..points).map(|j| (self.quantile((j as f64 + 0.5) / points as f64), weight)));
+        Ok(outcomes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_fit() -> Result<(), PokercraftLocalError> {
+        // Exact quantiles of a Pareto tail of alpha 2 above 10, with non-cashes below
+        let pareto = (0..200)
+            .map(|i| 10.0 * (1.0 - (i as f64 + 0.5) / 200.0).powf(-0.5))
+            .collect::<Vec<_>>();
+        let scores = [pareto.clone(), vec![0.0; 300], vec![2.0; 100]].concat();
+        let fit = TailFit::pareto(&scores, 10.0)?;
+        assert_eq!(fit.tail_samples, 200);
+        let TailModel::Pareto { alpha } = fit.model else {
+            panic!("Expected a Pareto model");
+        };
+        assert!((alpha - 2.0).abs() < 0.05);
+        assert!(fit.ks_statistic < 0.02 && fit.p_value > 0.9);
+        assert!((fit.survival(20.0) - 0.25).abs() < 0.01);
+        assert!((fit.quantile(0.75) - 20.0).abs() < 0.5);
+        assert_eq!(fit.survival(5.0), 1.0);
+
+        // Log-normal fits of positive scores have lighter tails
+        let log_normal = TailFit::log_normal(&scores, 10.0)?;
+        assert_eq!(log_normal.tail_samples, 200);
+        assert!(log_normal.log_likelihood < fit.log_likelihood);
+        assert!(log_normal.survival(1000.0) < fit.survival(1000.0));
+        assert!(log_normal.quantile(0.0) >= 10.0);
+        assert!(log_normal.quantile(0.5) < log_normal.quantile(0.9));
+
+        let outcomes = fit.weighted_outcomes(&scores, 1000)?;
+        assert_eq!(outcomes.len(), 400 + 1000);
+        let total_weight = outcomes.iter().map(|(_, weight)| weight).sum::<f64>();
+        assert!((total_weight - 600.0).abs() < 1e-9);
+        let max_score = outcomes.iter().map(|(score, _)| *score).fold(0.0, f64::max);
+        assert!(max_score > pareto.iter().copied().fold(0.0, f64::max));
+
+        assert!(TailFit::pareto(&scores, 0.0).is_err());
+        assert!(TailFit::pareto(&scores, 1e6).is_err());
+        assert!(TailFit::pareto(&[10.0, 10.0], 10.0).is_err());
+        assert!(TailFit::log_normal(&[10.0, 10.0], 10.0).is_err());
+        assert!(fit.weighted_outcomes(&scores, 0).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+//! Timezone-aware normalization of timestamps in exported files,
+//! and calendar groupings (day, week, month) in a target timezone.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::errors::PokercraftLocalError;
+use crate::parser::TournamentSummary;
+
+/// Calendar units to group timestamps by.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum CalendarGrouping {
+    Day,
+    /// Weeks starting on Monday.
+    Week,
+    Month,
+}
+
+/// Converts naive timestamps written in exports into instants,
+/// and groups them by calendar units in the target timezone.
+#[derive(Clone, Copy, Debug)]
+pub struct TimeNormalizer {
+    /// Timezone which timestamps in exports are written in.
+    source: Tz,
+    /// Timezone to report in.
+    target: Tz,
+    /// Hours after midnight when a new calendar day starts,
+    /// so late-night sessions belong to the day they started.
+    day_start_hours: u32,
+}
+
+impl TimeNormalizer {
+    /// Create a new normalizer from the source timezone to the target timezone.
+    pub fn new(source: Tz, target: Tz) -> Self {
+        TimeNormalizer {
+            source,
+            target,
+            day_start_hours: 0,
+        }
+    }
+
+    /// Create a new normalizer from timezone names like `"Asia/Seoul"`.
+    pub fn from_names(source: &str, target: &str) -> Result<Self, PokercraftLocalError> {
+        let parse = |name: &str| {
+            name.parse::<Tz>().map_err(|_| {
+                PokercraftLocalError::GeneralError(format!("Unknown timezone: {}", name))
+            })
+        };
+        Ok(Self::new(parse(source)?, parse(target)?))
+    }
+
+    /// Set hours after midnight (0 ~ 23) when a new calendar day starts.
+    pub fn with_day_start_hours(mut self, hours: u32) -> Result<Self, PokercraftLocalError> {
+        if hours >= 24 {
+            return Err(PokercraftLocalError::GeneralError(format!(
+                "Day start hours should be less than 24, got {}",
+                hours
+            )));
+        }
+        self.day_start_hours = hours;
+        Ok(self)
+    }
+
+    /// Interpret the naive timestamp in the source timezone.
+    /// Ambiguous local times (DST fold) resolve to the earlier instant.
+    pub fn to_instant(&self, naive: NaiveDateTime) -> Result<DateTime<Utc>, PokercraftLocalError> {
+        match self.source.from_local_datetime(&naive) {
+            LocalResult::Single(datetime) | LocalResult::Ambiguous(datetime, _) => {
+                Ok(datetime.with_timezone(&Utc))
+            }
+            LocalResult::None => Err(PokercraftLocalError::GeneralError(format!(
+                "{} does not exist in timezone {}",
+                naive, self.source
+            ))),
+        }
+    }
+
+    /// Convert the naive timestamp into the target timezone.
+    pub fn to_target(&self, naive: NaiveDateTime) -> Result<DateTime<Tz>, PokercraftLocalError> {
+        Ok(self.to_instant(naive)?.with_timezone(&self.target))
+    }
+
+    /// Get the calendar day of the timestamp in the target timezone,
+    /// respecting the configured day start.
+    pub fn day_of(&self, naive: NaiveDateTime) -> Result<NaiveDate, PokercraftLocalError> {
+        let local = self.to_target(naive)?.naive_local();
+        Ok((local - Duration::hours(self.day_start_hours as i64)).date())
+    }
+
+    /// Get the first day of the calendar unit which the timestamp belongs to.
+    pub fn group_of(
+        &self,
+        naive: NaiveDateTime,
+        grouping: CalendarGrouping,
+    ) -> Result<NaiveDate, PokercraftLocalError> {
+        let day = self.day_of(naive)?;
+        Ok(match grouping {
+            CalendarGrouping::Day => day,
+            CalendarGrouping::Week => {
+                day - Duration::days(day.weekday().num_days_from_monday() as i64)
+            }
+            CalendarGrouping::Month => day.with_day(1).expect("First day always exists"),
+        })
+    }
+
+    /// Sum profits of the given tournaments by calendar units of their start times.
+    pub fn group_tournament_profits<'a, I>(
+        &self,
+        tournaments: I,
+        grouping: CalendarGrouping,
+    ) -> Result<BTreeMap<NaiveDate, f64>, PokercraftLocalError>
+    where
+        I: IntoIterator<Item = &'a TournamentSummary>,
+    {
+        let mut profits: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+        for tournament in tournaments {
+            *profits
+                .entry(self.group_of(tournament.start_time, grouping)?)
+                .or_default() += tournament.profit();
+        }
+        Ok(profits)
+    }
+}
+
+impl Default for TimeNormalizer {
+    /// Both source and target are UTC.
+    fn default() -> Self {
+        Self::new(Tz::UTC, Tz::UTC)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y/%m/%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_time_normalizer() -> Result<(), PokercraftLocalError> {
+        let normalizer = TimeNormalizer::from_names("UTC", "Asia/Seoul")?;
+        let target = normalizer.to_target(naive("2025/08/01 16:30:00"))?;
+        assert_eq!(target.naive_local(), naive("2025/08/02 01:30:00"));
+        assert_eq!(
+            normalizer.day_of(naive("2025/08/01 16:30:00"))?,
+            NaiveDate::from_ymd_opt(2025, 8, 2).unwrap()
+        );
+
+        // Late-night play belongs to the previous day with day start at 6 AM
+        let shifted = normalizer.with_day_start_hours(6)?;
+        assert_eq!(
+            shifted.day_of(naive("2025/08/01 16:30:00"))?,
+            NaiveDate::from_ymd_opt(2025, 8, 1).unwrap()
+        );
+        assert_eq!(
+            shifted.group_of(naive("2025/08/01 16:30:00"), CalendarGrouping::Week)?,
+            NaiveDate::from_ymd_opt(2025, 7, 28).unwrap()
+        );
+        assert_eq!(
+            shifted.group_of(naive("2025/08/01 16:30:00"), CalendarGrouping::Month)?,
+            NaiveDate::from_ymd_opt(2025, 8, 1).unwrap()
+        );
+        assert!(TimeNormalizer::from_names("UTC", "Mars/Olympus").is_err());
+        assert!(shifted.with_day_start_hours(24).is_err());
+
+        // 02:30 is skipped on the DST start in New York
+        let new_york = TimeNormalizer::from_names("America/New_York", "UTC")?;
+        assert!(new_york.to_instant(naive("2025/03/09 02:30:00")).is_err());
+        Ok(())
+    }
+}
@@ -1,5 +1,87 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::errors::PokercraftLocalError;
+
+/// Thread pool of parallel computations; `None` means the global rayon pool.
+static THREAD_POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+
+/// Set the number of threads used by parallel computations,
+/// like equities, bankroll simulations, and hand filtering.
+/// `0` restores the default, which uses all cores unless `RAYON_NUM_THREADS` is set.
+/// Computations already running keep their threads.
+pub fn set_parallelism(threads: usize) -> Result<(), PokercraftLocalError> {
+    let pool = match threads {
+        0 => None,
+        threads => Some(Arc::new(
+            ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|i| format!("pokercraft-{}", i))
+                .build()
+                .map_err(|e| {
+                    PokercraftLocalError::GeneralError(format!(
+                        "Failed to create thread pool: {}",
+                        e
+                    ))
+                })?,
+        )),
+    };
+    *THREAD_POOL.write().unwrap_or_else(|e| e.into_inner()) = pool;
+    Ok(())
+}
+
+/// Get the number of threads used by parallel computations.
+pub fn parallelism() -> usize {
+    match THREAD_POOL
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+    {
+        Some(pool) => pool.current_num_threads(),
+        None => rayon::current_num_threads(),
+    }
+}
+
+/// Run the given parallel computation in the thread pool set by `set_parallelism`.
+pub(crate) fn in_thread_pool<R, F>(op: F) -> R
+where
+    R: Send,
+    F: FnOnce() -> R + Send,
+{
+    let pool = THREAD_POOL
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    match pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// Convert the given value into a plain JS object,
+/// with maps as objects instead of `Map`s.
+#[cfg(feature = "wasm")]
+pub(crate) fn to_js_object<T: serde::Serialize + ?Sized>(
+    value: &T,
+) -> Result<wasm_bindgen::JsValue, wasm_bindgen::JsValue> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| wasm_bindgen::JsValue::from_str(&e.to_string()))
+}
+
+/// Get the number of `k`-combinations of `n` elements.
+pub fn binomial(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    (0..k.min(n - k)).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
 /// An iterator that yields all combinations
-/// of `K` elements from a list of candidates.
+/// of `K` elements from a list of candidates,
+/// in the lexicographic order of candidate indices.
 #[derive(Debug, Clone)]
 pub struct FixedSizedCombinationIterator<T, const K: usize> {
     candidates: Vec<T>,
@@ -25,23 +107,61 @@ impl<T, const K: usize> FixedSizedCombinationIterator<T, K> {
         }
     }
 
+    /// Same as `new`, but starts from the `start`-th (0-based) combination,
+    /// so disjoint ranges of combinations can be enumerated independently.
+    pub fn new_from<I>(candidates: I, start: u64) -> Self
+    where
+        I: Iterator<Item = T>,
+    {
+        let mut result = Self::new(candidates);
+        let n = result.candidates.len();
+        if result.finished || start >= binomial(n as u64, K as u64) {
+            result.finished = true;
+            return result;
+        }
+
+        // Pick each index by skipping blocks of combinations sharing the prefix
+        let mut remaining = start;
+        let mut next = 0;
+        for i in 0..K {
+            loop {
+                let block = binomial((n - next - 1) as u64, (K - i - 1) as u64);
+                if remaining < block {
+                    break;
+                }
+                remaining -= block;
+                next += 1;
+            }
+            result.idxes[i] = next;
+            next += 1;
+        }
+        result
+    }
+
     /// Internal method to increment the idxes to the next combination.
     /// Do not call this method directly.
     fn increment_idxes(&mut self) -> () {
-        let n = self.candidates.len();
-        let mut done_loop = false;
-        for i in (0..K).rev() {
-            if self.idxes[i] < n - (K - i) {
-                self.idxes[i] += 1;
-                for j in (i + 1)..K {
-                    self.idxes[j] = self.idxes[j - 1] + 1;
-                }
-                done_loop = true;
-                break;
+        self.finished |= !next_combination(&mut self.idxes, self.candidates.len());
+    }
+}
+
+/// Advance the given sorted indices to the next `K`-combination
+/// of `n` elements in lexicographic order, without allocating.
+/// Returns `false` if there is no next combination.
+pub(crate) fn next_combination<const K: usize>(idxes: &mut [usize; K], n: usize) -> bool {
+    if K == 0 || n < K {
+        return false;
+    }
+    for i in (0..K).rev() {
+        if idxes[i] < n - (K - i) {
+            idxes[i] += 1;
+            for j in (i + 1)..K {
+                idxes[j] = idxes[j - 1] + 1;
             }
+            return true;
         }
-        self.finished |= K == 0 || n < K || self.idxes[K - 1] >= n || !done_loop;
     }
+    false
 }
 
 impl<T, const K: usize> Iterator for FixedSizedCombinationIterator<T, K>
@@ -61,21 +181,103 @@ where
     }
 }
 
-/// A wrapper around an iterator.
-/// For unknown reason `Box<dyn Iterator<Item = T>>`
-/// does not implement `rayon::iter::ParallelBridge`.
-pub struct IterWrapper<T> {
-    pub iter: Box<dyn Iterator<Item = T> + Send>,
+/// Progress of a long computation shared between threads.
+/// The callback receives `(done, total)` about every 1% of the total work,
+/// and cancels the computation by returning `false`.
+pub struct Progress<'a> {
+    done: AtomicU64,
+    total: u64,
+    step: u64,
+    cancelled: AtomicBool,
+    callback: &'a (dyn Fn(u64, u64) -> bool + Sync),
 }
 
-impl<T> Iterator for IterWrapper<T>
-where
-    T: Send,
-{
-    type Item = T;
+impl<'a> Progress<'a> {
+    /// Create a new progress of `total` units of work.
+    pub fn new(total: u64, callback: &'a (dyn Fn(u64, u64) -> bool + Sync)) -> Self {
+        Progress {
+            done: AtomicU64::new(0),
+            total,
+            step: (total / 100).max(1),
+            cancelled: AtomicBool::new(false),
+            callback,
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
+    /// Record a finished unit of work.
+    /// Returns `PokercraftLocalError::Cancelled` once the callback cancelled the computation.
+    pub fn tick(&self) -> Result<(), PokercraftLocalError> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(PokercraftLocalError::Cancelled);
+        }
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if (done.is_multiple_of(self.step) || done == self.total)
+            && !(self.callback)(done, self.total)
+        {
+            self.cancelled.store(true, Ordering::Relaxed);
+            return Err(PokercraftLocalError::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+/// A JS progress callback of `(done, total) => boolean | void` for `Progress`.
+/// Returning `false` or throwing cancels the computation.
+/// Only the thread which created this calls the callback; Reports from other threads are skipped.
+#[cfg(feature = "wasm")]
+pub(crate) struct JsProgress {
+    callback: Option<js_sys::Function>,
+    /// The error thrown by the callback.
+    error: std::cell::RefCell<Option<wasm_bindgen::JsValue>>,
+    /// The thread which created this, the only one accessing `callback` and `error`.
+    owner: std::thread::ThreadId,
+}
+
+// SAFETY: `callback` and `error` are only accessed on the `owner` thread,
+// so sharing references with other threads (like rayon workers) never races.
+// `JsProgress` is still not `Send`, so it is also dropped on the `owner` thread.
+#[cfg(feature = "wasm")]
+unsafe impl Sync for JsProgress {}
+
+#[cfg(feature = "wasm")]
+impl JsProgress {
+    pub(crate) fn new(callback: Option<js_sys::Function>) -> Self {
+        JsProgress {
+            callback,
+            error: std::cell::RefCell::new(None),
+            owner: std::thread::current().id(),
+        }
+    }
+
+    /// Check if the current thread is the one which created this.
+    fn is_owner(&self) -> bool {
+        std::thread::current().id() == self.owner
+    }
+
+    /// Call the callback; Returns `false` if the computation should be cancelled.
+    pub(crate) fn report(&self, done: u64, total: u64) -> bool {
+        let Some(callback) = self.callback.as_ref().filter(|_| self.is_owner()) else {
+            return true;
+        };
+        match callback.call2(
+            &wasm_bindgen::JsValue::NULL,
+            &(done as f64).into(),
+            &(total as f64).into(),
+        ) {
+            Ok(result) => result.as_bool() != Some(false),
+            Err(err) => {
+                *self.error.borrow_mut() = Some(err);
+                false
+            }
+        }
+    }
+
+    /// Convert the error of the computation, preferring the error thrown by the callback.
+    pub(crate) fn convert_error(&self, err: PokercraftLocalError) -> wasm_bindgen::JsValue {
+        if !self.is_owner() {
+            return err.into();
+        }
+        self.error.borrow_mut().take().unwrap_or_else(|| err.into())
     }
 }
 
@@ -118,4 +320,35 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_combinations_from_index() {
+        assert_eq!(binomial(52, 5), 2598960);
+        assert_eq!(binomial(3, 5), 0);
+        assert_eq!(binomial(4, 0), 1);
+
+        let candidates = ["apple", "banana", "cherry", "duel", "egg", "fox", "grape"];
+        let all = FixedSizedCombinationIterator::<&'static str, 3>::new(candidates.iter().copied())
+            .collect::<Vec<_>>();
+        assert_eq!(all.len() as u64, binomial(7, 3));
+        for start in 0..=all.len() {
+            let from = FixedSizedCombinationIterator::<&'static str, 3>::new_from(
+                candidates.iter().copied(),
+                start as u64,
+            )
+            .collect::<Vec<_>>();
+            assert_eq!(from, all[start..]);
+        }
+    }
+
+    #[test]
+    fn test_set_parallelism() -> Result<(), PokercraftLocalError> {
+        set_parallelism(2)?;
+        assert_eq!(parallelism(), 2);
+        assert_eq!(in_thread_pool(rayon::current_num_threads), 2);
+
+        set_parallelism(0)?;
+        assert_eq!(parallelism(), rayon::current_num_threads());
+        Ok(())
+    }
 }
@@ -0,0 +1,72 @@
+//! Regenerates the C header `crates/ffi/include/pokercraft.h` from the exported functions.
+//!
+//! Run `cargo run -p pokercraft-ffi-headergen` after changing the bindings,
+//! or with `--check` to fail if the committed header is outdated.
+
+use std::path::{Path, PathBuf};
+
+const HEADER_PATH: &str = "include/pokercraft.h";
+
+fn ffi_crate_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .join("ffi")
+}
+
+/// Generate the header of the ffi crate in `crate_dir`, configured by its `cbindgen.toml`.
+fn generate_header(crate_dir: &Path) -> std::io::Result<String> {
+    let bindings = cbindgen::generate(crate_dir).map_err(std::io::Error::other)?;
+    let mut header = Vec::new();
+    bindings.write(&mut header);
+    String::from_utf8(header).map_err(std::io::Error::other)
+}
+
+fn main() -> std::io::Result<()> {
+    let check = match std::env::args().nth(1).as_deref() {
+        None => false,
+        Some("--check") => true,
+        Some(arg) => {
+            eprintln!(
+                "Unknown argument \"{}\"; Usage: generate_c_header [--check]",
+                arg
+            );
+            std::process::exit(2);
+        }
+    };
+    let crate_dir = ffi_crate_dir();
+    let path = crate_dir.join(HEADER_PATH);
+    let header = generate_header(&crate_dir)?;
+    // Avoid touching an unchanged header
+    if std::fs::read_to_string(&path).ok().as_deref() == Some(header.as_str()) {
+        return Ok(());
+    }
+    if check {
+        eprintln!(
+            "Outdated header {}; Run `cargo run -p pokercraft-ffi-headergen`",
+            path.display()
+        );
+        std::process::exit(1);
+    }
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(&path, header)?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_committed_header_is_up_to_date() -> std::io::Result<()> {
+        let crate_dir = ffi_crate_dir();
+        let committed = std::fs::read_to_string(crate_dir.join(HEADER_PATH))?;
+        assert!(
+            committed == generate_header(&crate_dir)?,
+            "{} is outdated; Run `cargo run -p pokercraft-ffi-headergen`",
+            HEADER_PATH
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+#ifndef POKERCRAFT_H
+#define POKERCRAFT_H
+
+/* Generated by cbindgen from crates/ffi/src/lib.rs; Do not edit manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Result status of every function.
+ */
+typedef enum PcStatus {
+  PC_STATUS_OK = 0,
+  /**
+   * A required pointer is null.
+   */
+  PC_STATUS_NULL_POINTER = 1,
+  /**
+   * An argument is malformed, like an invalid card string.
+   */
+  PC_STATUS_INVALID_ARGUMENT = 2,
+  /**
+   * Calculation failed or panicked.
+   */
+  PC_STATUS_CALCULATION_ERROR = 3,
+} PcStatus;
+
+/**
+ * Numerized rank of a 5-card hand; Compare `category` first, then `value`.
+ */
+typedef struct PcHandRank {
+  /**
+   * 0 for high card, up to 8 for straight flush.
+   */
+  uint8_t category;
+  uint64_t value;
+} PcHandRank;
+
+/**
+ * Summary of bankroll simulations.
+ */
+typedef struct PcBankrollResult {
+  uint64_t simulations;
+  double bankruptcy_rate;
+  double survival_rate;
+  double profitable_rate;
+} PcBankrollResult;
+
+/**
+ * Expected value of jamming all-in against a single player, in chips relative to folding.
+ */
+typedef struct PcShoveEv {
+  double fold_frequency;
+  double called_equity;
+  /**
+   * Chips at risk when called, which is the smaller stack.
+   */
+  double risk;
+  double called_ev;
+  double ev;
+} PcShoveEv;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Get the library version as a static nul-terminated string.
+ */
+const char *pc_version(void);
+
+/**
+ * Get the error message of the last call returning `PcStatus` on this thread,
+ * or null if it succeeded. The pointer is valid until the next call.
+ */
+const char *pc_last_error_message(void);
+
+/**
+ * Evaluate the best 5-card hand from 5 or more concatenated cards, like `"AsKsQsJsTs2c"`.
+ *
+ * # Safety
+ * `cards` should be a valid nul-terminated string,
+ * and `out_rank` should be a valid pointer.
+ */
+enum PcStatus pc_evaluate_hand(const char *cards, struct PcHandRank *out_rank);
+
+/**
+ * Calculate equities of `num_hands` players on the given board.
+ * Each hand is a 2-card string like `"AsKd"`, and `board` has 0 to 5 cards
+ * (null means no board). Equities are written into `out_equities`,
+ * which should have room for `num_hands` values.
+ *
+ * # Safety
+ * `hands` should point to `num_hands` valid nul-terminated strings,
+ * `board` should be null or a valid nul-terminated string,
+ * and `out_equities` should point to `num_hands` writable values.
+ */
+enum PcStatus pc_calculate_equity(const char *const *hands,
+                                  size_t num_hands,
+                                  const char *board,
+                                  bool parallel,
+                                  double *out_equities);
+
+/**
+ * Calculate probabilities of winning outright, chopping and losing of `num_hands` players,
+ * with the same arguments as `pc_calculate_equity`. Row `i` of `out_rates` gets
+ * `num_hands + 1` values of the `i`-th player; Winning outright, chopping 2 ways
+ * up to `num_hands` ways, then losing.
+ *
+ * # Safety
+ * Same as `pc_calculate_equity`, except `out_rates` should point to
+ * `num_hands * (num_hands + 1)` writable values.
+ */
+enum PcStatus pc_calculate_outcome_rates(const char *const *hands,
+                                         size_t num_hands,
+                                         const char *board,
+                                         bool parallel,
+                                         double *out_rates);
+
+/**
+ * Simulate bankroll with relative returns of `num_returns` tournament results.
+ * See `simulate_core` of pokercraft-core for parameters.
+ *
+ * # Safety
+ * `relative_returns` should point to `num_returns` values,
+ * and `out_result` should be a valid pointer.
+ */
+enum PcStatus pc_simulate_bankroll(double initial_capital,
+                                   const double *relative_returns,
+                                   size_t num_returns,
+                                   uint32_t max_iteration,
+                                   double profit_exit_multiplier,
+                                   uint32_t simulation_count,
+                                   struct PcBankrollResult *out_result);
+
+/**
+ * Get the minimum equity to call `bet` into `pot`.
+ *
+ * # Safety
+ * `out` should be a valid pointer.
+ */
+enum PcStatus pc_required_equity(double bet, double pot, double *out);
+
+/**
+ * Get the minimum defense frequency against `bet` into `pot`.
+ *
+ * # Safety
+ * `out` should be a valid pointer.
+ */
+enum PcStatus pc_minimum_defense_frequency(double bet, double pot, double *out);
+
+/**
+ * Get the stack-to-pot ratio of the effective stack.
+ *
+ * # Safety
+ * `out` should be a valid pointer.
+ */
+enum PcStatus pc_stack_to_pot_ratio(double effective_stack, double pot, double *out);
+
+/**
+ * Get the expected value of jamming `hand` like `"AsKd"` into `pot` on the board
+ * (null means no board), against a villain calling with `calling_range` like `"QQ+,AK"`.
+ * See `shove_ev` of pokercraft-core for parameters.
+ *
+ * # Safety
+ * `hand` and `calling_range` should be valid nul-terminated strings,
+ * `board` should be null or a valid nul-terminated string,
+ * and `out_result` should be a valid pointer.
+ */
+enum PcStatus pc_shove_ev(const char *hand,
+                          const char *calling_range,
+                          const char *board,
+                          double pot,
+                          double hero_stack,
+                          double villain_stack,
+                          uint64_t trials,
+                          struct PcShoveEv *out_result);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* POKERCRAFT_H */
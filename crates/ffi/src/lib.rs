@@ -0,0 +1,567 @@
+//! C ABI bindings for pokercraft-core.
+//!
+//! Every function returns a `PcStatus`, and writes results into out-pointers.
+//! On failure, `pc_last_error_message` describes the error of the calling thread.
+//! The header `include/pokercraft.h` is regenerated with `cargo run -p pokercraft-ffi-headergen`.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, UnwindSafe};
+
+use pokercraft_core::bankroll::simulate_core;
+use pokercraft_core::card::{self, Card, Hand, HandRank};
+use pokercraft_core::equity::EquityResult;
+use pokercraft_core::errors::PokercraftLocalError;
+use pokercraft_core::math;
+use pokercraft_core::range::HandRange;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Result status of every function.
+#[repr(C)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PcStatus {
+    Ok = 0,
+    /// A required pointer is null.
+    NullPointer = 1,
+    /// An argument is malformed, like an invalid card string.
+    InvalidArgument = 2,
+    /// Calculation failed or panicked.
+    CalculationError = 3,
+}
+
+/// Numerized rank of a 5-card hand; Compare `category` first, then `value`.
+#[repr(C)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct PcHandRank {
+    /// 0 for high card, up to 8 for straight flush.
+    pub category: u8,
+    pub value: u64,
+}
+
+/// Summary of bankroll simulations.
+#[repr(C)]
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct PcBankrollResult {
+    pub simulations: u64,
+    pub bankruptcy_rate: f64,
+    pub survival_rate: f64,
+    pub profitable_rate: f64,
+}
+
+/// Expected value of jamming all-in against a single player, in chips relative to folding.
+#[repr(C)]
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct PcShoveEv {
+    pub fold_frequency: f64,
+    pub called_equity: f64,
+    /// Chips at risk when called, which is the smaller stack.
+    pub risk: f64,
+    pub called_ev: f64,
+    pub ev: f64,
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', ""))
+        .unwrap_or_else(|_| CString::new("Unknown error").unwrap());
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Run the given function, recording its error and catching panics.
+/// The last error is cleared on success.
+fn guard<F>(f: F) -> PcStatus
+where
+    F: FnOnce() -> Result<(), (PcStatus, String)> + UnwindSafe,
+{
+    match catch_unwind(f) {
+        Ok(Ok(())) => {
+            LAST_ERROR.with(|last| *last.borrow_mut() = None);
+            PcStatus::Ok
+        }
+        Ok(Err((status, message))) => {
+            set_last_error(message);
+            status
+        }
+        Err(_) => {
+            set_last_error("Panicked during calculation".to_string());
+            PcStatus::CalculationError
+        }
+    }
+}
+
+fn invalid(err: PokercraftLocalError) -> (PcStatus, String) {
+    (PcStatus::InvalidArgument, err.to_string())
+}
+
+fn calculation(err: PokercraftLocalError) -> (PcStatus, String) {
+    (PcStatus::CalculationError, err.to_string())
+}
+
+/// Read a nul-terminated string.
+///
+/// # Safety
+/// `s` should be null or a valid nul-terminated string.
+unsafe fn read_str<'a>(s: *const c_char, name: &str) -> Result<&'a str, (PcStatus, String)> {
+    if s.is_null() {
+        return Err((PcStatus::NullPointer, format!("{} is null", name)));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| (PcStatus::InvalidArgument, format!("{} is not UTF-8", name)))
+}
+
+/// Parse card lists like `"AsKd"` or `"As Kd"`.
+fn parse_cards(s: &str) -> Result<Vec<Card>, (PcStatus, String)> {
+    card::parse_cards(s).map_err(invalid)
+}
+
+/// Parse exactly `N` cards like `"AsKd"`.
+fn parse_cards_exact<const N: usize>(s: &str) -> Result<[Card; N], (PcStatus, String)> {
+    card::parse_cards_exact(s).map_err(invalid)
+}
+
+/// Get the library version as a static nul-terminated string.
+#[no_mangle]
+pub extern "C" fn pc_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// Get the error message of the last call returning `PcStatus` on this thread,
+/// or null if it succeeded. The pointer is valid until the next call.
+#[no_mangle]
+pub extern "C" fn pc_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Evaluate the best 5-card hand from 5 or more concatenated cards, like `"AsKsQsJsTs2c"`.
+///
+/// # Safety
+/// `cards` should be a valid nul-terminated string,
+/// and `out_rank` should be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn pc_evaluate_hand(
+    cards: *const c_char,
+    out_rank: *mut PcHandRank,
+) -> PcStatus {
+    guard(|| {
+        let cards = parse_cards(read_str(cards, "cards")?)?;
+        if out_rank.is_null() {
+            return Err((PcStatus::NullPointer, "out_rank is null".to_string()));
+        }
+        let (_best5, rank) = HandRank::find_best5(&cards).map_err(invalid)?;
+        let (category, value) = rank.numerize();
+        *out_rank = PcHandRank { category, value };
+        Ok(())
+    })
+}
+
+/// Calculate equities of `num_hands` players on the given board.
+/// Each hand is a 2-card string like `"AsKd"`, and `board` has 0 to 5 cards
+/// (null means no board). Equities are written into `out_equities`,
+/// which should have room for `num_hands` values.
+///
+/// # Safety
+/// `hands` should point to `num_hands` valid nul-terminated strings,
+/// `board` should be null or a valid nul-terminated string,
+/// and `out_equities` should point to `num_hands` writable values.
+#[no_mangle]
+pub unsafe extern "C" fn pc_calculate_equity(
+    hands: *const *const c_char,
+    num_hands: usize,
+    board: *const c_char,
+    parallel: bool,
+    out_equities: *mut f64,
+) -> PcStatus {
+    guard(|| {
+        if out_equities.is_null() {
+            return Err((PcStatus::NullPointer, "out_equities is null".to_string()));
+        }
+        let result = calculate_equity(hands, num_hands, board, parallel)?;
+        let out = std::slice::from_raw_parts_mut(out_equities, num_hands);
+        for (i, equity) in out.iter_mut().enumerate() {
+            *equity = result.get_equity(i).map_err(calculation)?;
+        }
+        Ok(())
+    })
+}
+
+/// Calculate equities of `num_hands` players like `pc_calculate_equity`.
+///
+/// # Safety
+/// Same as `pc_calculate_equity`.
+unsafe fn calculate_equity(
+    hands: *const *const c_char,
+    num_hands: usize,
+    board: *const c_char,
+    parallel: bool,
+) -> Result<EquityResult, (PcStatus, String)> {
+    if hands.is_null() {
+        return Err((PcStatus::NullPointer, "hands is null".to_string()));
+    }
+    let mut cards_people: Vec<Hand> = Vec::with_capacity(num_hands);
+    for &hand in std::slice::from_raw_parts(hands, num_hands) {
+        let [card1, card2] = parse_cards_exact::<2>(read_str(hand, "hand")?)?;
+        cards_people.push((card1, card2));
+    }
+    let board = if board.is_null() {
+        Vec::new()
+    } else {
+        parse_cards(read_str(board, "board")?)?
+    };
+    EquityResult::new(cards_people, board, parallel).map_err(calculation)
+}
+
+/// Calculate probabilities of winning outright, chopping and losing of `num_hands` players,
+/// with the same arguments as `pc_calculate_equity`. Row `i` of `out_rates` gets
+/// `num_hands + 1` values of the `i`-th player; Winning outright, chopping 2 ways
+/// up to `num_hands` ways, then losing.
+///
+/// # Safety
+/// Same as `pc_calculate_equity`, except `out_rates` should point to
+/// `num_hands * (num_hands + 1)` writable values.
+#[no_mangle]
+pub unsafe extern "C" fn pc_calculate_outcome_rates(
+    hands: *const *const c_char,
+    num_hands: usize,
+    board: *const c_char,
+    parallel: bool,
+    out_rates: *mut f64,
+) -> PcStatus {
+    guard(|| {
+        if out_rates.is_null() {
+            return Err((PcStatus::NullPointer, "out_rates is null".to_string()));
+        }
+        let result = calculate_equity(hands, num_hands, board, parallel)?;
+        let out = std::slice::from_raw_parts_mut(out_rates, num_hands * (num_hands + 1));
+        for (i, row) in out.chunks_exact_mut(num_hands + 1).enumerate() {
+            let rates = result.outcome_rates(i).map_err(calculation)?;
+            row[0] = rates.win_outright;
+            row[1..num_hands].copy_from_slice(&rates.chops);
+            row[num_hands] = rates.lose;
+        }
+        Ok(())
+    })
+}
+
+/// Simulate bankroll with relative returns of `num_returns` tournament results.
+/// See `simulate_core` of pokercraft-core for parameters.
+///
+/// # Safety
+/// `relative_returns` should point to `num_returns` values,
+/// and `out_result` should be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn pc_simulate_bankroll(
+    initial_capital: f64,
+    relative_returns: *const f64,
+    num_returns: usize,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+    out_result: *mut PcBankrollResult,
+) -> PcStatus {
+    guard(|| {
+        if relative_returns.is_null() || out_result.is_null() {
+            return Err((
+                PcStatus::NullPointer,
+                "relative_returns or out_result is null".to_string(),
+            ));
+        }
+        let returns = std::slice::from_raw_parts(relative_returns, num_returns).to_vec();
+        let metric = simulate_core(
+            initial_capital,
+            returns,
+            max_iteration,
+            profit_exit_multiplier,
+            simulation_count,
+        )
+        .map_err(invalid)?;
+        *out_result = PcBankrollResult {
+            simulations: metric.len() as u64,
+            bankruptcy_rate: metric.get_bankruptcy_rate(),
+            survival_rate: metric.get_survival_rate(),
+            profitable_rate: metric.get_profitable_rate(),
+        };
+        Ok(())
+    })
+}
+
+/// Write the result of a bet math function into `out`.
+unsafe fn write_math(
+    out: *mut f64,
+    result: Result<f64, PokercraftLocalError>,
+) -> Result<(), (PcStatus, String)> {
+    if out.is_null() {
+        return Err((PcStatus::NullPointer, "out is null".to_string()));
+    }
+    *out = result.map_err(invalid)?;
+    Ok(())
+}
+
+/// Get the minimum equity to call `bet` into `pot`.
+///
+/// # Safety
+/// `out` should be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn pc_required_equity(bet: f64, pot: f64, out: *mut f64) -> PcStatus {
+    guard(|| write_math(out, math::required_equity(bet, pot)))
+}
+
+/// Get the minimum defense frequency against `bet` into `pot`.
+///
+/// # Safety
+/// `out` should be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn pc_minimum_defense_frequency(
+    bet: f64,
+    pot: f64,
+    out: *mut f64,
+) -> PcStatus {
+    guard(|| write_math(out, math::minimum_defense_frequency(bet, pot)))
+}
+
+/// Get the stack-to-pot ratio of the effective stack.
+///
+/// # Safety
+/// `out` should be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn pc_stack_to_pot_ratio(
+    effective_stack: f64,
+    pot: f64,
+    out: *mut f64,
+) -> PcStatus {
+    guard(|| write_math(out, math::stack_to_pot_ratio(effective_stack, pot)))
+}
+
+/// Get the expected value of jamming `hand` like `"AsKd"` into `pot` on the board
+/// (null means no board), against a villain calling with `calling_range` like `"QQ+,AK"`.
+/// See `shove_ev` of pokercraft-core for parameters.
+///
+/// # Safety
+/// `hand` and `calling_range` should be valid nul-terminated strings,
+/// `board` should be null or a valid nul-terminated string,
+/// and `out_result` should be a valid pointer.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn pc_shove_ev(
+    hand: *const c_char,
+    calling_range: *const c_char,
+    board: *const c_char,
+    pot: f64,
+    hero_stack: f64,
+    villain_stack: f64,
+    trials: u64,
+    out_result: *mut PcShoveEv,
+) -> PcStatus {
+    guard(|| {
+        let [card1, card2] = parse_cards_exact::<2>(read_str(hand, "hand")?)?;
+        let calling_range =
+            HandRange::try_from(read_str(calling_range, "calling_range")?).map_err(invalid)?;
+        let board = if board.is_null() {
+            Vec::new()
+        } else {
+            parse_cards(read_str(board, "board")?)?
+        };
+        if out_result.is_null() {
+            return Err((PcStatus::NullPointer, "out_result is null".to_string()));
+        }
+        let result = math::shove_ev(
+            (card1, card2),
+            &calling_range,
+            &board,
+            pot,
+            hero_stack,
+            villain_stack,
+            trials,
+        )
+        .map_err(invalid)?;
+        *out_result = PcShoveEv {
+            fold_frequency: result.fold_frequency,
+            called_equity: result.called_equity,
+            risk: result.risk,
+            called_ev: result.called_ev,
+            ev: result.ev,
+        };
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(pc_last_error_message()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_ffi() {
+        let version = unsafe { CStr::from_ptr(pc_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+
+        let mut flush = PcHandRank::default();
+        let mut pair = PcHandRank::default();
+        let cards = CString::new("As Ks 2s 7s 9s 9d").unwrap();
+        assert_eq!(
+            unsafe { pc_evaluate_hand(cards.as_ptr(), &mut flush) },
+            PcStatus::Ok
+        );
+        let cards = CString::new("AsAd2c7h9d").unwrap();
+        assert_eq!(
+            unsafe { pc_evaluate_hand(cards.as_ptr(), &mut pair) },
+            PcStatus::Ok
+        );
+        assert_eq!((flush.category, pair.category), (5, 1));
+
+        let invalid = CString::new("AsXx2c7h9d").unwrap();
+        assert_eq!(
+            unsafe { pc_evaluate_hand(invalid.as_ptr(), &mut pair) },
+            PcStatus::InvalidArgument
+        );
+        assert!(last_error().contains('X'));
+        assert_eq!(
+            unsafe { pc_evaluate_hand(std::ptr::null(), &mut pair) },
+            PcStatus::NullPointer
+        );
+
+        let hands = [CString::new("AsAh").unwrap(), CString::new("KdKc").unwrap()];
+        let hand_ptrs: Vec<*const c_char> = hands.iter().map(|hand| hand.as_ptr()).collect();
+        let board = CString::new("2c7d9h4s").unwrap();
+        let mut equities = [0.0; 2];
+        let status = unsafe {
+            pc_calculate_equity(
+                hand_ptrs.as_ptr(),
+                2,
+                board.as_ptr(),
+                false,
+                equities.as_mut_ptr(),
+            )
+        };
+        assert_eq!(status, PcStatus::Ok);
+        assert!((equities[0] - 42.0 / 44.0).abs() < 1e-9);
+        assert!((equities[0] + equities[1] - 1.0).abs() < 1e-9);
+
+        let mut rates = [0.0; 6];
+        let status = unsafe {
+            pc_calculate_outcome_rates(
+                hand_ptrs.as_ptr(),
+                2,
+                board.as_ptr(),
+                false,
+                rates.as_mut_ptr(),
+            )
+        };
+        assert_eq!(status, PcStatus::Ok);
+        assert_eq!(
+            rates,
+            [42.0 / 44.0, 0.0, 2.0 / 44.0, 2.0 / 44.0, 0.0, 42.0 / 44.0]
+        );
+        let status = unsafe {
+            pc_calculate_outcome_rates(
+                hand_ptrs.as_ptr(),
+                2,
+                board.as_ptr(),
+                false,
+                std::ptr::null_mut(),
+            )
+        };
+        assert_eq!(status, PcStatus::NullPointer);
+
+        let mut bankroll = PcBankrollResult::default();
+        let returns = [1.0, -1.0, 2.0];
+        let status =
+            unsafe { pc_simulate_bankroll(10.0, returns.as_ptr(), 3, 100, 2.0, 50, &mut bankroll) };
+        assert_eq!(status, PcStatus::Ok);
+        assert_eq!(bankroll.simulations, 50);
+        let status =
+            unsafe { pc_simulate_bankroll(-1.0, returns.as_ptr(), 3, 100, 2.0, 50, &mut bankroll) };
+        assert_eq!(status, PcStatus::InvalidArgument);
+        assert!(last_error().contains("Initial capital"));
+
+        let mut value = 0.0;
+        assert_eq!(
+            unsafe { pc_required_equity(50.0, 100.0, &mut value) },
+            PcStatus::Ok
+        );
+        assert!((value - 0.25).abs() < 1e-9);
+        assert_eq!(
+            unsafe { pc_minimum_defense_frequency(100.0, 100.0, &mut value) },
+            PcStatus::Ok
+        );
+        assert!((value - 0.5).abs() < 1e-9);
+        assert_eq!(
+            unsafe { pc_stack_to_pot_ratio(450.0, 100.0, &mut value) },
+            PcStatus::Ok
+        );
+        assert_eq!(value, 4.5);
+        assert_eq!(
+            unsafe { pc_stack_to_pot_ratio(450.0, 0.0, &mut value) },
+            PcStatus::InvalidArgument
+        );
+        assert_eq!(
+            unsafe { pc_required_equity(50.0, 100.0, std::ptr::null_mut()) },
+            PcStatus::NullPointer
+        );
+
+        let mut shove = PcShoveEv::default();
+        let aces = CString::new("AsAh").unwrap();
+        let kings = CString::new("KK").unwrap();
+        let board = CString::new("2c7d9h4s").unwrap();
+        let shove_ev = |board: *const c_char, out: *mut PcShoveEv| unsafe {
+            pc_shove_ev(
+                aces.as_ptr(),
+                kings.as_ptr(),
+                board,
+                100.0,
+                300.0,
+                200.0,
+                1000,
+                out,
+            )
+        };
+        assert_eq!(shove_ev(board.as_ptr(), &mut shove), PcStatus::Ok);
+        assert!((shove.called_equity - 42.0 / 44.0).abs() < 1e-9);
+        assert_eq!(shove.risk, 200.0);
+        assert_eq!(
+            shove_ev(board.as_ptr(), std::ptr::null_mut()),
+            PcStatus::NullPointer
+        );
+        assert_eq!(
+            unsafe {
+                pc_shove_ev(
+                    aces.as_ptr(),
+                    kings.as_ptr(),
+                    aces.as_ptr(),
+                    100.0,
+                    300.0,
+                    200.0,
+                    1000,
+                    &mut shove,
+                )
+            },
+            PcStatus::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_last_error_cleared_on_success() {
+        let mut value = 0.0;
+        assert_eq!(
+            unsafe { pc_stack_to_pot_ratio(450.0, 0.0, &mut value) },
+            PcStatus::InvalidArgument
+        );
+        assert!(!pc_last_error_message().is_null());
+        assert_eq!(
+            unsafe { pc_stack_to_pot_ratio(450.0, 100.0, &mut value) },
+            PcStatus::Ok
+        );
+        assert!(pc_last_error_message().is_null());
+    }
+}
@@ -0,0 +1,68 @@
+//! Generates the `Analysis` service of `proto/pokercraft.proto` into `OUT_DIR`.
+//! Messages are written by hand in `src/proto.rs`, so `protoc` is not required.
+
+use tonic_build::manual::{Builder, Method, MethodBuilder, Service};
+
+fn method(name: &str, route_name: &str, input: &str, output: &str) -> MethodBuilder {
+    Method::builder()
+        .name(name)
+        .route_name(route_name)
+        .input_type(format!("crate::proto::{}", input))
+        .output_type(format!("crate::proto::{}", output))
+        .codec_path("tonic_prost::ProstCodec")
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    let service = Service::builder()
+        .name("Analysis")
+        .package("pokercraft")
+        .method(
+            method(
+                "evaluate_equity",
+                "EvaluateEquity",
+                "EquityRequest",
+                "EquityResponse",
+            )
+            .build(),
+        )
+        .method(
+            method(
+                "simulate_bankroll",
+                "SimulateBankroll",
+                "BankrollRequest",
+                "BankrollResponse",
+            )
+            .build(),
+        )
+        .method(
+            method(
+                "parse_histories",
+                "ParseHistories",
+                "HistoryFile",
+                "ParsedHand",
+            )
+            .client_streaming()
+            .server_streaming()
+            .build(),
+        )
+        .method(
+            method("export_hands", "ExportHands", "HistoryFile", "ArrowChunk")
+                .client_streaming()
+                .server_streaming()
+                .build(),
+        )
+        .method(
+            method(
+                "export_hud_stats",
+                "ExportHudStats",
+                "HistoryFile",
+                "ArrowChunk",
+            )
+            .client_streaming()
+            .server_streaming()
+            .build(),
+        )
+        .build();
+    Builder::new().compile(&[service]);
+}
@@ -0,0 +1,26 @@
+use std::net::SocketAddr;
+use std::process::ExitCode;
+
+use clap::Parser;
+
+/// gRPC server of pokercraft analyses; See `proto/pokercraft.proto` for the service.
+#[derive(Parser, Debug)]
+#[command(name = "pokercraft-grpc", version)]
+struct Cli {
+    /// Address to listen on; Bound to localhost by default.
+    #[arg(long, default_value = "127.0.0.1:50051")]
+    address: SocketAddr,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    println!("Listening on {}", cli.address);
+    match pokercraft_grpc::serve(cli.address).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! gRPC service of pokercraft analyses, for embedding the engine in backend pipelines.
+//! The service is defined in `proto/pokercraft.proto`;
+//! Equity and bankroll analyses are shared with the `pokercraft` CLI,
+//! and tables are sent in the Arrow IPC streaming format.
+
+pub mod proto;
+
+use std::future::Future;
+use std::net::SocketAddr;
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status, Streaming};
+
+use pokercraft_cli::bankroll::{self, SimulationArgs};
+use pokercraft_cli::equity::{self, EquityArgs};
+use pokercraft_core::export::{
+    hands_to_record_batch, hud_stats_to_record_batch, write_record_batch, ArrowStreamEncoder,
+    ColumnarFormat,
+};
+use pokercraft_core::parser::{parse_hands, HandHistory};
+use pokercraft_core::stats::HudStatsEngine;
+use pokercraft_core::{CardStyle, PokercraftLocalError};
+
+use proto::analysis_server::{Analysis, AnalysisServer};
+use proto::{
+    parsed_hand, ArrowChunk, BankrollRequest, BankrollResponse, EquityRequest, EquityResponse,
+    HistoryFile, ParsedHand, PlayerEquity, Quantile,
+};
+
+/// Number of messages buffered for a slow client.
+const STREAM_BUFFER_SIZE: usize = 256;
+
+type ResponseStream<T> = ReceiverStream<Result<T, Status>>;
+
+fn to_status(err: PokercraftLocalError) -> Status {
+    Status::invalid_argument(err.to_string())
+}
+
+/// Run the given function on a blocking thread.
+async fn blocking<T, F>(function: F) -> Result<T, Status>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    tokio::task::spawn_blocking(function)
+        .await
+        .map_err(|e| Status::internal(e.to_string()))
+}
+
+/// Run the given analysis on a blocking thread.
+async fn run_blocking<T, F>(analysis: F) -> Result<Response<T>, Status>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, PokercraftLocalError> + Send + 'static,
+{
+    blocking(analysis)
+        .await?
+        .map(Response::new)
+        .map_err(to_status)
+}
+
+/// Run the given producer of a response stream on a new task;
+/// If the producer fails, its error is sent as the last message.
+fn spawn_stream<T, F, Fut>(producer: F) -> ResponseStream<T>
+where
+    T: Send + 'static,
+    F: FnOnce(mpsc::Sender<Result<T, Status>>) -> Fut,
+    Fut: Future<Output = Result<(), Status>> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel(STREAM_BUFFER_SIZE);
+    let producer = producer(sender.clone());
+    tokio::spawn(async move {
+        if let Err(status) = producer.await {
+            let _ = sender.send(Err(status)).await;
+        }
+    });
+    ReceiverStream::new(receiver)
+}
+
+/// Parse all hands of the given file, skipping unparsable hands.
+fn parse_file(file: &HistoryFile) -> Vec<HandHistory> {
+    parse_hands(file.text.as_bytes())
+        .filter_map(Result::ok)
+        .collect()
+}
+
+/// Implementation of the `Analysis` service.
+#[derive(Default, Debug)]
+pub struct AnalysisService;
+
+#[tonic::async_trait]
+impl Analysis for AnalysisService {
+    async fn evaluate_equity(
+        &self,
+        request: Request<EquityRequest>,
+    ) -> Result<Response<EquityResponse>, Status> {
+        let request = request.into_inner();
+        let args = EquityArgs {
+            players: request.players,
+            board: request.board,
+            trials: match request.trials {
+                0 => pokercraft_cli::default_trials(),
+                trials => trials,
+            },
+            card_style: CardStyle::default(),
+        };
+        run_blocking(move || {
+            let report = equity::build_report(&args)?;
+            Ok(EquityResponse {
+                exact: report.exact,
+                boards: report.boards,
+                players: report
+                    .players
+                    .into_iter()
+                    .map(|player| PlayerEquity {
+                        range: player.range,
+                        combos: player.combos as u64,
+                        equity: player.equity,
+                        win: player.win,
+                        tie: player.tie,
+                    })
+                    .collect(),
+            })
+        })
+        .await
+    }
+
+    async fn simulate_bankroll(
+        &self,
+        request: Request<BankrollRequest>,
+    ) -> Result<Response<BankrollResponse>, Status> {
+        let request = request.into_inner();
+        let args = SimulationArgs {
+            capital: request.capital,
+            iterations: match request.iterations {
+                0 => bankroll::default_iterations(),
+                iterations => iterations,
+            },
+            exit_multiplier: request.exit_multiplier,
+            simulations: match request.simulations {
+                0 => bankroll::default_simulations(),
+                simulations => simulations,
+            },
+            cash_flow_every: request.cash_flow_every,
+            cash_flow: request.cash_flow,
+            cash_flow_fraction: request.cash_flow_fraction,
+        };
+        run_blocking(move || {
+            let report = bankroll::build_report_from_results(request.results, &args)?;
+            Ok(BankrollResponse {
+                simulations: report.simulations as u64,
+                bankruptcy_rate: report.bankruptcy_rate,
+                survival_rate: report.survival_rate,
+                profitable_rate: report.profitable_rate,
+                final_capital_quantiles: report
+                    .final_capital_quantiles
+                    .into_iter()
+                    .map(|quantile| Quantile {
+                        quantile: quantile.quantile,
+                        capital: quantile.capital,
+                    })
+                    .collect(),
+            })
+        })
+        .await
+    }
+
+    type ParseHistoriesStream = ResponseStream<ParsedHand>;
+
+    async fn parse_histories(
+        &self,
+        request: Request<Streaming<HistoryFile>>,
+    ) -> Result<Response<Self::ParseHistoriesStream>, Status> {
+        let mut files = request.into_inner();
+        Ok(Response::new(spawn_stream(|sender| async move {
+            while let Some(file) = files.message().await? {
+                let sender = sender.clone();
+                // Returns `false` if the client has gone
+                let connected = blocking(move || {
+                    parse_hands(file.text.as_bytes()).all(|hand| {
+                        let result = match hand {
+                            Ok(hand) => parsed_hand::Result::Hand((&hand).into()),
+                            Err(err) => parsed_hand::Result::Error(err.to_string()),
+                        };
+                        sender
+                            .blocking_send(Ok(ParsedHand {
+                                file: file.name.clone(),
+                                result: Some(result),
+                            }))
+                            .is_ok()
+                    })
+                })
+                .await?;
+                if !connected {
+                    break;
+                }
+            }
+            Ok(())
+        })))
+    }
+
+    type ExportHandsStream = ResponseStream<ArrowChunk>;
+
+    async fn export_hands(
+        &self,
+        request: Request<Streaming<HistoryFile>>,
+    ) -> Result<Response<Self::ExportHandsStream>, Status> {
+        let mut files = request.into_inner();
+        let schema = hands_to_record_batch(&[]).map_err(to_status)?.schema();
+        let mut encoder = ArrowStreamEncoder::new(&schema).map_err(to_status)?;
+        Ok(Response::new(spawn_stream(|sender| async move {
+            while let Some(file) = files.message().await? {
+                let data;
+                (encoder, data) = blocking(move || {
+                    let data = hands_to_record_batch(&parse_file(&file))
+                        .and_then(|batch| encoder.encode(&batch));
+                    (encoder, data)
+                })
+                .await?;
+                let chunk = ArrowChunk {
+                    data: data.map_err(to_status)?,
+                };
+                if sender.send(Ok(chunk)).await.is_err() {
+                    return Ok(());
+                }
+            }
+            let data = encoder.finish().map_err(to_status)?;
+            let _ = sender.send(Ok(ArrowChunk { data })).await;
+            Ok(())
+        })))
+    }
+
+    type ExportHudStatsStream = ResponseStream<ArrowChunk>;
+
+    async fn export_hud_stats(
+        &self,
+        request: Request<Streaming<HistoryFile>>,
+    ) -> Result<Response<Self::ExportHudStatsStream>, Status> {
+        let mut files = request.into_inner();
+        Ok(Response::new(spawn_stream(|sender| async move {
+            let mut engine = HudStatsEngine::new();
+            while let Some(file) = files.message().await? {
+                engine = blocking(move || {
+                    engine.add_hands(&parse_file(&file));
+                    engine
+                })
+                .await?;
+            }
+            let data = blocking(move || {
+                let batch = hud_stats_to_record_batch(engine.players())?;
+                let mut data = Vec::new();
+                write_record_batch(&batch, ColumnarFormat::ArrowIpcStream, &mut data)?;
+                Ok::<_, PokercraftLocalError>(data)
+            })
+            .await?
+            .map_err(to_status)?;
+            let _ = sender.send(Ok(ArrowChunk { data })).await;
+            Ok(())
+        })))
+    }
+}
+
+/// Serve the `Analysis` service on the given address until interrupted.
+pub async fn serve(address: SocketAddr) -> Result<(), PokercraftLocalError> {
+    tonic::transport::Server::builder()
+        .add_service(AnalysisServer::new(AnalysisService))
+        .serve_with_shutdown(address, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .map_err(|e| PokercraftLocalError::GeneralError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    use arrow::ipc::reader::StreamReader;
+    use proto::analysis_client::AnalysisClient;
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    const SAMPLE_HAND: &str = "Poker Hand #TM1: Tournament #1, Test Hold'em No Limit - Level1(100/200) - 2025/08/01 00:00:00
+Table '1' 4-max Seat #1 is the button
+Seat 1: Hero (10,000 in chips)
+Seat 2: aaaa (10,000 in chips)
+Seat 3: bbbb (10,000 in chips)
+Seat 4: cccc (10,000 in chips)
+aaaa: posts small blind 100
+bbbb: posts big blind 200
+*** HOLE CARDS ***
+Dealt to Hero [As Ks]
+cccc: raises 400 to 600
+Hero: raises 1,200 to 1,800
+aaaa: folds
+bbbb: folds
+cccc: calls 1,200
+*** FLOP *** [2c 7d 9h]
+cccc: checks
+Hero: bets 1,000
+cccc: calls 1,000
+*** TURN *** [2c 7d 9h] [Jd]
+cccc: checks
+Hero: checks
+*** RIVER *** [2c 7d 9h Jd] [3s]
+cccc: checks
+Hero: checks
+cccc: shows [Qd Qh]
+Hero: shows [As Ks]
+*** SHOWDOWN ***
+cccc collected 5,900 from pot
+*** SUMMARY ***
+";
+
+    async fn connect() -> AnalysisClient<tonic::transport::Channel> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(AnalysisServer::new(AnalysisService))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        AnalysisClient::connect(format!("http://{}", address))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_equity() {
+        let mut client = connect().await;
+        let response = client
+            .evaluate_equity(EquityRequest {
+                players: vec!["AsAh".to_string(), "KdKc".to_string()],
+                board: "2c7d9h4s".to_string(),
+                trials: 0,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.exact);
+        assert_eq!(response.boards, 44);
+        assert!((response.players[0].win - 42.0 / 44.0).abs() < 1e-9);
+
+        let status = client
+            .evaluate_equity(EquityRequest {
+                players: vec!["AsAh".to_string(), "KdKc".to_string()],
+                board: "2c7d9h4x".to_string(),
+                trials: 0,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_parse_histories() {
+        let mut client = connect().await;
+        let files = tokio_stream::iter(vec![HistoryFile {
+            name: "sample.txt".to_string(),
+            text: SAMPLE_HAND.to_string(),
+        }]);
+        let mut stream = client.parse_histories(files).await.unwrap().into_inner();
+        let mut hands = Vec::new();
+        while let Some(parsed) = stream.message().await.unwrap() {
+            assert_eq!(parsed.file, "sample.txt");
+            match parsed.result {
+                Some(parsed_hand::Result::Hand(hand)) => hands.push(hand),
+                other => panic!("Unexpected result: {:?}", other),
+            }
+        }
+        assert_eq!(hands.len(), 1);
+        assert_eq!(hands[0].id, "TM1");
+        assert_eq!(hands[0].players, vec!["Hero", "aaaa", "bbbb", "cccc"]);
+        assert_eq!(hands[0].community_cards.len(), 5);
+        assert_eq!(hands[0].datetime, "2025-08-01T00:00:00");
+    }
+
+    #[tokio::test]
+    async fn test_export_hands() {
+        let mut client = connect().await;
+        let files = tokio_stream::iter(vec![
+            HistoryFile {
+                name: "sample.txt".to_string(),
+                text: SAMPLE_HAND.to_string(),
+            },
+            HistoryFile {
+                name: "empty.txt".to_string(),
+                text: String::new(),
+            },
+        ]);
+        let mut stream = client.export_hands(files).await.unwrap().into_inner();
+        let mut data = Vec::new();
+        while let Some(chunk) = stream.message().await.unwrap() {
+            data.extend(chunk.data);
+        }
+        let reader = StreamReader::try_new(Cursor::new(data), None).unwrap();
+        let rows = reader
+            .map(|batch| batch.unwrap().num_rows())
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![1, 0]);
+    }
+}
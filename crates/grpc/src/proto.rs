@@ -0,0 +1,165 @@
+//! Messages of `proto/pokercraft.proto`, and the generated `Analysis` service.
+
+/// See `EquityRequest` of the proto file.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EquityRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub players: Vec<String>,
+    #[prost(string, tag = "2")]
+    pub board: String,
+    #[prost(uint64, tag = "3")]
+    pub trials: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PlayerEquity {
+    #[prost(string, tag = "1")]
+    pub range: String,
+    #[prost(uint64, tag = "2")]
+    pub combos: u64,
+    #[prost(double, tag = "3")]
+    pub equity: f64,
+    #[prost(double, tag = "4")]
+    pub win: f64,
+    #[prost(double, tag = "5")]
+    pub tie: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EquityResponse {
+    #[prost(bool, tag = "1")]
+    pub exact: bool,
+    #[prost(uint64, tag = "2")]
+    pub boards: u64,
+    #[prost(message, repeated, tag = "3")]
+    pub players: Vec<PlayerEquity>,
+}
+
+/// See `BankrollRequest` of the proto file.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BankrollRequest {
+    #[prost(double, repeated, tag = "1")]
+    pub results: Vec<f64>,
+    #[prost(double, tag = "2")]
+    pub capital: f64,
+    #[prost(uint32, tag = "3")]
+    pub iterations: u32,
+    #[prost(double, tag = "4")]
+    pub exit_multiplier: f64,
+    #[prost(uint32, tag = "5")]
+    pub simulations: u32,
+    #[prost(uint32, tag = "6")]
+    pub cash_flow_every: u32,
+    #[prost(double, tag = "7")]
+    pub cash_flow: f64,
+    #[prost(double, tag = "8")]
+    pub cash_flow_fraction: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Quantile {
+    #[prost(double, tag = "1")]
+    pub quantile: f64,
+    #[prost(double, tag = "2")]
+    pub capital: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BankrollResponse {
+    #[prost(uint64, tag = "1")]
+    pub simulations: u64,
+    #[prost(double, tag = "2")]
+    pub bankruptcy_rate: f64,
+    #[prost(double, tag = "3")]
+    pub survival_rate: f64,
+    #[prost(double, tag = "4")]
+    pub profitable_rate: f64,
+    #[prost(message, repeated, tag = "5")]
+    pub final_capital_quantiles: Vec<Quantile>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HistoryFile {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub text: String,
+}
+
+/// See `Hand` of the proto file.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Hand {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(uint64, optional, tag = "2")]
+    pub tournament_id: Option<u64>,
+    #[prost(string, optional, tag = "3")]
+    pub tournament_name: Option<String>,
+    #[prost(uint32, tag = "4")]
+    pub level: u32,
+    #[prost(uint64, tag = "5")]
+    pub sb: u64,
+    #[prost(uint64, tag = "6")]
+    pub bb: u64,
+    #[prost(string, tag = "7")]
+    pub datetime: String,
+    #[prost(uint32, tag = "8")]
+    pub max_seats: u32,
+    #[prost(string, repeated, tag = "9")]
+    pub players: Vec<String>,
+    #[prost(string, repeated, tag = "10")]
+    pub community_cards: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ParsedHand {
+    #[prost(string, tag = "1")]
+    pub file: String,
+    #[prost(oneof = "parsed_hand::Result", tags = "2, 3")]
+    pub result: Option<parsed_hand::Result>,
+}
+
+pub mod parsed_hand {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Result {
+        #[prost(message, tag = "2")]
+        Hand(super::Hand),
+        /// Message of a hand which failed to parse.
+        #[prost(string, tag = "3")]
+        Error(String),
+    }
+}
+
+/// See `ArrowChunk` of the proto file.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ArrowChunk {
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: Vec<u8>,
+}
+
+include!(concat!(env!("OUT_DIR"), "/pokercraft.Analysis.rs"));
+
+impl From<&pokercraft_core::parser::HandHistory> for Hand {
+    fn from(hand: &pokercraft_core::parser::HandHistory) -> Self {
+        Hand {
+            id: hand.id.clone(),
+            tournament_id: hand.tournament_id,
+            tournament_name: hand.tournament_name.clone(),
+            level: hand.level,
+            sb: hand.sb,
+            bb: hand.bb,
+            datetime: hand.datetime.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            max_seats: hand.max_seats,
+            players: hand
+                .seats
+                .values()
+                .map(|(player_id, _chips)| player_id.clone())
+                .collect(),
+            community_cards: hand
+                .community_cards
+                .iter()
+                .map(|card| card.to_string())
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,591 @@
+//! Node.js native bindings for pokercraft-core.
+//!
+//! Unlike the WASM bindings, heavy computations run in parallel on native threads.
+//! Functions suffixed with `Async` return a Promise, running on the libuv thread pool
+//! instead of blocking the JS thread.
+//! Build with: `npm run build`
+
+use napi::bindgen_prelude::{AsyncTask, Uint32Array, Uint8Array};
+use napi::{Env, Error, Result, Task};
+use napi_derive::napi;
+
+use pokercraft_core::bankroll::simulate_core;
+use pokercraft_core::card::{self, Card, Hand, HandRank};
+use pokercraft_core::equity::{
+    equity_batch as core_equity_batch, EquityEvolution as CoreEquityEvolution,
+    EquityResult as CoreEquityResult, LuckCalculator as CoreLuckCalculator,
+};
+use pokercraft_core::errors::PokercraftLocalError;
+use pokercraft_core::evaluator;
+use pokercraft_core::generator::Spot as CoreSpot;
+use pokercraft_core::math;
+use pokercraft_core::range::HandRange;
+
+fn to_napi_error(err: PokercraftLocalError) -> Error {
+    Error::from_reason(err.to_string())
+}
+
+fn parse_card(value: &str) -> Result<Card> {
+    Card::try_from(value).map_err(to_napi_error)
+}
+
+/// Parse card strings, where each string may have multiple cards like `"AsKd"`.
+fn parse_cards(values: &[String]) -> Result<Vec<Card>> {
+    let mut cards = Vec::with_capacity(values.len());
+    for value in values {
+        cards.extend(card::parse_cards(value).map_err(to_napi_error)?);
+    }
+    Ok(cards)
+}
+
+/// Parse pairs of card strings, like `[["As", "Kh"], ["Qd", "Jc"]]`.
+fn parse_hands(hands: &[Vec<String>]) -> Result<Vec<Hand>> {
+    hands
+        .iter()
+        .map(|hand| match hand.as_slice() {
+            [card1, card2] => Ok((parse_card(card1)?, parse_card(card2)?)),
+            _ => Err(Error::from_reason("Each hand must have exactly 2 cards")),
+        })
+        .collect()
+}
+
+/// Get the library version.
+#[napi]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+/// Set the number of threads used by parallel computations;
+/// `0` restores the default of using all cores.
+#[napi]
+pub fn set_parallelism(threads: u32) -> Result<()> {
+    pokercraft_core::set_parallelism(threads as usize).map_err(to_napi_error)
+}
+
+/// Get the number of threads used by parallel computations.
+#[napi]
+pub fn parallelism() -> u32 {
+    pokercraft_core::parallelism() as u32
+}
+
+/// Best 5-card hand found from given cards.
+#[napi(object)]
+pub struct BestHand {
+    /// Card strings of the best 5 cards, like `"As"`.
+    pub cards: Vec<String>,
+    /// Human readable hand rank.
+    pub rank: String,
+    /// Hand rank description, like `"Full House, Aces full of Kings"`.
+    pub description: String,
+}
+
+/// Find the best 5-card hand from 5 or more card strings.
+#[napi]
+pub fn evaluate_hand(cards: Vec<String>) -> Result<BestHand> {
+    let (best5, rank) = HandRank::find_best5(&parse_cards(&cards)?).map_err(to_napi_error)?;
+    Ok(BestHand {
+        cards: best5.iter().map(|card| card.to_string()).collect(),
+        rank: rank.to_string(),
+        description: rank.describe(),
+    })
+}
+
+/// Evaluate the best 5-card hand rank of each board of 5, 6 or 7 cards, in parallel.
+/// `cards` are card bytes (indices in the deck) of boards of `cardsPerBoard` cards concatenated.
+/// Returns rank keys, where a larger key is a stronger hand.
+#[napi]
+pub fn evaluate_many(cards: Uint8Array, cards_per_board: u32) -> Result<Uint32Array> {
+    let cards = cards
+        .iter()
+        .map(|&byte| Card::try_from(byte))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(to_napi_error)?;
+    let keys = evaluator::evaluate_many(&cards, cards_per_board as usize).map_err(to_napi_error)?;
+    Ok(Uint32Array::new(
+        keys.into_iter().map(|key| key.0).collect(),
+    ))
+}
+
+/// Result of single equity calculation.
+#[napi]
+pub struct EquityResult {
+    inner: CoreEquityResult,
+}
+
+#[napi]
+impl EquityResult {
+    /// Create a new EquityResult by calculating equities in parallel.
+    /// `hands` is an array of card string pairs, e.g., [["As", "Kh"], ["Qd", "Jc"]]
+    /// `community` is an array of card strings, e.g., ["2c", "3d", "4h"]
+    #[napi(constructor)]
+    pub fn new(hands: Vec<Vec<String>>, community: Vec<String>) -> Result<Self> {
+        let inner = calculate_equity(&hands, &community)?;
+        Ok(EquityResult { inner })
+    }
+
+    /// Get the equity of the given player index (0-based).
+    #[napi]
+    pub fn get_equity(&self, player_index: u32) -> Result<f64> {
+        self.inner
+            .get_equity(player_index as usize)
+            .map_err(to_napi_error)
+    }
+
+    /// Check if the given player index (0-based) has never lost in all scenarios.
+    #[napi]
+    pub fn never_lost(&self, player_index: u32) -> Result<bool> {
+        let (_wins, loses) = self
+            .inner
+            .get_winlosses(player_index as usize)
+            .map_err(to_napi_error)?;
+        Ok(loses == 0)
+    }
+
+    /// Get probabilities of winning outright, chopping each number of ways and losing
+    /// of the given player index (0-based).
+    #[napi]
+    pub fn outcome_rates(&self, player_index: u32) -> Result<OutcomeRates> {
+        let rates = self
+            .inner
+            .outcome_rates(player_index as usize)
+            .map_err(to_napi_error)?;
+        Ok(OutcomeRates {
+            win_outright: rates.win_outright,
+            chops: rates.chops,
+            lose: rates.lose,
+        })
+    }
+}
+
+fn calculate_equity(hands: &[Vec<String>], community: &[String]) -> Result<CoreEquityResult> {
+    CoreEquityResult::new(parse_hands(hands)?, parse_cards(community)?, true).map_err(to_napi_error)
+}
+
+/// Task of `calculateEquityAsync`.
+pub struct EquityTask {
+    hands: Vec<Vec<String>>,
+    community: Vec<String>,
+}
+
+impl Task for EquityTask {
+    type Output = CoreEquityResult;
+    type JsValue = EquityResult;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        calculate_equity(&self.hands, &self.community)
+    }
+
+    fn resolve(&mut self, _env: Env, inner: Self::Output) -> Result<Self::JsValue> {
+        Ok(EquityResult { inner })
+    }
+}
+
+/// Same as `new EquityResult(hands, community)`, but resolves without blocking the JS thread.
+#[napi]
+pub fn calculate_equity_async(
+    hands: Vec<Vec<String>>,
+    community: Vec<String>,
+) -> AsyncTask<EquityTask> {
+    AsyncTask::new(EquityTask { hands, community })
+}
+
+/// Hole cards of each player and community cards.
+#[napi(object)]
+pub struct Spot {
+    /// Pairs of card strings, e.g., [["As", "Kh"], ["Qd", "Jc"]]
+    pub hands: Vec<Vec<String>>,
+    pub board: Vec<String>,
+}
+
+/// Calculate equities of many independent spots in one call, in parallel over spots.
+/// Returns equities of all players of each spot, in the same order as `spots`.
+#[napi]
+pub fn equity_batch(spots: Vec<Spot>) -> Result<Vec<Vec<f64>>> {
+    calculate_equity_batch(&spots)
+}
+
+fn calculate_equity_batch(spots: &[Spot]) -> Result<Vec<Vec<f64>>> {
+    let spots = spots
+        .iter()
+        .map(|spot| {
+            Ok(CoreSpot {
+                hands: parse_hands(&spot.hands)?,
+                board: parse_cards(&spot.board)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    core_equity_batch(spots)
+        .map_err(to_napi_error)?
+        .iter()
+        .map(|result| {
+            (0..result.num_players())
+                .map(|i| result.get_equity(i))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(to_napi_error)
+        })
+        .collect()
+}
+
+/// Task of `equityBatchAsync`.
+pub struct EquityBatchTask {
+    spots: Vec<Spot>,
+}
+
+impl Task for EquityBatchTask {
+    type Output = Vec<Vec<f64>>;
+    type JsValue = Vec<Vec<f64>>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        calculate_equity_batch(&self.spots)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// Same as `equityBatch`, but resolves without blocking the JS thread.
+#[napi]
+pub fn equity_batch_async(spots: Vec<Spot>) -> AsyncTask<EquityBatchTask> {
+    AsyncTask::new(EquityBatchTask { spots })
+}
+
+/// Probabilities of winning outright, chopping and losing of a player.
+#[napi(object)]
+pub struct OutcomeRates {
+    pub win_outright: f64,
+    /// `chops[k]` is the probability of chopping `k + 2` ways.
+    pub chops: Vec<f64>,
+    pub lose: f64,
+}
+
+/// Equities of all players after each street of a runout.
+#[napi(object)]
+pub struct EquityEvolution {
+    pub preflop: Vec<f64>,
+    pub flop: Vec<f64>,
+    pub turn: Vec<f64>,
+    pub river: Vec<f64>,
+}
+
+/// Calculate equities of the given hands after each street of the 5-card runout, in parallel.
+#[napi]
+pub fn equity_evolution(hands: Vec<Vec<String>>, runout: Vec<String>) -> Result<EquityEvolution> {
+    let evolution = CoreEquityEvolution::new(parse_hands(&hands)?, &parse_cards(&runout)?, true)
+        .map_err(to_napi_error)?;
+    Ok(EquityEvolution {
+        preflop: evolution.preflop,
+        flop: evolution.flop,
+        turn: evolution.turn,
+        river: evolution.river,
+    })
+}
+
+/// Luck calculator over all-in results.
+#[napi]
+pub struct LuckCalculator {
+    inner: CoreLuckCalculator,
+}
+
+#[napi]
+impl LuckCalculator {
+    /// Create a new empty LuckCalculator.
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        LuckCalculator {
+            inner: CoreLuckCalculator::new(),
+        }
+    }
+
+    /// Create a new LuckCalculator filled with all-in spots
+    /// of the given player from the hand history text.
+    #[napi(factory)]
+    pub fn from_hand_history_text(hand_history_text: String, player_id: String) -> Result<Self> {
+        let hands = pokercraft_core::parser::parse_hands(hand_history_text.as_bytes())
+            .flatten()
+            .collect::<Vec<_>>();
+        let inner = CoreLuckCalculator::from_hands(&hands, &player_id).map_err(to_napi_error)?;
+        Ok(LuckCalculator { inner })
+    }
+
+    /// Add a new result to the calculator.
+    #[napi]
+    pub fn add_result(&mut self, equity: f64, actual: f64) -> Result<()> {
+        self.inner.add_result(equity, actual).map_err(to_napi_error)
+    }
+
+    /// Add results at once, where `actuals[i]` is the result with `equities[i]`.
+    /// Nothing is added if any of them is invalid.
+    #[napi]
+    pub fn add_results(&mut self, equities: Vec<f64>, actuals: Vec<f64>) -> Result<()> {
+        if equities.len() != actuals.len() {
+            return Err(Error::from_reason(
+                "Equities and actuals should have the same length",
+            ));
+        }
+        self.inner
+            .add_results(equities.into_iter().zip(actuals))
+            .map_err(to_napi_error)
+    }
+
+    /// Remove the last result and return it as `[equity, actual]`.
+    #[napi]
+    pub fn pop(&mut self) -> Option<Vec<f64>> {
+        self.inner
+            .pop()
+            .map(|(equity, actual)| vec![equity, actual])
+    }
+
+    /// Keep only the first `length` results, removing the rest.
+    #[napi]
+    pub fn truncate(&mut self, length: u32) {
+        self.inner.truncate(length as usize)
+    }
+
+    /// Calculate the Luck-score of the results.
+    #[napi]
+    pub fn luck_score(&self) -> Result<f64> {
+        self.inner
+            .luck_score()
+            .ok_or_else(|| Error::from_reason("Cannot calculate Luck-score"))
+    }
+
+    /// Get the distribution of the number of wins and the actual number of wins.
+    #[napi]
+    pub fn pmf(&self) -> LuckDistribution {
+        let distribution = self.inner.pmf();
+        LuckDistribution {
+            pmf: distribution.pmf,
+            observed_wins: distribution.observed_wins,
+        }
+    }
+}
+
+/// Distribution of the number of wins, to plot where the actual results landed.
+#[napi(object)]
+pub struct LuckDistribution {
+    /// `pmf[k]` is the probability of winning exactly `k` times.
+    pub pmf: Vec<f64>,
+    /// Actual number of wins, where ties count as fractional wins.
+    pub observed_wins: f64,
+}
+
+impl Default for LuckCalculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Summary of bankroll simulations.
+#[napi(object)]
+pub struct BankruptcyMetric {
+    /// Number of simulations performed.
+    pub length: u32,
+    pub bankruptcy_rate: f64,
+    pub survival_rate: f64,
+    pub profitable_rate: f64,
+}
+
+/// Simulate the bankruptcy metric in parallel.
+#[napi]
+pub fn simulate(
+    initial_capital: f64,
+    relative_return_results: Vec<f64>,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+) -> Result<BankruptcyMetric> {
+    SimulateTask {
+        initial_capital,
+        relative_return_results,
+        max_iteration,
+        profit_exit_multiplier,
+        simulation_count,
+    }
+    .compute()
+}
+
+/// Task of `simulateAsync`.
+pub struct SimulateTask {
+    initial_capital: f64,
+    relative_return_results: Vec<f64>,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+}
+
+impl Task for SimulateTask {
+    type Output = BankruptcyMetric;
+    type JsValue = BankruptcyMetric;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let metric = simulate_core(
+            self.initial_capital,
+            &self.relative_return_results,
+            self.max_iteration,
+            self.profit_exit_multiplier,
+            self.simulation_count,
+        )
+        .map_err(to_napi_error)?;
+        Ok(BankruptcyMetric {
+            length: metric.len() as u32,
+            bankruptcy_rate: metric.get_bankruptcy_rate(),
+            survival_rate: metric.get_survival_rate(),
+            profitable_rate: metric.get_profitable_rate(),
+        })
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// Same as `simulate`, but resolves without blocking the JS thread.
+#[napi]
+pub fn simulate_async(
+    initial_capital: f64,
+    relative_return_results: Vec<f64>,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+) -> AsyncTask<SimulateTask> {
+    AsyncTask::new(SimulateTask {
+        initial_capital,
+        relative_return_results,
+        max_iteration,
+        profit_exit_multiplier,
+        simulation_count,
+    })
+}
+
+/// Get the minimum equity to call `bet` into `pot`.
+#[napi]
+pub fn required_equity(bet: f64, pot: f64) -> Result<f64> {
+    math::required_equity(bet, pot).map_err(to_napi_error)
+}
+
+/// Get the minimum defense frequency against `bet` into `pot`.
+#[napi]
+pub fn minimum_defense_frequency(bet: f64, pot: f64) -> Result<f64> {
+    math::minimum_defense_frequency(bet, pot).map_err(to_napi_error)
+}
+
+/// Get the stack-to-pot ratio of the effective stack.
+#[napi]
+pub fn stack_to_pot_ratio(effective_stack: f64, pot: f64) -> Result<f64> {
+    math::stack_to_pot_ratio(effective_stack, pot).map_err(to_napi_error)
+}
+
+/// Expected value of jamming all-in against a single player, in chips relative to folding.
+#[napi(object)]
+pub struct ShoveEv {
+    pub fold_frequency: f64,
+    pub called_equity: f64,
+    /// Chips at risk when called, which is the smaller stack.
+    pub risk: f64,
+    pub called_ev: f64,
+    pub ev: f64,
+}
+
+/// Get the expected value of jamming `hand` like `"AsKd"` into `pot` on the board,
+/// against a villain calling with `calling_range` like `"QQ+,AK"`.
+/// Equities against calling combos are calculated in parallel.
+#[napi]
+pub fn shove_ev(
+    hand: String,
+    calling_range: String,
+    board: Vec<String>,
+    pot: f64,
+    hero_stack: f64,
+    villain_stack: f64,
+    trials: u32,
+) -> Result<ShoveEv> {
+    let [card1, card2] = card::parse_cards_exact::<2>(&hand).map_err(to_napi_error)?;
+    let calling_range = HandRange::try_from(calling_range.as_str()).map_err(to_napi_error)?;
+    let result = math::shove_ev(
+        (card1, card2),
+        &calling_range,
+        &parse_cards(&board)?,
+        pot,
+        hero_stack,
+        villain_stack,
+        trials as u64,
+    )
+    .map_err(to_napi_error)?;
+    Ok(ShoveEv {
+        fold_frequency: result.fold_frequency,
+        called_equity: result.called_equity,
+        risk: result.risk,
+        called_ev: result.called_ev,
+        ev: result.ev,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_equity() -> Result<()> {
+        let hands = vec![strings(&["As", "Ah"]), strings(&["Kd", "Kc"])];
+        let community = strings(&["2c", "7d", "9h", "4s"]);
+        let result = EquityResult::new(hands.clone(), community.clone())?;
+        assert!((result.get_equity(0)? - 42.0 / 44.0).abs() < 1e-9);
+        assert!(!result.never_lost(0)?);
+
+        let mut task = EquityTask { hands, community };
+        let inner = task.compute()?;
+        assert_eq!(inner.get_equity(0).unwrap(), result.get_equity(0)?);
+
+        let mut task = EquityTask {
+            hands: vec![strings(&["As"])],
+            community: vec![],
+        };
+        assert!(task.compute().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_equity_batch() -> Result<()> {
+        let spots = || {
+            vec![
+                Spot {
+                    hands: vec![strings(&["As", "Ah"]), strings(&["Kd", "Kc"])],
+                    board: strings(&["2c", "7d", "9h", "4s"]),
+                },
+                Spot {
+                    hands: vec![strings(&["As", "Ks"]), strings(&["Qd", "Qc"])],
+                    board: strings(&["Ac", "7d", "9h", "4s", "2s"]),
+                },
+            ]
+        };
+        let equities = equity_batch(spots())?;
+        assert_eq!(equities.len(), 2);
+        assert_eq!(equities[1], vec![1.0, 0.0]);
+        assert_eq!(EquityBatchTask { spots: spots() }.compute()?, equities);
+        Ok(())
+    }
+
+    #[test]
+    fn test_simulate() -> Result<()> {
+        let mut task = SimulateTask {
+            initial_capital: 10.0,
+            relative_return_results: vec![1.0, -1.0, 2.0],
+            max_iteration: 100,
+            profit_exit_multiplier: 2.0,
+            simulation_count: 50,
+        };
+        let metric = task.compute()?;
+        assert_eq!(metric.length, 50);
+        assert!(
+            (metric.bankruptcy_rate + metric.survival_rate - 1.0).abs() < 1e-9,
+            "Bankruptcy and survival rates should sum to 1"
+        );
+        assert!(simulate(-1.0, vec![1.0], 100, 2.0, 50).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,637 @@
+//! Regenerates type stubs `crates/python/python/pokercraft_local/rust/*.pyi` from the bound
+//! classes and functions, so IDEs and mypy see the signatures of the `pokercraft_local.rust` module.
+//! `lib.rs` becomes `__init__.pyi`, and every other source file with bindings becomes a submodule.
+//!
+//! Run `cargo run -p pokercraft-python-stubgen` after changing the bindings,
+//! or with `--check` to fail if the committed stubs are outdated.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use syn::{Attribute, Expr, FnArg, GenericArgument, ImplItem, Item, Lit, Pat, PathArguments};
+use syn::{ReturnType, Type};
+
+const STUB_DIR: &str = "python/pokercraft_local/rust";
+
+/// Python types of argument types which are converted by hand-written `FromPyObject`.
+const CONVERSIONS: [(&str, &str); 1] = [(
+    "RelativeReturns",
+    "Sequence[float] | npt.NDArray[np.float64]",
+)];
+
+/// Python types of arguments which are called back, as they are bound as `PyAny`.
+const CALLBACKS: [(&str, &str); 1] = [("progress", "Callable[[int, int], object]")];
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum FunctionKind {
+    Function,
+    Method,
+    Constructor,
+    StaticMethod,
+    Getter,
+}
+
+struct Argument {
+    name: String,
+    ty: Type,
+    default: Option<String>,
+}
+
+struct Function {
+    name: String,
+    docs: Vec<String>,
+    kind: FunctionKind,
+    args: Vec<Argument>,
+    returns: Option<Type>,
+}
+
+struct Class {
+    name: String,
+    docs: Vec<String>,
+    /// Options of `#[pyclass(...)]`, like `eq` or `ord`.
+    options: Vec<String>,
+    variants: Vec<String>,
+    methods: Vec<Function>,
+}
+
+#[derive(Default)]
+struct Module {
+    name: String,
+    functions: Vec<Function>,
+    classes: Vec<Class>,
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident(name))
+}
+
+fn attr_tokens(attrs: &[Attribute], name: &str) -> Option<String> {
+    attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(name))
+        .map(|attr| match attr.meta.require_list() {
+            Ok(list) => list.tokens.to_string(),
+            Err(_) => String::new(),
+        })
+}
+
+fn docs(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta.require_name_value().ok()?.value {
+            Expr::Lit(expr) => match &expr.lit {
+                Lit::Str(s) => Some(
+                    s.value()
+                        .strip_prefix(' ')
+                        .unwrap_or(&s.value())
+                        .to_string(),
+                ),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Split by commas outside of brackets.
+fn split_top_level(s: &str) -> Vec<String> {
+    let (mut parts, mut current, mut depth) = (Vec::new(), String::new(), 0);
+    for c in s.chars() {
+        match c {
+            '(' | '[' | '<' => depth += 1,
+            ')' | ']' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(current);
+    parts
+        .into_iter()
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Get `{argument: default}` from `#[pyo3(signature = (...))]`.
+fn signature_defaults(attrs: &[Attribute]) -> Vec<(String, String)> {
+    let Some(tokens) = attr_tokens(attrs, "pyo3") else {
+        return Vec::new();
+    };
+    let Some(signature) = tokens
+        .strip_prefix("signature")
+        .and_then(|rest| rest.trim_start().strip_prefix('='))
+        .map(|rest| rest.trim().trim_start_matches('(').trim_end_matches(')'))
+    else {
+        return Vec::new();
+    };
+    split_top_level(signature)
+        .into_iter()
+        .filter_map(|part| {
+            let (name, default) = part.split_once('=')?;
+            let default = match default.trim() {
+                "true" => "True",
+                "false" => "False",
+                "None" => "None",
+                _ => "...",
+            };
+            Some((name.trim().to_string(), default.to_string()))
+        })
+        .collect()
+}
+
+fn parse_function(sig: &syn::Signature, attrs: &[Attribute], kind: FunctionKind) -> Function {
+    let defaults = signature_defaults(attrs);
+    let args = sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(arg) => {
+                let Pat::Ident(pat) = arg.pat.as_ref() else {
+                    return None;
+                };
+                let name = pat.ident.to_string();
+                if name == "slf" || last_ident(&arg.ty).as_deref() == Some("Python") {
+                    return None;
+                }
+                let default = defaults
+                    .iter()
+                    .find(|(arg_name, _)| arg_name == &name)
+                    .map(|(_, default)| default.clone());
+                Some(Argument {
+                    name,
+                    ty: (*arg.ty).clone(),
+                    default,
+                })
+            }
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+    Function {
+        name: sig.ident.to_string(),
+        docs: docs(attrs),
+        kind,
+        args,
+        returns: match &sig.output {
+            ReturnType::Default => None,
+            ReturnType::Type(_, ty) => Some((**ty).clone()),
+        },
+    }
+}
+
+fn last_ident(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|seg| seg.ident.to_string()),
+        Type::Reference(reference) => last_ident(&reference.elem),
+        _ => None,
+    }
+}
+
+fn generic_types(args: &PathArguments) -> Vec<&Type> {
+    match args {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_module(name: &str, source: &str) -> Module {
+    let file =
+        syn::parse_file(source).unwrap_or_else(|e| panic!("Failed to parse {}: {}", name, e));
+    let mut module = Module {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    for item in file.items {
+        match item {
+            Item::Struct(item) if has_attr(&item.attrs, "pyclass") => module.classes.push(Class {
+                name: item.ident.to_string(),
+                docs: docs(&item.attrs),
+                options: class_options(&item.attrs),
+                variants: Vec::new(),
+                methods: Vec::new(),
+            }),
+            Item::Enum(item) if has_attr(&item.attrs, "pyclass") => module.classes.push(Class {
+                name: item.ident.to_string(),
+                docs: docs(&item.attrs),
+                options: class_options(&item.attrs),
+                variants: item.variants.iter().map(|v| v.ident.to_string()).collect(),
+                methods: Vec::new(),
+            }),
+            Item::Fn(item) if has_attr(&item.attrs, "pyfunction") => module.functions.push(
+                parse_function(&item.sig, &item.attrs, FunctionKind::Function),
+            ),
+            Item::Impl(item) if has_attr(&item.attrs, "pymethods") => {
+                let class_name = last_ident(&item.self_ty).unwrap_or_default();
+                let methods = item.items.iter().filter_map(|item| match item {
+                    ImplItem::Fn(method) => {
+                        let kind = if has_attr(&method.attrs, "new") {
+                            FunctionKind::Constructor
+                        } else if has_attr(&method.attrs, "staticmethod") {
+                            FunctionKind::StaticMethod
+                        } else if has_attr(&method.attrs, "getter") {
+                            FunctionKind::Getter
+                        } else {
+                            FunctionKind::Method
+                        };
+                        Some(parse_function(&method.sig, &method.attrs, kind))
+                    }
+                    _ => None,
+                });
+                // `#[pymethods]` come after `#[pyclass]` in the same file
+                if let Some(class) = module.classes.iter_mut().find(|c| c.name == class_name) {
+                    class.methods.extend(methods);
+                }
+            }
+            _ => {}
+        }
+    }
+    module
+}
+
+fn class_options(attrs: &[Attribute]) -> Vec<String> {
+    attr_tokens(attrs, "pyclass")
+        .map(|tokens| split_top_level(&tokens))
+        .unwrap_or_default()
+}
+
+/// Resolves Rust types into Python types within a module.
+struct TypeResolver<'a> {
+    module: &'a Module,
+    all_modules: &'a [Module],
+    /// `(module, class)` pairs to import from other modules.
+    imports: Vec<(String, String)>,
+}
+
+impl TypeResolver<'_> {
+    fn resolve(&mut self, ty: &Type, class_name: Option<&str>) -> String {
+        match ty {
+            Type::Reference(reference) => self.resolve(&reference.elem, class_name),
+            Type::Tuple(tuple) if tuple.elems.is_empty() => "None".to_string(),
+            Type::Tuple(tuple) => format!(
+                "tuple[{}]",
+                tuple
+                    .elems
+                    .iter()
+                    .map(|elem| self.resolve(elem, class_name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Type::Path(path) => {
+                let Some(segment) = path.path.segments.last() else {
+                    return "Any".to_string();
+                };
+                let generics = generic_types(&segment.arguments);
+                let ident = segment.ident.to_string();
+                match ident.as_str() {
+                    "f32" | "f64" => "float".to_string(),
+                    "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64"
+                    | "isize" => "int".to_string(),
+                    "bool" => "bool".to_string(),
+                    "String" | "str" | "char" => "str".to_string(),
+                    "PathBuf" => "str | os.PathLike[str]".to_string(),
+                    "Vec" => format!("list[{}]", self.resolve(generics[0], class_name)),
+                    "Option" => format!("{} | None", self.resolve(generics[0], class_name)),
+                    "PyResult" | "Result" | "Bound" | "Py" | "PyRef" | "Borrowed" => {
+                        self.resolve(generics[0], class_name)
+                    }
+                    "PyArray1" | "PyArray2" | "PyReadonlyArray1" | "PyReadonlyArray2" => {
+                        let dtype = match last_ident(generics[0]).as_deref() {
+                            Some("f64") => "float64",
+                            Some("u8") => "uint8",
+                            Some("u32") => "uint32",
+                            Some("u64") => "uint64",
+                            _ => "generic",
+                        };
+                        format!("npt.NDArray[np.{}]", dtype)
+                    }
+                    "PyAny" => "Any".to_string(),
+                    "PyCapsule" => "object".to_string(),
+                    "CompareOp" => "int".to_string(),
+                    "Self" => class_name.unwrap_or("Any").to_string(),
+                    _ => {
+                        if let Some((_, py_type)) =
+                            CONVERSIONS.iter().find(|(name, _)| *name == ident)
+                        {
+                            return py_type.to_string();
+                        }
+                        if self.module.classes.iter().any(|class| class.name == ident) {
+                            return ident;
+                        }
+                        match self
+                            .all_modules
+                            .iter()
+                            .find(|module| module.classes.iter().any(|class| class.name == ident))
+                        {
+                            Some(module) => {
+                                self.imports.push((module.name.clone(), ident.clone()));
+                                ident
+                            }
+                            None => "Any".to_string(),
+                        }
+                    }
+                }
+            }
+            _ => "Any".to_string(),
+        }
+    }
+
+    /// Get the return type; `*_async` functions return awaitables
+    /// of their synchronous counterparts, or of their classes.
+    fn returns(
+        &mut self,
+        function: &Function,
+        siblings: &[Function],
+        class_name: Option<&str>,
+    ) -> String {
+        if let Some(sync_name) = function.name.strip_suffix("_async") {
+            let result = match siblings.iter().find(|sibling| sibling.name == sync_name) {
+                Some(sibling) => self.returns(sibling, siblings, class_name),
+                None => class_name.unwrap_or("Any").to_string(),
+            };
+            return format!("Awaitable[{}]", result);
+        }
+        match &function.returns {
+            Some(ty) => self.resolve(ty, class_name),
+            None => "None".to_string(),
+        }
+    }
+}
+
+fn write_docs(out: &mut String, docs: &[String], indent: &str) {
+    match docs {
+        [] => {}
+        [line] => writeln!(out, "{}\"\"\"{}\"\"\"", indent, line).unwrap(),
+        lines => {
+            writeln!(out, "{}\"\"\"{}", indent, lines[0]).unwrap();
+            for line in &lines[1..] {
+                writeln!(out, "{}{}", indent, line).unwrap();
+            }
+            writeln!(out, "{}\"\"\"", indent).unwrap();
+        }
+    }
+}
+
+fn write_function(
+    out: &mut String,
+    resolver: &mut TypeResolver<'_>,
+    function: &Function,
+    siblings: &[Function],
+    class_name: Option<&str>,
+) {
+    let indent = if class_name.is_some() { "    " } else { "" };
+    let (name, returns) = match function.kind {
+        FunctionKind::Constructor => ("__init__".to_string(), "None".to_string()),
+        _ => (
+            function.name.clone(),
+            resolver.returns(function, siblings, class_name),
+        ),
+    };
+    if function.name == "__richcmp__" {
+        for op in ["__eq__", "__ne__", "__lt__", "__le__", "__gt__", "__ge__"] {
+            writeln!(
+                out,
+                "{}def {}(self, other: object) -> bool: ...",
+                indent, op
+            )
+            .unwrap();
+        }
+        return;
+    }
+
+    let mut args = Vec::new();
+    match function.kind {
+        FunctionKind::Method | FunctionKind::Constructor | FunctionKind::Getter => {
+            args.push("self".to_string())
+        }
+        FunctionKind::StaticMethod => writeln!(out, "{}@staticmethod", indent).unwrap(),
+        FunctionKind::Function => {}
+    }
+    if function.kind == FunctionKind::Getter {
+        writeln!(out, "{}@property", indent).unwrap();
+    }
+    for arg in function.args.iter() {
+        let ty = match CALLBACKS.iter().find(|(name, _)| *name == arg.name) {
+            Some((_, callback)) if last_ident(&arg.ty).as_deref() == Some("Option") => {
+                format!("{} | None", callback)
+            }
+            Some((_, callback)) => callback.to_string(),
+            None => resolver.resolve(&arg.ty, class_name),
+        };
+        args.push(match &arg.default {
+            Some(default) => format!("{}: {} = {}", arg.name, ty, default),
+            None => format!("{}: {}", arg.name, ty),
+        });
+    }
+    let header = format!(
+        "{}def {}({}) -> {}:",
+        indent,
+        name,
+        args.join(", "),
+        returns
+    );
+    if function.docs.is_empty() {
+        writeln!(out, "{} ...", header).unwrap();
+    } else {
+        writeln!(out, "{}", header).unwrap();
+        write_docs(out, &function.docs, &format!("{}    ", indent));
+    }
+}
+
+fn render(module: &Module, all_modules: &[Module], submodules: &[String]) -> String {
+    let mut resolver = TypeResolver {
+        module,
+        all_modules,
+        imports: Vec::new(),
+    };
+    let mut body = String::new();
+    for function in module.functions.iter() {
+        body.push('\n');
+        write_function(&mut body, &mut resolver, function, &module.functions, None);
+    }
+    for class in module.classes.iter() {
+        writeln!(body, "\nclass {}:", class.name).unwrap();
+        write_docs(&mut body, &class.docs, "    ");
+        for variant in class.variants.iter() {
+            writeln!(body, "    {}: ClassVar[{}]", variant, class.name).unwrap();
+        }
+        if class.options.iter().any(|option| option == "eq_int") {
+            writeln!(body, "    def __int__(self) -> int: ...").unwrap();
+        }
+        if class.options.iter().any(|option| option == "ord") {
+            for op in ["__lt__", "__le__", "__gt__", "__ge__"] {
+                writeln!(
+                    body,
+                    "    def {}(self, other: {}) -> bool: ...",
+                    op, class.name
+                )
+                .unwrap();
+            }
+        }
+        for method in class.methods.iter() {
+            write_function(
+                &mut body,
+                &mut resolver,
+                method,
+                &class.methods,
+                Some(&class.name),
+            );
+        }
+        if class.variants.is_empty() && class.methods.is_empty() && class.docs.is_empty() {
+            writeln!(body, "    ...").unwrap();
+        }
+    }
+
+    let mut out = String::from(
+        "# Generated by crates/python-stubgen from crates/python/src; Do not edit manually.\n\n",
+    );
+    out.push_str("from __future__ import annotations\n\n");
+    let mut std_imports = Vec::new();
+    if body.contains("os.") {
+        std_imports.push("import os".to_string());
+    }
+    let typing = ["Any", "Awaitable", "Callable", "ClassVar", "Sequence"]
+        .into_iter()
+        .filter(|name| {
+            body.contains(&format!("{}[", name))
+                || body.contains(&format!(": {}", name))
+                || body.contains(&format!("-> {}", name))
+        })
+        .collect::<Vec<_>>();
+    if !typing.is_empty() {
+        std_imports.push(format!("from typing import {}", typing.join(", ")));
+    }
+    if !std_imports.is_empty() {
+        out.push_str(&(std_imports.join("\n") + "\n\n"));
+    }
+    if body.contains("np.") {
+        out.push_str("import numpy as np\nimport numpy.typing as npt\n\n");
+    }
+    resolver.imports.sort();
+    resolver.imports.dedup();
+    let mut local_imports = resolver
+        .imports
+        .iter()
+        .map(|(module, class)| format!("from .{} import {}", module, class))
+        .collect::<Vec<_>>();
+    if !submodules.is_empty() {
+        local_imports.insert(0, format!("from . import {}", submodules.join(", ")));
+    }
+    if !local_imports.is_empty() {
+        out.push_str(&(local_imports.join("\n") + "\n\n"));
+    }
+    out.push_str(body.trim_start_matches('\n'));
+    out
+}
+
+/// Get the directory of the `pokercraft-python` crate.
+fn python_crate_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .join("python")
+}
+
+/// Generate stubs from sources of the given crate directory,
+/// as pairs of file names in the stub directory and their contents.
+fn generate_stubs(crate_dir: &Path) -> std::io::Result<Vec<(String, String)>> {
+    let mut sources = std::fs::read_dir(crate_dir.join("src"))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    sources.retain(|path| path.extension().is_some_and(|ext| ext == "rs"));
+    sources.sort();
+    let mut modules = Vec::new();
+    for path in sources.iter() {
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let module = parse_module(&name, &std::fs::read_to_string(path)?);
+        if module.name == "lib" || !(module.functions.is_empty() && module.classes.is_empty()) {
+            modules.push(module);
+        }
+    }
+    let submodules = modules
+        .iter()
+        .filter(|module| module.name != "lib")
+        .map(|module| module.name.clone())
+        .collect::<Vec<_>>();
+
+    let mut stubs = modules
+        .iter()
+        .map(|module| {
+            let (filename, submodules) = match module.name.as_str() {
+                "lib" => ("__init__.pyi".to_string(), submodules.as_slice()),
+                name => (format!("{}.pyi", name), &[][..]),
+            };
+            (filename, render(module, &modules, submodules))
+        })
+        .collect::<Vec<_>>();
+    stubs.push(("py.typed".to_string(), String::new()));
+    Ok(stubs)
+}
+
+fn main() -> std::io::Result<()> {
+    let check = match std::env::args().nth(1).as_deref() {
+        None => false,
+        Some("--check") => true,
+        Some(arg) => {
+            eprintln!(
+                "Unknown argument \"{}\"; Usage: generate_python_stubs [--check]",
+                arg
+            );
+            std::process::exit(2);
+        }
+    };
+    let crate_dir = python_crate_dir();
+    let stub_dir = crate_dir.join(STUB_DIR);
+    let mut outdated = Vec::new();
+    for (filename, stub) in generate_stubs(&crate_dir)? {
+        let path = stub_dir.join(&filename);
+        // Avoid touching unchanged stubs
+        if std::fs::read_to_string(&path).ok().as_deref() == Some(stub.as_str()) {
+            continue;
+        }
+        if !check {
+            std::fs::create_dir_all(&stub_dir)?;
+            std::fs::write(&path, stub)?;
+            println!("Wrote {}", path.display());
+        }
+        outdated.push(filename);
+    }
+    if check && !outdated.is_empty() {
+        eprintln!(
+            "Outdated stubs in {}: {}; Run `cargo run -p pokercraft-python-stubgen`",
+            stub_dir.display(),
+            outdated.join(", ")
+        );
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_committed_stubs_are_up_to_date() -> std::io::Result<()> {
+        let crate_dir = python_crate_dir();
+        for (filename, stub) in generate_stubs(&crate_dir)? {
+            let committed = std::fs::read_to_string(crate_dir.join(STUB_DIR).join(&filename))?;
+            assert!(
+                committed == stub,
+                "{} is outdated; Run `cargo run -p pokercraft-python-stubgen`",
+                filename
+            );
+        }
+        Ok(())
+    }
+}
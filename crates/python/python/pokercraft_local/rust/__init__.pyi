@@ -0,0 +1,22 @@
+# Generated by crates/python-stubgen from crates/python/src; Do not edit manually.
+
+from __future__ import annotations
+
+from . import bankroll, card, equity, export, math
+
+def version() -> str:
+    """Get the library version."""
+
+def set_parallelism(threads: int) -> None:
+    """Set the number of threads used by parallel computations;
+    `0` restores the default of using all cores.
+    """
+
+def parallelism() -> int:
+    """Get the number of threads used by parallel computations."""
+
+def enable_logging(level: str = ...) -> None:
+    """Log diagnostics of parsing, enumeration and simulation to stderr
+    at the given level: `"off"`, `"error"`, `"warn"`, `"info"`, `"debug"` or `"trace"`.
+    Calling again changes the level.
+    """
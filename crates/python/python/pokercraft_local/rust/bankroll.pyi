@@ -0,0 +1,94 @@
+# Generated by crates/python-stubgen from crates/python/src; Do not edit manually.
+
+from __future__ import annotations
+
+from typing import Awaitable, Callable, Sequence
+
+import numpy as np
+import numpy.typing as npt
+
+def required_bankroll(relative_return_results: list[float], target_ruin_rate: float, max_iteration: int, simulation_count: int) -> float:
+    """Find the minimum initial capital whose bankruptcy rate in `max_iteration` tournaments
+    is at most `target_ruin_rate`, by bisection over simulations.
+    The GIL is released during the simulations.
+    """
+
+def simulate_proportional(results_in_buy_ins: list[float], buy_in_fraction: float, max_iteration: int, simulation_count: int) -> GrowthMetric:
+    """Simulate growth of the capital in parallel, where each buy-in is `buy_in_fraction`
+    of the current capital and results are net results in buy-ins, like `-1` for not cashing.
+    The GIL is released during the simulation.
+    """
+
+def expected_growth_rate(results_in_buy_ins: list[float], buy_in_fraction: float) -> float:
+    """Get the expected log growth of the capital per tournament with proportional buy-ins."""
+
+def simulate(initial_capital: float, relative_return_results: Sequence[float] | npt.NDArray[np.float64], max_iteration: int, profit_exit_multiplier: float, simulation_count: int, cash_flows: list[tuple[int, float, float]] = ...) -> BankruptcyMetric:
+    """Simulate the bankruptcy metric in parallel.
+    Relative return results can be a list or a numpy float64 array,
+    which is read without copying if contiguous.
+    Each of `cash_flows` is `(every, amount, fraction)`, adding `amount` and `fraction`
+    of the capital after every `every` tournaments; Negative values are withdrawals.
+    The GIL is released during the simulation.
+    """
+
+def simulate_async(initial_capital: float, relative_return_results: Sequence[float] | npt.NDArray[np.float64], max_iteration: int, profit_exit_multiplier: float, simulation_count: int, progress: Callable[[int, int], object] | None = None) -> Awaitable[BankruptcyMetric]:
+    """Same as `simulate`, but returns an awaitable running on a background thread.
+    `progress(done, total)` is called on the event loop about every 1% of simulations.
+    """
+
+def simulate_finishes(initial_capital: float, finishes: list[tuple[int, int]], buy_in: float, entries: int, payouts: list[float], max_iteration: int, profit_exit_multiplier: float, simulation_count: int) -> BankruptcyMetric:
+    """Simulate the bankruptcy metric in parallel, playing each tournament in the structure
+    of `buy_in`, `entries` and `payouts` of the 1st, 2nd, ... places, with a finish position
+    resampled from `(rank, total players)` of past tournaments and scaled to the same percentile.
+    The GIL is released during the simulation.
+    """
+
+class BankruptcyMetric:
+    """Summary of bankroll simulations."""
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    @property
+    def length(self) -> int:
+        """Number of simulations performed."""
+    @property
+    def bankruptcy_rate(self) -> float: ...
+    @property
+    def survival_rate(self) -> float: ...
+    @property
+    def profitable_rate(self) -> float: ...
+    def relative_return_quantile(self, q: float) -> float:
+        """Get the `q`-quantile of relative returns (final capital / initial capital)."""
+    def relative_returns(self) -> npt.NDArray[np.float64]:
+        """Get relative returns (final capital / initial capital) of all simulations
+        as a numpy array.
+        """
+    def bankrupt_iterations(self) -> npt.NDArray[np.uint32]:
+        """Get bankrupted iteration numbers (0 if not bankrupted) of all simulations
+        as a numpy array.
+        """
+    @property
+    def target_reached_rate(self) -> float:
+        """Rate of simulations reaching the profit exit capital."""
+    def time_to_target_quantile(self, q: float) -> float | None:
+        """Get the `q`-quantile of iterations to reach the profit exit capital
+        among simulations reaching it, like the median time to double,
+        or `None` if no simulation reached it.
+        """
+    def target_iterations(self) -> npt.NDArray[np.uint32]:
+        """Get iteration numbers of reaching the profit exit capital
+        of simulations reaching it as a numpy array.
+        """
+
+class GrowthMetric:
+    """Growth rates of simulations with proportional buy-ins."""
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    @property
+    def mean_growth_rate(self) -> float:
+        """Average log growth of the capital per tournament."""
+    def growth_rate_quantile(self, q: float) -> float:
+        """Get the `q`-quantile of growth rates per tournament."""
+    def relative_return_quantile(self, q: float) -> float:
+        """Get the `q`-quantile of relative returns (final capital / initial capital)."""
+    def growth_rates(self) -> npt.NDArray[np.float64]:
+        """Get log growths of the capital per tournament of all simulations as a numpy array."""
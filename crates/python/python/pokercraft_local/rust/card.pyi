@@ -0,0 +1,95 @@
+# Generated by crates/python-stubgen from crates/python/src; Do not edit manually.
+
+from __future__ import annotations
+
+from typing import ClassVar
+
+import numpy as np
+import numpy.typing as npt
+
+def parse_cards(value: str) -> list[Card]:
+    """Parse a list of cards like `"As Kd 2c"`, `"As,Kd,2c"` or `"AsKd2c"`."""
+
+def random_spot(seed: int, num_players: int, board_size: int = ..., dead: list[str] = ...) -> Spot:
+    """Generate a random spot from the given seed, never generating `dead` cards."""
+
+def find_best5(cards: list[str]) -> tuple[list[Card], str]:
+    """Find the best 5-card hand from 5 or more card strings.
+    Returns the best 5 cards and the human readable hand rank.
+    """
+
+def evaluate_many(boards: npt.NDArray[np.uint8]) -> npt.NDArray[np.uint32]:
+    """Evaluate the best 5-card hand rank of each board of 5, 6 or 7 cards,
+    given as a 2-dimensional uint8 array of card indices (see `Card.index`) per row.
+    Returns a uint32 array of rank keys, where a larger key is a stronger hand.
+    The GIL is released during the evaluation.
+    """
+
+class CardShape:
+    """Card shapes (suits) in a standard deck of playing cards."""
+    Spade: ClassVar[CardShape]
+    Heart: ClassVar[CardShape]
+    Diamond: ClassVar[CardShape]
+    Club: ClassVar[CardShape]
+    def __int__(self) -> int: ...
+    def __init__(self, value: str) -> None:
+        """Create a card shape from a character, like `CardShape("s")`."""
+    def __str__(self) -> str: ...
+
+class CardNumber:
+    """Card numbers (ranks) in a standard deck of playing cards."""
+    Two: ClassVar[CardNumber]
+    Three: ClassVar[CardNumber]
+    Four: ClassVar[CardNumber]
+    Five: ClassVar[CardNumber]
+    Six: ClassVar[CardNumber]
+    Seven: ClassVar[CardNumber]
+    Eight: ClassVar[CardNumber]
+    Nine: ClassVar[CardNumber]
+    Ten: ClassVar[CardNumber]
+    Jack: ClassVar[CardNumber]
+    Queen: ClassVar[CardNumber]
+    King: ClassVar[CardNumber]
+    Ace: ClassVar[CardNumber]
+    def __int__(self) -> int: ...
+    def __lt__(self, other: CardNumber) -> bool: ...
+    def __le__(self, other: CardNumber) -> bool: ...
+    def __gt__(self, other: CardNumber) -> bool: ...
+    def __ge__(self, other: CardNumber) -> bool: ...
+    def __init__(self, value: int) -> None:
+        """Create a card number from an integer from 2 to 14 (Ace; 1 is also Ace), like `CardNumber(11)`."""
+    def __str__(self) -> str: ...
+
+class Card:
+    """A playing card in a standard deck of 52 cards."""
+    def __init__(self, value: str) -> None:
+        """Create a card from a string, like `Card("As")`."""
+    def __str__(self) -> str: ...
+    def __repr__(self) -> str: ...
+    def __hash__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __lt__(self, other: object) -> bool: ...
+    def __le__(self, other: object) -> bool: ...
+    def __gt__(self, other: object) -> bool: ...
+    def __ge__(self, other: object) -> bool: ...
+    @property
+    def shape(self) -> CardShape: ...
+    @property
+    def number(self) -> CardNumber: ...
+    @property
+    def index(self) -> int:
+        """Index of this card in the deck from 0 to 51, used as card bytes of `evaluate_many`."""
+
+class Spot:
+    """Hole cards of each player and community cards."""
+    def __init__(self, hands: list[tuple[str, str]], board: list[str] = ...) -> None:
+        """Create a spot from hole cards of each player, like `[("As", "Kh"), ("Qd", "Jc")]`,
+        and community cards, like `["2c", "3d", "4h"]`.
+        """
+    @property
+    def hands(self) -> list[tuple[Card, Card]]:
+        """Hole cards of each player, with the higher card first."""
+    @property
+    def board(self) -> list[Card]: ...
+    def __repr__(self) -> str: ...
@@ -0,0 +1,99 @@
+# Generated by crates/python-stubgen from crates/python/src; Do not edit manually.
+
+from __future__ import annotations
+
+import os
+from typing import Awaitable, Callable
+
+import numpy as np
+import numpy.typing as npt
+
+from .card import Spot
+
+def equity_evolution(hands: list[tuple[str, str]], runout: list[str], parallel: bool = True) -> list[list[float]]:
+    """Calculate equities of the given hands after each street of the 5-card runout,
+    returned as `[preflop, flop, turn, river]` where each has equities of all players.
+    The GIL is released during the calculation.
+    """
+
+def equity_batch(spots: list[Spot]) -> list[EquityResult]:
+    """Calculate equities of many independent spots in one call, in parallel over spots.
+    Returns results in the same order as `spots`. The GIL is released during the calculation.
+    """
+
+class EquityResult:
+    """Result of single equity calculation."""
+    def __init__(self, hands: list[tuple[str, str]], community: list[str], parallel: bool = True) -> None:
+        """Calculate equities of the given hands, like `[("As", "Kh"), ("Qd", "Jc")]`,
+        on the given community cards, like `["2c", "3d", "4h"]`.
+        The GIL is released during the calculation.
+        """
+    @staticmethod
+    def calculate_async(hands: list[tuple[str, str]], community: list[str], parallel: bool = True, progress: Callable[[int, int], object] | None = None) -> Awaitable[EquityResult]:
+        """Same as the constructor, but returns an awaitable running on a background thread.
+        `progress(done, total)` is called on the event loop about every 1% of boards.
+        """
+    def __repr__(self) -> str: ...
+    def get_equity(self, player_index: int) -> float:
+        """Get the equity of the given player index (0-based)."""
+    def get_winlosses(self, player_index: int) -> tuple[list[int], int]:
+        """Get win counts by number of tied winners, and the lose count
+        of the given player index (0-based).
+        """
+    def equities(self) -> npt.NDArray[np.float64]:
+        """Get equities of all players as a numpy array."""
+    def wins_matrix(self) -> npt.NDArray[np.uint64]:
+        """Get the win count matrix as a numpy array, where `[i, c]` is the number of
+        `i`-th player wins with `c` other players having the same rank.
+        """
+    def loses(self) -> npt.NDArray[np.uint64]:
+        """Get lose counts of all players as a numpy array."""
+    def num_players(self) -> int:
+        """Get the number of players."""
+    def never_lost(self, player_index: int) -> bool:
+        """Check if the given player index (0-based) has never lost in all scenarios."""
+    def outcome_rates(self, player_index: int) -> tuple[float, list[float], float]:
+        """Get probabilities of `(winning outright, chops, losing)` of the given player index
+        (0-based), where `chops[k]` is the probability of chopping `k + 2` ways.
+        """
+
+class HUPreflopEquityCache:
+    """Preflop equity cache for heads-up situations."""
+    def __init__(self, path: str | os.PathLike[str]) -> None:
+        """Load the cache from the given gzipped cache file.
+        The GIL is released while building the cache.
+        """
+    @staticmethod
+    def load_async(path: str | os.PathLike[str]) -> Awaitable[HUPreflopEquityCache]:
+        """Same as the constructor, but returns an awaitable loading on a background thread."""
+    def get_winlose(self, hand1: tuple[str, str], hand2: tuple[str, str]) -> tuple[int, int, int]:
+        """Get `(P1 wins, P2 wins, ties)` of the given hands, like `("As", "Kh")`."""
+
+class LuckCalculator:
+    """Luck calculator over all-in results."""
+    def __init__(self) -> None:
+        """Create a new empty calculator."""
+    @staticmethod
+    def from_hand_history_text(hand_history_text: str, player_id: str) -> LuckCalculator:
+        """Create a new calculator filled with all-in spots
+        of the given player from the hand history text.
+        The GIL is released while calculating equities.
+        """
+    def add_result(self, equity: float, actual: float) -> None:
+        """Add a new result to the calculator."""
+    def add_results(self, results: list[tuple[float, float]]) -> None:
+        """Add `(equity, actual)` results at once.
+        Nothing is added if any of them is invalid.
+        """
+    def pop(self) -> tuple[float, float] | None:
+        """Remove the last result and return it as `(equity, actual)`."""
+    def truncate(self, length: int) -> None:
+        """Keep only the first `length` results, removing the rest."""
+    def luck_score(self) -> float:
+        """Calculate the Luck-score of the results."""
+    def tails(self) -> tuple[float, float, float]:
+        """Get upper-tail, lower-tail, and two-sided p-values."""
+    def pmf(self) -> tuple[npt.NDArray[np.float64], float]:
+        """Get the distribution of the number of wins as a numpy array, where `[k]` is
+        the probability of winning exactly `k` times, and the actual number of wins.
+        """
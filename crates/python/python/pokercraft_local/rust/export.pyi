@@ -0,0 +1,39 @@
+# Generated by crates/python-stubgen from crates/python/src; Do not edit manually.
+
+from __future__ import annotations
+
+import os
+from typing import Any
+
+def tournaments_table(path: str | os.PathLike[str], allow_freerolls: bool = False) -> ArrowTable:
+    """Get tournament results from the given directory, `.zip` bundle, or `.txt` file,
+    one row per tournament. Unparsable files are skipped.
+    """
+
+def hands_table(path: str | os.PathLike[str]) -> ArrowTable:
+    """Get hands from the given directory, `.zip` bundle, or `.txt` file,
+    one row per hand. Unparsable hands are skipped.
+    """
+
+def hud_stats_table(path: str | os.PathLike[str]) -> ArrowTable:
+    """Get standard HUD stats of all players in hands from the given directory,
+    `.zip` bundle, or `.txt` file, one row per player and stat.
+    """
+
+class ArrowTable:
+    """An Arrow record batch, exported through the Arrow PyCapsule interface.
+    Pass it to `pyarrow.table`, `polars.DataFrame`, or any library
+    which accepts objects with `__arrow_c_stream__`.
+    """
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    @property
+    def num_rows(self) -> int: ...
+    @property
+    def column_names(self) -> list[str]: ...
+    def __arrow_c_stream__(self, requested_schema: Any | None = None) -> object:
+        """Export as an Arrow C stream; `requested_schema` is ignored."""
+    def to_pandas(self) -> Any:
+        """Convert into a pandas DataFrame; Requires pyarrow."""
+    def to_polars(self) -> Any:
+        """Convert into a polars DataFrame; Requires polars."""
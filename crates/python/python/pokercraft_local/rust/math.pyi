@@ -0,0 +1,35 @@
+# Generated by crates/python-stubgen from crates/python/src; Do not edit manually.
+
+from __future__ import annotations
+
+def required_equity(bet: float, pot: float) -> float:
+    """Get the minimum equity to call `bet` into `pot`, which is `bet / (pot + 2 * bet)`."""
+
+def minimum_defense_frequency(bet: float, pot: float) -> float:
+    """Get the minimum defense frequency against `bet` into `pot`, which is `pot / (pot + bet)`."""
+
+def stack_to_pot_ratio(effective_stack: float, pot: float) -> float:
+    """Get the stack-to-pot ratio of the effective stack."""
+
+def shove_ev(hand: tuple[str, str], calling_range: str, board: list[str], pot: float, hero_stack: float, villain_stack: float, trials: int = ...) -> ShoveEv:
+    """Get the expected value of jamming `hand` like `("As", "Kd")` into `pot` on the board,
+    against a villain calling with `calling_range` like `"QQ+,AK"`.
+    Stacks are chips behind. The GIL is released during the calculation.
+    """
+
+class ShoveEv:
+    """Expected value of jamming all-in against a single player, in chips relative to folding."""
+    @property
+    def fold_frequency(self) -> float:
+        """Probability that the villain folds, holding any combo not blocked by known cards."""
+    @property
+    def called_equity(self) -> float:
+        """Equity of the hero hand against the calling range, or `0` if nothing calls."""
+    @property
+    def risk(self) -> float:
+        """Chips at risk when called, which is the smaller stack."""
+    @property
+    def called_ev(self) -> float: ...
+    @property
+    def ev(self) -> float: ...
+    def __repr__(self) -> str: ...
@@ -0,0 +1,374 @@
+//! `pokercraft_local.rust.bankroll`: Bankroll simulations.
+
+use std::borrow::Cow;
+
+use numpy::{PyArray1, PyReadonlyArray1};
+use pyo3::prelude::*;
+
+use pokercraft_core::bankroll::{
+    self, simulate_with_cash_flows, simulate_with_progress,
+    BankruptcyMetric as CoreBankruptcyMetric, CashFlow, GrowthMetric as CoreGrowthMetric,
+    TournamentStructure,
+};
+
+use crate::future::spawn_future;
+use crate::to_py_error;
+
+/// Relative return results, either a numpy float64 array or a sequence of floats.
+enum RelativeReturns<'py> {
+    Array(PyReadonlyArray1<'py, f64>),
+    Sequence(Vec<f64>),
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for RelativeReturns<'py> {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        // Looking up numpy arrays requires numpy, which is loaded if `obj` is an array.
+        let numpy_loaded = obj
+            .py()
+            .import("sys")?
+            .getattr("modules")?
+            .contains("numpy")?;
+        if numpy_loaded {
+            if let Ok(array) = obj.extract::<PyReadonlyArray1<'py, f64>>() {
+                return Ok(RelativeReturns::Array(array));
+            }
+        }
+        Ok(RelativeReturns::Sequence(obj.extract()?))
+    }
+}
+
+/// Summary of bankroll simulations.
+#[pyclass(frozen)]
+pub struct BankruptcyMetric {
+    inner: CoreBankruptcyMetric,
+}
+
+#[pymethods]
+impl BankruptcyMetric {
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "BankruptcyMetric(length={}, bankruptcy_rate={}, survival_rate={}, profitable_rate={})",
+            self.inner.len(),
+            self.inner.get_bankruptcy_rate(),
+            self.inner.get_survival_rate(),
+            self.inner.get_profitable_rate()
+        )
+    }
+
+    /// Number of simulations performed.
+    #[getter]
+    fn length(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[getter]
+    fn bankruptcy_rate(&self) -> f64 {
+        self.inner.get_bankruptcy_rate()
+    }
+
+    #[getter]
+    fn survival_rate(&self) -> f64 {
+        self.inner.get_survival_rate()
+    }
+
+    #[getter]
+    fn profitable_rate(&self) -> f64 {
+        self.inner.get_profitable_rate()
+    }
+
+    /// Get the `q`-quantile of relative returns (final capital / initial capital).
+    fn relative_return_quantile(&self, q: f64) -> f64 {
+        self.inner.get_relative_return_quantile(q)
+    }
+
+    /// Get relative returns (final capital / initial capital) of all simulations
+    /// as a numpy array.
+    fn relative_returns<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_iter(
+            py,
+            self.inner
+                .simulated_results()
+                .iter()
+                .map(|(relative_return, _iteration)| *relative_return),
+        )
+    }
+
+    /// Get bankrupted iteration numbers (0 if not bankrupted) of all simulations
+    /// as a numpy array.
+    fn bankrupt_iterations<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<u32>> {
+        PyArray1::from_iter(
+            py,
+            self.inner
+                .simulated_results()
+                .iter()
+                .map(
+                    |&(relative_return, iteration)| {
+                        if relative_return > 0.0 {
+                            0
+                        } else {
+                            iteration
+                        }
+                    },
+                ),
+        )
+    }
+
+    /// Rate of simulations reaching the profit exit capital.
+    #[getter]
+    fn target_reached_rate(&self) -> f64 {
+        self.inner.get_target_reached_rate()
+    }
+
+    /// Get the `q`-quantile of iterations to reach the profit exit capital
+    /// among simulations reaching it, like the median time to double,
+    /// or `None` if no simulation reached it.
+    fn time_to_target_quantile(&self, q: f64) -> Option<f64> {
+        self.inner.get_time_to_target_quantile(q)
+    }
+
+    /// Get iteration numbers of reaching the profit exit capital
+    /// of simulations reaching it as a numpy array.
+    fn target_iterations<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<u32>> {
+        PyArray1::from_vec(py, self.inner.target_iterations())
+    }
+}
+
+/// Find the minimum initial capital whose bankruptcy rate in `max_iteration` tournaments
+/// is at most `target_ruin_rate`, by bisection over simulations.
+/// The GIL is released during the simulations.
+#[pyfunction]
+fn required_bankroll(
+    py: Python<'_>,
+    relative_return_results: Vec<f64>,
+    target_ruin_rate: f64,
+    max_iteration: u32,
+    simulation_count: u32,
+) -> PyResult<f64> {
+    py.detach(|| {
+        bankroll::required_bankroll(
+            &relative_return_results,
+            target_ruin_rate,
+            max_iteration,
+            simulation_count,
+        )
+    })
+    .map_err(to_py_error)
+}
+
+/// Growth rates of simulations with proportional buy-ins.
+#[pyclass(frozen)]
+pub struct GrowthMetric {
+    inner: CoreGrowthMetric,
+}
+
+#[pymethods]
+impl GrowthMetric {
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "GrowthMetric(length={}, mean_growth_rate={})",
+            self.inner.len(),
+            self.inner.get_mean_growth_rate()
+        )
+    }
+
+    /// Average log growth of the capital per tournament.
+    #[getter]
+    fn mean_growth_rate(&self) -> f64 {
+        self.inner.get_mean_growth_rate()
+    }
+
+    /// Get the `q`-quantile of growth rates per tournament.
+    fn growth_rate_quantile(&self, q: f64) -> f64 {
+        self.inner.get_growth_rate_quantile(q)
+    }
+
+    /// Get the `q`-quantile of relative returns (final capital / initial capital).
+    fn relative_return_quantile(&self, q: f64) -> f64 {
+        self.inner.get_relative_return_quantile(q)
+    }
+
+    /// Get log growths of the capital per tournament of all simulations as a numpy array.
+    fn growth_rates<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, self.inner.growth_rates())
+    }
+}
+
+/// Simulate growth of the capital in parallel, where each buy-in is `buy_in_fraction`
+/// of the current capital and results are net results in buy-ins, like `-1` for not cashing.
+/// The GIL is released during the simulation.
+#[pyfunction]
+fn simulate_proportional(
+    py: Python<'_>,
+    results_in_buy_ins: Vec<f64>,
+    buy_in_fraction: f64,
+    max_iteration: u32,
+    simulation_count: u32,
+) -> PyResult<GrowthMetric> {
+    let inner = py
+        .detach(|| {
+            bankroll::simulate_proportional(
+                &results_in_buy_ins,
+                buy_in_fraction,
+                max_iteration,
+                simulation_count,
+            )
+        })
+        .map_err(to_py_error)?;
+    Ok(GrowthMetric { inner })
+}
+
+/// Get the expected log growth of the capital per tournament with proportional buy-ins.
+#[pyfunction]
+fn expected_growth_rate(results_in_buy_ins: Vec<f64>, buy_in_fraction: f64) -> PyResult<f64> {
+    bankroll::expected_growth_rate(&results_in_buy_ins, buy_in_fraction).map_err(to_py_error)
+}
+
+/// Simulate the bankruptcy metric in parallel.
+/// Relative return results can be a list or a numpy float64 array,
+/// which is read without copying if contiguous.
+/// Each of `cash_flows` is `(every, amount, fraction)`, adding `amount` and `fraction`
+/// of the capital after every `every` tournaments; Negative values are withdrawals.
+/// The GIL is released during the simulation.
+#[pyfunction]
+#[pyo3(signature = (
+    initial_capital,
+    relative_return_results,
+    max_iteration,
+    profit_exit_multiplier,
+    simulation_count,
+    cash_flows = Vec::new(),
+))]
+fn simulate(
+    py: Python<'_>,
+    initial_capital: f64,
+    relative_return_results: RelativeReturns<'_>,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+    cash_flows: Vec<(u32, f64, f64)>,
+) -> PyResult<BankruptcyMetric> {
+    let cash_flows = cash_flows
+        .into_iter()
+        .map(|(every, amount, fraction)| CashFlow {
+            every,
+            amount,
+            fraction,
+        })
+        .collect::<Vec<_>>();
+    let relative_return_results: Cow<'_, [f64]> = match &relative_return_results {
+        RelativeReturns::Array(array) => match array.as_slice() {
+            Ok(slice) => Cow::Borrowed(slice),
+            Err(_) => Cow::Owned(array.as_array().to_vec()),
+        },
+        RelativeReturns::Sequence(values) => Cow::Borrowed(values),
+    };
+    let inner = py
+        .detach(|| {
+            simulate_with_cash_flows(
+                initial_capital,
+                relative_return_results,
+                &cash_flows,
+                max_iteration,
+                profit_exit_multiplier,
+                simulation_count,
+            )
+        })
+        .map_err(to_py_error)?;
+    Ok(BankruptcyMetric { inner })
+}
+
+/// Same as `simulate`, but returns an awaitable running on a background thread.
+/// `progress(done, total)` is called on the event loop about every 1% of simulations.
+#[pyfunction]
+#[pyo3(signature = (
+    initial_capital,
+    relative_return_results,
+    max_iteration,
+    profit_exit_multiplier,
+    simulation_count,
+    progress = None,
+))]
+fn simulate_async<'py>(
+    py: Python<'py>,
+    initial_capital: f64,
+    relative_return_results: RelativeReturns<'_>,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+    progress: Option<Py<PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let relative_return_results = match relative_return_results {
+        RelativeReturns::Array(array) => array.as_array().to_vec(),
+        RelativeReturns::Sequence(values) => values,
+    };
+    spawn_future(py, progress, move |on_progress| {
+        simulate_with_progress(
+            initial_capital,
+            relative_return_results,
+            max_iteration,
+            profit_exit_multiplier,
+            simulation_count,
+            on_progress,
+        )
+        .map(|inner| BankruptcyMetric { inner })
+    })
+}
+
+/// Simulate the bankruptcy metric in parallel, playing each tournament in the structure
+/// of `buy_in`, `entries` and `payouts` of the 1st, 2nd, ... places, with a finish position
+/// resampled from `(rank, total players)` of past tournaments and scaled to the same percentile.
+/// The GIL is released during the simulation.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn simulate_finishes(
+    py: Python<'_>,
+    initial_capital: f64,
+    finishes: Vec<(u32, u32)>,
+    buy_in: f64,
+    entries: u32,
+    payouts: Vec<f64>,
+    max_iteration: u32,
+    profit_exit_multiplier: f64,
+    simulation_count: u32,
+) -> PyResult<BankruptcyMetric> {
+    let structure = TournamentStructure {
+        buy_in,
+        entries,
+        payouts,
+    };
+    let inner = py
+        .detach(|| {
+            bankroll::simulate_finishes(
+                initial_capital,
+                &finishes,
+                &structure,
+                max_iteration,
+                profit_exit_multiplier,
+                simulation_count,
+            )
+        })
+        .map_err(to_py_error)?;
+    Ok(BankruptcyMetric { inner })
+}
+
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<BankruptcyMetric>()?;
+    m.add_class::<GrowthMetric>()?;
+    m.add_function(wrap_pyfunction!(simulate, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_async, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_finishes, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_proportional, m)?)?;
+    m.add_function(wrap_pyfunction!(expected_growth_rate, m)?)?;
+    m.add_function(wrap_pyfunction!(required_bankroll, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,333 @@
+//! `pokercraft_local.rust.card`: Cards and hand evaluation.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash as _, Hasher};
+
+use numpy::{PyArray1, PyReadonlyArray2};
+use pyo3::basic::CompareOp;
+use pyo3::prelude::*;
+
+use pokercraft_core::card::{
+    parse_cards as core_parse_cards, Card as CoreCard, CardNumber as CoreCardNumber,
+    CardShape as CoreCardShape, Hand, HandRank,
+};
+use pokercraft_core::evaluator::evaluate_many as core_evaluate_many;
+use pokercraft_core::generator::{CardGenerator, Spot as CoreSpot};
+
+use crate::to_py_error;
+
+/// Card shapes (suits) in a standard deck of playing cards.
+#[pyclass(eq, eq_int, hash, frozen)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum CardShape {
+    Spade,
+    Heart,
+    Diamond,
+    Club,
+}
+
+impl From<CoreCardShape> for CardShape {
+    fn from(shape: CoreCardShape) -> Self {
+        match shape {
+            CoreCardShape::Spade => CardShape::Spade,
+            CoreCardShape::Heart => CardShape::Heart,
+            CoreCardShape::Diamond => CardShape::Diamond,
+            CoreCardShape::Club => CardShape::Club,
+        }
+    }
+}
+
+impl From<CardShape> for CoreCardShape {
+    fn from(shape: CardShape) -> Self {
+        match shape {
+            CardShape::Spade => CoreCardShape::Spade,
+            CardShape::Heart => CoreCardShape::Heart,
+            CardShape::Diamond => CoreCardShape::Diamond,
+            CardShape::Club => CoreCardShape::Club,
+        }
+    }
+}
+
+#[pymethods]
+impl CardShape {
+    /// Create a card shape from a character, like `CardShape("s")`.
+    #[new]
+    fn new(value: char) -> PyResult<Self> {
+        Ok(CoreCardShape::try_from(value).map_err(to_py_error)?.into())
+    }
+
+    fn __str__(&self) -> String {
+        CoreCardShape::from(*self).to_string()
+    }
+}
+
+/// Card numbers (ranks) in a standard deck of playing cards.
+#[pyclass(eq, eq_int, hash, ord, frozen)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum CardNumber {
+    Two = 2,
+    Three = 3,
+    Four = 4,
+    Five = 5,
+    Six = 6,
+    Seven = 7,
+    Eight = 8,
+    Nine = 9,
+    Ten = 10,
+    Jack = 11,
+    Queen = 12,
+    King = 13,
+    Ace = 14,
+}
+
+impl From<CoreCardNumber> for CardNumber {
+    fn from(number: CoreCardNumber) -> Self {
+        match number {
+            CoreCardNumber::Two => CardNumber::Two,
+            CoreCardNumber::Three => CardNumber::Three,
+            CoreCardNumber::Four => CardNumber::Four,
+            CoreCardNumber::Five => CardNumber::Five,
+            CoreCardNumber::Six => CardNumber::Six,
+            CoreCardNumber::Seven => CardNumber::Seven,
+            CoreCardNumber::Eight => CardNumber::Eight,
+            CoreCardNumber::Nine => CardNumber::Nine,
+            CoreCardNumber::Ten => CardNumber::Ten,
+            CoreCardNumber::Jack => CardNumber::Jack,
+            CoreCardNumber::Queen => CardNumber::Queen,
+            CoreCardNumber::King => CardNumber::King,
+            CoreCardNumber::Ace => CardNumber::Ace,
+        }
+    }
+}
+
+impl From<CardNumber> for CoreCardNumber {
+    fn from(number: CardNumber) -> Self {
+        match number {
+            CardNumber::Two => CoreCardNumber::Two,
+            CardNumber::Three => CoreCardNumber::Three,
+            CardNumber::Four => CoreCardNumber::Four,
+            CardNumber::Five => CoreCardNumber::Five,
+            CardNumber::Six => CoreCardNumber::Six,
+            CardNumber::Seven => CoreCardNumber::Seven,
+            CardNumber::Eight => CoreCardNumber::Eight,
+            CardNumber::Nine => CoreCardNumber::Nine,
+            CardNumber::Ten => CoreCardNumber::Ten,
+            CardNumber::Jack => CoreCardNumber::Jack,
+            CardNumber::Queen => CoreCardNumber::Queen,
+            CardNumber::King => CoreCardNumber::King,
+            CardNumber::Ace => CoreCardNumber::Ace,
+        }
+    }
+}
+
+#[pymethods]
+impl CardNumber {
+    /// Create a card number from an integer from 2 to 14 (Ace; 1 is also Ace), like `CardNumber(11)`.
+    #[new]
+    fn new(value: i32) -> PyResult<Self> {
+        Ok(CoreCardNumber::try_from(value).map_err(to_py_error)?.into())
+    }
+
+    fn __str__(&self) -> String {
+        CoreCardNumber::from(*self).to_string()
+    }
+}
+
+/// A playing card in a standard deck of 52 cards.
+#[pyclass(frozen)]
+#[derive(Clone, Copy, Debug)]
+pub struct Card {
+    inner: CoreCard,
+}
+
+impl From<CoreCard> for Card {
+    fn from(inner: CoreCard) -> Self {
+        Card { inner }
+    }
+}
+
+#[pymethods]
+impl Card {
+    /// Create a card from a string, like `Card("As")`.
+    #[new]
+    fn new(value: &str) -> PyResult<Self> {
+        Ok(parse_card(value)?.into())
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Card(\"{}\")", self.inner)
+    }
+
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.inner.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Cards are ordered by numbers, then by shapes.
+    fn __richcmp__(&self, other: PyRef<'_, Self>, op: CompareOp) -> bool {
+        op.matches(self.inner.cmp(&other.inner))
+    }
+
+    #[getter]
+    fn shape(&self) -> CardShape {
+        self.inner.shape.into()
+    }
+
+    #[getter]
+    fn number(&self) -> CardNumber {
+        self.inner.number.into()
+    }
+
+    /// Index of this card in the deck from 0 to 51, used as card bytes of `evaluate_many`.
+    #[getter]
+    fn index(&self) -> u8 {
+        u8::from(self.inner)
+    }
+}
+
+/// Parse a card string like `"As"`.
+pub(crate) fn parse_card(value: &str) -> PyResult<CoreCard> {
+    CoreCard::try_from(value).map_err(to_py_error)
+}
+
+/// Parse card strings like `["As", "Kd"]`, where each string may have multiple cards like `"AsKd"`.
+pub(crate) fn parse_card_strings(values: &[String]) -> PyResult<Vec<CoreCard>> {
+    let mut cards = Vec::with_capacity(values.len());
+    for value in values {
+        cards.extend(core_parse_cards(value).map_err(to_py_error)?);
+    }
+    Ok(cards)
+}
+
+/// Parse a pair of card strings like `("As", "Kd")`.
+pub(crate) fn parse_hand((card1, card2): &(String, String)) -> PyResult<Hand> {
+    Ok((parse_card(card1)?, parse_card(card2)?))
+}
+
+/// Parse a list of cards like `"As Kd 2c"`, `"As,Kd,2c"` or `"AsKd2c"`.
+#[pyfunction]
+fn parse_cards(value: &str) -> PyResult<Vec<Card>> {
+    Ok(core_parse_cards(value)
+        .map_err(to_py_error)?
+        .into_iter()
+        .map(Card::from)
+        .collect())
+}
+
+/// Hole cards of each player and community cards.
+#[pyclass(frozen)]
+pub struct Spot {
+    pub(crate) inner: CoreSpot,
+}
+
+#[pymethods]
+impl Spot {
+    /// Create a spot from hole cards of each player, like `[("As", "Kh"), ("Qd", "Jc")]`,
+    /// and community cards, like `["2c", "3d", "4h"]`.
+    #[new]
+    #[pyo3(signature = (hands, board = Vec::new()))]
+    fn new(hands: Vec<(String, String)>, board: Vec<String>) -> PyResult<Self> {
+        let inner = CoreSpot {
+            hands: hands.iter().map(parse_hand).collect::<PyResult<Vec<_>>>()?,
+            board: parse_card_strings(&board)?,
+        };
+        Ok(Spot { inner })
+    }
+
+    /// Hole cards of each player, with the higher card first.
+    #[getter]
+    fn hands(&self) -> Vec<(Card, Card)> {
+        self.inner
+            .hands
+            .iter()
+            .map(|&(c1, c2)| (Card::from(c1), Card::from(c2)))
+            .collect()
+    }
+
+    #[getter]
+    fn board(&self) -> Vec<Card> {
+        self.inner.board.iter().copied().map(Card::from).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        let hands = self
+            .inner
+            .hands
+            .iter()
+            .map(|(c1, c2)| format!("{}{}", c1, c2))
+            .collect::<Vec<_>>();
+        let board = self
+            .inner
+            .board
+            .iter()
+            .map(|card| card.to_string())
+            .collect::<String>();
+        format!("Spot(hands=[{}], board={})", hands.join(", "), board)
+    }
+}
+
+/// Generate a random spot from the given seed, never generating `dead` cards.
+#[pyfunction]
+#[pyo3(signature = (seed, num_players, board_size = 5, dead = Vec::new()))]
+fn random_spot(
+    seed: u64,
+    num_players: usize,
+    board_size: usize,
+    dead: Vec<String>,
+) -> PyResult<Spot> {
+    let inner = CardGenerator::new(seed)
+        .with_dead_cards(&parse_card_strings(&dead)?)
+        .spot(num_players, board_size)
+        .map_err(to_py_error)?;
+    Ok(Spot { inner })
+}
+
+/// Find the best 5-card hand from 5 or more card strings.
+/// Returns the best 5 cards and the human readable hand rank.
+#[pyfunction]
+fn find_best5(cards: Vec<String>) -> PyResult<(Vec<Card>, String)> {
+    let (best5, rank) = HandRank::find_best5(&parse_card_strings(&cards)?).map_err(to_py_error)?;
+    Ok((
+        best5.into_iter().map(Card::from).collect(),
+        rank.to_string(),
+    ))
+}
+
+/// Evaluate the best 5-card hand rank of each board of 5, 6 or 7 cards,
+/// given as a 2-dimensional uint8 array of card indices (see `Card.index`) per row.
+/// Returns a uint32 array of rank keys, where a larger key is a stronger hand.
+/// The GIL is released during the evaluation.
+#[pyfunction]
+fn evaluate_many<'py>(
+    py: Python<'py>,
+    boards: PyReadonlyArray2<'py, u8>,
+) -> PyResult<Bound<'py, PyArray1<u32>>> {
+    let boards = boards.as_array();
+    let cards = boards
+        .iter()
+        .map(|&byte| CoreCard::try_from(byte))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(to_py_error)?;
+    let cards_per_board = boards.ncols();
+    let keys = py
+        .detach(|| core_evaluate_many(&cards, cards_per_board))
+        .map_err(to_py_error)?;
+    Ok(PyArray1::from_iter(py, keys.into_iter().map(|key| key.0)))
+}
+
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<CardShape>()?;
+    m.add_class::<CardNumber>()?;
+    m.add_class::<Card>()?;
+    m.add_class::<Spot>()?;
+    m.add_function(wrap_pyfunction!(parse_cards, m)?)?;
+    m.add_function(wrap_pyfunction!(find_best5, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate_many, m)?)?;
+    m.add_function(wrap_pyfunction!(random_spot, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,310 @@
+//! `pokercraft_local.rust.equity`: Equity calculations and luck analysis.
+
+use std::path::PathBuf;
+
+use numpy::{PyArray1, PyArray2};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use pokercraft_core::equity::{
+    equity_batch as core_equity_batch, EquityEvolution, EquityResult as CoreEquityResult,
+    HUPreflopEquityCache as CoreHUPreflopEquityCache, LuckCalculator as CoreLuckCalculator,
+};
+use pokercraft_core::parser::parse_hands;
+
+use crate::card::{parse_card_strings, parse_hand, Spot};
+use crate::future::spawn_future;
+use crate::to_py_error;
+
+/// Result of single equity calculation.
+#[pyclass(frozen)]
+pub struct EquityResult {
+    inner: CoreEquityResult,
+}
+
+#[pymethods]
+impl EquityResult {
+    /// Calculate equities of the given hands, like `[("As", "Kh"), ("Qd", "Jc")]`,
+    /// on the given community cards, like `["2c", "3d", "4h"]`.
+    /// The GIL is released during the calculation.
+    #[new]
+    #[pyo3(signature = (hands, community, parallel = true))]
+    fn new(
+        py: Python<'_>,
+        hands: Vec<(String, String)>,
+        community: Vec<String>,
+        parallel: bool,
+    ) -> PyResult<Self> {
+        let cards_people = hands.iter().map(parse_hand).collect::<PyResult<Vec<_>>>()?;
+        let cards_community = parse_card_strings(&community)?;
+        let inner = py
+            .detach(|| CoreEquityResult::new(cards_people, cards_community, parallel))
+            .map_err(to_py_error)?;
+        Ok(EquityResult { inner })
+    }
+
+    /// Same as the constructor, but returns an awaitable running on a background thread.
+    /// `progress(done, total)` is called on the event loop about every 1% of boards.
+    #[staticmethod]
+    #[pyo3(signature = (hands, community, parallel = true, progress = None))]
+    fn calculate_async<'py>(
+        py: Python<'py>,
+        hands: Vec<(String, String)>,
+        community: Vec<String>,
+        parallel: bool,
+        progress: Option<Py<PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let cards_people = hands.iter().map(parse_hand).collect::<PyResult<Vec<_>>>()?;
+        let cards_community = parse_card_strings(&community)?;
+        spawn_future(py, progress, move |on_progress| {
+            CoreEquityResult::new_with_progress(
+                cards_people,
+                cards_community,
+                parallel,
+                on_progress,
+            )
+            .map(|inner| EquityResult { inner })
+        })
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        let equities = (0..self.num_players())
+            .map(|i| self.inner.get_equity(i).map(|equity| equity.to_string()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(to_py_error)?;
+        Ok(format!("EquityResult(equities=[{}])", equities.join(", ")))
+    }
+
+    /// Get the equity of the given player index (0-based).
+    fn get_equity(&self, player_index: usize) -> PyResult<f64> {
+        self.inner.get_equity(player_index).map_err(to_py_error)
+    }
+
+    /// Get win counts by number of tied winners, and the lose count
+    /// of the given player index (0-based).
+    fn get_winlosses(&self, player_index: usize) -> PyResult<(Vec<u64>, u64)> {
+        self.inner.get_winlosses(player_index).map_err(to_py_error)
+    }
+
+    /// Get equities of all players as a numpy array.
+    fn equities<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        let equities = (0..self.num_players())
+            .map(|i| self.inner.get_equity(i))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(to_py_error)?;
+        Ok(PyArray1::from_vec(py, equities))
+    }
+
+    /// Get the win count matrix as a numpy array, where `[i, c]` is the number of
+    /// `i`-th player wins with `c` other players having the same rank.
+    fn wins_matrix<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<u64>>> {
+        let wins = (0..self.num_players())
+            .map(|i| Ok(self.inner.get_winlosses(i)?.0))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(to_py_error)?;
+        PyArray2::from_vec2(py, &wins).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Get lose counts of all players as a numpy array.
+    fn loses<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray1<u64>>> {
+        let loses = (0..self.num_players())
+            .map(|i| Ok(self.inner.get_winlosses(i)?.1))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(to_py_error)?;
+        Ok(PyArray1::from_vec(py, loses))
+    }
+
+    /// Get the number of players.
+    fn num_players(&self) -> usize {
+        self.inner.num_players()
+    }
+
+    /// Check if the given player index (0-based) has never lost in all scenarios.
+    fn never_lost(&self, player_index: usize) -> PyResult<bool> {
+        let (_wins, loses) = self
+            .inner
+            .get_winlosses(player_index)
+            .map_err(to_py_error)?;
+        Ok(loses == 0)
+    }
+
+    /// Get probabilities of `(winning outright, chops, losing)` of the given player index
+    /// (0-based), where `chops[k]` is the probability of chopping `k + 2` ways.
+    fn outcome_rates(&self, player_index: usize) -> PyResult<(f64, Vec<f64>, f64)> {
+        let rates = self
+            .inner
+            .outcome_rates(player_index)
+            .map_err(to_py_error)?;
+        Ok((rates.win_outright, rates.chops, rates.lose))
+    }
+}
+
+/// Preflop equity cache for heads-up situations.
+#[pyclass(frozen)]
+pub struct HUPreflopEquityCache {
+    inner: CoreHUPreflopEquityCache,
+}
+
+#[pymethods]
+impl HUPreflopEquityCache {
+    /// Load the cache from the given gzipped cache file.
+    /// The GIL is released while building the cache.
+    #[new]
+    fn new(py: Python<'_>, path: PathBuf) -> PyResult<Self> {
+        let inner = py
+            .detach(|| CoreHUPreflopEquityCache::new(&path))
+            .map_err(to_py_error)?;
+        Ok(HUPreflopEquityCache { inner })
+    }
+
+    /// Same as the constructor, but returns an awaitable loading on a background thread.
+    #[staticmethod]
+    fn load_async(py: Python<'_>, path: PathBuf) -> PyResult<Bound<'_, PyAny>> {
+        spawn_future(py, None, move |_on_progress| {
+            CoreHUPreflopEquityCache::new(&path).map(|inner| HUPreflopEquityCache { inner })
+        })
+    }
+
+    /// Get `(P1 wins, P2 wins, ties)` of the given hands, like `("As", "Kh")`.
+    fn get_winlose(
+        &self,
+        hand1: (String, String),
+        hand2: (String, String),
+    ) -> PyResult<(u64, u64, u64)> {
+        self.inner
+            .get_winlose(parse_hand(&hand1)?, parse_hand(&hand2)?)
+            .map_err(to_py_error)
+    }
+}
+
+/// Luck calculator over all-in results.
+#[pyclass]
+pub struct LuckCalculator {
+    inner: CoreLuckCalculator,
+}
+
+#[pymethods]
+impl LuckCalculator {
+    /// Create a new empty calculator.
+    #[new]
+    fn new() -> Self {
+        LuckCalculator {
+            inner: CoreLuckCalculator::new(),
+        }
+    }
+
+    /// Create a new calculator filled with all-in spots
+    /// of the given player from the hand history text.
+    /// The GIL is released while calculating equities.
+    #[staticmethod]
+    fn from_hand_history_text(
+        py: Python<'_>,
+        hand_history_text: String,
+        player_id: String,
+    ) -> PyResult<Self> {
+        let inner = py
+            .detach(|| {
+                let hands = parse_hands(hand_history_text.as_bytes())
+                    .flatten()
+                    .collect::<Vec<_>>();
+                CoreLuckCalculator::from_hands(&hands, &player_id)
+            })
+            .map_err(to_py_error)?;
+        Ok(LuckCalculator { inner })
+    }
+
+    /// Add a new result to the calculator.
+    fn add_result(&mut self, equity: f64, actual: f64) -> PyResult<()> {
+        self.inner.add_result(equity, actual).map_err(to_py_error)
+    }
+
+    /// Add `(equity, actual)` results at once.
+    /// Nothing is added if any of them is invalid.
+    fn add_results(&mut self, results: Vec<(f64, f64)>) -> PyResult<()> {
+        self.inner.add_results(results).map_err(to_py_error)
+    }
+
+    /// Remove the last result and return it as `(equity, actual)`.
+    fn pop(&mut self) -> Option<(f64, f64)> {
+        self.inner.pop()
+    }
+
+    /// Keep only the first `length` results, removing the rest.
+    fn truncate(&mut self, length: usize) {
+        self.inner.truncate(length)
+    }
+
+    /// Calculate the Luck-score of the results.
+    fn luck_score(&self) -> PyResult<f64> {
+        self.inner
+            .luck_score()
+            .ok_or_else(|| PyValueError::new_err("Cannot calculate Luck-score"))
+    }
+
+    /// Get upper-tail, lower-tail, and two-sided p-values.
+    fn tails(&self) -> PyResult<(f64, f64, f64)> {
+        self.inner
+            .tails()
+            .ok_or_else(|| PyValueError::new_err("Cannot calculate tails without results"))
+    }
+
+    /// Get the distribution of the number of wins as a numpy array, where `[k]` is
+    /// the probability of winning exactly `k` times, and the actual number of wins.
+    fn pmf<'py>(&self, py: Python<'py>) -> (Bound<'py, PyArray1<f64>>, f64) {
+        let distribution = self.inner.pmf();
+        (
+            PyArray1::from_vec(py, distribution.pmf),
+            distribution.observed_wins,
+        )
+    }
+}
+
+/// Calculate equities of the given hands after each street of the 5-card runout,
+/// returned as `[preflop, flop, turn, river]` where each has equities of all players.
+/// The GIL is released during the calculation.
+#[pyfunction]
+#[pyo3(signature = (hands, runout, parallel = true))]
+fn equity_evolution(
+    py: Python<'_>,
+    hands: Vec<(String, String)>,
+    runout: Vec<String>,
+    parallel: bool,
+) -> PyResult<Vec<Vec<f64>>> {
+    let cards_people = hands.iter().map(parse_hand).collect::<PyResult<Vec<_>>>()?;
+    let runout = parse_card_strings(&runout)?;
+    let evolution = py
+        .detach(|| EquityEvolution::new(cards_people, &runout, parallel))
+        .map_err(to_py_error)?;
+    Ok(vec![
+        evolution.preflop,
+        evolution.flop,
+        evolution.turn,
+        evolution.river,
+    ])
+}
+
+/// Calculate equities of many independent spots in one call, in parallel over spots.
+/// Returns results in the same order as `spots`. The GIL is released during the calculation.
+#[pyfunction]
+fn equity_batch(py: Python<'_>, spots: Vec<PyRef<'_, Spot>>) -> PyResult<Vec<EquityResult>> {
+    let spots = spots
+        .iter()
+        .map(|spot| spot.inner.clone())
+        .collect::<Vec<_>>();
+    let results = py
+        .detach(|| core_equity_batch(spots))
+        .map_err(to_py_error)?;
+    Ok(results
+        .into_iter()
+        .map(|inner| EquityResult { inner })
+        .collect())
+}
+
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<EquityResult>()?;
+    m.add_class::<HUPreflopEquityCache>()?;
+    m.add_class::<LuckCalculator>()?;
+    m.add_function(wrap_pyfunction!(equity_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(equity_evolution, m)?)?;
+    Ok(())
+}
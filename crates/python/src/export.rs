@@ -0,0 +1,163 @@
+//! `pokercraft_local.rust.export`: Parsed data as Arrow tables,
+//! convertible to pandas or polars DataFrames without copying through Python objects.
+
+use std::ffi::CString;
+use std::path::PathBuf;
+
+use arrow::ffi_stream::FFI_ArrowArrayStream;
+use arrow::record_batch::{RecordBatch, RecordBatchIterator};
+use pyo3::prelude::*;
+use pyo3::types::PyCapsule;
+
+use pokercraft_core::export::{
+    hands_to_record_batch, hud_stats_to_record_batch, tournaments_to_record_batch,
+};
+use pokercraft_core::parser::ingest_path;
+use pokercraft_core::stats::HudStatsEngine;
+
+use crate::to_py_error;
+
+/// An Arrow record batch, exported through the Arrow PyCapsule interface.
+/// Pass it to `pyarrow.table`, `polars.DataFrame`, or any library
+/// which accepts objects with `__arrow_c_stream__`.
+#[pyclass(frozen)]
+pub struct ArrowTable {
+    batch: RecordBatch,
+}
+
+#[pymethods]
+impl ArrowTable {
+    fn __len__(&self) -> usize {
+        self.batch.num_rows()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ArrowTable(num_rows={}, column_names={:?})",
+            self.batch.num_rows(),
+            self.column_names()
+        )
+    }
+
+    #[getter]
+    fn num_rows(&self) -> usize {
+        self.batch.num_rows()
+    }
+
+    #[getter]
+    fn column_names(&self) -> Vec<String> {
+        self.batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect()
+    }
+
+    /// Export as an Arrow C stream; `requested_schema` is ignored.
+    #[pyo3(signature = (requested_schema = None))]
+    fn __arrow_c_stream__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyCapsule>> {
+        let _ = requested_schema;
+        let reader = RecordBatchIterator::new(vec![Ok(self.batch.clone())], self.batch.schema());
+        let stream = FFI_ArrowArrayStream::new(Box::new(reader));
+        PyCapsule::new(py, stream, Some(CString::new("arrow_array_stream")?))
+    }
+
+    /// Convert into a pandas DataFrame; Requires pyarrow.
+    fn to_pandas<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        slf.py()
+            .import("pyarrow")?
+            .call_method1("table", (slf,))?
+            .call_method0("to_pandas")
+    }
+
+    /// Convert into a polars DataFrame; Requires polars.
+    fn to_polars<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        slf.py().import("polars")?.call_method1("DataFrame", (slf,))
+    }
+}
+
+/// Get tournament results from the given directory, `.zip` bundle, or `.txt` file,
+/// one row per tournament. Unparsable files are skipped.
+#[pyfunction]
+#[pyo3(signature = (path, allow_freerolls = false))]
+fn tournaments_table(py: Python<'_>, path: PathBuf, allow_freerolls: bool) -> PyResult<ArrowTable> {
+    let batch = py
+        .detach(|| tournaments_to_record_batch(&ingest_path(&path, allow_freerolls)?.tournaments))
+        .map_err(to_py_error)?;
+    Ok(ArrowTable { batch })
+}
+
+/// Get hands from the given directory, `.zip` bundle, or `.txt` file,
+/// one row per hand. Unparsable hands are skipped.
+#[pyfunction]
+fn hands_table(py: Python<'_>, path: PathBuf) -> PyResult<ArrowTable> {
+    let batch = py
+        .detach(|| hands_to_record_batch(&ingest_path(&path, false)?.hand_histories))
+        .map_err(to_py_error)?;
+    Ok(ArrowTable { batch })
+}
+
+/// Get standard HUD stats of all players in hands from the given directory,
+/// `.zip` bundle, or `.txt` file, one row per player and stat.
+#[pyfunction]
+fn hud_stats_table(py: Python<'_>, path: PathBuf) -> PyResult<ArrowTable> {
+    let batch = py
+        .detach(|| {
+            let mut engine = HudStatsEngine::new();
+            engine.add_hands(&ingest_path(&path, false)?.hand_histories);
+            hud_stats_to_record_batch(engine.players())
+        })
+        .map_err(to_py_error)?;
+    Ok(ArrowTable { batch })
+}
+
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<ArrowTable>()?;
+    m.add_function(wrap_pyfunction!(tournaments_table, m)?)?;
+    m.add_function(wrap_pyfunction!(hands_table, m)?)?;
+    m.add_function(wrap_pyfunction!(hud_stats_table, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::ffi_stream::ArrowArrayStreamReader;
+    use arrow::record_batch::RecordBatchReader;
+    use pyo3::types::PyCapsuleMethods;
+
+    use super::*;
+
+    #[test]
+    fn test_arrow_c_stream() -> PyResult<()> {
+        let dir =
+            std::env::temp_dir().join(format!("pokercraft-python-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let result = Python::attach(|py| {
+            let table = Bound::new(py, tournaments_table(py, dir.clone(), false)?)?;
+            assert_eq!(table.get().num_rows(), 0);
+            let capsule = table
+                .call_method0("__arrow_c_stream__")?
+                .cast_into::<PyCapsule>()?;
+            let pointer = capsule.pointer_checked(Some(c"arrow_array_stream"))?;
+            let stream = unsafe { FFI_ArrowArrayStream::from_raw(pointer.as_ptr().cast()) };
+            let reader = ArrowArrayStreamReader::try_new(stream)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+            assert_eq!(
+                reader.schema().field(0).name(),
+                &table.get().column_names()[0]
+            );
+            assert_eq!(
+                reader.map(|batch| batch.unwrap().num_rows()).sum::<usize>(),
+                0
+            );
+            Ok(())
+        });
+        std::fs::remove_dir_all(&dir)?;
+        result
+    }
+}
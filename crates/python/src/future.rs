@@ -0,0 +1,143 @@
+//! Awaitable computations running on background threads,
+//! resolved on the running asyncio event loop.
+//!
+//! Computations are CPU-bound and already parallel over all cores, so this doesn't bring in
+//! an async runtime like `pyo3-async-runtimes`; They run on a small pool of worker threads
+//! instead, with progress reports and cancellation which `future_into_py` doesn't provide.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use pyo3::prelude::*;
+use pyo3::types::{PyCFunction, PyDict, PyTuple};
+use pyo3::IntoPyObjectExt;
+
+use pokercraft_core::errors::PokercraftLocalError;
+
+use crate::to_py_error;
+
+/// Maximum number of computations running at once; The rest wait in a queue.
+/// Each computation is parallel by itself, so more of them would only oversubscribe cores.
+const MAX_RUNNING_FUTURES: usize = 4;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+static JOBS: OnceLock<Sender<Job>> = OnceLock::new();
+
+/// Queue `job` to the worker threads, which are started on the first call.
+fn submit(job: Job) {
+    let jobs = JOBS.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for i in 0..MAX_RUNNING_FUTURES {
+            let receiver = receiver.clone();
+            std::thread::Builder::new()
+                .name(format!("pokercraft-future-{}", i))
+                .spawn(move || loop {
+                    let job = receiver.lock().unwrap().recv();
+                    match job {
+                        // Keep the worker alive even if the job panics
+                        Ok(job) => drop(catch_unwind(AssertUnwindSafe(job))),
+                        Err(_) => break,
+                    }
+                })
+                .expect("Failed to spawn a worker thread");
+        }
+        sender
+    });
+    jobs.send(job)
+        .expect("Worker threads should be alive as long as the queue");
+}
+
+/// Queue `f` to the worker threads and get an asyncio future of its result.
+/// `f` receives a progress callback of `(done, total)`, which forwards updates
+/// to `progress` on the event loop and returns `false` once the future is cancelled.
+pub(crate) fn spawn_future<'py, T, F>(
+    py: Python<'py>,
+    progress: Option<Py<PyAny>>,
+    f: F,
+) -> PyResult<Bound<'py, PyAny>>
+where
+    T: for<'a> IntoPyObject<'a> + Send + 'static,
+    F: FnOnce(&(dyn Fn(u64, u64) -> bool + Sync)) -> Result<T, PokercraftLocalError>
+        + Send
+        + 'static,
+{
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    let future = event_loop.call_method0("create_future")?;
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let on_done = {
+        let cancelled = cancelled.clone();
+        PyCFunction::new_closure(
+            py,
+            None,
+            None,
+            move |args: &Bound<'_, PyTuple>, _kwargs: Option<&Bound<'_, PyDict>>| -> PyResult<()> {
+                if args.get_item(0)?.call_method0("cancelled")?.is_truthy()? {
+                    cancelled.store(true, Ordering::Relaxed);
+                }
+                Ok(())
+            },
+        )?
+    };
+    future.call_method1("add_done_callback", (on_done,))?;
+
+    let event_loop = event_loop.unbind();
+    let target = future.clone().unbind();
+    submit(Box::new(move || {
+        // Cancelled while waiting in the queue
+        if cancelled.load(Ordering::Relaxed) {
+            return;
+        }
+        let report = |done: u64, total: u64| {
+            if cancelled.load(Ordering::Relaxed) {
+                return false;
+            }
+            if let Some(progress) = progress.as_ref() {
+                Python::attach(|py| {
+                    // The event loop may be closed already; Nothing to report then.
+                    let _ = event_loop.call_method1(
+                        py,
+                        "call_soon_threadsafe",
+                        (progress, done, total),
+                    );
+                });
+            }
+            true
+        };
+        let result = f(&report);
+
+        Python::attach(|py| {
+            let outcome: PyResult<Py<PyAny>> = result
+                .map_err(to_py_error)
+                .and_then(|value| value.into_py_any(py));
+            let resolve = PyCFunction::new_closure(
+                py,
+                None,
+                None,
+                move |args: &Bound<'_, PyTuple>,
+                      _kwargs: Option<&Bound<'_, PyDict>>|
+                      -> PyResult<()> {
+                    let future = args.get_item(0)?;
+                    if future.call_method0("done")?.is_truthy()? {
+                        return Ok(()); // Cancelled
+                    }
+                    match &outcome {
+                        Ok(value) => future.call_method1("set_result", (value,))?,
+                        Err(err) => future.call_method1(
+                            "set_exception",
+                            (err.clone_ref(args.py()).into_value(args.py()),),
+                        )?,
+                    };
+                    Ok(())
+                },
+            );
+            if let Ok(resolve) = resolve {
+                let _ = event_loop.call_method1(py, "call_soon_threadsafe", (resolve, target));
+            }
+        });
+    }));
+    Ok(future)
+}
@@ -0,0 +1,256 @@
+//! Python bindings for pokercraft-core, imported as `pokercraft_local.rust`.
+//!
+//! Heavy computations release the GIL, so other Python threads keep running
+//! and multiple calculations can run concurrently.
+//! Those also have `*_async` variants returning awaitables for asyncio,
+//! which accept a `progress(done, total)` callback and stop when cancelled.
+//! Build with: `maturin develop --release`
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use pokercraft_core::errors::PokercraftLocalError;
+
+pub mod bankroll;
+pub mod card;
+pub mod equity;
+pub mod export;
+mod future;
+pub mod math;
+
+fn to_py_error(err: PokercraftLocalError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// Get the library version.
+#[pyfunction]
+fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// Add a submodule, also registering it in `sys.modules`
+/// so `import pokercraft_local.rust.<name>` works.
+fn add_submodule(
+    parent: &Bound<'_, PyModule>,
+    name: &str,
+    register: fn(&Bound<'_, PyModule>) -> PyResult<()>,
+) -> PyResult<()> {
+    let py = parent.py();
+    let module = PyModule::new(py, name)?;
+    register(&module)?;
+    parent.add_submodule(&module)?;
+    py.import("sys")?
+        .getattr("modules")?
+        .set_item(format!("pokercraft_local.rust.{}", name), &module)?;
+    Ok(())
+}
+
+/// Set the number of threads used by parallel computations;
+/// `0` restores the default of using all cores.
+#[pyfunction]
+fn set_parallelism(threads: usize) -> PyResult<()> {
+    pokercraft_core::set_parallelism(threads).map_err(to_py_error)
+}
+
+/// Get the number of threads used by parallel computations.
+#[pyfunction]
+fn parallelism() -> usize {
+    pokercraft_core::parallelism()
+}
+
+/// Log diagnostics of parsing, enumeration and simulation to stderr
+/// at the given level: `"off"`, `"error"`, `"warn"`, `"info"`, `"debug"` or `"trace"`.
+/// Calling again changes the level.
+#[pyfunction]
+#[pyo3(signature = (level = "info"))]
+fn enable_logging(level: &str) -> PyResult<()> {
+    pokercraft_core::logging::enable_logging(level).map_err(to_py_error)
+}
+
+#[pymodule]
+#[pyo3(name = "rust")]
+fn pokercraft_local_rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(version, m)?)?;
+    m.add_function(wrap_pyfunction!(set_parallelism, m)?)?;
+    m.add_function(wrap_pyfunction!(parallelism, m)?)?;
+    m.add_function(wrap_pyfunction!(enable_logging, m)?)?;
+    add_submodule(m, "bankroll", bankroll::register)?;
+    add_submodule(m, "card", card::register)?;
+    add_submodule(m, "equity", equity::register)?;
+    add_submodule(m, "export", export::register)?;
+    add_submodule(m, "math", math::register)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::types::PyDict;
+
+    use super::*;
+
+    #[test]
+    fn test_python_module() -> PyResult<()> {
+        Python::attach(|py| {
+            let module = pyo3::wrap_pymodule!(pokercraft_local_rust)(py);
+            let globals = PyDict::new(py);
+            globals.set_item("rust", module)?;
+            py.run(
+                cr#"
+from concurrent.futures import ThreadPoolExecutor
+
+assert rust.version()
+
+rust.enable_logging("off")
+try:
+    rust.enable_logging("verbose")
+    raise AssertionError("Unknown log level should fail")
+except ValueError:
+    pass
+
+cards, rank = rust.card.find_best5(["As", "Ks", "2s", "7s", "9s", "9d"])
+assert len(cards) == 5
+assert cards[0].number == rust.card.CardNumber.Ace
+assert cards[0].shape == rust.card.CardShape.Spade
+
+Card, CardNumber, CardShape = rust.card.Card, rust.card.CardNumber, rust.card.CardShape
+assert Card("As") == cards[0] and str(Card("td")) == "Td"
+assert repr(Card("Kh")) == 'Card("Kh")'
+assert Card("2s") < Card("2c") < Card("3s")
+assert sorted([Card("Ah"), Card("9d")]) == [Card("9d"), Card("Ah")]
+assert len({Card("As"), Card("AS"), Card("Ks")}) == 2
+assert Card("As") != "As"
+assert CardNumber(11) == CardNumber.Jack and CardNumber(1) == CardNumber(14) > CardNumber.King
+assert CardShape("h") == CardShape.Heart
+assert len({CardNumber(2), CardNumber.Two}) == 1
+for invalid in [lambda: Card("Xs"), lambda: CardNumber(15), lambda: CardShape("x")]:
+    try:
+        invalid()
+        raise AssertionError("Invalid value should fail")
+    except ValueError:
+        pass
+
+def equity(board):
+    result = rust.equity.EquityResult([("As", "Ah"), ("Kd", "Kc")], board)
+    return result.get_equity(0)
+
+with ThreadPoolExecutor(2) as executor:
+    equities = list(executor.map(equity, [["2c", "7d", "9h", "4s"], ["2c", "7d", "9h"]]))
+assert abs(equities[0] - 42 / 44) < 1e-9
+assert equities[1] > 0.9
+chop = rust.equity.EquityResult([("Ah", "Kd"), ("Ac", "Kh")], ["Qs", "Jh", "9c", "7d", "2s"])
+assert chop.outcome_rates(0) == (0.0, [1.0], 0.0)
+
+metric = rust.bankroll.simulate(10.0, [1.0, -1.0, 2.0], 100, 2.0, 50)
+assert metric.length == len(metric) == 50
+assert repr(metric).startswith("BankruptcyMetric(length=50")
+assert 0.0 <= metric.bankruptcy_rate <= 1.0
+assert 0.0 <= metric.target_reached_rate <= 1.0
+doubled = rust.bankroll.simulate(10.0, [1.0], 100, 2.0, 10)
+assert doubled.target_reached_rate == 1.0 and doubled.time_to_target_quantile(0.5) == 10.0
+metric = rust.bankroll.simulate(10.0, [1.0], 100, 0.0, 10, cash_flows=[(1, -2.0, 0.0)])
+assert metric.bankruptcy_rate == 1.0
+assert 0.0 < rust.bankroll.required_bankroll([-1.0, 3.0], 0.5, 10, 100) < 10.0
+growth = rust.bankroll.simulate_proportional([2.0], 0.5, 10, 5)
+assert len(growth) == 5 and abs(growth.mean_growth_rate - rust.bankroll.expected_growth_rate([2.0], 0.5)) < 1e-12
+metric = rust.bankroll.simulate_finishes(100.0, [(1, 10), (10, 10)], 10.0, 1000, [3000.0, 2000.0], 50, 0.0, 20)
+assert len(metric) == 20
+
+assert abs(rust.math.required_equity(100.0, 100.0) - 1 / 3) < 1e-9
+assert rust.math.minimum_defense_frequency(50.0, 100.0) == 100 / 150
+assert rust.math.stack_to_pot_ratio(450.0, 100.0) == 4.5
+shove = rust.math.shove_ev(("As", "Ah"), "KK", ["2c", "7d", "9h", "4s"], 100.0, 300.0, 200.0)
+assert abs(shove.called_equity - 42 / 44) < 1e-9 and shove.risk == 200.0
+assert repr(shove).startswith("ShoveEv(fold_frequency=")
+try:
+    rust.math.required_equity(100.0, 0.0)
+    raise AssertionError("Empty pot should fail")
+except ValueError:
+    pass
+
+try:
+    import numpy as np
+except ImportError:
+    np = None
+if np is not None:
+    returns = np.array([1.0, -1.0, 2.0, 0.5])
+    metric = rust.bankroll.simulate(10.0, returns[::2], 100, 2.0, 50)
+    assert metric.relative_returns().shape == (50,)
+    assert metric.bankrupt_iterations().dtype == np.uint32
+    result = rust.equity.EquityResult([("As", "Ah"), ("Kd", "Kc")], ["2c", "7d", "9h", "4s"])
+    assert np.allclose(result.equities(), [42 / 44, 2 / 44])
+    assert result.wins_matrix().shape == (2, 2)
+    assert result.loses().tolist() == [2, 42]
+    luck = rust.equity.LuckCalculator()
+    luck.add_result(0.2, 1.0)
+    luck.add_result(0.5, 0.0)
+    pmf, observed = luck.pmf()
+    assert np.allclose(pmf, [0.4, 0.5, 0.1]) and observed == 1.0
+    boards = np.array(
+        [[Card(c).index for c in ["As", "Ks", "Qs", "Js", "Ts", "2d"]],
+         [Card(c).index for c in ["Ah", "Ad", "Kc", "Kd", "2s", "3c"]]],
+        dtype=np.uint8,
+    )
+    keys = rust.card.evaluate_many(boards)
+    assert keys.dtype == np.uint32 and keys.shape == (2,) and keys[0] > keys[1]
+    luck.add_results([(0.8, 1.0), (0.5, 0.5)])
+    assert luck.pop() == (0.5, 0.5)
+    luck.truncate(1)
+    assert np.allclose(luck.tails(), (0.2, 1.0, 0.4))
+
+import asyncio
+
+async def run_async():
+    updates = []
+    metric = await rust.bankroll.simulate_async(
+        10.0, [1.0, -1.0, 2.0], 100, 2.0, 200, progress=lambda *update: updates.append(update)
+    )
+    assert len(metric) == 200 and updates[-1] == (200, 200)
+
+    hands = [("As", "Ah"), ("Kd", "Kc")]
+    result = await rust.equity.EquityResult.calculate_async(hands, ["2c", "7d", "9h", "4s"])
+    assert abs(result.get_equity(0) - 42 / 44) < 1e-9
+
+    try:
+        await rust.bankroll.simulate_async(-1.0, [1.0], 100, 2.0, 50)
+        raise AssertionError("Negative initial capital should fail")
+    except ValueError:
+        pass
+
+    # More than the worker threads, which are queued
+    metrics = await asyncio.gather(
+        *(rust.bankroll.simulate_async(10.0, [1.0, -1.0], 100, 2.0, 20) for _ in range(10))
+    )
+    assert all(len(metric) == 20 for metric in metrics)
+
+    future = rust.equity.EquityResult.calculate_async(hands, [])
+    await asyncio.sleep(0.01)
+    future.cancel()
+    try:
+        await future
+        raise AssertionError("Cancelled calculation should not finish")
+    except asyncio.CancelledError:
+        pass
+
+asyncio.run(run_async())
+
+# After the cancellation above, which needs an uncached preflop calculation
+evolution = rust.equity.equity_evolution([("As", "Ah"), ("Kd", "Kc")], ["2c", "7d", "9h", "Ks", "3s"])
+assert abs(evolution[2][0] - 2 / 44) < 1e-9 and evolution[3] == [0.0, 1.0]
+batch = rust.equity.equity_batch([
+    rust.card.Spot([("As", "Ah"), ("Kd", "Kc")], ["2c", "7d", "9h", "Ks"]),
+    rust.card.Spot([("Ah", "Kd"), ("Ac", "Kh")], ["Qs", "Jh", "9c", "7d", "2s"]),
+])
+assert abs(batch[0].get_equity(0) - 2 / 44) < 1e-9 and batch[1].get_equity(0) == 0.5
+
+try:
+    rust.bankroll.simulate(-1.0, [1.0], 100, 2.0, 50)
+    raise AssertionError("Negative initial capital should fail")
+except ValueError as err:
+    assert "Initial capital" in str(err)
+"#,
+                Some(&globals),
+                None,
+            )
+        })
+    }
+}
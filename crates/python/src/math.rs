@@ -0,0 +1,116 @@
+//! `pokercraft_local.rust.math`: Pot odds, minimum defense frequency, stack-to-pot ratio
+//! and the expected value of jamming.
+
+use pyo3::prelude::*;
+
+use pokercraft_core::math::{self, ShoveEv as CoreShoveEv};
+use pokercraft_core::range::HandRange;
+
+use crate::card::{parse_card_strings, parse_hand};
+use crate::to_py_error;
+
+/// Get the minimum equity to call `bet` into `pot`, which is `bet / (pot + 2 * bet)`.
+#[pyfunction]
+fn required_equity(bet: f64, pot: f64) -> PyResult<f64> {
+    math::required_equity(bet, pot).map_err(to_py_error)
+}
+
+/// Get the minimum defense frequency against `bet` into `pot`, which is `pot / (pot + bet)`.
+#[pyfunction]
+fn minimum_defense_frequency(bet: f64, pot: f64) -> PyResult<f64> {
+    math::minimum_defense_frequency(bet, pot).map_err(to_py_error)
+}
+
+/// Get the stack-to-pot ratio of the effective stack.
+#[pyfunction]
+fn stack_to_pot_ratio(effective_stack: f64, pot: f64) -> PyResult<f64> {
+    math::stack_to_pot_ratio(effective_stack, pot).map_err(to_py_error)
+}
+
+/// Expected value of jamming all-in against a single player, in chips relative to folding.
+#[pyclass(frozen)]
+pub struct ShoveEv {
+    inner: CoreShoveEv,
+}
+
+#[pymethods]
+impl ShoveEv {
+    /// Probability that the villain folds, holding any combo not blocked by known cards.
+    #[getter]
+    fn fold_frequency(&self) -> f64 {
+        self.inner.fold_frequency
+    }
+
+    /// Equity of the hero hand against the calling range, or `0` if nothing calls.
+    #[getter]
+    fn called_equity(&self) -> f64 {
+        self.inner.called_equity
+    }
+
+    /// Chips at risk when called, which is the smaller stack.
+    #[getter]
+    fn risk(&self) -> f64 {
+        self.inner.risk
+    }
+
+    #[getter]
+    fn called_ev(&self) -> f64 {
+        self.inner.called_ev
+    }
+
+    #[getter]
+    fn ev(&self) -> f64 {
+        self.inner.ev
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ShoveEv(fold_frequency={}, called_equity={}, ev={})",
+            self.inner.fold_frequency, self.inner.called_equity, self.inner.ev
+        )
+    }
+}
+
+/// Get the expected value of jamming `hand` like `("As", "Kd")` into `pot` on the board,
+/// against a villain calling with `calling_range` like `"QQ+,AK"`.
+/// Stacks are chips behind. The GIL is released during the calculation.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (hand, calling_range, board, pot, hero_stack, villain_stack, trials = 100000))]
+fn shove_ev(
+    py: Python<'_>,
+    hand: (String, String),
+    calling_range: &str,
+    board: Vec<String>,
+    pot: f64,
+    hero_stack: f64,
+    villain_stack: f64,
+    trials: u64,
+) -> PyResult<ShoveEv> {
+    let hand = parse_hand(&hand)?;
+    let calling_range = HandRange::try_from(calling_range).map_err(to_py_error)?;
+    let board = parse_card_strings(&board)?;
+    let inner = py
+        .detach(|| {
+            math::shove_ev(
+                hand,
+                &calling_range,
+                &board,
+                pot,
+                hero_stack,
+                villain_stack,
+                trials,
+            )
+        })
+        .map_err(to_py_error)?;
+    Ok(ShoveEv { inner })
+}
+
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(required_equity, m)?)?;
+    m.add_function(wrap_pyfunction!(minimum_defense_frequency, m)?)?;
+    m.add_function(wrap_pyfunction!(stack_to_pot_ratio, m)?)?;
+    m.add_function(wrap_pyfunction!(shove_ev, m)?)?;
+    m.add_class::<ShoveEv>()?;
+    Ok(())
+}
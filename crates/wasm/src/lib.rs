@@ -6,11 +6,31 @@
 use wasm_bindgen::prelude::*;
 
 // Re-export types from pokercraft-core with WASM bindings
-pub use pokercraft_core::bankroll::BankruptcyMetric;
-pub use pokercraft_core::card::{Card, CardNumber, CardShape};
-pub use pokercraft_core::equity::{EquityResult, LuckCalculator};
+pub use pokercraft_core::bankroll::{BankruptcyMetric, GrowthMetric};
+pub use pokercraft_core::card::{
+    decode_cards_wasm as decode_cards, encode_cards_wasm as encode_cards,
+    find_best5_wasm as find_best5, parse_cards_wasm as parse_cards, Card, CardNumber, CardShape,
+};
+pub use pokercraft_core::equity::{
+    calculate_equity_wasm as calculate_equity, equity_batch_wasm as equity_batch,
+    equity_evolution_wasm as equity_evolution, EquityResult, LuckCalculator,
+};
+pub use pokercraft_core::evaluator::evaluate_many_wasm as evaluate_many;
+pub use pokercraft_core::generator::random_spot_wasm as random_spot;
+pub use pokercraft_core::math::{
+    minimum_defense_frequency, required_equity, shove_ev_wasm as shove_ev, stack_to_pot_ratio,
+};
+pub use pokercraft_core::parser::hand_history::parse_hands_wasm as parse_hands;
+pub use pokercraft_core::report::generate_html_report_wasm as generate_html_report;
+pub use pokercraft_core::stats::hud::compute_hud_stats_wasm as compute_hud_stats;
+pub use pokercraft_core::stats::tournament::aggregate_tournaments_wasm as aggregate_tournaments;
 
-// Re-export the simulate function
+// Re-export the simulate functions
+pub use pokercraft_core::bankroll::required_bankroll_wasm as required_bankroll;
+pub use pokercraft_core::bankroll::simulate_finishes_wasm as simulate_finishes;
+pub use pokercraft_core::bankroll::simulate_proportional_wasm as simulate_proportional;
+pub use pokercraft_core::bankroll::simulate_stream_wasm as simulate_stream;
+pub use pokercraft_core::bankroll::simulate_summary_wasm as simulate_summary;
 pub use pokercraft_core::bankroll::simulate_wasm as simulate;
 
 /// Initialize the WASM module (called automatically).
@@ -26,3 +46,32 @@ pub fn init() {
 pub fn version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_FEATURES: &'static str = r#"
+/** Target features this module was compiled with. */
+export interface Features {
+    /** Whether SIMD128 instructions are used. */
+    simd: boolean;
+    /** Whether shared memory and atomics are available for threads. */
+    threads: boolean;
+}
+"#;
+
+/// Get target features this module was compiled with, so the caller can
+/// check whether the SIMD build (see `scripts/build-wasm.sh --simd`) is active.
+#[wasm_bindgen(unchecked_return_type = "Features")]
+pub fn features() -> Result<JsValue, JsValue> {
+    let features = js_sys::Object::new();
+    js_sys::Reflect::set(
+        &features,
+        &"simd".into(),
+        &cfg!(target_feature = "simd128").into(),
+    )?;
+    js_sys::Reflect::set(
+        &features,
+        &"threads".into(),
+        &cfg!(target_feature = "atomics").into(),
+    )?;
+    Ok(features.into())
+}
@@ -1,5 +1,9 @@
 #!/bin/bash
 # Build WASM module for pokercraft-local web app
+# Usage: ./scripts/build-wasm.sh [--simd]
+# With `--simd`, a SIMD128 module is also built into `web/src/wasm-simd`,
+# which the web app loads instead when the browser supports SIMD (see `web/src/utils/wasm.ts`);
+# Call `features()` of the loaded module to see which one is active.
 
 set -e
 
@@ -7,11 +11,28 @@ SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
 PROJECT_ROOT="$(dirname "$SCRIPT_DIR")"
 WASM_CRATE="$PROJECT_ROOT/crates/wasm"
 WEB_WASM_DIR="$PROJECT_ROOT/web/src/wasm"
+WEB_WASM_SIMD_DIR="$PROJECT_ROOT/web/src/wasm-simd"
+CORE_TABLES_DIR="$PROJECT_ROOT/crates/core/tables"
+WEB_PUBLIC_DIR="$PROJECT_ROOT/web/public"
+
+BUILD_SIMD=false
+for arg in "$@"; do
+    case "$arg" in
+        --simd) BUILD_SIMD=true ;;
+        *)
+            echo "Error: unknown argument $arg"
+            exit 1
+            ;;
+    esac
+done
 
 echo "=== Building WASM module ==="
 echo "Project root: $PROJECT_ROOT"
 echo "WASM crate: $WASM_CRATE"
 echo "Output dir: $WEB_WASM_DIR"
+if [ "$BUILD_SIMD" = true ]; then
+    echo "SIMD output dir: $WEB_WASM_SIMD_DIR"
+fi
 echo
 
 # Check if wasm-pack is installed
@@ -25,13 +46,27 @@ fi
 cd "$WASM_CRATE"
 echo "Running wasm-pack build..."
 wasm-pack build --target web --out-dir "$WEB_WASM_DIR"
+if [ "$BUILD_SIMD" = true ]; then
+    echo "Running wasm-pack build with SIMD128..."
+    RUSTFLAGS="${RUSTFLAGS:+$RUSTFLAGS }-C target-feature=+simd128" \
+        wasm-pack build --target web --out-dir "$WEB_WASM_SIMD_DIR"
+fi
 
 # Clean up unnecessary files
 echo "Cleaning up..."
-rm -f "$WEB_WASM_DIR/.gitignore"
-rm -f "$WEB_WASM_DIR/package.json"
+for dir in "$WEB_WASM_DIR" "$WEB_WASM_SIMD_DIR"; do
+    rm -f "$dir/.gitignore"
+    rm -f "$dir/package.json"
+done
+
+# The web app fetches the heads-up preflop cache instead of embedding it
+echo "Copying heads-up preflop cache..."
+cp "$CORE_TABLES_DIR/hu_preflop_cache.bin" "$WEB_PUBLIC_DIR/hu_preflop_cache.bin"
 
 echo
 echo "=== WASM build complete ==="
 echo "Output files:"
 ls -la "$WEB_WASM_DIR"
+if [ "$BUILD_SIMD" = true ]; then
+    ls -la "$WEB_WASM_SIMD_DIR"
+fi
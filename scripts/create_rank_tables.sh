@@ -0,0 +1,2 @@
+#!/bin/bash
+cargo run --release --bin generate_rank_tables -- --file crates/core/tables/rank_tables.bin
@@ -3,3 +3,5 @@
  */
 
 export { yieldToBrowser } from './async'
+export { isSimdSupported, loadWasm } from './wasm'
+export type { PokercraftWasm } from './wasm'
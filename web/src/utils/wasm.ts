@@ -0,0 +1,48 @@
+/**
+ * Loader of the WASM module
+ *
+ * Picks the SIMD128 build (`scripts/build-wasm.sh --simd`) when the browser
+ * supports SIMD and the build exists, and falls back to the baseline build.
+ */
+
+import type * as PokercraftWasmModule from '../wasm/pokercraft_wasm'
+
+export type PokercraftWasm = typeof PokercraftWasmModule
+
+/**
+ * Smallest module using SIMD128 instructions:
+ * `(func (result v128) i32.const 0 i8x16.splat i8x16.popcnt)`
+ */
+const SIMD_PROBE = new Uint8Array([
+  0, 97, 115, 109, 1, 0, 0, 0, 1, 5, 1, 96, 0, 1, 123, 3, 2, 1, 0, 10, 10, 1, 8, 0, 65, 0, 253, 15,
+  253, 98, 11,
+])
+
+/** The SIMD build is only present if it was built, so it is globbed instead of imported. */
+const simdBuilds = import.meta.glob<PokercraftWasm>('../wasm-simd/pokercraft_wasm.js')
+
+/** Check whether the browser supports WASM SIMD128. */
+export function isSimdSupported(): boolean {
+  try {
+    return WebAssembly.validate(SIMD_PROBE)
+  } catch {
+    return false
+  }
+}
+
+let loading: Promise<PokercraftWasm> | null = null
+
+/** Load and initialize the fastest available WASM module once. */
+export function loadWasm(): Promise<PokercraftWasm> {
+  if (!loading) {
+    loading = (async () => {
+      const loadSimd = simdBuilds['../wasm-simd/pokercraft_wasm.js']
+      const wasm = loadSimd && isSimdSupported()
+        ? await loadSimd()
+        : await import('../wasm/pokercraft_wasm')
+      await wasm.default()
+      return wasm
+    })()
+  }
+  return loading
+}
@@ -14,6 +14,7 @@ import type {
   EquityWorkerOutput,
 } from '../../workers/equityWorker'
 import type { Translate } from '../../i18n'
+import { loadWasm } from '../../utils/wasm'
 
 export interface AllInHandData {
   handId: string
@@ -271,8 +272,7 @@ export type LuckScore = number | 'no-data' | 'failed'
 
 export async function calculateLuckScore(allInData: AllInHandData[]): Promise<LuckScore> {
   if (allInData.length === 0) return 'no-data'
-  const wasmModule = await import('../../wasm/pokercraft_wasm')
-  await wasmModule.default()  // Initialize WASM
+  const wasmModule = await loadWasm()
   const luckCalc = new wasmModule.LuckCalculator()
   try {
     for (const data of allInData) {
@@ -3,7 +3,7 @@
  * Handles file parsing and equity calculations off the main thread
  */
 
-import init, { EquityResult, simulate, version } from '../wasm/pokercraft_wasm'
+import { loadWasm } from '../utils/wasm'
 import { loadAndParseFiles, CurrencyRateConverter } from '../parser'
 import type { TournamentSummary, HandHistory, ParseResult } from '../types'
 import {
@@ -61,15 +61,6 @@ export interface BankrollWorkerResult {
   survivalRate: number
 }
 
-let wasmInitialized = false
-
-async function ensureWasmInit(): Promise<void> {
-  if (!wasmInitialized) {
-    await init()
-    wasmInitialized = true
-  }
-}
-
 function postProgress(
   stage: WorkerProgress['stage'],
   current: number,
@@ -126,7 +117,7 @@ function getCommunityAtStreet(h: HandHistory, street: string): string[] {
  * Calculate equity data for all-in hands
  */
 async function calculateEquityData(handHistories: HandHistory[]): Promise<AllInEquityWorkerData[]> {
-  await ensureWasmInit()
+  const wasm = await loadWasm()
 
   const results: AllInEquityWorkerData[] = []
   const eligibleHands = handHistories.filter(h => {
@@ -154,7 +145,7 @@ async function calculateEquityData(handHistories: HandHistory[]): Promise<AllInE
     try {
       const community = getCommunityAtStreet(h, street)
       const hands = [[heroCards[0], heroCards[1]], ...opponents]
-      const equityResult = new EquityResult(hands, community)
+      const equityResult = new wasm.EquityResult(hands, community)
       const equity = equityResult.getEquity(0)
       equityResult.free()
 
@@ -184,7 +175,7 @@ async function calculateEquityData(handHistories: HandHistory[]): Promise<AllInE
  * Run bankroll simulation
  */
 async function runBankrollSimulation(tournaments: TournamentSummary[]): Promise<BankrollWorkerResult[]> {
-  await ensureWasmInit()
+  const wasm = await loadWasm()
 
   const relativeReturns: number[] = []
   for (const t of tournaments) {
@@ -207,7 +198,7 @@ async function runBankrollSimulation(tournaments: TournamentSummary[]): Promise<
     })
 
     try {
-      const result = simulate(
+      const result = wasm.simulate(
         initialCapital,
         new Float64Array(relativeReturns),
         maxIterations,
@@ -235,8 +226,7 @@ self.onmessage = async (event: MessageEvent<WorkerMessage>) => {
   try {
     if (type === 'parse' && files) {
       postProgress('init', 0, 1, 'progress.init')
-      await ensureWasmInit()
-      const wasmVer = version()
+      const wasmVer = (await loadWasm()).version()
 
       postProgress('parsing', 0, files.length, 'progress.parsing')
       const rateConverter = new CurrencyRateConverter()
@@ -252,7 +242,7 @@ self.onmessage = async (event: MessageEvent<WorkerMessage>) => {
     }
 
     if (type === 'analyze') {
-      await ensureWasmInit()
+      await loadWasm()
 
       let equityData: AllInEquityWorkerData[] = []
       let bankrollResults: BankrollWorkerResult[] = []
@@ -4,7 +4,8 @@
  * Falls back to full calculation for other cases
  */
 
-import init, { EquityResult, LuckCalculator, HUPreflopEquityCache } from '../wasm/pokercraft_wasm'
+import type { EquityResult, HUPreflopEquityCache } from '../wasm/pokercraft_wasm'
+import { loadWasm, type PokercraftWasm } from '../utils/wasm'
 
 export interface EquityWorkerInput {
   type: 'calculate'
@@ -50,17 +51,9 @@ export interface EquityWorkerError {
 
 export type EquityWorkerOutput = EquityWorkerProgress | EquityWorkerResult | EquityWorkerError
 
-let wasmInitialized = false
 let preflopCache: HUPreflopEquityCache | null = null
 
-async function ensureWasmInit(): Promise<void> {
-  if (!wasmInitialized) {
-    await init()
-    wasmInitialized = true
-  }
-}
-
-async function ensurePreflopCache(): Promise<HUPreflopEquityCache | null> {
+async function ensurePreflopCache(wasm: PokercraftWasm): Promise<HUPreflopEquityCache | null> {
   if (preflopCache) return preflopCache
 
   // Try multiple possible paths (dev vs production, with/without base path)
@@ -77,7 +70,7 @@ async function ensurePreflopCache(): Promise<HUPreflopEquityCache | null> {
       if (!response.ok) continue
 
       const bytes = new Uint8Array(await response.arrayBuffer())
-      preflopCache = new HUPreflopEquityCache(bytes)
+      preflopCache = new wasm.HUPreflopEquityCache(bytes)
       return preflopCache
     } catch {
       continue
@@ -97,6 +90,7 @@ let fullCalcs = 0
  * Uses preflop cache for 2-player preflop, full calculation otherwise
  */
 function calculateEquity(
+  wasm: PokercraftWasm,
   heroCards: [string, string],
   opponents: string[][],
   communityAtAllIn: string[],
@@ -128,7 +122,7 @@ function calculateEquity(
   let equityResult: EquityResult | null = null
   try {
     const allHands = [heroCards, ...opponents]
-    equityResult = new EquityResult(allHands, communityAtAllIn)
+    equityResult = new wasm.EquityResult(allHands, communityAtAllIn)
     return equityResult.getEquity(0)
   } catch {
     return null
@@ -143,16 +137,17 @@ self.onmessage = async (event: MessageEvent<EquityWorkerInput>) => {
   if (type !== 'calculate') return
 
   try {
-    await ensureWasmInit()
-    const cache = await ensurePreflopCache()
+    const wasm = await loadWasm()
+    const cache = await ensurePreflopCache(wasm)
 
     const results: EquityWorkerResult['data'] = []
-    const luckCalc = new LuckCalculator()
+    const luckCalc = new wasm.LuckCalculator()
 
     for (let i = 0; i < hands.length; i++) {
       const h = hands[i]
 
       const equity = calculateEquity(
+        wasm,
         h.heroCards,
         h.opponents,
         h.communityAtAllIn,